@@ -1,5 +1,8 @@
 use anyhow::{bail, Result};
-use gix::{bstr::BString, config::AsKey};
+use gix::{
+    bstr::{BStr, BString, ByteSlice},
+    config::AsKey,
+};
 
 use crate::OutputFormat;
 
@@ -50,6 +53,166 @@ pub fn list(
     Ok(())
 }
 
+/// A way to select values of multi-valued keys, like the `value-pattern` of `git config`.
+pub struct ValuePattern {
+    /// A regular expression, or a value to compare with if `fixed_value` is set.
+    /// A regular expression prefixed with `!` matches only values that don't match it.
+    pub pattern: BString,
+    /// If set, `pattern` must be equal to the value for it to match.
+    pub fixed_value: bool,
+}
+
+/// A change to make to a configuration file, along the lines of the respective `git config` option.
+pub enum Edit {
+    /// Set the single value of `key`, like `git config <key> <value>`.
+    Set { key: BString, value: BString },
+    /// Add another value to `key`, like `git config --add <key> <value>`.
+    Add { key: BString, value: BString },
+    /// Replace all matching values of `key` with `value`, like `git config --replace-all <key> <value> [<value-pattern>]`.
+    ReplaceAll {
+        key: BString,
+        value: BString,
+        value_pattern: Option<ValuePattern>,
+    },
+    /// Remove the single matching value of `key`, like `git config --unset <key> [<value-pattern>]`.
+    Unset {
+        key: BString,
+        value_pattern: Option<ValuePattern>,
+    },
+    /// Remove all matching values of `key`, like `git config --unset-all <key> [<value-pattern>]`.
+    UnsetAll {
+        key: BString,
+        value_pattern: Option<ValuePattern>,
+    },
+    /// Rename the section `old_name` to `new_name`, like `git config --rename-section <old-name> <new-name>`.
+    RenameSection { old_name: BString, new_name: BString },
+    /// Remove the section `name`, like `git config --remove-section <name>`.
+    RemoveSection { name: BString },
+}
+
+/// Apply `edit` to the configuration file of `source`, which is written back right away.
+pub fn edit(mut repo: gix::Repository, source: gix::config::Source, edit: Edit) -> Result<()> {
+    let mut file = repo.config_file_mut(source)?;
+    match edit {
+        Edit::Set { key, value } => {
+            file.set_value(&key, value.as_bstr())?;
+        }
+        Edit::Add { key, value } => file.add_value(&key, value.as_bstr())?,
+        Edit::ReplaceAll {
+            key,
+            value,
+            value_pattern,
+        } => {
+            file.replace_values(&key, value.as_bstr(), value_matcher(value_pattern)?)?;
+        }
+        Edit::Unset { key, value_pattern } => match value_pattern {
+            None => {
+                if file.remove_value(&key)?.is_none() {
+                    bail!("No value found for key '{key}'");
+                }
+            }
+            Some(pattern) => {
+                let mut matches = value_matcher(Some(pattern))?;
+                let num_matches = file
+                    .raw_values(&key)
+                    .map(|values| values.iter().filter(|value| matches(value.as_ref())).count())
+                    .unwrap_or_default();
+                match num_matches {
+                    0 => bail!("No matching value found for key '{key}'"),
+                    1 => {
+                        file.remove_values(&key, matches)?;
+                    }
+                    _ => bail!("Key '{key}' has {num_matches} matching values - use unset-all to remove them all"),
+                }
+            }
+        },
+        Edit::UnsetAll { key, value_pattern } => {
+            if file.remove_values(&key, value_matcher(value_pattern)?)? == 0 {
+                bail!("No matching value found for key '{key}'");
+            }
+        }
+        Edit::RenameSection { old_name, new_name } => {
+            let (old_name, old_subsection) = split_section_name(old_name.as_bstr());
+            let (new_name, new_subsection) = split_section_name(new_name.as_bstr());
+            let old_name = old_name.to_str()?;
+            let num_sections = file
+                .sections_by_name(old_name)
+                .map(|sections| {
+                    sections
+                        .filter(|section| section.header().subsection_name() == old_subsection)
+                        .count()
+                })
+                .unwrap_or_default();
+            if num_sections == 0 {
+                bail!("No such section: '{old_name}'");
+            }
+            // Like `git`, rename all sections of the same name, knowing that each call renames the last one.
+            for _ in 0..num_sections {
+                file.rename_section(
+                    old_name,
+                    old_subsection,
+                    new_name.to_str()?.to_owned(),
+                    new_subsection.map(|subsection| subsection.to_owned().into()),
+                )?;
+            }
+        }
+        Edit::RemoveSection { name } => {
+            let (section_name, subsection) = split_section_name(name.as_bstr());
+            let section_name = section_name.to_str()?;
+            let mut num_removed = 0;
+            while file.remove_section(section_name, subsection).is_some() {
+                num_removed += 1;
+            }
+            if num_removed == 0 {
+                bail!("No such section: '{name}'");
+            }
+        }
+    }
+    file.commit()?;
+    Ok(())
+}
+
+fn value_matcher(pattern: Option<ValuePattern>) -> Result<impl FnMut(&BStr) -> bool> {
+    enum Matcher {
+        All,
+        Fixed(BString),
+        Regex { regex: regex::bytes::Regex, negated: bool },
+    }
+    let matcher = match pattern {
+        None => Matcher::All,
+        Some(ValuePattern {
+            pattern,
+            fixed_value: true,
+        }) => Matcher::Fixed(pattern),
+        Some(ValuePattern {
+            pattern,
+            fixed_value: false,
+        }) => {
+            let (pattern, negated) = match pattern.strip_prefix(b"!") {
+                Some(pattern) => (pattern, true),
+                None => (pattern.as_slice(), false),
+            };
+            Matcher::Regex {
+                regex: regex::bytes::Regex::new(pattern.to_str()?)?,
+                negated,
+            }
+        }
+    };
+    Ok(move |value: &BStr| match &matcher {
+        Matcher::All => true,
+        Matcher::Fixed(expected) => value == expected.as_bstr(),
+        Matcher::Regex { regex, negated } => regex.is_match(value) != *negated,
+    })
+}
+
+/// Split `name` like `remote.origin` into the section name and the optional subsection name.
+fn split_section_name(name: &BStr) -> (&BStr, Option<&BStr>) {
+    match name.find_byte(b'.') {
+        Some(pos) => (name[..pos].as_bstr(), Some(name[pos + 1..].as_bstr())),
+        None => (name, None),
+    }
+}
+
 struct Filter {
     name: String,
     subsection: Option<BString>,
@@ -50,6 +50,156 @@ pub fn decode(data: &[u8]) -> Result<(Vec, &[u8]), decode::Error> {
     ))
 }
 
+mod init {
+    use super::{access::*, Vec};
+
+    impl Default for Vec {
+        fn default() -> Self {
+            Vec {
+                num_bits: 0,
+                bits: vec![0],
+                rlw: 0,
+            }
+        }
+    }
+
+    impl Vec {
+        /// Set the bit at `index` to true, which must be greater than the index of all previously set bits.
+        ///
+        /// This is how bitmaps are built up by git, which allows us to produce the exact same encoding.
+        ///
+        /// ### Panics
+        ///
+        /// If `index` isn't greater than the index of the last set bit.
+        pub fn set(&mut self, index: usize) {
+            let num_bits = self.num_bits as usize;
+            assert!(index >= num_bits, "bits must be set in ascending order");
+            let dist = words_for_bits(index + 1) - words_for_bits(num_bits);
+            self.num_bits = u32::try_from(index + 1).expect("less than 4 billion bits");
+            let bit = 1u64 << (index % BITS_IN_WORD);
+
+            if dist > 0 {
+                if dist > 1 {
+                    self.add_empty_words(false, (dist - 1) as u64);
+                }
+                self.add_literal(bit);
+                return;
+            }
+
+            if rlw_literal_words(&self.bits[self.rlw()]) == 0 {
+                let rlw = self.rlw();
+                let running_len = rlw_running_len(&self.bits[rlw]);
+                rlw_set_running_len(&mut self.bits[rlw], running_len - 1);
+                self.add_literal(bit);
+                return;
+            }
+
+            let last = self.bits.last_mut().expect("at least one literal word");
+            *last |= bit;
+            if *last == u64::MAX {
+                self.bits.pop();
+                let rlw = self.rlw();
+                let literal_words = rlw_literal_words(&self.bits[rlw]);
+                rlw_set_literal_words(&mut self.bits[rlw], literal_words - 1);
+                self.add_empty_word(true);
+            }
+        }
+
+        fn rlw(&self) -> usize {
+            self.rlw as usize
+        }
+
+        fn push_rlw(&mut self) {
+            self.bits.push(0);
+            self.rlw = (self.bits.len() - 1) as u64;
+        }
+
+        fn add_literal(&mut self, word: u64) {
+            let rlw = self.rlw();
+            let literal_words = rlw_literal_words(&self.bits[rlw]);
+            if literal_words >= RLW_LARGEST_LITERAL_COUNT {
+                self.push_rlw();
+                let rlw = self.rlw();
+                rlw_set_literal_words(&mut self.bits[rlw], 1);
+            } else {
+                rlw_set_literal_words(&mut self.bits[rlw], literal_words + 1);
+            }
+            self.bits.push(word);
+        }
+
+        fn add_empty_word(&mut self, value: bool) {
+            let rlw = self.rlw();
+            let no_literal = rlw_literal_words(&self.bits[rlw]) == 0;
+            let running_len = rlw_running_len(&self.bits[rlw]);
+            if no_literal && running_len == 0 {
+                rlw_set_runbit(&mut self.bits[rlw], value);
+            }
+            if no_literal && rlw_runbit_is_set(&self.bits[rlw]) == value && running_len < RLW_LARGEST_RUNNING_COUNT {
+                rlw_set_running_len(&mut self.bits[rlw], running_len + 1);
+            } else {
+                self.push_rlw();
+                let rlw = self.rlw();
+                rlw_set_runbit(&mut self.bits[rlw], value);
+                rlw_set_running_len(&mut self.bits[rlw], 1);
+            }
+        }
+
+        fn add_empty_words(&mut self, value: bool, mut count: u64) {
+            let rlw = self.rlw();
+            let word = self.bits[rlw];
+            if rlw_runbit_is_set(&word) != value && rlw_running_len(&word) + rlw_literal_words(&word) == 0 {
+                rlw_set_runbit(&mut self.bits[rlw], value);
+            } else if rlw_literal_words(&word) != 0 || rlw_runbit_is_set(&word) != value {
+                self.push_rlw();
+                let rlw = self.rlw();
+                rlw_set_runbit(&mut self.bits[rlw], value);
+            }
+
+            let rlw = self.rlw();
+            let running_len = rlw_running_len(&self.bits[rlw]);
+            let can_add = count.min(RLW_LARGEST_RUNNING_COUNT - running_len);
+            rlw_set_running_len(&mut self.bits[rlw], running_len + can_add);
+            count -= can_add;
+
+            while count > 0 {
+                let num = count.min(RLW_LARGEST_RUNNING_COUNT);
+                self.push_rlw();
+                let rlw = self.rlw();
+                rlw_set_runbit(&mut self.bits[rlw], value);
+                rlw_set_running_len(&mut self.bits[rlw], num);
+                count -= num;
+            }
+        }
+    }
+
+    fn words_for_bits(num_bits: usize) -> usize {
+        (num_bits + BITS_IN_WORD - 1) / BITS_IN_WORD
+    }
+
+    const BITS_IN_WORD: usize = 64;
+}
+
+mod write {
+    use super::Vec;
+
+    impl Vec {
+        /// Serialize this bitmap into `out` in a format compatible with [`decode()`](super::decode()).
+        pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+            out.write_all(&self.num_bits.to_be_bytes())?;
+            out.write_all(
+                &u32::try_from(self.bits.len())
+                    .expect("less than 4 billion words")
+                    .to_be_bytes(),
+            )?;
+            for word in &self.bits {
+                out.write_all(&word.to_be_bytes())?;
+            }
+            out.write_all(&u32::try_from(self.rlw).expect("rlw fits into u32").to_be_bytes())?;
+            Ok(())
+        }
+    }
+}
+
 mod access {
     use super::Vec;
 
@@ -99,26 +249,49 @@ mod access {
     }
 
     #[inline]
-    fn rlw_running_len(w: &u64) -> u64 {
+    pub(super) fn rlw_running_len(w: &u64) -> u64 {
         (w >> 1) & RLW_LARGEST_RUNNING_COUNT
     }
 
     #[inline]
-    fn rlw_literal_words(w: &u64) -> u64 {
+    pub(super) fn rlw_literal_words(w: &u64) -> u64 {
         w >> (1 + RLW_RUNNING_BITS)
     }
 
     #[inline]
-    fn rlw_runbit_is_set(w: &u64) -> bool {
+    pub(super) fn rlw_runbit_is_set(w: &u64) -> bool {
         w & 1 == 1
     }
 
+    #[inline]
+    pub(super) fn rlw_set_runbit(w: &mut u64, value: bool) {
+        if value {
+            *w |= 1;
+        } else {
+            *w &= !1;
+        }
+    }
+
+    #[inline]
+    pub(super) fn rlw_set_running_len(w: &mut u64, len: u64) {
+        *w &= !(RLW_LARGEST_RUNNING_COUNT << 1);
+        *w |= len << 1;
+    }
+
+    #[inline]
+    pub(super) fn rlw_set_literal_words(w: &mut u64, num: u64) {
+        *w &= (1 << (1 + RLW_RUNNING_BITS)) - 1;
+        *w |= num << (1 + RLW_RUNNING_BITS);
+    }
+
     const RLW_RUNNING_BITS: u64 = 4 * 8;
-    const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+    pub(super) const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+    pub(super) const RLW_LARGEST_LITERAL_COUNT: u64 = (1 << (64 - 1 - RLW_RUNNING_BITS)) - 1;
 }
 
 /// A growable collection of u64 that are seen as stream of individual bits.
-#[allow(dead_code)]
+///
+/// Use [`Vec::default()`] to create an empty bitmap, and [`Vec::set()`] to build it up.
 #[derive(Clone)]
 pub struct Vec {
     num_bits: u32,
@@ -0,0 +1,202 @@
+use gix_hash::ObjectId;
+
+use crate::Header;
+
+/// The error returned by [`create()`][crate::create()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not find object to peel or traverse")]
+    FindExisting(#[from] gix_object::find::existing::Error),
+    #[error("Could not find commit to obtain its message")]
+    FindExistingCommit(#[from] gix_object::find::existing_object::Error),
+    #[error(transparent)]
+    Traverse(#[from] gix_traverse::commit::simple::Error),
+    #[error(transparent)]
+    Decode(#[from] gix_object::decode::Error),
+    #[error("Refusing to create an empty bundle as no commit is reachable from the tips that isn't also reachable from the excluded commits")]
+    Empty,
+    #[error(transparent)]
+    Count(#[from] gix_pack::data::output::count::objects::Error),
+    #[error(transparent)]
+    Entries(#[from] gix_pack::data::output::bytes::Error<gix_pack::data::output::entry::iter_from_counts::Error>),
+    #[error(transparent)]
+    Header(#[from] crate::header::encode::Error),
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// Options for use in [`create()`][crate::create()].
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// The amount of threads to use when producing pack entries, or `None` to use all logical cores.
+    pub thread_limit: Option<usize>,
+    /// If `true`, the pack may contain deltas against objects it doesn't contain, but which are known to be reachable
+    /// from the [prerequisites][Header::prerequisites]. This makes the bundle smaller, but receivers need to complete
+    /// the pack when indexing it.
+    pub thin: bool,
+    /// The kind of hash used by `objects`, which determines the object format of the bundle and its pack.
+    pub object_hash: gix_hash::Kind,
+}
+
+/// The result of [`create()`][crate::create()].
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The header that was written, with refs and prerequisites in the order they are listed in the bundle.
+    pub header: Header,
+    /// The hash of the pack that follows the header.
+    pub pack_hash: ObjectId,
+    /// The amount of objects written into the pack.
+    pub num_objects: usize,
+}
+
+pub(crate) mod function {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use gix_features::{
+        parallel::InOrderIter,
+        progress::{DynNestedProgress, Progress},
+    };
+    use gix_hash::ObjectId;
+    use gix_object::FindExt;
+    use gix_pack::data::output;
+
+    use super::{Error, Options, Outcome};
+    use crate::{Header, Prerequisite, Ref};
+
+    /// Write a bundle to `out` which contains all `refs` along with all objects reachable from them, except for the ones
+    /// that are also reachable from the commits in `exclude`, similar to `git bundle create <file> <ref>… ^<exclude>…`.
+    ///
+    /// `objects` is used to traverse the commit graph and to obtain the objects to put into the pack. If it's a `gix_odb` handle,
+    /// it must be configured to `prevent_pack_unload()` as pack locations are used to copy entries.
+    /// Commits in `exclude` which are parents of commits in the pack become the [prerequisites][Header::prerequisites]
+    /// of the bundle. Tags are peeled to find the commits to traverse, while the tag objects themselves are included in the pack.
+    ///
+    /// Use `should_interrupt` to stop the operation early, and `progress` to learn about the progress of counting and writing.
+    pub fn create<Find>(
+        objects: Find,
+        refs: impl IntoIterator<Item = Ref>,
+        exclude: impl IntoIterator<Item = ObjectId>,
+        out: &mut dyn std::io::Write,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        Options {
+            thread_limit,
+            thin,
+            object_hash,
+        }: Options,
+    ) -> Result<Outcome, Error>
+    where
+        Find: gix_pack::Find + gix_object::Find + Send + Clone + 'static,
+    {
+        let refs: Vec<_> = refs.into_iter().collect();
+        let mut buf = Vec::new();
+        let exclude = exclude
+            .into_iter()
+            .map(|id| peel_to_commit(&objects, id, &mut buf))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut hidden = gix_hashtable::HashSet::default();
+        for info in gix_traverse::commit::Simple::new(exclude, &objects) {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            hidden.insert(info?.id);
+        }
+
+        let tips = refs
+            .iter()
+            .map(|r| peel_to_commit(&objects, r.id, &mut buf))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut commits = Vec::new();
+        let mut prerequisites = Vec::<Prerequisite>::new();
+        for info in gix_traverse::commit::Simple::filtered(tips, &objects, |id| !hidden.contains(id)) {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let info = info?;
+            for parent_id in info.parent_ids.iter().filter(|id| hidden.contains(*id)) {
+                if prerequisites.iter().any(|p| p.id == *parent_id) {
+                    continue;
+                }
+                let comment = objects
+                    .find_commit(parent_id, &mut buf)?
+                    .message()
+                    .summary()
+                    .into_owned();
+                prerequisites.push(Prerequisite {
+                    id: *parent_id,
+                    comment,
+                });
+            }
+            commits.push(info.id);
+        }
+        if commits.is_empty() {
+            return Err(Error::Empty);
+        }
+
+        let input = refs
+            .iter()
+            .map(|r| r.id)
+            .filter(|id| !hidden.contains(id))
+            .chain(commits)
+            .map(Ok)
+            .collect::<Vec<_>>();
+        let (counts, _) = {
+            let mut progress = progress.add_child("counting".into());
+            progress.init(None, gix_features::progress::count("objects"));
+            output::count::objects_unthreaded(
+                &objects,
+                &mut input.into_iter(),
+                &progress,
+                should_interrupt,
+                output::count::objects::ObjectExpansion::TreeAdditionsComparedToAncestor,
+            )?
+        };
+        let num_objects = counts.len();
+
+        let header = Header::new(refs, prerequisites, object_hash, None);
+        header.write_to(out)?;
+
+        let entries = InOrderIter::from(output::entry::iter_from_counts(
+            counts,
+            objects,
+            Box::new(progress.add_child("creating entries".into())),
+            output::entry::iter_from_counts::Options {
+                thread_limit,
+                mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                allow_thin_pack: thin,
+                ..Default::default()
+            },
+        ));
+        let mut write = output::bytes::FromEntriesIter::new(
+            entries,
+            out,
+            num_objects as u32,
+            gix_pack::data::Version::V2,
+            object_hash,
+        );
+        for res in write.by_ref() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            res?;
+        }
+        let pack_hash = write.digest().expect("iteration is done");
+
+        Ok(Outcome {
+            header,
+            pack_hash,
+            num_objects,
+        })
+    }
+
+    fn peel_to_commit(objects: &impl gix_object::Find, mut id: ObjectId, buf: &mut Vec<u8>) -> Result<ObjectId, Error> {
+        loop {
+            let obj = objects.find(&id, buf)?;
+            match obj.kind {
+                gix_object::Kind::Tag => id = obj.decode()?.into_tag().expect("tag").target(),
+                _ => return Ok(id),
+            }
+        }
+    }
+}
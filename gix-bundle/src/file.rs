@@ -0,0 +1,83 @@
+use std::{
+    io::{BufReader, Seek, SeekFrom},
+    path::PathBuf,
+};
+
+use crate::{File, Header};
+
+///
+#[allow(clippy::empty_docs)]
+pub mod open {
+    /// The error returned by [`File::at()`][crate::File::at()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not open bundle file at '{}'", path.display())]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error(transparent)]
+        Header(#[from] crate::header::decode::Error),
+    }
+}
+
+/// Lifecycle
+impl File {
+    /// Open the bundle at `path` and decode its header, leaving the pack untouched until it is
+    /// [read][File::pack_reader()].
+    pub fn at(path: impl Into<PathBuf>) -> Result<Self, open::Error> {
+        let path = path.into();
+        let file = std::fs::File::open(&path).map_err(|source| open::Error::Io {
+            source,
+            path: path.clone(),
+        })?;
+        let (header, pack_offset) = Header::from_read(&mut BufReader::new(file))?;
+        Ok(File {
+            header,
+            path,
+            pack_offset,
+        })
+    }
+}
+
+/// Access
+impl File {
+    /// Return the offset in bytes at which the pack starts within the bundle file.
+    pub fn pack_offset(&self) -> u64 {
+        self.pack_offset
+    }
+
+    /// Open the bundle file once more and return a buffered reader positioned at the beginning of its pack.
+    ///
+    /// The pack can then be indexed with `gix_pack::Bundle::write_to_directory()`, which needs access to the
+    /// [prerequisites][Header::prerequisites] if the pack is thin.
+    pub fn pack_reader(&self) -> std::io::Result<BufReader<std::fs::File>> {
+        let mut file = std::fs::File::open(&self.path)?;
+        file.seek(SeekFrom::Start(self.pack_offset))?;
+        Ok(BufReader::new(file))
+    }
+}
+
+/// Return `true` if the file at `path` starts with a bundle signature.
+///
+/// This is a quick check which only reads the first line, and is useful to distinguish bundle files from repositories.
+pub fn is_bundle(path: &std::path::Path) -> bool {
+    use std::io::Read;
+    let Ok(file) = std::fs::File::open(path) else {
+        return false;
+    };
+    let mut buf = [0u8; 16];
+    let mut file = file.take(buf.len() as u64);
+    let mut filled = 0;
+    while filled < buf.len() {
+        match file.read(&mut buf[filled..]) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => filled += n,
+        }
+    }
+    let line = &buf[..filled];
+    [crate::Version::V2, crate::Version::V3].iter().any(|v| {
+        line.len() > v.signature().len() && line.starts_with(v.signature()) && line[v.signature().len()] == b'\n'
+    })
+}
@@ -0,0 +1,260 @@
+use std::io::BufRead;
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_hash::ObjectId;
+
+use crate::{Header, Prerequisite, Ref, Version};
+
+///
+#[allow(clippy::empty_docs)]
+pub mod decode {
+    use bstr::BString;
+
+    /// The error returned by [`Header::from_read()`][crate::Header::from_read()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read bundle header")]
+        Io(#[from] std::io::Error),
+        #[error("Unknown bundle signature: {signature:?}")]
+        UnknownSignature { signature: BString },
+        #[error("The bundle header ended before the blank line separating it from the pack")]
+        UnexpectedEof,
+        #[error("Capabilities like {line:?} are only allowed at the beginning of v3 bundles")]
+        MisplacedCapability { line: BString },
+        #[error("The capability {name:?} is not supported")]
+        UnsupportedCapability { name: BString },
+        #[error("The object format {format:?} is not supported")]
+        UnsupportedObjectFormat { format: BString },
+        #[error("The header line {line:?} did not start with a valid object id")]
+        InvalidId {
+            line: BString,
+            source: gix_hash::decode::Error,
+        },
+        #[error("The header line {line:?} is not a valid reference line")]
+        MalformedRef { line: BString },
+    }
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod encode {
+    /// The error returned by [`Header::write_to()`][crate::Header::write_to()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error(
+            "Version 2 bundles can't represent a filter or an object format other than SHA1, use version 3 instead"
+        )]
+        CapabilitiesNeedV3,
+    }
+}
+
+/// Decoding
+impl Header {
+    /// Read a bundle header from `read`, leaving it positioned at the first byte of the pack.
+    ///
+    /// Returns the header along with the amount of bytes it took up, which is the offset at which the pack starts.
+    pub fn from_read(read: &mut impl BufRead) -> Result<(Self, u64), decode::Error> {
+        let mut line = Vec::new();
+        let mut consumed = 0u64;
+        let mut next_line = |line: &mut Vec<u8>| -> Result<bool, decode::Error> {
+            line.clear();
+            let num_read = read.read_until(b'\n', line)?;
+            consumed += num_read as u64;
+            let complete = line.last() == Some(&b'\n');
+            while matches!(line.last(), Some(b'\n' | b'\r')) {
+                line.pop();
+            }
+            Ok(complete)
+        };
+
+        if !next_line(&mut line)? {
+            return Err(decode::Error::UnknownSignature {
+                signature: line.as_bstr().into(),
+            });
+        }
+        let version = if line == Version::V2.signature() {
+            Version::V2
+        } else if line == Version::V3.signature() {
+            Version::V3
+        } else {
+            return Err(decode::Error::UnknownSignature {
+                signature: line.as_bstr().into(),
+            });
+        };
+
+        let mut header = Header {
+            version,
+            ..Default::default()
+        };
+        let mut capabilities_allowed = version == Version::V3;
+        loop {
+            if !next_line(&mut line)? {
+                return Err(decode::Error::UnexpectedEof);
+            }
+            if line.is_empty() {
+                break;
+            }
+            match line[0] {
+                b'@' => {
+                    if !capabilities_allowed {
+                        return Err(decode::Error::MisplacedCapability {
+                            line: line.as_bstr().into(),
+                        });
+                    }
+                    header.parse_capability(line[1..].as_bstr())?;
+                }
+                b'-' => {
+                    capabilities_allowed = false;
+                    let (id, comment) = header.parse_id_and_rest(&line, 1)?;
+                    header.prerequisites.push(Prerequisite {
+                        id,
+                        comment: comment.into(),
+                    });
+                }
+                _ => {
+                    capabilities_allowed = false;
+                    let (id, name) = header.parse_id_and_rest(&line, 0)?;
+                    if name.is_empty() {
+                        return Err(decode::Error::MalformedRef {
+                            line: line.as_bstr().into(),
+                        });
+                    }
+                    header.refs.push(Ref { id, name: name.into() });
+                }
+            }
+        }
+        Ok((header, consumed))
+    }
+
+    fn parse_capability(&mut self, capability: &BStr) -> Result<(), decode::Error> {
+        let (name, value) = capability
+            .split_once_str(b"=")
+            .map_or((capability.as_bytes(), None), |(name, value)| {
+                (name, Some(value.as_bstr()))
+            });
+        match (name, value) {
+            (b"object-format", Some(format)) => {
+                self.object_hash = format
+                    .to_str()
+                    .ok()
+                    .and_then(|format| format.parse().ok())
+                    .ok_or_else(|| decode::Error::UnsupportedObjectFormat { format: format.into() })?;
+            }
+            (b"filter", Some(spec)) => self.filter = Some(spec.into()),
+            _ => {
+                return Err(decode::Error::UnsupportedCapability {
+                    name: name.as_bstr().into(),
+                })
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_id_and_rest<'a>(&self, line: &'a [u8], start: usize) -> Result<(ObjectId, &'a BStr), decode::Error> {
+        let hex_len = self.object_hash.len_in_hex();
+        let line_without_prefix = &line[start..];
+        let (hex, rest) = if line_without_prefix.len() > hex_len {
+            let (hex, rest) = line_without_prefix.split_at(hex_len);
+            if rest[0] != b' ' {
+                return Err(decode::Error::MalformedRef {
+                    line: line.as_bstr().into(),
+                });
+            }
+            (hex, &rest[1..])
+        } else {
+            (line_without_prefix, &[][..])
+        };
+        let id = ObjectId::from_hex(hex).map_err(|source| decode::Error::InvalidId {
+            line: line.as_bstr().into(),
+            source,
+        })?;
+        Ok((id, rest.as_bstr()))
+    }
+}
+
+/// Encoding
+impl Header {
+    /// Write this header to `out` in a format suitable to be followed by a pack, as described by its [`version`][Header::version].
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> Result<(), encode::Error> {
+        if self.version == Version::V2 && (self.filter.is_some() || self.object_hash != gix_hash::Kind::Sha1) {
+            return Err(encode::Error::CapabilitiesNeedV3);
+        }
+        out.write_all(self.version.signature())?;
+        out.write_all(b"\n")?;
+        if self.version == Version::V3 {
+            writeln!(out, "@object-format={}", self.object_hash.to_string().to_lowercase())?;
+            if let Some(filter) = &self.filter {
+                out.write_all(b"@filter=")?;
+                out.write_all(filter)?;
+                out.write_all(b"\n")?;
+            }
+        }
+        for prerequisite in &self.prerequisites {
+            write!(out, "-{}", prerequisite.id)?;
+            if !prerequisite.comment.is_empty() {
+                out.write_all(b" ")?;
+                out.write_all(&prerequisite.comment)?;
+            }
+            out.write_all(b"\n")?;
+        }
+        for r in &self.refs {
+            write!(out, "{} ", r.id)?;
+            out.write_all(&r.name)?;
+            out.write_all(b"\n")?;
+        }
+        out.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+/// Access
+impl Header {
+    /// Return all refs whose name matches one of `names`, in the order in which they appear in the bundle,
+    /// similar to `git bundle list-heads <file> <refname>…`.
+    ///
+    /// A name matches if it's equal to the full ref name, or if it matches one of its trailing path components,
+    /// so `main` matches `refs/heads/main`. If `names` is empty, all refs are returned.
+    pub fn list_heads<'a>(&'a self, names: &'a [&BStr]) -> impl Iterator<Item = &'a Ref> + 'a {
+        self.refs.iter().filter(move |r| {
+            names.is_empty()
+                || names.iter().any(|name| {
+                    r.name == *name
+                        || (r.name.len() > name.len()
+                            && r.name.ends_with(name)
+                            && r.name[r.name.len() - name.len() - 1] == b'/')
+                })
+        })
+    }
+
+    /// Return `true` if the pack of this bundle is complete in itself, i.e. it doesn't need any prerequisites and wasn't filtered.
+    pub fn is_self_contained(&self) -> bool {
+        self.prerequisites.is_empty() && self.filter.is_none()
+    }
+}
+
+impl Header {
+    /// Produce a header containing the given `refs` and `prerequisites`, choosing the lowest version that can represent them.
+    pub fn new(
+        refs: impl IntoIterator<Item = Ref>,
+        prerequisites: impl IntoIterator<Item = Prerequisite>,
+        object_hash: gix_hash::Kind,
+        filter: Option<BString>,
+    ) -> Self {
+        let version = if filter.is_some() || object_hash != gix_hash::Kind::Sha1 {
+            Version::V3
+        } else {
+            Version::V2
+        };
+        Header {
+            version,
+            object_hash,
+            filter,
+            prerequisites: prerequisites.into_iter().collect(),
+            refs: refs.into_iter().collect(),
+        }
+    }
+}
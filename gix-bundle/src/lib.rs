@@ -0,0 +1,121 @@
+//! Read, verify and create [git bundles](https://git-scm.com/docs/gitformat-bundle), files which carry a pack
+//! along with the references it provides and the commits it requires to be present.
+//!
+//! A bundle is [opened][File::at()] to access its [header][Header], which can then be used to
+//! [verify prerequisites][Header::verify_prerequisites()] against an object database before
+//! [reading the pack][File::pack_reader()] it contains.
+//! ## Feature Flags
+#![cfg_attr(
+    all(doc, feature = "document-features"),
+    doc = ::document_features::document_features!()
+)]
+#![cfg_attr(all(doc, feature = "document-features"), feature(doc_cfg, doc_auto_cfg))]
+#![deny(missing_docs, rust_2018_idioms)]
+#![forbid(unsafe_code)]
+
+use bstr::BString;
+use gix_hash::ObjectId;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod header;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod file;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod verify;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod list;
+
+///
+#[cfg(feature = "create")]
+#[allow(clippy::empty_docs)]
+pub mod create;
+#[cfg(feature = "create")]
+pub use create::function::create;
+
+#[cfg(feature = "blocking-client")]
+pub mod transport;
+
+/// The version of the bundle format.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum Version {
+    /// The original format, which only supports SHA1 and has no capabilities.
+    #[default]
+    V2,
+    /// The format which supports capabilities, like `object-format` and `filter`.
+    V3,
+}
+
+impl Version {
+    /// Return the signature line that starts bundles of this version, without trailing newline.
+    pub fn signature(&self) -> &'static [u8] {
+        match self {
+            Version::V2 => b"# v2 git bundle",
+            Version::V3 => b"# v3 git bundle",
+        }
+    }
+}
+
+/// A commit which has to exist in the receiving repository for the pack in the bundle to be complete.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct Prerequisite {
+    /// The id of the commit that is expected to be present.
+    pub id: ObjectId,
+    /// An optional comment, typically the subject line of the commit, which is informational only.
+    pub comment: BString,
+}
+
+/// A reference provided by the bundle, pointing to an object contained in its pack.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct Ref {
+    /// The object the reference points to.
+    pub id: ObjectId,
+    /// The full name of the reference, like `refs/heads/main` or `HEAD`.
+    pub name: BString,
+}
+
+/// The header of a bundle file, which precedes the pack.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+pub struct Header {
+    /// The version of the bundle.
+    pub version: Version,
+    /// The kind of hash used for all object ids in the bundle, as specified by the `object-format` capability.
+    pub object_hash: gix_hash::Kind,
+    /// The object filter used to create the bundle, as specified by the `filter` capability.
+    ///
+    /// If set, the pack is incomplete and objects are missing according to the filter specification, like `blob:none`.
+    pub filter: Option<BString>,
+    /// All commits that must be present for the pack to be complete.
+    pub prerequisites: Vec<Prerequisite>,
+    /// The references provided by the bundle.
+    pub refs: Vec<Ref>,
+}
+
+impl Default for Header {
+    fn default() -> Self {
+        Header {
+            version: Version::default(),
+            object_hash: gix_hash::Kind::Sha1,
+            filter: None,
+            prerequisites: Vec::new(),
+            refs: Vec::new(),
+        }
+    }
+}
+
+/// A bundle file on disk with its decoded [`Header`].
+#[derive(Debug, Clone)]
+pub struct File {
+    /// The decoded header of the bundle.
+    pub header: Header,
+    /// The path at which the bundle file was opened.
+    pub path: std::path::PathBuf,
+    /// The offset in bytes at which the pack starts.
+    pack_offset: u64,
+}
@@ -0,0 +1,167 @@
+use bstr::{BStr, BString, ByteSlice};
+
+/// How the bundles of a [`List`] relate to each other.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum Mode {
+    /// All bundles are needed to obtain the complete set of objects.
+    #[default]
+    All,
+    /// Any of the bundles is sufficient, as they are copies of each other, for instance in different geographic locations.
+    Any,
+}
+
+/// A bundle within a [`List`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct Item {
+    /// The identifier of the bundle, unique within its list.
+    pub id: BString,
+    /// The location of the bundle, or of yet another bundle list, which may be relative to the location of the list itself.
+    pub uri: BString,
+    /// The `creationToken` of the bundle, which orders bundles such that earlier ones provide the prerequisites of later ones.
+    pub creation_token: Option<u64>,
+}
+
+/// A [bundle list](https://git-scm.com/docs/bundle-uri#_bundle_lists) which advertises bundles to bootstrap a repository with.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Clone)]
+pub struct List {
+    /// How the bundles relate to each other.
+    pub mode: Mode,
+    /// The heuristic to use for ordering bundles, like `creationToken`.
+    pub heuristic: Option<BString>,
+    /// All bundles in the order in which they were listed.
+    pub bundles: Vec<Item>,
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod from_key_values {
+    use bstr::BString;
+
+    /// The error returned by [`List::from_key_values()`][crate::list::List::from_key_values()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The bundle list doesn't specify 'bundle.version'")]
+        MissingVersion,
+        #[error("Bundle list version {version:?} is not supported, only version 1 is")]
+        UnsupportedVersion { version: BString },
+        #[error("Bundle list mode {mode:?} is not supported, use 'all' or 'any'")]
+        UnsupportedMode { mode: BString },
+        #[error("The creation token {value:?} of bundle {id:?} is not a valid number")]
+        InvalidCreationToken { id: BString, value: BString },
+        #[error("The bundle {id:?} doesn't have an 'uri'")]
+        MissingUri { id: BString },
+    }
+}
+
+/// Initialization
+impl List {
+    /// Create a new list from `(key, value)` pairs like `bundle.version=1` or `bundle.<id>.uri=<uri>`, as received by the
+    /// `bundle-uri` protocol command or read from a bundle list file in `git-config` format.
+    ///
+    /// Section and key names are matched case-insensitively, and unknown keys are ignored.
+    /// Bundles are listed in the order in which their first key was seen.
+    pub fn from_key_values<K, V>(pairs: impl IntoIterator<Item = (K, V)>) -> Result<Self, from_key_values::Error>
+    where
+        K: AsRef<BStr>,
+        V: AsRef<BStr>,
+    {
+        use from_key_values::Error;
+        let mut version = None;
+        let mut mode = Mode::default();
+        let mut heuristic = None;
+        let mut ids = Vec::<BString>::new();
+        let mut uris = Vec::<Option<BString>>::new();
+        let mut tokens = Vec::<Option<u64>>::new();
+
+        for (key, value) in pairs {
+            let (key, value) = (key.as_ref(), value.as_ref());
+            let Some((section, rest)) = key.split_once_str(".") else {
+                continue;
+            };
+            if !section.eq_ignore_ascii_case(b"bundle") {
+                continue;
+            }
+            match rest.rsplit_once_str(".") {
+                None => {
+                    if rest.eq_ignore_ascii_case(b"version") {
+                        version = Some(value.to_owned());
+                    } else if rest.eq_ignore_ascii_case(b"mode") {
+                        mode = if value.eq_ignore_ascii_case(b"all") {
+                            Mode::All
+                        } else if value.eq_ignore_ascii_case(b"any") {
+                            Mode::Any
+                        } else {
+                            return Err(Error::UnsupportedMode { mode: value.into() });
+                        };
+                    } else if rest.eq_ignore_ascii_case(b"heuristic") {
+                        heuristic = Some(value.to_owned());
+                    }
+                }
+                Some((id, name)) => {
+                    let idx = match ids.iter().position(|existing| existing == id) {
+                        Some(idx) => idx,
+                        None => {
+                            ids.push(id.into());
+                            uris.push(None);
+                            tokens.push(None);
+                            ids.len() - 1
+                        }
+                    };
+                    if name.eq_ignore_ascii_case(b"uri") {
+                        uris[idx] = Some(value.into());
+                    } else if name.eq_ignore_ascii_case(b"creationtoken") {
+                        tokens[idx] =
+                            Some(value.to_str().ok().and_then(|v| v.trim().parse().ok()).ok_or_else(|| {
+                                Error::InvalidCreationToken {
+                                    id: id.into(),
+                                    value: value.into(),
+                                }
+                            })?);
+                    }
+                }
+            }
+        }
+
+        match version {
+            None => return Err(Error::MissingVersion),
+            Some(version) if version.trim() != b"1" => return Err(Error::UnsupportedVersion { version }),
+            Some(_) => {}
+        }
+        let bundles = ids
+            .into_iter()
+            .zip(uris)
+            .zip(tokens)
+            .map(|((id, uri), creation_token)| match uri {
+                Some(uri) => Ok(Item {
+                    id,
+                    uri,
+                    creation_token,
+                }),
+                None => Err(Error::MissingUri { id }),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(List {
+            mode,
+            heuristic,
+            bundles,
+        })
+    }
+}
+
+/// Access
+impl List {
+    /// Return the bundles in the order in which they should be applied, which is by ascending creation token
+    /// if the `creationToken` heuristic is used, or in the order they were listed otherwise.
+    pub fn bundles_in_application_order(&self) -> Vec<&Item> {
+        let mut out: Vec<_> = self.bundles.iter().collect();
+        if self
+            .heuristic
+            .as_ref()
+            .map_or(false, |h| h.eq_ignore_ascii_case(b"creationToken"))
+        {
+            out.sort_by_key(|item| item.creation_token.unwrap_or(u64::MAX));
+        }
+        out
+    }
+}
@@ -0,0 +1,305 @@
+//! A blocking transport which serves the refs and the pack of a bundle file as if it was a remote repository speaking protocol V1.
+//!
+//! This makes it possible to clone or fetch from bundles with the same machinery used for any other remote.
+use std::{
+    any::Any,
+    borrow::Cow,
+    io::{Cursor, Read},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_transport::{
+    client::{self, capabilities, Capabilities, MessageKind, RequestWriter, SetServiceResponse, WriteMode},
+    packetline::{self, PacketLineRef},
+    Protocol, Service,
+};
+
+/// The capabilities we advertise, just enough to make clients negotiate, send `done` and read a pack through the first side-band,
+/// along with the object format of the bundle.
+fn capabilities(object_hash: gix_hash::Kind) -> String {
+    format!(
+        "multi_ack_detailed side-band-64k ofs-delta object-format={} agent=gix-bundle",
+        object_hash.to_string().to_lowercase()
+    )
+}
+
+/// The error returned by [`connect()`].
+pub type Error = crate::file::open::Error;
+
+/// A connection to a bundle file, created with [`connect()`].
+pub struct Connection {
+    bundle: crate::File,
+    line_provider: packetline::StreamingPeekableIter<Box<dyn Read + Send>>,
+    trace: bool,
+}
+
+/// Open the bundle file at `path` to use it as transport.
+///
+/// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+pub fn connect(path: impl Into<PathBuf>, trace: bool) -> Result<Connection, Error> {
+    Ok(Connection {
+        bundle: crate::File::at(path)?,
+        line_provider: packetline::StreamingPeekableIter::new(
+            Box::new(std::io::empty()),
+            &[PacketLineRef::Flush],
+            trace,
+        ),
+        trace,
+    })
+}
+
+/// Access
+impl Connection {
+    /// Return the bundle we are serving.
+    pub fn bundle(&self) -> &crate::File {
+        &self.bundle
+    }
+}
+
+impl client::TransportWithoutIO for Connection {
+    fn request(
+        &mut self,
+        write_mode: WriteMode,
+        on_into_read: MessageKind,
+        trace: bool,
+    ) -> Result<RequestWriter<'_>, client::Error> {
+        let request = Arc::new(Mutex::new(Vec::new()));
+        self.line_provider = packetline::StreamingPeekableIter::new(
+            Box::new(Response::Pending {
+                request: request.clone(),
+                path: self.bundle.path.clone(),
+                pack_offset: self.bundle.pack_offset(),
+            }),
+            &[PacketLineRef::Flush],
+            self.trace,
+        );
+        self.line_provider.fail_on_err_lines(true);
+        Ok(RequestWriter::new_from_bufread(
+            Request(request),
+            Box::new(self.line_provider.as_read_without_sidebands()),
+            write_mode,
+            on_into_read,
+            trace,
+        ))
+    }
+
+    fn to_url(&self) -> Cow<'_, BStr> {
+        Cow::Owned(file_url(&self.bundle.path))
+    }
+
+    fn supported_protocol_versions(&self) -> &[Protocol] {
+        &[Protocol::V1]
+    }
+
+    fn connection_persists_across_multiple_requests(&self) -> bool {
+        true
+    }
+
+    fn configure(&mut self, _config: &dyn Any) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        Ok(())
+    }
+}
+
+impl client::Transport for Connection {
+    fn handshake<'a>(
+        &mut self,
+        service: Service,
+        _extra_parameters: &'a [(&'a str, Option<&'a str>)],
+    ) -> Result<SetServiceResponse<'_>, client::Error> {
+        if service != Service::UploadPack {
+            return Err(client::Error::Io(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                format!("bundles can only be read from, {} is unsupported", service.as_str()),
+            )));
+        }
+        let mut advertisement = Vec::new();
+        encode_ref_advertisement(&self.bundle.header, &mut advertisement)?;
+        self.line_provider = packetline::StreamingPeekableIter::new(
+            Box::new(Cursor::new(advertisement)),
+            &[PacketLineRef::Flush],
+            self.trace,
+        );
+        let capabilities::recv::Outcome {
+            capabilities,
+            refs,
+            protocol: actual_protocol,
+        } = Capabilities::from_lines_with_version_detection(&mut self.line_provider)?;
+        Ok(SetServiceResponse {
+            actual_protocol,
+            capabilities,
+            refs,
+        })
+    }
+}
+
+fn file_url(path: &std::path::Path) -> BString {
+    let mut url = BString::from("file://");
+    url.extend_from_slice(path.to_string_lossy().as_bytes());
+    url
+}
+
+/// Write the refs of `header` like `git upload-pack` would advertise them in protocol V1.
+fn encode_ref_advertisement(header: &crate::Header, out: &mut Vec<u8>) -> std::io::Result<()> {
+    let capabilities = capabilities(header.object_hash);
+    let mut line = Vec::new();
+    if header.refs.is_empty() {
+        line.extend_from_slice(header.object_hash.null().to_string().as_bytes());
+        line.extend_from_slice(b" capabilities^{}\0");
+        line.extend_from_slice(capabilities.as_bytes());
+        line.push(b'\n');
+        packetline::encode::data_to_write(&line, &mut *out)?;
+    }
+    for (idx, r) in header.refs.iter().enumerate() {
+        line.clear();
+        line.extend_from_slice(r.id.to_string().as_bytes());
+        line.push(b' ');
+        line.extend_from_slice(&r.name);
+        if idx == 0 {
+            line.push(0);
+            line.extend_from_slice(capabilities.as_bytes());
+        }
+        line.push(b'\n');
+        packetline::encode::data_to_write(&line, &mut *out)?;
+    }
+    packetline::encode::flush_to_write(out)?;
+    Ok(())
+}
+
+/// Collects all packetlines sent by the client as part of a single request.
+struct Request(Arc<Mutex<Vec<u8>>>);
+
+impl std::io::Write for Request {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0
+            .lock()
+            .expect("no panic while holding lock")
+            .extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The response to a request, produced lazily once the client starts reading as only then the request is known to be complete.
+enum Response {
+    Pending {
+        request: Arc<Mutex<Vec<u8>>>,
+        path: PathBuf,
+        pack_offset: u64,
+    },
+    Streaming(Box<dyn Read + Send>),
+}
+
+impl Response {
+    fn into_stream(request: &[u8], path: &std::path::Path, pack_offset: u64) -> std::io::Result<Box<dyn Read + Send>> {
+        let mut is_done = false;
+        let mut request = request;
+        while !request.is_empty() {
+            match packetline::decode::streaming(request)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?
+            {
+                packetline::decode::Stream::Complete { line, bytes_consumed } => {
+                    is_done |= line.as_slice().map_or(false, |data| data.trim_end() == b"done");
+                    request = &request[bytes_consumed..];
+                }
+                packetline::decode::Stream::Incomplete { .. } => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::UnexpectedEof,
+                        "the request ended with an incomplete packetline",
+                    ))
+                }
+            }
+        }
+
+        // We never have anything in common with the client, and always send everything we have once it is done negotiating.
+        let mut nak = Vec::new();
+        packetline::encode::data_to_write(b"NAK\n", &mut nak)?;
+        let nak = Cursor::new(nak);
+        Ok(if is_done {
+            let mut pack = std::fs::File::open(path)?;
+            std::io::Seek::seek(&mut pack, std::io::SeekFrom::Start(pack_offset))?;
+            let mut flush = Vec::new();
+            packetline::encode::flush_to_write(&mut flush)?;
+            Box::new(nak.chain(SideBand::new(pack)).chain(Cursor::new(flush)))
+        } else {
+            Box::new(nak)
+        })
+    }
+}
+
+impl Read for Response {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            match self {
+                Response::Pending {
+                    request,
+                    path,
+                    pack_offset,
+                } => {
+                    let stream = {
+                        let request = request.lock().expect("no panic while holding lock");
+                        Response::into_stream(&request, path, *pack_offset)?
+                    };
+                    *self = Response::Streaming(stream);
+                }
+                Response::Streaming(stream) => return stream.read(buf),
+            }
+        }
+    }
+}
+
+/// Encodes everything read from the inner reader as packetlines in the data side-band.
+struct SideBand<R> {
+    inner: R,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl<R> SideBand<R> {
+    /// The maximum amount of data in a packetline minus the byte that designates the band.
+    const CHUNK_SIZE: usize = 65515;
+
+    fn new(inner: R) -> Self {
+        SideBand {
+            inner,
+            buf: Vec::new(),
+            pos: 0,
+        }
+    }
+}
+
+impl<R: Read> Read for SideBand<R> {
+    fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+        if self.pos == self.buf.len() {
+            let mut chunk = vec![0; Self::CHUNK_SIZE];
+            let num_read = read_fill(&mut self.inner, &mut chunk)?;
+            if num_read == 0 {
+                return Ok(0);
+            }
+            self.buf.clear();
+            self.pos = 0;
+            packetline::encode::band_to_write(packetline::Channel::Data, &chunk[..num_read], &mut self.buf)?;
+        }
+        let num_copied = (self.buf.len() - self.pos).min(out.len());
+        out[..num_copied].copy_from_slice(&self.buf[self.pos..][..num_copied]);
+        self.pos += num_copied;
+        Ok(num_copied)
+    }
+}
+
+fn read_fill(read: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match read.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(filled)
+}
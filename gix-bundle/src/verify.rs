@@ -0,0 +1,32 @@
+use gix_hash::ObjectId;
+
+use crate::Header;
+
+/// The error returned by [`Header::verify_prerequisites()`].
+#[derive(Debug, thiserror::Error)]
+#[error("The bundle requires {} commit(s) which are not present in the object database: {}", missing.len(), missing.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+pub struct Error {
+    /// The ids of all prerequisites which were not found, in the order in which they appear in the bundle.
+    pub missing: Vec<ObjectId>,
+}
+
+/// Verification
+impl Header {
+    /// Check that all [prerequisites][Header::prerequisites] of this bundle exist in `objects`, which is required
+    /// for its pack to be usable, similar to `git bundle verify`.
+    ///
+    /// Note that connectivity isn't verified, we merely assume that objects reachable from present commits are present as well.
+    pub fn verify_prerequisites(&self, objects: &dyn gix_object::Exists) -> Result<(), Error> {
+        let missing: Vec<_> = self
+            .prerequisites
+            .iter()
+            .filter(|p| !objects.exists(&p.id))
+            .map(|p| p.id)
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(Error { missing })
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+use gix_hash::ObjectId;
+
+pub fn hex_to_id(hex: &str) -> ObjectId {
+    ObjectId::from_hex(hex.as_bytes()).expect("40 bytes hex")
+}
+
+pub fn fixture_dir() -> PathBuf {
+    gix_testtools::scripted_fixture_read_only("make_bundles.sh").expect("fixture is valid")
+}
+
+/// Return the ids of `main`, `main~1`, the annotated tag `v2` and `other` in the fixture repository.
+pub fn fixture_ids() -> [ObjectId; 4] {
+    let ids = std::fs::read_to_string(fixture_dir().join("ids")).expect("ids were written");
+    let ids: Vec<_> = ids.lines().map(hex_to_id).collect();
+    ids.try_into().expect("four ids")
+}
+
+pub fn odb(name: &str) -> gix_odb::HandleArc {
+    let store = gix_odb::Store::at_opts(
+        fixture_dir().join(name).join(".git").join("objects"),
+        &mut None.into_iter(),
+        Default::default(),
+    )
+    .expect("valid odb");
+    let mut handle = std::sync::Arc::new(store).to_cache_arc();
+    handle.prevent_pack_unload();
+    handle
+}
+
+mod header;
+mod list;
+mod verify;
+
+#[cfg(feature = "create")]
+mod create;
+#[cfg(feature = "blocking-client")]
+mod transport;
@@ -0,0 +1,109 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_bundle::{create, File, Header, Prerequisite, Ref};
+use gix_features::progress;
+
+use crate::{fixture_ids, odb};
+
+fn create_and_reopen(
+    refs: Vec<Ref>,
+    exclude: Vec<gix_hash::ObjectId>,
+) -> gix_testtools::Result<(create::Outcome, File, gix_testtools::tempfile::TempDir)> {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let path = tmp.path().join("out.bundle");
+    let mut out = std::fs::File::create(&path)?;
+    let outcome = gix_bundle::create(
+        odb("base"),
+        refs,
+        exclude,
+        &mut out,
+        &mut progress::Discard,
+        &AtomicBool::default(),
+        create::Options::default(),
+    )?;
+    drop(out);
+    let bundle = File::at(&path)?;
+    Ok((outcome, bundle, tmp))
+}
+
+fn index_pack(bundle: &File, dir: &std::path::Path) -> gix_testtools::Result<gix_pack::bundle::write::Outcome> {
+    Ok(gix_pack::Bundle::write_to_directory(
+        &mut bundle.pack_reader()?,
+        Some(dir),
+        &mut progress::Discard,
+        &AtomicBool::default(),
+        Some(&odb("base")),
+        Default::default(),
+    )?)
+}
+
+#[test]
+fn with_prerequisites() -> gix_testtools::Result {
+    let [main, main_parent, _v2, _other] = fixture_ids();
+    let refs = vec![Ref {
+        id: main,
+        name: "refs/heads/main".into(),
+    }];
+    let (outcome, bundle, tmp) = create_and_reopen(refs.clone(), vec![main_parent])?;
+    assert_eq!(
+        bundle.header,
+        Header::new(
+            refs,
+            Some(Prerequisite {
+                id: main_parent,
+                comment: "second".into()
+            }),
+            gix_hash::Kind::Sha1,
+            None
+        )
+    );
+    assert_eq!(outcome.header, bundle.header, "the written header is returned");
+    bundle.header.verify_prerequisites(&odb("base"))?;
+
+    let indexed = index_pack(&bundle, tmp.path())?;
+    assert_eq!(indexed.index.data_hash, outcome.pack_hash);
+    assert_eq!(indexed.index.num_objects as usize, outcome.num_objects);
+    Ok(())
+}
+
+#[test]
+fn all_refs_with_annotated_tag() -> gix_testtools::Result {
+    let [main, _main_parent, v2, other] = fixture_ids();
+    let refs = vec![
+        Ref {
+            id: main,
+            name: "refs/heads/main".into(),
+        },
+        Ref {
+            id: v2,
+            name: "refs/tags/v2".into(),
+        },
+        Ref {
+            id: other,
+            name: "refs/heads/other".into(),
+        },
+    ];
+    let (outcome, bundle, tmp) = create_and_reopen(refs, Vec::new())?;
+    assert!(bundle.header.is_self_contained());
+    assert_eq!(
+        outcome.num_objects,
+        4 /* commits */ + 4 /* trees */ + 4 /* blobs */ + 1, /* tag */
+        "everything is contained"
+    );
+    index_pack(&bundle, tmp.path())?;
+    Ok(())
+}
+
+#[test]
+fn nothing_new_is_an_error() {
+    let [main, _main_parent, _v2, _other] = fixture_ids();
+    let err = create_and_reopen(
+        vec![Ref {
+            id: main,
+            name: "refs/heads/main".into(),
+        }],
+        vec![main],
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("empty bundle"));
+}
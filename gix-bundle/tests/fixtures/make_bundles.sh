@@ -0,0 +1,20 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q base
+(cd base
+  echo 1 > file && git add file && git commit -q -m "first"
+  echo 2 > file && git commit -q -am "second"
+  git tag -a -m "annotated" v2
+  echo 3 > file && git commit -q -am "third"
+  git checkout -q -b other HEAD~1
+  echo other > other && git add other && git commit -q -m "other"
+  git checkout -q main
+)
+
+git -C base bundle create ../all.bundle --all
+git -C base bundle create ../incremental.bundle main~1..main
+git -C base bundle create --version=3 ../v3.bundle main
+git -C base rev-parse main main~1 v2 other > ids
+
+git init -q empty
@@ -0,0 +1,149 @@
+use gix_bundle::{header::decode, File, Header, Prerequisite, Ref, Version};
+use gix_testtools::bstr::ByteSlice;
+
+use crate::{fixture_dir, fixture_ids};
+
+#[test]
+fn all_refs_without_prerequisites() -> gix_testtools::Result {
+    let bundle = File::at(fixture_dir().join("all.bundle"))?;
+    let [main, _main_parent, v2, other] = fixture_ids();
+    assert_eq!(bundle.header.version, Version::V2);
+    assert_eq!(bundle.header.object_hash, gix_hash::Kind::Sha1);
+    assert!(bundle.header.is_self_contained());
+    assert_eq!(
+        bundle.header.refs,
+        vec![
+            Ref {
+                id: main,
+                name: "refs/heads/main".into()
+            },
+            Ref {
+                id: other,
+                name: "refs/heads/other".into()
+            },
+            Ref {
+                id: v2,
+                name: "refs/tags/v2".into()
+            },
+            Ref {
+                id: main,
+                name: "HEAD".into()
+            },
+        ]
+    );
+    let mut pack = Vec::new();
+    std::io::Read::read_to_end(&mut bundle.pack_reader()?, &mut pack)?;
+    assert!(pack.starts_with(b"PACK"), "the pack is located right after the header");
+    Ok(())
+}
+
+#[test]
+fn prerequisites_with_comments() -> gix_testtools::Result {
+    let bundle = File::at(fixture_dir().join("incremental.bundle"))?;
+    let [main, main_parent, _v2, _other] = fixture_ids();
+    assert_eq!(
+        bundle.header.prerequisites,
+        vec![Prerequisite {
+            id: main_parent,
+            comment: "second".into()
+        }]
+    );
+    assert_eq!(
+        bundle.header.refs,
+        vec![Ref {
+            id: main,
+            name: "refs/heads/main".into()
+        }]
+    );
+    assert!(!bundle.header.is_self_contained());
+    Ok(())
+}
+
+#[test]
+fn v3_with_object_format() -> gix_testtools::Result {
+    let bundle = File::at(fixture_dir().join("v3.bundle"))?;
+    assert_eq!(bundle.header.version, Version::V3);
+    assert_eq!(bundle.header.object_hash, gix_hash::Kind::Sha1);
+    assert_eq!(bundle.header.filter, None);
+    Ok(())
+}
+
+#[test]
+fn v3_with_filter_round_trips() -> gix_testtools::Result {
+    let input = b"# v3 git bundle\n@object-format=sha1\n@filter=blob:none\n-ffffffffffffffffffffffffffffffffffffffff\n1111111111111111111111111111111111111111 refs/heads/main\n\nPACK";
+    let mut read = &input[..];
+    let (header, pack_offset) = Header::from_read(&mut read)?;
+    assert_eq!(
+        header.filter.as_ref().map(|f| f.as_bstr()),
+        Some(b"blob:none".as_bstr())
+    );
+    assert_eq!(header.prerequisites.len(), 1);
+    assert_eq!(header.prerequisites[0].comment, "", "comments are optional");
+    assert_eq!(&input[pack_offset as usize..], b"PACK");
+
+    let mut out = Vec::new();
+    header.write_to(&mut out)?;
+    assert_eq!(out.as_bstr(), input[..input.len() - 4].as_bstr());
+    Ok(())
+}
+
+#[test]
+fn v2_can_not_have_capabilities() {
+    let input = b"# v2 git bundle\n@object-format=sha1\n\n";
+    assert!(matches!(
+        Header::from_read(&mut &input[..]),
+        Err(decode::Error::MisplacedCapability { .. })
+    ));
+
+    let header = Header {
+        filter: Some("blob:none".into()),
+        ..Default::default()
+    };
+    assert!(
+        header.write_to(&mut Vec::new()).is_err(),
+        "a filter can't be represented in v2"
+    );
+}
+
+#[test]
+fn unknown_capabilities_and_signatures_are_rejected() {
+    for (input, expected) in [
+        (&b"# v4 git bundle\n\n"[..], "Unknown bundle signature"),
+        (b"# v3 git bundle\n@unknown\n\n", "capability"),
+        (b"# v3 git bundle\n@object-format=sha256\n\n", "object format"),
+        (
+            b"# v2 git bundle\n1111111111111111111111111111111111111111 refs/heads/main\n",
+            "ended",
+        ),
+        (
+            b"# v2 git bundle\n1111111111111111111111111111111111111111\n\n",
+            "not a valid reference",
+        ),
+    ] {
+        let err = Header::from_read(&mut &input[..]).unwrap_err();
+        assert!(err.to_string().contains(expected), "{err} should contain {expected}");
+    }
+}
+
+#[test]
+fn list_heads_matches_trailing_components() -> gix_testtools::Result {
+    let bundle = File::at(fixture_dir().join("all.bundle"))?;
+    let names: Vec<_> = bundle
+        .header
+        .list_heads(&["main".into(), "HEAD".into()])
+        .map(|r| r.name.clone())
+        .collect();
+    assert_eq!(names, vec!["refs/heads/main", "HEAD"]);
+    assert_eq!(bundle.header.list_heads(&[]).count(), 4, "no name means all refs");
+    assert_eq!(bundle.header.list_heads(&["ain".into()]).count(), 0);
+    Ok(())
+}
+
+#[test]
+fn is_bundle() {
+    let dir = fixture_dir();
+    assert!(gix_bundle::file::is_bundle(&dir.join("all.bundle")));
+    assert!(gix_bundle::file::is_bundle(&dir.join("v3.bundle")));
+    assert!(!gix_bundle::file::is_bundle(&dir.join("ids")));
+    assert!(!gix_bundle::file::is_bundle(&dir.join("base")));
+}
@@ -0,0 +1,89 @@
+use gix_bundle::list::{from_key_values, Item, List, Mode};
+
+#[test]
+fn from_key_values_with_creation_tokens() -> gix_testtools::Result {
+    let list = List::from_key_values([
+        ("bundle.version", "1"),
+        ("bundle.mode", "all"),
+        ("bundle.heuristic", "creationToken"),
+        ("bundle.incremental.uri", "incremental.bundle"),
+        ("bundle.incremental.creationToken", "2"),
+        ("bundle.base.uri", "https://example.com/base.bundle"),
+        ("bundle.base.creationToken", "1"),
+        ("bundle.base.unknownKey", "ignored"),
+    ])?;
+    assert_eq!(list.mode, Mode::All);
+    assert_eq!(
+        list.bundles,
+        vec![
+            Item {
+                id: "incremental".into(),
+                uri: "incremental.bundle".into(),
+                creation_token: Some(2),
+            },
+            Item {
+                id: "base".into(),
+                uri: "https://example.com/base.bundle".into(),
+                creation_token: Some(1),
+            }
+        ],
+        "bundles are kept in order of appearance"
+    );
+    assert_eq!(
+        list.bundles_in_application_order()
+            .into_iter()
+            .map(|item| item.id.to_string())
+            .collect::<Vec<_>>(),
+        ["base", "incremental"],
+        "the lowest creation token is applied first"
+    );
+    Ok(())
+}
+
+#[test]
+fn ids_may_contain_dots_and_keys_are_case_insensitive() -> gix_testtools::Result {
+    let list = List::from_key_values([
+        ("Bundle.Version", "1"),
+        ("BUNDLE.MODE", "any"),
+        ("bundle.eu.west.URI", "https://eu.example.com/all.bundle"),
+        ("remote.origin.url", "ignored"),
+    ])?;
+    assert_eq!(list.mode, Mode::Any);
+    assert_eq!(
+        list.bundles,
+        vec![Item {
+            id: "eu.west".into(),
+            uri: "https://eu.example.com/all.bundle".into(),
+            creation_token: None,
+        }]
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_lists() {
+    assert!(matches!(
+        List::from_key_values([("bundle.one.uri", "a.bundle")]),
+        Err(from_key_values::Error::MissingVersion)
+    ));
+    assert!(matches!(
+        List::from_key_values([("bundle.version", "2")]),
+        Err(from_key_values::Error::UnsupportedVersion { .. })
+    ));
+    assert!(matches!(
+        List::from_key_values([("bundle.version", "1"), ("bundle.mode", "some")]),
+        Err(from_key_values::Error::UnsupportedMode { .. })
+    ));
+    assert!(matches!(
+        List::from_key_values([("bundle.version", "1"), ("bundle.one.creationToken", "1")]),
+        Err(from_key_values::Error::MissingUri { .. })
+    ));
+    assert!(matches!(
+        List::from_key_values([
+            ("bundle.version", "1"),
+            ("bundle.one.uri", "a.bundle"),
+            ("bundle.one.creationToken", "nan")
+        ]),
+        Err(from_key_values::Error::InvalidCreationToken { .. })
+    ));
+}
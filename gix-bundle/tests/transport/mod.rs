@@ -0,0 +1,63 @@
+use std::io::{BufRead, Read, Write};
+
+use gix_transport::{
+    client::{MessageKind, Transport, TransportWithoutIO, WriteMode},
+    Protocol, Service,
+};
+
+use crate::{fixture_dir, fixture_ids};
+
+#[test]
+fn handshake_advertises_refs_in_v1() -> gix_testtools::Result {
+    let mut transport = gix_bundle::transport::connect(fixture_dir().join("incremental.bundle"), false)?;
+    let res = transport.handshake(Service::UploadPack, &[])?;
+    assert_eq!(res.actual_protocol, Protocol::V1);
+    assert!(res.capabilities.contains("side-band-64k"));
+    assert_eq!(
+        res.capabilities.capability("object-format").and_then(|c| c.value()),
+        Some("sha1".into()),
+        "the object format is the one of the bundle"
+    );
+    let mut refs = String::new();
+    res.refs.expect("refs in V1").read_to_string(&mut refs)?;
+    assert_eq!(refs, format!("{} refs/heads/main\n", fixture_ids()[0]));
+    Ok(())
+}
+
+#[test]
+fn pushing_is_unsupported() -> gix_testtools::Result {
+    let mut transport = gix_bundle::transport::connect(fixture_dir().join("all.bundle"), false)?;
+    assert!(transport.handshake(Service::ReceivePack, &[]).is_err());
+    Ok(())
+}
+
+#[test]
+fn pack_is_sent_once_done() -> gix_testtools::Result {
+    let bundle_path = fixture_dir().join("all.bundle");
+    let mut transport = gix_bundle::transport::connect(&bundle_path, false)?;
+    drop(transport.handshake(Service::UploadPack, &[])?);
+
+    let mut writer = transport.request(
+        WriteMode::OneLfTerminatedLinePerWriteCall,
+        MessageKind::Text(b"done"),
+        false,
+    )?;
+    writer.write_all(format!("want {}", fixture_ids()[0]).as_bytes())?;
+    writer.write_message(MessageKind::Flush)?;
+    let mut reader = writer.into_read()?;
+    let mut line = String::new();
+    reader.readline_str(&mut line)?;
+    assert_eq!(line, "NAK\n");
+    reader.set_progress_handler(Some(Box::new(|_is_err, _text| {
+        gix_transport::packetline::read::ProgressAction::Continue
+    })));
+    let mut pack = Vec::new();
+    reader.read_to_end(&mut pack)?;
+
+    let bundle = gix_bundle::File::at(&bundle_path)?;
+    let mut expected = Vec::new();
+    bundle.pack_reader()?.read_to_end(&mut expected)?;
+    assert_eq!(pack, expected, "the pack is streamed through the data side-band");
+    assert!(reader.fill_buf()?.is_empty());
+    Ok(())
+}
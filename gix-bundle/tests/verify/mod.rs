@@ -0,0 +1,24 @@
+use gix_bundle::File;
+
+use crate::{fixture_dir, fixture_ids, odb};
+
+#[test]
+fn prerequisites_present() -> gix_testtools::Result {
+    let bundle = File::at(fixture_dir().join("incremental.bundle"))?;
+    bundle.header.verify_prerequisites(&odb("base"))?;
+    Ok(())
+}
+
+#[test]
+fn prerequisites_missing() -> gix_testtools::Result {
+    let bundle = File::at(fixture_dir().join("incremental.bundle"))?;
+    let err = bundle
+        .header
+        .verify_prerequisites(&odb("empty"))
+        .expect_err("the empty repository has no objects");
+    assert_eq!(err.missing, vec![fixture_ids()[1]]);
+
+    let bundle = File::at(fixture_dir().join("all.bundle"))?;
+    bundle.header.verify_prerequisites(&odb("empty"))?;
+    Ok(())
+}
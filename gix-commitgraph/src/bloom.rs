@@ -0,0 +1,158 @@
+//! Changed-path Bloom filters as written by `git commit-graph write --changed-paths`.
+//!
+//! Each commit may have a filter which contains all paths that changed compared to its first parent, along with all
+//! of their leading directories. A filter can tell for sure that a path did *not* change, which allows to skip
+//! tree-diffs when following the history of a path.
+use bstr::{BStr, ByteSlice};
+
+/// The settings stored in the header of the Bloom filter data chunk, which are needed to compute [keys](Key).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Settings {
+    /// The version of the hash function, which is `1` for the original implementation that sign-extends bytes with
+    /// the high bit set, or `2` for the one that hashes them correctly.
+    pub hash_version: u32,
+    /// The amount of hashes to compute for each path.
+    pub num_hashes: u32,
+    /// The amount of bits used per changed path entry.
+    pub bits_per_entry: u32,
+}
+
+impl Settings {
+    /// Decode settings from the header of a Bloom filter data chunk, returning `None` if it is too short or if the
+    /// hash version isn't supported.
+    pub(crate) fn from_bytes(header: &[u8]) -> Option<Self> {
+        let read = |idx: usize| Some(u32::from_be_bytes(header.get(idx * 4..)?.get(..4)?.try_into().ok()?));
+        let settings = Settings {
+            hash_version: read(0)?,
+            num_hashes: read(1)?,
+            bits_per_entry: read(2)?,
+        };
+        (matches!(settings.hash_version, 1 | 2) && settings.num_hashes > 0).then_some(settings)
+    }
+}
+
+/// The key of a path, for checking if it is contained in a [`Filter`].
+///
+/// It holds the hashes of the path as well as of all of its leading directories, which all have to be contained
+/// in a filter for the path to be possibly contained, reducing the amount of false positives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Key {
+    settings: Settings,
+    hashes: Vec<u32>,
+}
+
+impl Key {
+    /// Compute the key for `path`, a slash-separated path relative to the root of the repository, using `settings` which
+    /// must match the ones of the filters it is used with.
+    pub fn new(path: &BStr, settings: Settings) -> Self {
+        let mut hashes = Vec::new();
+        let mut path = path.trim_end_with(|c| c == '/');
+        while !path.is_empty() {
+            hashes.extend(hashes_of(path, settings));
+            path = path.rfind_byte(b'/').map_or(&[][..], |pos| &path[..pos]);
+        }
+        Key { settings, hashes }
+    }
+
+    /// Return the settings this key was computed with.
+    pub fn settings(&self) -> Settings {
+        self.settings
+    }
+}
+
+/// A Bloom filter of all paths that changed in a commit compared to its first parent.
+#[derive(Debug, Clone, Copy)]
+pub struct Filter<'a> {
+    pub(crate) data: &'a [u8],
+    pub(crate) settings: Settings,
+}
+
+impl<'a> Filter<'a> {
+    /// Return the settings of this filter, which must be used to compute [keys](Key) for use in [`contains()`](Self::contains()).
+    pub fn settings(&self) -> Settings {
+        self.settings
+    }
+
+    /// Return `false` if the path of `key` definitely did not change, or `true` if it might have changed.
+    ///
+    /// # Panics
+    ///
+    /// If `key` was computed with different settings than this filter.
+    pub fn contains(&self, key: &Key) -> bool {
+        assert_eq!(
+            key.settings, self.settings,
+            "BUG: keys must be computed with the settings of the filter"
+        );
+        let num_bits = self.data.len() as u64 * 8;
+        key.hashes.iter().all(|hash| {
+            let bit = u64::from(*hash) % num_bits;
+            self.data[(bit / 8) as usize] & (1 << (bit % 8)) != 0
+        })
+    }
+}
+
+fn hashes_of(path: &[u8], settings: Settings) -> impl Iterator<Item = u32> {
+    const SEED0: u32 = 0x293a_e76f;
+    const SEED1: u32 = 0x7e64_6e2c;
+    let sign_extend = settings.hash_version == 1;
+    let hash0 = murmur3(SEED0, path, sign_extend);
+    let hash1 = murmur3(SEED1, path, sign_extend);
+    (0..settings.num_hashes).map(move |idx| hash0.wrapping_add(idx.wrapping_mul(hash1)))
+}
+
+/// The 32 bit murmur3 hash as implemented by `git`, optionally with the `sign_extend` bug of its first version.
+fn murmur3(mut seed: u32, data: &[u8], sign_extend: bool) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    let byte = |b: u8| if sign_extend { b as i8 as u32 } else { u32::from(b) };
+
+    let mut chunks = data.chunks_exact(4);
+    for chunk in chunks.by_ref() {
+        let k = byte(chunk[0]) | byte(chunk[1]) << 8 | byte(chunk[2]) << 16 | byte(chunk[3]) << 24;
+        seed ^= k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        seed = seed.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        let k = tail
+            .iter()
+            .enumerate()
+            .fold(0u32, |k, (idx, b)| k ^ byte(*b) << (8 * idx));
+        seed ^= k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+    }
+
+    seed ^= data.len() as u32;
+    seed ^= seed >> 16;
+    seed = seed.wrapping_mul(0x85eb_ca6b);
+    seed ^= seed >> 13;
+    seed = seed.wrapping_mul(0xc2b2_ae35);
+    seed ^ (seed >> 16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::murmur3;
+
+    #[test]
+    fn murmur3_matches_git() {
+        // Values from git's `t0095-bloom.sh`.
+        assert_eq!(murmur3(0, b"", false), 0x0000_0000);
+        assert_eq!(murmur3(0, b"Hello world!", false), 0x627b_0c2c);
+        assert_eq!(
+            murmur3(0, b"The quick brown fox jumps over the lazy dog", false),
+            0x2e4f_f723
+        );
+    }
+
+    #[test]
+    fn murmur3_v1_sign_extends_bytes_with_high_bit() {
+        let data = b"\x99\xaa\xbb\xcc\xdd\xee\xff";
+        assert_eq!(murmur3(0, data, false), 0xa183_ccfd);
+        assert_eq!(murmur3(0, data, true), 0xdd92_776e);
+        assert_eq!(
+            murmur3(0, b"ascii", true),
+            murmur3(0, b"ascii", false),
+            "both versions agree on ascii input"
+        );
+    }
+}
@@ -4,6 +4,7 @@ use std::{
 };
 
 use crate::{
+    bloom,
     file::{self, commit::Commit, COMMIT_DATA_ENTRY_SIZE_SANS_HASH},
     File,
 };
@@ -15,6 +16,38 @@ impl File {
         self.base_graph_count
     }
 
+    /// Returns the changed-path Bloom filter of the commit at the given lexicographical position, or `None` if this file
+    /// doesn't contain Bloom filters or if no filter was computed for the commit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is out of bounds.
+    pub fn bloom_filter_at(&self, pos: file::Position) -> Option<bloom::Filter<'_>> {
+        assert!(
+            pos.0 < self.num_commits(),
+            "expected lexicographical position less than {}, got {}",
+            self.num_commits(),
+            pos.0
+        );
+        let filters = self.bloom_filters.as_ref()?;
+        let end_of = |pos: usize| -> Option<usize> {
+            let bytes = self.data.get(filters.index_offset + pos * 4..)?.get(..4)?;
+            usize::try_from(u32::from_be_bytes(bytes.try_into().ok()?)).ok()
+        };
+        let pos = usize::try_from(pos.0).expect("an architecture able to hold 32 bits of integer");
+        let start = if pos == 0 { 0 } else { end_of(pos - 1)? };
+        let data = self.data.get(filters.data.clone())?.get(start..end_of(pos)?)?;
+        (!data.is_empty()).then_some(bloom::Filter {
+            data,
+            settings: filters.settings,
+        })
+    }
+
+    /// Returns the settings of the changed-path Bloom filters in this file, if there are any.
+    pub fn bloom_filter_settings(&self) -> Option<bloom::Settings> {
+        self.bloom_filters.as_ref().map(|filters| filters.settings)
+    }
+
     /// Returns the commit data for the commit located at the given lexicographical position.
     ///
     /// `pos` must range from 0 to `self.num_commits()`.
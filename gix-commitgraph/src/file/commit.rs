@@ -59,6 +59,12 @@ impl<'a> Commit<'a> {
         }
     }
 
+    /// Returns the changed-path Bloom filter of this commit, with all paths that changed compared to its first parent,
+    /// or `None` if it isn't available.
+    pub fn bloom_filter(&self) -> Option<crate::bloom::Filter<'a>> {
+        self.file.bloom_filter_at(self.pos)
+    }
+
     /// Returns the committer timestamp of this commit.
     ///
     /// The value is the number of seconds since 1970-01-01 00:00:00 UTC.
@@ -2,8 +2,10 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use crate::{
+    bloom,
     file::{
-        ChunkId, BASE_GRAPHS_LIST_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
+        BloomFilters, ChunkId, BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN,
+        BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
         EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, HEADER_LEN, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
     },
     File,
@@ -184,6 +186,22 @@ impl File {
 
         let extra_edges_list_range = chunks.usize_offset_by_id(EXTENDED_EDGES_LIST_CHUNK_ID).ok();
 
+        // Like git, ignore Bloom filters that can't be used instead of failing.
+        let bloom_filters = chunks
+            .usize_offset_by_id(BLOOM_FILTER_INDEX_CHUNK_ID)
+            .ok()
+            .filter(|index| index.len() == commit_data_count as usize * 4)
+            .zip(chunks.usize_offset_by_id(BLOOM_FILTER_DATA_CHUNK_ID).ok())
+            .and_then(|(index, filters)| {
+                let settings =
+                    bloom::Settings::from_bytes(data.get(filters.clone())?.get(..BLOOM_FILTER_DATA_HEADER_LEN)?)?;
+                Some(BloomFilters {
+                    index_offset: index.start,
+                    data: filters.start + BLOOM_FILTER_DATA_HEADER_LEN..filters.end,
+                    settings,
+                })
+            });
+
         let trailer = &data[chunks.highest_offset() as usize..];
         if trailer.len() != object_hash.len_in_bytes() {
             return Err(Error::Trailer(format!(
@@ -220,6 +238,7 @@ impl File {
         Ok(File {
             base_graph_count,
             base_graphs_list_offset,
+            bloom_filters,
             commit_data_offset,
             data,
             extra_edges_list_range,
@@ -0,0 +1,73 @@
+use gix_commitgraph::{bloom::Key, Graph};
+use gix_testtools::scripted_fixture_read_only;
+
+use crate::graph_and_expected;
+
+/// The paths that changed in each commit.
+type Changes = Vec<(gix_hash::ObjectId, Vec<String>)>;
+
+fn graph_and_changes() -> gix_testtools::Result<(Graph, Changes)> {
+    let repo_dir = scripted_fixture_read_only("changed_paths.sh")?;
+    let graph = Graph::from_info_dir(&repo_dir.join(".git").join("objects").join("info"))?;
+    let changes = std::fs::read_to_string(repo_dir.join("changes.git"))?
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .map(|block| {
+            let mut lines = block.lines();
+            let id = gix_hash::ObjectId::from_hex(lines.next().expect("commit").as_bytes()).expect("valid hex");
+            (id, lines.map(ToOwned::to_owned).collect())
+        })
+        .collect();
+    Ok((graph, changes))
+}
+
+#[test]
+fn changed_paths_and_their_directories_are_contained() -> gix_testtools::Result {
+    let (graph, changes) = graph_and_changes()?;
+    assert_eq!(changes.len(), 6);
+    for (id, paths) in changes {
+        let filter = graph
+            .commit_by_id(id)
+            .expect("present")
+            .bloom_filter()
+            .expect("all commits have filters");
+        assert_eq!(filter.settings().hash_version, 1, "the version git writes by default");
+        for path in &paths {
+            let key = Key::new(path.as_str().into(), filter.settings());
+            assert!(filter.contains(&key), "{id}: {path} changed");
+            if let Some((dir, _)) = path.rsplit_once('/') {
+                let key = Key::new(dir.into(), filter.settings());
+                assert!(filter.contains(&key), "{id}: leading directory {dir} changed");
+            }
+        }
+
+        let num_false_positives = (0..100)
+            .filter(|idx| {
+                filter.contains(&Key::new(
+                    format!("does-not-exist-{idx}").as_str().into(),
+                    filter.settings(),
+                ))
+            })
+            .count();
+        if paths.len() > 512 {
+            assert_eq!(
+                num_false_positives, 100,
+                "{id}: filters with too many changes contain everything"
+            );
+        } else {
+            assert!(
+                num_false_positives < 10,
+                "{id}: {num_false_positives} paths that never changed are reported as changed"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn graphs_without_changed_paths_have_no_filters() {
+    let (graph, refs) = graph_and_expected("single_parent.sh", &["child"]);
+    let commit = graph.commit_at(refs["child"].pos());
+    assert!(commit.bloom_filter().is_none());
+    assert!(graph.iter_commits().all(|commit| commit.bloom_filter().is_none()));
+}
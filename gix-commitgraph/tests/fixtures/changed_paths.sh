@@ -0,0 +1,31 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+mkdir -p dir/sub
+echo a > a && echo b > dir/sub/b && echo c > "dir/ümlaut"
+git add . && git commit -q -m "root"
+
+echo changed > dir/sub/b
+git commit -q -am "change nested file"
+
+git mv a renamed
+git commit -q -m "rename"
+
+git commit -q --allow-empty -m "empty"
+
+mkdir many
+for i in $(seq 600); do echo $i > many/$i; done
+git add many && git commit -q -m "more changes than a filter can hold"
+
+echo changed > "dir/ümlaut"
+git commit -q -am "change path with bytes above 0x7f"
+
+git commit-graph write --no-progress --reachable --changed-paths
+
+for commit in $(git rev-list HEAD); do
+  echo "$commit"
+  git -c core.quotePath=false diff-tree -r --root --name-only --no-commit-id "$commit"
+  echo
+done > changes.git
@@ -2,3 +2,4 @@ mod comfort;
 mod mutate;
 mod raw;
 mod read_only;
+mod url_match;
@@ -0,0 +1,111 @@
+use std::borrow::Cow;
+
+use bstr::BStr;
+
+use crate::{file, file::MetadataFilter, url_match, value, AsKey, File};
+
+/// Access to values in sections whose subsection is a URL, like `http.<url>.*` or `credential.<url>.*`.
+///
+/// The subsection name of `key` is ignored, so `http.proxy` would match `http.proxy` as well as `http.https://example.com.proxy`.
+impl<'event> File<'event> {
+    /// Like [`url_matching_string_filter()`](File::url_matching_string_filter()), but without filtering sections.
+    pub fn url_matching_string(&self, key: impl AsKey, url: &url_match::Url) -> Option<Cow<'_, BStr>> {
+        self.url_matching_string_filter(key, url, &mut |_| true)
+    }
+
+    /// Return the value at `key` from the section whose subsection [matches](url_match::Url::match_pattern()) `url` the best,
+    /// or from a section without subsection, if the section passes `filter`.
+    ///
+    /// This is equivalent to `git config --get-urlmatch <key> <url>`, so more specific matches win, and among equally
+    /// specific matches the last one wins. Subsections which aren't valid URL patterns are ignored.
+    pub fn url_matching_string_filter(
+        &self,
+        key: impl AsKey,
+        url: &url_match::Url,
+        filter: &mut MetadataFilter,
+    ) -> Option<Cow<'_, BStr>> {
+        let key = key.try_as_key()?;
+        let mut best = None::<(url_match::Match, Cow<'_, BStr>)>;
+        for (section, quality) in self.url_matching_sections(key.section_name, url, filter)? {
+            if let Some(value) = section.value(key.value_name) {
+                if best.as_ref().map_or(true, |(best, _)| quality >= *best) {
+                    best = Some((quality, value));
+                }
+            }
+        }
+        best.map(|(_, value)| value)
+    }
+
+    /// Like [`url_matching_string_filter()`](File::url_matching_string_filter()), but interprets the value as boolean,
+    /// where keys without value are `true`.
+    pub fn url_matching_boolean_filter(
+        &self,
+        key: impl AsKey,
+        url: &url_match::Url,
+        filter: &mut MetadataFilter,
+    ) -> Option<Result<bool, value::Error>> {
+        let key = key.try_as_key()?;
+        let mut best = None::<(url_match::Match, Option<Cow<'_, BStr>>)>;
+        for (section, quality) in self.url_matching_sections(key.section_name, url, filter)? {
+            if let Some(value) = section.value_implicit(key.value_name) {
+                if best.as_ref().map_or(true, |(best, _)| quality >= *best) {
+                    best = Some((quality, value));
+                }
+            }
+        }
+        best.map(|(_, value)| match value {
+            Some(v) => crate::Boolean::try_from(v).map(Into::into),
+            None => Ok(true),
+        })
+    }
+
+    /// Like [`url_matching_strings_filter()`](File::url_matching_strings_filter()), but without filtering sections.
+    pub fn url_matching_strings(&self, key: impl AsKey, url: &url_match::Url) -> Option<Vec<Cow<'_, BStr>>> {
+        self.url_matching_strings_filter(key, url, &mut |_| true)
+    }
+
+    /// Return all values at `key` from all sections that match `url` and pass `filter`, in the order in which they appear,
+    /// or `None` if there was no such value.
+    ///
+    /// This is useful for multi-valued keys like `credential.helper`, where all matching values apply.
+    pub fn url_matching_strings_filter(
+        &self,
+        key: impl AsKey,
+        url: &url_match::Url,
+        filter: &mut MetadataFilter,
+    ) -> Option<Vec<Cow<'_, BStr>>> {
+        let key = key.try_as_key()?;
+        let values: Vec<_> = self
+            .url_matching_sections(key.section_name, url, filter)?
+            .into_iter()
+            .flat_map(|(section, _)| section.values(key.value_name))
+            .collect();
+        (!values.is_empty()).then_some(values)
+    }
+
+    /// Return all sections named `section_name` that pass `filter` and which either have no subsection or one that matches `url`,
+    /// along with the quality of the match, in order of appearance.
+    fn url_matching_sections<'a>(
+        &'a self,
+        section_name: &str,
+        url: &url_match::Url,
+        filter: &mut MetadataFilter,
+    ) -> Option<Vec<(&'a file::Section<'event>, url_match::Match)>> {
+        let section_ids = self.section_ids_by_name(section_name).ok()?;
+        Some(
+            section_ids
+                .filter_map(|id| {
+                    let section = self.sections.get(&id).expect("known section id");
+                    if !filter(section.meta()) {
+                        return None;
+                    }
+                    let quality = match section.header().subsection_name() {
+                        Some(pattern) => url.match_pattern(&url_match::Url::from_pattern(pattern).ok()?)?,
+                        None => url_match::Match::default(),
+                    };
+                    Some((section, quality))
+                })
+                .collect(),
+        )
+    }
+}
@@ -38,14 +38,78 @@ impl File<'static> {
 }
 
 pub(crate) fn resolve(config: &mut File<'static>, buf: &mut Vec<u8>, options: init::Options<'_>) -> Result<(), Error> {
-    resolve_includes_recursive(config, 0, buf, options)
+    resolve_includes_recursive(config, 0, buf, options, None)?;
+    resolve_hasconfig_includes(config, buf, options)
 }
 
+/// Follow `includeIf.hasconfig:remote.*.url:<glob>.path` directives, which can only be evaluated once all other includes
+/// were resolved as they depend on all remote URLs in the configuration. Just like `git`, we don't allow files included
+/// this way to define remote URLs themselves, as these could change the outcome of the evaluation.
+fn resolve_hasconfig_includes(
+    config: &mut File<'static>,
+    buf: &mut Vec<u8>,
+    options: init::Options<'_>,
+) -> Result<(), Error> {
+    if options.includes.max_depth == 0 {
+        return Ok(());
+    }
+    let mut section_ids_and_include_paths = Vec::new();
+    for (id, section) in config.section_order.iter().map(|id| (*id, &config.sections[id])) {
+        let header = &section.header;
+        if header.name.as_ref() == "includeIf"
+            && header
+                .subsection_name
+                .as_ref()
+                .map_or(false, |condition| condition.starts_with(b"hasconfig:"))
+        {
+            detach_include_paths(&mut section_ids_and_include_paths, section, id);
+        }
+    }
+    if section_ids_and_include_paths.is_empty() {
+        return Ok(());
+    }
+
+    let remote_urls = collect_remote_urls(config);
+    for (id, path) in section_ids_and_include_paths {
+        let section = &config.sections[&id];
+        let condition = section
+            .header
+            .subsection_name
+            .as_ref()
+            .expect("only conditional includes were collected");
+        let depth = section.meta.level;
+        if include_condition_match(
+            condition.as_ref(),
+            section.meta.path.as_deref(),
+            options.includes,
+            Some(&remote_urls),
+        )? {
+            append_followed_includes_recursively(vec![(id, path)], config, depth, options, buf, Some(&remote_urls))?;
+        }
+    }
+    Ok(())
+}
+
+/// Return all values of `remote.<name>.url`.
+fn collect_remote_urls(config: &File<'_>) -> Vec<BString> {
+    config
+        .sections_by_name("remote")
+        .into_iter()
+        .flatten()
+        .filter(|section| section.header.subsection_name.is_some())
+        .flat_map(|section| section.body.values("url"))
+        .map(Cow::into_owned)
+        .collect()
+}
+
+/// Resolve all includes in `target_config`, with `remote_urls` set if `hasconfig` conditions should be evaluated,
+/// which also means that the includes are the result of such a condition.
 fn resolve_includes_recursive(
     target_config: &mut File<'static>,
     depth: u8,
     buf: &mut Vec<u8>,
     options: init::Options<'_>,
+    remote_urls: Option<&[BString]>,
 ) -> Result<(), Error> {
     if depth == options.includes.max_depth {
         return if options.includes.err_on_max_depth_exceeded {
@@ -70,14 +134,21 @@ fn resolve_includes_recursive(
         } else if header_name == "includeIf" {
             if let Some(condition) = &header.subsection_name {
                 let target_config_path = section.meta.path.as_deref();
-                if include_condition_match(condition.as_ref(), target_config_path, options.includes)? {
+                if include_condition_match(condition.as_ref(), target_config_path, options.includes, remote_urls)? {
                     detach_include_paths(&mut section_ids_and_include_paths, section, id)
                 }
             }
         }
     }
 
-    append_followed_includes_recursively(section_ids_and_include_paths, target_config, depth, options, buf)
+    append_followed_includes_recursively(
+        section_ids_and_include_paths,
+        target_config,
+        depth,
+        options,
+        buf,
+        remote_urls,
+    )
 }
 
 fn append_followed_includes_recursively(
@@ -86,6 +157,7 @@ fn append_followed_includes_recursively(
     depth: u8,
     options: init::Options<'_>,
     buf: &mut Vec<u8>,
+    remote_urls: Option<&[BString]>,
 ) -> Result<(), Error> {
     for (section_id, config_path) in section_ids_and_include_paths {
         let meta = OwnShared::clone(&target_config.sections[&section_id].meta);
@@ -108,7 +180,7 @@ fn append_followed_includes_recursively(
         )
         .map_err(Error::CopyBuffer)?;
         let config_meta = Metadata {
-            path: Some(config_path),
+            path: Some(config_path.clone()),
             trust: meta.trust,
             level: meta.level + 1,
             source: meta.source,
@@ -124,7 +196,10 @@ fn append_followed_includes_recursively(
                 init::Error::Interpolate(err) => Error::Interpolate(err),
                 init::Error::Includes(_) => unreachable!("BUG: {:?} not possible due to no-follow options", err),
             })?;
-        resolve_includes_recursive(&mut include_config, depth + 1, buf, options)?;
+        resolve_includes_recursive(&mut include_config, depth + 1, buf, options, remote_urls)?;
+        if remote_urls.is_some() && !collect_remote_urls(&include_config).is_empty() {
+            return Err(Error::RemoteUrlInHasconfigInclude { path: config_path });
+        }
 
         target_config.append_or_insert(include_config, Some(section_id));
     }
@@ -149,6 +224,7 @@ fn include_condition_match(
     condition: &BStr,
     target_config_path: Option<&Path>,
     options: Options<'_>,
+    remote_urls: Option<&[BString]>,
 ) -> Result<bool, Error> {
     let mut tokens = condition.splitn(2, |b| *b == b':');
     let (prefix, condition) = match (tokens.next(), tokens.next()) {
@@ -170,10 +246,26 @@ fn include_condition_match(
             gix_glob::wildmatch::Mode::IGNORE_CASE,
         ),
         b"onbranch" => Ok(onbranch_matches(condition, options.conditional).is_some()),
+        b"hasconfig" => Ok(remote_urls.map_or(false, |urls| hasconfig_matches(condition, urls))),
         _ => Ok(false),
     }
 }
 
+/// Return `true` if `condition` is `remote.*.url:<glob>` and `<glob>` matches one of the `remote_urls`.
+/// Other keys are unsupported, just like in `git`.
+fn hasconfig_matches(condition: &BStr, remote_urls: &[BString]) -> bool {
+    let Some(glob) = condition.strip_prefix(b"remote.*.url:") else {
+        return false;
+    };
+    remote_urls.iter().any(|url| {
+        gix_glob::wildmatch(
+            glob.as_bstr(),
+            url.as_bstr(),
+            gix_glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+        )
+    })
+}
+
 fn onbranch_matches(
     condition: &BStr,
     conditional::Context { branch_name, .. }: conditional::Context<'_>,
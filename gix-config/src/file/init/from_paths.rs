@@ -1,7 +1,7 @@
 use std::collections::BTreeSet;
 
 use crate::{
-    file::{init, init::Options, Metadata},
+    file::{includes, init, init::Options, Metadata},
     File,
 };
 
@@ -119,7 +119,14 @@ impl File<'static> {
             };
             meta.path = Some(path);
 
-            let config = Self::from_bytes_owned(buf, meta, options)?;
+            let config = Self::from_bytes_owned(
+                buf,
+                meta,
+                Options {
+                    includes: includes::Options::no_follow(),
+                    ..options
+                },
+            )?;
             match &mut target {
                 None => {
                     target = Some(config);
@@ -129,6 +136,10 @@ impl File<'static> {
                 }
             }
         }
+        // Includes are resolved only once all files are loaded as `hasconfig` conditions depend on all of them.
+        if let Some(target) = &mut target {
+            includes::resolve(target, buf, options).map_err(init::Error::from)?;
+        }
         Ok(target)
     }
 }
@@ -55,3 +55,6 @@ pub use types::{File, Source};
 ///
 #[allow(clippy::empty_docs)]
 pub mod source;
+///
+#[allow(clippy::empty_docs)]
+pub mod url_match;
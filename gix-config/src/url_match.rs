@@ -0,0 +1,323 @@
+use std::ops::Range;
+
+use bstr::{BStr, BString, ByteSlice};
+
+/// The error returned by [`Url::from_bytes()`] and [`Url::from_pattern()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Invalid URL scheme name or missing '://' suffix in {url:?}")]
+    Scheme { url: BString },
+    #[error("Invalid %XX escape sequence in {url:?}")]
+    Escape { url: BString },
+    #[error("Missing host and scheme is not 'file:' in {url:?}")]
+    MissingHost { url: BString },
+    #[error("Invalid characters in host name of {url:?}")]
+    Host { url: BString },
+    #[error("Invalid port number in {url:?}")]
+    Port { url: BString },
+    #[error("Invalid '..' path segment in {url:?}")]
+    PathSegment { url: BString },
+}
+
+/// A URL normalized the way `git` does it before matching it against URLs in configuration subsections like `http.<url>.*`.
+///
+/// This means that the scheme and host are lower-cased, default ports are removed, unnecessary `%XX` escapes are decoded
+/// while all other escapes use upper-case hex digits, and `.` and `..` path segments are resolved.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Url {
+    url: BString,
+    scheme: Range<usize>,
+    user: Option<Range<usize>>,
+    host: Range<usize>,
+    port: Range<usize>,
+    path: Range<usize>,
+}
+
+/// Describes how well a pattern [matched](Url::match_pattern()) a URL, where greater values are better matches.
+///
+/// The order of fields is significant as they are compared one after another.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Match {
+    /// The length of the host in the pattern, including wildcards.
+    pub host_len: usize,
+    /// The length of the path prefix that matched, or `0` if the pattern had no URL at all.
+    pub path_len: usize,
+    /// `true` if the pattern contained a user name which matched.
+    pub user_matched: bool,
+}
+
+/// Initialization
+impl Url {
+    /// Normalize `url` for use in matching, failing if it isn't a URL with scheme and host.
+    pub fn from_bytes(url: &BStr) -> Result<Self, Error> {
+        normalize(url, false)
+    }
+
+    /// Normalize `pattern` as found in configuration subsections like `http.<url>.*`, which is like [`from_bytes()`](Self::from_bytes()),
+    /// but allows globs like `*` in each dot-separated component of the host.
+    pub fn from_pattern(pattern: &BStr) -> Result<Self, Error> {
+        normalize(pattern, true)
+    }
+}
+
+/// Access
+impl Url {
+    /// Return the entire normalized URL.
+    pub fn as_bstr(&self) -> &BStr {
+        self.url.as_bstr()
+    }
+
+    /// Return the lower-cased scheme, like `https`.
+    pub fn scheme(&self) -> &BStr {
+        self.url[self.scheme.clone()].as_bstr()
+    }
+
+    /// Return the user name, if present.
+    pub fn user(&self) -> Option<&BStr> {
+        self.user.clone().map(|range| self.url[range].as_bstr())
+    }
+
+    /// Return the lower-cased host, which is empty for `file://` URLs.
+    pub fn host(&self) -> &BStr {
+        self.url[self.host.clone()].as_bstr()
+    }
+
+    /// Return the port if it isn't the default port of the scheme.
+    pub fn port(&self) -> Option<&BStr> {
+        (!self.port.is_empty()).then(|| self.url[self.port.clone()].as_bstr())
+    }
+
+    /// Return the path, which always starts with `/`, along with query and fragment if present.
+    pub fn path(&self) -> &BStr {
+        self.url[self.path.clone()].as_bstr()
+    }
+}
+
+/// Matching
+impl Url {
+    /// Return how well the normalized `pattern` matches this URL, or `None` if it doesn't match at all.
+    ///
+    /// A pattern matches if the scheme is the same, the user is the same if the pattern has one, each component of the host
+    /// matches (with glob support), the port is the same, and the path of the pattern is a prefix of our path which ends at
+    /// a path component boundary.
+    pub fn match_pattern(&self, pattern: &Url) -> Option<Match> {
+        if self.scheme() != pattern.scheme() {
+            return None;
+        }
+        let user_matched = match pattern.user() {
+            Some(user) => {
+                if self.user() != Some(user) {
+                    return None;
+                }
+                true
+            }
+            None => false,
+        };
+        if !host_matches(pattern.host(), self.host()) || self.port() != pattern.port() {
+            return None;
+        }
+        let path_len = path_prefix_match_len(self.path(), pattern.path())?;
+        Some(Match {
+            host_len: pattern.host.len(),
+            path_len,
+            user_matched,
+        })
+    }
+}
+
+fn host_matches(pattern: &BStr, host: &BStr) -> bool {
+    let mut pattern_components = pattern.split_str(".");
+    let mut host_components = host.split_str(".");
+    loop {
+        match (pattern_components.next(), host_components.next()) {
+            (Some(pattern), Some(host)) => {
+                if !gix_glob::wildmatch(pattern.as_bstr(), host.as_bstr(), gix_glob::wildmatch::Mode::empty()) {
+                    return false;
+                }
+            }
+            (None, None) => return true,
+            (Some(_), None) | (None, Some(_)) => return false,
+        }
+    }
+}
+
+/// Return the length of `prefix` plus one if it matches `path` on a path component boundary.
+/// Both are considered to end with an implicit `/`.
+fn path_prefix_match_len(path: &BStr, prefix: &BStr) -> Option<usize> {
+    if prefix.is_empty() || prefix == "/" {
+        return (path.is_empty() || path[0] == b'/').then_some(1);
+    }
+    let prefix = prefix.strip_suffix(b"/").unwrap_or(prefix);
+    let rest = path.strip_prefix(prefix)?;
+    (rest.is_empty() || rest[0] == b'/').then_some(prefix.len() + 1)
+}
+
+const UNSAFE: &[u8] = b" <>\"%{}|\\^`";
+const RESERVED: &[u8] = b":/?#[]@!$&'()*+,;=";
+
+/// Append `input` to `out` while normalizing escapes, returning `false` if there was an invalid escape sequence.
+fn append_normalized_escapes(out: &mut BString, input: &[u8]) -> bool {
+    let mut bytes = input.iter().copied();
+    while let Some(mut b) = bytes.next() {
+        let mut was_escaped = false;
+        if b == b'%' {
+            let Some(decoded) = bytes
+                .next()
+                .zip(bytes.next())
+                .and_then(|(hi, lo)| Some(((hi as char).to_digit(16)? << 4 | (lo as char).to_digit(16)?) as u8))
+            else {
+                return false;
+            };
+            b = decoded;
+            was_escaped = true;
+        }
+        if b <= 0x1f || b >= 0x7f || UNSAFE.contains(&b) || (was_escaped && RESERVED.contains(&b)) {
+            out.extend_from_slice(format!("%{b:02X}").as_bytes());
+        } else {
+            out.push(b);
+        }
+    }
+    true
+}
+
+fn normalize(input: &BStr, allow_globs: bool) -> Result<Url, Error> {
+    let err_url = || input.to_owned();
+    let scheme_len = input
+        .iter()
+        .take_while(|b| b.is_ascii_alphanumeric() || b"+-.".contains(b))
+        .count();
+    if scheme_len == 0 || !input[0].is_ascii_alphabetic() || !input[scheme_len..].starts_with(b"://") {
+        return Err(Error::Scheme { url: err_url() });
+    }
+    let mut url = BString::from(input[..scheme_len].to_ascii_lowercase());
+    let scheme = 0..scheme_len;
+    url.extend_from_slice(b"://");
+    let mut rest: &[u8] = &input[scheme_len + 3..];
+
+    let authority_end = rest.find_byteset(b"/?#").unwrap_or(rest.len());
+    let mut user = None;
+    if let Some(at) = rest[..authority_end].rfind_byte(b'@') {
+        let user_info = &rest[..at];
+        let (name, password) = match user_info.find_byte(b':') {
+            Some(colon) => (&user_info[..colon], Some(&user_info[colon + 1..])),
+            None => (user_info, None),
+        };
+        let start = url.len();
+        if !append_normalized_escapes(&mut url, name) {
+            return Err(Error::Escape { url: err_url() });
+        }
+        user = Some(start..url.len());
+        if let Some(password) = password {
+            url.push(b':');
+            if !append_normalized_escapes(&mut url, password) {
+                return Err(Error::Escape { url: err_url() });
+            }
+        }
+        url.push(b'@');
+        rest = &rest[at + 1..];
+    }
+
+    let authority_end = rest.find_byteset(b"/?#").unwrap_or(rest.len());
+    let host_and_port = &rest[..authority_end];
+    let port_colon = host_and_port
+        .rfind_byteset(b":]")
+        .filter(|pos| host_and_port[*pos] == b':');
+    let (host, port) = match port_colon {
+        Some(colon) => (&host_and_port[..colon], &host_and_port[colon + 1..]),
+        None => (host_and_port, &[][..]),
+    };
+    if host.is_empty() {
+        if url[scheme.clone()] != *b"file" {
+            return Err(Error::MissingHost { url: err_url() });
+        }
+    } else if !host
+        .iter()
+        .all(|b| b.is_ascii_alphanumeric() || b".-_[]:".contains(b) || (allow_globs && *b == b'*'))
+    {
+        return Err(Error::Host { url: err_url() });
+    }
+    let host_start = url.len();
+    url.extend_from_slice(&host.to_ascii_lowercase());
+    let host = host_start..url.len();
+
+    let mut port_range = url.len()..url.len();
+    if !port.is_empty() {
+        if !port.iter().all(u8::is_ascii_digit) {
+            return Err(Error::Port { url: err_url() });
+        }
+        let port = port.trim_start_with(|c| c == '0');
+        let number = port
+            .to_str()
+            .ok()
+            .and_then(|port| port.parse::<u16>().ok())
+            .filter(|port| *port != 0)
+            .ok_or_else(|| Error::Port { url: err_url() })?;
+        let is_default = matches!((&url[scheme.clone()], number), (b"http", 80) | (b"https", 443));
+        if !is_default {
+            url.push(b':');
+            let start = url.len();
+            url.extend_from_slice(port);
+            port_range = start..url.len();
+        }
+    }
+    rest = &rest[authority_end..];
+
+    let path_start = url.len();
+    url.push(b'/');
+    rest = rest.strip_prefix(b"/").unwrap_or(rest);
+    while !rest.is_empty() && rest[0] != b'?' && rest[0] != b'#' {
+        let segment_end = rest.find_byteset(b"/?#").unwrap_or(rest.len());
+        let segment = &rest[..segment_end];
+        let is_dot_segment = match segment {
+            b"." => true,
+            b".." => {
+                // Remove the previous segment along with its trailing slash, but never go above the root.
+                if url.len() == path_start + 1 {
+                    return Err(Error::PathSegment { url: err_url() });
+                }
+                url.pop();
+                let previous_slash = url[path_start..].rfind_byte(b'/').expect("path starts with slash");
+                url.truncate(path_start + previous_slash + 1);
+                true
+            }
+            _ => {
+                if !append_normalized_escapes(&mut url, segment) {
+                    return Err(Error::Escape { url: err_url() });
+                }
+                false
+            }
+        };
+        rest = &rest[segment_end..];
+        if let Some(stripped) = rest.strip_prefix(b"/") {
+            if !is_dot_segment {
+                url.push(b'/');
+            }
+            rest = stripped;
+        }
+    }
+    for separator in [b'?', b'#'] {
+        if rest.first() == Some(&separator) {
+            let end = if separator == b'?' {
+                rest.find_byte(b'#').unwrap_or(rest.len())
+            } else {
+                rest.len()
+            };
+            url.push(separator);
+            if !append_normalized_escapes(&mut url, &rest[1..end]) {
+                return Err(Error::Escape { url: err_url() });
+            }
+            rest = &rest[end..];
+        }
+    }
+    let path = path_start..url.len();
+
+    Ok(Url {
+        url,
+        scheme,
+        user,
+        host,
+        port: port_range,
+        path,
+    })
+}
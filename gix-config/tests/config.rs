@@ -5,4 +5,5 @@ mod key;
 mod mem;
 mod parse;
 mod source;
+mod url_match;
 mod value;
@@ -1,3 +1,4 @@
 mod mutate;
 mod raw;
 mod read_only;
+mod url_match;
@@ -0,0 +1,130 @@
+use gix_config::{url_match::Url, File};
+
+use crate::file::cow_str;
+
+fn config() -> File<'static> {
+    File::try_from(
+        r#"
+[http]
+    proxy = generic
+    sslVerify = true
+[http "https://*.example.com"]
+    proxy = wildcard
+[http "https://internal.example.com/"]
+    proxy = host
+    extraHeader = a
+[http "https://internal.example.com/org"]
+    sslVerify = false
+    extraHeader = b
+[http "https://user@internal.example.com"]
+    proxy = user
+[http "https://other.com"]
+    proxy = other
+[http "not a url"]
+    proxy = invalid
+[http]
+    proxy = generic-late
+"#,
+    )
+    .expect("valid")
+}
+
+fn url(url: &str) -> Url {
+    Url::from_bytes(url.into()).expect("valid")
+}
+
+#[test]
+fn string_picks_the_most_specific_match() {
+    let config = config();
+    for (input, expected) in [
+        ("https://internal.example.com/org/repo", "host"),
+        ("https://user@internal.example.com/org/repo", "user"),
+        ("https://sub.example.com/repo", "wildcard"),
+        ("http://internal.example.com/org/repo", "generic-late"),
+        ("https://unknown.com", "generic-late"),
+    ] {
+        assert_eq!(
+            config
+                .url_matching_string("http.proxy", &url(input))
+                .expect("present")
+                .as_ref(),
+            expected,
+            "{input}"
+        );
+    }
+    assert_eq!(
+        config
+            .url_matching_string("http.sslVerify", &url("https://internal.example.com/org/repo"))
+            .expect("present")
+            .as_ref(),
+        "false",
+        "longer paths are more specific"
+    );
+    assert_eq!(
+        config
+            .url_matching_string("http.sslVerify", &url("https://internal.example.com/organization"))
+            .expect("present")
+            .as_ref(),
+        "true",
+        "paths match on component boundaries only"
+    );
+    assert_eq!(
+        config.url_matching_string("http.unknown", &url("https://internal.example.com")),
+        None
+    );
+}
+
+#[test]
+fn strings_returns_all_matching_values_in_order() {
+    let config = config();
+    assert_eq!(
+        config
+            .url_matching_strings("http.extraHeader", &url("https://internal.example.com/org/repo"))
+            .expect("present"),
+        &[cow_str("a"), cow_str("b")]
+    );
+    assert_eq!(
+        config
+            .url_matching_strings("http.extraHeader", &url("https://internal.example.com"))
+            .expect("present"),
+        &[cow_str("a")]
+    );
+    assert_eq!(
+        config.url_matching_strings("http.extraHeader", &url("https://other.com")),
+        None
+    );
+}
+
+#[test]
+fn filtered_sections_are_ignored() {
+    let config = config();
+    assert_eq!(
+        config.url_matching_string_filter("http.proxy", &url("https://internal.example.com"), &mut |_| false),
+        None
+    );
+}
+
+#[test]
+fn boolean_supports_implicit_values() -> crate::Result {
+    let config = File::try_from(
+        r#"
+[http]
+    sslVerify = false
+[http "https://example.com"]
+    sslVerify
+[http "https://example.com/invalid"]
+    sslVerify = invalid
+"#,
+    )?;
+    assert!(config
+        .url_matching_boolean_filter("http.sslVerify", &url("https://example.com/repo"), &mut |_| true)
+        .expect("present")?);
+    assert!(!config
+        .url_matching_boolean_filter("http.sslVerify", &url("https://other.com"), &mut |_| true)
+        .expect("present")?);
+    assert!(config
+        .url_matching_boolean_filter("http.sslVerify", &url("https://example.com/invalid"), &mut |_| true)
+        .expect("present")
+        .is_err());
+    Ok(())
+}
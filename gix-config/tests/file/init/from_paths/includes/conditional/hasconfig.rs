@@ -0,0 +1,171 @@
+use std::{fs, path::Path};
+
+use gix_config::{
+    file::{includes, init, init::from_paths, Metadata},
+    File,
+};
+use gix_testtools::tempfile::tempdir;
+
+use crate::file::{cow_str, init::from_paths::escape_backslashes};
+
+#[test]
+fn remote_urls_of_all_files_are_considered() -> crate::Result {
+    let dir = tempdir()?;
+    let (global, local) = write_configs(
+        dir.path(),
+        "https://example.com/**",
+        "[remote \"origin\"]\n  url = https://example.com/org/repo.git",
+    )?;
+
+    let config = from_paths([global, local])?;
+    assert_eq!(
+        config.strings("user.name"),
+        Some(vec![cow_str("global"), cow_str("work")]),
+        "the include of the global file is triggered by the remote defined in the local file"
+    );
+    Ok(())
+}
+
+#[test]
+fn non_matching_urls_do_not_include() -> crate::Result {
+    let dir = tempdir()?;
+    for (glob, url) in [
+        ("https://example.com/*", "https://example.com/org/repo.git"),
+        ("https://example.org/**", "https://example.com/org/repo.git"),
+        ("https://example.com/**", "ssh://example.com/org/repo.git"),
+    ] {
+        let (global, local) = write_configs(dir.path(), glob, &format!("[remote \"origin\"]\n  url = {url}"))?;
+        let config = from_paths([global, local])?;
+        assert_eq!(
+            config.strings("user.name"),
+            Some(vec![cow_str("global")]),
+            "{glob} doesn't match {url}, and `*` doesn't match slashes"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn remote_urls_of_included_files_are_considered() -> crate::Result {
+    let dir = tempdir()?;
+    let remote_path = dir.path().join("remote");
+    fs::write(
+        &remote_path,
+        "[remote \"origin\"]\n  url = https://example.com/org/repo.git",
+    )?;
+    let (global, local) = write_configs(
+        dir.path(),
+        "https://example.com/**",
+        &format!("[include]\n  path = {}", escape_backslashes(&remote_path)),
+    )?;
+    let config = from_paths([global, local])?;
+    assert_eq!(
+        config.strings("user.name"),
+        Some(vec![cow_str("global"), cow_str("work")])
+    );
+    Ok(())
+}
+
+#[test]
+fn delayed_resolution_works_the_same() -> crate::Result {
+    let dir = tempdir()?;
+    let (global, local) = write_configs(
+        dir.path(),
+        "https://example.com/**",
+        "[remote \"origin\"]\n  url = https://example.com/org/repo.git",
+    )?;
+    let mut config = File::default();
+    for path in [global, local] {
+        config.append(File::from_path_no_includes(path, gix_config::Source::Api)?);
+    }
+    config.resolve_includes(options())?;
+    assert_eq!(
+        config.strings("user.name"),
+        Some(vec![cow_str("global"), cow_str("work")])
+    );
+    Ok(())
+}
+
+#[test]
+fn unsupported_keys_never_match() -> crate::Result {
+    let dir = tempdir()?;
+    let include_path = dir.path().join("include");
+    fs::write(&include_path, "[user]\n  name = work")?;
+    let config_path = dir.path().join("config");
+    fs::write(
+        &config_path,
+        format!(
+            "[remote \"origin\"]\n  url = https://example.com\n[includeIf \"hasconfig:remote.*.pushurl:**\"]\n  path = {}",
+            escape_backslashes(&include_path)
+        ),
+    )?;
+    let config = from_paths([config_path])?;
+    assert_eq!(config.strings("user.name"), None);
+    Ok(())
+}
+
+#[test]
+fn included_files_must_not_define_remote_urls() -> crate::Result {
+    let dir = tempdir()?;
+    let (global, local) = write_configs(
+        dir.path(),
+        "https://example.com/**",
+        "[remote \"origin\"]\n  url = https://example.com/org/repo.git",
+    )?;
+    let include_path = dir.path().join("include");
+    fs::write(
+        &include_path,
+        "[user]\n  name = work\n[remote \"other\"]\n  url = https://example.com/other.git",
+    )?;
+
+    let err = from_paths([global, local]).unwrap_err();
+    assert!(
+        matches!(
+            err.downcast_ref::<from_paths::Error>(),
+            Some(from_paths::Error::Init(init::Error::Includes(
+                includes::Error::RemoteUrlInHasconfigInclude { path }
+            ))) if *path == include_path
+        ),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+/// Write a global configuration which includes a file setting `user.name` if a remote matches `glob`,
+/// and a local configuration with `local_content`.
+fn write_configs(
+    dir: &Path,
+    glob: &str,
+    local_content: &str,
+) -> crate::Result<(std::path::PathBuf, std::path::PathBuf)> {
+    let include_path = dir.join("include");
+    fs::write(&include_path, "[user]\n  name = work")?;
+
+    let global_path = dir.join("global");
+    fs::write(
+        &global_path,
+        format!(
+            "[user]\n  name = global\n[includeIf \"hasconfig:remote.*.url:{glob}\"]\n  path = {}",
+            escape_backslashes(&include_path)
+        ),
+    )?;
+
+    let local_path = dir.join("local");
+    fs::write(&local_path, local_content)?;
+    Ok((global_path, local_path))
+}
+
+fn from_paths(paths: impl IntoIterator<Item = std::path::PathBuf>) -> crate::Result<File<'static>> {
+    let metas = paths
+        .into_iter()
+        .map(|path| Metadata::try_from_path(path, gix_config::Source::Api))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(File::from_paths_metadata(metas, options())?.expect("non-empty"))
+}
+
+fn options() -> init::Options<'static> {
+    init::Options {
+        includes: includes::Options::follow(Default::default(), Default::default()),
+        ..Default::default()
+    }
+}
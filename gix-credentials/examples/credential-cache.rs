@@ -0,0 +1,7 @@
+/// Run like this `echo url=https://example.com | cargo run --example credential-cache -- get` after storing credentials with
+/// `printf 'url=https://example.com\nusername=user\npassword=pass\n' | cargo run --example credential-cache -- store`.
+///
+/// The daemon that keeps the credentials is this very program, started with the `daemon` action.
+pub fn main() -> Result<(), gix_credentials::program::main::Error> {
+    gix_credentials::cache::main(std::env::args_os().skip(1), std::io::stdin(), std::io::stdout())
+}
@@ -0,0 +1,176 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use bstr::ByteSlice;
+
+use crate::protocol::Context;
+
+/// The time to wait for credentials to be stored while the cache is empty before shutting down.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Listen on `socket` and serve credential requests until all credentials expired, or until asked to exit.
+/// Once it accepts connections, `ok` is printed to `stdout` to let [`spawn()`] know that the daemon is ready.
+///
+/// This is the equivalent of `git credential-cache--daemon` and expected to run in its own process,
+/// which keeps credentials in memory only.
+pub fn serve(socket: &Path) -> std::io::Result<()> {
+    let listener = bind(socket)?;
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout, "ok")?;
+    stdout.flush()?;
+    drop(stdout);
+    serve_listener(listener, socket.to_owned());
+    Ok(())
+}
+
+/// Start `program` as detached process with `--socket <socket> daemon` as arguments, and return once it accepts connections.
+///
+/// `program` is expected to pass its arguments to [`cache::main()`](super::main()), which [serves](serve()) on `socket`.
+/// The daemon keeps running after the current process ended, until all credentials expired or it is asked to exit.
+pub fn spawn(program: &Path, socket: &Path) -> std::io::Result<()> {
+    let mut cmd = Command::new(program);
+    cmd.arg("--socket")
+        .arg(socket)
+        .arg("daemon")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    {
+        use std::os::unix::process::CommandExt;
+        // Don't receive the signals meant for our process group, like the interrupt of a terminal.
+        cmd.process_group(0);
+    }
+    gix_trace::debug!(cmd = ?cmd, "spawning credential cache daemon");
+    let mut child = cmd.spawn()?;
+    // Like `git`, wait for the daemon to tell us that it is listening, and let it run on its own afterwards.
+    let mut line = String::new();
+    BufReader::new(child.stdout.take().expect("configured")).read_line(&mut line)?;
+    if line.trim_end() != "ok" {
+        child.wait().ok();
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            format!("credential cache daemon failed to start on {socket:?}"),
+        ));
+    }
+    Ok(())
+}
+
+fn bind(socket: &Path) -> std::io::Result<UnixListener> {
+    if let Some(dir) = socket.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        use std::os::unix::fs::DirBuilderExt;
+        std::fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+    }
+    // A socket left behind by a daemon that didn't shut down gracefully prevents binding.
+    std::fs::remove_file(socket).ok();
+    UnixListener::bind(socket)
+}
+
+struct Entry {
+    credential: Context,
+    expires_at: Instant,
+}
+
+fn serve_listener(listener: UnixListener, socket: PathBuf) {
+    let (tx, rx) = mpsc::channel();
+    let acceptor = std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            if tx.send(stream).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut entries = Vec::<Entry>::new();
+    let mut idle_since = None;
+    loop {
+        let now = Instant::now();
+        entries.retain(|entry| entry.expires_at > now);
+        let wait = match entries.iter().map(|entry| entry.expires_at).min() {
+            Some(next_expiry) => {
+                idle_since = None;
+                next_expiry - now
+            }
+            None => {
+                let idle_since = *idle_since.get_or_insert(now);
+                match (idle_since + IDLE_TIMEOUT).checked_duration_since(now) {
+                    Some(wait) if !wait.is_zero() => wait,
+                    _ => break,
+                }
+            }
+        };
+        match rx.recv_timeout(wait) {
+            Ok(Ok(stream)) => {
+                if let Ok(Continue::Exit) = handle(stream, &mut entries) {
+                    break;
+                }
+            }
+            Ok(Err(_)) | Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    drop(rx);
+    // Wake up the acceptor so it notices that we are done.
+    UnixStream::connect(&socket).ok();
+    std::fs::remove_file(&socket).ok();
+    acceptor.join().ok();
+}
+
+enum Continue {
+    Serving,
+    Exit,
+}
+
+fn handle(mut stream: UnixStream, entries: &mut Vec<Entry>) -> std::io::Result<Continue> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut request = Vec::new();
+    stream.read_to_end(&mut request)?;
+
+    let mut lines = request.splitn(3, |b| *b == b'\n');
+    let (Some(action), Some(timeout), rest) = (
+        lines.next().and_then(|line| line.strip_prefix(b"action=")),
+        lines
+            .next()
+            .and_then(|line| line.strip_prefix(b"timeout="))
+            .and_then(|timeout| timeout.to_str().ok()?.parse::<u64>().ok()),
+        lines.next().unwrap_or_default(),
+    ) else {
+        return Ok(Continue::Serving);
+    };
+    let Ok(ctx) = Context::from_bytes(rest) else {
+        return Ok(Continue::Serving);
+    };
+    match action {
+        b"get" => {
+            if let Some(entry) = entries.iter().find(|entry| ctx.matches(&entry.credential, false)) {
+                let credential = &entry.credential;
+                Context {
+                    username: credential.username.clone(),
+                    password: credential.password.clone(),
+                    password_expiry_utc: credential.password_expiry_utc,
+                    oauth_refresh_token: credential.oauth_refresh_token.clone(),
+                    ..Default::default()
+                }
+                .write_to(&mut stream)?;
+            }
+        }
+        b"store" if ctx.username.is_some() && ctx.password.is_some() => {
+            entries.retain(|entry| !ctx.matches(&entry.credential, false));
+            entries.push(Entry {
+                credential: ctx,
+                expires_at: Instant::now() + Duration::from_secs(timeout.min(u32::MAX.into())),
+            });
+        }
+        b"erase" => entries.retain(|entry| !ctx.matches(&entry.credential, true)),
+        b"exit" => return Ok(Continue::Exit),
+        _ => {}
+    }
+    stream.flush()?;
+    Ok(Continue::Serving)
+}
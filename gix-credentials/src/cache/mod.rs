@@ -0,0 +1,235 @@
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{program::main::Action, protocol, protocol::Context};
+
+/// The options for the [cache helper](invoke()).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Options {
+    /// The socket the daemon listens on, or `None` to use the [default location](default_socket_path()).
+    pub socket: Option<PathBuf>,
+    /// The time for which stored credentials are kept, 900 seconds by default.
+    pub timeout: Duration,
+    /// The program to [start as daemon](daemon::spawn()) if none is running yet, or `None` to use the current executable.
+    ///
+    /// It is invoked with `--socket <path> daemon`, which makes every program that passes its arguments to [`main()`]
+    /// usable as its own daemon.
+    pub daemon_program: Option<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            socket: None,
+            timeout: Duration::from_secs(900),
+            daemon_program: None,
+        }
+    }
+}
+
+/// The error returned by [`invoke()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not determine the location of the daemon socket as the home directory is unknown")]
+    SocketPathUnknown,
+    #[error("Could not communicate with the credential cache daemon at {path:?}")]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error(transparent)]
+    ContextDecode(#[from] protocol::context::decode::Error),
+    #[error("Could not determine the program to start as credential cache daemon")]
+    DaemonProgramUnknown(#[source] std::io::Error),
+    #[error("The credential cache requires Unix domain sockets which are not available on this platform")]
+    Unsupported,
+}
+
+/// Return the location of the daemon socket that `git credential-cache` uses by default, which is
+/// `~/.git-credential-cache/socket` if that directory exists, or `$XDG_CACHE_HOME/git/credential/socket` otherwise.
+pub fn default_socket_path() -> Option<PathBuf> {
+    let home = gix_path::env::home_dir();
+    if let Some(dir) = home
+        .as_ref()
+        .map(|home| home.join(".git-credential-cache"))
+        .filter(|dir| dir.is_dir())
+    {
+        return Some(dir.join("socket"));
+    }
+    gix_path::env::var("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home.map(|home| home.join(".cache")))
+        .map(|dir| dir.join("git").join("credential").join("socket"))
+}
+
+/// Perform `action` for the credential identified by `ctx` by talking to the credential cache daemon as configured by `options`,
+/// just like `git credential-cache`. The daemon is compatible with the one of `git`, so either can serve the other's client.
+///
+/// * `Get` returns the matching credential, or an empty context if there is none or if no daemon is running.
+/// * `Store` keeps the credential for the configured timeout, and [starts a detached daemon](daemon::spawn()) if none is running yet.
+/// * `Erase` removes matching credentials with the same password, if set.
+///
+/// Use [`main()`] to implement a credential helper program with it.
+pub fn invoke(action: Action, ctx: Context, options: &Options) -> Result<Option<Context>, Error> {
+    let socket = socket_path(options)?;
+    let mut request = format!("action={}\ntimeout={}\n", action.as_str(), options.timeout.as_secs()).into_bytes();
+    ctx.write_to(&mut request).map_err(|err| Error::Io {
+        path: socket.clone(),
+        source: err,
+    })?;
+    let daemon_program = match action {
+        Action::Store => Some(daemon_program(options)?),
+        Action::Get | Action::Erase => None,
+    };
+    let response = send(socket, &request, daemon_program.as_deref())?;
+    Ok(match action {
+        Action::Get => Some(Context::from_bytes(&response.unwrap_or_default())?),
+        Action::Store | Action::Erase => None,
+    })
+}
+
+/// Ask the daemon configured by `options` to exit, forgetting all credentials, just like `git credential-cache exit`.
+/// Nothing happens if no daemon is running.
+pub fn exit(options: &Options) -> Result<(), Error> {
+    send(socket_path(options)?, b"action=exit\ntimeout=0\n", None)?;
+    Ok(())
+}
+
+/// Implement `git credential-cache` by passing `args`, `stdin` and `stdout` to [`program::main()`](crate::program::main()),
+/// with `args` being `[--timeout <seconds>] [--socket <path>] <action>`. The action may also be `exit` to stop the daemon,
+/// or `daemon` to [serve](daemon::serve()) as daemon in the current process, which is how [`invoke()`] starts it.
+///
+/// Call it from a program's `main()` with `std::env::args_os().skip(1)`, `stdin()` and `stdout()`.
+pub fn main(
+    args: impl IntoIterator<Item = OsString>,
+    stdin: impl std::io::Read,
+    stdout: impl std::io::Write,
+) -> Result<(), crate::program::main::Error> {
+    use crate::program::main::Error as MainError;
+    let mut options = Options::default();
+    let mut args = args.into_iter().peekable();
+    while let Some(flag) = args.next_if(|arg| arg.to_str().map_or(false, |arg| arg.starts_with("--"))) {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| MainError::ArgumentInvalid { name: flag.clone() })
+        };
+        match flag.to_str() {
+            Some("--timeout") => {
+                let timeout = value()?;
+                options.timeout = Duration::from_secs(
+                    timeout
+                        .to_str()
+                        .and_then(|timeout| timeout.parse().ok())
+                        .ok_or(MainError::ArgumentInvalid { name: timeout })?,
+                );
+            }
+            Some("--socket") => options.socket = Some(value()?.into()),
+            _ => return Err(MainError::ArgumentInvalid { name: flag }),
+        }
+    }
+    match args.peek().and_then(|action| action.to_str()) {
+        Some("exit") => return exit(&options).map_err(|err| MainError::Helper { source: Box::new(err) }),
+        Some("daemon") => return serve(&options).map_err(|err| MainError::Helper { source: Box::new(err) }),
+        _ => {}
+    }
+    crate::program::main(args, stdin, stdout, |action, ctx| invoke(action, ctx, &options))
+}
+
+fn socket_path(options: &Options) -> Result<PathBuf, Error> {
+    options
+        .socket
+        .clone()
+        .or_else(default_socket_path)
+        .ok_or(Error::SocketPathUnknown)
+}
+
+fn daemon_program(options: &Options) -> Result<PathBuf, Error> {
+    options
+        .daemon_program
+        .clone()
+        .map_or_else(std::env::current_exe, Ok)
+        .map_err(Error::DaemonProgramUnknown)
+}
+
+#[cfg(unix)]
+fn serve(options: &Options) -> Result<(), Error> {
+    let socket = socket_path(options)?;
+    daemon::serve(&socket).map_err(|err| Error::Io {
+        path: socket,
+        source: err,
+    })
+}
+
+#[cfg(not(unix))]
+fn serve(_options: &Options) -> Result<(), Error> {
+    Err(Error::Unsupported)
+}
+
+/// Send `request` to the daemon listening on `socket` and return its response, or `None` if none is running.
+/// If `daemon_program` is set, it is started as daemon if there is none.
+fn send(socket: PathBuf, request: &[u8], daemon_program: Option<&Path>) -> Result<Option<Vec<u8>>, Error> {
+    client::send(&socket, request, daemon_program).map_err(|err| {
+        if err.kind() == std::io::ErrorKind::Unsupported {
+            Error::Unsupported
+        } else {
+            Error::Io {
+                path: socket,
+                source: err,
+            }
+        }
+    })
+}
+
+#[cfg(unix)]
+mod client {
+    use std::{
+        io::{Read, Write},
+        os::unix::net::UnixStream,
+        path::Path,
+    };
+
+    /// Send `request` to the daemon listening on `socket` and return its response, or `None` if no daemon is running.
+    /// If `daemon_program` is set, it will be started as daemon if there is none.
+    pub fn send(socket: &Path, request: &[u8], daemon_program: Option<&Path>) -> std::io::Result<Option<Vec<u8>>> {
+        let mut stream = match UnixStream::connect(socket) {
+            Ok(stream) => stream,
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    std::io::ErrorKind::NotFound | std::io::ErrorKind::ConnectionRefused
+                ) =>
+            {
+                let Some(daemon_program) = daemon_program else {
+                    return Ok(None);
+                };
+                super::daemon::spawn(daemon_program, socket)?;
+                UnixStream::connect(socket)?
+            }
+            Err(err) => return Err(err),
+        };
+        stream.write_all(request)?;
+        stream.shutdown(std::net::Shutdown::Write)?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response)?;
+        Ok(Some(response))
+    }
+}
+
+#[cfg(not(unix))]
+mod client {
+    use std::path::Path;
+
+    pub fn send(_socket: &Path, _request: &[u8], _daemon_program: Option<&Path>) -> std::io::Result<Option<Vec<u8>>> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "Unix domain sockets are not available on this platform",
+        ))
+    }
+}
+
+///
+#[cfg(unix)]
+#[allow(clippy::empty_docs)]
+pub mod daemon;
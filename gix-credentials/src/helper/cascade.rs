@@ -98,16 +98,36 @@ impl Cascade {
                             (ctx.host, &mut dst_ctx.host),
                             (ctx.username, &mut dst_ctx.username),
                             (ctx.password, &mut dst_ctx.password),
+                            (ctx.authtype, &mut dst_ctx.authtype),
+                            (ctx.credential, &mut dst_ctx.credential),
+                            (ctx.oauth_refresh_token, &mut dst_ctx.oauth_refresh_token),
                         ] {
                             if let Some(src) = src {
                                 *dst = Some(src);
                             }
                         }
+                        if let Some(src) = ctx.password_expiry_utc {
+                            dst_ctx.password_expiry_utc = Some(src);
+                        }
+                        if let Some(src) = ctx.ephemeral {
+                            dst_ctx.ephemeral = Some(src);
+                        }
+                        dst_ctx.state.extend(ctx.state);
+                        if dst_ctx
+                            .password_expiry_utc
+                            .map_or(false, |expiry| expiry < seconds_since_epoch())
+                        {
+                            // Like `git`, discard expired passwords so the next helper gets a chance to provide a new one.
+                            dst_ctx.password = None;
+                            dst_ctx.password_expiry_utc = None;
+                        }
                         if let Some(src) = ctx.url {
                             dst_ctx.url = Some(src);
                             url = dst_ctx.destructure_url_in_place(self.use_http_path)?.url.take();
                         }
-                        if dst_ctx.username.is_some() && dst_ctx.password.is_some() {
+                        if (dst_ctx.username.is_some() && dst_ctx.password.is_some())
+                            || (dst_ctx.authtype.is_some() && dst_ctx.credential.is_some())
+                        {
                             break;
                         }
                         if ctx.quit.unwrap_or_default() {
@@ -125,7 +145,8 @@ impl Cascade {
         if prompt.mode != gix_prompt::Mode::Disable {
             if let Some(ctx) = action.context_mut() {
                 ctx.url = url;
-                if ctx.username.is_none() {
+                let has_credential = ctx.authtype.is_some() && ctx.credential.is_some();
+                if ctx.username.is_none() && !has_credential {
                     let message = ctx.to_prompt("Username");
                     prompt.mode = gix_prompt::Mode::Visible;
                     ctx.username = gix_prompt::ask(&message, &prompt)
@@ -135,7 +156,7 @@ impl Cascade {
                         })?
                         .into();
                 }
-                if ctx.password.is_none() {
+                if ctx.password.is_none() && !has_credential {
                     let message = ctx.to_prompt("Password");
                     prompt.mode = gix_prompt::Mode::Hidden;
                     ctx.password = gix_prompt::ask(&message, &prompt)
@@ -152,6 +173,8 @@ impl Cascade {
             action.context().map(|ctx| helper::Outcome {
                 username: ctx.username.clone(),
                 password: ctx.password.clone(),
+                authtype: ctx.authtype.clone(),
+                credential: ctx.credential.clone(),
                 quit: ctx.quit.unwrap_or(false),
                 next: ctx.to_owned().into(),
             }),
@@ -159,3 +182,9 @@ impl Cascade {
         )
     }
 }
+
+fn seconds_since_epoch() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
@@ -30,6 +30,8 @@ pub fn invoke(helper: &mut crate::Program, action: &Action) -> Result {
             Ok(Some(Outcome {
                 username: ctx.username,
                 password: ctx.password,
+                authtype: ctx.authtype,
+                credential: ctx.credential,
                 quit: ctx.quit.unwrap_or(false),
                 next: NextAction {
                     previous_output: stdout.into(),
@@ -40,6 +42,9 @@ pub fn invoke(helper: &mut crate::Program, action: &Action) -> Result {
 }
 
 pub(crate) fn raw(helper: &mut crate::Program, action: &Action) -> std::result::Result<Option<Vec<u8>>, Error> {
+    if let Some(res) = native(&helper.kind, action) {
+        return res;
+    }
     let (mut stdin, stdout) = helper.start(action)?;
     if let (Action::Get(_), None) = (&action, &stdout) {
         panic!("BUG: `Helper` impls must return an output handle to read output from if Action::Get is provided")
@@ -66,3 +71,36 @@ pub(crate) fn raw(helper: &mut crate::Program, action: &Action) -> std::result::
         Some(stdout) => Ok(Some(stdout)),
     }
 }
+
+/// Run the helpers implemented in this crate in-process, or return `None` if `kind` is an external program.
+fn native(kind: &crate::program::Kind, action: &Action) -> Option<std::result::Result<Option<Vec<u8>>, Error>> {
+    use crate::program::{main, Kind};
+    fn failed(err: impl std::error::Error + Send + Sync + 'static) -> Error {
+        Error::CredentialsHelperFailed {
+            source: std::io::Error::new(std::io::ErrorKind::Other, err),
+        }
+    }
+    let input = || -> std::result::Result<_, Error> {
+        Ok(match action {
+            Action::Get(ctx) => (main::Action::Get, ctx.clone()),
+            Action::Store(previous) => (main::Action::Store, Context::from_bytes(previous)?),
+            Action::Erase(previous) => (main::Action::Erase, Context::from_bytes(previous)?),
+        })
+    };
+    let res = match kind {
+        Kind::Store(options) => {
+            input().and_then(|(action, ctx)| crate::store::invoke(action, ctx, options).map_err(failed))
+        }
+        Kind::Cache(options) => {
+            input().and_then(|(action, ctx)| crate::cache::invoke(action, ctx, options).map_err(failed))
+        }
+        Kind::Builtin | Kind::ExternalName { .. } | Kind::ExternalPath { .. } | Kind::ExternalShellScript(_) => {
+            return None
+        }
+    };
+    Some(res.map(|ctx| {
+        action
+            .expects_output()
+            .then(|| ctx.map(|ctx| ctx.to_bstring().into()).unwrap_or_default())
+    }))
+}
@@ -25,6 +25,10 @@ pub struct Outcome {
     pub username: Option<String>,
     /// The password to use in the identity, if set.
     pub password: Option<String>,
+    /// The authentication scheme of `credential`, like `Bearer`, if set.
+    pub authtype: Option<String>,
+    /// A pre-encoded credential to use instead of username and password, if set.
+    pub credential: Option<String>,
     /// If set, the helper asked to stop the entire process, whether the identity is complete or not.
     pub quit: bool,
     /// A handle to the action to perform next in another call to [`helper::invoke()`][crate::helper::invoke()].
@@ -44,6 +48,19 @@ impl Outcome {
             .zip(self.password.take())
             .map(|(username, password)| gix_sec::identity::Account { username, password })
     }
+
+    /// Try to fetch authtype _and_ credential to form a pre-encoded credential. This will fail if one of them is not set.
+    ///
+    /// This does nothing if only one of the fields is set, or consume both.
+    pub fn consume_credential(&mut self) -> Option<protocol::Credential> {
+        if self.authtype.is_none() || self.credential.is_none() {
+            return None;
+        }
+        self.authtype
+            .take()
+            .zip(self.credential.take())
+            .map(|(authtype, credential)| protocol::Credential { authtype, credential })
+    }
 }
 
 /// The Result type used in [`invoke()`][crate::helper::invoke()].
@@ -32,6 +32,12 @@ pub mod program;
 #[allow(clippy::empty_docs)]
 pub mod protocol;
 
+/// A pure-Rust implementation of `git credential-cache`, keeping credentials in memory of a daemon process for a while.
+pub mod cache;
+
+/// A pure-Rust implementation of `git credential-store`, keeping credentials in plain-text files.
+pub mod store;
+
 /// Call the `git credential` helper program performing the given `action`, which reads all context from the git configuration
 /// and does everything `git` typically does. The `action` should have been created with [`helper::Action::get_for_url()`] to
 /// contain only the URL to kick off the process, or should be created by [`helper::NextAction`].
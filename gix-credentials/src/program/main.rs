@@ -45,6 +45,8 @@ pub enum Error {
     ActionInvalid { name: OsString },
     #[error("The first argument must be the action to perform")]
     ActionMissing,
+    #[error("Argument {name:?} is unknown or its value is missing or invalid")]
+    ArgumentInvalid { name: OsString },
     #[error(transparent)]
     Helper {
         source: Box<dyn std::error::Error + Send + Sync + 'static>,
@@ -71,7 +73,8 @@ pub(crate) mod function {
     /// action to perform (as opposed to the program name).
     /// Then read context information from `stdin` and if the action is `Action::Get`, then write the result to `stdout`.
     /// `credentials` is the API version of such call, where`Ok(Some(context))` returns credentials, and `Ok(None)` indicates
-    /// no credentials could be found for `url`, which is always set when called.
+    /// no credentials could be found for `url`, which is always set when called, and assembled from `protocol`, `host` and `path`
+    /// if it wasn't passed.
     ///
     /// Call this function from a programs `main`, passing `std::env::args_os()`, `stdin()` and `stdout` accordingly, along with
     /// your own helper implementation.
@@ -88,17 +91,15 @@ pub(crate) mod function {
         let action: Action = args.into_iter().next().ok_or(Error::ActionMissing)?.try_into()?;
         let mut buf = Vec::<u8>::with_capacity(512);
         stdin.read_to_end(&mut buf)?;
-        let ctx = Context::from_bytes(&buf)?;
+        let mut ctx = Context::from_bytes(&buf)?;
         if ctx.url.is_none() {
-            return Err(Error::UrlMissing);
+            // `git` passes the url in parts.
+            ctx.url = ctx.to_url();
         }
+        let url = ctx.url.clone().ok_or(Error::UrlMissing)?;
         let res = credentials(action, ctx).map_err(|err| Error::Helper { source: Box::new(err) })?;
         match (action, res) {
-            (Action::Get, None) => {
-                return Err(Error::CredentialsMissing {
-                    url: Context::from_bytes(&buf)?.url.expect("present and checked above"),
-                })
-            }
+            (Action::Get, None) => return Err(Error::CredentialsMissing { url }),
             (Action::Get, Some(ctx)) => ctx.write_to(stdout)?,
             (Action::Erase | Action::Store, None) => {}
             (Action::Erase | Action::Store, Some(_)) => {
@@ -24,6 +24,12 @@ pub enum Kind {
     },
     /// A script to execute with `sh`.
     ExternalShellScript(BString),
+    /// The [pure-Rust implementation](crate::store) of `git credential-store`, which runs in-process
+    /// and is used if the helper is configured as `store [--file <path>]`.
+    Store(crate::store::Options),
+    /// The [pure-Rust implementation](crate::cache) of `git credential-cache`, which runs in-process
+    /// and is used if the helper is configured as `cache [--timeout <seconds>] [--socket <path>]`.
+    Cache(crate::cache::Options),
 }
 
 /// Initialization
@@ -54,7 +60,7 @@ impl Program {
                 if gix_path::is_absolute(path) {
                     Kind::ExternalPath { path_and_args: input }
                 } else {
-                    Kind::ExternalName { name_and_args: input }
+                    native_kind(&input).unwrap_or(Kind::ExternalName { name_and_args: input })
                 }
             };
             Program {
@@ -67,9 +73,32 @@ impl Program {
     }
 
     /// Convert the program into the respective command, suitable to invoke `action`.
+    ///
+    /// Note that [`Store`](Kind::Store) and [`Cache`](Kind::Cache) helpers run in-process, and the returned command
+    /// is merely the `git` equivalent.
     pub fn to_command(&self, action: &helper::Action) -> std::process::Command {
         let git_program = gix_path::env::exe_invocation();
         let mut cmd = match &self.kind {
+            Kind::Store(crate::store::Options { file }) => {
+                let mut cmd = Command::new(git_program);
+                cmd.arg("credential-store");
+                if let Some(file) = file {
+                    cmd.arg("--file").arg(file);
+                }
+                cmd.arg(action.as_arg(true));
+                cmd
+            }
+            Kind::Cache(crate::cache::Options { socket, timeout, .. }) => {
+                let mut cmd = Command::new(git_program);
+                cmd.arg("credential-cache")
+                    .arg("--timeout")
+                    .arg(timeout.as_secs().to_string());
+                if let Some(socket) = socket {
+                    cmd.arg("--socket").arg(socket);
+                }
+                cmd.arg(action.as_arg(true));
+                cmd
+            }
             Kind::Builtin => {
                 let mut cmd = Command::new(git_program);
                 cmd.arg("credential").arg(action.as_arg(false));
@@ -104,6 +133,53 @@ impl Program {
     }
 }
 
+/// Parse `name_and_args` like `store --file ~/.creds` into the kind of the respective native helper, or return `None`
+/// if it isn't one or if its arguments aren't understood.
+fn native_kind(name_and_args: &BString) -> Option<Kind> {
+    let name_and_args = name_and_args.to_str().ok()?;
+    if name_and_args.contains(['"', '\'', '\\', '$', '`']) {
+        return None;
+    }
+    let mut tokens = name_and_args.split_whitespace();
+    let name = tokens.next()?;
+    let mut args = Vec::new();
+    for token in tokens {
+        match token.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => args.extend([flag, value]),
+            _ => args.push(token),
+        }
+    }
+    let mut args = args.into_iter();
+    let expand_home = |path: &str| match path.strip_prefix("~/") {
+        Some(relative) => gix_path::env::home_dir().map(|home| home.join(relative)),
+        None => Some(path.into()),
+    };
+    match name {
+        "store" => {
+            let mut options = crate::store::Options::default();
+            while let Some(flag) = args.next() {
+                match flag {
+                    "--file" => options.file = Some(expand_home(args.next()?)?),
+                    _ => return None,
+                }
+            }
+            Some(Kind::Store(options))
+        }
+        "cache" => {
+            let mut options = crate::cache::Options::default();
+            while let Some(flag) = args.next() {
+                match flag {
+                    "--timeout" => options.timeout = std::time::Duration::from_secs(args.next()?.parse().ok()?),
+                    "--socket" => options.socket = Some(expand_home(args.next()?)?),
+                    _ => return None,
+                }
+            }
+            Some(Kind::Cache(options))
+        }
+        _ => None,
+    }
+}
+
 /// Builder
 impl Program {
     /// By default `stderr` of programs is inherited and typically displayed in the terminal.
@@ -34,6 +34,19 @@ mod access {
             }
             buf.into()
         }
+        /// Return `true` if the fields identifying a credential that are set in `self`, namely protocol, host, path and username,
+        /// are equal to the ones in `other`, similar to how credential helpers look up their stored credentials.
+        /// If `match_password` is `true`, the password is compared in the same way.
+        pub fn matches(&self, other: &Context, match_password: bool) -> bool {
+            fn field_matches<T: PartialEq>(want: &Option<T>, have: &Option<T>) -> bool {
+                want.is_none() || want == have
+            }
+            field_matches(&self.protocol, &other.protocol)
+                && field_matches(&self.host, &other.host)
+                && field_matches(&self.path, &other.path)
+                && field_matches(&self.username, &other.username)
+                && (!match_password || field_matches(&self.password, &other.password))
+        }
         /// Compute a prompt to obtain the given value.
         pub fn to_prompt(&self, field: &str) -> String {
             match self.to_url() {
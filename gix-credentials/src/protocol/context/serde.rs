@@ -17,6 +17,14 @@ mod write {
                 out.write_all(value)?;
                 out.write_all(b"\n")
             }
+            fn write_str_key(out: &mut impl std::io::Write, key: &str, value: &str) -> std::io::Result<()> {
+                validate(key, value.into()).map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+                write_key(out, key, value.as_bytes().as_bstr()).ok();
+                Ok(())
+            }
+            for value in &self.capability {
+                write_str_key(&mut out, "capability[]", value)?;
+            }
             for (key, value) in [("url", &self.url), ("path", &self.path)] {
                 if let Some(value) = value {
                     validate(key, value.as_slice().into())
@@ -31,9 +39,27 @@ mod write {
                 ("password", &self.password),
             ] {
                 if let Some(value) = value {
-                    validate(key, value.as_str().into())
-                        .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
-                    write_key(&mut out, key, value.as_bytes().as_bstr()).ok();
+                    write_str_key(&mut out, key, value)?;
+                }
+            }
+            if let Some(expiry) = self.password_expiry_utc {
+                write_str_key(&mut out, "password_expiry_utc", &expiry.to_string())?;
+            }
+            for (key, value) in [
+                ("oauth_refresh_token", &self.oauth_refresh_token),
+                ("authtype", &self.authtype),
+                ("credential", &self.credential),
+            ] {
+                if let Some(value) = value {
+                    write_str_key(&mut out, key, value)?;
+                }
+            }
+            if let Some(ephemeral) = self.ephemeral {
+                write_str_key(&mut out, "ephemeral", if ephemeral { "1" } else { "0" })?;
+            }
+            for (key, values) in [("wwwauth[]", &self.wwwauth), ("state[]", &self.state)] {
+                for value in values {
+                    write_str_key(&mut out, key, value)?;
                 }
             }
             Ok(())
@@ -85,7 +111,13 @@ pub mod decode {
             }) {
                 let (key, value) = res?;
                 match key {
-                    "protocol" | "host" | "username" | "password" => {
+                    "protocol"
+                    | "host"
+                    | "username"
+                    | "password"
+                    | "authtype"
+                    | "credential"
+                    | "oauth_refresh_token" => {
                         if !value.is_utf8() {
                             return Err(Error::IllformedUtf8InValue { key: key.into(), value });
                         }
@@ -95,9 +127,35 @@ pub mod decode {
                             "host" => &mut ctx.host,
                             "username" => &mut ctx.username,
                             "password" => &mut ctx.password,
+                            "authtype" => &mut ctx.authtype,
+                            "credential" => &mut ctx.credential,
+                            "oauth_refresh_token" => &mut ctx.oauth_refresh_token,
                             _ => unreachable!("checked field names in match above"),
                         } = Some(value);
                     }
+                    "wwwauth[]" | "capability[]" | "state[]" => {
+                        if !value.is_utf8() {
+                            return Err(Error::IllformedUtf8InValue { key: key.into(), value });
+                        }
+                        let values = match key {
+                            "wwwauth[]" => &mut ctx.wwwauth,
+                            "capability[]" => &mut ctx.capability,
+                            "state[]" => &mut ctx.state,
+                            _ => unreachable!("checked field names in match above"),
+                        };
+                        // Like in `git`, an empty value resets the list.
+                        if value.is_empty() {
+                            values.clear();
+                        } else {
+                            values.push(value.to_string());
+                        }
+                    }
+                    "password_expiry_utc" => {
+                        ctx.password_expiry_utc = value.to_str().ok().and_then(|value| value.parse().ok());
+                    }
+                    "ephemeral" => {
+                        ctx.ephemeral = gix_config_value::Boolean::try_from(value.as_ref()).ok().map(Into::into);
+                    }
                     "url" => ctx.url = Some(value),
                     "path" => ctx.path = Some(value),
                     "quit" => {
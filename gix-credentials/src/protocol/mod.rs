@@ -6,11 +6,26 @@ use crate::helper;
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Outcome {
     /// The identity provide by the helper.
+    ///
+    /// If [`credential`](Self::credential) is set, the password is empty and the username may be empty as well.
     pub identity: gix_sec::identity::Account,
+    /// A pre-encoded credential along with its authentication scheme, provided by helpers that support the `authtype` capability.
+    ///
+    /// If set, it is to be used instead of the `identity`.
+    pub credential: Option<Credential>,
     /// A handle to the action to perform next in another call to [`helper::invoke()`][crate::helper::invoke()].
     pub next: helper::NextAction,
 }
 
+/// A pre-encoded credential for use in an `Authorization` header, like `Bearer <token>`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Credential {
+    /// The authentication scheme, like `Bearer`.
+    pub authtype: String,
+    /// The credential to send verbatim along with `authtype`.
+    pub credential: String,
+}
+
 /// The Result type used in credentials top-level functions to obtain a complete identity.
 pub type Result = std::result::Result<Option<Outcome>, Error>;
 
@@ -54,29 +69,60 @@ pub struct Context {
     pub url: Option<BString>,
     /// If true, the caller should stop asking for credentials immediately without calling more credential helpers in the chain.
     pub quit: Option<bool>,
+    /// The values of all `WWW-Authenticate` headers of the server response that demanded authentication, in order.
+    pub wwwauth: Vec<String>,
+    /// The capabilities supported by the sender, like `authtype` and `state`.
+    ///
+    /// Fields related to a capability are only to be sent by a helper if the caller announced that capability.
+    pub capability: Vec<String>,
+    /// The authentication scheme for `credential`, like `Bearer`, requiring the `authtype` capability.
+    pub authtype: Option<String>,
+    /// A pre-encoded credential to be used with `authtype` instead of `username` and `password`.
+    pub credential: Option<String>,
+    /// If true, the `credential` is valid only for a single request and should not be stored.
+    pub ephemeral: Option<bool>,
+    /// Opaque state set by helpers which is passed back to them in subsequent invocations, requiring the `state` capability.
+    pub state: Vec<String>,
+    /// The time in seconds since the Unix epoch after which the `password` must not be used anymore.
+    pub password_expiry_utc: Option<u64>,
+    /// An OAuth refresh token which may accompany a `password` that is an OAuth access token.
+    pub oauth_refresh_token: Option<String>,
 }
 
 /// Convert the outcome of a helper invocation to a helper result, assuring that the identity is complete in the process.
 #[allow(clippy::result_large_err)]
 pub fn helper_outcome_to_result(outcome: Option<helper::Outcome>, action: helper::Action) -> Result {
     fn redact(mut ctx: Context) -> Context {
-        if let Some(pw) = ctx.password.as_mut() {
-            *pw = "<redacted>".into()
+        for secret in [&mut ctx.password, &mut ctx.credential, &mut ctx.oauth_refresh_token] {
+            if let Some(secret) = secret.as_mut() {
+                *secret = "<redacted>".into()
+            }
         }
         ctx
     }
     match (action, outcome) {
         (helper::Action::Get(ctx), None) => Err(Error::IdentityMissing { context: redact(ctx) }),
-        (helper::Action::Get(ctx), Some(mut outcome)) => match outcome.consume_identity() {
-            Some(identity) => Ok(Some(Outcome {
-                identity,
+        (helper::Action::Get(ctx), Some(mut outcome)) => match outcome.consume_credential() {
+            Some(credential) => Ok(Some(Outcome {
+                identity: gix_sec::identity::Account {
+                    username: outcome.username.unwrap_or_default(),
+                    password: String::new(),
+                },
+                credential: Some(credential),
                 next: outcome.next,
             })),
-            None => Err(if outcome.quit {
-                Error::Quit
-            } else {
-                Error::IdentityMissing { context: redact(ctx) }
-            }),
+            None => match outcome.consume_identity() {
+                Some(identity) => Ok(Some(Outcome {
+                    identity,
+                    credential: None,
+                    next: outcome.next,
+                })),
+                None => Err(if outcome.quit {
+                    Error::Quit
+                } else {
+                    Error::IdentityMissing { context: redact(ctx) }
+                }),
+            },
         },
         (helper::Action::Store(_) | helper::Action::Erase(_), _ignore) => Ok(None),
     }
@@ -0,0 +1,160 @@
+use std::{path::PathBuf, time::Duration};
+
+use gix_credentials::{cache, program::main, protocol::Context};
+use once_cell::sync::Lazy;
+
+/// The `credential-cache` example, which implements the helper with [`cache::main()`] and serves as its own daemon.
+static DAEMON_PROGRAM: Lazy<PathBuf> = Lazy::new(|| {
+    let status = std::process::Command::new(env!("CARGO"))
+        .args(["build", "-p", "gix-credentials", "--example", "credential-cache"])
+        .status()
+        .expect("cargo can be invoked");
+    assert!(status.success(), "example builds OK");
+    std::env::current_exe()
+        .expect("test executable is known")
+        .ancestors()
+        .nth(3)
+        .expect("test executable is in <target-dir>/<profile>/deps")
+        .join("debug")
+        .join("examples")
+        .join(format!("credential-cache{}", std::env::consts::EXE_SUFFIX))
+});
+
+fn options(socket: PathBuf) -> cache::Options {
+    cache::Options {
+        socket: Some(socket),
+        daemon_program: Some(DAEMON_PROGRAM.clone()),
+        ..Default::default()
+    }
+}
+
+fn ctx(host: &str, username: Option<&str>, password: Option<&str>) -> Context {
+    Context {
+        protocol: Some("https".into()),
+        host: Some(host.into()),
+        username: username.map(Into::into),
+        password: password.map(Into::into),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn without_daemon_nothing_is_found() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let options = options(dir.path().join("socket"));
+    assert_eq!(
+        cache::invoke(main::Action::Get, ctx("example.com", None, None), &options)?,
+        Some(Context::default())
+    );
+    cache::invoke(main::Action::Erase, ctx("example.com", None, None), &options)?;
+    assert!(!dir.path().join("socket").exists(), "no daemon is started for erasing");
+    Ok(())
+}
+
+#[test]
+fn storing_starts_a_daemon_which_serves_until_credentials_expire() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let socket = dir.path().join("socket");
+    let options = cache::Options {
+        timeout: Duration::from_secs(1),
+        ..options(socket)
+    };
+    cache::invoke(
+        main::Action::Store,
+        ctx("example.com", Some("user"), Some("pass")),
+        &options,
+    )?;
+    assert_eq!(
+        cache::invoke(main::Action::Get, ctx("example.com", None, None), &options)?,
+        Some(Context {
+            username: Some("user".into()),
+            password: Some("pass".into()),
+            ..Default::default()
+        })
+    );
+    assert_eq!(
+        cache::invoke(main::Action::Get, ctx("other.com", None, None), &options)?,
+        Some(Context::default()),
+        "the host has to match"
+    );
+
+    std::thread::sleep(Duration::from_millis(1100));
+    assert_eq!(
+        cache::invoke(main::Action::Get, ctx("example.com", None, None), &options)?,
+        Some(Context::default()),
+        "the credential expired"
+    );
+    Ok(())
+}
+
+#[test]
+fn erase_removes_credentials_with_matching_password() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let options = options(dir.path().join("socket"));
+    cache::invoke(
+        main::Action::Store,
+        ctx("example.com", Some("user"), Some("pass")),
+        &options,
+    )?;
+    cache::invoke(
+        main::Action::Erase,
+        ctx("example.com", Some("user"), Some("other")),
+        &options,
+    )?;
+    assert!(
+        cache::invoke(main::Action::Get, ctx("example.com", None, None), &options)?
+            .expect("always set")
+            .password
+            .is_some()
+    );
+    cache::invoke(
+        main::Action::Erase,
+        ctx("example.com", Some("user"), Some("pass")),
+        &options,
+    )?;
+    assert_eq!(
+        cache::invoke(main::Action::Get, ctx("example.com", None, None), &options)?,
+        Some(Context::default())
+    );
+    cache::exit(&options)?;
+    Ok(())
+}
+
+#[test]
+fn the_daemon_is_a_separate_process_which_main_can_stop() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let socket = dir.path().join("socket");
+    let args = |action: &str| ["--socket".into(), socket.clone().into_os_string(), action.into()];
+    let status = std::process::Command::new(&*DAEMON_PROGRAM)
+        .args(args("store"))
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            use std::io::Write;
+            child
+                .stdin
+                .take()
+                .expect("configured")
+                .write_all(b"protocol=https\nhost=example.com\nusername=user\npassword=pass\n")?;
+            child.wait()
+        })?;
+    assert!(status.success());
+    assert!(socket.exists(), "the daemon outlives the helper that started it");
+
+    let mut out = Vec::new();
+    cache::main(args("get"), &b"protocol=https\nhost=example.com\n"[..], &mut out)?;
+    assert_eq!(out.as_slice(), b"username=user\npassword=pass\n".as_slice());
+
+    cache::main(args("exit"), &b""[..], std::io::sink())?;
+    for _ in 0..100 {
+        if !socket.exists() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert!(!socket.exists(), "the daemon removes its socket when exiting");
+    let mut out = Vec::new();
+    cache::main(args("get"), &b"protocol=https\nhost=example.com\n"[..], &mut out)?;
+    assert_eq!(out, b"", "credentials are gone with the daemon");
+    Ok(())
+}
@@ -1,5 +1,8 @@
 pub use gix_testtools::Result;
 
+#[cfg(unix)]
+mod cache;
 mod helper;
 mod program;
 mod protocol;
+mod store;
@@ -0,0 +1,5 @@
+#!/usr/bin/env bash
+
+echo username=user
+echo password=expired
+echo password_expiry_utc=1
@@ -136,6 +136,30 @@ mod invoke {
         assert_eq!(actual.identity, identity("user", ""));
     }
 
+    #[test]
+    fn credentials_with_authtype_are_complete_without_password() {
+        let actual = invoke_cascade(["authtype", "custom-helper"], action_get())
+            .unwrap()
+            .expect("credentials");
+        assert_eq!(
+            actual.credential,
+            Some(protocol::Credential {
+                authtype: "Bearer".into(),
+                credential: "token".into()
+            })
+        );
+        assert_eq!(actual.identity, identity("", ""));
+    }
+
+    #[test]
+    fn expired_passwords_are_discarded() {
+        let actual = invoke_cascade(["expired-password", "password"], action_get())
+            .unwrap()
+            .expect("credentials");
+        assert_eq!(actual.identity, identity("user", "pass"));
+        assert_eq!(actual.credential, None);
+    }
+
     fn action_get() -> Action {
         Action::get_for_url("does/not/matter")
     }
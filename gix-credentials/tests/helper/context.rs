@@ -12,6 +12,17 @@ fn encode_decode_roundtrip_works_only_for_serializing_fields() {
             url: Some("https://github.com/byron/gitoxide".into()),
             ..Default::default()
         },
+        Context {
+            capability: vec!["authtype".into(), "state".into()],
+            wwwauth: vec![r#"Bearer realm="example""#.into(), "Basic".into()],
+            authtype: Some("Bearer".into()),
+            credential: Some("token".into()),
+            ephemeral: Some(true),
+            state: vec!["helper:key=value".into()],
+            password_expiry_utc: Some(1700000000),
+            oauth_refresh_token: Some("refresh".into()),
+            ..Default::default()
+        },
         Context::default(),
     ] {
         let mut buf = Vec::<u8>::new();
@@ -112,6 +123,33 @@ username=bob";
         }
     }
 
+    #[test]
+    fn multi_valued_keys_accumulate_and_are_reset_by_empty_values() {
+        let input = b"wwwauth[]=Basic
+wwwauth[]=Bearer
+capability[]=authtype
+capability[]=
+capability[]=state
+state[]=one";
+        assert_eq!(
+            Context::from_bytes(input).unwrap(),
+            Context {
+                wwwauth: vec!["Basic".into(), "Bearer".into()],
+                capability: vec!["state".into()],
+                state: vec!["one".into()],
+                ..Default::default()
+            }
+        )
+    }
+
+    #[test]
+    fn invalid_password_expiry_is_ignored() {
+        assert_eq!(
+            Context::from_bytes(b"password_expiry_utc=soon").unwrap(),
+            Context::default()
+        );
+    }
+
     #[test]
     fn null_bytes_when_decoding() {
         let err = Context::from_bytes(b"url=https://foo\0").unwrap_err();
@@ -12,6 +12,8 @@ mod invoke_outcome_to_helper_result {
             Some(helper::Outcome {
                 username: None,
                 password: None,
+                authtype: None,
+                credential: None,
                 quit: false,
                 next: protocol::Context::default().into(),
             }),
@@ -28,6 +30,8 @@ mod invoke_outcome_to_helper_result {
             Some(helper::Outcome {
                 username: None,
                 password: None,
+                authtype: None,
+                credential: None,
                 quit: true,
                 next: protocol::Context::default().into(),
             }),
@@ -110,3 +110,30 @@ fn path_with_simple_args() {
         "a shell is used as there are arguments, and it's generally more flexible, but on windows we split ourselves"
     );
 }
+
+#[test]
+fn store_and_cache_are_implemented_natively() {
+    let prog = Program::from_custom_definition("store --file=/path/to/credentials");
+    assert!(
+        matches!(&prog.kind, Kind::Store(options) if options.file.as_deref() == Some(std::path::Path::new("/path/to/credentials")))
+    );
+    let git = *GIT;
+    assert_eq!(
+        format!("{:?}", prog.to_command(&helper::Action::Store("egal".into()))),
+        format!(r#""{git}" "credential-store" "--file" "/path/to/credentials" "store""#),
+        "the command is what git would run instead"
+    );
+
+    let prog = Program::from_custom_definition("cache --timeout 5");
+    assert!(
+        matches!(&prog.kind, Kind::Cache(options) if options.timeout == std::time::Duration::from_secs(5) && options.socket.is_none())
+    );
+
+    for input in ["store --unknown", "cache --timeout=$TIMEOUT", "store --file=\"a b\""] {
+        let prog = Program::from_custom_definition(input);
+        assert!(
+            matches!(&prog.kind, Kind::ExternalName { name_and_args } if name_and_args == input),
+            "anything that isn't understood is left to git"
+        );
+    }
+}
@@ -85,50 +85,147 @@ pub(crate) mod function {
     }
 }
 
+/// Parse relative dates similar to `git`'s approxidate, like `2 weeks ago`, `2.weeks.ago`, `yesterday`, `last monday`
+/// or `3 months ago noon`.
+///
+/// All words are processed in order and move the current time further into the past, which is why `ago` is optional.
+/// Calendar-based computations like `midnight` or weekdays are performed in the local timezone, if it can be determined.
 mod relative {
     use std::{str::FromStr, time::SystemTime};
 
-    use time::{Duration, OffsetDateTime};
+    use time::{Date, Duration, Month, OffsetDateTime, UtcOffset, Weekday};
 
     use crate::parse::Error;
 
-    fn parse_inner(input: &str) -> Option<Duration> {
-        let mut split = input.split_whitespace();
-        let multiplier = i64::from_str(split.next()?).ok()?;
-        let period = split.next()?;
-        if split.next()? != "ago" {
-            return None;
+    /// A single step towards the past, to be applied to the current time in order.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Adjustment {
+        /// Go back by the given duration.
+        Duration(Duration),
+        /// Go back the given amount of calendar months, clamping the day to the last day of the resulting month.
+        Months(i64),
+        /// Go back to the most recent `day` before today, and then the given amount of `weeks` further.
+        Weekday { day: Weekday, weeks: i64 },
+        /// Set the time of day to the given hour, going back a day if that time didn't pass yet, like `noon`.
+        Hour(u8),
+    }
+
+    fn parse_inner(input: &str) -> Option<Vec<Adjustment>> {
+        let mut out = Vec::new();
+        let mut number = None::<i64>;
+        let mut recognized = false;
+        let mut prev_was_unit = false;
+        for token in input
+            .split(|c: char| c.is_whitespace() || c == '.' || c == ',')
+            .filter(|token| !token.is_empty())
+        {
+            let token = token.to_ascii_lowercase();
+            let is_unit = std::mem::take(&mut prev_was_unit);
+            if token.bytes().all(|b| b.is_ascii_digit()) {
+                if number.is_some() {
+                    return None;
+                }
+                number = Some(i64::from_str(&token).ok()?);
+                continue;
+            }
+            let adjustment = match token.as_str() {
+                "ago" if is_unit => continue,
+                "last" if number.is_none() => {
+                    number = Some(1);
+                    continue;
+                }
+                "now" | "today" if number.is_none() => None,
+                "yesterday" if number.is_none() => Some(Adjustment::Duration(Duration::DAY)),
+                "noon" if number.is_none() => Some(Adjustment::Hour(12)),
+                "midnight" if number.is_none() => Some(Adjustment::Hour(0)),
+                word => {
+                    if let Some(day) = weekday(word) {
+                        Some(Adjustment::Weekday {
+                            day,
+                            weeks: number.take().map_or(0, |n| n - 1),
+                        })
+                    } else {
+                        prev_was_unit = true;
+                        Some(unit(word, number.take()?)?)
+                    }
+                }
+            };
+            recognized = true;
+            out.extend(adjustment);
         }
-        duration(period, multiplier)
+        (recognized && number.is_none()).then_some(out)
     }
 
     pub(crate) fn parse(input: &str, now: Option<SystemTime>) -> Option<Result<OffsetDateTime, Error>> {
-        parse_inner(input).map(|offset| {
-            let offset = std::time::Duration::from_secs(offset.whole_seconds().try_into()?);
-            now.ok_or(Error::MissingCurrentTime).and_then(|now| {
-                std::panic::catch_unwind(|| {
-                    now.checked_sub(offset)
-                        .expect("BUG: values can't be large enough to cause underflow")
-                        .into()
-                })
-                .map_err(|_| Error::RelativeTimeConversion)
-            })
+        parse_inner(input).map(|adjustments| {
+            let now = OffsetDateTime::from(now.ok_or(Error::MissingCurrentTime)?);
+            let offset = UtcOffset::local_offset_at(now).unwrap_or(UtcOffset::UTC);
+            adjustments
+                .into_iter()
+                .try_fold(now.to_offset(offset), apply)
+                .map(|date| date.to_offset(UtcOffset::UTC))
+                .ok_or(Error::RelativeTimeConversion)
+        })
+    }
+
+    fn apply(date: OffsetDateTime, adjustment: Adjustment) -> Option<OffsetDateTime> {
+        Some(match adjustment {
+            Adjustment::Duration(duration) => date.checked_sub(duration)?,
+            Adjustment::Months(months) => {
+                let months =
+                    (i64::from(date.year()) * 12 + i64::from(u8::from(date.month())) - 1).checked_sub(months)?;
+                let year = i32::try_from(months.div_euclid(12)).ok()?;
+                let month = Month::try_from(u8::try_from(months.rem_euclid(12) + 1).ok()?).ok()?;
+                let day = date.day().min(time::util::days_in_year_month(year, month));
+                date.replace_date(Date::from_calendar_date(year, month, day).ok()?)
+            }
+            Adjustment::Weekday { day, weeks } => {
+                let days_back = match (i64::from(date.weekday().number_days_from_monday())
+                    - i64::from(day.number_days_from_monday()))
+                .rem_euclid(7)
+                {
+                    0 => 7,
+                    days => days,
+                };
+                date.checked_sub(Duration::days(days_back.checked_add(weeks.checked_mul(7)?)?))?
+            }
+            Adjustment::Hour(hour) => {
+                let at_hour = date.replace_time(time::Time::from_hms(hour, 0, 0).ok()?);
+                if at_hour > date {
+                    at_hour.checked_sub(Duration::DAY)?
+                } else {
+                    at_hour
+                }
+            }
         })
     }
 
-    fn duration(period: &str, multiplier: i64) -> Option<Duration> {
-        let period = period.strip_suffix('s').unwrap_or(period);
+    fn weekday(word: &str) -> Option<Weekday> {
+        use Weekday::*;
+        [Monday, Tuesday, Wednesday, Thursday, Friday, Saturday, Sunday]
+            .into_iter()
+            .find(|day| {
+                let name = day.to_string().to_ascii_lowercase();
+                word == name || word == &name[..3]
+            })
+    }
+
+    fn unit(word: &str, multiplier: i64) -> Option<Adjustment> {
+        let period = word.strip_suffix('s').unwrap_or(word);
         let seconds: i64 = match period {
-            "second" => 1,
-            "minute" => 60,
+            "second" | "sec" => 1,
+            "minute" | "min" => 60,
             "hour" => 60 * 60,
             "day" => 24 * 60 * 60,
             "week" => 7 * 24 * 60 * 60,
-            // TODO months & years? YES
-            // Ignore values you don't know, assume seconds then (so does git)
+            "fortnight" => 14 * 24 * 60 * 60,
+            "month" => return Some(Adjustment::Months(multiplier)),
+            "year" => return multiplier.checked_mul(12).map(Adjustment::Months),
             _ => return None,
         };
-        seconds.checked_mul(multiplier).map(Duration::seconds)
+        seconds
+            .checked_mul(multiplier)
+            .map(|seconds| Adjustment::Duration(Duration::seconds(seconds)))
     }
 
     #[cfg(test)]
@@ -137,7 +234,25 @@ mod relative {
 
         #[test]
         fn two_weeks_ago() {
-            assert_eq!(parse_inner("2 weeks ago"), Some(Duration::weeks(2)));
+            let expected = Some(vec![Adjustment::Duration(Duration::weeks(2))]);
+            assert_eq!(parse_inner("2 weeks ago"), expected);
+            assert_eq!(parse_inner("2.weeks.ago"), expected, "dots are separators");
+            assert_eq!(parse_inner("2 weeks"), expected, "'ago' is implied");
+        }
+
+        #[test]
+        fn dangling_or_misplaced_words_are_rejected() {
+            for input in [
+                "2",
+                "ago",
+                "2 ago weeks",
+                "weeks ago",
+                "1 2 weeks",
+                "2 yesterday",
+                "soon",
+            ] {
+                assert_eq!(parse_inner(input), None, "{input:?}");
+            }
         }
     }
 }
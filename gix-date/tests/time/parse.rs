@@ -146,7 +146,7 @@ mod relative {
     use std::time::SystemTime;
 
     use gix_date::time::Sign;
-    use time::{Duration, OffsetDateTime};
+    use time::{macros::datetime, Duration, OffsetDateTime, UtcOffset, Weekday};
 
     #[test]
     fn large_offsets() {
@@ -182,6 +182,83 @@ mod relative {
             "relative times differ"
         );
     }
+
+    fn at(input: &str, now: OffsetDateTime) -> OffsetDateTime {
+        let time = gix_date::parse(input, Some(now.into())).unwrap_or_else(|err| panic!("{input:?}: {err}"));
+        OffsetDateTime::from_unix_timestamp(time.seconds).unwrap()
+    }
+
+    fn local_offset(now: OffsetDateTime) -> UtcOffset {
+        UtcOffset::local_offset_at(now).unwrap_or(UtcOffset::UTC)
+    }
+
+    #[test]
+    fn approxidate_forms() {
+        let now = datetime!(2024-03-31 15:20:10 UTC);
+        assert_eq!(at("2.weeks.ago", now), now - Duration::weeks(2));
+        assert_eq!(at("3 days", now), now - Duration::days(3), "'ago' is implied");
+        assert_eq!(at("1 hour 30 minutes ago", now), now - Duration::minutes(90));
+        assert_eq!(at("yesterday", now), now - Duration::DAY);
+        assert_eq!(at("now", now), now);
+        assert_eq!(at("today", now), now);
+        assert_eq!(at("last week", now), now - Duration::WEEK);
+    }
+
+    #[test]
+    fn months_and_years_are_calendar_based() {
+        let now = datetime!(2024-03-31 15:20:10 UTC);
+        let offset = local_offset(now);
+        let local = now.to_offset(offset);
+        assert_eq!(
+            at("1 month ago", now).to_offset(offset).date(),
+            local.date().replace_day(1).unwrap().previous_day().unwrap(),
+            "days past the end of the month are clamped"
+        );
+        assert_eq!(
+            at("2.years.ago", now),
+            (local - Duration::days(731)).to_offset(UtcOffset::UTC)
+        );
+        assert_eq!(at("12 months ago", now), at("1 year ago", now));
+    }
+
+    #[test]
+    fn times_of_day_and_weekdays_are_in_the_past() {
+        let now = OffsetDateTime::now_utc().replace_nanosecond(0).unwrap();
+        let offset = local_offset(now);
+        let local = now.to_offset(offset);
+
+        let midnight = at("midnight", now).to_offset(offset);
+        assert_eq!(midnight.time(), time::Time::MIDNIGHT);
+        assert_eq!(midnight.date(), local.date());
+
+        let noon = at("noon", now).to_offset(offset);
+        assert_eq!(noon.hour(), 12);
+        assert!(noon <= now && now - noon < Duration::DAY);
+        assert_eq!(at("yesterday noon", now).to_offset(offset), noon - Duration::DAY);
+
+        for (input, weekday) in [("last monday", Weekday::Monday), ("Fri", Weekday::Friday)] {
+            let date = at(input, now).to_offset(offset);
+            assert_eq!(date.weekday(), weekday, "{input}");
+            assert_eq!(date.time(), local.time(), "{input}: the time of day is kept");
+            assert!(
+                date < now && now - date <= Duration::WEEK,
+                "{input}: it's always before today"
+            );
+        }
+        assert_eq!(
+            at("2 monday", now),
+            at("last monday", now) - Duration::WEEK,
+            "numbers go back in weeks"
+        );
+    }
+
+    #[test]
+    fn relative_words_need_the_current_time() {
+        assert!(matches!(
+            gix_date::parse("yesterday", None),
+            Err(gix_date::parse::Error::MissingCurrentTime)
+        ));
+    }
 }
 
 /// Various cases the fuzzer found
@@ -13,6 +13,19 @@ pub mod pipeline;
 #[allow(clippy::empty_docs)]
 pub mod platform;
 
+///
+#[allow(clippy::empty_docs)]
+pub mod unified_diff;
+pub use unified_diff::UnifiedDiff;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod stat;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod word_diff;
+
 /// Information about the diff performed to detect similarity.
 #[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
 pub struct DiffLineStats {
@@ -64,6 +77,11 @@ pub struct Driver {
     /// If `Some(false)`, it won't be considered binary, and the its data will not be sampled for the null-byte either.
     /// Leaving it to `None` means binary detection is automatic, and is based on the presence of the `0` byte in the first 8kB of the buffer.
     pub is_binary: Option<bool>,
+    /// The newline-separated regular expressions to find the name of the function a hunk is contained in, which
+    /// is shown in its header, as configured in `diff.<driver>.xfuncname`.
+    ///
+    /// Use it with [`unified_diff::FunctionName::from_driver()`] if the `xfuncname` feature is enabled.
+    pub xfuncname: Option<BString>,
 }
 
 /// A conversion pipeline to take an object or path from what's stored in `git` to what can be diffed, while
@@ -0,0 +1,205 @@
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// The changes to a single file for display with [`write_stat_to()`] or [`write_numstat_to()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The path to display, which may be produced with [`rename_path()`] for renames and copies.
+    pub path: BString,
+    /// The amount of changes.
+    pub counts: Counts,
+}
+
+/// The amount of changes to a file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Counts {
+    /// The amount of changed lines in a text file, typically obtained from [`DiffLineStats`](super::DiffLineStats)
+    /// or by counting lines in [hunks](super::unified_diff::Hunk).
+    Lines {
+        /// The amount of added lines.
+        insertions: u32,
+        /// The amount of removed lines.
+        removals: u32,
+    },
+    /// The sizes of a binary file.
+    Binary {
+        /// The size in bytes of the old version.
+        before: u64,
+        /// The size in bytes of the new version.
+        after: u64,
+    },
+}
+
+/// Produce the path `git` displays for a rename or copy from `old` to `new`, like `dir/{a => b}/file`, which
+/// factors out common leading and trailing directories.
+pub fn rename_path(old: &BStr, new: &BStr) -> BString {
+    let prefix_len = old.iter().zip(new.iter()).take_while(|(a, b)| a == b).count();
+    let prefix_len = old[..prefix_len].rfind_byte(b'/').map_or(0, |pos| pos + 1);
+
+    let max_suffix_len = old.len().min(new.len()) - prefix_len;
+    let suffix_len = old
+        .iter()
+        .rev()
+        .zip(new.iter().rev())
+        .take(max_suffix_len)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix_len = old[old.len() - suffix_len..]
+        .find_byte(b'/')
+        .map_or(0, |pos| suffix_len - pos);
+
+    let (old_middle, new_middle) = (
+        &old[prefix_len..old.len() - suffix_len],
+        &new[prefix_len..new.len() - suffix_len],
+    );
+    let mut out = BString::from(&old[..prefix_len]);
+    if prefix_len + suffix_len > 0 {
+        out.push_byte(b'{');
+    }
+    out.push_str(old_middle);
+    out.push_str(" => ");
+    out.push_str(new_middle);
+    if prefix_len + suffix_len > 0 {
+        out.push_byte(b'}');
+    }
+    out.push_str(&old[old.len() - suffix_len..]);
+    out
+}
+
+/// Write `entries` in the format of `git diff --numstat` to `out`, with one line of `<insertions>\t<removals>\t<path>`
+/// per entry, where binary files show `-` instead of counts.
+pub fn write_numstat_to(entries: &[Entry], out: &mut dyn std::io::Write) -> std::io::Result<()> {
+    for entry in entries {
+        match entry.counts {
+            Counts::Lines { insertions, removals } => write!(out, "{insertions}\t{removals}\t")?,
+            Counts::Binary { .. } => out.write_all(b"-\t-\t")?,
+        }
+        out.write_all(&entry.path)?;
+        out.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// Write `entries` in the format of `git diff --stat` to `out`, using no more than `width` columns per line
+/// (`git` uses 80 if not connected to a terminal) unless binary files need more, followed by a summary line.
+///
+/// Note that column widths are determined by counting characters, which can differ from what a terminal displays.
+pub fn write_stat_to(entries: &[Entry], width: usize, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+    let path_width = |path: &BStr| path.chars().count();
+    let mut max_path_len = 0;
+    let mut max_change = 0;
+    let mut number_width = 0;
+    let mut bin_width = 0;
+    for entry in entries {
+        max_path_len = max_path_len.max(path_width(entry.path.as_ref()));
+        match entry.counts {
+            Counts::Lines { insertions, removals } => {
+                max_change = max_change.max(insertions as usize + removals as usize);
+            }
+            Counts::Binary { before, after } => {
+                number_width = 3;
+                bin_width = bin_width.max(14 + decimal_width(before as usize) + decimal_width(after as usize));
+            }
+        }
+    }
+    number_width = number_width.max(decimal_width(max_change));
+
+    // Guarantee some space for the graph and the path.
+    let width = width.max(16 + 6 + number_width);
+    let mut graph_width = if max_change + 4 > bin_width {
+        max_change
+    } else {
+        bin_width - 4
+    };
+    let mut name_width = max_path_len;
+    if name_width + number_width + 6 + graph_width > width {
+        if graph_width + number_width + 6 > width * 3 / 8 {
+            graph_width = (width * 3 / 8).saturating_sub(number_width + 6).max(6);
+        }
+        if name_width + number_width + 6 + graph_width > width {
+            name_width = width.saturating_sub(number_width + 6 + graph_width);
+        } else {
+            graph_width = width - number_width - 6 - name_width;
+        }
+    }
+
+    let (mut total_insertions, mut total_removals) = (0, 0);
+    for entry in entries {
+        let path = entry.path.as_bstr();
+        let mut len = path_width(path);
+        let (prefix, path) = if len > name_width {
+            let keep = name_width.saturating_sub(3);
+            let mut path = &path[path
+                .char_indices()
+                .nth(len - keep)
+                .map_or(path.len(), |(start, _, _)| start)..];
+            if let Some(pos) = path.find_byte(b'/') {
+                path = &path[pos..];
+            }
+            len = 3 + path_width(path.as_bstr());
+            ("...", path.as_bstr())
+        } else {
+            ("", path)
+        };
+        write!(
+            out,
+            " {prefix}{path}{:padding$} |",
+            "",
+            padding = name_width.saturating_sub(len)
+        )?;
+        match entry.counts {
+            Counts::Binary { before, after } => {
+                writeln!(out, " {:>number_width$} {before} -> {after} bytes", "Bin")?;
+            }
+            Counts::Lines { insertions, removals } => {
+                total_insertions += insertions as usize;
+                total_removals += removals as usize;
+                let (mut add, mut del) = (insertions as usize, removals as usize);
+                write!(out, " {:>number_width$}", add + del)?;
+                if add + del != 0 {
+                    out.write_all(b" ")?;
+                }
+                if graph_width <= max_change {
+                    let mut total = scale_linear(add + del, graph_width, max_change);
+                    if total < 2 && add != 0 && del != 0 {
+                        total = 2;
+                    }
+                    if add < del {
+                        add = scale_linear(add, graph_width, max_change);
+                        del = total - add;
+                    } else {
+                        del = scale_linear(del, graph_width, max_change);
+                        add = total - del;
+                    }
+                }
+                writeln!(out, "{}{}", "+".repeat(add), "-".repeat(del))?;
+            }
+        }
+    }
+
+    let plural = |n: usize| if n == 1 { "" } else { "s" };
+    write!(out, " {} file{} changed", entries.len(), plural(entries.len()))?;
+    if total_insertions != 0 || total_removals == 0 {
+        write!(out, ", {total_insertions} insertion{}(+)", plural(total_insertions))?;
+    }
+    if total_removals != 0 || total_insertions == 0 {
+        write!(out, ", {total_removals} deletion{}(-)", plural(total_removals))?;
+    }
+    writeln!(out)
+}
+
+fn decimal_width(mut n: usize) -> usize {
+    let mut width = 1;
+    while n >= 10 {
+        n /= 10;
+        width += 1;
+    }
+    width
+}
+
+fn scale_linear(n: usize, width: usize, max_change: usize) -> usize {
+    if n == 0 {
+        0
+    } else {
+        1 + (n * (width - 1) / max_change)
+    }
+}
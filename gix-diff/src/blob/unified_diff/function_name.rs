@@ -0,0 +1,100 @@
+use bstr::{BStr, ByteSlice};
+
+use crate::blob::unified_diff::FunctionName;
+
+/// The maximum amount of bytes of a function name, matching what `git` shows.
+const MAX_LEN: usize = 80;
+
+impl FunctionName {
+    /// Return the function name contained in `line`, or `None` if it doesn't denote a function.
+    ///
+    /// `line` may end with a line terminator, which is ignored.
+    pub fn find<'a>(&self, line: &'a [u8]) -> Option<&'a BStr> {
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let name = match self {
+            FunctionName::Default => line
+                .first()
+                .filter(|b| b.is_ascii_alphabetic() || **b == b'_' || **b == b'$')
+                .map(|_| line)?,
+            #[cfg(feature = "xfuncname")]
+            FunctionName::Patterns(patterns) => patterns.find(line)?,
+        };
+        Some(name[..name.len().min(MAX_LEN)].trim_end().as_bstr())
+    }
+
+    /// Return the matcher to use for the given `driver` as obtained via git-attributes, which uses its
+    /// [`xfuncname`](crate::blob::Driver::xfuncname) patterns if present, or [the default](FunctionName::Default).
+    #[cfg(feature = "xfuncname")]
+    pub fn from_driver(driver: Option<&crate::blob::Driver>) -> Result<Self, Error> {
+        Ok(match driver.and_then(|d| d.xfuncname.as_ref()) {
+            Some(patterns) => FunctionName::Patterns(Patterns::from_xfuncname(patterns.as_ref())?),
+            None => FunctionName::Default,
+        })
+    }
+}
+
+#[cfg(feature = "xfuncname")]
+pub use patterns::{Error, Patterns};
+
+#[cfg(feature = "xfuncname")]
+mod patterns {
+    use bstr::{BStr, ByteSlice};
+
+    /// The error returned by [`Patterns::from_xfuncname()`].
+    #[derive(Debug, thiserror::Error)]
+    #[error("Invalid function name pattern {pattern:?}")]
+    pub struct Error {
+        /// The pattern that failed to compile.
+        pub pattern: bstr::BString,
+        /// The underlying error.
+        pub source: regex::Error,
+    }
+
+    /// A list of regular expressions as configured in `diff.<driver>.xfuncname`.
+    ///
+    /// The first pattern that matches a line determines the outcome. If it is negated with a leading `!`, the line isn't
+    /// a function name. Otherwise, the first capture group or the whole match, if there is no group, is the function name.
+    #[derive(Debug, Clone)]
+    pub struct Patterns {
+        patterns: Vec<(bool, regex::bytes::Regex)>,
+    }
+
+    impl Patterns {
+        /// Parse the newline-separated `patterns` as found in `diff.<driver>.xfuncname`.
+        ///
+        /// Note that `git` uses POSIX extended regular expressions, which are only approximated here.
+        pub fn from_xfuncname(patterns: &BStr) -> Result<Self, Error> {
+            let patterns = patterns
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(|line| {
+                    let (negated, pattern) = match line.strip_prefix(b"!") {
+                        Some(pattern) => (true, pattern),
+                        None => (false, line),
+                    };
+                    regex::bytes::RegexBuilder::new(&pattern.to_str_lossy())
+                        .unicode(false)
+                        .build()
+                        .map(|re| (negated, re))
+                        .map_err(|source| Error {
+                            pattern: line.into(),
+                            source,
+                        })
+                })
+                .collect::<Result<_, _>>()?;
+            Ok(Patterns { patterns })
+        }
+
+        pub(super) fn find<'a>(&self, line: &'a [u8]) -> Option<&'a [u8]> {
+            let (negated, captures) = self
+                .patterns
+                .iter()
+                .find_map(|(negated, re)| re.captures(line).map(|c| (*negated, c)))?;
+            if negated {
+                return None;
+            }
+            captures.get(1).or_else(|| captures.get(0)).map(|m| m.as_bytes())
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use crate::blob::unified_diff::Header;
+
+/// The kind of change a [`Header`] describes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// The file was added.
+    Addition,
+    /// The file was deleted.
+    Deletion,
+    /// The file was modified in place.
+    Modification,
+    /// The file was renamed from the old path to the new path.
+    Rename {
+        /// The similarity between the old and the new version in percent.
+        similarity: u8,
+    },
+    /// The file at the new path was copied from the old path.
+    Copy {
+        /// The similarity between the old and the new version in percent.
+        similarity: u8,
+    },
+}
+
+impl<'a> Header<'a> {
+    /// Create a new instance for a modification of the file at `path` with the given ids and modes.
+    pub fn modification(
+        path: &'a bstr::BStr,
+        (old_id, old_mode): (&'a gix_hash::oid, gix_object::tree::EntryKind),
+        (new_id, new_mode): (&'a gix_hash::oid, gix_object::tree::EntryKind),
+    ) -> Self {
+        Header {
+            kind: Kind::Modification,
+            old_path: path,
+            new_path: path,
+            old_mode,
+            new_mode,
+            old_id,
+            new_id,
+            hex_len: 7,
+        }
+    }
+
+    /// Write all lines of the extended header to `out`, from `diff --git` up to and including the `index` line if there is one.
+    ///
+    /// Note that paths are written verbatim, without quoting.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        writeln!(out, "diff --git a/{} b/{}", self.old_path, self.new_path)?;
+        let mut mode_in_index_line = false;
+        match self.kind {
+            Kind::Addition => writeln!(out, "new file mode {}", self.new_mode.as_octal_str())?,
+            Kind::Deletion => writeln!(out, "deleted file mode {}", self.old_mode.as_octal_str())?,
+            Kind::Modification | Kind::Rename { .. } | Kind::Copy { .. } => {
+                if self.old_mode == self.new_mode {
+                    mode_in_index_line = true;
+                } else {
+                    writeln!(out, "old mode {}", self.old_mode.as_octal_str())?;
+                    writeln!(out, "new mode {}", self.new_mode.as_octal_str())?;
+                }
+            }
+        }
+        match self.kind {
+            Kind::Rename { similarity } => {
+                writeln!(out, "similarity index {similarity}%")?;
+                writeln!(out, "rename from {}", self.old_path)?;
+                writeln!(out, "rename to {}", self.new_path)?;
+            }
+            Kind::Copy { similarity } => {
+                writeln!(out, "similarity index {similarity}%")?;
+                writeln!(out, "copy from {}", self.old_path)?;
+                writeln!(out, "copy to {}", self.new_path)?;
+            }
+            Kind::Addition | Kind::Deletion | Kind::Modification => {}
+        }
+
+        if self.has_content_change() {
+            write!(
+                out,
+                "index {}..{}",
+                self.old_id().to_hex_with_len(self.hex_len),
+                self.new_id().to_hex_with_len(self.hex_len)
+            )?;
+            if mode_in_index_line {
+                write!(out, " {}", self.new_mode.as_octal_str())?;
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+
+    /// Write the `--- a/<old_path>` and `+++ b/<new_path>` lines to `out`, which precede the hunks of textual changes,
+    /// using `/dev/null` for the missing side of additions and deletions.
+    pub fn write_file_names_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        match self.kind {
+            Kind::Addition => writeln!(out, "--- /dev/null")?,
+            _ => writeln!(out, "--- a/{}", self.old_path)?,
+        }
+        match self.kind {
+            Kind::Deletion => writeln!(out, "+++ /dev/null"),
+            _ => writeln!(out, "+++ b/{}", self.new_path),
+        }
+    }
+
+    /// Write the line `Binary files a/<old_path> and b/<new_path> differ` to `out`, which `git` writes instead of
+    /// hunks if one of the versions is considered binary.
+    pub fn write_binary_files_differ_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        let old = match self.kind {
+            Kind::Addition => "/dev/null".into(),
+            _ => format!("a/{}", self.old_path),
+        };
+        let new = match self.kind {
+            Kind::Deletion => "/dev/null".into(),
+            _ => format!("b/{}", self.new_path),
+        };
+        writeln!(out, "Binary files {old} and {new} differ")
+    }
+
+    /// Return `true` if the content of the old and new version differ, which is when an `index` line is written.
+    pub fn has_content_change(&self) -> bool {
+        self.old_id() != self.new_id()
+    }
+
+    fn old_id(&self) -> &gix_hash::oid {
+        match self.kind {
+            Kind::Addition => self.new_id.kind().null_ref(),
+            _ => self.old_id,
+        }
+    }
+
+    fn new_id(&self) -> &gix_hash::oid {
+        match self.kind {
+            Kind::Deletion => self.old_id.kind().null_ref(),
+            _ => self.new_id,
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use std::ops::Range;
+
+use bstr::{BStr, ByteSlice};
+use imara_diff::{
+    intern::{InternedInput, Interner, Token},
+    Sink,
+};
+
+///
+#[allow(clippy::empty_docs)]
+pub mod function_name;
+///
+#[allow(clippy::empty_docs)]
+pub mod header;
+
+/// The amount of unchanged lines to show before and after each change, which also determines when changes are close enough
+/// to be merged into a single hunk.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ContextSize {
+    /// The amount of unchanged lines before and after each change, like `-U<n>` or `diff.context`.
+    pub symmetrical: u32,
+}
+
+impl Default for ContextSize {
+    /// Show three lines of context, just like `git` does by default.
+    fn default() -> Self {
+        ContextSize::symmetrical(3)
+    }
+}
+
+impl ContextSize {
+    /// Show `n` lines of context before and after each change.
+    pub fn symmetrical(n: u32) -> Self {
+        ContextSize { symmetrical: n }
+    }
+}
+
+/// A way to determine the name of the function a hunk belongs to, shown after the `@@ … @@` hunk header.
+#[derive(Debug, Clone)]
+pub enum FunctionName {
+    /// Use lines that start with an alphabetic character, `_` or `$`, which is what `git` does if no
+    /// `diff.<driver>.xfuncname` is configured.
+    Default,
+    /// Use the regular expressions of `diff.<driver>.xfuncname`.
+    #[cfg(feature = "xfuncname")]
+    Patterns(function_name::Patterns),
+}
+
+/// The kind of a line in a [`Hunk`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum LineKind {
+    /// The line is unchanged and shown for context, prefixed with a space.
+    Context,
+    /// The line was removed from the old version, prefixed with `-`.
+    Remove,
+    /// The line was added to the new version, prefixed with `+`.
+    Add,
+}
+
+impl LineKind {
+    /// Return the character that prefixes a line of this kind in a unified diff.
+    pub fn to_prefix(self) -> u8 {
+        match self {
+            LineKind::Context => b' ',
+            LineKind::Remove => b'-',
+            LineKind::Add => b'+',
+        }
+    }
+}
+
+/// A set of changes that are close to each other along with the lines of context surrounding them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk<'a> {
+    /// The zero-based range of lines in the old version of the file that this hunk covers, including context lines.
+    pub before: Range<u32>,
+    /// The zero-based range of lines in the new version of the file that this hunk covers, including context lines.
+    pub after: Range<u32>,
+    /// The name of the function the hunk is contained in, if it could be determined.
+    pub function_name: Option<&'a BStr>,
+    /// All lines of the hunk in order, including their line terminator if there was one.
+    pub lines: Vec<(LineKind, &'a BStr)>,
+}
+
+/// A [`Sink`] to build [hunks](Hunk) suitable to produce a unified diff, similar to what `git diff` prints.
+///
+/// The input must be tokenized into lines *including* their line terminator, like
+/// [`prepare_diff::Outcome::interned_input()`](crate::blob::platform::prepare_diff::Outcome::interned_input()) does,
+/// so that missing newlines at the end of a file can be detected.
+pub struct UnifiedDiff<'a> {
+    before: &'a [Token],
+    after: &'a [Token],
+    interner: &'a Interner<&'a [u8]>,
+
+    context_size: ContextSize,
+    function_name: Option<&'a FunctionName>,
+
+    /// The end of the last change in `before` and `after` respectively.
+    pos_before: u32,
+    pos_after: u32,
+    current: Option<Hunk<'a>>,
+    hunks: Vec<Hunk<'a>>,
+
+    /// All lines before this one were already searched for function names.
+    function_name_searched_to: u32,
+    last_function_name: Option<&'a BStr>,
+}
+
+impl<'a> UnifiedDiff<'a> {
+    /// Create a new instance to produce hunks from `input` with `context_size` lines of context around each change.
+    pub fn new(input: &'a InternedInput<&'a [u8]>, context_size: ContextSize) -> Self {
+        UnifiedDiff {
+            before: &input.before,
+            after: &input.after,
+            interner: &input.interner,
+            context_size,
+            function_name: None,
+            pos_before: 0,
+            pos_after: 0,
+            current: None,
+            hunks: Vec::new(),
+            function_name_searched_to: 0,
+            last_function_name: None,
+        }
+    }
+
+    /// Find the names of the functions that hunks are contained in using `function_name`, by searching
+    /// the old version of the file backwards from the start of each hunk.
+    pub fn with_function_name(mut self, function_name: &'a FunctionName) -> Self {
+        self.function_name = Some(function_name);
+        self
+    }
+
+    fn find_function_name(&mut self, hunk_start: u32) -> Option<&'a BStr> {
+        let matcher = self.function_name?;
+        for line in (self.function_name_searched_to..hunk_start).rev() {
+            if let Some(name) = matcher.find(self.interner[self.before[line as usize]]) {
+                self.last_function_name = Some(name);
+                break;
+            }
+        }
+        self.function_name_searched_to = hunk_start;
+        self.last_function_name
+    }
+
+    fn push_lines(&self, hunk: &mut Hunk<'a>, kind: LineKind, tokens: &[Token]) {
+        hunk.lines
+            .extend(tokens.iter().map(|token| (kind, self.interner[*token].as_bstr())));
+    }
+
+    fn flush(&mut self) {
+        let Some(mut hunk) = self.current.take() else {
+            return;
+        };
+        let end = (self.pos_before + self.context_size.symmetrical).min(self.before.len() as u32);
+        self.push_lines(
+            &mut hunk,
+            LineKind::Context,
+            &self.before[self.pos_before as usize..end as usize],
+        );
+        hunk.before.end = end;
+        hunk.after.end = self.pos_after + (end - self.pos_before);
+        self.hunks.push(hunk);
+    }
+}
+
+impl<'a> Sink for UnifiedDiff<'a> {
+    type Out = Vec<Hunk<'a>>;
+
+    fn process_change(&mut self, before: Range<u32>, after: Range<u32>) {
+        if self.current.is_some() && before.start - self.pos_before > 2 * self.context_size.symmetrical {
+            self.flush();
+        }
+        let mut hunk = match self.current.take() {
+            Some(mut hunk) => {
+                self.push_lines(
+                    &mut hunk,
+                    LineKind::Context,
+                    &self.before[self.pos_before as usize..before.start as usize],
+                );
+                hunk
+            }
+            None => {
+                let start = before.start.saturating_sub(self.context_size.symmetrical);
+                let leading_context = before.start - start;
+                let mut hunk = Hunk {
+                    before: start..start,
+                    after: after.start - leading_context..after.start - leading_context,
+                    function_name: self.find_function_name(start),
+                    lines: Vec::new(),
+                };
+                self.push_lines(
+                    &mut hunk,
+                    LineKind::Context,
+                    &self.before[start as usize..before.start as usize],
+                );
+                hunk
+            }
+        };
+        self.push_lines(
+            &mut hunk,
+            LineKind::Remove,
+            &self.before[before.start as usize..before.end as usize],
+        );
+        self.push_lines(
+            &mut hunk,
+            LineKind::Add,
+            &self.after[after.start as usize..after.end as usize],
+        );
+        self.pos_before = before.end;
+        self.pos_after = after.end;
+        self.current = Some(hunk);
+    }
+
+    fn finish(mut self) -> Self::Out {
+        self.flush();
+        self.hunks
+    }
+}
+
+impl Hunk<'_> {
+    /// Write the `@@ -<before> +<after> @@ <function name>` line of this hunk, including its newline, to `out`.
+    pub fn write_header_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        out.write_all(b"@@ -")?;
+        write_range(&self.before, out)?;
+        out.write_all(b" +")?;
+        write_range(&self.after, out)?;
+        out.write_all(b" @@")?;
+        if let Some(name) = self.function_name {
+            out.write_all(b" ")?;
+            out.write_all(name)?;
+        }
+        out.write_all(b"\n")
+    }
+
+    /// Write the header and all lines of this hunk to `out`, marking lines without trailing newline with
+    /// `\ No newline at end of file`, just like `git` does.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        self.write_header_to(out)?;
+        for (kind, line) in &self.lines {
+            out.write_all(&[kind.to_prefix()])?;
+            out.write_all(line)?;
+            if !line.ends_with(b"\n") {
+                out.write_all(b"\n\\ No newline at end of file\n")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write `range` the way `git` does, with one-based line numbers, omitting the length if it is one, and using the line
+/// before the hunk as start if it is empty.
+fn write_range(range: &Range<u32>, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+    match range.end - range.start {
+        0 => write!(out, "{},0", range.start),
+        1 => write!(out, "{}", range.start + 1),
+        len => write!(out, "{},{}", range.start + 1, len),
+    }
+}
+
+/// The extended header of a file in a patch, like `git diff` writes it before the hunks of each file, starting with
+/// `diff --git a/<path> b/<path>`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Header<'a> {
+    /// The kind of change to the file.
+    pub kind: header::Kind,
+    /// The path of the old version of the file, without prefix.
+    pub old_path: &'a BStr,
+    /// The path of the new version of the file, without prefix, the same as `old_path` unless the file was renamed or copied.
+    pub new_path: &'a BStr,
+    /// The mode of the old version of the file, ignored for additions.
+    pub old_mode: gix_object::tree::EntryKind,
+    /// The mode of the new version of the file, ignored for deletions.
+    pub new_mode: gix_object::tree::EntryKind,
+    /// The id of the old version of the file, ignored for additions.
+    pub old_id: &'a gix_hash::oid,
+    /// The id of the new version of the file, ignored for deletions.
+    pub new_id: &'a gix_hash::oid,
+    /// The amount of hexadecimal characters to show of each id in the `index` line, 7 by default.
+    pub hex_len: usize,
+}
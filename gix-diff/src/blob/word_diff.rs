@@ -0,0 +1,96 @@
+use std::ops::Range;
+
+use imara_diff::intern::{InternedInput, TokenSource};
+
+/// A [`TokenSource`] which splits text into words, i.e. runs of non-whitespace characters, ignoring all whitespace
+/// in between like `git diff --word-diff` does by default.
+#[derive(Debug, Copy, Clone)]
+pub struct Words<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Words<'a> {
+    /// Split `data` into words.
+    pub fn new(data: &'a [u8]) -> Self {
+        Words { data }
+    }
+
+    /// Return an iterator over the byte ranges of all words.
+    pub fn ranges(&self) -> impl Iterator<Item = Range<usize>> + 'a {
+        let data = self.data;
+        let mut pos = 0;
+        std::iter::from_fn(move || {
+            let start = pos + data[pos..].iter().position(|b| !b.is_ascii_whitespace())?;
+            let end = data[start..]
+                .iter()
+                .position(u8::is_ascii_whitespace)
+                .map_or(data.len(), |len| start + len);
+            pos = end;
+            Some(start..end)
+        })
+    }
+}
+
+impl<'a> TokenSource for Words<'a> {
+    type Token = &'a [u8];
+    type Tokenizer = Box<dyn Iterator<Item = &'a [u8]> + 'a>;
+
+    fn tokenize(&self) -> Self::Tokenizer {
+        let data = self.data;
+        Box::new(self.ranges().map(move |range| &data[range]))
+    }
+
+    fn estimate_tokens(&self) -> u32 {
+        (self.data.len() / 6) as u32
+    }
+}
+
+/// Write the difference between the text `before` and `after` to `out` in the format of `git diff --word-diff=plain`,
+/// which shows unchanged text as is, removed words as `[-removed-]` and added words as `{+added+}`, using `algorithm`.
+///
+/// Typically, `before` are all removed lines of a [hunk](super::unified_diff::Hunk) and `after` are all of its added lines.
+/// Whitespace between words is taken from `after`, unless it's within a change.
+pub fn write_to(
+    before: &[u8],
+    after: &[u8],
+    algorithm: imara_diff::Algorithm,
+    out: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    let (old, new) = (Words::new(before), Words::new(after));
+    let input = InternedInput::new(old, new);
+    let mut changes = Vec::new();
+    imara_diff::diff(algorithm, &input, |before: Range<u32>, after: Range<u32>| {
+        changes.push((before, after))
+    });
+
+    let old_words: Vec<_> = old.ranges().collect();
+    let new_words: Vec<_> = new.ranges().collect();
+    let bytes_of = |words: &[Range<usize>], tokens: Range<u32>| {
+        if tokens.is_empty() {
+            let end = tokens.start.checked_sub(1).map_or(0, |prev| words[prev as usize].end);
+            end..end
+        } else {
+            words[tokens.start as usize].start..words[tokens.end as usize - 1].end
+        }
+    };
+
+    let mut pos = 0;
+    for (removed, added) in changes {
+        let (removed_is_empty, added_is_empty) = (removed.is_empty(), added.is_empty());
+        let removed = bytes_of(&old_words, removed);
+        let added = bytes_of(&new_words, added);
+        out.write_all(&after[pos..added.start])?;
+        if !removed_is_empty {
+            out.write_all(b"[-")?;
+            out.write_all(&before[removed])?;
+            out.write_all(b"-]")?;
+        }
+        if !added_is_empty {
+            out.write_all(b"{+")?;
+            out.write_all(&after[added.clone()])?;
+            out.write_all(b"+}")?;
+        }
+        pos = added.end;
+    }
+    out.write_all(&after[pos..])
+}
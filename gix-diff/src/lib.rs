@@ -50,3 +50,7 @@ pub mod tree;
 ///
 #[cfg(feature = "blob")]
 pub mod blob;
+
+///
+#[cfg(feature = "blob")]
+pub mod patch;
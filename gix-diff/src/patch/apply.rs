@@ -0,0 +1,229 @@
+use bstr::ByteSlice;
+
+use crate::{
+    blob::unified_diff::LineKind,
+    patch::{delta, Binary, Hunk},
+};
+
+/// How to compare lines of the patch with lines of the file it is applied to.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Whitespace {
+    /// Lines must match exactly.
+    #[default]
+    Exact,
+    /// Lines match if they only differ in the amount of whitespace, like `git apply --ignore-whitespace`.
+    ///
+    /// Note that lines of context will retain their original whitespace.
+    IgnoreChanges,
+}
+
+/// Options for use in [`hunks()`].
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The maximum amount of lines of context at the beginning and end of each hunk that may be ignored if the hunk
+    /// doesn't apply otherwise, like `patch --fuzz` or `git apply -C<n>` with the amount of context subtracted.
+    pub fuzz: u32,
+    /// How to compare lines.
+    pub whitespace: Whitespace,
+    /// If `true`, apply the patch in reverse, like `git apply -R`.
+    pub reverse: bool,
+}
+
+/// Information about how a single hunk was applied.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HunkOutcome {
+    /// The hunk was applied.
+    Applied {
+        /// The amount of lines the hunk was moved relative to the position stated in the patch.
+        offset: i64,
+        /// The amount of lines of context that had to be ignored at the beginning and end of the hunk.
+        fuzz: u32,
+    },
+    /// The hunk could not be applied and its change is missing in the result.
+    Rejected,
+}
+
+/// The outcome of [`hunks()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The data with all applicable hunks applied.
+    pub data: Vec<u8>,
+    /// Information about each hunk, in order.
+    pub hunks: Vec<HunkOutcome>,
+}
+
+impl Outcome {
+    /// Return the indices of all hunks that could not be applied.
+    pub fn rejected_hunks(&self) -> Vec<usize> {
+        self.hunks
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, outcome)| matches!(outcome, HunkOutcome::Rejected).then_some(idx))
+            .collect()
+    }
+
+    /// Return `true` if all hunks were applied.
+    pub fn is_complete(&self) -> bool {
+        self.hunks
+            .iter()
+            .all(|outcome| matches!(outcome, HunkOutcome::Applied { .. }))
+    }
+}
+
+/// Apply `hunks` to `data` and return the result along with information about each hunk.
+///
+/// Hunks are searched for near the position stated in the patch, adjusted by the offset of previous hunks, and
+/// they can only apply after previously applied hunks. Hunks that can't be applied are skipped and reported as
+/// [rejected](HunkOutcome::Rejected).
+pub fn hunks(data: &[u8], hunks: &[Hunk], options: Options) -> Outcome {
+    let lines: Vec<&[u8]> = data.lines_with_terminator().collect();
+    let mut out = Vec::with_capacity(data.len());
+    let mut outcomes = Vec::with_capacity(hunks.len());
+    let (mut pos, mut offset) = (0, 0i64);
+
+    for hunk in hunks {
+        let patch_lines: Vec<_> = hunk
+            .lines
+            .iter()
+            .map(|(kind, line)| {
+                let kind = match (kind, options.reverse) {
+                    (LineKind::Add, true) => LineKind::Remove,
+                    (LineKind::Remove, true) => LineKind::Add,
+                    (kind, _) => *kind,
+                };
+                (kind, line.as_slice())
+            })
+            .collect();
+        let start = if options.reverse {
+            hunk.after.start
+        } else {
+            hunk.before.start
+        };
+        let leading = patch_lines
+            .iter()
+            .take_while(|(kind, _)| *kind == LineKind::Context)
+            .count();
+        let trailing = patch_lines
+            .iter()
+            .rev()
+            .take_while(|(kind, _)| *kind == LineKind::Context)
+            .count()
+            .min(patch_lines.len() - leading);
+
+        let mut outcome = HunkOutcome::Rejected;
+        for fuzz in 0..=(options.fuzz as usize).min(leading.max(trailing)) {
+            let (skip_leading, skip_trailing) = (fuzz.min(leading), fuzz.min(trailing));
+            let patch_lines = &patch_lines[skip_leading..patch_lines.len() - skip_trailing];
+            let preimage: Vec<_> = patch_lines
+                .iter()
+                .filter(|(kind, _)| *kind != LineKind::Add)
+                .map(|(_, line)| *line)
+                .collect();
+            let has_context = leading + trailing > 0;
+            let anchor_beginning = has_context && leading == 0;
+            let anchor_end = has_context && trailing == 0;
+            let expected = start as i64 + offset + skip_leading as i64;
+
+            let Some(found) = find_preimage(
+                &lines,
+                &preimage,
+                pos,
+                expected,
+                anchor_beginning,
+                anchor_end,
+                options.whitespace,
+            ) else {
+                continue;
+            };
+
+            for line in &lines[pos..found] {
+                out.extend_from_slice(line);
+            }
+            let mut target = lines[found..found + preimage.len()].iter();
+            for (kind, line) in patch_lines {
+                match kind {
+                    LineKind::Context => out.extend_from_slice(target.next().expect("matched")),
+                    LineKind::Remove => {
+                        target.next();
+                    }
+                    LineKind::Add => out.extend_from_slice(line),
+                }
+            }
+            pos = found + preimage.len();
+            offset = found as i64 - (start as i64 + skip_leading as i64);
+            outcome = HunkOutcome::Applied {
+                offset,
+                fuzz: fuzz as u32,
+            };
+            break;
+        }
+        outcomes.push(outcome);
+    }
+    for line in &lines[pos..] {
+        out.extend_from_slice(line);
+    }
+    Outcome {
+        data: out,
+        hunks: outcomes,
+    }
+}
+
+/// The error returned by [`binary()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The binary patch can't be applied in reverse as it lacks the reverse hunk")]
+    MissingReverseHunk,
+    #[error(transparent)]
+    Delta(#[from] delta::Error),
+}
+
+/// Apply the binary `patch` to `data`, in `reverse` if `true`, and return the result.
+pub fn binary(data: &[u8], patch: &Binary, reverse: bool) -> Result<Vec<u8>, Error> {
+    let hunk = if reverse {
+        patch.reverse.as_ref().ok_or(Error::MissingReverseHunk)?
+    } else {
+        &patch.forward
+    };
+    Ok(hunk.apply(data)?)
+}
+
+/// Find `preimage` in `lines` at or after `min_pos`, as close as possible to `expected`.
+fn find_preimage(
+    lines: &[&[u8]],
+    preimage: &[&[u8]],
+    min_pos: usize,
+    expected: i64,
+    anchor_beginning: bool,
+    anchor_end: bool,
+    whitespace: Whitespace,
+) -> Option<usize> {
+    let max_pos = lines.len().checked_sub(preimage.len())?;
+    if min_pos > max_pos {
+        return None;
+    }
+    let matches_at = |pos: usize| {
+        (!anchor_beginning || pos == 0)
+            && (!anchor_end || pos == max_pos)
+            && lines[pos..pos + preimage.len()]
+                .iter()
+                .zip(preimage)
+                .all(|(a, b)| lines_match(a, b, whitespace))
+    };
+    let expected = expected.clamp(min_pos as i64, max_pos as i64) as usize;
+    let max_distance = (expected - min_pos).max(max_pos - expected);
+    (0..=max_distance).find_map(|distance| {
+        let before = expected.checked_sub(distance).filter(|pos| *pos >= min_pos);
+        let after = Some(expected + distance).filter(|pos| distance != 0 && *pos <= max_pos);
+        before
+            .filter(|pos| matches_at(*pos))
+            .or_else(|| after.filter(|pos| matches_at(*pos)))
+    })
+}
+
+fn lines_match(a: &[u8], b: &[u8], whitespace: Whitespace) -> bool {
+    match whitespace {
+        Whitespace::Exact => a == b,
+        Whitespace::IgnoreChanges => a.fields().eq(b.fields()),
+    }
+}
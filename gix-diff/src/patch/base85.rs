@@ -0,0 +1,52 @@
+//! The base85 encoding `git` uses for binary patches.
+
+const ALPHABET: &[u8; 85] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+
+/// The error returned by [`decode()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Invalid base85 character {byte:?}")]
+    InvalidCharacter { byte: char },
+    #[error("Base85 data must come in groups of 5 characters, got {len}")]
+    InvalidLength { len: usize },
+    #[error("A group of base85 characters overflows 32 bits")]
+    Overflow,
+}
+
+/// Encode `data` as base85 and append it to `out`, padding `data` with zeroes to a multiple of 4 bytes.
+pub fn encode(data: &[u8], out: &mut Vec<u8>) {
+    for chunk in data.chunks(4) {
+        let mut group = [0u8; 4];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let mut acc = u32::from_be_bytes(group);
+        let mut encoded = [0u8; 5];
+        for byte in encoded.iter_mut().rev() {
+            *byte = ALPHABET[(acc % 85) as usize];
+            acc /= 85;
+        }
+        out.extend_from_slice(&encoded);
+    }
+}
+
+/// Decode the base85 characters in `data` and append the resulting bytes, a multiple of 4, to `out`.
+pub fn decode(data: &[u8], out: &mut Vec<u8>) -> Result<(), Error> {
+    if data.len() % 5 != 0 {
+        return Err(Error::InvalidLength { len: data.len() });
+    }
+    for group in data.chunks(5) {
+        let mut acc = 0u32;
+        for byte in group {
+            let value = ALPHABET
+                .iter()
+                .position(|b| b == byte)
+                .ok_or(Error::InvalidCharacter { byte: *byte as char })?;
+            acc = acc
+                .checked_mul(85)
+                .and_then(|acc| acc.checked_add(value as u32))
+                .ok_or(Error::Overflow)?;
+        }
+        out.extend_from_slice(&acc.to_be_bytes());
+    }
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use std::io::Write;
+
+use crate::patch::{base85, delta, BinaryHunk};
+
+/// The maximum amount of bytes encoded on a single line.
+const MAX_BYTES_PER_LINE: usize = 52;
+
+/// Write a binary patch to turn `old` into `new` and vice versa to `out`, just like `git diff --binary` does, starting
+/// with `GIT binary patch`.
+///
+/// Each direction is encoded as `literal` if that's smaller than a `delta`, compressed with zlib and encoded with base85.
+/// Note that `git apply` expects the [`Header`](crate::blob::unified_diff::Header) to use full ids, i.e. a `hex_len` of 40.
+pub fn write_to(old: &[u8], new: &[u8], out: &mut dyn std::io::Write) -> std::io::Result<()> {
+    out.write_all(b"GIT binary patch\n")?;
+    write_hunk(old, new, out)?;
+    write_hunk(new, old, out)
+}
+
+fn write_hunk(from: &[u8], to: &[u8], out: &mut dyn std::io::Write) -> std::io::Result<()> {
+    let literal = deflate(to)?;
+    let delta = if from.is_empty() || to.is_empty() {
+        None
+    } else {
+        let delta = delta::create(from, to);
+        let compressed = deflate(&delta)?;
+        (compressed.len() < literal.len()).then_some((delta.len(), compressed))
+    };
+    let data = match delta {
+        Some((size, compressed)) => {
+            writeln!(out, "delta {size}")?;
+            compressed
+        }
+        None => {
+            writeln!(out, "literal {}", to.len())?;
+            literal
+        }
+    };
+
+    let mut line = Vec::with_capacity(1 + MAX_BYTES_PER_LINE / 4 * 5 + 1);
+    for chunk in data.chunks(MAX_BYTES_PER_LINE) {
+        line.clear();
+        line.push(match chunk.len() {
+            len @ 1..=26 => b'A' + len as u8 - 1,
+            len => b'a' + len as u8 - 27,
+        });
+        base85::encode(chunk, &mut line);
+        line.push(b'\n');
+        out.write_all(&line)?;
+    }
+    out.write_all(b"\n")
+}
+
+fn deflate(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut out = gix_features::zlib::stream::deflate::Write::new(Vec::new());
+    out.write_all(data)?;
+    out.flush()?;
+    Ok(out.into_inner())
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod decode {
+    /// The error returned by [`decode()`](super::decode()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Expected a line like 'literal <size>' or 'delta <size>', got {line:?}")]
+        InvalidHeader { line: bstr::BString },
+        #[error("Invalid length marker in binary patch line {line:?}")]
+        InvalidLength { line: bstr::BString },
+        #[error(transparent)]
+        Base85(#[from] crate::patch::base85::Error),
+        #[error("Could not decompress binary patch data")]
+        Inflate(#[from] gix_features::zlib::inflate::Error),
+        #[error("Decompressed binary patch data has {actual} bytes, but {expected} bytes were expected")]
+        Size { expected: usize, actual: usize },
+    }
+}
+
+/// Decode a single hunk of a binary patch from its `header` line, like `literal 42`, and the encoded `lines` that follow it,
+/// all without line terminator.
+pub fn decode<'a>(header: &[u8], lines: impl IntoIterator<Item = &'a [u8]>) -> Result<BinaryHunk, decode::Error> {
+    let invalid_header = || decode::Error::InvalidHeader { line: header.into() };
+    let (kind, size) = header
+        .iter()
+        .position(|b| *b == b' ')
+        .map(|pos| (&header[..pos], &header[pos + 1..]))
+        .ok_or_else(invalid_header)?;
+    let size: usize = std::str::from_utf8(size)
+        .ok()
+        .and_then(|size| size.parse().ok())
+        .ok_or_else(invalid_header)?;
+
+    let mut compressed = Vec::new();
+    let mut decoded = Vec::with_capacity(MAX_BYTES_PER_LINE);
+    for line in lines {
+        let len = match line.first() {
+            Some(len @ b'A'..=b'Z') => (len - b'A') as usize + 1,
+            Some(len @ b'a'..=b'z') => (len - b'a') as usize + 27,
+            _ => return Err(decode::Error::InvalidLength { line: line.into() }),
+        };
+        decoded.clear();
+        base85::decode(&line[1..], &mut decoded)?;
+        if decoded.len() < len {
+            return Err(decode::Error::InvalidLength { line: line.into() });
+        }
+        compressed.extend_from_slice(&decoded[..len]);
+    }
+
+    let mut data = vec![0; size];
+    let mut inflate = gix_features::zlib::Inflate::default();
+    let (status, _, produced) = inflate.once(&compressed, &mut data)?;
+    if status != gix_features::zlib::Status::StreamEnd || produced != size {
+        return Err(decode::Error::Size {
+            expected: size,
+            actual: produced,
+        });
+    }
+    match kind {
+        b"literal" => Ok(BinaryHunk::Literal(data)),
+        b"delta" => Ok(BinaryHunk::Delta(data)),
+        _ => Err(invalid_header()),
+    }
+}
+
+impl BinaryHunk {
+    /// Apply this hunk to `source` and return the result.
+    pub fn apply(&self, source: &[u8]) -> Result<Vec<u8>, delta::Error> {
+        match self {
+            BinaryHunk::Literal(data) => Ok(data.clone()),
+            BinaryHunk::Delta(delta) => delta::apply(source, delta),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Create and apply deltas in the format `git` uses in packs and binary patches.
+use std::collections::HashMap;
+
+/// The amount of bytes to match at least to consider copying them from the base.
+const BLOCK_SIZE: usize = 16;
+/// The maximum amount of bytes to copy with a single instruction.
+const MAX_COPY: usize = 0x10000;
+/// The maximum amount of bytes to insert with a single instruction.
+const MAX_INSERT: usize = 0x7f;
+
+/// The error returned by [`apply()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The delta expects a base of {expected} bytes, but it has {actual} bytes")]
+    BaseSize { expected: u64, actual: usize },
+    #[error("The delta is truncated or contains an invalid instruction")]
+    Corrupt,
+    #[error("The delta was supposed to produce {expected} bytes, but produced {actual} bytes")]
+    ResultSize { expected: u64, actual: usize },
+}
+
+/// Create a delta which turns `base` into `target` when [applied](apply()).
+pub fn create(base: &[u8], target: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode_size(base.len() as u64, &mut out);
+    encode_size(target.len() as u64, &mut out);
+
+    let mut blocks = HashMap::<&[u8], usize>::new();
+    for (idx, block) in base.chunks_exact(BLOCK_SIZE).enumerate() {
+        blocks.entry(block).or_insert(idx * BLOCK_SIZE);
+    }
+
+    let (mut pos, mut insert_start) = (0, 0);
+    while pos + BLOCK_SIZE <= target.len() {
+        let Some(&base_pos) = blocks.get(&target[pos..pos + BLOCK_SIZE]) else {
+            pos += 1;
+            continue;
+        };
+        let (mut base_start, mut start) = (base_pos, pos);
+        while start > insert_start && base_start > 0 && base[base_start - 1] == target[start - 1] {
+            base_start -= 1;
+            start -= 1;
+        }
+        let len = base[base_start..]
+            .iter()
+            .zip(&target[start..])
+            .take_while(|(a, b)| a == b)
+            .count();
+        push_insert(&target[insert_start..start], &mut out);
+        push_copy(base_start, len, &mut out);
+        pos = start + len;
+        insert_start = pos;
+    }
+    push_insert(&target[insert_start..], &mut out);
+    out
+}
+
+/// Apply `delta` to `base` and return the result.
+pub fn apply(base: &[u8], delta: &[u8]) -> Result<Vec<u8>, Error> {
+    let (base_size, mut delta) = decode_size(delta).ok_or(Error::Corrupt)?;
+    if base_size != base.len() as u64 {
+        return Err(Error::BaseSize {
+            expected: base_size,
+            actual: base.len(),
+        });
+    }
+    let (result_size, rest) = decode_size(delta).ok_or(Error::Corrupt)?;
+    delta = rest;
+
+    let mut out = Vec::with_capacity(result_size as usize);
+    while let Some((&cmd, rest)) = delta.split_first() {
+        delta = rest;
+        if cmd & 0x80 != 0 {
+            let mut next = |flag: u8, shift: u32| -> Result<usize, Error> {
+                if cmd & flag == 0 {
+                    return Ok(0);
+                }
+                let (&byte, rest) = delta.split_first().ok_or(Error::Corrupt)?;
+                delta = rest;
+                Ok((byte as usize) << shift)
+            };
+            let offset = next(0x01, 0)? | next(0x02, 8)? | next(0x04, 16)? | next(0x08, 24)?;
+            let size = match next(0x10, 0)? | next(0x20, 8)? | next(0x40, 16)? {
+                0 => MAX_COPY,
+                size => size,
+            };
+            out.extend_from_slice(base.get(offset..offset + size).ok_or(Error::Corrupt)?);
+        } else if cmd != 0 {
+            let data = delta.get(..cmd as usize).ok_or(Error::Corrupt)?;
+            out.extend_from_slice(data);
+            delta = &delta[cmd as usize..];
+        } else {
+            return Err(Error::Corrupt);
+        }
+    }
+    if out.len() as u64 != result_size {
+        return Err(Error::ResultSize {
+            expected: result_size,
+            actual: out.len(),
+        });
+    }
+    Ok(out)
+}
+
+fn encode_size(mut size: u64, out: &mut Vec<u8>) {
+    loop {
+        let byte = (size & 0x7f) as u8;
+        size >>= 7;
+        if size == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn decode_size(data: &[u8]) -> Option<(u64, &[u8])> {
+    let mut size = 0u64;
+    for (idx, byte) in data.iter().enumerate().take(10) {
+        size |= ((byte & 0x7f) as u64) << (idx * 7);
+        if byte & 0x80 == 0 {
+            return Some((size, &data[idx + 1..]));
+        }
+    }
+    None
+}
+
+fn push_insert(data: &[u8], out: &mut Vec<u8>) {
+    for chunk in data.chunks(MAX_INSERT) {
+        out.push(chunk.len() as u8);
+        out.extend_from_slice(chunk);
+    }
+}
+
+fn push_copy(mut offset: usize, mut len: usize, out: &mut Vec<u8>) {
+    while len > 0 {
+        let size = len.min(MAX_COPY);
+        let cmd_pos = out.len();
+        let mut cmd = 0x80u8;
+        out.push(cmd);
+        for (idx, byte) in (offset as u32).to_le_bytes().into_iter().enumerate() {
+            if byte != 0 {
+                cmd |= 1 << idx;
+                out.push(byte);
+            }
+        }
+        if size != MAX_COPY {
+            for (idx, byte) in (size as u32).to_le_bytes().into_iter().take(3).enumerate() {
+                if byte != 0 {
+                    cmd |= 0x10 << idx;
+                    out.push(byte);
+                }
+            }
+        }
+        out[cmd_pos] = cmd;
+        offset += size;
+        len -= size;
+    }
+}
@@ -0,0 +1,122 @@
+use std::ops::Range;
+
+use bstr::ByteSlice;
+use imara_diff::{intern::InternedInput, Algorithm};
+
+use crate::blob::sources::byte_lines_with_terminator;
+
+/// The names of the sides of a [three-way merge](three_way()) for use in conflict markers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Labels<'a> {
+    /// The name of the current version, shown after `<<<<<<<`.
+    pub ours: &'a str,
+    /// The name of the version to merge in, shown after `>>>>>>>`.
+    pub theirs: &'a str,
+}
+
+impl Default for Labels<'_> {
+    fn default() -> Self {
+        Labels {
+            ours: "ours",
+            theirs: "theirs",
+        }
+    }
+}
+
+/// The outcome of [`three_way()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The merged data, with conflict markers around conflicting changes.
+    pub data: Vec<u8>,
+    /// The amount of conflicts, each of which is surrounded by conflict markers in `data`.
+    pub num_conflicts: usize,
+}
+
+/// Merge the changes from `base` to `ours` and from `base` to `theirs` line by line and return the result.
+///
+/// Changes that touch the same or adjacent lines conflict unless they are identical, which is marked with
+/// `<<<<<<<`, `=======` and `>>>>>>>` lines using the given `labels`. This is used as fallback when a patch doesn't
+/// apply directly, similar to `git apply --3way`.
+pub fn three_way(base: &[u8], ours: &[u8], theirs: &[u8], labels: Labels<'_>, algorithm: Algorithm) -> Outcome {
+    let base_lines: Vec<&[u8]> = base.lines_with_terminator().collect();
+    let ours_lines: Vec<&[u8]> = ours.lines_with_terminator().collect();
+    let theirs_lines: Vec<&[u8]> = theirs.lines_with_terminator().collect();
+    let ours_changes = changes(base, ours, algorithm);
+    let theirs_changes = changes(base, theirs, algorithm);
+
+    let mut out = Vec::with_capacity(ours.len().max(theirs.len()));
+    let mut num_conflicts = 0;
+    let mut base_pos = 0;
+    let (mut ours_iter, mut theirs_iter) = (ours_changes.iter().peekable(), theirs_changes.iter().peekable());
+    // The difference in line numbers between `base` and each side, before the current group of changes.
+    let (mut ours_delta, mut theirs_delta) = (0i64, 0i64);
+
+    loop {
+        let start = match (ours_iter.peek(), theirs_iter.peek()) {
+            (None, None) => break,
+            (Some(ours), None) => ours.0.start,
+            (None, Some(theirs)) => theirs.0.start,
+            (Some(ours), Some(theirs)) => ours.0.start.min(theirs.0.start),
+        };
+        let (mut end, mut has_ours, mut has_theirs) = (start, false, false);
+        let (ours_delta_before, theirs_delta_before) = (ours_delta, theirs_delta);
+        loop {
+            if let Some((before, after)) = ours_iter.next_if(|(before, _)| before.start <= end) {
+                end = end.max(before.end);
+                ours_delta += after.len() as i64 - before.len() as i64;
+                has_ours = true;
+            } else if let Some((before, after)) = theirs_iter.next_if(|(before, _)| before.start <= end) {
+                end = end.max(before.end);
+                theirs_delta += after.len() as i64 - before.len() as i64;
+                has_theirs = true;
+            } else {
+                break;
+            }
+        }
+
+        for line in &base_lines[base_pos..start as usize] {
+            out.extend_from_slice(line);
+        }
+        let side = |lines: &[&[u8]], delta_before: i64, delta_after: i64| -> Vec<u8> {
+            lines[(start as i64 + delta_before) as usize..(end as i64 + delta_after) as usize].concat()
+        };
+        let ours_content = side(&ours_lines, ours_delta_before, ours_delta);
+        let theirs_content = side(&theirs_lines, theirs_delta_before, theirs_delta);
+        if !has_theirs || ours_content == theirs_content {
+            out.extend_from_slice(&ours_content);
+        } else if !has_ours {
+            out.extend_from_slice(&theirs_content);
+        } else {
+            num_conflicts += 1;
+            out.extend_from_slice(format!("<<<<<<< {}\n", labels.ours).as_bytes());
+            push_with_newline(&mut out, &ours_content);
+            out.extend_from_slice(b"=======\n");
+            push_with_newline(&mut out, &theirs_content);
+            out.extend_from_slice(format!(">>>>>>> {}\n", labels.theirs).as_bytes());
+        }
+        base_pos = end as usize;
+    }
+    for line in &base_lines[base_pos..] {
+        out.extend_from_slice(line);
+    }
+    Outcome {
+        data: out,
+        num_conflicts,
+    }
+}
+
+fn changes(before: &[u8], after: &[u8], algorithm: Algorithm) -> Vec<(Range<u32>, Range<u32>)> {
+    let input = InternedInput::new(byte_lines_with_terminator(before), byte_lines_with_terminator(after));
+    let mut changes = Vec::new();
+    imara_diff::diff(algorithm, &input, |before: Range<u32>, after: Range<u32>| {
+        changes.push((before, after))
+    });
+    changes
+}
+
+fn push_with_newline(out: &mut Vec<u8>, data: &[u8]) {
+    out.extend_from_slice(data);
+    if !data.is_empty() && !data.ends_with(b"\n") {
+        out.push(b'\n');
+    }
+}
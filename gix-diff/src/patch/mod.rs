@@ -0,0 +1,89 @@
+//! Parse patches as produced by `git diff` or `git format-patch`, produce and apply binary patches, and apply
+//! patches to buffers, similar to what `git apply` does with individual files.
+use std::ops::Range;
+
+use bstr::BString;
+
+use crate::blob::unified_diff::{header, LineKind};
+
+///
+#[allow(clippy::empty_docs)]
+pub mod apply;
+pub mod base85;
+///
+#[allow(clippy::empty_docs)]
+pub mod binary;
+pub mod delta;
+///
+#[allow(clippy::empty_docs)]
+pub mod merge;
+///
+#[allow(clippy::empty_docs)]
+pub mod parse;
+pub use parse::function::parse;
+
+/// The changes to a single file as parsed from a patch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct File {
+    /// The kind of change.
+    pub kind: header::Kind,
+    /// The path of the old version of the file, with the leading `a/` removed, or `None` if the file was added.
+    pub old_path: Option<BString>,
+    /// The path of the new version of the file, with the leading `b/` removed, or `None` if the file was deleted.
+    pub new_path: Option<BString>,
+    /// The mode of the old version of the file, if it was mentioned in the patch.
+    pub old_mode: Option<gix_object::tree::EntryKind>,
+    /// The mode of the new version of the file, if it was mentioned in the patch.
+    pub new_mode: Option<gix_object::tree::EntryKind>,
+    /// The possibly abbreviated hexadecimal id of the old version of the file, as found on the `index` line.
+    pub old_id: Option<BString>,
+    /// The possibly abbreviated hexadecimal id of the new version of the file, as found on the `index` line.
+    pub new_id: Option<BString>,
+    /// The hunks of textual changes, which may be empty for binary files, pure renames or mode changes.
+    pub hunks: Vec<Hunk>,
+    /// The binary patch if the file is binary and the patch contains the data to apply it.
+    pub binary: Option<Binary>,
+    /// `true` if the patch only states that binary files differ, without the data needed to apply it.
+    pub is_binary_without_data: bool,
+}
+
+impl File {
+    /// Return the path of the file in its old version, or its new version if it was added.
+    pub fn path(&self) -> &BString {
+        self.old_path
+            .as_ref()
+            .or(self.new_path.as_ref())
+            .expect("parsing assures that at least one path is set")
+    }
+}
+
+/// A hunk of textual changes to a file, as parsed from a patch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// The zero-based range of lines in the old version of the file that this hunk covers, including context lines.
+    pub before: Range<u32>,
+    /// The zero-based range of lines in the new version of the file that this hunk covers, including context lines.
+    pub after: Range<u32>,
+    /// The function name shown after the `@@ … @@` header, if present.
+    pub function_name: Option<BString>,
+    /// All lines of the hunk, including their line terminator unless they were marked with `\ No newline at end of file`.
+    pub lines: Vec<(LineKind, BString)>,
+}
+
+/// A binary patch as written by `git diff --binary`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binary {
+    /// The change to turn the old version into the new version.
+    pub forward: BinaryHunk,
+    /// The change to turn the new version into the old version, if present.
+    pub reverse: Option<BinaryHunk>,
+}
+
+/// A single change to a binary file, with its data already decoded and decompressed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BinaryHunk {
+    /// The complete content of the resulting file.
+    Literal(Vec<u8>),
+    /// A [delta](delta::apply()) to apply to the source file to obtain the resulting file.
+    Delta(Vec<u8>),
+}
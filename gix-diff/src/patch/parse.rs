@@ -0,0 +1,404 @@
+use bstr::BString;
+
+/// The error returned by [`parse()`](crate::patch::parse()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Line {line_number}: could not parse hunk header {line:?}")]
+    InvalidHunkHeader { line_number: usize, line: BString },
+    #[error("Line {line_number}: found hunk before the file it belongs to")]
+    HunkWithoutFile { line_number: usize },
+    #[error("Line {line_number}: the hunk ended prematurely or contained an invalid line")]
+    TruncatedHunk { line_number: usize },
+    #[error("Line {line_number}: invalid file mode in {line:?}")]
+    InvalidMode { line_number: usize, line: BString },
+    #[error("Line {line_number}: could not decode binary patch")]
+    Binary {
+        line_number: usize,
+        source: crate::patch::binary::decode::Error,
+    },
+    #[error("Line {line_number}: could not determine the path of the file")]
+    MissingPath { line_number: usize },
+}
+
+pub(super) mod function {
+    use bstr::{BString, ByteSlice};
+    use gix_object::tree::EntryKind;
+
+    use super::Error;
+    use crate::{
+        blob::unified_diff::{header::Kind, LineKind},
+        patch::{binary, Binary, File, Hunk},
+    };
+
+    /// Parse all file changes from `data`, which typically is the output of `git diff` or `git format-patch`, but may also
+    /// be a plain unified diff.
+    ///
+    /// Everything that isn't part of a file change, like commit messages, is ignored. Paths have their first component,
+    /// typically `a/` or `b/`, removed, like `git apply -p1` would.
+    pub fn parse(data: &[u8]) -> Result<Vec<File>, Error> {
+        let mut lines = data.lines_with_terminator().enumerate().peekable();
+        let mut files = Vec::new();
+        let mut current: Option<File> = None;
+        let mut in_header = false;
+        let mut header_line_number = 0;
+
+        while let Some((idx, line)) = lines.next() {
+            let line_number = idx + 1;
+            let content = trim_newline(line);
+            if let Some(paths) = content.strip_prefix(b"diff --git ") {
+                files.extend(finish(current.take(), header_line_number)?);
+                let (old, new) = split_git_paths(paths);
+                current = Some(new_file(Some(old), Some(new)));
+                in_header = true;
+                header_line_number = line_number;
+                continue;
+            }
+            if content.starts_with(b"--- ") && lines.peek().map_or(false, |(_, next)| next.starts_with(b"+++ ")) {
+                let (_, next) = lines.next().expect("peeked");
+                let old = parse_file_name(&content[4..]);
+                let new = parse_file_name(&trim_newline(next)[4..]);
+                match current.as_mut().filter(|_| in_header) {
+                    Some(file) => {
+                        if !matches!(file.kind, Kind::Rename { .. } | Kind::Copy { .. }) {
+                            file.old_path = old;
+                            file.new_path = new;
+                        }
+                    }
+                    None => {
+                        files.extend(finish(current.take(), header_line_number)?);
+                        let mut file = new_file(old, new);
+                        file.kind = match (&file.old_path, &file.new_path) {
+                            (None, _) => Kind::Addition,
+                            (_, None) => Kind::Deletion,
+                            _ => Kind::Modification,
+                        };
+                        current = Some(file);
+                        header_line_number = line_number;
+                    }
+                }
+                in_header = false;
+                continue;
+            }
+            if content.starts_with(b"@@ -") {
+                let file = current.as_mut().ok_or(Error::HunkWithoutFile { line_number })?;
+                file.hunks.push(parse_hunk(content, line_number, &mut lines)?);
+                in_header = false;
+                continue;
+            }
+            let Some(file) = current.as_mut().filter(|_| in_header) else {
+                continue;
+            };
+
+            let mode = |value: &[u8]| {
+                parse_mode(value).ok_or_else(|| Error::InvalidMode {
+                    line_number,
+                    line: content.into(),
+                })
+            };
+            if let Some(value) = content.strip_prefix(b"old mode ") {
+                file.old_mode = Some(mode(value)?);
+            } else if let Some(value) = content.strip_prefix(b"new mode ") {
+                file.new_mode = Some(mode(value)?);
+            } else if let Some(value) = content.strip_prefix(b"deleted file mode ") {
+                file.kind = Kind::Deletion;
+                file.old_mode = Some(mode(value)?);
+                file.new_path = None;
+            } else if let Some(value) = content.strip_prefix(b"new file mode ") {
+                file.kind = Kind::Addition;
+                file.new_mode = Some(mode(value)?);
+                file.old_path = None;
+            } else if let Some(value) = content.strip_prefix(b"similarity index ") {
+                let similarity = value
+                    .strip_suffix(b"%")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(100);
+                file.kind = match file.kind {
+                    Kind::Copy { .. } => Kind::Copy { similarity },
+                    _ => Kind::Rename { similarity },
+                };
+            } else if let Some(path) = content
+                .strip_prefix(b"rename from ")
+                .or_else(|| content.strip_prefix(b"rename old "))
+            {
+                file.kind = Kind::Rename {
+                    similarity: similarity_of(file.kind),
+                };
+                file.old_path = Some(unquote(path));
+            } else if let Some(path) = content
+                .strip_prefix(b"rename to ")
+                .or_else(|| content.strip_prefix(b"rename new "))
+            {
+                file.kind = Kind::Rename {
+                    similarity: similarity_of(file.kind),
+                };
+                file.new_path = Some(unquote(path));
+            } else if let Some(path) = content.strip_prefix(b"copy from ") {
+                file.kind = Kind::Copy {
+                    similarity: similarity_of(file.kind),
+                };
+                file.old_path = Some(unquote(path));
+            } else if let Some(path) = content.strip_prefix(b"copy to ") {
+                file.kind = Kind::Copy {
+                    similarity: similarity_of(file.kind),
+                };
+                file.new_path = Some(unquote(path));
+            } else if let Some(value) = content.strip_prefix(b"index ") {
+                let (ids, index_mode) = value
+                    .find_byte(b' ')
+                    .map_or((value, None), |pos| (&value[..pos], Some(&value[pos + 1..])));
+                if let Some(pos) = ids.find(b"..") {
+                    file.old_id = Some(ids[..pos].into());
+                    file.new_id = Some(ids[pos + 2..].into());
+                }
+                if let Some(index_mode) = index_mode {
+                    let index_mode = mode(index_mode)?;
+                    file.old_mode.get_or_insert(index_mode);
+                    file.new_mode.get_or_insert(index_mode);
+                }
+            } else if content.starts_with(b"Binary files ") && content.ends_with(b" differ") {
+                file.is_binary_without_data = true;
+                in_header = false;
+            } else if content == b"GIT binary patch" {
+                let forward = parse_binary_hunk(&mut lines)?.ok_or(Error::TruncatedHunk { line_number })?;
+                let reverse = parse_binary_hunk(&mut lines)?;
+                file.binary = Some(Binary { forward, reverse });
+                in_header = false;
+            }
+        }
+        files.extend(finish(current, header_line_number)?);
+        Ok(files)
+    }
+
+    type Lines<'a> = std::iter::Peekable<std::iter::Enumerate<bstr::LinesWithTerminator<'a>>>;
+
+    fn new_file(old_path: Option<BString>, new_path: Option<BString>) -> File {
+        File {
+            kind: Kind::Modification,
+            old_path,
+            new_path,
+            old_mode: None,
+            new_mode: None,
+            old_id: None,
+            new_id: None,
+            hunks: Vec::new(),
+            binary: None,
+            is_binary_without_data: false,
+        }
+    }
+
+    fn finish(file: Option<File>, line_number: usize) -> Result<Option<File>, Error> {
+        match file {
+            Some(file) if file.old_path.is_none() && file.new_path.is_none() => Err(Error::MissingPath { line_number }),
+            file => Ok(file),
+        }
+    }
+
+    fn similarity_of(kind: Kind) -> u8 {
+        match kind {
+            Kind::Rename { similarity } | Kind::Copy { similarity } => similarity,
+            _ => 100,
+        }
+    }
+
+    fn parse_hunk(header: &[u8], line_number: usize, lines: &mut Lines<'_>) -> Result<Hunk, Error> {
+        let invalid = || Error::InvalidHunkHeader {
+            line_number,
+            line: header.into(),
+        };
+        let rest = &header[b"@@ -".len()..];
+        let end = rest.find(b" @@").ok_or_else(invalid)?;
+        let (ranges, function_name) = (&rest[..end], &rest[end + 3..]);
+        let (before, after) = ranges
+            .find(b" +")
+            .map(|pos| (&ranges[..pos], &ranges[pos + 2..]))
+            .ok_or_else(invalid)?;
+        let before = parse_range(before).ok_or_else(invalid)?;
+        let after = parse_range(after).ok_or_else(invalid)?;
+        let function_name = function_name.strip_prefix(b" ").map(Into::into);
+
+        let (mut remaining_before, mut remaining_after) = (before.len(), after.len());
+        let mut hunk_lines: Vec<(LineKind, BString)> = Vec::new();
+        while remaining_before > 0 || remaining_after > 0 {
+            let (idx, line) = lines.next().ok_or(Error::TruncatedHunk { line_number })?;
+            let truncated = || Error::TruncatedHunk { line_number: idx + 1 };
+            let (kind, content) = match line.first() {
+                Some(b' ') => (LineKind::Context, &line[1..]),
+                Some(b'-') => (LineKind::Remove, &line[1..]),
+                Some(b'+') => (LineKind::Add, &line[1..]),
+                // Some tools strip trailing whitespace, which turns empty context lines into empty lines.
+                Some(b'\n' | b'\r') => (LineKind::Context, line),
+                Some(b'\\') => {
+                    strip_newline(&mut hunk_lines);
+                    continue;
+                }
+                _ => return Err(truncated()),
+            };
+            match kind {
+                LineKind::Context if remaining_before > 0 && remaining_after > 0 => {
+                    remaining_before -= 1;
+                    remaining_after -= 1;
+                }
+                LineKind::Remove if remaining_before > 0 => remaining_before -= 1,
+                LineKind::Add if remaining_after > 0 => remaining_after -= 1,
+                _ => return Err(truncated()),
+            }
+            hunk_lines.push((kind, content.into()));
+        }
+        if lines.peek().map_or(false, |(_, line)| line.starts_with(b"\\")) {
+            lines.next();
+            strip_newline(&mut hunk_lines);
+        }
+        Ok(Hunk {
+            before,
+            after,
+            function_name,
+            lines: hunk_lines,
+        })
+    }
+
+    fn strip_newline(lines: &mut [(LineKind, BString)]) {
+        if let Some((_, line)) = lines.last_mut() {
+            if line.ends_with(b"\n") {
+                line.pop();
+            }
+        }
+    }
+
+    /// Parse `<start>[,<len>]` into a zero-based range.
+    fn parse_range(range: &[u8]) -> Option<std::ops::Range<u32>> {
+        let (start, len) = match range.find_byte(b',') {
+            Some(pos) => (&range[..pos], Some(&range[pos + 1..])),
+            None => (range, None),
+        };
+        let start: u32 = start.to_str().ok()?.parse().ok()?;
+        let len: u32 = match len {
+            Some(len) => len.to_str().ok()?.parse().ok()?,
+            None => 1,
+        };
+        let start = if len == 0 { start } else { start.checked_sub(1)? };
+        Some(start..start + len)
+    }
+
+    fn parse_binary_hunk(lines: &mut Lines<'_>) -> Result<Option<crate::patch::BinaryHunk>, Error> {
+        let Some((idx, header)) =
+            lines.next_if(|(_, line)| line.starts_with(b"literal ") || line.starts_with(b"delta "))
+        else {
+            return Ok(None);
+        };
+        let mut data = Vec::new();
+        for (_, line) in lines.by_ref() {
+            let line = trim_newline(line);
+            if line.is_empty() {
+                break;
+            }
+            data.push(line);
+        }
+        binary::decode(trim_newline(header), data)
+            .map(Some)
+            .map_err(|source| Error::Binary {
+                line_number: idx + 1,
+                source,
+            })
+    }
+
+    fn parse_mode(mode: &[u8]) -> Option<EntryKind> {
+        let mode = u32::from_str_radix(mode.trim().to_str().ok()?, 8).ok()?;
+        let kind = gix_object::tree::EntryMode::try_from(mode).ok()?.kind();
+        (kind != EntryKind::Tree).then_some(kind)
+    }
+
+    /// Split the paths of a `diff --git a/<old> b/<new>` line, which is ambiguous if paths contain spaces and aren't quoted.
+    fn split_git_paths(paths: &[u8]) -> (BString, BString) {
+        if paths.starts_with(b"\"") {
+            let (old, rest) = parse_quoted(paths);
+            return (strip_component(old), strip_component(unquote(rest.trim_start())));
+        }
+        let candidates: Vec<_> = paths.find_iter(b" ").collect();
+        let split = |pos: usize| (&paths[..pos], &paths[pos + 1..]);
+        let (old, new) = candidates
+            .iter()
+            .map(|pos| split(*pos))
+            .find(|(old, new)| strip_component((*old).into()) == strip_component(unquote(new)))
+            .or_else(|| {
+                candidates
+                    .iter()
+                    .map(|pos| split(*pos))
+                    .find(|(_, new)| new.starts_with(b"b/") || new.starts_with(b"\""))
+            })
+            .unwrap_or((paths, paths));
+        (strip_component(old.into()), strip_component(unquote(new)))
+    }
+
+    fn parse_file_name(name: &[u8]) -> Option<BString> {
+        let name = if name.starts_with(b"\"") {
+            parse_quoted(name).0
+        } else {
+            name.find_byte(b'\t').map_or(name, |pos| &name[..pos]).into()
+        };
+        (name != "/dev/null").then(|| strip_component(name))
+    }
+
+    /// Remove the first path component, like `a/` or `b/`.
+    fn strip_component(path: BString) -> BString {
+        match path.find_byte(b'/') {
+            Some(pos) => path[pos + 1..].into(),
+            None => path,
+        }
+    }
+
+    fn unquote(path: &[u8]) -> BString {
+        if path.starts_with(b"\"") {
+            parse_quoted(path).0
+        } else {
+            path.into()
+        }
+    }
+
+    /// Parse a C-style quoted string at the beginning of `input` and return it along with the remaining input.
+    fn parse_quoted(input: &[u8]) -> (BString, &[u8]) {
+        let mut out = BString::default();
+        let mut pos = 1;
+        while let Some(&byte) = input.get(pos) {
+            pos += 1;
+            match byte {
+                b'"' => return (out, &input[pos..]),
+                b'\\' => {
+                    let Some(&escaped) = input.get(pos) else { break };
+                    pos += 1;
+                    out.push(match escaped {
+                        b'a' => 0x07,
+                        b'b' => 0x08,
+                        b'f' => 0x0c,
+                        b'n' => b'\n',
+                        b'r' => b'\r',
+                        b't' => b'\t',
+                        b'v' => 0x0b,
+                        b'0'..=b'3' => {
+                            let digits = input.get(pos - 1..pos + 2).unwrap_or_default();
+                            match std::str::from_utf8(digits)
+                                .ok()
+                                .and_then(|d| u8::from_str_radix(d, 8).ok())
+                            {
+                                Some(value) => {
+                                    pos += 2;
+                                    value
+                                }
+                                None => escaped,
+                            }
+                        }
+                        other => other,
+                    });
+                }
+                other => out.push(other),
+            }
+        }
+        (out, &input[input.len()..])
+    }
+
+    fn trim_newline(line: &[u8]) -> &[u8] {
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        line.strip_suffix(b"\r").unwrap_or(line)
+    }
+}
@@ -1,2 +1,3 @@
 pub(crate) mod pipeline;
 mod platform;
+mod unified_diff;
@@ -0,0 +1,359 @@
+use gix_diff::blob::{
+    intern::InternedInput,
+    sources::byte_lines_with_terminator,
+    stat,
+    unified_diff::{header, ContextSize, FunctionName, Header},
+    word_diff, Algorithm, UnifiedDiff,
+};
+use gix_object::{bstr::ByteSlice, tree::EntryKind};
+
+use crate::hex_to_id;
+
+const OLD: &str = "fn a() {\n  1\n  2\n  3\n  4\n  5\n}\n\nfn b() {\n  1\n  2\n  3\n  4\n  5\n  6\n  7\n  8\n}\n";
+const NEW: &str =
+    "fn a() {\n  1\n  two\n  3\n  4\n  5\n}\n\nfn b() {\n  1\n  two\n  3\n  4\n  5\n  6\n  seven\n  8\n}\ntail";
+
+fn unified_diff(old: &str, new: &str, context_size: u32, function_name: Option<&FunctionName>) -> String {
+    let input = InternedInput::new(
+        byte_lines_with_terminator(old.as_bytes()),
+        byte_lines_with_terminator(new.as_bytes()),
+    );
+    let mut sink = UnifiedDiff::new(&input, ContextSize::symmetrical(context_size));
+    if let Some(function_name) = function_name {
+        sink = sink.with_function_name(function_name);
+    }
+    let mut out = Vec::new();
+    for hunk in gix_diff::blob::diff(Algorithm::Myers, &input, sink) {
+        hunk.write_to(&mut out).unwrap();
+    }
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn hunks_with_default_context_and_missing_newline_at_end_of_file() {
+    assert_eq!(
+        unified_diff(OLD, NEW, 3, Some(&FunctionName::Default)),
+        r"@@ -1,6 +1,6 @@
+ fn a() {
+   1
+-  2
++  two
+   3
+   4
+   5
+@@ -8,11 +8,12 @@ fn a() {
+ 
+ fn b() {
+   1
+-  2
++  two
+   3
+   4
+   5
+   6
+-  7
++  seven
+   8
+ }
++tail
+\ No newline at end of file
+",
+        "the output matches `git diff` exactly, including the function name which is searched before the hunk"
+    );
+}
+
+#[test]
+fn hunks_with_less_context() {
+    assert_eq!(
+        unified_diff(OLD, NEW, 1, Some(&FunctionName::Default)),
+        r"@@ -2,3 +2,3 @@ fn a() {
+   1
+-  2
++  two
+   3
+@@ -10,3 +10,3 @@ fn b() {
+   1
+-  2
++  two
+   3
+@@ -15,4 +15,5 @@ fn b() {
+   6
+-  7
++  seven
+   8
+ }
++tail
+\ No newline at end of file
+"
+    );
+
+    assert_eq!(
+        unified_diff(OLD, NEW, 0, None),
+        r"@@ -3 +3 @@
+-  2
++  two
+@@ -11 +11 @@
+-  2
++  two
+@@ -16 +16 @@
+-  7
++  seven
+@@ -18,0 +19 @@
++tail
+\ No newline at end of file
+",
+        "empty ranges refer to the line before, and function names are only shown if configured"
+    );
+}
+
+#[test]
+fn hunk_ranges_and_lines() {
+    let input = InternedInput::new(
+        byte_lines_with_terminator(b"a\nb\n"),
+        byte_lines_with_terminator(b"a\nc\nb\n"),
+    );
+    let hunks = gix_diff::blob::diff(
+        Algorithm::Histogram,
+        &input,
+        UnifiedDiff::new(&input, ContextSize::default()),
+    );
+    assert_eq!(hunks.len(), 1);
+    let hunk = &hunks[0];
+    assert_eq!(hunk.before, 0..2);
+    assert_eq!(hunk.after, 0..3);
+    assert_eq!(hunk.function_name, None);
+    assert_eq!(
+        hunk.lines
+            .iter()
+            .map(|(kind, line)| (kind.to_prefix() as char, line.to_str().unwrap()))
+            .collect::<Vec<_>>(),
+        [(' ', "a\n"), ('+', "c\n"), (' ', "b\n")]
+    );
+}
+
+#[test]
+fn function_names_from_xfuncname_patterns() -> crate::Result {
+    let patterns = FunctionName::from_driver(Some(&gix_diff::blob::Driver {
+        name: "custom".into(),
+        xfuncname: Some("!^fn a\n^fn ([a-z]+)".into()),
+        ..Default::default()
+    }))?;
+    assert_eq!(
+        unified_diff(OLD, NEW, 1, Some(&patterns)),
+        r"@@ -2,3 +2,3 @@
+   1
+-  2
++  two
+   3
+@@ -10,3 +10,3 @@ b
+   1
+-  2
++  two
+   3
+@@ -15,4 +15,5 @@ b
+   6
+-  7
++  seven
+   8
+ }
++tail
+\ No newline at end of file
+",
+        "negated patterns prevent matches, and the first capture group is used if present"
+    );
+
+    assert!(
+        matches!(FunctionName::from_driver(None)?, FunctionName::Default),
+        "without driver, the default is used"
+    );
+    assert!(
+        FunctionName::from_driver(Some(&gix_diff::blob::Driver {
+            xfuncname: Some("(".into()),
+            ..Default::default()
+        }))
+        .is_err(),
+        "invalid patterns are detected early"
+    );
+    Ok(())
+}
+
+#[test]
+fn default_function_names() {
+    let name = FunctionName::Default;
+    assert_eq!(name.find(b"fn main() {  \r\n"), Some("fn main() {".into()));
+    assert_eq!(name.find(b"$var = 1\n"), Some("$var = 1".into()));
+    assert_eq!(name.find(b"_private:"), Some("_private:".into()));
+    assert_eq!(name.find(b"  indented\n"), None);
+    assert_eq!(name.find(b"}\n"), None);
+    assert_eq!(
+        name.find(&[b'a'; 100]).map(|name| name.len()),
+        Some(80),
+        "names are truncated"
+    );
+}
+
+#[test]
+fn headers() -> crate::Result {
+    let old_id = hex_to_id("787710f000000000000000000000000000000000");
+    let new_id = hex_to_id("e91008d000000000000000000000000000000000");
+    let modification = Header::modification("file".into(), (&old_id, EntryKind::Blob), (&new_id, EntryKind::Blob));
+
+    let write = |header: Header<'_>| -> std::io::Result<String> {
+        let mut out = Vec::new();
+        header.write_to(&mut out)?;
+        header.write_file_names_to(&mut out)?;
+        Ok(String::from_utf8(out).expect("valid UTF-8"))
+    };
+
+    assert_eq!(
+        write(modification)?,
+        "diff --git a/file b/file\nindex 787710f..e91008d 100644\n--- a/file\n+++ b/file\n"
+    );
+    assert_eq!(
+        write(Header {
+            new_mode: EntryKind::BlobExecutable,
+            hex_len: 10,
+            ..modification
+        })?,
+        "diff --git a/file b/file\nold mode 100644\nnew mode 100755\nindex 787710f000..e91008d000\n--- a/file\n+++ b/file\n"
+    );
+    assert_eq!(
+        write(Header {
+            kind: header::Kind::Addition,
+            ..modification
+        })?,
+        "diff --git a/file b/file\nnew file mode 100644\nindex 0000000..e91008d\n--- /dev/null\n+++ b/file\n"
+    );
+    assert_eq!(
+        write(Header {
+            kind: header::Kind::Deletion,
+            old_mode: EntryKind::Link,
+            ..modification
+        })?,
+        "diff --git a/file b/file\ndeleted file mode 120000\nindex 787710f..0000000\n--- a/file\n+++ /dev/null\n"
+    );
+    assert_eq!(
+        write(Header {
+            kind: header::Kind::Rename { similarity: 90 },
+            new_path: "dir/file".into(),
+            ..modification
+        })?,
+        "diff --git a/file b/dir/file\nsimilarity index 90%\nrename from file\nrename to dir/file\nindex 787710f..e91008d 100644\n--- a/file\n+++ b/dir/file\n"
+    );
+
+    let exact_copy = Header {
+        kind: header::Kind::Copy { similarity: 100 },
+        new_path: "copy".into(),
+        new_id: &old_id,
+        ..modification
+    };
+    assert!(!exact_copy.has_content_change());
+    let mut out = Vec::new();
+    exact_copy.write_to(&mut out)?;
+    assert_eq!(
+        out.as_bstr(),
+        "diff --git a/file b/copy\nsimilarity index 100%\ncopy from file\ncopy to copy\n",
+        "without content change there is no index line"
+    );
+
+    out.clear();
+    modification.write_binary_files_differ_to(&mut out)?;
+    assert_eq!(out.as_bstr(), "Binary files a/file and b/file differ\n");
+    Ok(())
+}
+
+#[test]
+fn rename_paths() {
+    assert_eq!(stat::rename_path("small".into(), "smaller".into()), "small => smaller");
+    assert_eq!(
+        stat::rename_path("dir/sub/big".into(), "dir/sub/bigger".into()),
+        "dir/sub/{big => bigger}"
+    );
+    assert_eq!(
+        stat::rename_path("dir/sub/big".into(), "bigger".into()),
+        "dir/sub/big => bigger"
+    );
+    assert_eq!(stat::rename_path("a/b/c/d".into(), "a/x/c/d".into()), "a/{b => x}/c/d");
+    assert_eq!(stat::rename_path("a/file".into(), "file".into()), "a/file => file");
+    assert_eq!(stat::rename_path("file".into(), "b/file".into()), "file => b/file");
+}
+
+fn stat_entries() -> Vec<stat::Entry> {
+    vec![
+        stat::Entry {
+            path: "b.bin".into(),
+            counts: stat::Counts::Binary { before: 4, after: 9 },
+        },
+        stat::Entry {
+            path: stat::rename_path("dir/sub/big".into(), "dir/sub/bigger".into()),
+            counts: stat::Counts::Lines {
+                insertions: 100,
+                removals: 1,
+            },
+        },
+        stat::Entry {
+            path: "small => smaller".into(),
+            counts: stat::Counts::Lines {
+                insertions: 2,
+                removals: 0,
+            },
+        },
+    ]
+}
+
+#[test]
+fn stat_and_numstat() -> crate::Result {
+    let entries = stat_entries();
+    let mut out = Vec::new();
+    stat::write_numstat_to(&entries, &mut out)?;
+    assert_eq!(
+        out.as_bstr(),
+        "-\t-\tb.bin\n100\t1\tdir/sub/{big => bigger}\n2\t0\tsmall => smaller\n"
+    );
+
+    out.clear();
+    stat::write_stat_to(&entries, 80, &mut out)?;
+    assert_eq!(
+        out.as_bstr(),
+        r" b.bin                   | Bin 4 -> 9 bytes
+ dir/sub/{big => bigger} | 101 +++++++++++++++++++++++++++++++++++++++++++++++-
+ small => smaller        |   2 +
+ 3 files changed, 102 insertions(+), 1 deletion(-)
+",
+        "the graph is scaled to fit the width, like `git diff --stat`"
+    );
+
+    out.clear();
+    stat::write_stat_to(&entries, 40, &mut out)?;
+    assert_eq!(
+        out.as_bstr(),
+        r" b.bin                   | Bin 4 -> 9 bytes
+ dir/sub/{big => bigger} | 101 +++++++-
+ small => smaller        |   2 +
+ 3 files changed, 102 insertions(+), 1 deletion(-)
+"
+    );
+
+    out.clear();
+    stat::write_stat_to(&entries[2..], 80, &mut out)?;
+    assert_eq!(
+        out.as_bstr(),
+        " small => smaller | 2 ++\n 1 file changed, 2 insertions(+)\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn word_diff() -> std::io::Result<()> {
+    let mut out = Vec::new();
+    word_diff::write_to(b"a x b c\n", b"a b d e\n", Algorithm::Myers, &mut out)?;
+    assert_eq!(out.as_bstr(), "a[-x-] b [-c-]{+d e+}\n", "this is what git produces");
+
+    out.clear();
+    word_diff::write_to(b"one  two", b"zero one two three", Algorithm::Myers, &mut out)?;
+    assert_eq!(out.as_bstr(), "{+zero+} one two {+three+}");
+
+    let words: Vec<_> = word_diff::Words::new(b" a\tbc \n").ranges().collect();
+    assert_eq!(words, [1..2, 3..5]);
+    Ok(())
+}
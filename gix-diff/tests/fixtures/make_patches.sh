@@ -0,0 +1,29 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  seq 1 20 > numbers
+  printf 'no newline' > no-newline
+  for _ in $(seq 1 100); do printf 'binary\0data'; done > binary
+  seq 1 50 > to-rename
+  echo deleted > deleted
+  echo mode > mode
+  git add . && git commit -q -m base
+
+  { seq 1 4; echo five; seq 6 14; echo fifteen; seq 16 20; } > numbers
+  printf 'no newline, changed' > no-newline
+  for _ in $(seq 1 100); do printf 'binary\0data'; done > binary
+  printf 'more' >> binary
+  git mv to-rename renamed && echo 51 >> renamed
+  git rm -q deleted
+  chmod +x mode
+  echo added > added
+  git add -A && git commit -q -m change
+
+  git diff --binary -M HEAD~1 HEAD > ../changes.patch
+  git format-patch -1 --stdout --binary -M > ../changes.mbox
+  mkdir ../before ../after
+  git archive HEAD~1 | tar -x -C ../before
+  git archive HEAD | tar -x -C ../after
+)
@@ -0,0 +1,312 @@
+use std::path::Path;
+
+use gix_diff::{
+    blob::unified_diff::{header::Kind, LineKind},
+    patch::{self, apply, merge, BinaryHunk},
+};
+use gix_object::{
+    bstr::{BString, ByteSlice},
+    tree::EntryKind,
+};
+
+fn fixture() -> crate::Result<std::path::PathBuf> {
+    gix_testtools::scripted_fixture_read_only_standalone("make_patches.sh")
+}
+
+fn apply_all(root: &Path, files: &[patch::File]) -> crate::Result {
+    for file in files {
+        let old = match &file.old_path {
+            Some(path) => std::fs::read(root.join("before").join(gix_path::from_bstr(path.as_bstr())))?,
+            None => Vec::new(),
+        };
+        let new = match &file.binary {
+            Some(binary) => apply::binary(&old, binary, false)?,
+            None => {
+                let outcome = apply::hunks(&old, &file.hunks, Default::default());
+                assert!(outcome.is_complete(), "{:?} applies cleanly", file.path());
+                outcome.data
+            }
+        };
+        match &file.new_path {
+            Some(path) => assert_eq!(
+                new.as_bstr(),
+                std::fs::read(root.join("after").join(gix_path::from_bstr(path.as_bstr())))?.as_bstr(),
+                "{path}: the result matches what git produced"
+            ),
+            None => assert!(new.is_empty(), "deleted files are empty after the patch"),
+        }
+
+        if let Some(binary) = &file.binary {
+            assert_eq!(
+                apply::binary(&new, binary, true)?,
+                old,
+                "git writes reverse hunks as well"
+            );
+        } else {
+            let outcome = apply::hunks(
+                &new,
+                &file.hunks,
+                apply::Options {
+                    reverse: true,
+                    ..Default::default()
+                },
+            );
+            assert!(outcome.is_complete());
+            assert_eq!(outcome.data, old, "patches can be applied in reverse");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn parse_and_apply_git_diff_output() -> crate::Result {
+    let root = fixture()?;
+    for patch_file in ["changes.patch", "changes.mbox"] {
+        let files = patch::parse(&std::fs::read(root.join(patch_file))?)?;
+        assert_eq!(
+            files
+                .iter()
+                .map(|f| (
+                    f.kind,
+                    f.old_path.as_ref().map(ToString::to_string),
+                    f.new_path.as_ref().map(ToString::to_string)
+                ))
+                .collect::<Vec<_>>(),
+            [
+                (Kind::Addition, None, Some("added".into())),
+                (Kind::Modification, Some("binary".into()), Some("binary".into())),
+                (Kind::Deletion, Some("deleted".into()), None),
+                (Kind::Modification, Some("mode".into()), Some("mode".into())),
+                (Kind::Modification, Some("no-newline".into()), Some("no-newline".into())),
+                (Kind::Modification, Some("numbers".into()), Some("numbers".into())),
+                (
+                    Kind::Rename { similarity: 97 },
+                    Some("to-rename".into()),
+                    Some("renamed".into())
+                ),
+            ],
+            "{patch_file}: mails are parsed as well, ignoring the message"
+        );
+        let mode = &files[3];
+        assert_eq!(mode.old_mode, Some(EntryKind::Blob));
+        assert_eq!(mode.new_mode, Some(EntryKind::BlobExecutable));
+        assert!(mode.hunks.is_empty());
+        assert_eq!(files[0].new_mode, Some(EntryKind::Blob));
+        assert_eq!(files[0].old_id.as_ref().map(|id| id.len()), Some(7));
+        assert!(
+            matches!(files[1].binary.as_ref().map(|b| &b.forward), Some(BinaryHunk::Delta(_))),
+            "small changes to binary files are deltas"
+        );
+        assert_eq!(files[5].hunks.len(), 2);
+        assert_eq!(files[5].hunks[1].function_name.as_ref().map(ToString::to_string), None);
+
+        apply_all(&root, &files)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn no_newline_at_end_of_file() -> crate::Result {
+    let files = patch::parse(b"--- a/file\n+++ b/file\n@@ -1 +1,2 @@\n-a\n\\ No newline at end of file\n+a\n+b\n\\ No newline at end of file\n")?;
+    assert_eq!(files.len(), 1, "plain unified diffs are supported as well");
+    assert_eq!(
+        files[0].hunks[0].lines,
+        [
+            (LineKind::Remove, BString::from("a")),
+            (LineKind::Add, "a\n".into()),
+            (LineKind::Add, "b".into())
+        ]
+    );
+    let outcome = apply::hunks(b"a", &files[0].hunks, Default::default());
+    assert_eq!(outcome.data.as_bstr(), "a\nb");
+    Ok(())
+}
+
+fn numbers(range: std::ops::RangeInclusive<u32>) -> String {
+    range.map(|n| format!("{n}\n")).collect()
+}
+
+const PATCH: &str = "diff --git a/n b/n
+--- a/n
++++ b/n
+@@ -3,5 +3,5 @@ fn
+ 3
+ 4
+-5
++five
+ 6
+ 7
+@@ -13,3 +13,3 @@
+ 13
+-14
++fourteen
+ 15
+";
+
+#[test]
+fn offsets_fuzz_and_rejected_hunks() -> crate::Result {
+    let files = patch::parse(PATCH.as_bytes())?;
+    let hunks = &files[0].hunks;
+    assert_eq!(
+        hunks[0].function_name.as_ref().map(ToString::to_string),
+        Some("fn".into())
+    );
+    assert_eq!(hunks[0].before, 2..7);
+
+    let input = format!("0\n-1\n{}", numbers(1..=20));
+    let outcome = apply::hunks(input.as_bytes(), hunks, Default::default());
+    assert_eq!(
+        outcome.hunks,
+        [
+            apply::HunkOutcome::Applied { offset: 2, fuzz: 0 },
+            apply::HunkOutcome::Applied { offset: 2, fuzz: 0 }
+        ],
+        "hunks are found even if the file has additional lines"
+    );
+    assert!(outcome.data.as_bstr().contains_str("\nfive\n"));
+    assert!(outcome.data.as_bstr().contains_str("\nfourteen\n"));
+
+    let input = numbers(1..=20).replace("\n3\n", "\nthree\n");
+    let outcome = apply::hunks(input.as_bytes(), hunks, Default::default());
+    assert_eq!(outcome.rejected_hunks(), [0], "the first hunk doesn't match anymore");
+    assert!(
+        outcome.data.as_bstr().contains_str("\nfourteen\n"),
+        "the second one applies"
+    );
+    assert!(!outcome.is_complete());
+
+    let outcome = apply::hunks(
+        input.as_bytes(),
+        hunks,
+        apply::Options {
+            fuzz: 1,
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        outcome.hunks[0],
+        apply::HunkOutcome::Applied { offset: 0, fuzz: 1 },
+        "with fuzz, the first line of context is ignored"
+    );
+    assert!(outcome.data.as_bstr().contains_str("\nthree\n4\nfive\n"));
+    Ok(())
+}
+
+#[test]
+fn whitespace_changes_can_be_ignored() -> crate::Result {
+    let files = patch::parse(PATCH.as_bytes())?;
+    let input = numbers(1..=20).replace("\n4\n", "\n  4 \n");
+    let outcome = apply::hunks(input.as_bytes(), &files[0].hunks, Default::default());
+    assert_eq!(outcome.rejected_hunks(), [0]);
+
+    let outcome = apply::hunks(
+        input.as_bytes(),
+        &files[0].hunks,
+        apply::Options {
+            whitespace: apply::Whitespace::IgnoreChanges,
+            ..Default::default()
+        },
+    );
+    assert!(outcome.is_complete());
+    assert!(
+        outcome.data.as_bstr().contains_str("\n  4 \nfive\n"),
+        "context retains its whitespace"
+    );
+    Ok(())
+}
+
+#[test]
+fn three_way_merge() {
+    let base = numbers(1..=10);
+    let ours = base.replace("\n2\n", "\ntwo\n");
+    let theirs = base.replace("\n8\n", "\neight\n");
+    let outcome = merge::three_way(
+        base.as_bytes(),
+        ours.as_bytes(),
+        theirs.as_bytes(),
+        Default::default(),
+        gix_diff::blob::Algorithm::Myers,
+    );
+    assert_eq!(outcome.num_conflicts, 0);
+    assert_eq!(
+        outcome.data.as_bstr(),
+        base.replace("\n2\n", "\ntwo\n").replace("\n8\n", "\neight\n")
+    );
+
+    let theirs = base.replace("\n2\n", "\nzwei\n");
+    let outcome = merge::three_way(
+        base.as_bytes(),
+        ours.as_bytes(),
+        theirs.as_bytes(),
+        merge::Labels {
+            ours: "HEAD",
+            theirs: "patch",
+        },
+        gix_diff::blob::Algorithm::Myers,
+    );
+    assert_eq!(outcome.num_conflicts, 1);
+    assert_eq!(
+        outcome.data.as_bstr(),
+        "1\n<<<<<<< HEAD\ntwo\n=======\nzwei\n>>>>>>> patch\n3\n4\n5\n6\n7\n8\n9\n10\n"
+    );
+
+    let outcome = merge::three_way(
+        base.as_bytes(),
+        ours.as_bytes(),
+        ours.as_bytes(),
+        Default::default(),
+        gix_diff::blob::Algorithm::Myers,
+    );
+    assert_eq!(outcome.num_conflicts, 0, "identical changes don't conflict");
+    assert_eq!(outcome.data, ours.as_bytes());
+}
+
+#[test]
+fn binary_patches_round_trip() -> crate::Result {
+    let old: Vec<u8> = (0..2000u32).flat_map(u32::to_le_bytes).collect();
+    let mut new = old.clone();
+    new[100..110].copy_from_slice(b"0123456789");
+    new.extend_from_slice(b"\0appended");
+
+    for (old, new, expect_delta) in [
+        (&old, &new, true),
+        (&Vec::new(), &new, false),
+        (&old, &Vec::new(), false),
+    ] {
+        let mut out = b"diff --git a/file b/file\n".to_vec();
+        patch::binary::write_to(old, new, &mut out)?;
+        let files = patch::parse(&out)?;
+        let binary = files[0].binary.as_ref().expect("binary patch present");
+        assert_eq!(matches!(binary.forward, BinaryHunk::Delta(_)), expect_delta);
+        assert_eq!(&apply::binary(old, binary, false)?, new);
+        assert_eq!(&apply::binary(new, binary, true)?, old);
+    }
+    Ok(())
+}
+
+#[test]
+fn deltas() -> crate::Result {
+    let base = b"the quick brown fox jumps over the lazy dog, again and again and again".repeat(10);
+    let mut target = base.clone();
+    target.splice(300..310, b"something else entirely".iter().copied());
+    let delta = patch::delta::create(&base, &target);
+    assert!(delta.len() < target.len() / 4, "most of the data is copied");
+    assert_eq!(patch::delta::apply(&base, &delta)?, target);
+    assert!(
+        patch::delta::apply(&target, &delta).is_err(),
+        "the base size is verified"
+    );
+    Ok(())
+}
+
+#[test]
+fn base85() -> crate::Result {
+    let mut encoded = Vec::new();
+    patch::base85::encode(b"\0\0\0\0\xff\xff\xff\xff", &mut encoded);
+    assert_eq!(encoded.as_bstr(), "00000|NsC0");
+    let mut decoded = Vec::new();
+    patch::base85::decode(&encoded, &mut decoded)?;
+    assert_eq!(decoded, b"\0\0\0\0\xff\xff\xff\xff");
+    assert!(patch::base85::decode(b"~~~~~", &mut decoded).is_err(), "overflow");
+    Ok(())
+}
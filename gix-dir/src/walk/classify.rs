@@ -405,7 +405,7 @@ fn resolve_file_type_with_index(
     (uptodate_kind, index_kind, special_property)
 }
 
-fn is_eq(lhs: &BStr, rhs: impl AsRef<BStr>, ignore_case: bool) -> bool {
+pub(super) fn is_eq(lhs: &BStr, rhs: impl AsRef<BStr>, ignore_case: bool) -> bool {
     if ignore_case {
         lhs.eq_ignore_ascii_case(rhs.as_ref().as_ref())
     } else {
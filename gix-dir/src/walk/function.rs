@@ -1,9 +1,12 @@
 use std::borrow::Cow;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use bstr::{BStr, BString, ByteSlice};
 
-use crate::walk::{classify, readdir, Action, Context, Delegate, Error, ForDeletionMode, Options, Outcome};
+use crate::walk::{
+    classify, prefetch, readdir, untracked_cache, Action, Context, Delegate, Error, ForDeletionMode, Options, Outcome,
+};
 use crate::{entry, EntryRef};
 
 /// A function to perform a git-style, unsorted, directory walk.
@@ -24,21 +27,13 @@ use crate::{entry, EntryRef};
 ///
 /// ### Performance Notes
 ///
-/// In theory, parallel directory traversal can be significantly faster, and what's possible for our current
-/// `gix_features::fs::WalkDir` implementation is to abstract a `filter_entry()` method so it works both for
-/// the iterator from the `walkdir` crate as well as from `jwalk`. However, doing so as initial version
-/// has the risk of not being significantly harder if not impossible to implement as flow-control is very
-/// limited.
+/// The traversal itself is single-threaded and akin to the Git implementation, as flow-control during the traversal
+/// is very fine-grained and depends on the classification of each entry.
 ///
-/// Thus the decision was made to start out with something akin to the Git implementation, get all tests and
-/// baseline comparison to pass, and see if an iterator with just `filter_entry` would be capable of dealing with
-/// it. Note that `filter_entry` is the only just-in-time traversal control that `walkdir` offers, even though
-/// one could consider switching to `jwalk` and just use its single-threaded implementation if a unified interface
-/// is necessary to make this work - `jwalk` has a more powerful API for this to work.
-///
-/// If that was the case, we are talking about 0.5s for single-threaded traversal (without doing any extra work)
-/// or 0.25s for optimal multi-threaded performance, all in the WebKit directory with 388k items to traverse.
-/// Thus, the speedup could easily be 2x or more and thus worth investigating in due time.
+/// However, with [`Options::threads`] set, reading directories is distributed over multiple threads that read
+/// the sub-directories of each directory the traversal enters ahead of time, while the traversal consumes them
+/// in the same order it would have read them itself.
+/// This speeds up the traversal in particular if reading directories is slow, while producing exactly the same output.
 pub fn walk(
     worktree_root: &Path,
     mut ctx: Context<'_>,
@@ -101,18 +96,67 @@ pub fn walk(
     }
 
     let mut state = readdir::State::new(worktree_root, ctx.current_dir, options.for_deletion.is_some());
+    let untracked_cache_is_usable = root == worktree_root
+        && ctx.pathspec.patterns().len() == 0
+        && ctx.excludes.is_some()
+        && !options.emit_tracked
+        && !options.emit_pruned
+        && options.emit_ignored.is_none()
+        && options.for_deletion.is_none();
+    if untracked_cache_is_usable {
+        state.untracked_cache = ctx
+            .untracked_cache
+            .as_mut()
+            .and_then(|cache| untracked_cache::State::new(cache, &options));
+    }
     let may_collapse = root != worktree_root && state.may_collapse(&current);
-    let (action, _) = readdir::recursive(
-        may_collapse,
-        &mut current,
-        &mut buf,
-        root_info,
-        &mut ctx,
-        options,
-        delegate,
-        &mut out,
-        &mut state,
-    )?;
+    let num_threads = options
+        .threads
+        .map_or(1, |limit| gix_features::parallel::num_threads(Some(limit)));
+    let res = if num_threads > 1 && state.untracked_cache.is_none() {
+        let prefetch = Arc::new(prefetch::Shared::new(options.precompose_unicode));
+        state.prefetch = Some(prefetch.clone());
+        gix_features::parallel::threads(|scope| {
+            for id in 0..num_threads {
+                gix_features::parallel::build_thread()
+                    .name(format!("gix-dir.walk.read-dir.{id}"))
+                    .spawn_scoped(scope, {
+                        let prefetch = prefetch.clone();
+                        move || prefetch.run_reader()
+                    })
+                    .expect("valid name");
+            }
+            let _shutdown = prefetch::ShutdownOnDrop(&prefetch);
+            readdir::recursive(
+                may_collapse,
+                &mut current,
+                &mut buf,
+                root_info,
+                &mut ctx,
+                options,
+                delegate,
+                &mut out,
+                &mut state,
+            )
+        })
+    } else {
+        readdir::recursive(
+            may_collapse,
+            &mut current,
+            &mut buf,
+            root_info,
+            &mut ctx,
+            options,
+            delegate,
+            &mut out,
+            &mut state,
+        )
+    };
+    state.prefetch = None;
+    if let Some((untracked_cache, cache)) = state.untracked_cache.take().zip(ctx.untracked_cache.as_mut()) {
+        *cache.cache.directories_mut() = untracked_cache.into_directories();
+    }
+    let (action, _) = res?;
     if action != Action::Cancel {
         state.emit_remaining(may_collapse, options, &mut out, delegate);
         assert_eq!(state.on_hold.len(), 0, "BUG: after emission, on hold must be empty");
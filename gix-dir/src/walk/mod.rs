@@ -1,5 +1,5 @@
 use crate::{entry, EntryRef};
-use bstr::BStr;
+use bstr::{BStr, BString};
 use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
 
@@ -192,6 +192,15 @@ pub struct Options {
     ///
     /// In other words, for Git compatibility this flag should be `false`, the default, for `git2` compatibility it should be `true`.
     pub symlinks_to_directories_are_ignored_like_directories: bool,
+    /// If `Some(thread_limit)`, read directories ahead of the traversal with up to `thread_limit` threads, or as many threads
+    /// as there are logical cores if it is `0`.
+    /// If `None`, the default, or if the `parallel` feature of `gix-features` isn't enabled, directories are read
+    /// by the traversal itself.
+    ///
+    /// The traversal itself remains single-threaded, so the output ordering and the way directories are collapsed are
+    /// the same as without threads. Note that some directories may be read even though they aren't traversed, like ignored
+    /// directories. This is also why threads are not used if an [untracked cache](Context::untracked_cache) is used.
+    pub threads: Option<usize>,
 }
 
 /// All information that is required to perform a dirwalk, and classify paths properly.
@@ -257,6 +266,48 @@ pub struct Context<'a> {
     ///
     /// If the `traversal_root` is not in the `worktree_root` passed to [walk()](crate::walk()).
     pub explicit_traversal_root: Option<&'a std::path::Path>,
+    /// If not `None`, the sorted and repository-relative paths, without trailing slash, that a filesystem monitor reported
+    /// as changed since it was last queried.
+    ///
+    /// It's only used along with the [`untracked_cache`](Self::untracked_cache), whose information about directories that aren't
+    /// one of these paths, don't contain one of them and aren't contained in one of them is trusted without checking their stat
+    /// information, similar to what Git does.
+    /// Without an untracked cache, all directories are read as the untracked files they contain wouldn't be known otherwise.
+    pub fs_monitor_changed_paths: Option<&'a [BString]>,
+    /// If not `None`, the untracked cache to use to avoid reading directories that didn't change since the cache was last updated,
+    /// and to update with the information obtained during the walk so that it can be written back to the index.
+    ///
+    /// It's only used if the walk starts at the worktree root, if there is no pathspec, if [`excludes`](Self::excludes) are set, and if
+    /// neither tracked, pruned nor ignored entries are emitted, and if the walk isn't [for deletion](Options::for_deletion).
+    /// Otherwise, it remains untouched.
+    ///
+    /// Note that the cache is replaced with what was observed during the walk, even if the walk fails or is cancelled.
+    pub untracked_cache: Option<UntrackedCache<'a>>,
+}
+
+/// An untracked cache as used in [`Context::untracked_cache`], along with information to validate it.
+///
+/// Only directories with tracked files are cached, along with their untracked files and directories, by their stat information
+/// and the id of the [exclude file](gix_index::extension::UntrackedCache::exclude_filename_per_dir()) they contain.
+/// If neither changed, and if the exclude files of their parent directories didn't change either, the cached entries are used
+/// instead of reading the directory.
+///
+/// The cache is started afresh if its [flags](gix_index::extension::UntrackedCache::dir_flags()) don't match the
+/// [options](Options) of the walk, or if the ids of the global exclude files changed.
+///
+/// ### Important
+///
+/// The cache [identifier](gix_index::extension::UntrackedCache::identifier()) has to be validated by the caller,
+/// and a new cache must be used if it doesn't match the current worktree.
+pub struct UntrackedCache<'a> {
+    /// The cache to use and to update, typically obtained from the index.
+    pub cache: &'a mut gix_index::extension::UntrackedCache,
+    /// Options to control how the stat information of directories is compared to the one stored in the cache.
+    pub stat_options: gix_index::entry::stat::Options,
+    /// The current state of the `$GIT_DIR/info/exclude` file, or `None` if it doesn't exist.
+    pub info_exclude: Option<gix_index::extension::untracked_cache::OidStat>,
+    /// The current state of the file configured with `core.excludesFile`, or `None` if it doesn't exist.
+    pub excludes_file: Option<gix_index::extension::untracked_cache::OidStat>,
 }
 
 /// Additional information collected as outcome of [`walk()`](function::walk()).
@@ -304,4 +355,6 @@ pub enum Error {
 
 mod classify;
 pub(crate) mod function;
+mod prefetch;
 mod readdir;
+mod untracked_cache;
@@ -0,0 +1,159 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+use bstr::BString;
+
+use crate::entry::Kind;
+use crate::walk::Error;
+
+/// An entry of a directory that was read ahead of the traversal.
+pub(super) struct Entry {
+    /// The name of the entry within its directory.
+    pub name: BString,
+    /// The kind of the entry on disk, if it could be determined.
+    pub kind: Option<Kind>,
+}
+
+/// All entries of a directory, or the error that occurred when reading it.
+pub(super) type Listing = Result<Vec<Entry>, Error>;
+
+enum Slot {
+    /// The directory waits for a thread to read it.
+    Queued,
+    /// A thread is currently reading the directory.
+    InProgress,
+    /// The directory was read.
+    Ready(Listing),
+}
+
+#[derive(Default)]
+struct Queue {
+    /// The directories to read, in the order they were submitted.
+    paths: VecDeque<PathBuf>,
+    /// The state of each submitted directory that wasn't taken yet.
+    slots: HashMap<PathBuf, Slot>,
+    /// If `true`, threads should stop reading directories.
+    is_done: bool,
+}
+
+/// Directories to read ahead of the traversal, shared between the traversal and the threads that read them.
+///
+/// The traversal itself is never parallel, and it sees directory entries in the same order as if it read them itself.
+/// This keeps the output ordering and all classification and collapsing semantics, while the costly `readdir` calls
+/// are distributed over multiple threads.
+pub(super) struct Shared {
+    queue: Mutex<Queue>,
+    changed: Condvar,
+    precompose_unicode: bool,
+}
+
+impl Shared {
+    pub(super) fn new(precompose_unicode: bool) -> Self {
+        Shared {
+            queue: Default::default(),
+            changed: Default::default(),
+            precompose_unicode,
+        }
+    }
+
+    /// Queue all `directories` so they are read by one of the threads in [`Self::run_reader()`].
+    pub(super) fn submit(&self, directories: impl IntoIterator<Item = PathBuf>) {
+        let mut queue = self.lock();
+        for path in directories {
+            if queue.slots.contains_key(&path) {
+                continue;
+            }
+            queue.slots.insert(path.clone(), Slot::Queued);
+            queue.paths.push_back(path);
+        }
+        drop(queue);
+        self.changed.notify_all();
+    }
+
+    /// Return the listing of the directory at `path`, waiting for it if it's currently read, or reading it on the current
+    /// thread if no other thread started reading it yet.
+    pub(super) fn take(&self, path: &Path) -> Listing {
+        let mut queue = self.lock();
+        while let Some(Slot::InProgress) = queue.slots.get(path) {
+            queue = self.changed.wait(queue).expect("no panic while holding lock");
+        }
+        match queue.slots.remove(path) {
+            Some(Slot::Ready(listing)) => listing,
+            Some(Slot::Queued | Slot::InProgress) | None => {
+                drop(queue);
+                read(path, self.precompose_unicode)
+            }
+        }
+    }
+
+    /// Read queued directories until [`Self::shutdown()`] is called.
+    pub(super) fn run_reader(&self) {
+        loop {
+            let mut queue = self.lock();
+            let path = loop {
+                if queue.is_done {
+                    return;
+                }
+                match queue.paths.pop_front() {
+                    Some(path) => {
+                        if let Some(slot) = queue.slots.get_mut(&path).filter(|slot| matches!(slot, Slot::Queued)) {
+                            *slot = Slot::InProgress;
+                            break path;
+                        }
+                    }
+                    None => queue = self.changed.wait(queue).expect("no panic while holding lock"),
+                }
+            };
+            drop(queue);
+
+            let listing = read(&path, self.precompose_unicode);
+            self.lock().slots.insert(path, Slot::Ready(listing));
+            self.changed.notify_all();
+        }
+    }
+
+    /// Make all threads stop reading directories once they are done with the current one.
+    pub(super) fn shutdown(&self) {
+        let mut queue = self.lock();
+        queue.is_done = true;
+        queue.paths.clear();
+        drop(queue);
+        self.changed.notify_all();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Queue> {
+        self.queue.lock().expect("no panic while holding lock")
+    }
+}
+
+/// Calls [`Shared::shutdown()`] when dropped, to be sure reading threads stop even if the traversal fails or panics.
+pub(super) struct ShutdownOnDrop<'a>(pub &'a Shared);
+
+impl Drop for ShutdownOnDrop<'_> {
+    fn drop(&mut self) {
+        self.0.shutdown();
+    }
+}
+
+fn read(path: &Path, precompose_unicode: bool) -> Listing {
+    gix_fs::read_dir(path, precompose_unicode)
+        .map_err(|err| Error::ReadDir {
+            path: path.to_owned(),
+            source: err,
+        })?
+        .map(|entry| {
+            let entry = entry.map_err(|err| Error::DirEntry {
+                parent_directory: path.to_owned(),
+                source: err,
+            })?;
+            Ok(Entry {
+                name: gix_path::try_os_str_into_bstr(Cow::Borrowed(entry.file_name().as_ref()))
+                    .expect("no illformed UTF-8")
+                    .into_owned(),
+                kind: entry.file_type().ok().map(Into::into),
+            })
+        })
+        .collect()
+}
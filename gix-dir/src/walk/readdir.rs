@@ -2,11 +2,16 @@ use bstr::{BStr, BString, ByteSlice};
 use std::borrow::Cow;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::Ordering;
+use std::sync::Arc;
 
 use crate::entry::{PathspecMatch, Status};
+use crate::walk::classify::is_eq;
 use crate::walk::function::{can_recurse, emit_entry};
 use crate::walk::EmissionMode::CollapseDirectory;
-use crate::walk::{classify, Action, CollapsedEntriesEmissionMode, Context, Delegate, Error, Options, Outcome};
+use crate::walk::{
+    classify, prefetch, untracked_cache, Action, CollapsedEntriesEmissionMode, Context, Delegate, Error, Options,
+    Outcome,
+};
 use crate::{entry, walk, Entry, EntryRef};
 
 /// ### Deviation
@@ -27,20 +32,77 @@ pub(super) fn recursive(
     if ctx.should_interrupt.map_or(false, |flag| flag.load(Ordering::Relaxed)) {
         return Err(Error::Interrupted);
     }
-    out.read_dir_calls += 1;
-    let entries = gix_fs::read_dir(current, opts.precompose_unicode).map_err(|err| Error::ReadDir {
-        path: current.to_owned(),
-        source: err,
-    })?;
+    let use_untracked_cache =
+        state.untracked_cache.is_some() && (current_bstr.is_empty() || current_info.status == Status::Tracked);
+    let cached_entries = if use_untracked_cache {
+        state
+            .untracked_cache
+            .as_mut()
+            .and_then(|cache| cache.enter(current, current_bstr.as_bstr(), ctx))
+    } else {
+        None
+    };
+    let is_cached = cached_entries.is_some();
+    let (mut disk_entries, mut prefetched_entries) = match (is_cached, state.prefetch.as_deref()) {
+        (true, _) => (None, None),
+        (false, Some(prefetch)) => {
+            out.read_dir_calls += 1;
+            let entries = prefetch.take(current)?;
+            prefetch.submit(
+                entries
+                    .iter()
+                    .filter(|entry| {
+                        entry.kind.map_or(false, |kind| kind.is_dir())
+                            && !is_eq(entry.name.as_bstr(), ".git", opts.ignore_case)
+                    })
+                    .map(|entry| current.join(gix_path::from_bstr(entry.name.as_bstr()))),
+            );
+            (None, Some(entries.into_iter()))
+        }
+        (false, None) => {
+            out.read_dir_calls += 1;
+            (
+                Some(
+                    gix_fs::read_dir(current, opts.precompose_unicode).map_err(|err| Error::ReadDir {
+                        path: current.to_owned(),
+                        source: err,
+                    })?,
+                ),
+                None,
+            )
+        }
+    };
+    let mut cached_entries = cached_entries.into_iter().flatten();
 
     let mut num_entries = 0;
     let mark = state.mark(may_collapse);
     let mut prevent_collapse = false;
-    for entry in entries {
-        let entry = entry.map_err(|err| Error::DirEntry {
-            parent_directory: current.to_owned(),
-            source: err,
-        })?;
+    loop {
+        let (file_name, disk_kind) = if let Some(entries) = disk_entries.as_mut() {
+            match entries.next() {
+                Some(entry) => {
+                    let entry = entry.map_err(|err| Error::DirEntry {
+                        parent_directory: current.to_owned(),
+                        source: err,
+                    })?;
+                    let file_name: BString = gix_path::try_os_str_into_bstr(Cow::Borrowed(entry.file_name().as_ref()))
+                        .expect("no illformed UTF-8")
+                        .into_owned();
+                    (file_name, DiskKind::OnDemand(entry))
+                }
+                None => break,
+            }
+        } else if let Some(entries) = prefetched_entries.as_mut() {
+            match entries.next() {
+                Some(entry) => (entry.name, DiskKind::Prefetched(entry.kind)),
+                None => break,
+            }
+        } else {
+            match cached_entries.next() {
+                Some(entry) => (entry.name, DiskKind::Cached(entry.kind)),
+                None => break,
+            }
+        };
         // Important to count right away, otherwise the directory could be seen as empty even though it's not.
         // That is, this should be independent of the kind.
         num_entries += 1;
@@ -49,23 +111,30 @@ pub(super) fn recursive(
         if prev_len != 0 {
             current_bstr.push(b'/');
         }
-        let file_name = entry.file_name();
-        current_bstr.extend_from_slice(
-            gix_path::try_os_str_into_bstr(Cow::Borrowed(file_name.as_ref()))
-                .expect("no illformed UTF-8")
-                .as_ref(),
-        );
-        current.push(file_name);
+        current_bstr.extend_from_slice(&file_name);
+        current.push(gix_path::from_bstr(file_name.as_bstr()));
 
         let info = classify::path(
             current,
             current_bstr,
             if prev_len == 0 { 0 } else { prev_len + 1 },
-            None,
-            || entry.file_type().ok().map(Into::into),
+            match disk_kind {
+                DiskKind::Cached(kind) => Some(kind),
+                DiskKind::OnDemand(_) | DiskKind::Prefetched(_) => None,
+            },
+            || match disk_kind {
+                DiskKind::Cached(_) => None,
+                DiskKind::OnDemand(entry) => entry.file_type().ok().map(Into::into),
+                DiskKind::Prefetched(kind) => kind,
+            },
             opts,
             ctx,
         )?;
+        if use_untracked_cache {
+            if let Some(cache) = state.untracked_cache.as_mut() {
+                cache.record(file_name.as_bstr(), info);
+            }
+        }
 
         if can_recurse(
             current_bstr.as_bstr(),
@@ -99,6 +168,17 @@ pub(super) fn recursive(
         current_bstr.truncate(prev_len);
         current.pop();
     }
+    if use_untracked_cache {
+        if let Some(cache) = state.untracked_cache.as_mut() {
+            cache.leave();
+        }
+    }
+    if is_cached {
+        // The tracked entries aren't cached, but they were seen when the cache was updated.
+        // They prevent the directory from collapsing, and from being empty.
+        num_entries += 1;
+        prevent_collapse = true;
+    }
 
     let res = mark.reduce_held_entries(
         num_entries,
@@ -115,9 +195,23 @@ pub(super) fn recursive(
     Ok((res, prevent_collapse))
 }
 
+/// The way to learn about the kind of a directory entry on disk.
+enum DiskKind {
+    /// The kind was stored in the untracked cache, and it's known to be up-to-date.
+    Cached(entry::Kind),
+    /// The kind can be obtained from the directory entry, if needed.
+    OnDemand(gix_fs::read_dir::DirEntry),
+    /// The kind was obtained when the directory was read ahead of time.
+    Prefetched(Option<entry::Kind>),
+}
+
 pub(super) struct State {
     /// The entries to hold back until it's clear what to do with them.
     pub on_hold: Vec<Entry>,
+    /// The untracked cache to use and update, if it is used at all.
+    pub untracked_cache: Option<untracked_cache::State>,
+    /// The directories to read ahead of time, if reading directories is parallel.
+    pub prefetch: Option<Arc<prefetch::Shared>>,
     /// The path the user is currently in, as seen from the workdir root.
     worktree_relative_current_dir: Option<PathBuf>,
 }
@@ -154,6 +248,8 @@ impl State {
         };
         Self {
             on_hold: Vec::new(),
+            untracked_cache: None,
+            prefetch: None,
             worktree_relative_current_dir,
         }
     }
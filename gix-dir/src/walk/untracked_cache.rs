@@ -0,0 +1,253 @@
+use bstr::{BStr, BString, ByteSlice};
+use gix_index::extension::untracked_cache::{dir_flags, Directory, OidStat};
+use std::path::Path;
+
+use crate::entry::{Kind, Status};
+use crate::walk::{classify, Context, EmissionMode, Options};
+
+/// An entry of a directory that didn't change since it was cached.
+pub(super) struct CachedEntry {
+    /// The name of the entry within its directory.
+    pub name: BString,
+    /// The kind of the entry on disk.
+    pub kind: Kind,
+}
+
+/// The state to use the untracked cache during the walk, and to record what's needed to update it.
+pub(super) struct State {
+    /// The directories of the cache as they were before the walk.
+    previous: Vec<Directory>,
+    /// The directories as observed during the walk, with the root directory first.
+    current: Vec<Directory>,
+    /// One level for each directory we are currently in, with the last one being the directory currently read.
+    stack: Vec<Level>,
+    stat_options: gix_index::entry::stat::Options,
+}
+
+struct Level {
+    /// The index of the directory in `current`.
+    current: usize,
+    /// The index of the directory in `previous`, if it exists and if it can be used to look up cached sub-directories.
+    previous_for_sub_directories: Option<usize>,
+    /// The stat information to store once the directory was fully processed.
+    stat: Option<gix_index::entry::Stat>,
+    /// If `true`, tracked entries were found in the directory.
+    ///
+    /// Only then we can be sure that the directory wouldn't be collapsed when replaying its entries from the cache.
+    has_tracked_entries: bool,
+}
+
+impl State {
+    /// Take the directories out of `cache`, or return `None` if it can't be used with the exclude files we know.
+    ///
+    /// If the cache was created for a walk with different `opts` or with different global exclude files,
+    /// it's replaced with an empty cache for the current configuration.
+    pub(super) fn new(cache: &mut crate::walk::UntrackedCache<'_>, opts: &Options) -> Option<Self> {
+        if cache.cache.exclude_filename_per_dir() != ".gitignore" {
+            return None;
+        }
+        let dir_flags = expected_dir_flags(opts);
+        let id = |oid_stat: Option<&OidStat>| oid_stat.map(|oid_stat| oid_stat.id);
+        if cache.cache.dir_flags() != dir_flags
+            || id(cache.cache.info_exclude()) != id(cache.info_exclude.as_ref())
+            || id(cache.cache.excludes_file()) != id(cache.excludes_file.as_ref())
+        {
+            *cache.cache = gix_index::extension::UntrackedCache::new(
+                cache.cache.identifier().to_owned(),
+                cache.info_exclude.clone(),
+                cache.excludes_file.clone(),
+                ".gitignore",
+                dir_flags,
+            );
+        }
+        Some(State {
+            previous: std::mem::take(cache.cache.directories_mut()),
+            current: Vec::new(),
+            stack: Vec::new(),
+            stat_options: cache.stat_options,
+        })
+    }
+
+    /// Return the directories as observed during the walk.
+    pub(super) fn into_directories(self) -> Vec<Directory> {
+        self.current
+    }
+
+    /// Enter the directory at `dir_path`, which is `rela_path` relative to the worktree root, and return its cached entries
+    /// if the cache can be trusted.
+    ///
+    /// Must be followed by a call to [`Self::leave()`] once all entries were [recorded](Self::record()).
+    pub(super) fn enter(&mut self, dir_path: &Path, rela_path: &BStr, ctx: &Context<'_>) -> Option<Vec<CachedEntry>> {
+        let name = rela_path
+            .rfind_byte(b'/')
+            .map_or(rela_path, |pos| rela_path[pos + 1..].as_bstr());
+        let (previous, parent) = match self.stack.last() {
+            None => ((!self.previous.is_empty()).then_some(0), None),
+            Some(parent) => (
+                parent.previous_for_sub_directories.and_then(|parent| {
+                    self.previous[parent]
+                        .sub_directories
+                        .iter()
+                        .copied()
+                        .find(|idx| self.previous[*idx].name == name)
+                }),
+                Some(parent.current),
+            ),
+        };
+        let previous_dir = previous.map(|idx| &self.previous[idx]);
+        let is_unchanged_according_to_fs_monitor = ctx
+            .fs_monitor_changed_paths
+            .map_or(false, |paths| !is_affected_by_changes(paths, rela_path));
+
+        let (stat, exclude_file_oid) = match previous_dir {
+            Some(dir) if is_unchanged_according_to_fs_monitor && dir.stat.is_some() => (dir.stat, dir.exclude_file_oid),
+            _ => (
+                gix_index::fs::Metadata::from_path_no_follow(dir_path)
+                    .ok()
+                    .and_then(|md| gix_index::entry::Stat::from_fs(&md).ok()),
+                exclude_file_oid(dir_path, rela_path, ctx.index),
+            ),
+        };
+        let exclude_file_is_unchanged = previous_dir.map_or(false, |dir| dir.exclude_file_oid == exclude_file_oid);
+        let is_valid = exclude_file_is_unchanged
+            && previous_dir.map_or(false, |dir| {
+                !dir.check_only
+                    && match (dir.stat, stat) {
+                        (Some(previous), Some(current)) => {
+                            is_unchanged_according_to_fs_monitor
+                                || (previous.matches(&current, self.stat_options)
+                                    && !previous.is_racy(ctx.index.timestamp(), self.stat_options))
+                        }
+                        _ => false,
+                    }
+            });
+
+        let current = self.current.len();
+        self.current.push(Directory {
+            name: name.to_owned(),
+            untracked_entries: Vec::new(),
+            sub_directories: Vec::new(),
+            stat: None,
+            exclude_file_oid,
+            check_only: false,
+        });
+        if let Some(parent) = parent {
+            self.current[parent].sub_directories.push(current);
+        }
+        self.stack.push(Level {
+            current,
+            previous_for_sub_directories: previous.filter(|_| exclude_file_is_unchanged),
+            stat,
+            has_tracked_entries: is_valid,
+        });
+
+        let previous_dir = previous_dir.filter(|_| is_valid)?;
+        let mut entries: Vec<_> = previous_dir
+            .sub_directories
+            .iter()
+            .map(|idx| CachedEntry {
+                name: self.previous[*idx].name.clone(),
+                kind: Kind::Directory,
+            })
+            .collect();
+        let num_sub_directories = entries.len();
+        for untracked in &previous_dir.untracked_entries {
+            let name = untracked.strip_suffix(b"/").unwrap_or(untracked).as_bstr();
+            if entries[..num_sub_directories].iter().any(|e| e.name == name) {
+                continue;
+            }
+            let Some(kind) = dir_path
+                .join(gix_path::from_bstr(name))
+                .symlink_metadata()
+                .ok()
+                .map(|md| md.file_type().into())
+            else {
+                continue;
+            };
+            entries.push(CachedEntry {
+                name: name.to_owned(),
+                kind,
+            });
+        }
+        Some(entries)
+    }
+
+    /// Record the directory entry `name` with its classification `info` for the directory that was entered last.
+    pub(super) fn record(&mut self, name: &BStr, info: classify::Outcome) {
+        let level = self
+            .stack
+            .last_mut()
+            .expect("BUG: record() must be called after enter()");
+        match info.status {
+            Status::Untracked => {
+                let mut name = name.to_owned();
+                if info.disk_kind.map_or(false, |kind| kind.is_dir()) {
+                    name.push(b'/');
+                }
+                self.current[level.current].untracked_entries.push(name);
+            }
+            Status::Tracked => level.has_tracked_entries = true,
+            Status::Ignored(_) | Status::Pruned => {}
+        }
+    }
+
+    /// Leave the directory that was entered last after all of its entries were recorded,
+    /// making it available for use in the next walk.
+    pub(super) fn leave(&mut self) {
+        let level = self.stack.pop().expect("BUG: leave() must be called after enter()");
+        if level.has_tracked_entries {
+            self.current[level.current].stat = level.stat;
+        }
+    }
+}
+
+/// Return the id of the `.gitignore` file in `dir_path`, with `rela_dir` being its worktree-relative path.
+/// Use the id stored in the index if it's known to be up-to-date, or hash the file otherwise.
+fn exclude_file_oid(dir_path: &Path, rela_dir: &BStr, index: &gix_index::State) -> Option<gix_index::hash::ObjectId> {
+    let mut rela_path = rela_dir.to_owned();
+    if !rela_path.is_empty() {
+        rela_path.push(b'/');
+    }
+    rela_path.extend_from_slice(b".gitignore");
+    if let Some(entry) = index.entry_by_path(rela_path.as_bstr()).filter(|entry| {
+        entry
+            .flags
+            .intersects(gix_index::entry::Flags::UPTODATE | gix_index::entry::Flags::FSMONITOR_VALID)
+    }) {
+        return Some(entry.id);
+    }
+    let data = std::fs::read(dir_path.join(".gitignore")).ok()?;
+    Some(gix_object::compute_hash(
+        index.object_hash(),
+        gix_object::Kind::Blob,
+        &data,
+    ))
+}
+
+/// Return `true` if `dir` is one of the `changed_paths`, contains one of them or is contained in one of them.
+fn is_affected_by_changes(changed_paths: &[BString], dir: &BStr) -> bool {
+    let start = changed_paths.partition_point(|path| path.as_bstr() < dir);
+    let is_or_contains_change = changed_paths[start..]
+        .iter()
+        .take_while(|path| path.starts_with(dir))
+        .any(|path| path.len() == dir.len() || path[dir.len()] == b'/');
+    is_or_contains_change
+        || dir.rfind_iter("/").any(|pos| {
+            changed_paths
+                .binary_search_by(|path| path.as_bstr().cmp(&dir[..pos]))
+                .is_ok()
+        })
+}
+
+/// Return the flags describing a walk with `opts` the same way Git does.
+fn expected_dir_flags(opts: &Options) -> u32 {
+    if opts.emit_untracked == EmissionMode::CollapseDirectory {
+        let mut flags = dir_flags::SHOW_OTHER_DIRECTORIES;
+        if !opts.emit_empty_directories {
+            flags |= dir_flags::HIDE_EMPTY_DIRECTORIES;
+        }
+        flags
+    } else {
+        0
+    }
+}
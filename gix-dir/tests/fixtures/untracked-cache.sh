@@ -0,0 +1,11 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+mkdir -p tracked/sub ignoring
+touch tracked/a tracked/sub/b ignoring/tracked
+git add . && git commit -q -m init
+
+mkdir tracked/untracked-dir
+touch untracked tracked/untracked-dir/a tracked/sub/untracked ignoring/file.o
+echo "*.o" > ignoring/.gitignore
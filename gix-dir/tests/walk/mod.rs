@@ -1,3 +1,4 @@
+use bstr::BString;
 use gix_dir::{walk, EntryRef};
 use pretty_assertions::assert_eq;
 use std::sync::atomic::AtomicBool;
@@ -1139,6 +1140,306 @@ fn expendable_and_precious() {
     );
 }
 
+#[test]
+fn fs_monitor_changed_paths_are_ignored_without_untracked_cache() -> crate::Result {
+    let root = fixture("subdir-untracked");
+    for changed_paths in [vec!["d/d/a"], vec!["c", "d-other", "dd/a"], vec![]] {
+        let changed_paths: Vec<BString> = changed_paths.into_iter().map(Into::into).collect();
+        let ((out, _root), entries) = try_collect_filtered_opts_collect(
+            &root,
+            None,
+            |keep, ctx| walk(&root, ctx, options(), keep),
+            None::<&str>,
+            Options {
+                fs_monitor_changed_paths: Some(&changed_paths),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            out.read_dir_calls, 3,
+            "{changed_paths:?}: untracked files in unchanged directories wouldn't be known without reading them"
+        );
+        assert_eq!(entries, [entry("d/d/a", Untracked, File)], "{changed_paths:?}");
+    }
+    Ok(())
+}
+
+#[test]
+fn untracked_cache_avoids_reading_unchanged_directories() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("untracked-cache.sh")?;
+    let root = tmp.path();
+    let mut cache = gix_index::extension::UntrackedCache::new("identifier", None, None, ".gitignore", 0);
+    let stat_options = gix_index::entry::stat::Options {
+        check_stat: true,
+        use_nsec: true,
+        ..Default::default()
+    };
+    let walk_with_cache = |cache: Option<&mut gix_index::extension::UntrackedCache>,
+                           fs_monitor_changed_paths: Option<&[BString]>|
+     -> crate::Result<_> {
+        let ((out, _root), entries) = try_collect_filtered_opts_collect(
+            root,
+            None,
+            |keep, ctx| {
+                walk(
+                    root,
+                    ctx,
+                    walk::Options {
+                        emit_untracked: CollapseDirectory,
+                        ..options()
+                    },
+                    keep,
+                )
+            },
+            None::<&str>,
+            Options {
+                untracked_cache: cache.map(|cache| walk::UntrackedCache {
+                    cache,
+                    stat_options,
+                    info_exclude: None,
+                    excludes_file: None,
+                }),
+                fs_monitor_changed_paths,
+                index_timestamp: Some(std::time::SystemTime::now()),
+                ..Default::default()
+            },
+        )?;
+        Ok((out.read_dir_calls, entries))
+    };
+
+    let expected = vec![
+        entry("ignoring/.gitignore", Untracked, File),
+        entry("tracked/sub/untracked", Untracked, File),
+        entry("tracked/untracked-dir", Untracked, Directory),
+        entry("untracked", Untracked, File),
+    ];
+    assert_eq!(walk_with_cache(None, None)?, (5, expected.clone()));
+    assert_eq!(
+        walk_with_cache(Some(&mut cache), None)?,
+        (5, expected.clone()),
+        "the empty cache has to be filled first"
+    );
+    assert_eq!(
+        cache.directories().len(),
+        4,
+        "only directories with tracked files are cached"
+    );
+    assert_eq!(
+        walk_with_cache(Some(&mut cache), None)?,
+        (1, expected),
+        "now only untracked directories have to be read"
+    );
+
+    std::fs::write(root.join("tracked/sub/new"), b"")?;
+    std::fs::write(root.join("ignoring/.gitignore"), b"")?;
+    let expected = vec![
+        entry("ignoring/.gitignore", Untracked, File),
+        entry("ignoring/file.o", Untracked, File),
+        entry("tracked/sub/new", Untracked, File),
+        entry("tracked/sub/untracked", Untracked, File),
+        entry("tracked/untracked-dir", Untracked, Directory),
+        entry("untracked", Untracked, File),
+    ];
+    assert_eq!(
+        walk_with_cache(Some(&mut cache), None)?,
+        (3, expected.clone()),
+        "directories that changed are read, just like directories with a changed exclude file"
+    );
+    assert_eq!(walk_with_cache(Some(&mut cache), None)?, (1, expected.clone()));
+
+    std::fs::write(root.join("tracked/new"), b"")?;
+    assert_eq!(
+        walk_with_cache(Some(&mut cache), Some(&[]))?,
+        (1, expected),
+        "the filesystem monitor is trusted, so changes it didn't report go unnoticed"
+    );
+    let (read_dir_calls, entries) = walk_with_cache(Some(&mut cache), Some(&["tracked/new".into()]))?;
+    assert_eq!(read_dir_calls, 2, "now 'tracked' is read as well");
+    assert!(entries.contains(&entry("tracked/new", Untracked, File)));
+    Ok(())
+}
+
+#[test]
+fn untracked_cache_is_reset_if_flags_or_global_excludes_change() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("untracked-cache.sh")?;
+    let root = tmp.path();
+    let mut cache = gix_index::extension::UntrackedCache::new("identifier", None, None, ".gitignore", 0);
+    let info_exclude = gix_index::extension::untracked_cache::OidStat {
+        stat: Default::default(),
+        id: gix_index::hash::ObjectId::empty_blob(gix_index::hash::Kind::Sha1),
+    };
+    let stat_options = gix_index::entry::stat::Options {
+        check_stat: true,
+        use_nsec: true,
+        ..Default::default()
+    };
+    let walk_with_cache = |cache: &mut gix_index::extension::UntrackedCache,
+                           emit_untracked: walk::EmissionMode,
+                           info_exclude: Option<&gix_index::extension::untracked_cache::OidStat>|
+     -> crate::Result<_> {
+        let ((out, _root), _entries) = try_collect_filtered_opts_collect(
+            root,
+            None,
+            |keep, ctx| {
+                walk(
+                    root,
+                    ctx,
+                    walk::Options {
+                        emit_untracked,
+                        ..options()
+                    },
+                    keep,
+                )
+            },
+            None::<&str>,
+            Options {
+                untracked_cache: Some(walk::UntrackedCache {
+                    cache,
+                    stat_options,
+                    info_exclude: info_exclude.cloned(),
+                    excludes_file: None,
+                }),
+                index_timestamp: Some(std::time::SystemTime::now()),
+                ..Default::default()
+            },
+        )?;
+        Ok(out.read_dir_calls)
+    };
+
+    assert_eq!(
+        walk_with_cache(&mut cache, CollapseDirectory, None)?,
+        5,
+        "the cache is filled"
+    );
+    assert_eq!(walk_with_cache(&mut cache, CollapseDirectory, None)?, 1, "and used");
+    assert_eq!(
+        cache.dir_flags(),
+        gix_index::extension::untracked_cache::dir_flags::SHOW_OTHER_DIRECTORIES
+            | gix_index::extension::untracked_cache::dir_flags::HIDE_EMPTY_DIRECTORIES,
+        "the flags match the ones used by Git for the same kind of walk"
+    );
+
+    assert_eq!(
+        walk_with_cache(&mut cache, Matching, None)?,
+        5,
+        "a walk that lists untracked files individually can't use the cache of a collapsing walk"
+    );
+    assert_eq!(cache.dir_flags(), 0);
+    assert_eq!(walk_with_cache(&mut cache, Matching, None)?, 1);
+
+    assert_eq!(
+        walk_with_cache(&mut cache, Matching, Some(&info_exclude))?,
+        5,
+        "a changed exclude file affects all directories"
+    );
+    assert_eq!(cache.info_exclude(), Some(&info_exclude));
+    assert_eq!(walk_with_cache(&mut cache, Matching, Some(&info_exclude))?, 1);
+    Ok(())
+}
+
+#[test]
+fn untracked_cache_is_not_used_if_ignored_entries_are_emitted() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("untracked-cache.sh")?;
+    let root = tmp.path();
+    let mut cache = gix_index::extension::UntrackedCache::new("identifier", None, None, ".gitignore", 0);
+    for _ in 0..2 {
+        let ((out, _root), entries) = try_collect_filtered_opts_collect(
+            root,
+            None,
+            |keep, ctx| {
+                walk(
+                    root,
+                    ctx,
+                    walk::Options {
+                        emit_ignored: Some(Matching),
+                        ..options()
+                    },
+                    keep,
+                )
+            },
+            None::<&str>,
+            Options {
+                untracked_cache: Some(walk::UntrackedCache {
+                    cache: &mut cache,
+                    stat_options: Default::default(),
+                    info_exclude: None,
+                    excludes_file: None,
+                }),
+                index_timestamp: Some(std::time::SystemTime::now()),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(out.read_dir_calls, 5);
+        assert!(entries.contains(&entry("ignoring/file.o", Ignored(Expendable), File)));
+    }
+    assert!(cache.directories().is_empty(), "the cache remains untouched");
+    Ok(())
+}
+
+#[test]
+fn threads_do_not_affect_the_output() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only("many.sh")?;
+    let mut worktrees = Vec::new();
+    for entry in std::fs::read_dir(&root)? {
+        let path = entry?.path();
+        if path.join(".git").is_dir() {
+            worktrees.push(path);
+        }
+    }
+    assert!(worktrees.len() > 10, "there are enough worktrees to test with");
+    worktrees.sort();
+
+    let walk_with = |worktree: &std::path::Path, opts: walk::Options| {
+        let mut dlg = gix_dir::walk::delegate::Collect::default();
+        let res = try_collect_filtered_opts(
+            worktree,
+            None,
+            None,
+            None,
+            |keep, ctx| walk(worktree, ctx, opts, keep),
+            None::<&str>,
+            &mut dlg,
+            Options::default(),
+        );
+        res.map(|(out, _root)| (out, dlg.unorded_entries))
+            .map_err(|err| err.to_string())
+    };
+    for worktree in &worktrees {
+        for opts in [
+            options(),
+            options_emit_all(),
+            walk::Options {
+                emit_ignored: Some(CollapseDirectory),
+                emit_untracked: CollapseDirectory,
+                emit_collapsed: Some(OnStatusMismatch),
+                ..options()
+            },
+            walk::Options {
+                emit_ignored: Some(CollapseDirectory),
+                emit_untracked: CollapseDirectory,
+                for_deletion: Some(ForDeletionMode::FindRepositoriesInIgnoredDirectories),
+                ..options()
+            },
+        ] {
+            let expected = walk_with(worktree, opts);
+            for threads in [2, 0] {
+                let actual = walk_with(
+                    worktree,
+                    walk::Options {
+                        threads: Some(threads),
+                        ..opts
+                    },
+                );
+                assert_eq!(
+                    actual, expected,
+                    "{worktree:?}: reading directories with threads yields the same entries in the same order"
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
 #[test]
 fn subdir_untracked() -> crate::Result {
     let root = fixture("subdir-untracked");
@@ -1,4 +1,4 @@
-use bstr::BStr;
+use bstr::{BStr, BString};
 use gix_dir::{entry, walk, Entry};
 use gix_testtools::scripted_fixture_read_only;
 use std::path::{Path, PathBuf};
@@ -33,6 +33,7 @@ pub fn options_emit_all() -> walk::Options {
         emit_empty_directories: true,
         emit_collapsed: None,
         symlinks_to_directories_are_ignored_like_directories: false,
+        threads: None,
     }
 }
 
@@ -274,6 +275,9 @@ pub fn try_collect_filtered_opts(
         fresh_index,
         git_dir,
         should_interrupt,
+        fs_monitor_changed_paths,
+        untracked_cache,
+        index_timestamp,
     }: Options<'_>,
 ) -> Result<(walk::Outcome, PathBuf), walk::Error> {
     let git_dir = worktree_root.join(git_dir.unwrap_or(".git"));
@@ -282,7 +286,7 @@ pub fn try_collect_filtered_opts(
         |bytes| {
             gix_index::State::from_bytes(
                 &bytes,
-                std::time::UNIX_EPOCH.into(),
+                index_timestamp.unwrap_or(std::time::UNIX_EPOCH).into(),
                 gix_index::hash::Kind::Sha1,
                 Default::default(),
             )
@@ -349,6 +353,8 @@ pub fn try_collect_filtered_opts(
             objects: &gix_object::find::Never,
             explicit_traversal_root,
             should_interrupt,
+            fs_monitor_changed_paths,
+            untracked_cache,
         },
     )
 }
@@ -357,6 +363,9 @@ pub struct Options<'a> {
     pub fresh_index: bool,
     pub git_dir: Option<&'a str>,
     pub should_interrupt: Option<&'a AtomicBool>,
+    pub fs_monitor_changed_paths: Option<&'a [BString]>,
+    pub untracked_cache: Option<walk::UntrackedCache<'a>>,
+    pub index_timestamp: Option<std::time::SystemTime>,
 }
 
 impl<'a> Options<'a> {
@@ -374,6 +383,9 @@ impl<'a> Default for Options<'a> {
             fresh_index: true,
             git_dir: None,
             should_interrupt: None,
+            fs_monitor_changed_paths: None,
+            untracked_cache: None,
+            index_timestamp: None,
         }
     }
 }
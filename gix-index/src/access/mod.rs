@@ -137,40 +137,59 @@ impl State {
     /// Return a data structure to help with case-insensitive lookups.
     ///
     /// It's required perform any case-insensitive lookup.
-    /// TODO: needs multi-threaded insertion, raw-table to have multiple locks depending on bucket.
     pub fn prepare_icase_backing(&self) -> AccelerateLookup<'_> {
+        self.prepare_icase_backing_with_thread_limit(Some(1))
+    }
+
+    /// Like [`prepare_icase_backing()`](Self::prepare_icase_backing()), but compute the hashes of entries and their directories
+    /// using up to `thread_limit` threads, or as many threads as there are logical cores if `None`.
+    ///
+    /// The result is the same as the one produced by a single thread, no matter how many threads are used.
+    pub fn prepare_icase_backing_with_thread_limit(&self, thread_limit: Option<usize>) -> AccelerateLookup<'_> {
         let _span = gix_features::trace::detail!("prepare_icase_backing", entries = self.entries.len());
         let mut out = AccelerateLookup::with_capacity(self.entries.len());
-        for entry in &self.entries {
-            let entry_path = entry.path(self);
-            let hash = AccelerateLookup::icase_hash(entry_path);
-            out.icase_entries
-                .insert_unique(hash, entry, |e| AccelerateLookup::icase_hash(e.path(self)));
-
-            let mut last_pos = entry_path.len();
-            while let Some(slash_idx) = entry_path[..last_pos].rfind_byte(b'/') {
-                let dir = entry_path[..slash_idx].as_bstr();
-                last_pos = slash_idx;
-                let dir_range = entry.path.start..(entry.path.start + dir.len());
-
-                let hash = AccelerateLookup::icase_hash(dir);
-                if out
-                    .icase_dirs
-                    .find(hash, |dir| {
-                        dir.path(self) == self.path_backing[dir_range.clone()].as_bstr()
+        let num_threads = gix_features::parallel::num_threads(thread_limit);
+        const MIN_ENTRIES_PER_THREAD: usize = 10_000;
+        if num_threads <= 1 || self.entries.len() < MIN_ENTRIES_PER_THREAD * 2 {
+            for entry in &self.entries {
+                let hash = AccelerateLookup::icase_hash(entry.path(self));
+                out.insert(self, entry, hash, self.icase_dir_hashes(entry));
+            }
+        } else {
+            let chunk_size =
+                ((self.entries.len() as f32 / num_threads as f32).ceil() as usize).max(MIN_ENTRIES_PER_THREAD);
+            let chunks: Vec<_> = gix_features::parallel::threads(|scope| {
+                let threads: Vec<_> = self
+                    .entries
+                    .chunks(chunk_size)
+                    .enumerate()
+                    .map(|(id, entries)| {
+                        gix_features::parallel::build_thread()
+                            .name(format!("gix-index.prepare_icase_backing.{id}"))
+                            .spawn_scoped(scope, move || {
+                                let mut hashes = Vec::with_capacity(entries.len());
+                                let mut dir_hashes = Vec::new();
+                                for entry in entries {
+                                    let start = dir_hashes.len();
+                                    dir_hashes.extend(self.icase_dir_hashes(entry));
+                                    hashes.push((
+                                        AccelerateLookup::icase_hash(entry.path(self)),
+                                        start..dir_hashes.len(),
+                                    ));
+                                }
+                                (entries, hashes, dir_hashes)
+                            })
+                            .expect("valid name")
                     })
-                    .is_none()
-                {
-                    out.icase_dirs.insert_unique(
-                        hash,
-                        crate::DirEntry {
-                            entry,
-                            dir_end: dir_range.end,
-                        },
-                        |dir| AccelerateLookup::icase_hash(dir.path(self)),
-                    );
-                } else {
-                    break;
+                    .collect();
+                threads
+                    .into_iter()
+                    .map(|thread| thread.join().expect("no panic in hashing"))
+                    .collect()
+            });
+            for (entries, hashes, dir_hashes) in chunks {
+                for (entry, (hash, dir_range)) in entries.iter().zip(hashes) {
+                    out.insert(self, entry, hash, dir_hashes[dir_range].iter().copied());
                 }
             }
         }
@@ -178,6 +197,21 @@ impl State {
         out
     }
 
+    /// Return the hashes of all leading directories of `entry`, along with the end of the directory in the path backing,
+    /// starting with the directory closest to `entry`.
+    fn icase_dir_hashes<'a>(&'a self, entry: &'a Entry) -> impl Iterator<Item = (u64, usize)> + 'a {
+        let entry_path = entry.path(self);
+        let mut last_pos = entry_path.len();
+        std::iter::from_fn(move || {
+            let slash_idx = entry_path[..last_pos].rfind_byte(b'/')?;
+            last_pos = slash_idx;
+            Some((
+                AccelerateLookup::icase_hash(entry_path[..slash_idx].as_bstr()),
+                entry.path.start + slash_idx,
+            ))
+        })
+    }
+
     /// Return the entry at `path` that is at the lowest available stage, using `lookup` for acceleration.
     /// It must have been created from this instance, and was ideally kept up-to-date with it.
     ///
@@ -390,6 +424,35 @@ impl State {
 }
 
 impl<'a> AccelerateLookup<'a> {
+    /// Insert `entry` with `hash` of its path, along with its leading directories as described by `dir_hashes`,
+    /// which are ordered from the innermost directory outwards.
+    fn insert(
+        &mut self,
+        state: &'a State,
+        entry: &'a Entry,
+        hash: u64,
+        dir_hashes: impl IntoIterator<Item = (u64, usize)>,
+    ) {
+        self.icase_entries
+            .insert_unique(hash, entry, |e| AccelerateLookup::icase_hash(e.path(state)));
+        for (hash, dir_end) in dir_hashes {
+            let dir_range = entry.path.start..dir_end;
+            if self
+                .icase_dirs
+                .find(hash, |dir| {
+                    dir.path(state) == state.path_backing[dir_range.clone()].as_bstr()
+                })
+                .is_some()
+            {
+                break;
+            }
+            self.icase_dirs
+                .insert_unique(hash, crate::DirEntry { entry, dir_end }, |dir| {
+                    AccelerateLookup::icase_hash(dir.path(state))
+                });
+        }
+    }
+
     fn with_capacity(cap: usize) -> Self {
         let ratio_of_entries_to_dirs_in_webkit = 20; // 400k entries and 20k dirs
         Self {
@@ -481,6 +544,9 @@ impl State {
         mode: entry::Mode,
         path: &BStr,
     ) {
+        if let Some(untracked) = self.untracked.as_mut() {
+            untracked.invalidate_path(path);
+        }
         let path = {
             let path_start = self.path_backing.len();
             self.path_backing.push_str(path);
@@ -494,6 +560,7 @@ impl State {
             mode,
             path,
         });
+        self.fs_monitor = None;
     }
 
     /// Unconditionally sort entries as needed to perform lookups quickly.
@@ -503,6 +570,7 @@ impl State {
             Entry::cmp_filepaths(a.path_in(path_backing), b.path_in(path_backing))
                 .then_with(|| a.stage().cmp(&b.stage()))
         });
+        self.fs_monitor = None;
     }
 
     /// Similar to [`sort_entries()`][State::sort_entries()], but applies `compare` after comparing
@@ -514,6 +582,7 @@ impl State {
                 .then_with(|| a.stage().cmp(&b.stage()))
                 .then_with(|| compare(a, b))
         });
+        self.fs_monitor = None;
     }
 
     /// Physically remove all entries for which `should_remove(idx, path, entry)` returns `true`, traversing them from first to last.
@@ -527,12 +596,21 @@ impl State {
     pub fn remove_entries(&mut self, mut should_remove: impl FnMut(usize, &BStr, &mut Entry) -> bool) {
         let mut index = 0;
         let paths = &self.path_backing;
+        let untracked = &mut self.untracked;
         self.entries.retain_mut(|e| {
             let path = e.path_in(paths);
             let res = !should_remove(index, path, e);
+            if !res {
+                if let Some(untracked) = untracked.as_mut() {
+                    untracked.invalidate_path(e.path_in(paths));
+                }
+            }
             index += 1;
             res
         });
+        if index != self.entries.len() {
+            self.fs_monitor = None;
+        }
     }
 }
 
@@ -554,10 +632,23 @@ impl State {
     pub fn untracked(&self) -> Option<&extension::UntrackedCache> {
         self.untracked.as_ref()
     }
+    /// Set or remove the untracked cache extension, which is written along with the index.
+    ///
+    /// Note that the directories containing entries that are added or removed are invalidated automatically.
+    pub fn set_untracked(&mut self, untracked: Option<extension::UntrackedCache>) {
+        self.untracked = untracked;
+    }
     /// Obtain the fsmonitor extension.
     pub fn fs_monitor(&self) -> Option<&extension::FsMonitor> {
         self.fs_monitor.as_ref()
     }
+    /// Set or remove the fsmonitor extension, which is written along with the index.
+    ///
+    /// Note that its bitmap must match the entries at the time of writing, which is why it's removed automatically
+    /// whenever entries are added, removed or sorted.
+    pub fn set_fs_monitor(&mut self, fs_monitor: Option<extension::FsMonitor>) {
+        self.fs_monitor = fs_monitor;
+    }
     /// Return `true` if the end-of-index extension was present when decoding this index.
     pub fn had_end_of_index_marker(&self) -> bool {
         self.end_of_index_at_decode_time
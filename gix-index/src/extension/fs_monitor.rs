@@ -5,15 +5,72 @@ use crate::{
     util::{read_u32, read_u64, split_at_byte_exclusive},
 };
 
-#[derive(Clone)]
-#[allow(dead_code)]
+/// The token a filesystem monitor hands out to identify the point in time at which its changes were last queried.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Token {
-    V1 { nanos_since_1970: u64 },
-    V2 { token: BString },
+    /// The token of the version 1 protocol, which is a timestamp.
+    V1 {
+        /// The amount of nanoseconds since the unix epoch.
+        nanos_since_1970: u64,
+    },
+    /// The token of the version 2 protocol, which is opaque to us.
+    V2 {
+        /// The token as returned by the filesystem monitor.
+        token: BString,
+    },
 }
 
+/// The signature of the filesystem monitor extension.
 pub const SIGNATURE: Signature = *b"FSMN";
 
+/// Lifecycle
+impl FsMonitor {
+    /// Create a new instance from the `token` of the last query, along with a bitmap in which each set bit marks the
+    /// entry at the same position as *not* being known to be valid.
+    pub fn new(token: Token, entry_dirty: gix_bitmap::ewah::Vec) -> Self {
+        FsMonitor { token, entry_dirty }
+    }
+}
+
+/// Access
+impl FsMonitor {
+    /// The token of the last query to the filesystem monitor.
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    /// A bitmap in which each set bit marks the entry at the same position as *not* being known to be valid.
+    pub fn entry_dirty(&self) -> &gix_bitmap::ewah::Vec {
+        &self.entry_dirty
+    }
+
+    /// Serialize this instance to `out`.
+    pub fn write_to(&self, mut out: impl std::io::Write) -> Result<(), std::io::Error> {
+        let mut data = Vec::new();
+        match &self.token {
+            Token::V1 { nanos_since_1970 } => {
+                data.extend_from_slice(&1_u32.to_be_bytes());
+                data.extend_from_slice(&nanos_since_1970.to_be_bytes());
+            }
+            Token::V2 { token } => {
+                data.extend_from_slice(&2_u32.to_be_bytes());
+                data.extend_from_slice(token);
+                data.push(0);
+            }
+        }
+        let mut bitmap = Vec::new();
+        self.entry_dirty.write_to(&mut bitmap)?;
+        data.extend_from_slice(&u32::try_from(bitmap.len()).expect("less than 4GB bitmap").to_be_bytes());
+        data.extend_from_slice(&bitmap);
+
+        out.write_all(&SIGNATURE)?;
+        out.write_all(&(u32::try_from(data.len()).expect("less than 4GB extension")).to_be_bytes())?;
+        out.write_all(&data)?;
+        Ok(())
+    }
+}
+
+/// Decode the filesystem monitor extension from `data`, or return `None` if it is malformed.
 pub fn decode(data: &[u8]) -> Option<FsMonitor> {
     let (version, data) = read_u32(data)?;
     let (token, data) = match version {
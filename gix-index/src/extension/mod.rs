@@ -42,8 +42,7 @@ pub struct Link {
 }
 
 /// The extension for untracked files.
-#[allow(dead_code)]
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct UntrackedCache {
     /// Something identifying the location and machine that this cache is for.
     /// Should the repository be copied to a different machine, the entire cache can immediately be invalidated.
@@ -54,6 +53,7 @@ pub struct UntrackedCache {
     excludes_file: Option<untracked_cache::OidStat>,
     /// Usually `.gitignore`
     exclude_filename_per_dir: BString,
+    /// Flags describing how the directory walk that produced this cache was configured, see [`untracked_cache::dir_flags`].
     dir_flags: u32,
 
     /// A list of directories and sub-directories, with `directories[0]` being the root.
@@ -61,7 +61,6 @@ pub struct UntrackedCache {
 }
 
 /// The extension for keeping state on recent information provided by the filesystem monitor.
-#[allow(dead_code)]
 #[derive(Clone)]
 pub struct FsMonitor {
     token: fs_monitor::Token,
@@ -71,7 +70,9 @@ pub struct FsMonitor {
 
 mod iter;
 
-pub(crate) mod fs_monitor;
+///
+#[allow(clippy::empty_docs)]
+pub mod fs_monitor;
 
 ///
 #[allow(clippy::empty_docs)]
@@ -1,4 +1,4 @@
-use bstr::BString;
+use bstr::{BStr, BString, ByteSlice};
 use gix_hash::ObjectId;
 
 use crate::{
@@ -8,7 +8,7 @@ use crate::{
 };
 
 /// A structure to track filesystem stat information along with an object id, linking a worktree file with what's in our ODB.
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct OidStat {
     /// The file system stat information
     pub stat: entry::Stat,
@@ -16,27 +16,245 @@ pub struct OidStat {
     pub id: ObjectId,
 }
 
+impl OidStat {
+    /// Files that don't exist are stored with default stat information and a null-id.
+    fn into_option(self) -> Option<Self> {
+        (!self.id.is_null() || self.stat != entry::Stat::default()).then_some(self)
+    }
+}
+
 /// A directory with information about its untracked files, and its sub-directories
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Directory {
     /// The directories name, or an empty string if this is the root directory.
     pub name: BString,
-    /// Untracked files and directory names
+    /// Untracked files and directory names, with directories having a trailing slash.
     pub untracked_entries: Vec<BString>,
     /// indices for sub-directories similar to this one.
     pub sub_directories: Vec<usize>,
 
-    /// The directories stat data, if available or valid // TODO: or is it the exclude file?
+    /// The directories stat data at the time it was last read, or `None` if the cached information isn't valid
+    /// and the directory has to be read again.
     pub stat: Option<entry::Stat>,
     /// The oid of a .gitignore file, if it exists
     pub exclude_file_oid: Option<ObjectId>,
-    /// TODO: figure out what this really does
+    /// If `true`, the directory was only read to learn if it contains any untracked file, so its `untracked_entries`
+    /// may be incomplete.
     pub check_only: bool,
 }
 
+/// Flags that are stored in the untracked cache to describe the directory walk that produced it.
+pub mod dir_flags {
+    /// Untracked directories are shown as directory instead of listing all of their untracked files.
+    pub const SHOW_OTHER_DIRECTORIES: u32 = 1 << 1;
+    /// Directories without untracked files aren't shown.
+    pub const HIDE_EMPTY_DIRECTORIES: u32 = 1 << 2;
+}
+
 /// Only used as an indicator
 pub const SIGNATURE: Signature = *b"UNTR";
 
+/// Lifecycle
+impl UntrackedCache {
+    /// Create a new and empty cache for the machine and location described by `identifier`, along with the state of the
+    /// `info_exclude` and `excludes_file` files, the name of the per-directory exclude files like `.gitignore`
+    /// in `exclude_filename_per_dir` and `dir_flags` (see [`dir_flags`]) to describe the directory walk.
+    pub fn new(
+        identifier: impl Into<BString>,
+        info_exclude: Option<OidStat>,
+        excludes_file: Option<OidStat>,
+        exclude_filename_per_dir: impl Into<BString>,
+        dir_flags: u32,
+    ) -> Self {
+        UntrackedCache {
+            identifier: identifier.into(),
+            info_exclude,
+            excludes_file,
+            exclude_filename_per_dir: exclude_filename_per_dir.into(),
+            dir_flags,
+            directories: Vec::new(),
+        }
+    }
+}
+
+/// Access
+impl UntrackedCache {
+    /// Something identifying the location and machine that this cache is for.
+    pub fn identifier(&self) -> &BStr {
+        self.identifier.as_ref()
+    }
+
+    /// The state of the `.git/info/exclude` file at the time the cache was created, if it existed.
+    pub fn info_exclude(&self) -> Option<&OidStat> {
+        self.info_exclude.as_ref()
+    }
+
+    /// The state of the `core.excludesFile` file at the time the cache was created, if it existed.
+    pub fn excludes_file(&self) -> Option<&OidStat> {
+        self.excludes_file.as_ref()
+    }
+
+    /// The name of the exclude file that can be present in each directory, usually `.gitignore`.
+    pub fn exclude_filename_per_dir(&self) -> &BStr {
+        self.exclude_filename_per_dir.as_ref()
+    }
+
+    /// Flags describing the directory walk, see [`dir_flags`].
+    pub fn dir_flags(&self) -> u32 {
+        self.dir_flags
+    }
+
+    /// All directories, with `directories()[0]` being the root directory if there is any, and the others being
+    /// reachable through [`Directory::sub_directories`].
+    pub fn directories(&self) -> &[Directory] {
+        &self.directories
+    }
+
+    /// Mutable access to all directories, to be able to alter or replace them.
+    ///
+    /// Note that the first directory must be the root directory, and that only directories reachable from it
+    /// will be written.
+    pub fn directories_mut(&mut self) -> &mut Vec<Directory> {
+        &mut self.directories
+    }
+
+    /// Mark all directories leading to the directory containing `rela_path` as invalid, so they are read again.
+    ///
+    /// This must be called whenever `rela_path` was added to or removed from the index as untracked files are
+    /// only recorded in the cache while the directories themselves didn't change.
+    pub fn invalidate_path(&mut self, rela_path: &BStr) {
+        if self.directories.is_empty() {
+            return;
+        }
+        let mut dir_index = 0;
+        let mut components = rela_path.split_str("/").peekable();
+        while let Some(component) = components.next() {
+            self.directories[dir_index].stat = None;
+            if components.peek().is_none() {
+                break;
+            }
+            let dir = &self.directories[dir_index];
+            match dir
+                .sub_directories
+                .iter()
+                .copied()
+                .find(|idx| self.directories[*idx].name == component)
+            {
+                Some(subdir_index) => dir_index = subdir_index,
+                None => break,
+            }
+        }
+    }
+
+    /// Serialize this instance to `out`, using `object_hash` to write null-ids for files that don't exist.
+    pub fn write_to(&self, mut out: impl std::io::Write, object_hash: gix_hash::Kind) -> Result<(), std::io::Error> {
+        let mut data = Vec::new();
+        write_var_int(&mut data, self.identifier.len() as u64);
+        data.extend_from_slice(&self.identifier);
+        let null_oid_stat = OidStat {
+            stat: Default::default(),
+            id: object_hash.null(),
+        };
+        for oid_stat in [&self.info_exclude, &self.excludes_file] {
+            let oid_stat = oid_stat.as_ref().unwrap_or(&null_oid_stat);
+            write_stat(&mut data, &oid_stat.stat);
+            data.extend_from_slice(oid_stat.id.as_bytes());
+        }
+        data.extend_from_slice(&self.dir_flags.to_be_bytes());
+        data.extend_from_slice(&self.exclude_filename_per_dir);
+        data.push(0);
+
+        if self.directories.is_empty() {
+            write_var_int(&mut data, 0);
+        } else {
+            let mut ordered = Vec::with_capacity(self.directories.len());
+            let mut blocks = Vec::new();
+            self.write_directory_block(0, &mut blocks, &mut ordered);
+            write_var_int(&mut data, ordered.len() as u64);
+            data.extend_from_slice(&blocks);
+
+            let mut valid = gix_bitmap::ewah::Vec::default();
+            let mut check_only = gix_bitmap::ewah::Vec::default();
+            let mut hash_valid = gix_bitmap::ewah::Vec::default();
+            let mut stats = Vec::new();
+            let mut hashes = Vec::new();
+            for (bit, dir) in ordered.iter().map(|idx| &self.directories[*idx]).enumerate() {
+                if let Some(stat) = &dir.stat {
+                    valid.set(bit);
+                    write_stat(&mut stats, stat);
+                }
+                if dir.check_only {
+                    check_only.set(bit);
+                }
+                if let Some(id) = &dir.exclude_file_oid {
+                    hash_valid.set(bit);
+                    hashes.extend_from_slice(id.as_bytes());
+                }
+            }
+            for bitmap in [valid, check_only, hash_valid] {
+                bitmap.write_to(&mut data)?;
+            }
+            data.extend_from_slice(&stats);
+            data.extend_from_slice(&hashes);
+            data.push(0);
+        }
+
+        out.write_all(&SIGNATURE)?;
+        out.write_all(&(u32::try_from(data.len()).expect("less than 4GB extension")).to_be_bytes())?;
+        out.write_all(&data)?;
+        Ok(())
+    }
+
+    /// Write the directory at `index` and all of its sub-directories depth-first, recording the order in `ordered`.
+    fn write_directory_block(&self, index: usize, out: &mut Vec<u8>, ordered: &mut Vec<usize>) {
+        let dir = &self.directories[index];
+        ordered.push(index);
+        write_var_int(out, dir.untracked_entries.len() as u64);
+        write_var_int(out, dir.sub_directories.len() as u64);
+        out.extend_from_slice(&dir.name);
+        out.push(0);
+        for entry in &dir.untracked_entries {
+            out.extend_from_slice(entry);
+            out.push(0);
+        }
+        for subdir_index in &dir.sub_directories {
+            self.write_directory_block(*subdir_index, out, ordered);
+        }
+    }
+}
+
+fn write_var_int(out: &mut Vec<u8>, mut n: u64) {
+    let mut buf = [0u8; 10];
+    let mut pos = buf.len() - 1;
+    buf[pos] = n as u8 & 0b0111_1111;
+    loop {
+        n >>= 7;
+        if n == 0 {
+            break;
+        }
+        n -= 1;
+        pos -= 1;
+        buf[pos] = 0b1000_0000 | (n as u8 & 0b0111_1111);
+    }
+    out.extend_from_slice(&buf[pos..]);
+}
+
+fn write_stat(out: &mut Vec<u8>, stat: &entry::Stat) {
+    for field in [
+        stat.ctime.secs,
+        stat.ctime.nsecs,
+        stat.mtime.secs,
+        stat.mtime.nsecs,
+        stat.dev,
+        stat.ino,
+        stat.uid,
+        stat.gid,
+        stat.size,
+    ] {
+        out.extend_from_slice(&field.to_be_bytes());
+    }
+}
+
 // #[allow(unused)]
 /// Decode an untracked cache extension from `data`, assuming object hashes are of type `object_hash`.
 pub fn decode(data: &[u8], object_hash: gix_hash::Kind) -> Option<UntrackedCache> {
@@ -56,8 +274,8 @@ pub fn decode(data: &[u8], object_hash: gix_hash::Kind) -> Option<UntrackedCache
 
     let mut res = UntrackedCache {
         identifier: identifier.into(),
-        info_exclude: (!info_exclude.id.is_null()).then_some(info_exclude),
-        excludes_file: (!excludes_file.id.is_null()).then_some(excludes_file),
+        info_exclude: info_exclude.into_option(),
+        excludes_file: excludes_file.into_option(),
         exclude_filename_per_dir: exclude_filename_per_dir.into(),
         dir_flags,
         directories: Vec::new(),
@@ -19,6 +19,10 @@ pub enum Extensions {
         tree_cache: bool,
         /// Write the end-of-index-entry extension.
         end_of_index_entry: bool,
+        /// Write the untracked cache extension, if present.
+        untracked_cache: bool,
+        /// Write the filesystem monitor extension, if present.
+        fs_monitor: bool,
     },
     /// Write no optional extension at all for what should be the smallest possible index
     None,
@@ -33,9 +37,13 @@ impl Extensions {
             Extensions::Given {
                 tree_cache,
                 end_of_index_entry,
+                untracked_cache,
+                fs_monitor,
             } => match signature {
                 extension::tree::SIGNATURE => tree_cache,
                 extension::end_of_index_entry::SIGNATURE => end_of_index_entry,
+                extension::untracked_cache::SIGNATURE => untracked_cache,
+                extension::fs_monitor::SIGNATURE => fs_monitor,
                 _ => &false,
             }
             .then(|| signature),
@@ -117,6 +125,23 @@ impl State {
                     .should_write(extension::tree::SIGNATURE)
                     .and_then(|signature| self.tree().map(|tree| tree.write_to(write).map(|_| signature)))
             },
+            &|write| {
+                extensions
+                    .should_write(extension::untracked_cache::SIGNATURE)
+                    .and_then(|signature| {
+                        self.untracked()
+                            .map(|untracked| untracked.write_to(write, self.object_hash).map(|_| signature))
+                    })
+            },
+            &|write| {
+                extensions
+                    .should_write(extension::fs_monitor::SIGNATURE)
+                    .filter(|_| !self.entries().iter().any(|e| e.flags.contains(entry::Flags::REMOVE)))
+                    .and_then(|signature| {
+                        self.fs_monitor()
+                            .map(|fs_monitor| fs_monitor.write_to(write).map(|_| signature))
+                    })
+            },
             &|write| {
                 self.is_sparse()
                     .then(|| extension::sparse::write_to(write).map(|_| extension::sparse::SIGNATURE))
@@ -125,6 +125,44 @@ fn ignorecase_clashes_and_order() {
     );
 }
 
+#[test]
+fn icase_backing_is_the_same_no_matter_how_many_threads_are_used() {
+    let mut file = icase_fixture();
+    let entry = file.entry(0).clone();
+    for dir in 0..50 {
+        for name in 0..500 {
+            file.dangerously_push_entry(
+                entry.stat,
+                entry.id,
+                entry.flags,
+                entry.mode,
+                format!("Dir{dir}/sub/File{name}").as_str().into(),
+            );
+        }
+    }
+    file.sort_entries();
+
+    let serial = file.prepare_icase_backing();
+    let parallel = file.prepare_icase_backing_with_thread_limit(Some(4));
+    for entry in file.entries() {
+        let entry_path = entry.path(&file);
+        let upper: BString = entry_path.to_ascii_uppercase().into();
+        assert_eq!(
+            file.entry_by_path_icase(upper.as_bstr(), true, &parallel),
+            file.entry_by_path_icase(upper.as_bstr(), true, &serial),
+            "{entry_path}: icase clashes are resolved in the same way"
+        );
+        if let Some(slash_idx) = entry_path.rfind_byte(b'/') {
+            let dir: BString = entry_path[..slash_idx].to_ascii_lowercase().into();
+            assert_eq!(
+                file.entry_closest_to_directory_icase(dir.as_bstr(), true, &parallel),
+                file.entry_closest_to_directory_icase(dir.as_bstr(), true, &serial),
+                "{dir}: the first entry of each directory is the same"
+            );
+        }
+    }
+}
+
 #[test]
 fn prefixed_entries_icase_with_name_clashes() {
     let file = icase_fixture();
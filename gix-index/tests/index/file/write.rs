@@ -15,6 +15,35 @@ fn roundtrips() -> crate::Result {
             options_with(write::Extensions::Given {
                 tree_cache: true,
                 end_of_index_entry: true,
+                untracked_cache: false,
+                fs_monitor: false,
+            }),
+        ),
+        (
+            Loose("FSMN"),
+            options_with(write::Extensions::Given {
+                tree_cache: true,
+                end_of_index_entry: false,
+                untracked_cache: false,
+                fs_monitor: true,
+            }),
+        ),
+        (
+            Loose("UNTR"),
+            options_with(write::Extensions::Given {
+                tree_cache: true,
+                end_of_index_entry: false,
+                untracked_cache: true,
+                fs_monitor: false,
+            }),
+        ),
+        (
+            Loose("UNTR-with-oids"),
+            options_with(write::Extensions::Given {
+                tree_cache: true,
+                end_of_index_entry: false,
+                untracked_cache: true,
+                fs_monitor: false,
             }),
         ),
         (Generated("V2_empty"), only_tree_ext()),
@@ -149,10 +178,14 @@ fn state_comparisons_with_various_extension_configurations() {
             options_with(write::Extensions::Given {
                 tree_cache: true,
                 end_of_index_entry: false,
+                untracked_cache: false,
+                fs_monitor: false,
             }),
             options_with(write::Extensions::Given {
                 tree_cache: false,
                 end_of_index_entry: true,
+                untracked_cache: false,
+                fs_monitor: false,
             }),
         ] {
             let expected = fixture.open();
@@ -168,6 +201,139 @@ fn state_comparisons_with_various_extension_configurations() {
     }
 }
 
+#[test]
+fn fs_monitor_extension_roundtrips_with_new_tokens_and_bitmaps() -> crate::Result {
+    let mut index = Loose("FSMN").open();
+    let token = index.fs_monitor().expect("present").token().clone();
+    assert_eq!(
+        token,
+        extension::fs_monitor::Token::V2 {
+            token: "1642331326943378000".into()
+        }
+    );
+
+    let expected_dirty: Vec<_> = (0..64).chain([200, 201, 250]).chain(300..428).collect();
+    for token in [
+        extension::fs_monitor::Token::V1 { nanos_since_1970: 42 },
+        extension::fs_monitor::Token::V2 { token: "token".into() },
+    ] {
+        let mut bitmap = gix_bitmap::ewah::Vec::default();
+        for idx in &expected_dirty {
+            bitmap.set(*idx);
+        }
+        index.set_fs_monitor(Some(extension::FsMonitor::new(token.clone(), bitmap)));
+
+        let mut buf = Vec::new();
+        index.write_to(&mut buf, Default::default())?;
+        let (actual, _) = State::from_bytes(&buf, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+        let fs_monitor = actual.fs_monitor().expect("written and read back");
+        assert_eq!(fs_monitor.token(), &token);
+        let mut actual_dirty = Vec::new();
+        fs_monitor.entry_dirty().for_each_set_bit(|idx| {
+            actual_dirty.push(idx);
+            Some(())
+        });
+        assert_eq!(actual_dirty, expected_dirty);
+        assert_eq!(fs_monitor.entry_dirty().num_bits(), 428);
+    }
+
+    index.remove_entries(|idx, _, _| idx == 0);
+    assert!(
+        index.fs_monitor().is_none(),
+        "the bitmap can't be valid anymore once entries are removed"
+    );
+    Ok(())
+}
+
+#[test]
+fn untracked_cache_is_invalidated_along_the_path_of_changed_entries() {
+    let mut index = Loose("UNTR").open();
+    let valid_dirs = |index: &State| -> Vec<_> {
+        index
+            .untracked()
+            .expect("present")
+            .directories()
+            .iter()
+            .filter(|dir| dir.stat.is_some())
+            .map(|dir| dir.name.to_string())
+            .collect()
+    };
+    assert_eq!(valid_dirs(&index), ["", "done", "dthree", "dtwo"]);
+
+    index.remove_entries(|_, path, _| path == "done/one");
+    assert_eq!(
+        valid_dirs(&index),
+        ["dthree", "dtwo"],
+        "the directory of the removed file and all of its parents have to be read again"
+    );
+
+    let entry = index.entries()[0].clone();
+    index.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, "dtwo/new".into());
+    assert_eq!(valid_dirs(&index), ["dthree"]);
+}
+
+#[test]
+fn untracked_cache_roundtrips_with_new_directories() -> crate::Result {
+    let mut index = Loose("UNTR-with-oids").open();
+    let mut untracked = extension::UntrackedCache::new(
+        "identifier\0",
+        Some(extension::untracked_cache::OidStat {
+            stat: entry::Stat {
+                size: 42,
+                ..Default::default()
+            },
+            id: gix_hash::ObjectId::empty_blob(gix_hash::Kind::Sha1),
+        }),
+        None,
+        ".gitignore",
+        extension::untracked_cache::dir_flags::SHOW_OTHER_DIRECTORIES
+            | extension::untracked_cache::dir_flags::HIDE_EMPTY_DIRECTORIES,
+    );
+    let dir = |name: &str, untracked_entries: &[&str], sub_directories: Vec<usize>, valid: bool| {
+        extension::untracked_cache::Directory {
+            name: name.into(),
+            untracked_entries: untracked_entries.iter().map(|e| (*e).into()).collect(),
+            sub_directories,
+            stat: valid.then(|| entry::Stat {
+                mtime: entry::stat::Time { secs: 1, nsecs: 2 },
+                ctime: entry::stat::Time { secs: 3, nsecs: 4 },
+                ..Default::default()
+            }),
+            exclude_file_oid: valid.then(|| gix_hash::ObjectId::empty_tree(gix_hash::Kind::Sha1)),
+            check_only: !valid,
+        }
+    };
+    *untracked.directories_mut() = vec![
+        dir("", &["a", "b/"], vec![2, 1], true),
+        dir("d2", &[], vec![], false),
+        dir("d1", &["c"], vec![3], true),
+        dir("sub", &[], vec![], true),
+    ];
+    index.set_untracked(Some(untracked));
+
+    let mut buf = Vec::new();
+    index.write_to(&mut buf, Default::default())?;
+    let (actual, _) = State::from_bytes(&buf, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+    let actual = actual.untracked().expect("written and read back");
+    let expected = index.untracked().expect("set");
+    assert_eq!(actual.identifier(), expected.identifier());
+    assert_eq!(actual.info_exclude(), expected.info_exclude());
+    assert_eq!(actual.excludes_file(), None);
+    assert_eq!(actual.dir_flags(), expected.dir_flags());
+    assert_eq!(actual.exclude_filename_per_dir(), ".gitignore");
+    assert_eq!(
+        actual.directories(),
+        [
+            dir("", &["a", "b/"], vec![1, 3], true),
+            dir("d1", &["c"], vec![2], true),
+            dir("sub", &[], vec![], true),
+            dir("d2", &[], vec![], false),
+        ],
+        "directories are written depth-first, which may change their order"
+    );
+    Ok(())
+}
+
 #[test]
 fn extended_flags_automatically_upgrade_the_version_to_avoid_data_loss() -> crate::Result {
     let mut expected = Generated("v2").open();
@@ -292,6 +458,8 @@ fn only_tree_ext() -> Options {
     Options {
         extensions: write::Extensions::Given {
             end_of_index_entry: false,
+            untracked_cache: false,
+            fs_monitor: false,
             tree_cache: true,
         },
         skip_hash: false,
@@ -0,0 +1,102 @@
+mod error {
+    use bstr::BString;
+
+    /// The error returned by [`bundle_uri()`][crate::bundle_uri()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        Transport(#[from] gix_transport::client::Error),
+        #[error(transparent)]
+        DecodePacketline(#[from] gix_transport::packetline::decode::Error),
+        #[error("{0:?} could not be parsed. A bundle-uri line should be '<key>=<value>'.")]
+        MalformedLine(BString),
+    }
+
+    impl gix_transport::IsSpuriousError for Error {
+        fn is_spurious(&self) -> bool {
+            match self {
+                Error::Io(err) => err.is_spurious(),
+                Error::Transport(err) => err.is_spurious(),
+                _ => false,
+            }
+        }
+    }
+}
+pub use error::Error;
+
+/// Parse a single `line` of a `bundle-uri` response into its `(key, value)` pair.
+pub fn parse_line(line: &bstr::BStr) -> Result<(bstr::BString, bstr::BString), Error> {
+    use bstr::ByteSlice;
+    let line = line.trim_end_with(|c| c == '\n');
+    match line.find_byte(b'=') {
+        Some(pos) if pos > 0 => Ok((line[..pos].into(), line[pos + 1..].into())),
+        _ => Err(Error::MalformedLine(line.into())),
+    }
+}
+
+pub(crate) mod function {
+    use bstr::BString;
+    use gix_transport::client::{Capabilities, Transport, TransportV2Ext};
+    use maybe_async::maybe_async;
+
+    use super::{parse_line, Error};
+    use crate::Command;
+
+    /// Invoke a `bundle-uri` V2 command on `transport`, which requires a prior handshake that yielded
+    /// server `capabilities` which advertise the `bundle-uri` command.
+    /// Return all `(key, value)` pairs sent by the server, in order, which is typically a bundle list
+    /// with keys like `bundle.version` or `bundle.<id>.uri`.
+    /// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+    #[maybe_async]
+    pub async fn bundle_uri(
+        mut transport: impl Transport,
+        capabilities: &Capabilities,
+        trace: bool,
+    ) -> Result<Vec<(BString, BString)>, Error> {
+        let _span = gix_features::trace::detail!("gix_protocol::bundle_uri()", capabilities = ?capabilities);
+        let bundle_uri = Command::BundleUri;
+        let features = bundle_uri.default_features(gix_transport::Protocol::V2, capabilities);
+        let mut lines = transport
+            .invoke(
+                bundle_uri.as_str(),
+                features.into_iter(),
+                None::<std::iter::Empty<BString>>,
+                trace,
+            )
+            .await?;
+        let mut out = Vec::new();
+        while let Some(line) = lines
+            .readline()
+            .await
+            .transpose()?
+            .transpose()?
+            .and_then(|l| l.as_bstr())
+        {
+            out.push(parse_line(line)?);
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_line;
+
+    #[test]
+    fn key_value_lines() {
+        assert_eq!(
+            parse_line("bundle.version=1\n".into()).expect("valid"),
+            ("bundle.version".into(), "1".into())
+        );
+        assert_eq!(
+            parse_line("bundle.one.uri=https://example.com/a=b".into()).expect("valid"),
+            ("bundle.one.uri".into(), "https://example.com/a=b".into()),
+            "only the first separator counts"
+        );
+        assert!(parse_line("no-separator".into()).is_err());
+        assert!(parse_line("=value".into()).is_err(), "keys must not be empty");
+    }
+}
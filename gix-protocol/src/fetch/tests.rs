@@ -408,6 +408,29 @@ mod arguments {
 000eofs-delta
 001dwant-ref refs/heads/main
 0009done
+0000"
+                    .as_bstr()
+            )
+        }
+
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn packfile_uris() {
+            let mut out = Vec::new();
+            let mut t = transport(&mut out, false);
+            let mut arguments = arguments_v2(["packfile-uris"].iter().copied());
+            assert!(arguments.can_use_packfile_uris());
+
+            arguments.use_packfile_uris(["https", "file"]);
+            arguments.want(gix_hash::ObjectId::null(gix_hash::Kind::Sha1));
+            arguments.send(&mut t, true).await.expect("sending to buffer to work");
+            assert_eq!(
+                out.as_bstr(),
+                b"0012command=fetch
+0001000ethin-pack
+000eofs-delta
+001dpackfile-uris https,file
+0032want 0000000000000000000000000000000000000000
+0009done
 0000"
                     .as_bstr()
             )
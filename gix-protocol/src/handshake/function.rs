@@ -45,10 +45,22 @@ where
                 drop(result); // needed to workaround this: https://github.com/rust-lang/rust/issues/76149
                 let url = transport.to_url().into_owned();
                 progress.set_name("authentication".into());
-                let credentials::protocol::Outcome { identity, next } =
-                    authenticate(credentials::helper::Action::get_for_url(url.clone()))?
-                        .ok_or(Error::EmptyCredentials)?;
-                transport.set_identity(identity)?;
+                let mut action = credentials::helper::Action::get_for_url(url.clone());
+                if let Some(ctx) = action.context_mut() {
+                    ctx.capability = vec!["authtype".into()];
+                    ctx.wwwauth = transport.authentication_challenges().to_owned();
+                }
+                let credentials::protocol::Outcome {
+                    identity,
+                    credential,
+                    next,
+                } = authenticate(action)?.ok_or(Error::EmptyCredentials)?;
+                match credential {
+                    Some(credentials::protocol::Credential { authtype, credential }) => {
+                        transport.set_credential(authtype, credential)?
+                    }
+                    None => transport.set_identity(identity)?,
+                }
                 progress.step();
                 progress.set_name("handshake (authenticated)".into());
                 match transport.handshake(service, &extra_parameters).await {
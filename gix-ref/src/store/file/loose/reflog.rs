@@ -81,8 +81,38 @@ impl file::Store {
         let (base, rela_path) = self.reflog_base_and_relative_path(name);
         base.join(rela_path)
     }
+
+    /// Copy the reflog of `source` to the location of the reflog of `destination`, replacing what's there, unless reflogs are disabled.
+    /// The copy is written to a lock file first, which is then moved into place.
+    pub(in crate::store_impl::file) fn reflog_copy(
+        &self,
+        source: &FullNameRef,
+        destination: &FullNameRef,
+    ) -> std::io::Result<()> {
+        if self.write_reflog == file::WriteReflog::Disable {
+            return Ok(());
+        }
+        let destination = self.reflog_path(destination);
+        gix_tempfile::create_dir::all(
+            destination.parent().expect("always with parent directory"),
+            Default::default(),
+        )?;
+        if destination.is_dir() {
+            gix_tempfile::remove_dir::empty_depth_first(destination.clone())?;
+        }
+        let mut lock =
+            gix_lock::File::acquire_to_update_resource(&destination, gix_lock::acquire::Fail::Immediately, None)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+        std::io::copy(&mut std::fs::File::open(self.reflog_path(source))?, &mut lock)?;
+        lock.commit().map_err(|err| err.error)?;
+        Ok(())
+    }
 }
 
+///
+#[allow(clippy::empty_docs)]
+pub mod expire;
+
 ///
 #[allow(clippy::empty_docs)]
 pub mod create_or_update {
@@ -0,0 +1,110 @@
+use gix_actor::date::SecondsSinceUnixEpoch;
+
+use crate::{
+    store_impl::{file, file::log},
+    FullNameRef,
+};
+
+/// Options for use in [`file::Store::reflog_expire()`].
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Options {
+    /// Remove all entries older than the given time in seconds since the unix epoch, or keep them if `None`.
+    ///
+    /// This is what `gc.reflogExpire` controls.
+    pub expire: Option<SecondsSinceUnixEpoch>,
+    /// Remove all entries older than the given time in seconds since the unix epoch if the object they point to isn't
+    /// reachable from the current tip of the reference, or keep them if `None`.
+    ///
+    /// This is what `gc.reflogExpireUnreachable` controls.
+    pub expire_unreachable: Option<SecondsSinceUnixEpoch>,
+    /// If `true`, set the previous object id of each kept entry to the new object id of the kept entry before it,
+    /// so the log remains a consistent chain of changes, like `git reflog expire --rewrite` does.
+    pub rewrite: bool,
+}
+
+/// The outcome of [`file::Store::reflog_expire()`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of entries that were removed.
+    pub num_removed: usize,
+    /// The amount of entries that remain in the log.
+    pub num_kept: usize,
+}
+
+/// The error returned by [`file::Store::reflog_expire()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The lock for the reflog file could not be obtained")]
+    LockAcquire(#[from] gix_lock::acquire::Error),
+    #[error("Could not read or write the reflog file")]
+    Io(#[from] std::io::Error),
+    #[error("The reflog could not be parsed")]
+    Decode(#[from] log::iter::decode::Error),
+    #[error("The rewritten reflog could not be put into place")]
+    Commit(#[source] std::io::Error),
+}
+
+impl file::Store {
+    /// Remove entries from the reflog of the reference `name` according to `options`, and return how many were removed and kept.
+    ///
+    /// `is_reachable(id)` is called to learn if the object `id` of an entry is reachable from the tip of the reference,
+    /// but only for entries that are old enough to be expired if they are unreachable.
+    /// `lock_fail_mode` determines how to proceed if the reflog is currently locked.
+    ///
+    /// If the reflog doesn't exist, nothing happens. Note that the reflog file is only rewritten if entries were actually removed
+    /// or changed, in which case all kept entries are written back as they were parsed.
+    pub fn reflog_expire(
+        &self,
+        name: &FullNameRef,
+        options: Options,
+        lock_fail_mode: gix_lock::acquire::Fail,
+        is_reachable: &mut dyn FnMut(&gix_hash::oid) -> bool,
+    ) -> Result<Outcome, Error> {
+        let path = self.reflog_path(name);
+        if !path.is_file() {
+            return Ok(Outcome::default());
+        }
+        let mut lock = gix_lock::File::acquire_to_update_resource(&path, lock_fail_mode, None)?;
+        let content = std::fs::read(&path)?;
+
+        let Options {
+            expire,
+            expire_unreachable,
+            rewrite,
+        } = options;
+        let mut out = Outcome::default();
+        let mut needs_rewrite = false;
+        let mut kept = Vec::new();
+        for line in log::iter::forward(&content) {
+            let line = line?;
+            let time = line.signature.time.seconds;
+            let new_oid = line.new_oid();
+            let is_expired = expire.map_or(false, |expire| time < expire)
+                || expire_unreachable.map_or(false, |expire| time < expire && !is_reachable(&new_oid));
+            if is_expired {
+                out.num_removed += 1;
+                needs_rewrite = true;
+                continue;
+            }
+            let mut line = line.to_owned();
+            if rewrite {
+                let previous_oid = kept
+                    .last()
+                    .map_or_else(|| new_oid.kind().null(), |previous: &crate::log::Line| previous.new_oid);
+                needs_rewrite |= line.previous_oid != previous_oid;
+                line.previous_oid = previous_oid;
+            }
+            kept.push(line);
+        }
+        out.num_kept = kept.len();
+
+        if needs_rewrite {
+            for line in &kept {
+                line.write_to(&mut lock)?;
+            }
+            lock.commit().map_err(|err| Error::Commit(err.error))?;
+        }
+        Ok(out)
+    }
+}
@@ -90,6 +90,9 @@ pub struct Transaction<'s, 'p> {
     packed_transaction: Option<crate::store_impl::packed::Transaction>,
     updates: Option<Vec<transaction::Edit>>,
     packed_refs: transaction::PackedRefs<'p>,
+    /// Pairs of `(destination, source)` names, where the reflog of `source` is to be copied to `destination` before
+    /// `destination` receives its own log entry.
+    reflogs_to_copy: Vec<(crate::FullName, crate::FullName)>,
 }
 
 pub(in crate::store_impl::file) fn path_to_name<'a>(path: impl Into<Cow<'a, Path>>) -> Cow<'a, BStr> {
@@ -17,6 +17,7 @@ impl<'s, 'p> Transaction<'s, 'p> {
     ///
     /// In this stage, we perform the following operations:
     ///
+    /// * copy the ref log of renamed or copied references
     /// * update the ref log
     /// * move updated refs into place
     /// * delete reflogs and empty parent directories
@@ -47,6 +48,18 @@ impl<'s, 'p> Transaction<'s, 'p> {
                         RefLog::Only => (false, true),
                         RefLog::AndReference => (true, true),
                     };
+                    let reflog_source = self
+                        .reflogs_to_copy
+                        .iter()
+                        .find_map(|(destination, source)| (*destination == change.update.name).then_some(source));
+                    if let Some(source) = reflog_source.filter(|_| update_reflog) {
+                        self.store
+                            .reflog_copy(source.as_ref(), change.update.name.as_ref())
+                            .map_err(|err| Error::CopyReflog {
+                                source: err,
+                                full_name: change.name(),
+                            })?;
+                    }
                     if update_reflog {
                         let log_update = match new {
                             Target::Symbolic(_) => {
@@ -72,7 +85,11 @@ impl<'s, 'p> Transaction<'s, 'p> {
                             }
                         };
                         if let Some((previous, new_oid)) = log_update {
-                            let do_update = previous.as_ref().map_or(true, |previous| previous != new_oid);
+                            // Renamed or copied references note their new name in the log even though their value didn't change.
+                            let (previous, do_update) = match reflog_source {
+                                Some(_) => (Some(new_oid.to_owned()), true),
+                                None => (previous, previous.as_ref().map_or(true, |previous| previous != new_oid)),
+                            };
                             if do_update {
                                 self.store.reflog_create_or_append(
                                     change.update.name.as_ref(),
@@ -195,6 +212,8 @@ mod error {
         DeleteReference { full_name: BString, err: std::io::Error },
         #[error("The reflog of reference {full_name:?} could not be deleted")]
         DeleteReflog { full_name: BString, source: std::io::Error },
+        #[error("The reflog of the source of the renamed or copied reference {full_name:?} could not be copied")]
+        CopyReflog { full_name: BString, source: std::io::Error },
         #[error("The reflog could not be created or updated")]
         CreateOrUpdateRefLog(#[from] file::log::create_or_update::Error),
     }
@@ -73,6 +73,7 @@ impl file::Store {
             packed_transaction: None,
             updates: None,
             packed_refs: PackedRefs::default(),
+            reflogs_to_copy: Vec::new(),
         }
     }
 }
@@ -9,9 +9,10 @@ use crate::{
             Transaction,
         },
     },
-    transaction::{Change, LogChange, PreviousValue, RefEdit, RefEditsExt, RefLog},
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefEditsExt, RefLog, RenameMode},
     FullName, FullNameRef, Reference, Target,
 };
+use gix_object::bstr::BString;
 
 impl<'s, 'p> Transaction<'s, 'p> {
     fn lock_ref_and_apply_change(
@@ -215,6 +216,98 @@ impl<'s, 'p> Transaction<'s, 'p> {
         )
     }
 
+    /// Prepare renaming or copying the reference named `source` to `destination` according to `mode`, along with its reflog,
+    /// for calling [`commit(…)`][Transaction::commit()] which will then atomically apply the change.
+    ///
+    /// `destination` must not exist yet, and its reflog will be a copy of the one of `source` with an entry added using `message`.
+    /// When moving, `source` and its reflog will be removed, and if `HEAD` points to `source`, it will be adjusted to point
+    /// to `destination` instead. Symbolic references are renamed as is, without following them.
+    ///
+    /// See [`prepare(…)`][Transaction::prepare()] for more information on the lock fail modes.
+    pub fn prepare_rename(
+        mut self,
+        source: &FullNameRef,
+        destination: FullName,
+        mode: RenameMode,
+        message: BString,
+        ref_files_lock_fail_mode: gix_lock::acquire::Fail,
+        packed_refs_lock_fail_mode: gix_lock::acquire::Fail,
+    ) -> Result<Self, Error> {
+        let target = self
+            .store
+            .try_find(source.as_partial_name())?
+            .filter(|r| r.name.as_ref() == source)
+            .ok_or_else(|| Error::RenameSourceMustExist {
+                full_name: source.as_bstr().into(),
+            })?
+            .target;
+        // The update would be allowed if the destination already had the desired value, which isn't what a rename should do.
+        if let Some(existing) = self
+            .store
+            .try_find(destination.as_ref().as_partial_name())?
+            .filter(|r| r.name == destination)
+        {
+            return Err(Error::MustNotExist {
+                full_name: destination.into_inner(),
+                actual: existing.target,
+                new: target,
+            });
+        }
+        let has_reflog = self.store.reflog_path(source).is_file();
+        let mut edits = vec![RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: has_reflog,
+                    message: message.clone(),
+                },
+                expected: PreviousValue::MustNotExist,
+                new: target.clone(),
+            },
+            name: destination.clone(),
+            deref: false,
+        }];
+        if mode == RenameMode::Move {
+            edits.push(RefEdit {
+                change: Change::Delete {
+                    expected: PreviousValue::MustExistAndMatch(target),
+                    log: RefLog::AndReference,
+                },
+                name: source.to_owned(),
+                deref: false,
+            });
+            let head = FullNameRef::new_unchecked("HEAD".into());
+            let head_points_to_source = self
+                .store
+                .find_existing_inner(head.as_partial_name(), None)
+                .ok()
+                .map_or(false, |head| head.target.try_name() == Some(source));
+            if head_points_to_source {
+                edits.push(RefEdit {
+                    change: Change::Update {
+                        log: LogChange {
+                            mode: RefLog::AndReference,
+                            force_create_reflog: false,
+                            message,
+                        },
+                        expected: PreviousValue::MustExistAndMatch(Target::Symbolic(source.to_owned())),
+                        new: Target::Symbolic(destination.clone()),
+                    },
+                    name: head.to_owned(),
+                    deref: false,
+                });
+            }
+        }
+        if has_reflog {
+            self.reflogs_to_copy.push((destination, source.to_owned()));
+        }
+        self.prepare_inner(
+            &mut edits.into_iter(),
+            ref_files_lock_fail_mode,
+            packed_refs_lock_fail_mode,
+        )
+    }
+
     fn prepare_inner(
         mut self,
         edits: &mut dyn Iterator<Item = RefEdit>,
@@ -400,6 +493,33 @@ impl<'s, 'p> Transaction<'s, 'p> {
                     parent.leaf_referent_previous_oid = Some(oid);
                 }
             }
+
+            // Symbolic refs that are detached don't have a referent that would tell their previous id,
+            // so look it up to keep their reflog consistent. Edits of the reflog only don't change the ref, and know their previous id.
+            let change = &mut updates[cid];
+            let detached_previous_oid = match (
+                change.update.change.previous_value(),
+                change.parent_index,
+                &change.update.change,
+            ) {
+                (
+                    Some(crate::TargetRef::Symbolic(name)),
+                    None,
+                    Change::Update {
+                        new: Target::Peeled(_),
+                        log:
+                            LogChange {
+                                mode: RefLog::AndReference,
+                                ..
+                            },
+                        ..
+                    },
+                ) if change.leaf_referent_previous_oid.is_none() => peeled_id(self.store, name),
+                _ => None,
+            };
+            if detached_previous_oid.is_some() {
+                change.leaf_referent_previous_oid = detached_previous_oid;
+            }
         }
         self.updates = Some(updates);
         Ok(self)
@@ -420,6 +540,21 @@ impl<'s, 'p> Transaction<'s, 'p> {
     }
 }
 
+/// Follow the symbolic reference `name` to the id it ultimately points to, if possible.
+fn peeled_id(store: &file::Store, name: &FullNameRef) -> Option<gix_hash::ObjectId> {
+    use crate::file::ReferenceExt;
+
+    let mut reference = store.try_find(name).ok()??;
+    // Prevent endless loops in case of cycles, similar to `git` which gives up after 5 levels of indirection.
+    for _ in 0..5 {
+        match reference.target {
+            Target::Peeled(id) => return Some(id),
+            Target::Symbolic(_) => reference = reference.follow(store)?.ok()?,
+        }
+    }
+    None
+}
+
 fn possibly_adjust_name_for_prefixes(name: &FullNameRef) -> Option<FullName> {
     match name.category_and_short_name() {
         Some((c, sn)) => {
@@ -486,6 +621,10 @@ mod error {
         },
         #[error("Could not read reference")]
         ReferenceDecode(#[from] file::loose::reference::decode::Error),
+        #[error("The reference {full_name:?} to rename or copy did not exist")]
+        RenameSourceMustExist { full_name: BString },
+        #[error("The reference to rename or copy could not be read")]
+        Find(#[from] file::find::Error),
     }
 }
 
@@ -5,6 +5,7 @@
 //!
 //! * create or update reference
 //! * delete references
+//! * rename or copy references along with their reflog
 //!
 //! The following guarantees are made:
 //!
@@ -130,5 +131,14 @@ pub enum RefLog {
     Only,
 }
 
+/// The way to treat the source reference when renaming it.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum RenameMode {
+    /// Remove the source reference and its reflog after creating the destination, just like `git branch -m`.
+    Move,
+    /// Keep the source reference and its reflog, just like `git branch -c`.
+    Copy,
+}
+
 mod ext;
 pub use ext::RefEditsExt;
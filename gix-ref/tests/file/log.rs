@@ -192,3 +192,162 @@ mod iter {
         }
     }
 }
+
+mod expire {
+    use gix_lock::acquire::Fail;
+    use gix_ref::file::log::expire::{Options, Outcome};
+
+    use crate::{file::transaction::prepare_and_commit::empty_store, hex_to_id};
+
+    const A: &str = "134385f6d781b7e97062102c6a483440bfda2a03";
+    const B: &str = "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391";
+    const C: &str = "28ce6a8b26aa170e1de65536fe8abe1832bd3242";
+    const NULL: &str = "0000000000000000000000000000000000000000";
+
+    fn line(previous: &str, new: &str, time: u32, message: &str) -> String {
+        format!("{previous} {new} committer <committer@example.com> {time} +0000\t{message}\n")
+    }
+
+    fn reflog_with_three_entries() -> crate::Result<(gix_testtools::tempfile::TempDir, gix_ref::file::Store)> {
+        let (dir, store) = empty_store()?;
+        let logs = dir.path().join("logs").join("refs").join("heads");
+        std::fs::create_dir_all(&logs)?;
+        std::fs::write(
+            logs.join("main"),
+            [
+                line(NULL, A, 100, "first"),
+                line(A, B, 200, "second"),
+                line(B, C, 300, "third"),
+            ]
+            .concat(),
+        )?;
+        Ok((dir, store))
+    }
+
+    fn read_log(dir: &gix_testtools::tempfile::TempDir) -> crate::Result<String> {
+        Ok(std::fs::read_to_string(
+            dir.path().join("logs").join("refs").join("heads").join("main"),
+        )?)
+    }
+
+    #[test]
+    fn non_existing_reflogs_are_ignored() -> crate::Result {
+        let (_dir, store) = empty_store()?;
+        let out = store.reflog_expire(
+            "refs/heads/main".try_into()?,
+            Options {
+                expire: Some(i64::MAX),
+                ..Default::default()
+            },
+            Fail::Immediately,
+            &mut |_| unreachable!("not called"),
+        )?;
+        assert_eq!(out, Outcome::default());
+        Ok(())
+    }
+
+    #[test]
+    fn nothing_to_expire_leaves_the_log_untouched() -> crate::Result {
+        let (dir, store) = reflog_with_three_entries()?;
+        let before = read_log(&dir)?;
+        let out = store.reflog_expire(
+            "refs/heads/main".try_into()?,
+            Options {
+                expire: Some(100),
+                expire_unreachable: Some(100),
+                rewrite: true,
+            },
+            Fail::Immediately,
+            &mut |_| false,
+        )?;
+        assert_eq!(
+            out,
+            Outcome {
+                num_removed: 0,
+                num_kept: 3
+            }
+        );
+        assert_eq!(read_log(&dir)?, before);
+        Ok(())
+    }
+
+    #[test]
+    fn entries_older_than_expire_are_removed() -> crate::Result {
+        let (dir, store) = reflog_with_three_entries()?;
+        let out = store.reflog_expire(
+            "refs/heads/main".try_into()?,
+            Options {
+                expire: Some(250),
+                ..Default::default()
+            },
+            Fail::Immediately,
+            &mut |_| unreachable!("unreachable entries aren't expired"),
+        )?;
+        assert_eq!(
+            out,
+            Outcome {
+                num_removed: 2,
+                num_kept: 1
+            }
+        );
+        assert_eq!(read_log(&dir)?, line(B, C, 300, "third"));
+        Ok(())
+    }
+
+    #[test]
+    fn unreachable_entries_are_removed_and_the_chain_can_be_rewritten() -> crate::Result {
+        for rewrite in [false, true] {
+            let (dir, store) = reflog_with_three_entries()?;
+            let mut seen = Vec::new();
+            let out = store.reflog_expire(
+                "refs/heads/main".try_into()?,
+                Options {
+                    expire_unreachable: Some(250),
+                    rewrite,
+                    ..Default::default()
+                },
+                Fail::Immediately,
+                &mut |id| {
+                    seen.push(id.to_owned());
+                    id != hex_to_id(B)
+                },
+            )?;
+            assert_eq!(seen, [hex_to_id(A), hex_to_id(B)], "only old entries are checked");
+            assert_eq!(
+                out,
+                Outcome {
+                    num_removed: 1,
+                    num_kept: 2
+                }
+            );
+            let expected_previous = if rewrite { A } else { B };
+            assert_eq!(
+                read_log(&dir)?,
+                [line(NULL, A, 100, "first"), line(expected_previous, C, 300, "third")].concat()
+            );
+        }
+        Ok(())
+    }
+    #[test]
+    fn malformed_entries_abort_without_changing_the_log() -> crate::Result {
+        let (dir, store) = reflog_with_three_entries()?;
+        let path = dir.path().join("logs").join("refs").join("heads").join("main");
+        let before = [line(NULL, A, 100, "first"), "broken\n".into(), line(B, C, 300, "third")].concat();
+        std::fs::write(&path, &before)?;
+        let err = store
+            .reflog_expire(
+                "refs/heads/main".try_into()?,
+                Options {
+                    expire: Some(150),
+                    ..Default::default()
+                },
+                Fail::Immediately,
+                &mut |_| unreachable!("unreachable entries aren't expired"),
+            )
+            .unwrap_err();
+        assert!(matches!(err, gix_ref::file::log::expire::Error::Decode(_)));
+        assert_eq!(read_log(&dir)?, before, "nothing is written if a line can't be parsed");
+        assert!(!path.with_extension("lock").exists(), "the lock is released");
+        Ok(())
+    }
+}
@@ -707,6 +707,41 @@ fn write_reference_to_which_head_points_to_does_not_update_heads_reflog_even_tho
     Ok(())
 }
 
+#[test]
+fn detaching_symbolic_head_logs_the_id_it_pointed_to() -> crate::Result {
+    let (_keep, store) = store_writable("make_repo_for_reflog.sh")?;
+    let new_id = hex_to_id("01dd4e2a978a9f5bd773dae6da7aa4a5ac1cdbbc");
+    let edits = store
+        .transaction()
+        .prepare(
+            Some(RefEdit {
+                change: Change::Update {
+                    log: LogChange {
+                        mode: RefLog::AndReference,
+                        force_create_reflog: false,
+                        message: "detach".into(),
+                    },
+                    expected: PreviousValue::MustExist,
+                    new: Target::Peeled(new_id),
+                },
+                name: "HEAD".try_into()?,
+                deref: false,
+            }),
+            Fail::Immediately,
+            Fail::Immediately,
+        )?
+        .commit(committer().to_ref())?;
+
+    assert_eq!(edits.len(), 1, "no split was performed");
+    assert_eq!(store.find_loose("HEAD")?.target, Target::Peeled(new_id));
+    assert_eq!(
+        reflog_lines(&store, "HEAD")?.last().expect("at least one line"),
+        &log_line(hex_to_id("02a7a22d90d7c02fb494ed25551850b868e634f0"), new_id, "detach"),
+        "the previous id is the one the referent pointed to"
+    );
+    Ok(())
+}
+
 #[test]
 fn packed_refs_are_looked_up_when_checking_existing_values() -> crate::Result {
     let (_keep, store) = store_writable("make_packed_ref_repository.sh")?;
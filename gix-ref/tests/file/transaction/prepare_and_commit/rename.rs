@@ -0,0 +1,169 @@
+use gix_lock::acquire::Fail;
+use gix_ref::{
+    file::transaction,
+    transaction::{Change, RenameMode},
+    Target,
+};
+
+use crate::file::{
+    store_writable,
+    transaction::prepare_and_commit::{committer, reflog_lines},
+};
+
+#[test]
+fn move_loose_ref_with_reflog_and_adjust_head() -> crate::Result {
+    let (_keep, store) = store_writable("make_repo_for_reflog.sh")?;
+    let source = store.find_loose("main")?;
+    let source_log = reflog_lines(&store, "refs/heads/main")?;
+
+    let edits = store
+        .transaction()
+        .prepare_rename(
+            "refs/heads/main".try_into()?,
+            "refs/heads/renamed".try_into()?,
+            RenameMode::Move,
+            "Branch: renamed refs/heads/main to refs/heads/renamed".into(),
+            Fail::Immediately,
+            Fail::Immediately,
+        )?
+        .commit(committer().to_ref())?;
+    assert_eq!(edits.len(), 3, "create destination, delete source, retarget HEAD");
+
+    assert!(store.try_find_loose("main")?.is_none(), "the source is gone");
+    assert!(
+        !store.reflog_exists("refs/heads/main")?,
+        "the reflog of the source is gone as well"
+    );
+    let renamed = store.find_loose("renamed")?;
+    assert_eq!(renamed.target, source.target, "the value is retained");
+
+    let log = reflog_lines(&store, "refs/heads/renamed")?;
+    assert_eq!(
+        log.len(),
+        source_log.len() + 1,
+        "the log was copied and one entry was added"
+    );
+    assert_eq!(&log[..source_log.len()], source_log.as_slice());
+    let last = log.last().expect("present");
+    let id = source.target.try_id().expect("peeled");
+    assert_eq!(last.previous_oid, id, "the renamed ref didn't change its value");
+    assert_eq!(last.new_oid, id);
+    assert_eq!(last.message, "Branch: renamed refs/heads/main to refs/heads/renamed");
+
+    assert_eq!(
+        store.find_loose("HEAD")?.target,
+        Target::Symbolic("refs/heads/renamed".try_into()?),
+        "HEAD follows the renamed branch"
+    );
+    Ok(())
+}
+
+#[test]
+fn copy_loose_ref_with_reflog() -> crate::Result {
+    let (_keep, store) = store_writable("make_repo_for_reflog.sh")?;
+    let source = store.find_loose("main")?;
+    let source_log = reflog_lines(&store, "refs/heads/main")?;
+
+    let edits = store
+        .transaction()
+        .prepare_rename(
+            "refs/heads/main".try_into()?,
+            "refs/heads/copied".try_into()?,
+            RenameMode::Copy,
+            "Branch: copied refs/heads/main to refs/heads/copied".into(),
+            Fail::Immediately,
+            Fail::Immediately,
+        )?
+        .commit(committer().to_ref())?;
+    assert_eq!(edits.len(), 1, "only the destination is created");
+
+    assert_eq!(
+        store.find_loose("main")?.target,
+        source.target,
+        "the source is untouched"
+    );
+    assert_eq!(
+        reflog_lines(&store, "refs/heads/main")?,
+        source_log,
+        "the log of the source is untouched"
+    );
+    assert_eq!(store.find_loose("copied")?.target, source.target);
+    assert_eq!(reflog_lines(&store, "refs/heads/copied")?.len(), source_log.len() + 1);
+    assert_eq!(
+        store.find_loose("HEAD")?.target,
+        Target::Symbolic("refs/heads/main".try_into()?),
+        "HEAD isn't affected by copies"
+    );
+    Ok(())
+}
+
+#[test]
+fn move_packed_ref() -> crate::Result {
+    let (_keep, store) = store_writable("make_packed_ref_repository.sh")?;
+    let source = store.find("d1")?;
+    assert!(store.try_find_loose("refs/heads/d1")?.is_none(), "the source is packed");
+
+    let edits = store
+        .transaction()
+        .prepare_rename(
+            "refs/heads/d1".try_into()?,
+            "refs/heads/renamed".try_into()?,
+            RenameMode::Move,
+            "rename".into(),
+            Fail::Immediately,
+            Fail::Immediately,
+        )?
+        .commit(committer().to_ref())?;
+    assert_eq!(edits.len(), 2);
+    assert!(
+        matches!(edits[1].change, Change::Delete { .. }),
+        "the packed source is deleted"
+    );
+
+    assert!(
+        store.try_find("refs/heads/d1")?.is_none(),
+        "the source is gone from packed-refs"
+    );
+    assert_eq!(store.find("renamed")?.target, source.target);
+    Ok(())
+}
+
+#[test]
+fn destination_must_not_exist_and_source_must_exist() -> crate::Result {
+    let (_keep, store) = store_writable("make_packed_ref_repository.sh")?;
+    for (source, destination) in [("refs/heads/d1", "refs/heads/dt1"), ("refs/heads/d1", "refs/heads/d1")] {
+        let err = store
+            .transaction()
+            .prepare_rename(
+                source.try_into()?,
+                destination.try_into()?,
+                RenameMode::Move,
+                "rename".into(),
+                Fail::Immediately,
+                Fail::Immediately,
+            )
+            .unwrap_err();
+        assert!(
+            matches!(err, transaction::prepare::Error::MustNotExist { ref full_name, .. } if full_name == destination),
+            "{err:?}"
+        );
+    }
+
+    let err = store
+        .transaction()
+        .prepare_rename(
+            "refs/heads/does-not-exist".try_into()?,
+            "refs/heads/new".try_into()?,
+            RenameMode::Copy,
+            "copy".into(),
+            Fail::Immediately,
+            Fail::Immediately,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "The reference \"refs/heads/does-not-exist\" to rename or copy did not exist"
+    );
+    assert!(store.try_find("new")?.is_none(), "nothing was created");
+    Ok(())
+}
@@ -0,0 +1,262 @@
+use bitflags::bitflags;
+
+bitflags! {
+    /// The flags used in the graph for finding [merge bases](crate::merge_base()).
+    #[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+    pub struct Flags: u8 {
+        /// The commit is reachable from the first commit.
+        const COMMIT1 = 1 << 0;
+        /// The commit is reachable from any of the other commits.
+        const COMMIT2 = 1 << 1;
+        /// The commit is reachable from a merge base, and thus can't be a merge base itself.
+        const STALE = 1 << 2;
+        /// The commit was found to be reachable from all commits and is a merge base candidate.
+        const RESULT = 1 << 3;
+    }
+}
+
+/// The error returned by the [`merge_base()`][function::merge_base()] function and its siblings.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A commit could not be inserted into the graph")]
+    InsertCommit(#[from] gix_revwalk::graph::try_lookup_or_insert_default::Error),
+    #[error("The commit {id} could not be found")]
+    MissingCommit { id: gix_hash::ObjectId },
+}
+
+pub(crate) mod function {
+    use gix_hash::ObjectId;
+    use gix_revwalk::graph;
+
+    use super::{Error, Flags};
+    use crate::{Graph, PriorityQueue};
+
+    /// Given a commit at `first` id, traverse the commit `graph` and return all possible merge-base between it and `others`,
+    /// sorted from best to worst. Returns `None` if there is no merge-base as `first` and `others` don't share history.
+    /// If `others` is empty, `None` is returned as well.
+    ///
+    /// Note that this function doesn't do any work if `first` is contained in `others`, which is when `first` will be returned
+    /// as only merge-base right away. This is even the case if some commits of `others` are disjoint.
+    ///
+    /// With more than one commit in `others`, the merge-bases are those of `first` and a hypothetical merge of all `others`,
+    /// just like `git merge-base <first> <others>…` computes them.
+    pub fn merge_base(
+        first: ObjectId,
+        others: &[ObjectId],
+        graph: &mut Graph<'_, graph::Commit<Flags>>,
+    ) -> Result<Option<Vec<ObjectId>>, Error> {
+        let _span = gix_trace::coarse!("gix_revision::merge_base()", ?first, ?others);
+        if others.is_empty() {
+            return Ok(None);
+        }
+        if others.contains(&first) {
+            return Ok(Some(vec![first]));
+        }
+
+        graph.clear();
+        let bases = paint_down_to_common(first, others, None, graph)?;
+        let bases = bases
+            .into_iter()
+            .filter(|id| !graph[id].data.contains(Flags::STALE))
+            .collect::<Vec<_>>();
+        let bases = remove_redundant(bases, graph)?;
+        Ok((!bases.is_empty()).then_some(bases))
+    }
+
+    /// Return the merge-bases of all `commits` like `git merge-base --octopus`, which are the commits that are reachable
+    /// from all of them, sorted from best to worst, or `None` if there is none or `commits` is empty.
+    pub fn octopus(
+        commits: &[ObjectId],
+        graph: &mut Graph<'_, graph::Commit<Flags>>,
+    ) -> Result<Option<Vec<ObjectId>>, Error> {
+        let Some((first, rest)) = commits.split_first() else {
+            return Ok(None);
+        };
+        let mut bases = vec![*first];
+        for commit in rest {
+            let mut next_bases = Vec::with_capacity(bases.len());
+            for base in &bases {
+                for id in merge_base(*base, &[*commit], graph)?.unwrap_or_default() {
+                    if !next_bases.contains(&id) {
+                        next_bases.push(id);
+                    }
+                }
+            }
+            if next_bases.is_empty() {
+                return Ok(None);
+            }
+            bases = next_bases;
+        }
+        Ok(Some(bases))
+    }
+
+    /// Return all `commits` that can't be reached from any other commit in `commits`, in their original order and without
+    /// duplicates, like `git merge-base --independent`.
+    pub fn independent(
+        commits: &[ObjectId],
+        graph: &mut Graph<'_, graph::Commit<Flags>>,
+    ) -> Result<Vec<ObjectId>, Error> {
+        let mut unique = Vec::with_capacity(commits.len());
+        for id in commits {
+            if !unique.contains(id) {
+                unique.push(*id);
+            }
+        }
+        remove_redundant(unique, graph)
+    }
+
+    /// Return `true` if `ancestor` is reachable from `descendant` or if both are the same commit, like `git merge-base --is-ancestor`.
+    ///
+    /// Generation numbers of the commit-graph are used to stop the traversal early if available.
+    pub fn is_ancestor(
+        ancestor: ObjectId,
+        descendant: ObjectId,
+        graph: &mut Graph<'_, graph::Commit<Flags>>,
+    ) -> Result<bool, Error> {
+        if ancestor == descendant {
+            return Ok(true);
+        }
+        graph.clear();
+        let min_generation = insert(graph, ancestor, Flags::empty())?.generation;
+        paint_down_to_common(ancestor, &[descendant], min_generation, graph)?;
+        Ok(graph[&ancestor].data.contains(Flags::COMMIT2))
+    }
+
+    /// Find the point at which `commit` forked from the history of an upstream branch, whose current and previous tips as
+    /// obtained from its reference log are passed as `upstream_tips`, like `git merge-base --fork-point`.
+    ///
+    /// This is useful if the upstream branch was rewritten, as the fork-point is still found if it isn't part of the upstream
+    /// history anymore. Returns `None` if no fork-point could be found, which is when the merge-base of `commit` and all
+    /// `upstream_tips` isn't unique or isn't one of the `upstream_tips`.
+    pub fn fork_point(
+        commit: ObjectId,
+        upstream_tips: &[ObjectId],
+        graph: &mut Graph<'_, graph::Commit<Flags>>,
+    ) -> Result<Option<ObjectId>, Error> {
+        let _span = gix_trace::coarse!(
+            "gix_revision::merge_base::fork_point()",
+            ?commit,
+            tips = upstream_tips.len()
+        );
+        if upstream_tips.contains(&commit) {
+            return Ok(Some(commit));
+        }
+        if upstream_tips.is_empty() {
+            return Ok(None);
+        }
+        graph.clear();
+        let bases = paint_down_to_common(commit, upstream_tips, None, graph)?;
+        let mut bases = bases.into_iter().filter(|id| !graph[id].data.contains(Flags::STALE));
+        Ok(match (bases.next(), bases.next()) {
+            (Some(base), None) if upstream_tips.contains(&base) => Some(base),
+            _ => None,
+        })
+    }
+
+    /// Remove all commits from `commits` that are reachable from another one, retaining the order of the remaining ones.
+    fn remove_redundant(
+        commits: Vec<ObjectId>,
+        graph: &mut Graph<'_, graph::Commit<Flags>>,
+    ) -> Result<Vec<ObjectId>, Error> {
+        if commits.len() < 2 {
+            return Ok(commits);
+        }
+        let mut is_redundant = vec![false; commits.len()];
+        for (idx, commit) in commits.iter().enumerate() {
+            for (other_idx, other) in commits.iter().enumerate() {
+                if idx == other_idx || is_redundant[other_idx] {
+                    continue;
+                }
+                if is_ancestor(*commit, *other, graph)? {
+                    is_redundant[idx] = true;
+                    break;
+                }
+            }
+        }
+        Ok(commits
+            .into_iter()
+            .zip(is_redundant)
+            .filter_map(|(id, is_redundant)| (!is_redundant).then_some(id))
+            .collect())
+    }
+
+    /// Mark `first` with [`Flags::COMMIT1`] and all `others` with [`Flags::COMMIT2`], and walk their history until all
+    /// commits that are reachable from both are found, which are returned from best to worst. They are marked with
+    /// [`Flags::RESULT`], and those which are reachable from other results are [`Flags::STALE`].
+    ///
+    /// If `min_generation` is set, commits with a lower generation aren't traversed.
+    fn paint_down_to_common(
+        first: ObjectId,
+        others: &[ObjectId],
+        min_generation: Option<graph::Generation>,
+        graph: &mut Graph<'_, graph::Commit<Flags>>,
+    ) -> Result<Vec<ObjectId>, Error> {
+        let mut queue = PriorityQueue::<GenThenTime, ObjectId>::new();
+        queue.insert(GenThenTime::from(&*insert(graph, first, Flags::COMMIT1)?), first);
+        for other in others {
+            queue.insert(GenThenTime::from(&*insert(graph, *other, Flags::COMMIT2)?), *other);
+        }
+
+        let mut out = Vec::new();
+        while queue.iter_unordered().any(|id| !graph[id].data.contains(Flags::STALE)) {
+            let (info, commit_id) = queue.pop().expect("we have non-stale");
+            if min_generation.map_or(false, |min_generation| info.generation < min_generation) {
+                break;
+            }
+            let commit = graph.get_mut(&commit_id).expect("everything queued is in graph");
+            let mut flags_without_result = commit.data & (Flags::COMMIT1 | Flags::COMMIT2 | Flags::STALE);
+            if flags_without_result == (Flags::COMMIT1 | Flags::COMMIT2) {
+                if !commit.data.contains(Flags::RESULT) {
+                    commit.data |= Flags::RESULT;
+                    out.push(commit_id);
+                }
+                flags_without_result |= Flags::STALE;
+            }
+
+            for parent_id in commit.parents.clone() {
+                let Some(parent) = graph.try_lookup_or_insert_commit(parent_id, |_| {})? else {
+                    // Missing parents are expected in shallow repositories.
+                    continue;
+                };
+                if parent.data.contains(flags_without_result) {
+                    continue;
+                }
+                parent.data |= flags_without_result;
+                queue.insert(GenThenTime::from(&*parent), parent_id);
+            }
+        }
+        Ok(out)
+    }
+
+    fn insert<'a>(
+        graph: &'a mut Graph<'_, graph::Commit<Flags>>,
+        id: ObjectId,
+        flags: Flags,
+    ) -> Result<&'a mut graph::Commit<Flags>, Error> {
+        graph
+            .try_lookup_or_insert_commit(id, |data| *data |= flags)?
+            .ok_or(Error::MissingCommit { id })
+    }
+
+    /// The sort key for the queue, traversing commits with higher generations first, and then by the newest commit date.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    struct GenThenTime {
+        /// Set to [`GENERATION_NUMBER_INFINITY`] if not available.
+        generation: graph::Generation,
+        time: gix_date::SecondsSinceUnixEpoch,
+    }
+
+    /// The generation of commits that are not part of the commit-graph, and thus are assumed to come first.
+    const GENERATION_NUMBER_INFINITY: graph::Generation = graph::Generation::MAX;
+
+    impl From<&graph::Commit<Flags>> for GenThenTime {
+        fn from(commit: &graph::Commit<Flags>) -> Self {
+            GenThenTime {
+                generation: commit.generation.unwrap_or(GENERATION_NUMBER_INFINITY),
+                time: commit.commit_time,
+            }
+        }
+    }
+}
+pub use function::{fork_point, independent, is_ancestor, octopus};
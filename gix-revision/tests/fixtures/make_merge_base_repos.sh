@@ -0,0 +1,74 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config merge.ff false
+
+function commit() {
+  git commit -q --allow-empty -m "$1"
+  git tag "$1"
+}
+
+function baseline() {
+  local mode=$1; shift
+  echo "$mode $(git rev-parse "$@" | tr '\n' ' ')" >>baseline.git
+  if [ "$mode" = "--is-ancestor" ]; then
+    git merge-base "$mode" "$@" && echo true >>baseline.git || echo false >>baseline.git
+  else
+    git merge-base "$mode" "$@" >>baseline.git || :
+  fi
+  echo >>baseline.git
+}
+
+git checkout -q -b main
+commit A
+commit B
+commit C
+
+git checkout -q -b topic B
+commit D
+commit E
+
+git checkout -q -b left A
+commit L1
+git checkout -q -b right A
+commit R1
+git checkout -q left
+git merge -q -m M1 R1 && git tag M1
+git checkout -q right
+git merge -q -m M2 L1 && git tag M2
+
+git checkout -q --orphan disjoint
+commit X
+
+git checkout -q -b upstream A
+commit U1
+git checkout -q -b feature
+commit F1
+git checkout -q upstream
+git reset -q --hard A
+commit U1-rewritten
+
+git commit-graph write --no-progress --reachable
+
+baseline --all C E
+baseline --all E C
+baseline --all M1 M2
+baseline --all C E M1
+baseline --all C X
+baseline --all C B
+baseline --octopus C E M1
+baseline --octopus C E D
+baseline --octopus C X
+baseline --independent C E B M1 M2 C
+baseline --is-ancestor A C
+baseline --is-ancestor C A
+baseline --is-ancestor L1 M2
+baseline --is-ancestor X C
+
+{
+  git rev-parse feature
+  git reflog show --format=%H upstream | tr '\n' ' '
+  echo
+  git merge-base --fork-point upstream feature
+} >fork-point.git
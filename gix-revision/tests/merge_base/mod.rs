@@ -0,0 +1,161 @@
+use gix_hash::ObjectId;
+use gix_revision::merge_base;
+
+use crate::hex_to_id;
+
+type Graph = gix_revision::Graph<'static, gix_revision::graph::Commit<merge_base::Flags>>;
+
+/// Run `test` with a graph that doesn't and one that does use the commit-graph, as well as the parsed git baseline.
+fn with_graphs(mut test: impl FnMut(&mut Graph, &[Baseline]) -> crate::Result) -> crate::Result {
+    let root = fixture_path();
+    let baselines = parse_baseline(&std::fs::read_to_string(root.join("baseline.git"))?);
+    let store = gix_odb::at(root.join(".git/objects"))?;
+    for use_commitgraph in [false, true] {
+        let cache = use_commitgraph
+            .then(|| gix_commitgraph::Graph::from_info_dir(&store.store_ref().path().join("info")).ok())
+            .flatten();
+        assert_eq!(
+            cache.is_some(),
+            use_commitgraph,
+            "the commit-graph is expected to be present"
+        );
+        let mut graph = gix_revision::Graph::new(store.clone(), cache);
+        test(&mut graph, &baselines)?;
+    }
+    Ok(())
+}
+
+fn fixture_path() -> std::path::PathBuf {
+    gix_testtools::scripted_fixture_read_only("make_merge_base_repos.sh").unwrap()
+}
+
+struct Baseline {
+    mode: String,
+    input: Vec<ObjectId>,
+    output: Vec<String>,
+}
+
+fn parse_baseline(baseline: &str) -> Vec<Baseline> {
+    baseline
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .map(|block| {
+            let mut lines = block.lines();
+            let mut header = lines.next().expect("header").split_whitespace();
+            Baseline {
+                mode: header.next().expect("mode").into(),
+                input: header.map(hex_to_id).collect(),
+                output: lines.map(ToOwned::to_owned).collect(),
+            }
+        })
+        .collect()
+}
+
+fn ids(output: &[String]) -> Vec<ObjectId> {
+    output.iter().map(|hex| hex_to_id(hex)).collect()
+}
+
+fn sorted(mut ids: Vec<ObjectId>) -> Vec<ObjectId> {
+    ids.sort();
+    ids
+}
+
+#[test]
+fn all_merge_bases_match_git() -> crate::Result {
+    with_graphs(|graph, baselines| {
+        for Baseline { mode, input, output } in baselines.iter().filter(|b| b.mode == "--all") {
+            let actual = gix_revision::merge_base(input[0], &input[1..], graph)?;
+            assert_eq!(
+                sorted(actual.unwrap_or_default()),
+                sorted(ids(output)),
+                "{mode} {input:?}: the set of merge-bases matches, including criss-cross merges"
+            );
+        }
+        Ok(())
+    })
+}
+
+#[test]
+fn octopus_matches_git() -> crate::Result {
+    with_graphs(|graph, baselines| {
+        for Baseline { mode, input, output } in baselines.iter().filter(|b| b.mode == "--octopus") {
+            let actual = merge_base::octopus(input, graph)?;
+            assert_eq!(
+                sorted(actual.unwrap_or_default()),
+                sorted(ids(output)),
+                "{mode} {input:?}"
+            );
+        }
+        Ok(())
+    })
+}
+
+#[test]
+fn independent_matches_git() -> crate::Result {
+    with_graphs(|graph, baselines| {
+        for Baseline { mode, input, output } in baselines.iter().filter(|b| b.mode == "--independent") {
+            let actual = merge_base::independent(input, graph)?;
+            assert_eq!(
+                sorted(actual),
+                sorted(ids(output)),
+                "{mode} {input:?}: duplicates and reachable commits are removed"
+            );
+        }
+        Ok(())
+    })
+}
+
+#[test]
+fn is_ancestor_matches_git() -> crate::Result {
+    with_graphs(|graph, baselines| {
+        for Baseline { mode, input, output } in baselines.iter().filter(|b| b.mode == "--is-ancestor") {
+            let actual = merge_base::is_ancestor(input[0], input[1], graph)?;
+            assert_eq!(actual.to_string(), output[0], "{mode} {input:?}");
+        }
+        assert!(
+            merge_base::is_ancestor(baselines[0].input[0], baselines[0].input[0], graph)?,
+            "commits are their own ancestors"
+        );
+        Ok(())
+    })
+}
+
+#[test]
+fn fork_point_uses_previous_tips_of_upstream() -> crate::Result {
+    let root = fixture_path();
+    let fork_point = std::fs::read_to_string(root.join("fork-point.git"))?;
+    let mut lines = fork_point.lines();
+    let commit = hex_to_id(lines.next().expect("commit"));
+    let upstream_tips: Vec<_> = lines.next().expect("tips").split_whitespace().map(hex_to_id).collect();
+    let expected = hex_to_id(lines.next().expect("fork-point"));
+
+    with_graphs(|graph, _| {
+        assert_eq!(
+            merge_base::fork_point(commit, &upstream_tips, graph)?,
+            Some(expected),
+            "the fork-point was rewritten away from the upstream, but is still found through its previous tips"
+        );
+        let merge_bases = gix_revision::merge_base(commit, &upstream_tips[..1], graph)?.expect("shared history");
+        assert_ne!(
+            merge_bases,
+            [expected],
+            "the merge-base alone doesn't know the fork-point"
+        );
+        assert_eq!(
+            merge_base::fork_point(commit, &upstream_tips[..1], graph)?,
+            None,
+            "the merge-base isn't one of the upstream tips"
+        );
+        Ok(())
+    })
+}
+
+#[test]
+fn missing_commits_are_an_error() -> crate::Result {
+    with_graphs(|graph, baselines| {
+        let missing = hex_to_id("0000000000000000000000000000000000000001");
+        let err = gix_revision::merge_base(baselines[0].input[0], &[missing], graph).unwrap_err();
+        assert!(matches!(err, merge_base::Error::MissingCommit { id } if id == missing));
+        Ok(())
+    })
+}
@@ -0,0 +1,246 @@
+//! Query filesystem monitor hooks to learn which index entries can be trusted to be unchanged, similar to `core.fsmonitor`.
+//!
+//! The typical flow is to [query](Hook::query()) the hook with the token stored in the index, [apply](mark_valid_entries())
+//! the reported changes to the index so that [`index_as_worktree()`](crate::index_as_worktree()) can skip all entries
+//! that are known to be unchanged, and to finally write the [updated extension](to_extension()) back to the index.
+use bstr::{BString, ByteSlice};
+use gix_index::entry::Flags;
+use std::path::Path;
+
+pub use gix_index::extension::fs_monitor::Token;
+
+/// The version of the hook protocol to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Version {
+    /// The hook receives a timestamp in nanoseconds and outputs the changed paths since then.
+    V1,
+    /// The hook receives an opaque token and outputs a new token, followed by the changed paths since then.
+    V2,
+}
+
+/// The changes reported by a filesystem monitor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Changes {
+    /// Everything has to be assumed to be changed, for instance because there was no previous token, or the
+    /// filesystem monitor lost track of changes.
+    All,
+    /// Only the given repository-relative paths changed, sorted and without duplicates.
+    ///
+    /// Note that a path may also refer to a directory, which means everything inside of it may have changed.
+    Paths(Vec<BString>),
+}
+
+impl Changes {
+    /// Return the changed paths, or `None` if everything has to be assumed to be changed.
+    pub fn paths(&self) -> Option<&[BString]> {
+        match self {
+            Changes::All => None,
+            Changes::Paths(paths) => Some(paths),
+        }
+    }
+}
+
+/// The result of [`Hook::query()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The token to store in the index and to pass to the next query.
+    pub token: Token,
+    /// The changes since the token that was passed to the query.
+    pub changes: Changes,
+}
+
+/// A filesystem monitor hook program, as configured with `core.fsmonitor`.
+#[derive(Debug, Clone)]
+pub struct Hook {
+    /// The program to run, which is run through the shell.
+    pub program: BString,
+    /// The version of the protocol to use, as configured with `core.fsmonitorHookVersion`.
+    ///
+    /// If `None`, the version 2 protocol is tried first, with the version 1 protocol as fallback.
+    pub version: Option<Version>,
+    /// Additional information to pass to the spawned hook, like the location of the repository.
+    pub context: Option<gix_command::Context>,
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod query {
+    use bstr::BString;
+
+    /// The error returned by [`Hook::query()`](super::Hook::query()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not spawn filesystem monitor hook {program:?}")]
+        Spawn { source: std::io::Error, program: BString },
+        #[error("Filesystem monitor hook {program:?} exited with {status}")]
+        Failed {
+            program: BString,
+            status: std::process::ExitStatus,
+        },
+        #[error("Filesystem monitor hook {program:?} did not output a token for the version 2 protocol")]
+        MissingToken { program: BString },
+    }
+}
+
+impl Hook {
+    /// Run the hook in `worktree` to learn about the changes since `token`, the token stored in the index, and return them
+    /// along with a new token.
+    ///
+    /// If there is no `token`, everything is considered changed, and the hook isn't run at all.
+    pub fn query(&self, worktree: &Path, token: Option<&Token>) -> Result<Outcome, query::Error> {
+        let now = nanos_since_1970();
+        let Some(token) = token else {
+            return Ok(Outcome {
+                token: Token::V2 {
+                    token: now.to_string().into(),
+                },
+                changes: Changes::All,
+            });
+        };
+        match self.version {
+            Some(version) => self.query_version(worktree, version, token, now),
+            None => self
+                .query_version(worktree, Version::V2, token, now)
+                .or_else(|_| self.query_version(worktree, Version::V1, token, now)),
+        }
+    }
+
+    fn query_version(
+        &self,
+        worktree: &Path,
+        version: Version,
+        token: &Token,
+        now: u64,
+    ) -> Result<Outcome, query::Error> {
+        let token_arg: BString = match (version, token) {
+            (Version::V1, Token::V1 { nanos_since_1970 }) => nanos_since_1970.to_string().into(),
+            (Version::V1, Token::V2 { token }) => token
+                .to_str()
+                .ok()
+                .and_then(|token| token.parse::<u64>().ok())
+                .map_or_else(|| "0".into(), |nanos| nanos.to_string().into()),
+            (Version::V2, Token::V1 { nanos_since_1970 }) => nanos_since_1970.to_string().into(),
+            (Version::V2, Token::V2 { token }) => token.clone(),
+        };
+        let mut prepare = gix_command::prepare(gix_path::from_bstr(self.program.as_bstr()).into_owned())
+            .with_shell()
+            .arg(match version {
+                Version::V1 => "1",
+                Version::V2 => "2",
+            })
+            .arg(gix_path::from_bstr(token_arg.as_bstr()).into_owned());
+        if let Some(ctx) = self.context.clone() {
+            prepare = prepare.with_context(ctx);
+        }
+        let mut cmd: std::process::Command = prepare.into();
+        let out = cmd.current_dir(worktree).output().map_err(|err| query::Error::Spawn {
+            source: err,
+            program: self.program.clone(),
+        })?;
+        if !out.status.success() {
+            return Err(query::Error::Failed {
+                program: self.program.clone(),
+                status: out.status,
+            });
+        }
+
+        let mut output = out.stdout.split_str(b"\0");
+        let token = match version {
+            Version::V1 => Token::V2 {
+                token: now.to_string().into(),
+            },
+            Version::V2 => Token::V2 {
+                token: output
+                    .next()
+                    .filter(|token| !token.is_empty())
+                    .ok_or_else(|| query::Error::MissingToken {
+                        program: self.program.clone(),
+                    })?
+                    .into(),
+            },
+        };
+        Ok(Outcome {
+            token,
+            changes: parse_paths(output),
+        })
+    }
+}
+
+/// Turn the NUL-separated paths reported by a hook into changes.
+/// If the first path is absolute, the hook signals that everything must be considered changed.
+fn parse_paths<'a>(paths: impl Iterator<Item = &'a [u8]>) -> Changes {
+    let mut out = Vec::new();
+    for path in paths.filter(|path| !path.is_empty()) {
+        if out.is_empty() && path.starts_with(b"/") {
+            return Changes::All;
+        }
+        let path = path.strip_suffix(b"/").unwrap_or(path);
+        if !path.is_empty() {
+            out.push(BString::from(path));
+        }
+    }
+    out.sort();
+    out.dedup();
+    Changes::Paths(out)
+}
+
+fn nanos_since_1970() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64)
+}
+
+/// Mark all entries of `state` that are known to be unchanged with [`Flags::FSMONITOR_VALID`], which makes
+/// [`index_as_worktree()`](crate::index_as_worktree()) skip them.
+///
+/// Entries are known to be unchanged if the filesystem monitor extension of `state` doesn't mark them as dirty,
+/// and if they aren't affected by `changes` either. Without such extension, no entry is known to be unchanged.
+pub fn mark_valid_entries(state: &mut gix_index::State, changes: &Changes) {
+    let num_entries = state.entries().len();
+    let mut valid = match state.fs_monitor() {
+        Some(ext) if ext.entry_dirty().num_bits() <= num_entries => {
+            let mut valid = vec![true; num_entries];
+            ext.entry_dirty().for_each_set_bit(|idx| {
+                valid[idx] = false;
+                Some(())
+            });
+            valid
+        }
+        _ => vec![false; num_entries],
+    };
+    match changes {
+        Changes::All => valid.iter_mut().for_each(|v| *v = false),
+        Changes::Paths(paths) => {
+            let mut dir = BString::default();
+            for path in paths {
+                if let Some(range) = state.entry_range(path.as_ref()) {
+                    valid[range].iter_mut().for_each(|v| *v = false);
+                }
+                dir.clear();
+                dir.extend_from_slice(path);
+                dir.push(b'/');
+                if let Some(range) = state.prefixed_entries_range(dir.as_ref()) {
+                    valid[range].iter_mut().for_each(|v| *v = false);
+                }
+            }
+        }
+    }
+    for (entry, valid) in state.entries_mut().iter_mut().zip(valid) {
+        entry.flags.set(Flags::FSMONITOR_VALID, valid);
+    }
+}
+
+/// Create a filesystem monitor extension with `token` that marks all entries of `state` as dirty that don't have
+/// the [`Flags::FSMONITOR_VALID`] flag set.
+///
+/// It's meant to be [set](gix_index::State::set_fs_monitor()) on `state` before it is written.
+pub fn to_extension(state: &gix_index::State, token: Token) -> gix_index::extension::FsMonitor {
+    let mut entry_dirty = gix_bitmap::ewah::Vec::default();
+    for (idx, entry) in state.entries().iter().enumerate() {
+        if !entry.flags.contains(Flags::FSMONITOR_VALID) {
+            entry_dirty.set(idx);
+        }
+    }
+    gix_index::extension::FsMonitor::new(token, entry_dirty)
+}
@@ -106,6 +106,8 @@ pub(super) mod function {
                                         excludes: excludes.as_mut(),
                                         objects: &objects,
                                         explicit_traversal_root: Some(worktree),
+                                        fs_monitor_changed_paths: dirwalk_ctx.fs_monitor_changed_paths,
+                                        untracked_cache: dirwalk_ctx.untracked_cache,
                                     },
                                     options,
                                     &mut collect,
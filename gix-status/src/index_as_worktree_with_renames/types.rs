@@ -1,5 +1,5 @@
 use crate::index_as_worktree::{Change, EntryStatus};
-use bstr::{BStr, ByteSlice};
+use bstr::{BStr, BString, ByteSlice};
 use std::sync::atomic::AtomicBool;
 
 /// The error returned by [index_as_worktree_with_renames()`](crate::index_as_worktree_with_renames()).
@@ -356,6 +356,14 @@ pub struct DirwalkContext<'a> {
     ///
     /// [Read more in `gix-dir`](gix_dir::walk::Context::ignore_case_index_lookup).
     pub ignore_case_index_lookup: Option<&'a gix_index::AccelerateLookup<'a>>,
+    /// The paths a filesystem monitor reported as changed, to limit the directories that are read.
+    ///
+    /// [Read more in `gix-dir`](gix_dir::walk::Context::fs_monitor_changed_paths).
+    pub fs_monitor_changed_paths: Option<&'a [BString]>,
+    /// The untracked cache to use and to update during the directory walk.
+    ///
+    /// [Read more in `gix-dir`](gix_dir::walk::Context::untracked_cache).
+    pub untracked_cache: Option<gix_dir::walk::UntrackedCache<'a>>,
 }
 
 /// Observe the status of an entry by comparing an index entry to the worktree, along
@@ -23,6 +23,14 @@ pub mod index_as_worktree_with_renames;
 #[cfg(feature = "worktree-rewrites")]
 pub use index_as_worktree_with_renames::function::index_as_worktree_with_renames;
 
+#[cfg(feature = "tree-index")]
+pub mod tree_index;
+#[cfg(feature = "tree-index")]
+pub use tree_index::function::tree_index;
+
+#[cfg(feature = "fs-monitor")]
+pub mod fs_monitor;
+
 /// A stack that validates we are not going through a symlink in a way that is read-only.
 ///
 /// It can efficiently validate paths when these are queried in sort-order, which leads to each component
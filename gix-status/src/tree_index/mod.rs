@@ -0,0 +1,291 @@
+//! Changes between a tree and the index, along with optional rename and copy tracking.
+mod types;
+pub use types::{Context, Entry, Error, Options, Outcome, Summary, VisitEntry};
+
+mod recorder;
+pub use recorder::Recorder;
+
+pub(super) mod function {
+    use crate::index_as_worktree::Conflict;
+    use crate::tree_index::{Context, Entry, Error, Options, Outcome, VisitEntry};
+    use bstr::BStr;
+    use gix_diff::rewrites::tracker::ChangeKind;
+    use gix_diff::tree::visit::Action;
+    use gix_object::tree::EntryMode;
+    use std::cmp::Ordering;
+    use std::collections::HashSet;
+
+    /// Compare the entries of `lhs`, an index created from a tree, typically the one of `HEAD`, with the entries of the index `rhs`
+    /// and pass each change to `delegate`, similar to what `git diff --cached` would show.
+    /// Entries are compared by id and mode only, which means the stat information of `lhs` isn't used.
+    ///
+    /// * `lhs`
+    ///     - The index created from a tree, for instance with [`State::from_tree()`](gix_index::State::from_tree()).
+    /// * `rhs`
+    ///     - The actual index, which may contain entries in conflicting state. These are [passed as such](Entry::Conflict),
+    ///       and entries marked with `git add --intent-to-add` are ignored.
+    /// * `delegate`
+    ///     - A [`VisitEntry`] implementation that sees the results of this operation.
+    /// * `objects`
+    ///     - A way to access blobs for similarity checks if rewrites are tracked.
+    /// * `ctx`
+    ///     - Additional information to limit the paths we look at, and to perform similarity checks.
+    /// * `options`
+    ///     - a way to configure the operation.
+    ///
+    /// Note that without rewrite tracking, changes are emitted in the order of their paths. With rewrite tracking, changes
+    /// that are candidates for a rewrite are emitted last, sorted by their path.
+    pub fn tree_index<'lhs, 'rhs, Find>(
+        lhs: &'lhs gix_index::State,
+        rhs: &'rhs gix_index::State,
+        delegate: &mut impl VisitEntry<'lhs, 'rhs>,
+        objects: Find,
+        ctx: Context<'_>,
+        options: Options,
+    ) -> Result<Outcome, Error>
+    where
+        Find: gix_object::FindObjectOrHeader,
+    {
+        let _span = gix_features::trace::coarse!("gix_status::tree_index()");
+        let Context {
+            mut pathspec,
+            pathspec_attributes,
+            resource_cache,
+        } = ctx;
+        let mut out = Outcome::default();
+        let mut tracker = options
+            .rewrites
+            .map(gix_diff::rewrites::Tracker::<Entry<'lhs, 'rhs>>::new);
+        let mut unchanged_copy_sources = options
+            .rewrites
+            .and_then(|rewrites| rewrites.copies)
+            .filter(|copies| copies.source == gix_diff::rewrites::CopySource::FromSetOfModifiedFilesAndAllSources)
+            .map(|_| Vec::new());
+
+        let (lhs_entries, rhs_entries) = (lhs.entries(), rhs.entries());
+        let (mut lhs_idx, mut rhs_idx) = (0, 0);
+        let mut is_excluded = |rela_path: &BStr, mode: gix_index::entry::Mode, skipped: &mut usize| {
+            let excluded = pathspec
+                .pattern_matching_relative_path(rela_path, Some(mode.is_submodule()), pathspec_attributes)
+                .map_or(true, |m| m.is_excluded());
+            *skipped += usize::from(excluded);
+            excluded
+        };
+        let mut emit = |entry: Entry<'lhs, 'rhs>| -> Action {
+            match tracker.as_mut() {
+                Some(tracker) => match tracker.try_push_change(entry, entry.rela_path()) {
+                    Some(entry) => delegate.visit_entry(entry),
+                    None => Action::Continue,
+                },
+                None => delegate.visit_entry(entry),
+            }
+        };
+        loop {
+            let lhs_entry = lhs_entries.get(lhs_idx).map(|e| (e, e.path(lhs)));
+            let rhs_entry = rhs_entries.get(rhs_idx).map(|e| (e, e.path(rhs)));
+            if let Some((entry, rela_path)) = lhs_entry {
+                if is_excluded(rela_path, entry.mode, &mut out.entries_skipped_by_pathspec) {
+                    lhs_idx += 1;
+                    continue;
+                }
+            }
+            if let Some((entry, rela_path)) = rhs_entry {
+                if entry.flags.contains(gix_index::entry::Flags::INTENT_TO_ADD) {
+                    out.entries_skipped_intent_to_add += 1;
+                    rhs_idx += 1;
+                    continue;
+                }
+                if is_excluded(rela_path, entry.mode, &mut out.entries_skipped_by_pathspec) {
+                    rhs_idx += 1;
+                    continue;
+                }
+            }
+
+            let ordering = match (lhs_entry, rhs_entry) {
+                (None, None) => break,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some((_, lhs_path)), Some((_, rhs_path))) => lhs_path.cmp(rhs_path),
+            };
+            let entry = match ordering {
+                Ordering::Less => {
+                    let (entry, rela_path) = lhs_entry.expect("present if less");
+                    let entry = Entry::Deletion {
+                        entry,
+                        entry_index: lhs_idx,
+                        rela_path,
+                    };
+                    lhs_idx += 1;
+                    entry
+                }
+                Ordering::Greater | Ordering::Equal => {
+                    let (entry, rela_path) = rhs_entry.expect("present if greater or equal");
+                    let previous_entry = lhs_entry.filter(|_| ordering == Ordering::Equal).map(|(e, _)| e);
+                    if previous_entry.is_some() {
+                        lhs_idx += 1;
+                    }
+                    let entry_index = rhs_idx;
+                    rhs_idx += 1;
+                    if entry.stage_raw() != 0 {
+                        match Conflict::try_from_entry(rhs_entries, rhs.path_backing(), entry_index, rela_path) {
+                            Some((conflict, num_extra_entries)) => {
+                                rhs_idx += num_extra_entries;
+                                Entry::Conflict {
+                                    entry,
+                                    entry_index,
+                                    rela_path,
+                                    conflict,
+                                }
+                            }
+                            None => continue,
+                        }
+                    } else {
+                        match previous_entry {
+                            None => Entry::Addition {
+                                entry,
+                                entry_index,
+                                rela_path,
+                            },
+                            Some(previous_entry) => {
+                                let change = Entry::Modification {
+                                    previous_entry,
+                                    previous_entry_index: lhs_idx - 1,
+                                    entry,
+                                    entry_index,
+                                    rela_path,
+                                };
+                                if previous_entry.id == entry.id && previous_entry.mode == entry.mode {
+                                    if let Some(sources) = unchanged_copy_sources.as_mut().filter(|_| {
+                                        entry
+                                            .mode
+                                            .to_tree_entry_mode()
+                                            .map_or(false, |mode| mode.is_blob_or_symlink())
+                                    }) {
+                                        sources.push(change);
+                                    }
+                                    continue;
+                                }
+                                change
+                            }
+                        }
+                    }
+                }
+            };
+            if emit(entry) == Action::Cancel {
+                return Ok(out);
+            }
+        }
+
+        let Some(mut tracker) = tracker else {
+            return Ok(out);
+        };
+        let mut emitted_copy_sources = HashSet::new();
+        let mut cancelled = false;
+        let outcome = tracker
+            .emit(
+                |dest, source| {
+                    let Some(source) = source else {
+                        return delegate.visit_entry(dest.change);
+                    };
+                    let Entry::Addition {
+                        entry,
+                        entry_index,
+                        rela_path,
+                    } = dest.change
+                    else {
+                        unreachable!("BUG: only additions can be the destination of a rewrite")
+                    };
+                    let (source_entry, source_entry_index, source_rela_path) = match *source.change {
+                        Entry::Deletion {
+                            entry,
+                            entry_index,
+                            rela_path,
+                        } => (entry, entry_index, rela_path),
+                        Entry::Modification {
+                            previous_entry,
+                            previous_entry_index,
+                            entry,
+                            entry_index,
+                            ..
+                        } => {
+                            let is_modified = previous_entry.id != entry.id || previous_entry.mode != entry.mode;
+                            if is_modified
+                                && emitted_copy_sources.insert(entry_index)
+                                && delegate.visit_entry(*source.change) == Action::Cancel
+                            {
+                                cancelled = true;
+                                return Action::Cancel;
+                            }
+                            (previous_entry, previous_entry_index, previous_entry.path(lhs))
+                        }
+                        Entry::Addition { .. } | Entry::Rewrite { .. } | Entry::Conflict { .. } => {
+                            unreachable!("BUG: only deletions and modifications can be the source of a rewrite")
+                        }
+                    };
+                    let action = delegate.visit_entry(Entry::Rewrite {
+                        source_entry,
+                        source_entry_index,
+                        source_rela_path,
+                        entry,
+                        entry_index,
+                        rela_path,
+                        diff: source.diff,
+                        copy: source.kind == gix_diff::rewrites::tracker::visit::SourceKind::Copy,
+                    });
+                    cancelled |= action == Action::Cancel;
+                    action
+                },
+                resource_cache,
+                &objects,
+                |push| {
+                    for source in unchanged_copy_sources.take().unwrap_or_default() {
+                        push(source, source.rela_path());
+                    }
+                    Ok::<_, std::convert::Infallible>(())
+                },
+            )
+            .map_err(Box::new)?;
+        if !cancelled {
+            out.rewrites = Some(outcome);
+        }
+        Ok(out)
+    }
+
+    impl gix_diff::rewrites::tracker::Change for Entry<'_, '_> {
+        /// Note that modifications are identified by their previous state, as they are only used as sources of copies.
+        fn id(&self) -> &gix_hash::oid {
+            match self {
+                Entry::Modification { previous_entry, .. } => &previous_entry.id,
+                Entry::Addition { entry, .. }
+                | Entry::Deletion { entry, .. }
+                | Entry::Rewrite { entry, .. }
+                | Entry::Conflict { entry, .. } => &entry.id,
+            }
+        }
+
+        fn kind(&self) -> ChangeKind {
+            match self {
+                Entry::Addition { .. } => ChangeKind::Addition,
+                Entry::Deletion { .. } => ChangeKind::Deletion,
+                Entry::Modification { .. } | Entry::Rewrite { .. } | Entry::Conflict { .. } => ChangeKind::Modification,
+            }
+        }
+
+        fn entry_mode(&self) -> EntryMode {
+            match self {
+                Entry::Addition { entry, .. }
+                | Entry::Deletion { entry, .. }
+                | Entry::Modification { entry, .. }
+                | Entry::Rewrite { entry, .. }
+                | Entry::Conflict { entry, .. } => entry
+                    .mode
+                    .to_tree_entry_mode()
+                    .unwrap_or(gix_object::tree::EntryKind::Blob.into()),
+            }
+        }
+
+        fn id_and_entry_mode(&self) -> (&gix_hash::oid, EntryMode) {
+            (self.id(), self.entry_mode())
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use crate::tree_index::{Entry, VisitEntry};
+
+/// Convenience implementation of [`VisitEntry`] that collects all changes into a `Vec`.
+#[derive(Debug, Default)]
+pub struct Recorder<'lhs, 'rhs> {
+    /// The collected changes.
+    pub records: Vec<Entry<'lhs, 'rhs>>,
+}
+
+impl<'lhs, 'rhs> VisitEntry<'lhs, 'rhs> for Recorder<'lhs, 'rhs> {
+    fn visit_entry(&mut self, entry: Entry<'lhs, 'rhs>) -> gix_diff::tree::visit::Action {
+        self.records.push(entry);
+        gix_diff::tree::visit::Action::Continue
+    }
+}
@@ -0,0 +1,216 @@
+use crate::index_as_worktree::Conflict;
+use bstr::BStr;
+
+/// The error returned by [`tree_index()`](crate::tree_index()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    RewriteTracker(#[from] Box<gix_diff::rewrites::tracker::emit::Error>),
+}
+
+/// Provide additional information collected during the runtime of [`tree_index()`](crate::tree_index()).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Outcome {
+    /// The amount of entries in the tree-index and the index that were skipped as they didn't match the pathspec.
+    pub entries_skipped_by_pathspec: usize,
+    /// The amount of index entries that were skipped as they were marked with `git add --intent-to-add`.
+    pub entries_skipped_intent_to_add: usize,
+    /// The result of the rewrite operation, if [rewrites were configured](Options::rewrites).
+    pub rewrites: Option<gix_diff::rewrites::Outcome>,
+}
+
+/// A change between the tree, represented as index, and the actual index.
+///
+/// Entries of the tree are on the *left* and are referred to as `previous_*` or `source_*`,
+/// whereas entries of the index are on the *right*.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Entry<'lhs, 'rhs> {
+    /// An entry was added to the index, and isn't present in the tree.
+    Addition {
+        /// The added index entry.
+        entry: &'rhs gix_index::Entry,
+        /// The position of `entry` in the entries of the index.
+        entry_index: usize,
+        /// The repository-relative path of the entry.
+        rela_path: &'rhs BStr,
+    },
+    /// An entry of the tree is missing in the index.
+    Deletion {
+        /// The entry of the tree that was deleted.
+        entry: &'lhs gix_index::Entry,
+        /// The position of `entry` in the entries of the tree-index.
+        entry_index: usize,
+        /// The repository-relative path of the entry.
+        rela_path: &'lhs BStr,
+    },
+    /// An entry is present in both, but changed its content or its mode or both.
+    ///
+    /// If both entries are submodules, the commit that is checked out in the submodule changed.
+    Modification {
+        /// The entry in the tree.
+        previous_entry: &'lhs gix_index::Entry,
+        /// The position of `previous_entry` in the entries of the tree-index.
+        previous_entry_index: usize,
+        /// The entry in the index.
+        entry: &'rhs gix_index::Entry,
+        /// The position of `entry` in the entries of the index.
+        entry_index: usize,
+        /// The repository-relative path of the entry, which is the same in the tree and in the index.
+        rela_path: &'rhs BStr,
+    },
+    /// The rewrite tracking matched an entry of the tree to an index entry at a different location,
+    /// and considers them similar enough, depending on the tracker settings.
+    Rewrite {
+        /// The entry of the tree that is the source of the rewrite.
+        source_entry: &'lhs gix_index::Entry,
+        /// The position of `source_entry` in the entries of the tree-index.
+        source_entry_index: usize,
+        /// The repository-relative path of `source_entry`.
+        source_rela_path: &'lhs BStr,
+        /// The index entry that is the destination of the rewrite.
+        entry: &'rhs gix_index::Entry,
+        /// The position of `entry` in the entries of the index.
+        entry_index: usize,
+        /// The repository-relative path of `entry`.
+        rela_path: &'rhs BStr,
+        /// It's `None` if `source_entry` and `entry` have the same id, as identity made an actual diff computation unnecessary.
+        /// Otherwise, and if enabled, it's `Some(stats)` to indicate how similar both entries were.
+        diff: Option<gix_diff::blob::DiffLineStats>,
+        /// If true, this rewrite is created by copy, and `source_entry` still exists in the index.
+        /// Otherwise, it's a rename, and `source_entry` was deleted.
+        copy: bool,
+    },
+    /// The index has unresolved conflicts at a path, represented by multiple entries in different stages.
+    ///
+    /// As the index entries of a conflict are no viable comparison with the tree, the tree entry
+    /// at the same path, if there is one, isn't reported separately.
+    Conflict {
+        /// The first of the index entries that make up the conflict.
+        entry: &'rhs gix_index::Entry,
+        /// The position of `entry` in the entries of the index, with all other stages following it.
+        entry_index: usize,
+        /// The repository-relative path of the conflicting entries.
+        rela_path: &'rhs BStr,
+        /// The kind of conflict.
+        conflict: Conflict,
+    },
+}
+
+/// An easy to grasp summary of the changes of the index compared to the tree.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub enum Summary {
+    /// An entry exists in the index but not in the tree.
+    Added,
+    /// An entry exists in the tree but not in the index.
+    Removed,
+    /// An entry changed its content or executable bit, or a submodule now points to a different commit.
+    Modified,
+    /// The type of the entry changed, for instance from a file to a symlink or submodule.
+    TypeChange,
+    /// An entry of the tree was moved to a different location in the index, possibly with modifications.
+    Renamed,
+    /// An entry of the tree was copied to a different location in the index, possibly with modifications.
+    Copied,
+    /// The index entry is in conflict.
+    Conflict,
+}
+
+impl Summary {
+    /// Summarize the change of an entry with `previous_mode` in the tree to an entry with `mode` in the index at the same path,
+    /// which is a [type change](Summary::TypeChange) unless both modes only differ in their executable bit.
+    pub fn from_modification(previous_mode: gix_index::entry::Mode, mode: gix_index::entry::Mode) -> Self {
+        if without_executable_bit(previous_mode) == without_executable_bit(mode) {
+            Summary::Modified
+        } else {
+            Summary::TypeChange
+        }
+    }
+}
+
+/// Access
+impl Entry<'_, '_> {
+    /// Return a summary of this entry as digest of its status.
+    pub fn summary(&self) -> Summary {
+        match self {
+            Entry::Addition { .. } => Summary::Added,
+            Entry::Deletion { .. } => Summary::Removed,
+            Entry::Modification {
+                previous_entry, entry, ..
+            } => Summary::from_modification(previous_entry.mode, entry.mode),
+            Entry::Rewrite { copy, .. } => {
+                if *copy {
+                    Summary::Copied
+                } else {
+                    Summary::Renamed
+                }
+            }
+            Entry::Conflict { .. } => Summary::Conflict,
+        }
+    }
+
+    /// The repository-relative path at which the source of a rewrite is located.
+    ///
+    /// If this isn't a rewrite, the path is the location of the entry itself.
+    pub fn source_rela_path(&self) -> &BStr {
+        match self {
+            Entry::Rewrite { source_rela_path, .. } => source_rela_path,
+            Entry::Deletion { rela_path, .. } => rela_path,
+            Entry::Addition { rela_path, .. }
+            | Entry::Modification { rela_path, .. }
+            | Entry::Conflict { rela_path, .. } => rela_path,
+        }
+    }
+
+    /// The repository-relative path of the entry in the index, or of the deleted entry in the tree.
+    pub fn rela_path(&self) -> &BStr {
+        match self {
+            Entry::Deletion { rela_path, .. } => rela_path,
+            Entry::Addition { rela_path, .. }
+            | Entry::Modification { rela_path, .. }
+            | Entry::Rewrite { rela_path, .. }
+            | Entry::Conflict { rela_path, .. } => rela_path,
+        }
+    }
+}
+
+fn without_executable_bit(mode: gix_index::entry::Mode) -> gix_index::entry::Mode {
+    if mode == gix_index::entry::Mode::FILE_EXECUTABLE {
+        gix_index::entry::Mode::FILE
+    } else {
+        mode
+    }
+}
+
+/// Options for use in [`tree_index()`](crate::tree_index()).
+#[derive(Clone, Default)]
+pub struct Options {
+    /// The configuration for the rewrite tracking, or `None` to not track rewrites.
+    ///
+    /// Note that if set, changes that are candidates for rewrites will be held back until all entries
+    /// were seen, which changes the order in which entries are observed. Their order will then be sorted by path.
+    pub rewrites: Option<gix_diff::Rewrites>,
+}
+
+/// The context for [`tree_index()`](crate::tree_index()).
+pub struct Context<'a> {
+    /// The pathspec to limit the amount of paths that are checked. Can be empty to allow all paths.
+    pub pathspec: gix_pathspec::Search,
+    /// A way to obtain attributes for use with pathspecs that use them, see
+    /// [`Search::pattern_matching_relative_path()`](gix_pathspec::Search::pattern_matching_relative_path()).
+    pub pathspec_attributes: &'a mut dyn FnMut(
+        &BStr,
+        gix_pathspec::attributes::glob::pattern::Case,
+        bool,
+        &mut gix_pathspec::attributes::search::Outcome,
+    ) -> bool,
+    /// A platform to produce diffable buffers, used by the rewrite tracking to determine the similarity of
+    /// blobs. Both sides of the comparison are read from the object database.
+    pub resource_cache: &'a mut gix_diff::blob::Platform,
+}
+
+/// Observe the changes between a tree and the index.
+pub trait VisitEntry<'lhs, 'rhs> {
+    /// Observe `entry`, and return [`Action::Cancel`](gix_diff::tree::visit::Action::Cancel) to stop the operation.
+    fn visit_entry(&mut self, entry: Entry<'lhs, 'rhs>) -> gix_diff::tree::visit::Action;
+}
@@ -0,0 +1,39 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+echo a > a
+mkdir dir
+echo b > dir/b
+echo c > dir/c
+echo d > d
+
+git add -A
+git commit -q -m "Commit"
+
+mkdir hooks
+cat <<'HOOK' > hooks/v2
+#!/bin/sh
+test "$1" = 2 || exit 1
+test "$2" = "previous-token" || exit 1
+printf 'new-token\0dir/\0d\0a\0d\0'
+HOOK
+
+cat <<'HOOK' > hooks/v1
+#!/bin/sh
+test "$1" = 1 || exit 1
+test "$2" = 42 || exit 1
+printf 'dir/b\0'
+HOOK
+
+cat <<'HOOK' > hooks/v2-all
+#!/bin/sh
+printf 'new-token\0/\0a\0'
+HOOK
+
+cat <<'HOOK' > hooks/v2-no-token
+#!/bin/sh
+printf ''
+HOOK
+chmod +x hooks/*
@@ -0,0 +1,51 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q changes
+(cd changes
+  echo content >modified
+  echo content >deleted
+  printf '1\n2\n3\n4\n5\n6\n7\n8\n' >to-rename
+  echo copy-source >copy-source
+  echo exe >exe
+  echo link-target >file-to-link
+  mkdir dir
+  echo content >dir/unchanged
+  git add .
+  git commit -q -m base
+  git update-index --add --cacheinfo "160000,$(git rev-parse HEAD),submodule"
+  git commit -q -m "add submodule"
+
+  echo changed >modified
+  git rm -q deleted
+  echo new >added
+  git mv to-rename renamed
+  printf '1\n2\n3\n4\n5\n6\n7\n8\n9\n' >renamed
+  cp copy-source copied
+  chmod +x exe
+  rm file-to-link && ln -s dir file-to-link
+  git add modified added renamed copied exe file-to-link
+  git update-index --cacheinfo "160000,$(git rev-parse HEAD),submodule"
+  echo intent >intent-to-add
+  git add --intent-to-add intent-to-add
+  echo unstaged >dir/unchanged
+)
+
+git init -q conflict
+(cd conflict
+  echo base >content
+  echo base >other
+  git add -A
+  git commit -q -m "base"
+
+  git checkout -q -b feat
+  echo feat >content
+  git commit -q -am "feat"
+
+  git checkout -q main
+  echo base-change >content
+  echo other-change >other
+  git commit -q -am "new base"
+
+  git merge feat || :
+)
@@ -0,0 +1,143 @@
+use bstr::BString;
+use gix_index::entry::Flags;
+use gix_status::fs_monitor::{mark_valid_entries, to_extension, Changes, Hook, Outcome, Token, Version};
+
+use crate::fixture_path;
+
+#[test]
+fn query_without_token_considers_everything_changed_without_running_the_hook() {
+    let worktree = fixture_path("fs_monitor");
+    let out = hook("does-not-exist", None).query(&worktree, None).unwrap();
+    assert_eq!(out.changes, Changes::All);
+    assert!(
+        matches!(out.token, Token::V2 { ref token } if token.iter().all(u8::is_ascii_digit)),
+        "the current time is used as token"
+    );
+}
+
+#[test]
+fn query_v2_returns_the_new_token_and_sorted_paths() {
+    let worktree = fixture_path("fs_monitor");
+    let token = v2_token("previous-token");
+    for version in [Some(Version::V2), None] {
+        let out = hook("hooks/v2", version).query(&worktree, Some(&token)).unwrap();
+        assert_eq!(
+            out,
+            Outcome {
+                token: v2_token("new-token"),
+                changes: Changes::Paths(paths(["a", "d", "dir"])),
+            },
+            "trailing slashes are removed, and duplicates are dropped"
+        );
+    }
+}
+
+#[test]
+fn query_falls_back_to_v1_if_the_version_is_unset() {
+    let worktree = fixture_path("fs_monitor");
+    let token = Token::V1 { nanos_since_1970: 42 };
+    let err = hook("hooks/v1", Some(Version::V2))
+        .query(&worktree, Some(&token))
+        .unwrap_err();
+    assert!(matches!(err, gix_status::fs_monitor::query::Error::Failed { .. }));
+
+    for version in [Some(Version::V1), None] {
+        let out = hook("hooks/v1", version).query(&worktree, Some(&token)).unwrap();
+        assert_eq!(out.changes, Changes::Paths(paths(["dir/b"])));
+        assert!(
+            matches!(out.token, Token::V2 { ref token } if token.iter().all(u8::is_ascii_digit)),
+            "like git, the time before the query is used as new token, written in the version 2 format"
+        );
+    }
+}
+
+#[test]
+fn query_v2_with_leading_absolute_path_considers_everything_changed() {
+    let worktree = fixture_path("fs_monitor");
+    let out = hook("hooks/v2-all", Some(Version::V2))
+        .query(&worktree, Some(&v2_token("previous-token")))
+        .unwrap();
+    assert_eq!(out.changes, Changes::All);
+    assert_eq!(out.token, v2_token("new-token"));
+
+    let err = hook("hooks/v2-no-token", Some(Version::V2))
+        .query(&worktree, Some(&v2_token("previous-token")))
+        .unwrap_err();
+    assert!(matches!(err, gix_status::fs_monitor::query::Error::MissingToken { .. }));
+}
+
+#[test]
+fn mark_valid_entries_and_to_extension() {
+    let worktree = fixture_path("fs_monitor");
+    let mut index = gix_index::File::at(
+        worktree.join(".git").join("index"),
+        gix_hash::Kind::Sha1,
+        false,
+        Default::default(),
+    )
+    .unwrap();
+    assert!(index.fs_monitor().is_none());
+
+    mark_valid_entries(&mut index, &Changes::Paths(Vec::new()));
+    assert_eq!(
+        valid_paths(&index),
+        Vec::<&str>::new(),
+        "without extension, no entry is known to be unchanged"
+    );
+
+    index
+        .entries_mut()
+        .iter_mut()
+        .for_each(|e| e.flags.insert(Flags::FSMONITOR_VALID));
+    let ext = to_extension(&index, v2_token("token"));
+    assert_eq!(ext.entry_dirty().num_bits(), 0, "nothing is dirty");
+    index.set_fs_monitor(Some(ext));
+
+    mark_valid_entries(&mut index, &Changes::Paths(paths(["d", "dir"])));
+    assert_eq!(
+        valid_paths(&index),
+        ["a"],
+        "paths match entries exactly or as directory, all other entries are trusted"
+    );
+
+    let ext = to_extension(&index, v2_token("next"));
+    let mut dirty = Vec::new();
+    ext.entry_dirty().for_each_set_bit(|idx| {
+        dirty.push(idx);
+        Some(())
+    });
+    assert_eq!(dirty, [1, 2, 3], "only `a` isn't dirty");
+    assert_eq!(ext.token(), &v2_token("next"));
+    index.set_fs_monitor(Some(ext));
+
+    mark_valid_entries(&mut index, &Changes::Paths(Vec::new()));
+    assert_eq!(valid_paths(&index), ["a"], "dirty entries remain dirty");
+
+    mark_valid_entries(&mut index, &Changes::All);
+    assert_eq!(valid_paths(&index), Vec::<&str>::new());
+}
+
+fn valid_paths(index: &gix_index::State) -> Vec<&str> {
+    index
+        .entries()
+        .iter()
+        .filter(|e| e.flags.contains(Flags::FSMONITOR_VALID))
+        .map(|e| std::str::from_utf8(e.path(index)).unwrap())
+        .collect()
+}
+
+fn hook(program: &str, version: Option<Version>) -> Hook {
+    Hook {
+        program: program.into(),
+        version,
+        context: None,
+    }
+}
+
+fn v2_token(token: &str) -> Token {
+    Token::V2 { token: token.into() }
+}
+
+fn paths<const N: usize>(paths: [&str; N]) -> Vec<BString> {
+    paths.into_iter().map(Into::into).collect()
+}
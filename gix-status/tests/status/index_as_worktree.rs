@@ -486,6 +486,40 @@ fn refresh() {
     );
 }
 
+#[test]
+fn modified_with_fs_monitor_only_checks_reported_entries() {
+    let actual_outcome = fixture_with_index(
+        "status_changed",
+        |index| {
+            use gix_status::fs_monitor::{self, Changes, Token};
+            index
+                .entries_mut()
+                .iter_mut()
+                .for_each(|e| e.flags.insert(gix_index::entry::Flags::FSMONITOR_VALID));
+            let ext = fs_monitor::to_extension(index, Token::V2 { token: "token".into() });
+            index.set_fs_monitor(Some(ext));
+            fs_monitor::mark_valid_entries(index, &Changes::Paths(vec!["dir/content2".into(), "empty".into()]));
+        },
+        &[
+            (
+                BStr::new(b"dir/content2"),
+                1,
+                Change::Modification {
+                    executable_bit_changed: false,
+                    content_change: Some(()),
+                    set_entry_stat_size_zero: false,
+                }
+                .into(),
+            ),
+            (BStr::new(b"empty"), 3, Change::Type.into()),
+        ],
+    );
+    assert_eq!(
+        actual_outcome.entries_skipped_by_entry_flags, 3,
+        "entries that the filesystem monitor didn't report are trusted to be unchanged"
+    );
+}
+
 #[test]
 fn modified() {
     let expected_outcome = Outcome {
@@ -240,6 +240,8 @@ fn fixture_filtered_detailed(
             git_dir_realpath: &git_dir_real,
             current_dir: &cwd,
             ignore_case_index_lookup: None,
+            fs_monitor_changed_paths: None,
+            untracked_cache: None,
         },
     };
     let options = Options {
@@ -0,0 +1,216 @@
+use crate::status::fixture_path;
+use bstr::ByteSlice;
+use gix_diff::rewrites::{Copies, CopySource};
+use gix_object::FindExt;
+use gix_status::index_as_worktree::Conflict;
+use gix_status::tree_index::{Context, Entry, Options, Outcome, Recorder, Summary};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn changes_without_rewrite_tracking() {
+    let (actual, out) = fixture("changes", &[], None);
+    assert_eq!(
+        actual,
+        expected([
+            ("added", Summary::Added, None),
+            ("copied", Summary::Added, None),
+            ("deleted", Summary::Removed, None),
+            ("exe", Summary::Modified, None),
+            ("file-to-link", Summary::TypeChange, None),
+            ("modified", Summary::Modified, None),
+            ("renamed", Summary::Added, None),
+            ("submodule", Summary::Modified, None),
+            ("to-rename", Summary::Removed, None),
+        ]),
+        "submodules that point to a different commit are modifications, unstaged changes are ignored"
+    );
+    assert_eq!(
+        out,
+        Outcome {
+            entries_skipped_by_pathspec: 0,
+            entries_skipped_intent_to_add: 1,
+            rewrites: None,
+        }
+    );
+}
+
+#[test]
+fn changes_with_rename_tracking() {
+    let (actual, out) = fixture("changes", &[], Some(gix_diff::Rewrites::default()));
+    assert_eq!(
+        actual,
+        expected([
+            ("exe", Summary::Modified, None),
+            ("file-to-link", Summary::TypeChange, None),
+            ("modified", Summary::Modified, None),
+            ("submodule", Summary::Modified, None),
+            ("renamed", Summary::Renamed, Some("to-rename")),
+            ("added", Summary::Added, None),
+            ("copied", Summary::Added, None),
+            ("deleted", Summary::Removed, None),
+        ]),
+        "changes that aren't candidates for renames are emitted right away, followed by rewrites and the remaining candidates"
+    );
+    let rewrites = out.rewrites.expect("rewrites were tracked");
+    assert_eq!(
+        rewrites.num_similarity_checks, 3,
+        "only files that differ in content need a similarity check"
+    );
+}
+
+#[test]
+fn changes_with_copy_tracking_from_all_sources() {
+    let (actual, _out) = fixture(
+        "changes",
+        &[],
+        Some(gix_diff::Rewrites {
+            copies: Some(Copies {
+                source: CopySource::FromSetOfModifiedFilesAndAllSources,
+                percentage: Some(0.5),
+            }),
+            ..Default::default()
+        }),
+    );
+    assert_eq!(
+        actual,
+        expected([
+            ("submodule", Summary::Modified, None),
+            ("renamed", Summary::Renamed, Some("to-rename")),
+            ("copied", Summary::Copied, Some("copy-source")),
+            ("added", Summary::Added, None),
+            ("deleted", Summary::Removed, None),
+            ("exe", Summary::Modified, None),
+            ("file-to-link", Summary::TypeChange, None),
+            ("modified", Summary::Modified, None),
+        ]),
+        "unchanged files can be the source of copies, and modified ones still show up as such"
+    );
+}
+
+#[test]
+fn changes_limited_by_pathspec() {
+    let (actual, out) = fixture("changes", &["modified", "dir"], None);
+    assert_eq!(actual, expected([("modified", Summary::Modified, None)]));
+    assert_eq!(
+        out.entries_skipped_by_pathspec, 13,
+        "entries of both sides count, except for the one marked with intent-to-add"
+    );
+}
+
+#[test]
+fn conflicts_are_reported_once_and_hide_the_tree_entry() {
+    let worktree = fixture_path("status_tree_index.sh").join("conflict");
+    let (lhs, rhs) = tree_and_index(&worktree);
+    let mut recorder = Recorder::default();
+    run(&worktree, &lhs, &rhs, &mut recorder, &[], Some(Default::default()));
+    assert_eq!(recorder.records.len(), 1);
+    let Entry::Conflict {
+        entry_index,
+        rela_path,
+        conflict,
+        ..
+    } = recorder.records[0]
+    else {
+        panic!("expected conflict, got {:?}", recorder.records[0])
+    };
+    assert_eq!(rela_path, "content");
+    assert_eq!(entry_index, 0, "the first of all stages is passed");
+    assert_eq!(conflict, Conflict::BothModified);
+}
+
+type Expectation = (String, Summary, Option<String>);
+
+fn expected<const N: usize>(items: [(&str, Summary, Option<&str>); N]) -> Vec<Expectation> {
+    items
+        .into_iter()
+        .map(|(rela_path, summary, source)| (rela_path.to_owned(), summary, source.map(ToOwned::to_owned)))
+        .collect()
+}
+
+fn fixture(subdir: &str, pathspecs: &[&str], rewrites: Option<gix_diff::Rewrites>) -> (Vec<Expectation>, Outcome) {
+    let worktree = fixture_path("status_tree_index.sh").join(subdir);
+    let (lhs, rhs) = tree_and_index(&worktree);
+    let mut recorder = Recorder::default();
+    let out = run(&worktree, &lhs, &rhs, &mut recorder, pathspecs, rewrites);
+    let actual = recorder
+        .records
+        .iter()
+        .map(|entry| {
+            let rela_path = entry.rela_path().to_str().unwrap().to_owned();
+            let source =
+                matches!(entry, Entry::Rewrite { .. }).then(|| entry.source_rela_path().to_str().unwrap().to_owned());
+            (rela_path, entry.summary(), source)
+        })
+        .collect();
+    (actual, out)
+}
+
+fn tree_and_index(worktree: &std::path::Path) -> (gix_index::State, gix_index::File) {
+    let git_dir = worktree.join(".git");
+    let odb = gix_odb::at(git_dir.join("objects")).unwrap();
+    let head = std::fs::read_to_string(git_dir.join("HEAD")).unwrap();
+    let head_ref = head.trim().strip_prefix("ref: ").expect("symbolic HEAD");
+    let head_id = gix_hash::ObjectId::from_hex(
+        std::fs::read_to_string(git_dir.join(head_ref))
+            .unwrap()
+            .trim()
+            .as_bytes(),
+    )
+    .unwrap();
+    let tree = odb.find_commit(&head_id, &mut Vec::new()).unwrap().tree();
+    let lhs = gix_index::State::from_tree(&tree, &odb, Default::default()).unwrap();
+    let rhs = gix_index::File::at(git_dir.join("index"), gix_hash::Kind::Sha1, false, Default::default()).unwrap();
+    (lhs, rhs)
+}
+
+fn run<'lhs, 'rhs>(
+    worktree: &std::path::Path,
+    lhs: &'lhs gix_index::State,
+    rhs: &'rhs gix_index::State,
+    recorder: &mut Recorder<'lhs, 'rhs>,
+    pathspecs: &[&str],
+    rewrites: Option<gix_diff::Rewrites>,
+) -> Outcome {
+    let git_dir = worktree.join(".git");
+    let pathspec = gix_pathspec::Search::from_specs(
+        crate::status::index_as_worktree::to_pathspecs(pathspecs),
+        None,
+        std::path::Path::new(""),
+    )
+    .expect("valid specs can be normalized");
+    let stack = gix_worktree::Stack::from_state_and_ignore_case(
+        worktree,
+        false,
+        gix_worktree::stack::State::AttributesStack(Default::default()),
+        rhs,
+        rhs.path_backing(),
+    );
+    let mut resource_cache = gix_diff::blob::Platform::new(
+        Default::default(),
+        gix_diff::blob::Pipeline::new(
+            Default::default(),
+            gix_filter::Pipeline::new(Default::default(), Default::default()),
+            vec![],
+            gix_diff::blob::pipeline::Options {
+                large_file_threshold_bytes: 0,
+                fs: gix_fs::Capabilities::probe(&git_dir),
+            },
+        ),
+        gix_diff::blob::pipeline::Mode::ToGit,
+        stack,
+    );
+    let objects = gix_odb::at(git_dir.join("objects")).unwrap().into_arc().unwrap();
+    gix_status::tree_index(
+        lhs,
+        rhs,
+        recorder,
+        objects,
+        Context {
+            pathspec,
+            pathspec_attributes: &mut |_, _, _, _| unreachable!("no pathspec attributes are used"),
+            resource_cache: &mut resource_cache,
+        },
+        Options { rewrites },
+    )
+    .unwrap()
+}
@@ -0,0 +1,522 @@
+use std::{
+    any::Any,
+    borrow::Cow,
+    cell::RefCell,
+    future::Future,
+    io,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use async_trait::async_trait;
+use base64::Engine;
+use bstr::BStr;
+use futures_io::{AsyncBufRead, AsyncRead, AsyncWrite};
+use futures_lite::{ready, AsyncBufReadExt, AsyncReadExt, StreamExt};
+use gix_packetline::{PacketLineRef, StreamingPeekableIter};
+
+use crate::{
+    client::{
+        self, capabilities,
+        http::{append_url, AuthenticationRequired, Error, GetResponse},
+        Capabilities, ExtendedBufRead, HandleProgress, MessageKind, ReadlineBufRead, RequestWriter,
+    },
+    Protocol, Service,
+};
+
+/// The `reqwest` backend for async HTTP transports.
+///
+/// Note that `reqwest` performs its IO using `tokio`, which means it has to be used from within a `tokio` runtime.
+#[cfg(feature = "async-http-client-reqwest")]
+pub mod reqwest;
+
+/// The actual http client implementation, using reqwest
+#[cfg(feature = "async-http-client-reqwest")]
+pub type Impl = reqwest::Remote;
+
+/// A trait to abstract the HTTP operations needed to power all git interactions: read via GET and write via POST.
+///
+/// Note that 401 must be turned into `std::io::Error(PermissionDenied)`, and other non-success http statuses must be transformed
+/// into `std::io::Error(Other)`. These errors are expected to be returned when reading the response [headers](Http::Headers).
+/// The 401 error should wrap an [`AuthenticationRequired`] to pass on the `WWW-Authenticate` headers to credential helpers.
+#[async_trait(?Send)]
+pub trait Http {
+    /// A type providing headers line by line.
+    type Headers: AsyncBufRead + Unpin;
+    /// A type providing the response.
+    type ResponseBody: AsyncBufRead + Unpin;
+
+    /// Initiate a `GET` request to `url` provided the given `headers`, where `base_url` is so that `base_url + tail == url`.
+    ///
+    /// The `base_url` helps to validate redirects and to swap it with the effective base after a redirect.
+    ///
+    /// The `headers` are provided verbatim and include both the key as well as the value.
+    async fn get(
+        &mut self,
+        url: &str,
+        base_url: &str,
+        headers: &[Cow<'_, str>],
+    ) -> Result<GetResponse<Self::Headers, Self::ResponseBody>, Error>;
+
+    /// Initiate a `POST` request to `url` providing the given `headers` and `body`, where `base_url` is so that `base_url + tail == url`.
+    ///
+    /// The `base_url` helps to validate redirects and to swap it with the effective base after a redirect.
+    ///
+    /// The `headers` are provided verbatim and include both the key as well as the value.
+    /// Unlike its blocking counterpart, the whole `body` to post is known upfront.
+    async fn post(
+        &mut self,
+        url: &str,
+        base_url: &str,
+        headers: &[Cow<'_, str>],
+        body: Vec<u8>,
+    ) -> Result<GetResponse<Self::Headers, Self::ResponseBody>, Error>;
+
+    /// Pass `config` which can deserialize in the implementation's configuration, as documented separately.
+    ///
+    /// The caller must know how that `config` data looks like for the intended implementation.
+    fn configure(&mut self, config: &dyn Any) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>>;
+}
+
+/// A transport for supporting arbitrary async http clients by abstracting interactions with them into the [Http] trait.
+pub struct Transport<H: Http> {
+    url: String,
+    user_agent_header: &'static str,
+    desired_version: Protocol,
+    actual_version: Protocol,
+    http: H,
+    service: Option<Service>,
+    line_provider: Option<StreamingPeekableIter<H::ResponseBody>>,
+    identity: Option<gix_sec::identity::Account>,
+    /// The authentication scheme and pre-encoded credential to use instead of `identity`, if set.
+    credential: Option<(String, String)>,
+    /// The `WWW-Authenticate` header values received along with the last authentication failure.
+    authentication_challenges: Vec<String>,
+    trace: bool,
+}
+
+impl<H: Http> Transport<H> {
+    /// Create a new instance with `http` as implementation to communicate to `url` using the given `desired_version`.
+    /// Note that we will always fallback to other versions as supported by the server.
+    /// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+    pub fn new_http(http: H, url: gix_url::Url, desired_version: Protocol, trace: bool) -> Self {
+        let identity = url
+            .user()
+            .zip(url.password())
+            .map(|(user, pass)| gix_sec::identity::Account {
+                username: user.to_string(),
+                password: pass.to_string(),
+            });
+        Transport {
+            url: url.to_bstring().to_string(),
+            user_agent_header: concat!("User-Agent: git/oxide-", env!("CARGO_PKG_VERSION")),
+            desired_version,
+            actual_version: Default::default(),
+            service: None,
+            http,
+            line_provider: None,
+            identity,
+            credential: None,
+            authentication_challenges: Vec::new(),
+            trace,
+        }
+    }
+
+    /// Returns the identity that the transport uses when connecting to the remote.
+    pub fn identity(&self) -> Option<&gix_sec::identity::Account> {
+        self.identity.as_ref()
+    }
+}
+
+#[cfg(feature = "async-http-client-reqwest")]
+impl Transport<Impl> {
+    /// Create a new instance to communicate to `url` using the given `desired_version` of the `git` protocol.
+    /// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+    ///
+    /// Note that the actual implementation depends on feature toggles.
+    pub fn new(url: gix_url::Url, desired_version: Protocol, trace: bool) -> Self {
+        Self::new_http(Impl::default(), url, desired_version, trace)
+    }
+}
+
+impl<H: Http> Transport<H> {
+    async fn check_content_type(service: Service, kind: &str, headers: H::Headers) -> Result<(), client::Error> {
+        let wanted_content_type = format!("application/x-{}-{}", service.as_str(), kind);
+        let mut lines = headers.lines();
+        let mut found = false;
+        while let Some(line) = lines.next().await {
+            let line = line?;
+            let mut tokens = line.split(':');
+            found |= tokens.next().zip(tokens.next()).map_or(false, |(name, value)| {
+                name.eq_ignore_ascii_case("content-type") && value.trim() == wanted_content_type
+            });
+        }
+        if !found {
+            return Err(client::Error::Http(Error::Detail {
+                description: format!(
+                    "Didn't find '{wanted_content_type}' header to indicate 'smart' protocol, and 'dumb' protocol is not supported."
+                ),
+            }));
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::unnecessary_wraps, unknown_lints)]
+    fn add_authorization_if_present(&self, headers: &mut Vec<Cow<'_, str>>) -> Result<(), client::Error> {
+        if self.credential.is_none() && self.identity.is_none() {
+            return Ok(());
+        }
+        #[cfg(not(debug_assertions))]
+        if self.url.starts_with("http://") {
+            return Err(client::Error::AuthenticationRefused(
+                "Will not send credentials in clear text over http",
+            ));
+        }
+        if let Some((authtype, credential)) = &self.credential {
+            headers.push(Cow::Owned(format!("Authorization: {authtype} {credential}")))
+        } else if let Some(gix_sec::identity::Account { username, password }) = &self.identity {
+            headers.push(Cow::Owned(format!(
+                "Authorization: Basic {}",
+                base64::engine::general_purpose::STANDARD.encode(format!("{username}:{password}"))
+            )))
+        }
+        Ok(())
+    }
+
+    /// Remember the authentication challenges carried by `err` if the server demanded authentication.
+    fn remember_authentication_challenges(&mut self, err: client::Error) -> client::Error {
+        if let client::Error::Io(io_err) = &err {
+            if let Some(auth) = AuthenticationRequired::from_io_error(io_err) {
+                self.authentication_challenges = auth.www_authenticate.clone();
+            }
+        }
+        err
+    }
+}
+
+impl<H: Http> client::TransportWithoutIO for Transport<H> {
+    fn set_identity(&mut self, identity: gix_sec::identity::Account) -> Result<(), client::Error> {
+        self.identity = Some(identity);
+        self.credential = None;
+        Ok(())
+    }
+
+    fn set_credential(&mut self, authtype: String, credential: String) -> Result<(), client::Error> {
+        self.credential = Some((authtype, credential));
+        Ok(())
+    }
+
+    fn authentication_challenges(&self) -> &[String] {
+        &self.authentication_challenges
+    }
+
+    fn request(
+        &mut self,
+        write_mode: client::WriteMode,
+        on_into_read: MessageKind,
+        trace: bool,
+    ) -> Result<RequestWriter<'_>, client::Error> {
+        let service = self.service.expect("handshake() must have been called first");
+        let url = append_url(&self.url, service.as_str());
+        let mut headers = vec![
+            Cow::Borrowed(self.user_agent_header),
+            Cow::Owned(format!("Content-Type: application/x-{}-request", service.as_str())),
+            format!("Accept: application/x-{}-result", service.as_str()).into(),
+        ];
+        self.add_authorization_if_present(&mut headers)?;
+        if self.actual_version != Protocol::V1 {
+            headers.push(Cow::Owned(format!(
+                "Git-Protocol: version={}",
+                self.actual_version as usize
+            )));
+        }
+
+        let post_body = PostBody::default();
+        let response = {
+            let body = post_body.0.clone();
+            let base_url = self.url.clone();
+            let http = &mut self.http;
+            let line_provider = self
+                .line_provider
+                .as_mut()
+                .expect("handshake to have been called first");
+            Box::pin(async move {
+                let body = std::mem::take(&mut *body.borrow_mut());
+                let GetResponse { headers, body } = http.post(&url, &base_url, &headers, body).await?;
+                <Transport<H>>::check_content_type(service, "result", headers).await?;
+                line_provider.replace(body);
+                Ok::<_, client::Error>(line_provider)
+            })
+        };
+        Ok(RequestWriter::new_from_bufread(
+            post_body,
+            Box::new(PostThenBody::<'_, H> {
+                state: State::Requesting(response),
+                handle_progress: None,
+                reset: None,
+            }),
+            write_mode,
+            on_into_read,
+            trace,
+        ))
+    }
+
+    fn to_url(&self) -> Cow<'_, BStr> {
+        Cow::Borrowed(self.url.as_str().into())
+    }
+
+    fn connection_persists_across_multiple_requests(&self) -> bool {
+        false
+    }
+
+    fn configure(&mut self, config: &dyn Any) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        self.http.configure(config)
+    }
+}
+
+#[async_trait(?Send)]
+impl<H: Http> client::Transport for Transport<H> {
+    async fn handshake<'a>(
+        &mut self,
+        service: Service,
+        extra_parameters: &'a [(&'a str, Option<&'a str>)],
+    ) -> Result<client::SetServiceResponse<'_>, client::Error> {
+        let url = append_url(self.url.as_ref(), &format!("info/refs?service={}", service.as_str()));
+        let mut headers = vec![Cow::Borrowed(self.user_agent_header)];
+        if self.desired_version != Protocol::V1 || !extra_parameters.is_empty() {
+            let mut parameters = if self.desired_version != Protocol::V1 {
+                let mut p = format!("version={}", self.desired_version as usize);
+                if !extra_parameters.is_empty() {
+                    p.push(':');
+                }
+                p
+            } else {
+                String::new()
+            };
+            parameters.push_str(
+                &extra_parameters
+                    .iter()
+                    .map(|(key, value)| match value {
+                        Some(value) => format!("{key}={value}"),
+                        None => key.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(":"),
+            );
+            headers.push(format!("Git-Protocol: {parameters}").into());
+        }
+        self.add_authorization_if_present(&mut headers)?;
+        let GetResponse { headers, body } = self.http.get(url.as_ref(), &self.url, &headers).await?;
+        <Transport<H>>::check_content_type(service, "advertisement", headers)
+            .await
+            .map_err(|err| self.remember_authentication_challenges(err))?;
+
+        match self.line_provider.as_mut() {
+            Some(line_provider) => {
+                line_provider.replace(body);
+            }
+            None => {
+                self.line_provider = Some(StreamingPeekableIter::new(body, &[PacketLineRef::Flush], self.trace));
+            }
+        }
+        let line_reader = self.line_provider.as_mut().expect("set above");
+
+        // the service announcement is only sent sometimes depending on the exact server/protocol version/used protocol (http?)
+        // eat the announcement when its there to avoid errors later (and check that the correct service was announced).
+        // Ignore the announcement otherwise.
+        let line_ = line_reader
+            .peek_line()
+            .await
+            .ok_or(client::Error::ExpectedLine("capabilities, version or service"))???;
+        let line = line_.as_text().ok_or(client::Error::ExpectedLine("text"))?;
+
+        if let Some(announced_service) = line.as_bstr().strip_prefix(b"# service=") {
+            if announced_service != service.as_str().as_bytes() {
+                return Err(client::Error::Http(Error::Detail {
+                    description: format!(
+                        "Expected to see service {:?}, but got {:?}",
+                        service.as_str(),
+                        announced_service
+                    ),
+                }));
+            }
+
+            line_reader.as_read().read_to_end(&mut Vec::new()).await?;
+        }
+
+        let capabilities::recv::Outcome {
+            capabilities,
+            refs,
+            protocol: actual_protocol,
+        } = Capabilities::from_lines_with_version_detection(line_reader).await?;
+        self.actual_version = actual_protocol;
+        self.service = Some(service);
+        Ok(client::SetServiceResponse {
+            actual_protocol,
+            capabilities,
+            refs,
+        })
+    }
+}
+
+/// Collects the body to post in memory, to be sent once the response is read.
+#[derive(Default)]
+struct PostBody(Rc<RefCell<Vec<u8>>>);
+
+impl AsyncWrite for PostBody {
+    fn poll_write(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+type PendingResponse<'a, H> =
+    Pin<Box<dyn Future<Output = Result<&'a mut StreamingPeekableIter<<H as Http>::ResponseBody>, client::Error>> + 'a>>;
+
+enum State<'a, H: Http> {
+    /// The body is posted once it's complete, which is when the response is first read.
+    Requesting(PendingResponse<'a, H>),
+    Reading(gix_packetline::read::WithSidebands<'a, H::ResponseBody, HandleProgress<'a>>),
+    Failed,
+}
+
+/// Posts the request body when the response is first read, to then read the response body.
+struct PostThenBody<'a, H: Http> {
+    state: State<'a, H>,
+    handle_progress: Option<HandleProgress<'a>>,
+    reset: Option<Protocol>,
+}
+
+impl<'a, H: Http> PostThenBody<'a, H> {
+    fn poll_reader(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<&mut gix_packetline::read::WithSidebands<'a, H::ResponseBody, HandleProgress<'a>>>> {
+        if let State::Requesting(response) = &mut self.state {
+            match ready!(response.as_mut().poll(cx)) {
+                Ok(line_provider) => {
+                    let mut reader = line_provider.as_read_without_sidebands();
+                    if let Some(handle_progress) = self.handle_progress.take() {
+                        reader.set_progress_handler(Some(handle_progress));
+                    }
+                    if let Some(version) = self.reset.take() {
+                        ExtendedBufRead::reset(&mut reader, version);
+                    }
+                    self.state = State::Reading(reader);
+                }
+                Err(err) => {
+                    self.state = State::Failed;
+                    return Poll::Ready(Err(match err {
+                        client::Error::Io(err) => err,
+                        err => io::Error::new(io::ErrorKind::Other, err),
+                    }));
+                }
+            }
+        }
+        match &mut self.state {
+            State::Reading(reader) => Poll::Ready(Ok(reader)),
+            State::Failed => Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::Other,
+                "The request failed and its response cannot be read",
+            ))),
+            State::Requesting(_) => unreachable!("requests are completed above"),
+        }
+    }
+
+    async fn reader(
+        &mut self,
+    ) -> io::Result<&mut gix_packetline::read::WithSidebands<'a, H::ResponseBody, HandleProgress<'a>>> {
+        futures_lite::future::poll_fn(|cx| self.poll_reader(cx).map_ok(|_| ())).await?;
+        match &mut self.state {
+            State::Reading(reader) => Ok(reader),
+            _ => unreachable!("poll_reader() only succeeds when reading"),
+        }
+    }
+}
+
+impl<'a, H: Http> AsyncRead for PostThenBody<'a, H> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let reader = ready!(self.get_mut().poll_reader(cx))?;
+        Pin::new(reader).poll_read(cx, buf)
+    }
+}
+
+impl<'a, H: Http> AsyncBufRead for PostThenBody<'a, H> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let reader = ready!(self.get_mut().poll_reader(cx))?;
+        Pin::new(reader).poll_fill_buf(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        if let State::Reading(reader) = &mut self.get_mut().state {
+            Pin::new(reader).consume(amt)
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, H: Http> ReadlineBufRead for PostThenBody<'a, H> {
+    async fn readline(&mut self) -> Option<io::Result<Result<PacketLineRef<'_>, gix_packetline::decode::Error>>> {
+        match self.reader().await {
+            Ok(reader) => reader.readline().await,
+            Err(err) => Some(Err(err)),
+        }
+    }
+
+    async fn readline_str(&mut self, line: &mut String) -> io::Result<usize> {
+        self.reader().await?.readline_str(line).await
+    }
+}
+
+#[async_trait(?Send)]
+impl<'a, H: Http> ExtendedBufRead<'a> for PostThenBody<'a, H> {
+    fn set_progress_handler(&mut self, handle_progress: Option<HandleProgress<'a>>) {
+        match &mut self.state {
+            State::Reading(reader) => reader.set_progress_handler(handle_progress),
+            _ => self.handle_progress = handle_progress,
+        }
+    }
+
+    async fn peek_data_line(&mut self) -> Option<io::Result<Result<&[u8], client::Error>>> {
+        match self.reader().await {
+            Ok(reader) => ExtendedBufRead::peek_data_line(reader).await,
+            Err(err) => Some(Err(err)),
+        }
+    }
+
+    fn reset(&mut self, version: Protocol) {
+        match &mut self.state {
+            State::Reading(reader) => ExtendedBufRead::reset(reader, version),
+            _ => self.reset = Some(version),
+        }
+    }
+
+    fn stopped_at(&self) -> Option<MessageKind> {
+        match &self.state {
+            State::Reading(reader) => ExtendedBufRead::stopped_at(reader),
+            _ => None,
+        }
+    }
+}
+
+/// Connect to the given `url` via HTTP/S using the `desired_version` of the `git` protocol, with `http` as implementation.
+/// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+pub fn connect_http<H: Http>(http: H, url: gix_url::Url, desired_version: Protocol, trace: bool) -> Transport<H> {
+    Transport::new_http(http, url, desired_version, trace)
+}
+
+/// Connect to the given `url` via HTTP/S using the `desired_version` of the `git` protocol.
+/// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+#[cfg(feature = "async-http-client-reqwest")]
+pub fn connect(url: gix_url::Url, desired_version: Protocol, trace: bool) -> Transport<Impl> {
+    Transport::new(url, desired_version, trace)
+}
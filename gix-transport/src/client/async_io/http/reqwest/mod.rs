@@ -0,0 +1,39 @@
+use std::sync::{atomic::AtomicBool, Arc, Mutex};
+
+use crate::client::http::options::{AuthenticateFn, FollowRedirects};
+
+/// An implementation for async HTTP requests via `reqwest`.
+#[derive(Default)]
+pub struct Remote {
+    /// The client to perform requests with, created on first use.
+    client: Option<reqwest::Client>,
+    /// Whether or not the redirect policy of the `client` allows redirects for the next request.
+    allow_redirects: Arc<AtomicBool>,
+    /// The way redirects are followed, initialized from the configuration of the first request.
+    follow: Option<FollowRedirects>,
+    /// The base url after a redirect, if one was followed.
+    redirected_base_url: Option<String>,
+    /// A mechanism for configuring the remote.
+    config: crate::client::http::Options,
+    /// The action to store or erase the credentials used for the proxy of the `client`, to be performed once
+    /// the first request through it is done.
+    proxy_auth_action: Option<(gix_credentials::helper::NextAction, Arc<Mutex<AuthenticateFn>>)>,
+}
+
+/// A function to configure a single request prior to sending it, support most complex configuration beyond what's possible with
+/// basic `git` http configuration.
+pub type ConfigureRequestFn = dyn FnMut(&mut reqwest::Request) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>>
+    + Send
+    + Sync
+    + 'static;
+
+/// Options to configure the reqwest HTTP handler.
+#[derive(Default)]
+pub struct Options {
+    /// A function to configure the request that is about to be made.
+    pub configure_request: Option<Box<ConfigureRequestFn>>,
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod remote;
@@ -0,0 +1,514 @@
+use std::{
+    any::Any,
+    borrow::Cow,
+    future::Future,
+    io,
+    pin::Pin,
+    str::FromStr,
+    sync::atomic,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use futures_io::{AsyncBufRead, AsyncRead};
+use futures_lite::{io::Cursor, ready, Stream, StreamExt};
+
+use crate::client::http::{
+    self,
+    options::{FollowRedirects, HttpVersion},
+    redirect,
+    reqwest::Remote,
+};
+
+/// The error returned by the 'remote' helper, a purely internal construct to perform http requests.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Reqwest(#[from] reqwest::Error),
+    #[error("Request configuration failed")]
+    ConfigureRequest(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    Redirect(#[from] redirect::Error),
+    #[error(transparent)]
+    Authenticate(#[from] gix_credentials::protocol::Error),
+    #[error("Could not read CA certificates from {path:?}")]
+    ReadCaInfo {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+}
+
+impl crate::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::Reqwest(err) => {
+                err.is_timeout() || err.is_connect() || err.status().map_or(false, |status| status.is_server_error())
+            }
+            _ => false,
+        }
+    }
+}
+
+impl From<Error> for http::Error {
+    fn from(err: Error) -> Self {
+        http::Error::InitHttpClient { source: Box::new(err) }
+    }
+}
+
+/// The headers of a response, one `name: value` pair per line.
+///
+/// Reading them fails if the request wasn't successful, with `401` turned into [`PermissionDenied`](io::ErrorKind::PermissionDenied).
+pub struct Headers {
+    error: Option<io::Error>,
+    lines: Cursor<Vec<u8>>,
+}
+
+impl Headers {
+    fn from_error(err: io::Error) -> Self {
+        Headers {
+            error: Some(err),
+            lines: Cursor::new(Vec::new()),
+        }
+    }
+}
+
+impl AsyncRead for Headers {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if let Some(err) = this.error.take() {
+            return Poll::Ready(Err(err));
+        }
+        Pin::new(&mut this.lines).poll_read(cx, buf)
+    }
+}
+
+impl AsyncBufRead for Headers {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        if let Some(err) = this.error.take() {
+            return Poll::Ready(Err(err));
+        }
+        Pin::new(&mut this.lines).poll_fill_buf(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        Pin::new(&mut self.get_mut().lines).consume(amt)
+    }
+}
+
+/// Abort a transfer if less than `limit` bytes per second were received within `time`,
+/// similar to `CURLOPT_LOW_SPEED_LIMIT` and `CURLOPT_LOW_SPEED_TIME`.
+struct LowSpeed {
+    limit: u32,
+    time: Duration,
+    /// The amount of bytes received since the `deadline` was set.
+    received: u64,
+    deadline: Pin<Box<tokio::time::Sleep>>,
+}
+
+impl LowSpeed {
+    fn from_options(options: &http::Options) -> Option<Self> {
+        (options.low_speed_limit_bytes_per_second > 0 && options.low_speed_time_seconds > 0).then(|| {
+            let time = Duration::from_secs(options.low_speed_time_seconds);
+            LowSpeed {
+                limit: options.low_speed_limit_bytes_per_second,
+                time,
+                received: 0,
+                deadline: Box::pin(tokio::time::sleep(time)),
+            }
+        })
+    }
+
+    /// Fail if the deadline passed without receiving enough bytes, or register `cx` to be woken up when it passes.
+    fn poll_check(&mut self, cx: &mut Context<'_>) -> io::Result<()> {
+        while self.deadline.as_mut().poll(cx).is_ready() {
+            if self.received < u64::from(self.limit) * self.time.as_secs() {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!(
+                        "Transfer speed was below {} bytes per second for {} seconds",
+                        self.limit,
+                        self.time.as_secs()
+                    ),
+                ));
+            }
+            self.received = 0;
+            self.deadline.as_mut().reset(tokio::time::Instant::now() + self.time);
+        }
+        Ok(())
+    }
+}
+
+/// The body of a response, streamed as it is received.
+pub struct Body {
+    chunks: Pin<Box<dyn Stream<Item = io::Result<Vec<u8>>> + Send>>,
+    buf: Vec<u8>,
+    pos: usize,
+    low_speed: Option<LowSpeed>,
+}
+
+impl Body {
+    fn new(res: reqwest::Response, low_speed: Option<LowSpeed>) -> Self {
+        Body {
+            chunks: Box::pin(res.bytes_stream().map(|chunk| {
+                chunk
+                    .map(|bytes| bytes.to_vec())
+                    .map_err(|err| io::Error::new(io::ErrorKind::Other, err))
+            })),
+            buf: Vec::new(),
+            pos: 0,
+            low_speed,
+        }
+    }
+
+    fn empty() -> Self {
+        Body {
+            chunks: Box::pin(futures_lite::stream::empty()),
+            buf: Vec::new(),
+            pos: 0,
+            low_speed: None,
+        }
+    }
+}
+
+impl AsyncRead for Body {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let available = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let amt = available.len().min(buf.len());
+        buf[..amt].copy_from_slice(&available[..amt]);
+        self.consume(amt);
+        Poll::Ready(Ok(amt))
+    }
+}
+
+impl AsyncBufRead for Body {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        while this.pos == this.buf.len() {
+            if let Some(low_speed) = &mut this.low_speed {
+                low_speed.poll_check(cx)?;
+            }
+            match ready!(this.chunks.as_mut().poll_next(cx)) {
+                Some(chunk) => {
+                    this.buf = chunk?;
+                    this.pos = 0;
+                    if let Some(low_speed) = &mut this.low_speed {
+                        low_speed.received += this.buf.len() as u64;
+                    }
+                }
+                None => break,
+            }
+        }
+        Poll::Ready(Ok(&this.buf[this.pos..]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        this.pos = (this.pos + amt).min(this.buf.len());
+    }
+}
+
+fn authentication_required(
+    status: reqwest::StatusCode,
+    headers: &reqwest::header::HeaderMap,
+) -> http::AuthenticationRequired {
+    http::AuthenticationRequired {
+        status: status.as_u16(),
+        www_authenticate: headers
+            .get_all(reqwest::header::WWW_AUTHENTICATE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .map(ToOwned::to_owned)
+            .collect(),
+    }
+}
+
+/// utilities
+impl Remote {
+    /// Return the client for all requests, or create it from our configuration.
+    fn client(&mut self) -> Result<reqwest::Client, Error> {
+        if let Some(client) = &self.client {
+            return Ok(client.clone());
+        }
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(self.config.connect_timeout.unwrap_or(Duration::from_secs(20)))
+            .http1_title_case_headers()
+            .redirect(reqwest::redirect::Policy::custom({
+                let allow_redirects = self.allow_redirects.clone();
+                move |attempt| {
+                    if allow_redirects.load(atomic::Ordering::Relaxed) {
+                        let curr_url = attempt.url();
+                        let prev_urls = attempt.previous();
+
+                        match prev_urls.first() {
+                            Some(prev_url) if prev_url.host_str() != curr_url.host_str() => {
+                                // git does not want to be redirected to a different host.
+                                attempt.stop()
+                            }
+                            _ => {
+                                // emulate default git behaviour which relies on curl default behaviour apparently.
+                                const CURL_DEFAULT_REDIRS: usize = 50;
+                                if prev_urls.len() >= CURL_DEFAULT_REDIRS {
+                                    attempt.error("too many redirects")
+                                } else {
+                                    attempt.follow()
+                                }
+                            }
+                        }
+                    } else {
+                        attempt.stop()
+                    }
+                }
+            }));
+        // HTTP/2 is negotiated if the server supports it, so only HTTP/1.1 needs to be enforced.
+        if self.config.http_version == Some(HttpVersion::V1_1) {
+            builder = builder.http1_only();
+        }
+        builder = self.configure_proxy(builder)?;
+        builder = self.configure_tls(builder)?;
+
+        let client = builder.build()?;
+        self.client = Some(client.clone());
+        Ok(client)
+    }
+
+    /// Configure `builder` to use the configured proxy, and obtain credentials for it if needed.
+    ///
+    /// Note that only basic authentication is supported for proxies, independently of the configured authentication method.
+    fn configure_proxy(&mut self, mut builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder, Error> {
+        let Some(proxy) = self.config.proxy.as_deref() else {
+            return Ok(builder);
+        };
+        let no_proxy = self.config.no_proxy.as_deref().map(str::trim);
+        if proxy.is_empty() || no_proxy == Some("*") {
+            return Ok(builder.no_proxy());
+        }
+        let mut proxy = reqwest::Proxy::all(proxy)?;
+        if let Some(no_proxy) = no_proxy {
+            proxy = proxy.no_proxy(reqwest::NoProxy::from_string(no_proxy));
+        }
+        if let Some((obtain_creds_action, authenticate)) = self.config.proxy_authenticate.clone() {
+            let creds = authenticate.lock().expect("no panics in other threads")(obtain_creds_action)?
+                .expect("action to fetch credentials");
+            proxy = proxy.basic_auth(&creds.identity.username, &creds.identity.password);
+            self.proxy_auth_action = Some((creds.next, authenticate));
+        }
+        builder = builder.proxy(proxy);
+        Ok(builder)
+    }
+
+    /// Configure `builder` with the TLS settings, which are ignored if no TLS backend was compiled in.
+    fn configure_tls(&self, builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder, Error> {
+        #[cfg(any(
+            feature = "async-http-client-reqwest-rust-tls",
+            feature = "async-http-client-reqwest-native-tls"
+        ))]
+        let builder = {
+            use crate::client::http::options::SslVersion;
+
+            let mut builder = builder;
+            if let Some(path) = &self.config.ssl_ca_info {
+                let pem = std::fs::read(path).map_err(|source| Error::ReadCaInfo {
+                    path: path.to_owned(),
+                    source,
+                })?;
+                builder = builder
+                    .tls_built_in_root_certs(false)
+                    .add_root_certificate(reqwest::Certificate::from_pem(&pem)?);
+            }
+            if !self.config.ssl_verify {
+                builder = builder.danger_accept_invalid_certs(true);
+            }
+            if let Some(range) = self.config.ssl_version {
+                let to_tls_version = |version: SslVersion| match version {
+                    SslVersion::TlsV1 | SslVersion::TlsV1_0 => Some(reqwest::tls::Version::TLS_1_0),
+                    SslVersion::TlsV1_1 => Some(reqwest::tls::Version::TLS_1_1),
+                    SslVersion::TlsV1_2 => Some(reqwest::tls::Version::TLS_1_2),
+                    SslVersion::TlsV1_3 => Some(reqwest::tls::Version::TLS_1_3),
+                    SslVersion::Default | SslVersion::SslV2 | SslVersion::SslV3 => None,
+                };
+                let (min, max) = range.min_max();
+                if let Some(min) = to_tls_version(min) {
+                    builder = builder.min_tls_version(min);
+                }
+                if let Some(max) = to_tls_version(max) {
+                    builder = builder.max_tls_version(max);
+                }
+            }
+            builder
+        };
+        Ok(builder)
+    }
+
+    async fn make_request(
+        &mut self,
+        url: &str,
+        base_url: &str,
+        headers: &[Cow<'_, str>],
+        body: Option<Vec<u8>>,
+    ) -> Result<http::GetResponse<Headers, Body>, http::Error> {
+        let mut header_map = reqwest::header::HeaderMap::new();
+        for header_line in headers {
+            let colon_pos = header_line
+                .find(':')
+                .expect("header line must contain a colon to separate key and value");
+            let header_name = &header_line[..colon_pos];
+            let value = &header_line[colon_pos + 1..];
+
+            match reqwest::header::HeaderName::from_str(header_name)
+                .ok()
+                .zip(reqwest::header::HeaderValue::try_from(value.trim()).ok())
+            {
+                Some((key, val)) => header_map.insert(key, val),
+                None => continue,
+            };
+        }
+        // The configured user agent takes precedence over the one of the transport, just like in `git`.
+        if let Some(user_agent) = self
+            .config
+            .user_agent
+            .as_deref()
+            .and_then(|user_agent| reqwest::header::HeaderValue::try_from(user_agent).ok())
+        {
+            header_map.insert(reqwest::header::USER_AGENT, user_agent);
+        }
+        for header_line in &self.config.extra_headers {
+            let Some((header_name, value)) = header_line.split_once(':') else {
+                continue;
+            };
+            if let Some((key, val)) = reqwest::header::HeaderName::from_str(header_name)
+                .ok()
+                .zip(reqwest::header::HeaderValue::try_from(value.trim()).ok())
+            {
+                header_map.append(key, val);
+            }
+        }
+
+        let client = self.client()?;
+        let effective_url = redirect::swap_tails(self.redirected_base_url.as_deref(), base_url, url.to_owned());
+        let req_builder = match body {
+            Some(body) => client.post(&effective_url).body(body),
+            None => client.get(&effective_url),
+        }
+        .headers(header_map);
+        let mut req = req_builder.build().map_err(Error::from)?;
+        if let Some(ref mut request_options) = self.config.backend.as_ref().and_then(|backend| backend.lock().ok()) {
+            if let Some(options) = request_options.downcast_mut::<super::Options>() {
+                if let Some(configure_request) = &mut options.configure_request {
+                    configure_request(&mut req).map_err(Error::from)?;
+                }
+            }
+        }
+
+        let follow = self.follow.get_or_insert(self.config.follow_redirects);
+        self.allow_redirects.store(
+            matches!(follow, FollowRedirects::Initial | FollowRedirects::All),
+            atomic::Ordering::Relaxed,
+        );
+
+        if *follow == FollowRedirects::Initial {
+            *follow = FollowRedirects::None;
+        }
+
+        let res = client.execute(req).await;
+        if let Some((action, authenticate)) = self.proxy_auth_action.take() {
+            let proxy_auth_succeeded = res.as_ref().map_or(false, |res| {
+                res.status() != reqwest::StatusCode::PROXY_AUTHENTICATION_REQUIRED
+            });
+            authenticate.lock().expect("no panics in other threads")(if proxy_auth_succeeded {
+                action.store()
+            } else {
+                action.erase()
+            })
+            .map_err(Error::from)?;
+        }
+        let res = match res {
+            Ok(res) if res.status() == reqwest::StatusCode::UNAUTHORIZED => {
+                let err = authentication_required(res.status(), res.headers());
+                return Ok(http::GetResponse {
+                    headers: Headers::from_error(err.into()),
+                    body: Body::empty(),
+                });
+            }
+            res => res,
+        };
+        let res = match res.and_then(reqwest::Response::error_for_status) {
+            Ok(res) => res,
+            Err(err) => {
+                let (kind, err) = match err.status() {
+                    Some(status) => {
+                        let kind = if status.is_server_error() {
+                            io::ErrorKind::ConnectionAborted
+                        } else {
+                            io::ErrorKind::Other
+                        };
+                        (kind, format!("Received HTTP status {}", status.as_str()))
+                    }
+                    None => (io::ErrorKind::Other, err.to_string()),
+                };
+                return Ok(http::GetResponse {
+                    headers: Headers::from_error(io::Error::new(kind, err)),
+                    body: Body::empty(),
+                });
+            }
+        };
+
+        let actual_url = res.url().as_str();
+        if actual_url != effective_url.as_str() {
+            self.redirected_base_url = redirect::base_url(actual_url, base_url, url.to_owned())
+                .map_err(Error::from)?
+                .into();
+        }
+
+        let mut headers = Vec::new();
+        for (name, value) in res.headers() {
+            headers.extend_from_slice(name.as_str().as_bytes());
+            headers.push(b':');
+            headers.extend_from_slice(value.as_bytes());
+            headers.push(b'\n');
+        }
+        Ok(http::GetResponse {
+            headers: Headers {
+                error: None,
+                lines: Cursor::new(headers),
+            },
+            body: Body::new(res, LowSpeed::from_options(&self.config)),
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl http::Http for Remote {
+    type Headers = Headers;
+    type ResponseBody = Body;
+
+    async fn get(
+        &mut self,
+        url: &str,
+        base_url: &str,
+        headers: &[Cow<'_, str>],
+    ) -> Result<http::GetResponse<Self::Headers, Self::ResponseBody>, http::Error> {
+        self.make_request(url, base_url, headers, None).await
+    }
+
+    async fn post(
+        &mut self,
+        url: &str,
+        base_url: &str,
+        headers: &[Cow<'_, str>],
+        body: Vec<u8>,
+    ) -> Result<http::GetResponse<Self::Headers, Self::ResponseBody>, http::Error> {
+        self.make_request(url, base_url, headers, Some(body)).await
+    }
+
+    fn configure(&mut self, config: &dyn Any) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+        if let Some(config) = config.downcast_ref::<http::Options>() {
+            self.config = config.clone();
+            // The client is built from the configuration, and needs to be recreated to see its changes.
+            self.client = None;
+        }
+        Ok(())
+    }
+}
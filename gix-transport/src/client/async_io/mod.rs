@@ -10,5 +10,9 @@ pub use traits::{SetServiceResponse, Transport, TransportV2Ext};
 ///
 #[allow(clippy::empty_docs)]
 pub mod connect;
-#[cfg(feature = "async-std")]
+#[cfg(any(feature = "async-std", feature = "async-http-client-reqwest"))]
 pub use connect::function::connect;
+
+///
+#[cfg(feature = "async-http-client")]
+pub mod http;
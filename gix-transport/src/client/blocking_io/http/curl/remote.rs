@@ -9,13 +9,12 @@ use std::{
 use curl::easy::{Auth, Easy2};
 use gix_features::io::pipe;
 
-use crate::client::{
-    blocking_io::http::{self, curl::Error, redirect},
-    http::{
-        curl::curl_is_spurious,
-        options::{FollowRedirects, HttpVersion, ProxyAuthMethod, SslVersion},
-        traits::PostBodyDataKind,
-    },
+use crate::client::http::{
+    self,
+    curl::{curl_is_spurious, Error},
+    options::{FollowRedirects, HttpVersion, ProxyAuthMethod, SslVersion},
+    redirect,
+    traits::PostBodyDataKind,
 };
 
 enum StreamOrBuffer {
@@ -31,6 +30,8 @@ struct Handler {
     checked_status: bool,
     last_status: usize,
     follow: FollowRedirects,
+    /// Set if authentication is required, to collect `WWW-Authenticate` headers until all headers are received.
+    authentication_required: Option<http::AuthenticationRequired>,
 }
 
 impl Handler {
@@ -38,6 +39,13 @@ impl Handler {
         self.checked_status = false;
         self.last_status = 0;
         self.follow = FollowRedirects::default();
+        self.authentication_required = None;
+    }
+    /// Send the pending authentication error, if there is one, to the header reader.
+    fn send_authentication_required(&mut self) {
+        if let Some((err, writer)) = self.authentication_required.take().zip(self.send_header.as_mut()) {
+            writer.channel.send(Err(err.into())).ok();
+        }
     }
     fn parse_status_inner(data: &[u8]) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
         let code = data
@@ -64,6 +72,7 @@ impl Handler {
 
 impl curl::easy::Handler for Handler {
     fn write(&mut self, data: &[u8]) -> Result<usize, curl::easy::WriteError> {
+        self.send_authentication_required();
         drop(self.send_header.take()); // signal header readers to stop trying
         match self.send_data.as_mut() {
             Some(writer) => writer.write_all(data).map(|_| data.len()).or(Ok(0)),
@@ -80,19 +89,31 @@ impl curl::easy::Handler for Handler {
 
     fn header(&mut self, data: &[u8]) -> bool {
         if let Some(writer) = self.send_header.as_mut() {
-            if self.checked_status {
+            if let Some(auth) = self.authentication_required.as_mut() {
+                if data.iter().all(u8::is_ascii_whitespace) {
+                    self.send_authentication_required();
+                } else {
+                    auth.parse_header_line(data);
+                }
+            } else if self.checked_status {
                 writer.write_all(data).ok();
             } else {
                 self.checked_status = true;
                 self.last_status = 200;
                 if let Some((status, err)) = Handler::parse_status(data, self.follow) {
                     self.last_status = status;
+                    if status == 401 {
+                        // Delay the error until all headers are received to pass on the authentication challenges.
+                        self.authentication_required = Some(http::AuthenticationRequired {
+                            status: status as u16,
+                            www_authenticate: Vec::new(),
+                        });
+                        return true;
+                    }
                     writer
                         .channel
                         .send(Err(io::Error::new(
-                            if status == 401 {
-                                io::ErrorKind::PermissionDenied
-                            } else if (500..600).contains(&status) {
+                            if (500..600).contains(&status) {
                                 io::ErrorKind::ConnectionAborted
                             } else {
                                 io::ErrorKind::Other
@@ -2,21 +2,19 @@ use std::{
     any::Any,
     borrow::Cow,
     io::{BufRead, Read},
-    path::PathBuf,
-    sync::{Arc, Mutex},
 };
 
 use base64::Engine;
 use bstr::BStr;
 use gix_packetline::PacketLineRef;
-pub use traits::{Error, GetResponse, Http, PostBodyDataKind, PostResponse};
+pub use traits::Http;
 
 use crate::{
     client::{
         self,
         blocking_io::bufread_ext::ReadlineBufRead,
         capabilities,
-        http::options::{HttpVersion, SslVersionRangeInclusive},
+        http::{append_url, AuthenticationRequired, Error, GetResponse, PostResponse},
         Capabilities, ExtendedBufRead, HandleProgress, MessageKind, RequestWriter,
     },
     Protocol, Service,
@@ -38,182 +36,6 @@ pub mod curl;
 pub mod reqwest;
 
 mod traits;
-
-///
-#[allow(clippy::empty_docs)]
-pub mod options {
-    /// A function to authenticate a URL.
-    pub type AuthenticateFn =
-        dyn FnMut(gix_credentials::helper::Action) -> gix_credentials::protocol::Result + Send + Sync;
-
-    /// Possible settings for the `http.followRedirects` configuration option.
-    #[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
-    pub enum FollowRedirects {
-        /// Follow only the first redirect request, most suitable for typical git requests.
-        #[default]
-        Initial,
-        /// Follow all redirect requests from the server unconditionally
-        All,
-        /// Follow no redirect request.
-        None,
-    }
-
-    /// The way to configure a proxy for authentication if a username is present in the configured proxy.
-    #[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
-    pub enum ProxyAuthMethod {
-        /// Automatically pick a suitable authentication method.
-        #[default]
-        AnyAuth,
-        ///HTTP basic authentication.
-        Basic,
-        /// Http digest authentication to prevent a password to be passed in clear text.
-        Digest,
-        /// GSS negotiate authentication.
-        Negotiate,
-        /// NTLM authentication
-        Ntlm,
-    }
-
-    /// Available SSL version numbers.
-    #[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd)]
-    #[allow(missing_docs)]
-    pub enum SslVersion {
-        /// The implementation default, which is unknown to this layer of abstraction.
-        Default,
-        TlsV1,
-        SslV2,
-        SslV3,
-        TlsV1_0,
-        TlsV1_1,
-        TlsV1_2,
-        TlsV1_3,
-    }
-
-    /// Available HTTP version numbers.
-    #[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd)]
-    #[allow(missing_docs)]
-    pub enum HttpVersion {
-        /// Equivalent to HTTP/1.1
-        V1_1,
-        /// Equivalent to HTTP/2
-        V2,
-    }
-
-    /// The desired range of acceptable SSL versions, or the single version to allow if both are set to the same value.
-    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-    pub struct SslVersionRangeInclusive {
-        /// The smallest allowed ssl version to use.
-        pub min: SslVersion,
-        /// The highest allowed ssl version to use.
-        pub max: SslVersion,
-    }
-
-    impl SslVersionRangeInclusive {
-        /// Return `min` and `max` fields in the right order so `min` is smaller or equal to `max`.
-        pub fn min_max(&self) -> (SslVersion, SslVersion) {
-            if self.min > self.max {
-                (self.max, self.min)
-            } else {
-                (self.min, self.max)
-            }
-        }
-    }
-}
-
-/// Options to configure http requests.
-// TODO: testing most of these fields requires a lot of effort, unless special flags to introspect ongoing requests are added.
-#[derive(Clone)]
-pub struct Options {
-    /// Headers to be added to every request.
-    /// They are applied unconditionally and are expected to be valid as they occur in an HTTP request, like `header: value`, without newlines.
-    ///
-    /// Refers to `http.extraHeader` multi-var.
-    pub extra_headers: Vec<String>,
-    /// How to handle redirects.
-    ///
-    /// Refers to `http.followRedirects`.
-    pub follow_redirects: options::FollowRedirects,
-    /// Used in conjunction with `low_speed_time_seconds`, any non-0 value signals the amount of bytes per second at least to avoid
-    /// aborting the connection.
-    ///
-    /// Refers to `http.lowSpeedLimit`.
-    pub low_speed_limit_bytes_per_second: u32,
-    /// Used in conjunction with `low_speed_bytes_per_second`, any non-0 value signals the amount seconds the minimal amount
-    /// of bytes per second isn't reached.
-    ///
-    /// Refers to `http.lowSpeedTime`.
-    pub low_speed_time_seconds: u64,
-    /// A curl-style proxy declaration of the form `[protocol://][user[:password]@]proxyhost[:port]`.
-    ///
-    /// Note that an empty string means the proxy is disabled entirely.
-    /// Refers to `http.proxy`.
-    pub proxy: Option<String>,
-    /// The comma-separated list of hosts to not send through the `proxy`, or `*` to entirely disable all proxying.
-    pub no_proxy: Option<String>,
-    /// The way to authenticate against the proxy if the `proxy` field contains a username.
-    ///
-    /// Refers to `http.proxyAuthMethod`.
-    pub proxy_auth_method: options::ProxyAuthMethod,
-    /// If authentication is needed for the proxy as its URL contains a username, this method must be set to provide a password
-    /// for it before making the request, and to store it if the connection succeeds.
-    pub proxy_authenticate: Option<(
-        gix_credentials::helper::Action,
-        Arc<std::sync::Mutex<options::AuthenticateFn>>,
-    )>,
-    /// The `HTTP` `USER_AGENT` string presented to an `HTTP` server, notably not the user agent present to the `git` server.
-    ///
-    /// If not overridden, it defaults to the user agent provided by `curl`, which is a deviation from how `git` handles this.
-    /// Thus it's expected from the callers to set it to their application, or use higher-level crates which make it easy to do this
-    /// more correctly.
-    ///
-    /// Using the correct user-agent might affect how the server treats the request.
-    ///
-    /// Refers to `http.userAgent`.
-    pub user_agent: Option<String>,
-    /// The amount of time we wait until aborting a connection attempt.
-    ///
-    /// If `None`, this typically defaults to 2 minutes to 5 minutes.
-    /// Refers to `gitoxide.http.connectTimeout`.
-    pub connect_timeout: Option<std::time::Duration>,
-    /// If enabled, emit additional information about connections and possibly the data received or written.
-    pub verbose: bool,
-    /// If set, use this path to point to a file with CA certificates to verify peers.
-    pub ssl_ca_info: Option<PathBuf>,
-    /// The SSL version or version range to use, or `None` to let the TLS backend determine which versions are acceptable.
-    pub ssl_version: Option<SslVersionRangeInclusive>,
-    /// Controls whether to perform SSL identity verification or not. Turning this off is not recommended and can lead to
-    /// various security risks. An example where this may be needed is when an internal git server uses a self-signed
-    /// certificate and the user accepts the associated security risks.
-    pub ssl_verify: bool,
-    /// The HTTP version to enforce. If unset, it is implementation defined.
-    pub http_version: Option<HttpVersion>,
-    /// Backend specific options, if available.
-    pub backend: Option<Arc<Mutex<dyn Any + Send + Sync + 'static>>>,
-}
-
-impl Default for Options {
-    fn default() -> Self {
-        Options {
-            extra_headers: vec![],
-            follow_redirects: Default::default(),
-            low_speed_limit_bytes_per_second: 0,
-            low_speed_time_seconds: 0,
-            proxy: None,
-            no_proxy: None,
-            proxy_auth_method: Default::default(),
-            proxy_authenticate: None,
-            user_agent: None,
-            connect_timeout: None,
-            verbose: false,
-            ssl_ca_info: None,
-            ssl_version: None,
-            ssl_verify: true,
-            http_version: None,
-            backend: None,
-        }
-    }
-}
-
 /// The actual http client implementation, using curl
 #[cfg(feature = "http-client-curl")]
 pub type Impl = curl::Curl;
@@ -231,6 +53,10 @@ pub struct Transport<H: Http> {
     service: Option<Service>,
     line_provider: Option<gix_packetline::StreamingPeekableIter<H::ResponseBody>>,
     identity: Option<gix_sec::identity::Account>,
+    /// The authentication scheme and pre-encoded credential to use instead of `identity`, if set.
+    credential: Option<(String, String)>,
+    /// The `WWW-Authenticate` header values received along with the last authentication failure.
+    authentication_challenges: Vec<String>,
     trace: bool,
 }
 
@@ -255,6 +81,8 @@ impl<H: Http> Transport<H> {
             http,
             line_provider: None,
             identity,
+            credential: None,
+            authentication_challenges: Vec::new(),
             trace,
         }
     }
@@ -297,14 +125,19 @@ impl<H: Http> Transport<H> {
     }
 
     #[allow(clippy::unnecessary_wraps, unknown_lints)]
-    fn add_basic_auth_if_present(&self, headers: &mut Vec<Cow<'_, str>>) -> Result<(), client::Error> {
-        if let Some(gix_sec::identity::Account { username, password }) = &self.identity {
-            #[cfg(not(debug_assertions))]
-            if self.url.starts_with("http://") {
-                return Err(client::Error::AuthenticationRefused(
-                    "Will not send credentials in clear text over http",
-                ));
-            }
+    fn add_authorization_if_present(&self, headers: &mut Vec<Cow<'_, str>>) -> Result<(), client::Error> {
+        if self.credential.is_none() && self.identity.is_none() {
+            return Ok(());
+        }
+        #[cfg(not(debug_assertions))]
+        if self.url.starts_with("http://") {
+            return Err(client::Error::AuthenticationRefused(
+                "Will not send credentials in clear text over http",
+            ));
+        }
+        if let Some((authtype, credential)) = &self.credential {
+            headers.push(Cow::Owned(format!("Authorization: {authtype} {credential}")))
+        } else if let Some(gix_sec::identity::Account { username, password }) = &self.identity {
             headers.push(Cow::Owned(format!(
                 "Authorization: Basic {}",
                 base64::engine::general_purpose::STANDARD.encode(format!("{username}:{password}"))
@@ -312,23 +145,34 @@ impl<H: Http> Transport<H> {
         }
         Ok(())
     }
-}
 
-fn append_url(base: &str, suffix: &str) -> String {
-    let mut buf = base.to_owned();
-    if base.as_bytes().last() != Some(&b'/') {
-        buf.push('/');
+    /// Remember the authentication challenges carried by `err` if the server demanded authentication.
+    fn remember_authentication_challenges(&mut self, err: client::Error) -> client::Error {
+        if let client::Error::Io(io_err) = &err {
+            if let Some(auth) = AuthenticationRequired::from_io_error(io_err) {
+                self.authentication_challenges = auth.www_authenticate.clone();
+            }
+        }
+        err
     }
-    buf.push_str(suffix);
-    buf
 }
 
 impl<H: Http> client::TransportWithoutIO for Transport<H> {
     fn set_identity(&mut self, identity: gix_sec::identity::Account) -> Result<(), client::Error> {
         self.identity = Some(identity);
+        self.credential = None;
+        Ok(())
+    }
+
+    fn set_credential(&mut self, authtype: String, credential: String) -> Result<(), client::Error> {
+        self.credential = Some((authtype, credential));
         Ok(())
     }
 
+    fn authentication_challenges(&self) -> &[String] {
+        &self.authentication_challenges
+    }
+
     fn request(
         &mut self,
         write_mode: client::WriteMode,
@@ -343,7 +187,7 @@ impl<H: Http> client::TransportWithoutIO for Transport<H> {
             format!("Accept: application/x-{}-result", service.as_str()).into(),
         ];
         let mut dynamic_headers = Vec::new();
-        self.add_basic_auth_if_present(&mut dynamic_headers)?;
+        self.add_authorization_if_present(&mut dynamic_headers)?;
         if self.actual_version != Protocol::V1 {
             dynamic_headers.push(Cow::Owned(format!(
                 "Git-Protocol: version={}",
@@ -423,11 +267,12 @@ impl<H: Http> client::Transport for Transport<H> {
             );
             dynamic_headers.push(format!("Git-Protocol: {parameters}").into());
         }
-        self.add_basic_auth_if_present(&mut dynamic_headers)?;
+        self.add_authorization_if_present(&mut dynamic_headers)?;
         let GetResponse { headers, body } =
             self.http
                 .get(url.as_ref(), &self.url, static_headers.iter().chain(&dynamic_headers))?;
-        <Transport<H>>::check_content_type(service, "advertisement", headers)?;
+        <Transport<H>>::check_content_type(service, "advertisement", headers)
+            .map_err(|err| self.remember_authentication_challenges(err))?;
 
         let line_reader = self.line_provider.get_or_insert_with(|| {
             gix_packetline::StreamingPeekableIter::new(body, &[PacketLineRef::Flush], self.trace)
@@ -552,7 +397,3 @@ pub fn connect_http<H: Http>(http: H, url: gix_url::Url, desired_version: Protoc
 pub fn connect(url: gix_url::Url, desired_version: Protocol, trace: bool) -> Transport<Impl> {
     Transport::new(url, desired_version, trace)
 }
-
-///
-#[cfg(any(feature = "http-client-curl", feature = "http-client-reqwest"))]
-pub mod redirect;
@@ -135,17 +135,20 @@ impl Default for Remote {
                     *follow = FollowRedirects::None;
                 }
 
-                let mut res = match client
-                    .execute(req)
-                    .and_then(reqwest::blocking::Response::error_for_status)
-                {
+                let res = match client.execute(req) {
+                    Ok(res) if res.status() == reqwest::StatusCode::UNAUTHORIZED => {
+                        let err = authentication_required(res.status(), res.headers());
+                        headers_tx.channel.send(Err(err.into())).ok();
+                        continue;
+                    }
+                    res => res,
+                };
+                let mut res = match res.and_then(reqwest::blocking::Response::error_for_status) {
                     Ok(res) => res,
                     Err(err) => {
                         let (kind, err) = match err.status() {
                             Some(status) => {
-                                let kind = if status == reqwest::StatusCode::UNAUTHORIZED {
-                                    std::io::ErrorKind::PermissionDenied
-                                } else if status.is_server_error() {
+                                let kind = if status.is_server_error() {
                                     std::io::ErrorKind::ConnectionAborted
                                 } else {
                                     std::io::ErrorKind::Other
@@ -202,6 +205,21 @@ impl Default for Remote {
     }
 }
 
+fn authentication_required(
+    status: reqwest::StatusCode,
+    headers: &reqwest::header::HeaderMap,
+) -> http::AuthenticationRequired {
+    http::AuthenticationRequired {
+        status: status.as_u16(),
+        www_authenticate: headers
+            .get_all(reqwest::header::WWW_AUTHENTICATE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .map(ToOwned::to_owned)
+            .collect(),
+    }
+}
+
 /// utilities
 impl Remote {
     fn restore_thread_after_failure(&mut self) -> http::Error {
@@ -0,0 +1,129 @@
+use crate::client::WriteMode;
+
+/// The error used by the [`Http`](crate::client::http::Http) trait.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not initialize the http client")]
+    InitHttpClient {
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("{description}")]
+    Detail { description: String },
+    #[error("An IO error occurred while uploading the body of a POST request")]
+    PostBody(#[from] std::io::Error),
+}
+
+impl crate::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::PostBody(err) => err.is_spurious(),
+            #[cfg(any(
+                feature = "http-client-reqwest",
+                feature = "http-client-curl",
+                feature = "async-http-client-reqwest"
+            ))]
+            Error::InitHttpClient { source } => {
+                #[cfg(feature = "http-client-curl")]
+                if let Some(err) = source.downcast_ref::<crate::client::http::curl::Error>() {
+                    return err.is_spurious();
+                };
+                #[cfg(any(feature = "http-client-reqwest", feature = "async-http-client-reqwest"))]
+                if let Some(err) = source.downcast_ref::<crate::client::http::reqwest::remote::Error>() {
+                    return err.is_spurious();
+                };
+                false
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The error used as inner error of an [`std::io::Error`] of kind [`PermissionDenied`](std::io::ErrorKind::PermissionDenied)
+/// when reading the headers of a response to a request that requires authentication.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Received HTTP status {status}")]
+pub struct AuthenticationRequired {
+    /// The HTTP status code, typically `401`.
+    pub status: u16,
+    /// The values of all `WWW-Authenticate` headers in the order they were received.
+    pub www_authenticate: Vec<String>,
+}
+
+impl AuthenticationRequired {
+    /// Remember the value of `line` if it is a `WWW-Authenticate` header, or a continuation of one.
+    pub fn parse_header_line(&mut self, line: &[u8]) {
+        let Ok(line) = std::str::from_utf8(line) else { return };
+        if line.starts_with([' ', '\t']) {
+            if let Some(last) = self.www_authenticate.last_mut() {
+                last.push(' ');
+                last.push_str(line.trim());
+            }
+            return;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("www-authenticate") {
+                self.www_authenticate.push(value.trim().to_owned());
+            }
+        }
+    }
+
+    /// Return the instance that is the inner error of `err`, if there is one.
+    pub fn from_io_error(err: &std::io::Error) -> Option<&Self> {
+        err.get_ref().and_then(|err| err.downcast_ref::<Self>())
+    }
+}
+
+impl From<AuthenticationRequired> for std::io::Error {
+    fn from(err: AuthenticationRequired) -> Self {
+        std::io::Error::new(std::io::ErrorKind::PermissionDenied, err)
+    }
+}
+
+/// The return value of [`Http::get()`](crate::client::http::Http::get()).
+pub struct GetResponse<H, B> {
+    /// The response headers.
+    pub headers: H,
+    /// The response body.
+    pub body: B,
+}
+
+/// The return value of [`Http::post()`](crate::client::http::Http::post()).
+pub struct PostResponse<H, B, PB> {
+    /// The body to post to the server as part of the request.
+    ///
+    /// **Note**: Implementations should drop the handle to avoid deadlocks.
+    pub post_body: PB,
+    /// The headers of the post response.
+    pub headers: H,
+    /// The body of the post response.
+    pub body: B,
+}
+
+/// Whether or not the post body is expected to fit into memory or not.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum PostBodyDataKind {
+    /// We know how much data we are sending and think it will fit into memory. This allows to collect it into a buffer
+    /// and send it with `Content-Length: <body-len>`.
+    BoundedAndFitsIntoMemory,
+    /// We don't know how much data we will send and assume it won't fit into memory. This enables streaming mode.
+    Unbounded,
+}
+
+impl From<WriteMode> for PostBodyDataKind {
+    fn from(m: WriteMode) -> Self {
+        match m {
+            WriteMode::Binary => PostBodyDataKind::Unbounded,
+            WriteMode::OneLfTerminatedLinePerWriteCall => PostBodyDataKind::BoundedAndFitsIntoMemory,
+        }
+    }
+}
+
+impl<A, B, C> From<PostResponse<A, B, C>> for GetResponse<A, B> {
+    fn from(v: PostResponse<A, B, C>) -> Self {
+        GetResponse {
+            headers: v.headers,
+            body: v.body,
+        }
+    }
+}
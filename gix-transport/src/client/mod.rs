@@ -11,8 +11,6 @@ pub use traits::TransportWithoutIO;
 
 #[cfg(feature = "blocking-client")]
 mod blocking_io;
-#[cfg(feature = "http-client")]
-pub use blocking_io::http;
 #[cfg(feature = "blocking-client")]
 pub use blocking_io::{
     connect, file, ssh, ExtendedBufRead, HandleProgress, ReadlineBufRead, RequestWriter, SetServiceResponse, Transport,
@@ -28,6 +26,10 @@ pub mod capabilities;
 #[doc(inline)]
 pub use capabilities::Capabilities;
 
+///
+#[cfg(any(feature = "http-client", feature = "async-http-client"))]
+pub mod http;
+
 mod non_io_types;
 pub use gix_sec::identity::Account;
 pub use non_io_types::{Error, MessageKind, WriteMode};
@@ -22,6 +22,18 @@ pub trait TransportWithoutIO {
     fn set_identity(&mut self, _identity: gix_sec::identity::Account) -> Result<(), Error> {
         Err(Error::AuthenticationUnsupported)
     }
+    /// Like [`set_identity()`](Self::set_identity()), but use a pre-encoded `credential` with the `authtype` authentication
+    /// scheme, like `Bearer`, instead of basic authentication for subsequent calls.
+    /// Transports that don't support this return [an error][Error::AuthenticationUnsupported].
+    fn set_credential(&mut self, _authtype: String, _credential: String) -> Result<(), Error> {
+        Err(Error::AuthenticationUnsupported)
+    }
+    /// Return the authentication challenges, like the values of `WWW-Authenticate` headers, that the server sent along with
+    /// the last failure due to [missing permissions](std::io::ErrorKind::PermissionDenied).
+    /// They are empty if there was no such failure or if the transport doesn't support them.
+    fn authentication_challenges(&self) -> &[String] {
+        &[]
+    }
     /// Get a writer for sending data and obtaining the response. It can be configured in various ways
     /// to support the task at hand.
     /// `write_mode` determines how calls to the `write(…)` method are interpreted, and `on_into_read` determines
@@ -71,6 +83,14 @@ impl<T: TransportWithoutIO + ?Sized> TransportWithoutIO for Box<T> {
         self.deref_mut().set_identity(identity)
     }
 
+    fn set_credential(&mut self, authtype: String, credential: String) -> Result<(), Error> {
+        self.deref_mut().set_credential(authtype, credential)
+    }
+
+    fn authentication_challenges(&self) -> &[String] {
+        self.deref().authentication_challenges()
+    }
+
     #[cfg(any(feature = "blocking-client", feature = "async-client"))]
     fn request(
         &mut self,
@@ -103,6 +123,14 @@ impl<T: TransportWithoutIO + ?Sized> TransportWithoutIO for &mut T {
         self.deref_mut().set_identity(identity)
     }
 
+    fn set_credential(&mut self, authtype: String, credential: String) -> Result<(), Error> {
+        self.deref_mut().set_credential(authtype, credential)
+    }
+
+    fn authentication_challenges(&self) -> &[String] {
+        self.deref().authentication_challenges()
+    }
+
     #[cfg(any(feature = "blocking-client", feature = "async-client"))]
     fn request(
         &mut self,
@@ -1,3 +1,5 @@
+#[cfg(feature = "async-client")]
+mod async_io;
 #[cfg(feature = "blocking-client")]
 mod blocking_io;
 mod capabilities;
@@ -70,6 +70,8 @@ pub(super) struct State {
     seen: HashSet<ObjectId>,
     parents_buf: Vec<u8>,
     parent_ids: SmallVec<[(ObjectId, SecondsSinceUnixEpoch); 2]>,
+    /// Tips whose ancestry is yet to be hidden before the first commit is returned.
+    hidden_tips: Vec<ObjectId>,
 }
 
 ///
@@ -78,6 +80,7 @@ mod init {
     use gix_date::SecondsSinceUnixEpoch;
     use gix_hash::{oid, ObjectId};
     use gix_object::{CommitRefIter, FindExt};
+    use smallvec::SmallVec;
 
     use super::{
         super::{simple::Sorting, Either, Info, ParentIds, Parents, Simple},
@@ -93,6 +96,7 @@ mod init {
                 seen: Default::default(),
                 parents_buf: vec![],
                 parent_ids: Default::default(),
+                hidden_tips: Vec::new(),
             }
         }
     }
@@ -103,6 +107,7 @@ mod init {
             self.queue.clear();
             self.buf.clear();
             self.seen.clear();
+            self.hidden_tips.clear();
         }
     }
 
@@ -148,6 +153,17 @@ mod init {
             self
         }
 
+        /// Hide the given `tips`, along with all commits reachable by them, so that they will not be returned by the traversal,
+        /// similar to `git rev-list ^<tip>`.
+        ///
+        /// Before the first commit is returned, interesting and hidden commits are traversed together by commit date until
+        /// only hidden commits are left to be traversed, just like `git` does it. Thus, with commit dates that are
+        /// significantly skewed, commits may be returned that would be reachable from hidden tips, which is the case for `git` as well.
+        pub fn hide(mut self, tips: impl IntoIterator<Item = impl Into<ObjectId>>) -> Self {
+            self.state.hidden_tips.extend(tips.into_iter().map(Into::into));
+            self
+        }
+
         /// Set the commitgraph as `cache` to greatly accelerate any traversal.
         ///
         /// The cache will be used if possible, but we will fall-back without error to using the object
@@ -250,6 +266,11 @@ mod init {
         type Item = Result<Info, Error>;
 
         fn next(&mut self) -> Option<Self::Item> {
+            if !self.state.hidden_tips.is_empty() {
+                if let Err(err) = self.mark_hidden() {
+                    return Some(Err(err));
+                }
+            }
             if matches!(self.parents, Parents::First) {
                 self.next_by_topology()
             } else {
@@ -274,12 +295,116 @@ mod init {
         }
     }
 
+    /// The state of commits during [`Simple::mark_hidden()`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Mark {
+        Interesting,
+        Hidden,
+    }
+
+    /// The amount of commits to keep traversing once only hidden ones are queued, to deal with minor clock skew.
+    const SLOP: usize = 5;
+
     /// Utilities
     impl<Find, Predicate> Simple<Find, Predicate>
     where
         Find: gix_object::Find,
         Predicate: FnMut(&oid) -> bool,
     {
+        /// Traverse all queued tips along with the hidden tips by commit date and stop once only hidden commits are left,
+        /// marking all commits reachable from hidden tips as seen so the actual traversal won't return them.
+        fn mark_hidden(&mut self) -> Result<(), Error> {
+            let hidden_tips = std::mem::take(&mut self.state.hidden_tips);
+            let mut marks = gix_hashtable::HashMap::<ObjectId, Mark>::default();
+            let mut queue = gix_revwalk::PriorityQueue::<SecondsSinceUnixEpoch, ObjectId>::new();
+            let tips: Vec<_> = self
+                .state
+                .next
+                .iter()
+                .copied()
+                .chain(self.state.queue.iter_unordered().copied())
+                .collect();
+            for (tip, mark) in tips
+                .into_iter()
+                .map(|id| (id, Mark::Interesting))
+                .chain(hidden_tips.into_iter().map(|id| (id, Mark::Hidden)))
+            {
+                marks.insert(tip, mark);
+                queue.insert(self.lookup(&tip)?.0, tip);
+            }
+
+            let mut slop = SLOP;
+            while let Some((_time, id)) = queue.pop() {
+                let mark = marks[&id];
+                let (_time, parents) = self.lookup(&id)?;
+                for parent_id in parents {
+                    match marks.get(&parent_id).copied() {
+                        None => {
+                            marks.insert(parent_id, mark);
+                            queue.insert(self.lookup(&parent_id)?.0, parent_id);
+                        }
+                        Some(Mark::Interesting) if mark == Mark::Hidden => {
+                            // Revisit it to propagate the new mark to its ancestors as well.
+                            marks.insert(parent_id, mark);
+                            queue.insert(self.lookup(&parent_id)?.0, parent_id);
+                        }
+                        Some(_) => {}
+                    }
+                    if mark == Mark::Interesting && matches!(self.parents, Parents::First) {
+                        break;
+                    }
+                }
+
+                if queue.iter_unordered().all(|id| marks[id] == Mark::Hidden) {
+                    slop -= 1;
+                    if slop == 0 {
+                        break;
+                    }
+                } else {
+                    slop = SLOP;
+                }
+            }
+
+            let state = &mut self.state;
+            let is_hidden = |id: &ObjectId| marks.get(id) == Some(&Mark::Hidden);
+            state.next.retain(|id| !is_hidden(id));
+            let queue = std::mem::replace(&mut state.queue, gix_revwalk::PriorityQueue::new());
+            state.queue = queue
+                .into_iter_unordered()
+                .filter(|(_time, id)| !is_hidden(id))
+                .collect();
+            state.seen.extend(
+                marks
+                    .into_iter()
+                    .filter_map(|(id, mark)| (mark == Mark::Hidden).then_some(id)),
+            );
+            Ok(())
+        }
+
+        /// Return the commit time and the parents of the commit with `id`.
+        fn lookup(&mut self, id: &oid) -> Result<(SecondsSinceUnixEpoch, SmallVec<[ObjectId; 2]>), Error> {
+            let state = &mut self.state;
+            Ok(
+                match super::super::find(self.cache.as_ref(), &self.objects, id, &mut state.buf)? {
+                    Either::CachedCommit(commit) => {
+                        if !collect_parents(&mut state.parent_ids, self.cache.as_ref(), commit.iter_parents()) {
+                            // drop corrupt caches and try again with ODB
+                            self.cache = None;
+                            return self.lookup(id);
+                        }
+                        (
+                            commit.committer_timestamp() as SecondsSinceUnixEpoch,
+                            state.parent_ids.drain(..).map(|(id, _time)| id).collect(),
+                        )
+                    }
+                    Either::CommitRefIter(commit_iter) => (
+                        commit_iter.committer()?.time.seconds,
+                        commit_iter.parent_ids().collect(),
+                    ),
+                },
+            )
+        }
+
         fn next_by_commit_date(
             &mut self,
             cutoff_older_than: Option<SecondsSinceUnixEpoch>,
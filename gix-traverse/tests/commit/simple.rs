@@ -8,6 +8,7 @@ struct TraversalAssertion<'a> {
     repo_name: &'a str,
     tips: &'a [&'a str],
     expected: &'a [&'a str],
+    hidden: &'a [&'a str],
     mode: commit::Parents,
     sorting: commit::simple::Sorting,
 }
@@ -23,6 +24,7 @@ impl<'a> TraversalAssertion<'a> {
             repo_name,
             tips,
             expected,
+            hidden: &[],
             mode: Default::default(),
             sorting: Default::default(),
         }
@@ -37,6 +39,11 @@ impl<'a> TraversalAssertion<'a> {
         self.sorting = sorting;
         self
     }
+
+    fn with_hidden(&mut self, hidden: &'a [&'a str]) -> &mut Self {
+        self.hidden = hidden;
+        self
+    }
 }
 
 impl TraversalAssertion<'_> {
@@ -47,6 +54,7 @@ impl TraversalAssertion<'_> {
         let expected: Vec<ObjectId> = tips
             .clone()
             .into_iter()
+            .filter(|tip| !self.hidden.iter().any(|hidden| hex_to_id(hidden) == *tip))
             .chain(self.expected.iter().map(|hex_id| hex_to_id(hex_id)))
             .collect();
         Ok((store, tips, expected))
@@ -67,6 +75,7 @@ impl TraversalAssertion<'_> {
                 .sorting(self.sorting)?
                 .parents(self.mode)
                 .commit_graph(self.setup_commitgraph(store.store_ref(), use_commitgraph))
+                .hide(self.hidden.iter().copied().map(hex_to_id))
                 .map(|res| res.map(|info| info.id))
                 .collect::<Result<Vec<_>, _>>()?;
 
@@ -83,6 +92,7 @@ impl TraversalAssertion<'_> {
                 .sorting(self.sorting)?
                 .parents(self.mode)
                 .commit_graph(self.setup_commitgraph(store.store_ref(), use_commitgraph))
+                .hide(self.hidden.iter().copied().map(hex_to_id))
                 .map(|res| res.map(|info| info.id))
                 .collect::<Result<Vec<_>, _>>()?;
             assert_eq!(oids, expected);
@@ -333,6 +343,41 @@ mod same_date {
             }
         })
     }
+
+    #[test]
+    fn head_breadth_first_with_hidden_commits() -> crate::Result {
+        TraversalAssertion::new(
+            "make_traversal_repo_for_commits_same_date.sh",
+            &["01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"], /* m1b1 */
+            &[
+                "efd9a841189668f1bab5b8ebade9cd0a1b139a37", /* c5 */
+                "ce2e8ffaa9608a26f7b21afc1db89cadb54fd353", /* b1c2 */
+                "9152eeee2328073cf23dcf8e90c949170b711659", /* b1c1 */
+            ],
+        )
+        .with_hidden(&["9556057aee5abb06912922e9f26c46386a816822" /* c4 */])
+        .check()?;
+
+        TraversalAssertion::new(
+            "make_traversal_repo_for_commits_same_date.sh",
+            &["01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"], /* m1b1 */
+            &["efd9a841189668f1bab5b8ebade9cd0a1b139a37" /* c5 */],
+        )
+        .with_hidden(&["ce2e8ffaa9608a26f7b21afc1db89cadb54fd353" /* b1c2 */])
+        .with_sorting(Sorting::ByCommitTimeNewestFirst)
+        .check()
+    }
+
+    #[test]
+    fn hidden_tips_hide_everything_if_they_are_also_tips() -> crate::Result {
+        TraversalAssertion::new(
+            "make_traversal_repo_for_commits_same_date.sh",
+            &["01ec18a3ebf2855708ad3c9d244306bc1fae3e9b"], /* m1b1 */
+            &[],
+        )
+        .with_hidden(&["01ec18a3ebf2855708ad3c9d244306bc1fae3e9b" /* m1b1 */])
+        .check()
+    }
 }
 
 /// Some dates adjusted to be a year apart, but still 'c1' and 'c2' with the same date.
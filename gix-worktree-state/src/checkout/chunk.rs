@@ -83,6 +83,7 @@ pub struct Options {
     pub fs: gix_fs::Capabilities,
     pub destination_is_initially_empty: bool,
     pub overwrite_existing: bool,
+    pub only_entries_flagged_for_update: bool,
     pub keep_going: bool,
     pub filter_process_delay: gix_filter::driver::apply::Delay,
 }
@@ -93,6 +94,7 @@ impl From<&checkout::Options> for Options {
             fs: opts.fs,
             destination_is_initially_empty: opts.destination_is_initially_empty,
             overwrite_existing: opts.overwrite_existing,
+            only_entries_flagged_for_update: opts.only_entries_flagged_for_update,
             keep_going: opts.keep_going,
             filter_process_delay: opts.filter_process_delay,
         }
@@ -116,6 +118,12 @@ where
     let mut files_in_chunk = 0;
 
     for (entry, entry_path) in entries_with_paths {
+        if ctx.options.only_entries_flagged_for_update {
+            if !entry.flags.contains(gix_index::entry::Flags::UPDATE) {
+                continue;
+            }
+            entry.flags.remove(gix_index::entry::Flags::UPDATE);
+        }
         // TODO: write test for that
         if entry.flags.contains(gix_index::entry::Flags::SKIP_WORKTREE) {
             files.fetch_add(1, Ordering::Relaxed);
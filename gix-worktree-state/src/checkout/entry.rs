@@ -245,6 +245,8 @@ fn open_options(path: &Path, destination_is_initially_empty: bool, overwrite_exi
     options
         .create_new(destination_is_initially_empty && !overwrite_existing)
         .create(!destination_is_initially_empty || overwrite_existing)
+        // Existing files are overwritten, which must not leave any of their previous content behind.
+        .truncate(!destination_is_initially_empty || overwrite_existing)
         .write(true);
     options
 }
@@ -56,6 +56,10 @@ pub struct Options {
     /// these will try to delete the existing entry.
     /// This is similar in behaviour as `git checkout --force`.
     pub overwrite_existing: bool,
+    /// If true, default false, only entries marked with the [`UPDATE`](gix_index::entry::Flags::UPDATE) flag are checked out,
+    /// while all other entries are skipped without being counted. The flag is removed from entries as they are processed.
+    /// This is useful to only update the files that changed in an existing worktree, like `git checkout <branch>` does.
+    pub only_entries_flagged_for_update: bool,
     /// If true, default false, try to checkout as much as possible and don't abort on first error which isn't
     /// due to a conflict.
     /// The checkout operation will never fail, but count the encountered errors instead along with their paths.
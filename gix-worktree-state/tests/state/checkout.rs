@@ -255,6 +255,40 @@ fn overwriting_files_and_lone_directories_works() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn only_entries_flagged_for_update_are_written() -> crate::Result {
+    let git_dir = fixture_path("make_mixed_without_submodules").join(".git");
+    let mut index = gix_index::File::at(git_dir.join("index"), gix_hash::Kind::Sha1, false, Default::default())?;
+    let odb = gix_odb::at(git_dir.join("objects"))?.into_inner().into_arc()?;
+    let destination = gix_testtools::tempfile::tempdir_in(std::env::current_dir()?)?;
+
+    let idx = index.entry_index_by_path("dir/content".into()).expect("present");
+    index.entries_mut()[idx].flags.insert(gix_index::entry::Flags::UPDATE);
+
+    let mut opts = opts_from_probe();
+    opts.only_entries_flagged_for_update = true;
+    let outcome = gix_worktree_state::checkout(
+        &mut index,
+        destination.path(),
+        odb,
+        &progress::Discard,
+        &progress::Discard,
+        &AtomicBool::default(),
+        opts,
+    )?;
+
+    assert_eq!(outcome.files_updated, 1, "only the flagged entry was written");
+    assert_eq!(
+        stripped_prefix(&destination, &dir_structure(&destination)),
+        paths(["dir/content"])
+    );
+    assert!(
+        !index.entries()[idx].flags.contains(gix_index::entry::Flags::UPDATE),
+        "the flag is removed once the entry was processed"
+    );
+    Ok(())
+}
+
 #[test]
 fn symlinks_become_files_if_disabled() -> crate::Result {
     let mut opts = opts_from_probe();
@@ -0,0 +1,160 @@
+//! Apply patches as produced by `git diff` or `git format-patch` to the worktree, the index or a tree, similar to `git apply`.
+//!
+//! This is what [`Repository::apply_patch()`](crate::Repository::apply_patch()) does, with the parsing and application
+//! of individual files being performed by [`gix_diff::patch`].
+use gix_hash::ObjectId;
+
+use crate::bstr::BString;
+
+/// Where to apply a patch to.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// Apply the patch to files in the worktree only, like `git apply`.
+    #[default]
+    Worktree,
+    /// Apply the patch to the blobs in the index, leaving the worktree untouched, like `git apply --cached`.
+    Index,
+    /// Apply the patch to the blobs in the index and write the result into the worktree as well, like `git apply --index`.
+    ///
+    /// Files touched by the patch must not have changes in the worktree compared to the index.
+    IndexAndWorktree,
+    /// Apply the patch to the tree with the given id and write a new tree, leaving index and worktree untouched.
+    ///
+    /// The id of the new tree is available in [`Outcome::tree`].
+    Tree(ObjectId),
+}
+
+/// Options for use in [`Repository::apply_patch()`](crate::Repository::apply_patch()).
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// Where to apply the patch to.
+    pub target: Target,
+    /// How to apply the hunks of each file, also to apply the patch in reverse.
+    pub hunks: gix_diff::patch::apply::Options,
+    /// If `true` and hunks don't apply, use the preimage blob of the patch to perform a three-way merge with the current
+    /// version of the file, like `git apply --3way`.
+    ///
+    /// This requires the blob recorded in the `index` line of the patch to be present in the object database.
+    /// Conflicts are marked with conflict markers, and are recorded as stages in the index if it is a target.
+    /// Conflicts can't be represented in a [`Target::Tree`] and are an error there.
+    pub three_way: bool,
+    /// If `true`, apply all hunks that apply and skip the others instead of failing entirely, similar to `git apply --reject`.
+    ///
+    /// Unlike `git`, no `.rej` files are written, instead the rejected hunks are reported in the [`Outcome`].
+    pub allow_rejects: bool,
+    /// If `true`, don't write anything but only check if the patch applies and return the outcome,
+    /// like `git apply --check`.
+    pub dry_run: bool,
+}
+
+/// The way the changes to a file were applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    /// All hunks applied cleanly.
+    Applied,
+    /// Hunks didn't apply, and the result was produced with a three-way merge.
+    Merged {
+        /// The amount of conflicts that are marked in the file.
+        num_conflicts: usize,
+    },
+    /// Some hunks were rejected, as permitted by [`Options::allow_rejects`].
+    PartiallyApplied {
+        /// The indices of the rejected hunks.
+        rejected_hunks: Vec<usize>,
+    },
+}
+
+/// Information about a file that was changed by a patch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileOutcome {
+    /// The path of the file before the change, or `None` if it was added.
+    pub old_path: Option<BString>,
+    /// The path of the file after the change, or `None` if it was deleted.
+    pub new_path: Option<BString>,
+    /// How the change was applied.
+    pub status: Status,
+}
+
+/// The outcome of [`Repository::apply_patch()`](crate::Repository::apply_patch()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// Information about each file in the patch, in order, with paths after considering
+    /// [reversal](gix_diff::patch::apply::Options::reverse).
+    pub files: Vec<FileOutcome>,
+    /// The id of the written tree if the target was a [`Target::Tree`] and this wasn't a [dry-run](Options::dry_run).
+    pub tree: Option<ObjectId>,
+}
+
+impl Outcome {
+    /// Return `true` if there are no conflicts or rejected hunks.
+    pub fn is_clean(&self) -> bool {
+        self.files.iter().all(|file| match &file.status {
+            Status::Applied => true,
+            Status::Merged { num_conflicts } => *num_conflicts == 0,
+            Status::PartiallyApplied { .. } => false,
+        })
+    }
+}
+
+/// The error returned by [`Repository::apply_patch()`](crate::Repository::apply_patch()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Parse(#[from] gix_diff::patch::parse::Error),
+    #[error("The patch doesn't contain any changes")]
+    EmptyPatch,
+    #[error("A worktree is required to apply patches to it")]
+    MissingWorktree,
+    #[error("Path {path:?} in patch is not valid within a repository")]
+    InvalidPath { path: BString },
+    #[error("{path:?} does not exist")]
+    MissingFile { path: BString },
+    #[error("{path:?} already exists")]
+    AlreadyExists { path: BString },
+    #[error("{path:?} has changes in the worktree that aren't in the index")]
+    WorktreeMismatch { path: BString },
+    #[error("Cannot apply the binary patch to {path:?} without full binary data")]
+    BinaryWithoutData { path: BString },
+    #[error("The binary patch applies to {path:?} with id {expected}, but its current id is {actual}")]
+    BinaryPreimageMismatch {
+        path: BString,
+        expected: ObjectId,
+        actual: ObjectId,
+    },
+    #[error("Could not apply the binary patch to {path:?}")]
+    Binary {
+        path: BString,
+        source: gix_diff::patch::apply::Error,
+    },
+    #[error("The hunks at indices {hunks:?} of the patch don't apply to {path:?}")]
+    Rejected { path: BString, hunks: Vec<usize> },
+    #[error("The three-way merge of {path:?} has conflicts that can't be stored in a tree")]
+    Conflict { path: BString },
+    #[error("Could not access {path:?} in the worktree")]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    ProtectOptions(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    DiffAlgorithm(#[from] crate::config::diff::algorithm::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    LookupPrefix(#[from] gix_odb::store::prefix::lookup::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error("Could not obtain the filesystem stats of {path:?}")]
+    Stat {
+        path: BString,
+        source: std::time::SystemTimeError,
+    },
+}
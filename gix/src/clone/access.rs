@@ -35,6 +35,16 @@ impl PrepareFetch {
         self
     }
 
+    /// Bootstrap the clone with the bundle or bundle list at `uri` before fetching from the remote, which then only has to send
+    /// what the bundles didn't contain. It takes precedence over `clone.bundleURI`.
+    ///
+    /// `uri` can be a path, a `file://` URL, or an `http(s)://` URL if HTTP support is compiled in.
+    /// Failing to obtain or apply bundles isn't fatal, the clone will then fetch everything from the remote.
+    pub fn with_bundle_uri(mut self, uri: impl Into<BString>) -> Self {
+        self.bundle_uri = Some(uri.into());
+        self
+    }
+
     /// Apply the given configuration `values` right before readying the actual fetch from the remote.
     /// The configuration is marked with [source API](gix_config::Source::Api), and will not be written back, it's
     /// retained only in memory.
@@ -0,0 +1,243 @@
+use std::{
+    io::{BufRead, Read},
+    sync::atomic::AtomicBool,
+};
+
+use gix_bundle::list::{List, Mode};
+use gix_ref::transaction::PreviousValue;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    remote::fetch::download,
+    Repository,
+};
+
+/// The error returned when bootstrapping a clone from bundles, which is never fatal to the clone itself.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub(super) enum Error {
+    #[error(transparent)]
+    Advertised(#[from] gix_protocol::bundle_uri::Error),
+    #[error(transparent)]
+    Download(#[from] download::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Header(#[from] gix_bundle::header::decode::Error),
+    #[error(transparent)]
+    Prerequisites(#[from] gix_bundle::verify::Error),
+    #[error("Could not parse bundle list")]
+    ParseList(#[from] gix_config::file::init::Error),
+    #[error(transparent)]
+    List(#[from] gix_bundle::list::from_key_values::Error),
+    #[error("The bundle list refers to another bundle list at {uri:?}, which isn't supported")]
+    NestedList { uri: BString },
+    #[error(transparent)]
+    PackConfig(#[from] crate::remote::fetch::Error),
+    #[error(transparent)]
+    WritePack(#[from] gix_pack::bundle::write::Error),
+    #[error(transparent)]
+    CreateRef(#[from] crate::reference::edit::Error),
+}
+
+/// Download the bundle or bundle list at `uri` and apply all of its bundles to `repo`, returning the amount of bundles applied.
+pub(super) fn apply_uri(
+    repo: &Repository,
+    uri: &BStr,
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<usize, Error> {
+    match obtain(repo, uri)? {
+        Content::Bundle(mut read) => {
+            apply_bundle(repo, &mut read, progress, should_interrupt)?;
+            Ok(1)
+        }
+        Content::List(content) => {
+            let list = List::from_key_values(list_key_values(&content)?)?;
+            apply_list(repo, uri, &list, progress, should_interrupt)
+        }
+    }
+}
+
+/// Apply all bundles in the bundle list made from `key_values`, as advertised by a server via the `bundle-uri` command,
+/// with relative URIs being relative to `base`.
+pub(super) fn apply_advertised(
+    repo: &Repository,
+    base: &BStr,
+    key_values: Vec<(BString, BString)>,
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<usize, Error> {
+    let list = List::from_key_values(key_values)?;
+    apply_list(repo, base, &list, progress, should_interrupt)
+}
+
+fn apply_list(
+    repo: &Repository,
+    base: &BStr,
+    list: &List,
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<usize, Error> {
+    let mut pending: Vec<_> = list
+        .bundles_in_application_order()
+        .into_iter()
+        .map(|item| download::resolve_relative(base, item.uri.as_ref()))
+        .collect();
+    match list.mode {
+        Mode::Any => {
+            let mut last_err = None;
+            for uri in pending {
+                match download_and_apply(repo, uri.as_ref(), progress, should_interrupt) {
+                    Ok(()) => return Ok(1),
+                    Err(err) => last_err = Some(err),
+                }
+            }
+            last_err.map_or(Ok(0), Err)
+        }
+        Mode::All => {
+            // Without creation tokens, we don't know which bundle provides the prerequisites of another, so we
+            // retry those with missing prerequisites for as long as we make progress.
+            let mut applied = 0;
+            loop {
+                let num_pending = pending.len();
+                let mut deferred = Vec::new();
+                let mut missing_prerequisites = None;
+                for uri in pending {
+                    match download_and_apply(repo, uri.as_ref(), progress, should_interrupt) {
+                        Ok(()) => applied += 1,
+                        Err(Error::Prerequisites(err)) => {
+                            deferred.push(uri);
+                            missing_prerequisites = Some(err);
+                        }
+                        Err(err) => return Err(err),
+                    }
+                }
+                match missing_prerequisites {
+                    None => return Ok(applied),
+                    Some(err) if deferred.len() == num_pending => return Err(err.into()),
+                    Some(_) => pending = deferred,
+                }
+            }
+        }
+    }
+}
+
+fn download_and_apply(
+    repo: &Repository,
+    uri: &BStr,
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<(), Error> {
+    match obtain(repo, uri)? {
+        Content::Bundle(mut read) => apply_bundle(repo, &mut read, progress, should_interrupt),
+        Content::List(_) => Err(Error::NestedList { uri: uri.to_owned() }),
+    }
+}
+
+enum Content {
+    /// A bundle, ready to be read from its beginning.
+    Bundle(Box<dyn BufRead>),
+    /// Anything that isn't a bundle is assumed to be a bundle list.
+    List(Vec<u8>),
+}
+
+fn obtain(repo: &Repository, uri: &BStr) -> Result<Content, Error> {
+    let mut read = download::open(repo, uri)?;
+    let mut first_line = Vec::new();
+    read.read_until(b'\n', &mut first_line)?;
+    Ok(if is_bundle_signature(&first_line) {
+        Content::Bundle(Box::new(std::io::Cursor::new(first_line).chain(read)))
+    } else {
+        let mut content = first_line;
+        read.read_to_end(&mut content)?;
+        Content::List(content)
+    })
+}
+
+/// Write the pack of the bundle in `read` to the object database of `repo` if all of its prerequisites are present,
+/// and create a `refs/bundles/<name>` reference for each of its `refs/heads/<name>` references.
+/// That way, these will be used as `have`s during the negotiation that follows.
+fn apply_bundle(
+    repo: &Repository,
+    read: &mut dyn BufRead,
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<(), Error> {
+    let (header, _pack_offset) = gix_bundle::Header::from_read(&mut &mut *read)?;
+    header.verify_prerequisites(&repo.objects)?;
+
+    progress.step();
+    progress.set_name("unbundle".into());
+    let mut outcome = gix_pack::Bundle::write_to_directory(
+        read,
+        Some(&repo.objects.store_ref().path().join("pack")),
+        progress,
+        should_interrupt,
+        Some(Box::new({
+            let repo = repo.clone();
+            repo.objects
+        })),
+        gix_pack::bundle::write::Options {
+            thread_limit: crate::remote::fetch::index_threads(repo)?,
+            index_version: crate::remote::fetch::pack_index_version(repo)?,
+            iteration_mode: gix_pack::data::input::Mode::Verify,
+            object_hash: repo.object_hash(),
+        },
+    )?;
+
+    for bundle_ref in &header.refs {
+        let Some(branch) = bundle_ref.name.strip_prefix(b"refs/heads/") else {
+            continue;
+        };
+        // This also makes the object database aware of the pack we just wrote.
+        if !repo.has_object(bundle_ref.id) {
+            continue;
+        }
+        let mut name = BString::from("refs/bundles/");
+        name.push_str(branch);
+        repo.reference(
+            name.as_bstr(),
+            bundle_ref.id,
+            PreviousValue::Any,
+            "bundle-uri: unbundle",
+        )?;
+    }
+
+    if let Some(path) = outcome.keep_path.take() {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+fn is_bundle_signature(line: &[u8]) -> bool {
+    [gix_bundle::Version::V2, gix_bundle::Version::V3]
+        .iter()
+        .any(|version| line.starts_with(version.signature()))
+}
+
+/// Turn a bundle list in `git-config` format into `(key, value)` pairs.
+fn list_key_values(content: &[u8]) -> Result<Vec<(BString, BString)>, Error> {
+    let list =
+        gix_config::File::from_bytes_no_includes(content, gix_config::file::Metadata::api(), Default::default())?;
+    let mut out = Vec::new();
+    for section in list.sections() {
+        let header = section.header();
+        let mut prefix = header.name().to_owned();
+        if let Some(subsection_name) = header.subsection_name() {
+            prefix.push_byte(b'.');
+            prefix.push_str(subsection_name);
+        }
+        for value_name in section.value_names() {
+            let Some(value) = section.value(value_name) else {
+                continue;
+            };
+            let mut key = prefix.clone();
+            key.push_byte(b'.');
+            let value_name: &str = value_name.as_ref();
+            key.push_str(value_name);
+            out.push((key, value.into_owned()));
+        }
+    }
+    Ok(out)
+}
@@ -58,6 +58,21 @@ impl PrepareFetch {
     ///
     /// Note that all data we created will be removed once this instance drops if the operation wasn't successful.
     ///
+    /// ### Bundle URIs
+    ///
+    /// If a [bundle URI](PrepareFetch::with_bundle_uri()) or `clone.bundleURI` is set, its bundles are downloaded and unbundled
+    /// before connecting to the remote. Otherwise, if `transfer.bundleURI` is `true` and the server advertises bundles via
+    /// the `bundle-uri` command, these are applied right after the handshake. Each of their branches is then available as
+    /// `refs/bundles/<branch>` so the following negotiation only has to fetch what's missing.
+    /// Bundles can be downloaded via `http(s)://` only if a blocking HTTP transport is compiled in, and failures are logged
+    /// without failing the clone.
+    ///
+    /// ### Shallow clones
+    ///
+    /// Unless the remote is [configured](PrepareFetch::configure_remote()), shallow clones only obtain tags that point into the fetched
+    /// history, instead of all tags along with their history. Tags that weren't sent along with the pack even though they point to
+    /// objects that are now present are fetched in a separate pass that doesn't change the shallow boundary.
+    ///
     /// ### Note for users of `async`
     ///
     /// Even though `async` is technically supported, it will still be blocking in nature as it uses a lot of non-async writes
@@ -116,7 +131,13 @@ impl PrepareFetch {
         if let Some(f) = self.configure_remote.as_mut() {
             remote = f(remote).map_err(Error::RemoteConfiguration)?;
         } else {
-            clone_fetch_tags = remote::fetch::Tags::All.into();
+            // Like git, shallow clones only follow tags into the fetched history as wanting all tags would pull in their history too.
+            clone_fetch_tags = if matches!(self.shallow, remote::fetch::Shallow::NoChange) {
+                remote::fetch::Tags::All
+            } else {
+                remote::fetch::Tags::Included
+            }
+            .into();
         }
 
         let config = util::write_remote_to_local_config_file(&mut remote, remote_name.clone())?;
@@ -135,7 +156,20 @@ impl PrepareFetch {
         )
         .expect("valid")
         .to_owned();
-        let pending_pack: remote::fetch::Prepare<'_, '_, _> = {
+        // Like git, apply bundles we were told about before talking to the remote, so their branches can be used in the negotiation.
+        let explicit_bundle_uri = self.bundle_uri.clone().or_else(|| {
+            repo.config
+                .resolved
+                .string(crate::config::tree::Clone::BUNDLE_URI)
+                .map(std::borrow::Cow::into_owned)
+        });
+        if let Some(uri) = &explicit_bundle_uri {
+            #[allow(unused_variables)]
+            if let Err(err) = bundle_uri::apply_uri(repo, uri.as_ref(), progress, should_interrupt) {
+                gix_trace::warn!("Could not bootstrap clone from bundles, fetching everything from the remote: {err}");
+            }
+        }
+        let mut pending_pack: remote::fetch::Prepare<'_, '_, _> = {
             let mut connection = remote.connect(remote::Direction::Fetch).await?;
             if let Some(f) = self.configure_connection.as_mut() {
                 f(&mut connection).map_err(Error::RemoteConnection)?;
@@ -191,6 +225,29 @@ impl PrepareFetch {
         if pending_pack.ref_map().object_hash != repo.object_hash() {
             unimplemented!("configure repository to expect a different object hash as advertised by the server")
         }
+
+        if explicit_bundle_uri.is_none()
+            && repo
+                .config
+                .resolved
+                .boolean(crate::config::tree::Transfer::BUNDLE_URI)
+                .and_then(Result::ok)
+                .unwrap_or(false)
+        {
+            let res = match pending_pack.bundle_uri().await {
+                Ok(Some(list)) => {
+                    bundle_uri::apply_advertised(repo, self.url.to_bstring().as_ref(), list, progress, should_interrupt)
+                }
+                Ok(None) => Ok(0),
+                Err(err) => Err(err.into()),
+            };
+            #[allow(unused_variables)]
+            if let Err(err) = res {
+                gix_trace::warn!(
+                    "Could not bootstrap clone from advertised bundles, fetching everything from the remote: {err}"
+                );
+            }
+        }
         let reflog_message = {
             let mut b = self.url.to_bstring();
             b.insert_str(0, "clone: from ");
@@ -205,6 +262,41 @@ impl PrepareFetch {
             .receive_inner(progress, should_interrupt)
             .await?;
 
+        if !matches!(self.shallow, remote::fetch::Shallow::NoChange)
+            && matches!(remote.fetch_tags(), remote::fetch::Tags::Included)
+        {
+            let tags = util::tags_to_backfill(repo, &outcome);
+            if !tags.is_empty() {
+                // Like git, fetch tags that point into the history we now have in a separate pass that doesn't deepen,
+                // on a new connection as the previous one may not be reused after deepening.
+                let mut tag_remote = remote.clone().with_fetch_tags(remote::fetch::Tags::None);
+                tag_remote
+                    .replace_refspecs(
+                        tags.iter().map(|name| {
+                            let mut spec = name.clone();
+                            spec.push_byte(b':');
+                            spec.push_str(name);
+                            spec
+                        }),
+                        remote::Direction::Fetch,
+                    )
+                    .expect("names of tags matched by a refspec are valid refspecs");
+                let mut connection = tag_remote.connect(remote::Direction::Fetch).await?;
+                if let Some(f) = self.configure_connection.as_mut() {
+                    f(&mut connection).map_err(Error::RemoteConnection)?;
+                }
+                connection
+                    .prepare_fetch(&mut *progress, Default::default())
+                    .await?
+                    .with_write_packed_refs_only(true)
+                    .with_reflog_message(RefLogMessage::Override {
+                        message: reflog_message.clone(),
+                    })
+                    .receive_inner(progress, should_interrupt)
+                    .await?;
+            }
+        }
+
         util::append_config_to_repo_config(repo, config);
         util::update_head(
             repo,
@@ -239,4 +331,5 @@ impl PrepareFetch {
     }
 }
 
+mod bundle_uri;
 mod util;
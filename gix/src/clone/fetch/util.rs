@@ -183,6 +183,27 @@ pub fn update_head(
     Ok(())
 }
 
+/// Return the names of all tags which the remote didn't send when following tags, even though the objects they point to
+/// are present after the fetch that produced `outcome`.
+pub(super) fn tags_to_backfill(repo: &Repository, outcome: &crate::remote::fetch::Outcome) -> Vec<BString> {
+    let update_refs = match &outcome.status {
+        crate::remote::fetch::Status::NoPackReceived { update_refs, .. }
+        | crate::remote::fetch::Status::Change { update_refs, .. } => update_refs,
+    };
+    update_refs
+        .updates
+        .iter()
+        .zip(&outcome.ref_map.mappings)
+        .filter(|(update, _)| update.mode == crate::remote::fetch::refs::update::Mode::ImplicitTagNotSentByRemote)
+        .filter_map(|(_, mapping)| {
+            let peeled = mapping.remote.peeled_id()?;
+            repo.has_object(peeled)
+                .then(|| mapping.remote.as_name().map(ToOwned::to_owned))
+                .flatten()
+        })
+        .collect()
+}
+
 pub(super) fn find_custom_refname<'a>(
     ref_map: &'a crate::remote::fetch::RefMap,
     ref_name: &PartialName,
@@ -37,6 +37,9 @@ pub struct PrepareFetch {
     /// The name of the reference to fetch. If `None`, the reference pointed to by `HEAD` will be checked out.
     #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
     ref_name: Option<gix_ref::PartialName>,
+    /// The location of a bundle or bundle list to bootstrap the clone with, overriding `clone.bundleURI`.
+    #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
+    bundle_uri: Option<BString>,
 }
 
 /// The error returned by [`PrepareFetch::new()`].
@@ -126,6 +129,7 @@ impl PrepareFetch {
             configure_connection: None,
             shallow: remote::fetch::Shallow::NoChange,
             ref_name: None,
+            bundle_uri: None,
         })
     }
 }
@@ -174,7 +178,11 @@ mod access_feat {
 }
 
 ///
-#[cfg(any(feature = "async-network-client-async-std", feature = "blocking-network-client"))]
+#[cfg(any(
+    feature = "async-network-client-async-std",
+    feature = "async-http-transport-reqwest",
+    feature = "blocking-network-client"
+))]
 pub mod fetch;
 
 mod access;
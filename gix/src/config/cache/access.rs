@@ -79,6 +79,9 @@ impl Cache {
             if let Some(textconv) = section.value(config::tree::Diff::DRIVER_TEXTCONV.name) {
                 driver.binary_to_text_command = textconv.into_owned().into();
             }
+            if let Some(xfuncname) = section.value(config::tree::Diff::DRIVER_XFUNCNAME.name) {
+                driver.xfuncname = xfuncname.into_owned().into();
+            }
             if let Some(algorithm) = section.value("algorithm") {
                 driver.algorithm = config::tree::Diff::DRIVER_ALGORITHM
                     .try_into_algorithm(algorithm)
@@ -117,6 +120,50 @@ impl Cache {
             .copied()
     }
 
+    /// The rename tracking to use between the `HEAD` tree and the index for `git status`, which is configured by
+    /// `status.renames` and `status.renameLimit`, falling back to their `diff.*` counterparts, and enabled by default.
+    #[cfg(feature = "status")]
+    pub(crate) fn status_renames(&self) -> Result<Option<gix_diff::Rewrites>, crate::diff::new_rewrites::Error> {
+        use crate::config::tree::{Diff, Status};
+        use crate::diff::rename::Tracking;
+        let tracking = self
+            .resolved
+            .boolean(Status::RENAMES)
+            .map(|value| Status::RENAMES.try_into_renames(value))
+            .or_else(|| {
+                self.resolved
+                    .boolean(Diff::RENAMES)
+                    .map(|value| Diff::RENAMES.try_into_renames(value))
+            })
+            .transpose()
+            .with_leniency(self.lenient_config)?
+            .unwrap_or(Tracking::Renames);
+        let copies = match tracking {
+            Tracking::Disabled => return Ok(None),
+            Tracking::Renames => None,
+            Tracking::RenamesAndCopies => Some(gix_diff::rewrites::Copies::default()),
+        };
+
+        let default = gix_diff::Rewrites::default();
+        Ok(gix_diff::Rewrites {
+            copies,
+            limit: self
+                .resolved
+                .integer(Status::RENAME_LIMIT)
+                .map(|value| Status::RENAME_LIMIT.try_into_usize(value))
+                .or_else(|| {
+                    self.resolved
+                        .integer(Diff::RENAME_LIMIT)
+                        .map(|value| Diff::RENAME_LIMIT.try_into_usize(value))
+                })
+                .transpose()
+                .with_leniency(self.lenient_config)?
+                .unwrap_or(default.limit),
+            ..default
+        }
+        .into())
+    }
+
     #[cfg(feature = "blob-diff")]
     pub(crate) fn big_file_threshold(&self) -> Result<u64, config::unsigned_integer::Error> {
         Ok(self
@@ -329,6 +376,7 @@ impl Cache {
             thread_limit,
             destination_is_initially_empty: false,
             overwrite_existing: false,
+            only_entries_flagged_for_update: false,
             keep_going: false,
             stat_options: self.stat_options().map_err(|err| match err {
                 config::stat_options::Error::ConfigCheckStat(err) => {
@@ -455,7 +503,8 @@ impl Cache {
     /// if it actually wants to use the home directory - we don't want to fail prematurely.
     #[cfg(any(
         feature = "blocking-http-transport-reqwest",
-        feature = "blocking-http-transport-curl"
+        feature = "blocking-http-transport-curl",
+        feature = "async-http-transport-reqwest"
     ))]
     pub(crate) fn home_dir(&self) -> Option<PathBuf> {
         home_dir(self.environment)
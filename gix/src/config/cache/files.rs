@@ -0,0 +1,156 @@
+use std::{
+    borrow::Cow,
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use gix_features::threading::{get_ref, OwnShared};
+
+use crate::{config::Cache, open};
+
+/// The storage for the most recent version of the configuration files, shared among all instances of the same repository.
+pub(crate) type FilesStorage = OwnShared<gix_fs::SharedFileSnapshotMut<Files>>;
+
+/// The resolved configuration along with all files that were, or could have been, used to assemble it,
+/// and what they looked like at the time, if they existed.
+#[derive(Debug)]
+pub(crate) struct Files {
+    /// The configuration as assembled from all files and other sources.
+    pub config: crate::Config,
+    paths: Vec<(PathBuf, Option<Stamp>)>,
+}
+
+/// What we know about a file to detect changes to it, even if its modification time stays the same
+/// due to a coarse filesystem time resolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Stamp {
+    modified: SystemTime,
+    len: u64,
+    /// The hash of the content if the file was modified so recently that another change might not alter `modified`.
+    racy_content_hash: Option<u64>,
+}
+
+/// The coarsest resolution of modification times we expect, which is the one of FAT filesystems.
+const TIMESTAMP_GRANULARITY: Duration = Duration::from_secs(2);
+
+impl Files {
+    /// Keep track of the files that `config` was assembled from, which is all files with sections in `config`, including includes,
+    /// and all files that might have been used as permitted by `permissions`, even if they didn't exist.
+    pub fn new(config: crate::Config, git_dir: &Path, common_dir: &Path, permissions: &open::Permissions) -> Self {
+        let open::permissions::Config {
+            git_binary: use_installation,
+            system: use_system,
+            git: use_git,
+            user: use_user,
+            ..
+        } = permissions.config;
+        let mut env = Cache::make_source_env(permissions.env);
+        let mut paths: Vec<_> = [
+            gix_config::source::Kind::GitInstallation,
+            gix_config::source::Kind::System,
+            gix_config::source::Kind::Global,
+        ]
+        .iter()
+        .flat_map(|kind| kind.sources())
+        .filter(|source| match source {
+            gix_config::Source::GitInstallation => use_installation,
+            gix_config::Source::System => use_system,
+            gix_config::Source::Git => use_git,
+            gix_config::Source::User => use_user,
+            _ => true,
+        })
+        .filter_map(|source| source.storage_location(&mut env).map(Cow::into_owned))
+        .chain([common_dir.join("config"), git_dir.join("config.worktree")])
+        .chain(config.sections().filter_map(|section| section.meta().path.clone()))
+        .collect();
+        paths.sort();
+        paths.dedup();
+
+        let paths = paths
+            .into_iter()
+            .map(|path| {
+                let stamp = stamp(&path);
+                (path, stamp)
+            })
+            .collect();
+        Files { config, paths }
+    }
+
+    /// Return the most recent modification time of all files, or the current time if one of them was created, deleted
+    /// or changed its modification time, size or content since we looked at it.
+    ///
+    /// The content is only compared for files that were modified within the timestamp granularity of the time we looked at them,
+    /// as only then a change might not be visible in their modification time. The current time is used for changed files
+    /// as their modification time may not be newer than the one of the snapshot they are compared to.
+    pub fn modification_time(&self) -> SystemTime {
+        let mut latest = SystemTime::UNIX_EPOCH;
+        for (path, known) in &self.paths {
+            let metadata = metadata(path);
+            match (known, metadata) {
+                (None, None) => {}
+                (Some(known), Some(metadata)) if known.matches(path, &metadata) => {
+                    latest = latest.max(known.modified);
+                }
+                _ => return SystemTime::now(),
+            }
+        }
+        latest
+    }
+}
+
+/// Lifecycle
+impl Files {
+    /// Place `self` into a new storage and return it, along with the snapshot it holds.
+    pub fn into_storage(self) -> (FilesStorage, gix_fs::SharedFileSnapshot<Files>) {
+        let storage: FilesStorage = OwnShared::new(gix_fs::SharedFileSnapshotMut::new());
+        let modified = self.modification_time();
+        storage
+            .force_refresh(|| Ok::<_, std::convert::Infallible>(Some((modified, self))))
+            .expect("cannot fail");
+        let snapshot = get_ref(&storage.0).clone().expect("just set");
+        (storage, snapshot)
+    }
+
+    /// Return the most recent snapshot in `storage`, which is shared by all instances of the repository.
+    pub fn latest(storage: &FilesStorage) -> Option<gix_fs::SharedFileSnapshot<Files>> {
+        get_ref(&storage.0).clone()
+    }
+}
+
+impl Stamp {
+    /// Return `true` if the file at `path` with `metadata` still looks like it did when this stamp was taken.
+    fn matches(&self, path: &Path, metadata: &std::fs::Metadata) -> bool {
+        metadata.modified().ok() == Some(self.modified)
+            && metadata.len() == self.len
+            && self
+                .racy_content_hash
+                .map_or(true, |hash| content_hash(path) == Some(hash))
+    }
+}
+
+fn metadata(path: &Path) -> Option<std::fs::Metadata> {
+    std::fs::metadata(path).ok().filter(std::fs::Metadata::is_file)
+}
+
+fn stamp(path: &Path) -> Option<Stamp> {
+    let metadata = metadata(path)?;
+    let modified = metadata.modified().ok()?;
+    let is_racy = SystemTime::now()
+        .duration_since(modified)
+        .map_or(true, |age| age < TIMESTAMP_GRANULARITY);
+    Some(Stamp {
+        modified,
+        len: metadata.len(),
+        racy_content_hash: if is_racy { Some(content_hash(path)?) } else { None },
+    })
+}
+
+/// Configuration files are small, so reading them entirely is affordable.
+fn content_hash(path: &Path) -> Option<u64> {
+    let content = std::fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    Some(hasher.finish())
+}
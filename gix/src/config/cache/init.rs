@@ -186,6 +186,8 @@ impl Cache {
             url_scheme: Default::default(),
             #[cfg(feature = "blob-diff")]
             diff_algorithm: Default::default(),
+            files_storage: None,
+            files: None,
         })
     }
 
@@ -302,6 +304,56 @@ impl crate::Repository {
         Ok(())
     }
 
+    /// Load all configuration files and other sources of configuration just like it's done when opening the repository,
+    /// and use them to replace our own configuration like [`Self::reread_values_and_clear_caches_replacing_config()`].
+    pub(crate) fn reload_config(&mut self) -> Result<(), Error> {
+        let cache = self.load_config()?;
+        self.reread_values_and_clear_caches_replacing_config(cache.resolved)
+    }
+
+    /// Load all configuration files and other sources of configuration just like it's done when opening the repository.
+    pub(crate) fn load_config(&self) -> Result<Cache, Error> {
+        let open::Options {
+            git_dir_trust,
+            lossy_config,
+            lenient_config,
+            permissions:
+                open::Permissions {
+                    env,
+                    config,
+                    attributes,
+                },
+            ref api_config_overrides,
+            ref cli_config_overrides,
+            ..
+        } = self.options;
+        let git_dir_trust = git_dir_trust.expect("trust must be determined by now");
+        let stage_one = StageOne::new(
+            self.common_dir(),
+            self.git_dir(),
+            git_dir_trust,
+            lossy_config,
+            lenient_config,
+        )?;
+        let head = self.refs.find("HEAD").ok();
+        let git_install_dir = crate::path::install_dir().ok();
+        let home = gix_path::env::home_dir().and_then(|home| env.home.check_opt(home));
+        Cache::from_stage_one(
+            stage_one,
+            self.common_dir(),
+            head.as_ref().and_then(|head| head.target.try_name()),
+            self.filter_config_section(),
+            git_install_dir.as_deref(),
+            home.as_deref(),
+            env,
+            attributes,
+            config,
+            lenient_config,
+            api_config_overrides,
+            cli_config_overrides,
+        )
+    }
+
     fn apply_changed_values(&mut self) {
         self.refs.write_reflog = util::reflog_or_default(self.config.reflog, self.work_dir().is_some());
         self.refs.namespace.clone_from(&self.config.refs_namespace);
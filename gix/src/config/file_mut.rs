@@ -0,0 +1,218 @@
+use std::{
+    io::Write,
+    ops::{Deref, DerefMut},
+    path::PathBuf,
+};
+
+use gix_config::{AsKey, KeyRef};
+
+use crate::{
+    bstr::{BStr, BString},
+    config::FileMut,
+};
+
+/// The error returned by [`Repository::config_file_mut()`](crate::Repository::config_file_mut()) and the methods of [`FileMut`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Configuration of source {0:?} isn't stored in a file and can't be changed")]
+    UnsupportedSource(gix_config::Source),
+    #[error("Could not determine where configuration of source {0:?} is stored")]
+    StorageLocationUnknown(gix_config::Source),
+    #[error(transparent)]
+    Lock(#[from] gix_lock::acquire::Error),
+    #[error(transparent)]
+    Read(#[from] gix_config::file::init::from_paths::Error),
+    #[error("Keys must have the form 'section.value' or 'section.subsection.value'")]
+    InvalidKey,
+    #[error("The key {key:?} has {count} values and can't be changed as a single value")]
+    MultipleValues { key: BString, count: usize },
+    #[error(transparent)]
+    SetRawValue(#[from] gix_config::file::set_raw_value::Error),
+    #[error("Could not write configuration file at {path:?}")]
+    Write { path: PathBuf, source: std::io::Error },
+    #[error(transparent)]
+    Reload(#[from] crate::config::Error),
+}
+
+/// Lifecycle
+impl<'repo> FileMut<'repo> {
+    /// Write all changes back to the configuration file atomically and release the lock, then reload the configuration of
+    /// the parent repository so it sees the changes.
+    pub fn commit(self) -> Result<&'repo mut crate::Repository, Error> {
+        let FileMut { repo, file, mut lock } = self;
+        let path = lock.resource_path();
+        let write_err = |source| Error::Write {
+            path: path.clone(),
+            source,
+        };
+        file.write_to(&mut lock).map_err(write_err)?;
+        lock.flush().map_err(write_err)?;
+        lock.commit().map_err(|err| write_err(err.error))?;
+        repo.reload_config()?;
+        Ok(repo)
+    }
+
+    /// Return the path to the configuration file we are editing.
+    pub fn path(&self) -> PathBuf {
+        self.lock.resource_path()
+    }
+}
+
+/// Value editing, with keys being of the form `section.value` or `section.subsection.value`.
+impl FileMut<'_> {
+    /// Set the single value at `key` to `value`, similar to `git config <key> <value>`, adding it to the last section with the
+    /// same name if it doesn't exist yet, or creating such section.
+    ///
+    /// Fails if `key` has more than one value, which is when [`replace_values()`](Self::replace_values()) should be used.
+    pub fn set_value<'a>(&mut self, key: impl AsKey, value: impl Into<&'a BStr>) -> Result<Option<BString>, Error> {
+        let key = parse_key(&key)?;
+        let count = self.value_count(&key);
+        if count > 1 {
+            return Err(Error::MultipleValues {
+                key: full_name(&key),
+                count,
+            });
+        }
+        let previous =
+            self.file
+                .set_raw_value_by(key.section_name, key.subsection_name, key.value_name.to_owned(), value)?;
+        Ok(previous.map(std::borrow::Cow::into_owned))
+    }
+
+    /// Add `value` as another value of `key`, similar to `git config --add <key> <value>`, to the last section with the same name,
+    /// creating such section if needed.
+    pub fn add_value<'a>(&mut self, key: impl AsKey, value: impl Into<&'a BStr>) -> Result<(), Error> {
+        let key = parse_key(&key)?;
+        let value_name = gix_config::parse::section::ValueName::try_from(key.value_name.to_owned())
+            .map_err(gix_config::file::set_raw_value::Error::from)?;
+        self.file
+            .section_mut_or_create_new(key.section_name, key.subsection_name)
+            .map_err(gix_config::file::set_raw_value::Error::from)?
+            .push(value_name, Some(value.into()));
+        Ok(())
+    }
+
+    /// Replace all values of `key` for which `matches(value)` returns `true` with a single `value`, similar to
+    /// `git config --replace-all <key> <value> [<value-pattern>]`, and return the amount of values that were replaced.
+    ///
+    /// The first matching value is changed in place while other matches are removed. If nothing matched, `value` is
+    /// [added](Self::add_value()) instead.
+    pub fn replace_values<'a>(
+        &mut self,
+        key: impl AsKey,
+        value: impl Into<&'a BStr>,
+        mut matches: impl FnMut(&BStr) -> bool,
+    ) -> Result<usize, Error> {
+        let key = parse_key(&key)?;
+        let value = value.into();
+        let matching = self.matching_value_indices(&key, &mut matches);
+        let Some((first, rest)) = matching.split_first() else {
+            self.add_value(key, value)?;
+            return Ok(0);
+        };
+        let mut values = self
+            .file
+            .raw_values_mut_by(key.section_name, key.subsection_name, key.value_name)
+            .expect("values exist as they matched");
+        for index in rest.iter().rev() {
+            values.delete(*index);
+        }
+        values.set_at(*first, value);
+        Ok(matching.len())
+    }
+
+    /// Remove the single value at `key`, similar to `git config --unset <key>`, and return it if it existed.
+    ///
+    /// Fails if `key` has more than one value, which is when [`remove_values()`](Self::remove_values()) should be used.
+    pub fn remove_value(&mut self, key: impl AsKey) -> Result<Option<BString>, Error> {
+        let key = parse_key(&key)?;
+        let count = self.value_count(&key);
+        if count > 1 {
+            return Err(Error::MultipleValues {
+                key: full_name(&key),
+                count,
+            });
+        }
+        let Ok(mut values) = self
+            .file
+            .raw_values_mut_by(key.section_name, key.subsection_name, key.value_name)
+        else {
+            return Ok(None);
+        };
+        let previous = values.get().ok().and_then(|values| values.into_iter().next());
+        let previous = previous.map(std::borrow::Cow::into_owned);
+        values.delete_all();
+        Ok(previous)
+    }
+
+    /// Remove all values of `key` for which `matches(value)` returns `true`, similar to `git config --unset-all <key> [<value-pattern>]`,
+    /// and return the amount of removed values.
+    pub fn remove_values(&mut self, key: impl AsKey, mut matches: impl FnMut(&BStr) -> bool) -> Result<usize, Error> {
+        let key = parse_key(&key)?;
+        let matching = self.matching_value_indices(&key, &mut matches);
+        if let Ok(mut values) = self
+            .file
+            .raw_values_mut_by(key.section_name, key.subsection_name, key.value_name)
+        {
+            for index in matching.iter().rev() {
+                values.delete(*index);
+            }
+        }
+        Ok(matching.len())
+    }
+
+    fn value_count(&self, key: &KeyRef<'_>) -> usize {
+        self.file
+            .raw_values_by(key.section_name, key.subsection_name, key.value_name)
+            .map_or(0, |values| values.len())
+    }
+
+    fn matching_value_indices(&self, key: &KeyRef<'_>, matches: &mut dyn FnMut(&BStr) -> bool) -> Vec<usize> {
+        self.file
+            .raw_values_by(key.section_name, key.subsection_name, key.value_name)
+            .map(|values| {
+                values
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, value)| matches(value.as_ref()).then_some(index))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn parse_key(key: &impl AsKey) -> Result<KeyRef<'_>, Error> {
+    key.try_as_key().ok_or(Error::InvalidKey)
+}
+
+fn full_name(key: &KeyRef<'_>) -> BString {
+    let mut name = BString::from(key.section_name);
+    if let Some(subsection) = key.subsection_name {
+        name.push(b'.');
+        name.extend_from_slice(subsection);
+    }
+    name.push(b'.');
+    name.extend_from_slice(key.value_name.as_bytes());
+    name
+}
+
+impl std::fmt::Debug for FileMut<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.file.to_string())
+    }
+}
+
+impl Deref for FileMut<'_> {
+    type Target = gix_config::File<'static>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.file
+    }
+}
+
+impl DerefMut for FileMut<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.file
+    }
+}
@@ -28,14 +28,29 @@ pub struct Snapshot<'repo> {
 ///
 /// Note that these values won't update even if the underlying file(s) change.
 ///
-/// Use [`forget()`][Self::forget()] to not apply any of the changes.
-// TODO: make it possible to load snapshots with reloading via .config() and write mutated snapshots back to disk which should be the way
-//       to affect all instances of a repo, probably via `config_mut()` and `config_mut_at()`.
+/// Use [`forget()`][Self::forget()] to not apply any of the changes, and [`Repository::config_file_mut()`] to persist
+/// changes to a configuration file so they affect all instances of a repository.
+// TODO: make it possible to load snapshots with reloading via .config().
 pub struct SnapshotMut<'repo> {
     pub(crate) repo: Option<&'repo mut Repository>,
     pub(crate) config: gix_config::File<'static>,
 }
 
+/// A configuration file of a single [source](gix_config::Source) that is locked for modification, with changes being written back
+/// to disk on [commit](Self::commit()), which also reloads the configuration of the parent repository.
+///
+/// It dereferences to the underlying [`gix_config::File`] to allow arbitrary lossless edits, like renaming or removing sections.
+/// Dropping it without committing leaves the configuration file unchanged.
+pub struct FileMut<'repo> {
+    pub(crate) repo: &'repo mut Repository,
+    pub(crate) file: gix_config::File<'static>,
+    pub(crate) lock: gix_lock::File,
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod file_mut;
+
 /// A utility structure created by [`SnapshotMut::commit_auto_rollback()`] that restores the previous configuration on drop.
 pub struct CommitAutoRollback<'repo> {
     pub(crate) repo: Option<&'repo mut Repository>,
@@ -636,6 +651,11 @@ pub(crate) struct Cache {
     #[cfg_attr(not(feature = "worktree-mutation"), allow(dead_code))]
     attributes: crate::open::permissions::Attributes,
     environment: crate::open::permissions::Environment,
+    /// The storage for the most recent configuration files shared among all instances of the repository,
+    /// or `None` if configuration isn't auto-refreshed.
+    pub(crate) files_storage: Option<cache::FilesStorage>,
+    /// The snapshot of configuration files that `resolved` was obtained from, or `None` if configuration isn't auto-refreshed.
+    pub(crate) files: Option<gix_fs::SharedFileSnapshot<cache::Files>>,
     // TODO: make core.precomposeUnicode available as well.
 }
 
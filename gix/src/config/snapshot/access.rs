@@ -57,6 +57,20 @@ impl<'repo> Snapshot<'repo> {
         self.repo.config.resolved.string(key.into())
     }
 
+    /// Return the string at `key`, like `http.proxy`, from the section whose subsection is the URL that matches `url` best,
+    /// like `http.https://example.com.proxy`, or from a section without subsection, just like `git config --get-urlmatch`.
+    ///
+    /// `None` is returned if there is no such value or if `url` is invalid.
+    /// Note that this method takes the best matching value even if it is from a file with reduced trust.
+    pub fn url_matching_string<'a>(
+        &self,
+        key: impl Into<&'a BStr>,
+        url: impl Into<&'a BStr>,
+    ) -> Option<Cow<'repo, BStr>> {
+        let url = gix_config::url_match::Url::from_bytes(url.into()).ok()?;
+        self.repo.config.resolved.url_matching_string(key.into(), &url)
+    }
+
     /// Return the trusted and fully interpolated path at `key`, or `None` if there is no such value
     /// or if no value was found in a trusted file.
     /// An error occurs if the path could not be interpolated to its final value.
@@ -80,12 +80,13 @@ pub(super) mod function {
     /// # Deviation
     ///
     /// - Invalid urls can't be used to obtain credential helpers as they are rejected early when creating a valid `url` here.
+    /// - Subsections which aren't valid URLs, like `credential.example.com.helper`, are ignored instead of being matched
+    ///   as partial URLs.
     /// - Parsed urls will automatically drop the port if it's the default, i.e. `http://host:80` becomes `http://host` when parsed.
     ///   This affects the prompt provided to the user, so that git will use the verbatim url, whereas we use `http://host`.
     /// - Upper-case scheme and host will be lower-cased automatically when parsing into a url, so prompts differ compared to git.
     /// - A **difference in prompt might affect the matching of getting existing stored credentials**, and it's a question of this being
     ///   a feature or a bug.
-    // TODO: when dealing with `http.*.*` configuration, generalize this algorithm as needed and support precedence.
     pub fn credential_helpers(
         mut url: gix_url::Url,
         config: &gix_config::File<'_>,
@@ -105,32 +106,23 @@ pub(super) mod function {
         let url_had_user_initially = url.user().is_some();
         normalize(&mut url);
 
+        let url_to_match = url_match_target(&url);
+
         if let Some(credential_sections) = config.sections_by_name_and_filter("credential", filter) {
             for section in credential_sections {
                 let section = match section.header().subsection_name() {
-                    Some(pattern) => gix_url::parse(pattern).ok().and_then(|mut pattern| {
-                        normalize(&mut pattern);
-                        let is_http = matches!(pattern.scheme, gix_url::Scheme::Https | gix_url::Scheme::Http);
-                        let scheme = &pattern.scheme;
-                        let host = pattern.host();
-                        let ports = is_http
-                            .then(|| (pattern.port_or_default(), url.port_or_default()))
-                            .unwrap_or((pattern.port, url.port));
-                        let path = (!(is_http && pattern.path_is_root())).then_some(&pattern.path);
-
-                        if !path.map_or(true, |path| path == &url.path) {
-                            return None;
-                        }
-                        if pattern.user().is_some() && pattern.user() != url.user() {
-                            return None;
-                        }
-                        (scheme == &url.scheme && host_matches(host, url.host()) && ports.0 == ports.1).then_some((
-                            section,
-                            &credential::UrlParameter::HELPER,
-                            &credential::UrlParameter::USERNAME,
-                            &credential::UrlParameter::USE_HTTP_PATH,
-                        ))
-                    }),
+                    Some(pattern) => url_to_match
+                        .as_ref()
+                        .zip(gix_config::url_match::Url::from_pattern(pattern).ok())
+                        .and_then(|(url, pattern)| url.match_pattern(&pattern))
+                        .map(|_| {
+                            (
+                                section,
+                                &credential::UrlParameter::HELPER,
+                                &credential::UrlParameter::USERNAME,
+                                &credential::UrlParameter::USE_HTTP_PATH,
+                            )
+                        }),
                     None => Some((
                         section,
                         &Credential::HELPER,
@@ -215,21 +207,24 @@ pub(super) mod function {
         ))
     }
 
-    fn host_matches(pattern: Option<&str>, host: Option<&str>) -> bool {
-        match (pattern, host) {
-            (Some(pattern), Some(host)) => {
-                let lfields = pattern.split('.');
-                let rfields = host.split('.');
-                if lfields.clone().count() != rfields.clone().count() {
-                    return false;
-                }
-                lfields.zip(rfields).all(|(pat, value)| {
-                    gix_glob::wildmatch(pat.into(), value.into(), gix_glob::wildmatch::Mode::empty())
-                })
-            }
-            (None, None) => true,
-            (Some(_), None) | (None, Some(_)) => false,
+    /// Produce the URL to match against `credential.<url>.*` sections, just like `git` would assemble it from the fields
+    /// of the credential, or `None` if it can't be normalized.
+    fn url_match_target(url: &gix_url::Url) -> Option<gix_config::url_match::Url> {
+        let mut buf = format!("{}://", url.scheme.as_str());
+        if let Some(user) = url.user() {
+            buf.push_str(user);
+            buf.push('@');
+        }
+        buf.push_str(url.host().unwrap_or_default());
+        if let Some(port) = url.port {
+            buf.push_str(&format!(":{port}"));
+        }
+        let mut buf: crate::bstr::BString = buf.into();
+        if !url.path.starts_with(b"/") {
+            buf.push(b'/');
         }
+        buf.extend_from_slice(&url.path);
+        gix_config::url_match::Url::from_bytes(buf.as_ref()).ok()
     }
 
     fn normalize(url: &mut gix_url::Url) {
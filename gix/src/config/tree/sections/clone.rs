@@ -9,6 +9,8 @@ impl Clone {
         keys::RemoteName::new_remote_name("defaultRemoteName", &config::Tree::CLONE);
     /// The `clone.rejectShallow` key.
     pub const REJECT_SHALLOW: keys::Boolean = keys::Boolean::new_boolean("rejectShallow", &config::Tree::CLONE);
+    /// The `clone.bundleURI` key.
+    pub const BUNDLE_URI: keys::String = keys::String::new_string("bundleURI", &config::Tree::CLONE);
 }
 
 impl Section for Clone {
@@ -17,6 +19,6 @@ impl Section for Clone {
     }
 
     fn keys(&self) -> &[&dyn Key] {
-        &[&Self::DEFAULT_REMOTE_NAME, &Self::REJECT_SHALLOW]
+        &[&Self::DEFAULT_REMOTE_NAME, &Self::REJECT_SHALLOW, &Self::BUNDLE_URI]
     }
 }
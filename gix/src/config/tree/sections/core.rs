@@ -26,6 +26,17 @@ impl Core {
     pub const EDITOR: keys::Program = keys::Program::new_program("editor", &config::Tree::CORE);
     /// The `core.fileMode` key.
     pub const FILE_MODE: keys::Boolean = keys::Boolean::new_boolean("fileMode", &config::Tree::CORE);
+    /// The `core.fsmonitor` key.
+    pub const FS_MONITOR: FsMonitor =
+        FsMonitor::new_with_validate("fsmonitor", &config::Tree::CORE, validate::FsMonitor).with_deviation(
+            "the builtin filesystem monitor daemon, enabled with a boolean, isn't supported and is ignored",
+        );
+    /// The `core.fsmonitorHookVersion` key.
+    pub const FS_MONITOR_HOOK_VERSION: FsMonitorHookVersion = FsMonitorHookVersion::new_with_validate(
+        "fsmonitorHookVersion",
+        &config::Tree::CORE,
+        validate::FsMonitorHookVersion,
+    );
     /// The `core.ignoreCase` key.
     pub const IGNORE_CASE: keys::Boolean = keys::Boolean::new_boolean("ignoreCase", &config::Tree::CORE);
     /// The `core.filesRefLockTimeout` key.
@@ -55,6 +66,9 @@ impl Core {
     pub const SYMLINKS: keys::Boolean = keys::Boolean::new_boolean("symlinks", &config::Tree::CORE);
     /// The `core.trustCTime` key.
     pub const TRUST_C_TIME: keys::Boolean = keys::Boolean::new_boolean("trustCTime", &config::Tree::CORE);
+    /// The `core.untrackedCache` key.
+    pub const UNTRACKED_CACHE: UntrackedCache =
+        UntrackedCache::new_with_validate("untrackedCache", &config::Tree::CORE, validate::UntrackedCache);
     /// The `core.worktree` key.
     pub const WORKTREE: keys::Any = keys::Any::new("worktree", &config::Tree::CORE)
         .with_environment_override("GIT_WORK_TREE")
@@ -110,6 +124,8 @@ impl Section for Core {
             &Self::DISAMBIGUATE,
             &Self::EDITOR,
             &Self::FILE_MODE,
+            &Self::FS_MONITOR,
+            &Self::FS_MONITOR_HOOK_VERSION,
             &Self::IGNORE_CASE,
             &Self::FILES_REF_LOCK_TIMEOUT,
             &Self::PACKED_REFS_TIMEOUT,
@@ -119,6 +135,7 @@ impl Section for Core {
             &Self::REPOSITORY_FORMAT_VERSION,
             &Self::SYMLINKS,
             &Self::TRUST_C_TIME,
+            &Self::UNTRACKED_CACHE,
             &Self::WORKTREE,
             &Self::PROTECT_HFS,
             &Self::PROTECT_NTFS,
@@ -152,6 +169,15 @@ pub type LogAllRefUpdates = keys::Any<validate::LogAllRefUpdates>;
 /// The `core.disambiguate` key.
 pub type Disambiguate = keys::Any<validate::Disambiguate>;
 
+/// The `core.untrackedCache` key.
+pub type UntrackedCache = keys::Any<validate::UntrackedCache>;
+
+/// The `core.fsmonitor` key.
+pub type FsMonitor = keys::Any<validate::FsMonitor>;
+
+/// The `core.fsmonitorHookVersion` key.
+pub type FsMonitorHookVersion = keys::Any<validate::FsMonitorHookVersion>;
+
 #[cfg(feature = "attributes")]
 mod filter {
     use super::validate;
@@ -357,6 +383,69 @@ mod log_all_ref_updates {
     }
 }
 
+mod untracked_cache {
+    use crate::{config, config::tree::core::UntrackedCache};
+
+    impl UntrackedCache {
+        /// Returns `Some(true)` if the untracked cache should be used and added if it's missing, `Some(false)` if it
+        /// shouldn't be used, or `None` if it should be used only if it's present, which is what `keep` means.
+        pub fn try_into_untracked_cache(
+            &'static self,
+            value: Result<bool, gix_config::value::Error>,
+        ) -> Result<Option<bool>, config::key::GenericErrorWithValue> {
+            match value {
+                Ok(enabled) => Ok(Some(enabled)),
+                Err(err) => match err.input {
+                    val if val.eq_ignore_ascii_case(b"keep") => Ok(None),
+                    val => Err(config::key::GenericErrorWithValue::from_value(self, val)),
+                },
+            }
+        }
+    }
+}
+
+mod fs_monitor {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config::tree::core::FsMonitor};
+
+    impl FsMonitor {
+        /// Return the filesystem monitor hook program to run, or `None` if `value` is a boolean.
+        ///
+        /// ### Deviation
+        ///
+        /// A `true` value enables the builtin filesystem monitor daemon in `git`, which isn't supported. Hence we return `None`
+        /// for it, just like for `false`.
+        pub fn hook_program<'a>(&'static self, value: Cow<'a, BStr>) -> Option<Cow<'a, BStr>> {
+            (!value.is_empty() && gix_config::Boolean::try_from(value.as_ref()).is_err()).then_some(value)
+        }
+    }
+}
+
+#[cfg(feature = "status")]
+mod fs_monitor_hook_version {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config, config::tree::core::FsMonitorHookVersion};
+
+    impl FsMonitorHookVersion {
+        /// Convert `value` into the protocol version to use when talking to the filesystem monitor hook, which is either `1` or `2`.
+        pub fn try_into_hook_version(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gix_status::fs_monitor::Version, config::key::GenericErrorWithValue> {
+            match gix_config::Integer::try_from(value.as_ref())
+                .ok()
+                .and_then(|int| int.to_decimal())
+            {
+                Some(1) => Ok(gix_status::fs_monitor::Version::V1),
+                Some(2) => Ok(gix_status::fs_monitor::Version::V2),
+                _ => Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned())),
+            }
+        }
+    }
+}
+
 mod check_stat {
     use std::borrow::Cow;
 
@@ -472,6 +561,31 @@ mod validate {
         }
     }
 
+    pub struct UntrackedCache;
+    impl keys::Validate for UntrackedCache {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Core::UNTRACKED_CACHE.try_into_untracked_cache(gix_config::Boolean::try_from(value).map(|b| b.0))?;
+            Ok(())
+        }
+    }
+
+    pub struct FsMonitor;
+    impl keys::Validate for FsMonitor {
+        fn validate(&self, _value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Ok(())
+        }
+    }
+
+    pub struct FsMonitorHookVersion;
+    impl keys::Validate for FsMonitorHookVersion {
+        #[cfg_attr(not(feature = "status"), allow(unused_variables))]
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            #[cfg(feature = "status")]
+            super::Core::FS_MONITOR_HOOK_VERSION.try_into_hook_version(value.into())?;
+            Ok(())
+        }
+    }
+
     pub struct CheckStat;
     impl keys::Validate for CheckStat {
         fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
@@ -14,6 +14,13 @@ impl Fetch {
     #[cfg(feature = "attributes")]
     pub const RECURSE_SUBMODULES: RecurseSubmodules =
         RecurseSubmodules::new_with_validate("recurseSubmodules", &config::Tree::FETCH, validate::RecurseSubmodules);
+    /// The `fetch.uriProtocols` key, a comma-separated list of protocols to allow for downloading packs advertised by the server
+    /// via `packfile-uris`.
+    pub const URI_PROTOCOLS: keys::String = keys::String::new_string("uriProtocols", &config::Tree::FETCH);
+    /// The `fetch.parallel` key, the amount of remotes to fetch in parallel when fetching a group of remotes, with `0` meaning
+    /// as many as there are logical cores.
+    pub const PARALLEL: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("parallel", &config::Tree::FETCH);
 }
 
 impl Section for Fetch {
@@ -26,6 +33,8 @@ impl Section for Fetch {
             &Self::NEGOTIATION_ALGORITHM,
             #[cfg(feature = "attributes")]
             &Self::RECURSE_SUBMODULES,
+            &Self::URI_PROTOCOLS,
+            &Self::PARALLEL,
         ]
     }
 }
@@ -0,0 +1,88 @@
+use crate::{
+    config,
+    config::tree::{keys, Gc, Key, Section},
+};
+
+impl Gc {
+    /// The `gc.auto` key.
+    pub const AUTO: keys::UnsignedInteger = keys::UnsignedInteger::new_unsigned_integer("auto", &config::Tree::GC);
+    /// The `gc.autoPackLimit` key.
+    pub const AUTO_PACK_LIMIT: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("autoPackLimit", &config::Tree::GC);
+    /// The `gc.pruneExpire` key.
+    pub const PRUNE_EXPIRE: Expiry = Expiry::new_with_validate("pruneExpire", &config::Tree::GC, validate::Expiry);
+    /// The `gc.reflogExpire` key.
+    pub const REFLOG_EXPIRE: Expiry = Expiry::new_with_validate("reflogExpire", &config::Tree::GC, validate::Expiry)
+        .with_deviation("Patterns like `gc.<pattern>.reflogExpire` aren't supported");
+    /// The `gc.reflogExpireUnreachable` key.
+    pub const REFLOG_EXPIRE_UNREACHABLE: Expiry =
+        Expiry::new_with_validate("reflogExpireUnreachable", &config::Tree::GC, validate::Expiry)
+            .with_deviation("Patterns like `gc.<pattern>.reflogExpireUnreachable` aren't supported");
+}
+
+/// The `gc.reflogExpire`, `gc.reflogExpireUnreachable` and `gc.pruneExpire` keys.
+pub type Expiry = keys::Any<validate::Expiry>;
+
+mod expiry {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config,
+        config::{key::GenericErrorWithValue, tree::gc::Expiry},
+    };
+
+    impl Expiry {
+        /// Parse `value` into the time in seconds since the unix epoch before which entries expire, using `now` as reference
+        /// for relative dates like `90.days.ago` or `2 weeks`.
+        ///
+        /// Return `None` if entries never expire, as indicated by `never` or `false`, while `now` and `all` cause all entries
+        /// to expire.
+        pub fn try_into_expiry(
+            &'static self,
+            value: Cow<'_, BStr>,
+            now: std::time::SystemTime,
+        ) -> Result<Option<gix_date::SecondsSinceUnixEpoch>, config::key::GenericErrorWithValue> {
+            let value_str = value
+                .to_str()
+                .map_err(|_| GenericErrorWithValue::from_value(self, value.clone().into_owned()))?;
+            Ok(match value_str {
+                "never" | "false" => None,
+                "now" | "all" => Some(gix_date::SecondsSinceUnixEpoch::MAX),
+                date => {
+                    let time = gix_date::parse(date, Some(now))
+                        .map_err(|_| GenericErrorWithValue::from_value(self, value.into_owned()))?;
+                    Some(time.seconds)
+                }
+            })
+        }
+    }
+}
+
+impl Section for Gc {
+    fn name(&self) -> &str {
+        "gc"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::AUTO,
+            &Self::AUTO_PACK_LIMIT,
+            &Self::PRUNE_EXPIRE,
+            &Self::REFLOG_EXPIRE,
+            &Self::REFLOG_EXPIRE_UNREACHABLE,
+        ]
+    }
+}
+
+mod validate {
+    use crate::{bstr::BStr, config::tree::keys};
+
+    pub struct Expiry;
+    impl keys::Validate for Expiry {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Gc::REFLOG_EXPIRE.try_into_expiry(value.into(), std::time::SystemTime::now())?;
+            Ok(())
+        }
+    }
+}
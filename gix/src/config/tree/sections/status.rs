@@ -9,6 +9,12 @@ impl Status {
         &config::Tree::STATUS,
         validate::ShowUntrackedFiles,
     );
+    /// The `status.renameLimit` key.
+    pub const RENAME_LIMIT: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("renameLimit", &config::Tree::STATUS)
+            .with_note("Overrides `diff.renameLimit` for rename tracking between the HEAD tree and the index");
+    /// The `status.renames` key.
+    pub const RENAMES: super::diff::Renames = super::diff::Renames::new_renames("renames", &config::Tree::STATUS);
 }
 
 /// The `status.showUntrackedFiles` key.
@@ -41,7 +47,7 @@ impl Section for Status {
     }
 
     fn keys(&self) -> &[&dyn Key] {
-        &[&Self::SHOW_UNTRACKED_FILES]
+        &[&Self::SHOW_UNTRACKED_FILES, &Self::RENAME_LIMIT, &Self::RENAMES]
     }
 }
 
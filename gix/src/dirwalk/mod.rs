@@ -9,6 +9,10 @@ mod options;
 #[allow(clippy::empty_docs)]
 pub mod iter;
 
+///
+#[allow(clippy::empty_docs)]
+pub mod untracked_cache;
+
 /// An iterator for entries in a directory walk.
 ///
 /// ### Parallel Operation
@@ -54,6 +58,11 @@ pub enum Error {
     Prefix(#[from] gix_path::realpath::Error),
     #[error(transparent)]
     FilesystemOptions(#[from] config::boolean::Error),
+    #[error(transparent)]
+    UntrackedCache(#[from] untracked_cache::Error),
+    #[cfg(feature = "status")]
+    #[error(transparent)]
+    FsMonitorHookVersion(#[from] config::key::GenericErrorWithValue),
 }
 
 /// The outcome of the [dirwalk()](crate::Repository::dirwalk).
@@ -87,5 +96,6 @@ pub struct Options {
     classify_untracked_bare_repositories: bool,
     emit_collapsed: Option<CollapsedEntriesEmissionMode>,
     symlinks_to_directories_are_ignored_like_directories: bool,
+    pub(crate) threads: Option<usize>,
     pub(crate) empty_patterns_match_prefix: bool,
 }
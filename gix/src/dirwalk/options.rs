@@ -18,6 +18,7 @@ impl Options {
             emit_collapsed: None,
             empty_patterns_match_prefix: false,
             symlinks_to_directories_are_ignored_like_directories: false,
+            threads: None,
         }
     }
 }
@@ -38,6 +39,7 @@ impl From<Options> for gix_dir::walk::Options {
             emit_collapsed: v.emit_collapsed,
             symlinks_to_directories_are_ignored_like_directories: v
                 .symlinks_to_directories_are_ignored_like_directories,
+            threads: v.threads,
         }
     }
 }
@@ -183,4 +185,19 @@ impl Options {
         self.symlinks_to_directories_are_ignored_like_directories = value;
         self
     }
+
+    /// If `Some(thread_limit)`, read directories ahead of the traversal using up to `thread_limit` threads,
+    /// or as many threads as there are logical cores if it is `0`. If `None`, the default, the traversal reads all directories itself.
+    ///
+    /// This doesn't affect the order in which entries are emitted, but may increase the amount of directories that are read.
+    pub fn threads(mut self, value: Option<usize>) -> Self {
+        self.threads = value;
+        self
+    }
+
+    /// Like [`threads()`](Self::threads), but only requires a mutably borrowed instance.
+    pub fn set_threads(&mut self, value: Option<usize>) -> &mut Self {
+        self.threads = value;
+        self
+    }
 }
@@ -0,0 +1,125 @@
+use crate::bstr::BString;
+use crate::config::cache::util::ApplyLeniency;
+use crate::config::tree::Core;
+use crate::Repository;
+use gix_index::extension::untracked_cache::OidStat;
+use std::path::Path;
+
+/// The error returned when preparing the untracked cache of an index for use in a directory walk.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    ConfigUntrackedCache(#[from] crate::config::key::GenericErrorWithValue),
+    #[error(transparent)]
+    ExcludesFile(#[from] crate::config::exclude_stack::Error),
+    #[error(transparent)]
+    StatOptions(#[from] crate::config::stat_options::Error),
+    #[error(transparent)]
+    Realpath(#[from] gix_path::realpath::Error),
+}
+
+/// A copy of the untracked cache of an index, along with everything needed to validate it during a directory walk.
+pub(crate) struct State {
+    /// The cache to use, which is updated during the walk.
+    pub cache: gix_index::extension::UntrackedCache,
+    stat_options: gix_index::entry::stat::Options,
+    info_exclude: Option<OidStat>,
+    excludes_file: Option<OidStat>,
+}
+
+impl State {
+    /// Prepare the cache for use in [`gix_dir::walk()`].
+    pub(crate) fn for_walk(&mut self) -> gix_dir::walk::UntrackedCache<'_> {
+        gix_dir::walk::UntrackedCache {
+            cache: &mut self.cache,
+            stat_options: self.stat_options,
+            info_exclude: self.info_exclude.clone(),
+            excludes_file: self.excludes_file.clone(),
+        }
+    }
+}
+
+impl Repository {
+    /// Return a copy of the untracked cache of `index` if it was created for this worktree and `core.untrackedCache` doesn't
+    /// disable it, or a new and empty cache if there is no usable one and `core.untrackedCache` is `true`.
+    ///
+    /// Just like Git, `core.untrackedCache=keep` or not setting it at all means an existing cache is used,
+    /// but none is created.
+    pub(crate) fn untracked_cache_for_dirwalk(&self, index: &gix_index::State) -> Result<Option<State>, Error> {
+        let Some(workdir) = self.work_dir() else {
+            return Ok(None);
+        };
+        let enabled = self
+            .config
+            .resolved
+            .boolean(Core::UNTRACKED_CACHE)
+            .map(|value| Core::UNTRACKED_CACHE.try_into_untracked_cache(value))
+            .transpose()
+            .with_leniency(self.config.lenient_config)?
+            .flatten();
+        if enabled == Some(false) {
+            return Ok(None);
+        }
+
+        let workdir = crate::path::realpath_opts(workdir, self.current_dir(), crate::path::realpath::MAX_SYMLINKS)?;
+        let mut identifier: BString = "Location ".into();
+        identifier.extend_from_slice(&gix_path::into_bstr(workdir));
+        identifier.extend_from_slice(b", system ");
+        let cache = match index.untracked() {
+            Some(cache) if cache.identifier().starts_with(identifier.as_slice()) => cache.clone(),
+            _ if enabled == Some(true) => {
+                identifier.extend_from_slice(system_name().as_bytes());
+                identifier.push(0);
+                gix_index::extension::UntrackedCache::new(identifier, None, None, ".gitignore", 0)
+            }
+            _ => return Ok(None),
+        };
+
+        let excludes_file = match self
+            .config
+            .excludes_file()
+            .transpose()
+            .map_err(crate::config::exclude_stack::Error::from)?
+        {
+            Some(path) => Some(path),
+            None => self
+                .config
+                .xdg_config_path("ignore")
+                .map_err(crate::config::exclude_stack::Error::from)?,
+        };
+        let object_hash = self.object_hash();
+        Ok(Some(State {
+            cache,
+            stat_options: self.stat_options()?,
+            info_exclude: oid_stat(&self.git_dir().join("info").join("exclude"), object_hash),
+            excludes_file: excludes_file.and_then(|path| oid_stat(&path, object_hash)),
+        }))
+    }
+}
+
+/// Return the stat information and the blob-id of the file at `path`, or `None` if it can't be read.
+fn oid_stat(path: &Path, object_hash: gix_hash::Kind) -> Option<OidStat> {
+    let data = std::fs::read(path).ok()?;
+    let stat = gix_index::fs::Metadata::from_path_no_follow(path)
+        .ok()
+        .and_then(|md| gix_index::entry::Stat::from_fs(&md).ok())
+        .unwrap_or_default();
+    Some(OidStat {
+        stat,
+        id: gix_object::compute_hash(object_hash, gix_object::Kind::Blob, &data),
+    })
+}
+
+/// The name of the operating system as it's used by Git in the untracked cache identifier.
+fn system_name() -> &'static str {
+    match std::env::consts::OS {
+        "linux" => "Linux",
+        "macos" => "Darwin",
+        "windows" => "Windows",
+        "freebsd" => "FreeBSD",
+        "netbsd" => "NetBSD",
+        "openbsd" => "OpenBSD",
+        other => other,
+    }
+}
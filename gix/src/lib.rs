@@ -95,6 +95,8 @@
 pub use gix_actor as actor;
 #[cfg(feature = "attributes")]
 pub use gix_attributes as attrs;
+#[cfg(feature = "blocking-network-client")]
+pub use gix_bundle as bundle;
 #[cfg(feature = "command")]
 pub use gix_command as command;
 pub use gix_commitgraph as commitgraph;
@@ -329,9 +331,21 @@ pub mod remote;
 #[allow(clippy::empty_docs)]
 pub mod init;
 
+#[cfg(feature = "maintenance")]
+pub mod maintenance;
+
+#[cfg(feature = "apply")]
+pub mod apply;
+
 /// Not to be confused with 'status'.
 pub mod state;
 
+#[cfg(feature = "worktree-mutation")]
+pub mod reset;
+
+#[cfg(feature = "worktree-mutation")]
+pub mod switch;
+
 ///
 #[allow(clippy::empty_docs)]
 #[cfg(feature = "status")]
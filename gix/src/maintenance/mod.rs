@@ -0,0 +1,198 @@
+//! Keep repositories efficient by packing references and objects, and by removing objects that aren't needed anymore.
+//!
+//! This is what `git gc` and `git maintenance` do, and it's triggered by [`Repository::gc()`](crate::Repository::gc())
+//! or [`Repository::gc_auto()`](crate::Repository::gc_auto()) if thresholds are exceeded.
+use std::path::PathBuf;
+
+use gix_date::SecondsSinceUnixEpoch;
+
+/// The way objects are put into packs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repack {
+    /// Don't touch any pack or loose object, but still prune unreachable loose objects if configured.
+    None,
+    /// Put all reachable objects into a single pack, along with unreachable objects in packs that aren't
+    /// old enough to be pruned yet, and remove all previous packs that aren't marked with a `.keep` file.
+    /// Unreachable loose objects that aren't old enough to be pruned stay loose.
+    ///
+    /// This is what `git gc` does by default, similar to `git repack -A -d`.
+    All,
+    /// Put all loose objects into a new pack and merge it with the smallest packs until the sizes of all remaining packs
+    /// form a geometric progression with the given `factor`, i.e. each pack has at least `factor` times as many objects
+    /// as the next smaller pack.
+    ///
+    /// This keeps the amount of packs low while avoiding to rewrite large packs, similar to `git repack --geometric=<factor> -d`.
+    Geometric {
+        /// The factor by which each pack must be larger than the next smaller one.
+        factor: u32,
+    },
+}
+
+/// The thresholds to trigger [`Repository::gc_auto()`](crate::Repository::gc_auto()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Auto {
+    /// If there are more than this amount of loose objects, they will be packed. `None` disables the check.
+    ///
+    /// This is what `gc.auto` controls, which defaults to 6700.
+    pub loose_objects: Option<usize>,
+    /// If there are more than this amount of packs, they will be consolidated into one. `None` disables the check.
+    ///
+    /// This is what `gc.autoPackLimit` controls, which defaults to 50.
+    pub packs: Option<usize>,
+}
+
+impl Default for Auto {
+    fn default() -> Self {
+        Auto {
+            loose_objects: Some(6700),
+            packs: Some(50),
+        }
+    }
+}
+
+/// Options for use in [`Repository::gc()`](crate::Repository::gc()), typically obtained with
+/// [`Repository::maintenance_options()`](crate::Repository::maintenance_options()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// The thresholds for use in [`Repository::gc_auto()`](crate::Repository::gc_auto()).
+    pub auto: Auto,
+    /// The way objects are put into packs.
+    pub repack: Repack,
+    /// Unreachable objects older than this time in seconds since the unix epoch will be removed, or none if `None`.
+    /// Objects that are reachable from more recent unreachable objects are kept as well.
+    ///
+    /// This is what `gc.pruneExpire` controls, which defaults to two weeks ago.
+    pub prune_expire: Option<SecondsSinceUnixEpoch>,
+    /// If `true`, old entries are removed from all reference logs before determining which objects are reachable,
+    /// as configured by `gc.reflogExpire` and `gc.reflogExpireUnreachable`.
+    pub expire_reflogs: bool,
+    /// If `true`, all loose references that aren't symbolic will be moved into the `packed-refs` file.
+    pub pack_refs: bool,
+    /// If `true`, a `multi-pack-index` is written for all packs. Note that an existing multi-pack-index is always rewritten
+    /// so that it doesn't refer to packs that were removed.
+    pub write_multi_pack_index: bool,
+    /// The amount of threads to use when creating packs, or `None` to use all logical cores.
+    pub thread_limit: Option<usize>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            auto: Auto::default(),
+            repack: Repack::All,
+            prune_expire: None,
+            expire_reflogs: true,
+            pack_refs: true,
+            write_multi_pack_index: false,
+            thread_limit: None,
+        }
+    }
+}
+
+/// The outcome of [`Repository::gc()`](crate::Repository::gc()).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of references that were moved into the `packed-refs` file.
+    pub num_packed_refs: usize,
+    /// The amount of entries that were removed from reference logs.
+    pub num_expired_reflog_entries: usize,
+    /// The path to the index of the pack that was written, if one was written at all.
+    pub written_pack: Option<PathBuf>,
+    /// The amount of objects in the written pack.
+    pub num_packed_objects: usize,
+    /// The paths to the indices of all packs that were removed as their objects are now in the written pack.
+    pub removed_packs: Vec<PathBuf>,
+    /// The amount of loose objects that were removed as they are now contained in the written pack.
+    pub num_removed_loose_objects: usize,
+    /// The amount of unreachable loose objects that were removed as they were older than the prune expiry.
+    pub num_pruned_objects: usize,
+    /// The path to the multi-pack-index that was written, if one was written.
+    pub multi_pack_index: Option<PathBuf>,
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod options {
+    /// The error returned by [`Repository::maintenance_options()`](crate::Repository::maintenance_options()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Integer(#[from] crate::config::unsigned_integer::Error),
+        #[error(transparent)]
+        Expiry(#[from] crate::config::key::GenericErrorWithValue),
+    }
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod is_needed {
+    /// The error returned by [`Repository::gc_is_needed()`](crate::Repository::gc_is_needed()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read the object database directory")]
+        Io(#[from] std::io::Error),
+    }
+}
+
+/// The error returned by [`Repository::gc()`](crate::Repository::gc()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Another maintenance operation seems to be in progress")]
+    Lock(#[from] gix_lock::acquire::Error),
+    #[error(transparent)]
+    IsNeeded(#[from] is_needed::Error),
+    #[error(transparent)]
+    ReferenceIter(#[from] crate::reference::iter::Error),
+    #[error("Could not obtain the next reference")]
+    NextReference(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::Error),
+    #[error("Could not open the repository of a worktree")]
+    OpenWorktree(#[from] crate::open::Error),
+    #[error(transparent)]
+    ReferenceIterInit(#[from] crate::reference::iter::init::Error),
+    #[error(transparent)]
+    ExpireReflog(#[from] crate::reference::expire_log::Error),
+    #[error("Could not move loose references into the packed-refs file")]
+    PackRefs(#[from] crate::reference::edit::Error),
+    #[error("Could not read the log of reference {name:?}")]
+    ReadReflog {
+        name: gix_ref::FullName,
+        source: std::io::Error,
+    },
+    #[error("Could not decode a line in the log of reference {name:?}")]
+    DecodeReflog {
+        name: gix_ref::FullName,
+        source: gix_ref::file::log::iter::decode::Error,
+    },
+    #[error(transparent)]
+    PeelReference(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    Walk(#[from] crate::revision::walk::Error),
+    #[error(transparent)]
+    WalkCommit(#[from] gix_traverse::commit::simple::Error),
+    #[error("Could not obtain all objects reachable from references, reference logs and the index")]
+    Count(#[from] gix_pack::data::output::count::objects::Error),
+    #[error("Could not write the pack")]
+    WritePack(#[from] gix_pack::data::output::bytes::Error<gix_pack::data::output::entry::iter_from_counts::Error>),
+    #[error("Could not index the written pack")]
+    IndexPack(#[from] gix_pack::bundle::write::Error),
+    #[error("Could not open the pack index at {path:?}")]
+    OpenPackIndex {
+        path: PathBuf,
+        source: gix_pack::index::init::Error,
+    },
+    #[error("Could not write the multi-pack-index")]
+    WriteMultiPackIndex(#[from] gix_pack::multi_index::write::Error),
+    #[error("Could not read or write in the object database")]
+    Io(#[from] std::io::Error),
+    #[error("Interrupted")]
+    Interrupted,
+}
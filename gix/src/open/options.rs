@@ -14,6 +14,7 @@ impl Default for Options {
             lenient_config: true,
             bail_if_untrusted: false,
             open_path_as_is: false,
+            auto_refresh_config: false,
             api_config_overrides: Vec::new(),
             cli_config_overrides: Vec::new(),
             current_dir: None,
@@ -142,6 +143,17 @@ impl Options {
         self
     }
 
+    /// If set, default is false, keep track of all configuration files and their modification time so configuration changes
+    /// on disk can be picked up with [`Repository::refresh_config()`](crate::Repository::refresh_config()), which happens
+    /// automatically each time a [`ThreadSafeRepository`] is turned into a thread-local [`Repository`](crate::Repository).
+    ///
+    /// This is useful for long-running processes which would otherwise never see configuration changes made after
+    /// the repository was opened.
+    pub fn auto_refresh_config(mut self, toggle: bool) -> Self {
+        self.auto_refresh_config = toggle;
+        self
+    }
+
     /// Open a repository at `path` with the options set so far.
     #[allow(clippy::result_large_err)]
     pub fn open(self, path: impl Into<PathBuf>) -> Result<ThreadSafeRepository, Error> {
@@ -167,6 +179,7 @@ impl gix_sec::trust::DefaultForLevel for Options {
                 bail_if_untrusted: false,
                 lenient_config: true,
                 open_path_as_is: false,
+                auto_refresh_config: false,
                 api_config_overrides: Vec::new(),
                 cli_config_overrides: Vec::new(),
                 current_dir: None,
@@ -179,6 +192,7 @@ impl gix_sec::trust::DefaultForLevel for Options {
                 bail_if_untrusted: false,
                 lenient_config: true,
                 open_path_as_is: false,
+                auto_refresh_config: false,
                 lossy_config: None,
                 api_config_overrides: Vec::new(),
                 cli_config_overrides: Vec::new(),
@@ -165,6 +165,7 @@ impl ThreadSafeRepository {
             lenient_config,
             bail_if_untrusted,
             open_path_as_is: _,
+            auto_refresh_config,
             permissions:
                 Permissions {
                     ref env,
@@ -234,7 +235,7 @@ impl ThreadSafeRepository {
         let home = gix_path::env::home_dir().and_then(|home| env.home.check_opt(home));
 
         let mut filter_config_section = filter_config_section.unwrap_or(config::section::is_trusted);
-        let config = config::Cache::from_stage_one(
+        let mut config = config::Cache::from_stage_one(
             repo_config,
             common_dir_ref,
             head.as_ref().and_then(|head| head.target.try_name()),
@@ -318,6 +319,14 @@ impl ThreadSafeRepository {
             })
             .unwrap_or_default();
 
+        if auto_refresh_config {
+            let (storage, files) =
+                config::cache::Files::new(config.resolved.clone(), &git_dir, common_dir_ref, &options.permissions)
+                    .into_storage();
+            config.files_storage = Some(storage);
+            config.files = Some(files);
+        }
+
         Ok(ThreadSafeRepository {
             objects: OwnShared::new(gix_odb::Store::at_opts(
                 common_dir_ref.join("objects"),
@@ -21,6 +21,48 @@ pub mod edit {
     }
 }
 
+///
+#[allow(clippy::empty_docs)]
+pub mod rename {
+    /// The error returned by [`Repository::rename_reference(…)`][crate::Repository::rename_reference()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReferenceEdit(#[from] crate::reference::edit::Error),
+        #[error(transparent)]
+        FindExisting(#[from] crate::reference::find::existing::Error),
+        #[error("Could not rename the branch configuration in the local configuration file")]
+        BranchConfig(#[from] crate::config::file_mut::Error),
+    }
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod expire_log {
+    use crate::config;
+
+    /// The error returned by [`Reference::expire_log(…)`][crate::Reference::expire_log()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ExpiryConfiguration(#[from] config::key::GenericErrorWithValue),
+        #[error("Could not interpret core.filesRefLockTimeout, it must be the number in milliseconds to wait for locks or negative to wait forever")]
+        LockTimeoutConfiguration(#[from] config::lock_timeout::Error),
+        #[error(transparent)]
+        PeelToId(#[from] crate::reference::peel::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        Walk(#[from] crate::revision::walk::Error),
+        #[error(transparent)]
+        WalkCommit(#[from] gix_traverse::commit::simple::Error),
+        #[error(transparent)]
+        Expire(#[from] gix_ref::file::log::expire::Error),
+    }
+}
+
 ///
 #[allow(clippy::empty_docs)]
 pub mod peel {
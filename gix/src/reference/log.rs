@@ -5,6 +5,8 @@ use gix_ref::file::ReferenceExt;
 
 use crate::{
     bstr::{BStr, BString, ByteVec},
+    config::{cache::util::ApplyLeniency, tree::Gc},
+    reference::expire_log,
     Reference,
 };
 
@@ -18,6 +20,67 @@ impl<'repo> Reference<'repo> {
     pub fn log_exists(&self) -> bool {
         self.inner.log_exists(&self.repo.refs)
     }
+
+    /// Remove old entries from the reflog of this reference, as configured by `gc.reflogExpire` (defaulting to 90 days) and
+    /// `gc.reflogExpireUnreachable` (defaulting to 30 days), similar to `git reflog expire`.
+    ///
+    /// An entry is unreachable if the commit it points to isn't reachable from the commit this reference currently points to.
+    pub fn expire_log(&self) -> Result<gix_ref::file::log::expire::Outcome, expire_log::Error> {
+        let repo = self.repo;
+        let now = std::time::SystemTime::now();
+        let now_seconds = now
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs() as gix_date::SecondsSinceUnixEpoch);
+        let mut expiry = [None, None];
+        for (idx, (key, default_days)) in [(&Gc::REFLOG_EXPIRE, 90), (&Gc::REFLOG_EXPIRE_UNREACHABLE, 30)]
+            .into_iter()
+            .enumerate()
+        {
+            expiry[idx] = match repo
+                .config
+                .resolved
+                .string(key)
+                .map(|value| key.try_into_expiry(value, now))
+                .transpose()
+                .with_leniency(repo.config.lenient_config)?
+            {
+                Some(expiry) => expiry,
+                None => Some(now_seconds - default_days * 24 * 60 * 60),
+            };
+        }
+        let [expire, expire_unreachable] = expiry;
+
+        let reachable = match expire_unreachable {
+            Some(_) => self.reachable_commits()?,
+            None => Default::default(),
+        };
+        let (lock_fail_mode, _) = repo.config.lock_timeout()?;
+        Ok(repo.refs.reflog_expire(
+            self.name(),
+            gix_ref::file::log::expire::Options {
+                expire,
+                expire_unreachable,
+                rewrite: false,
+            },
+            lock_fail_mode,
+            &mut |id| reachable.contains(id),
+        )?)
+    }
+
+    fn reachable_commits(&self) -> Result<gix_hashtable::HashSet, expire_log::Error> {
+        let mut out = gix_hashtable::HashSet::default();
+        let Some(tip) = self.clone().into_fully_peeled_id().ok() else {
+            return Ok(out);
+        };
+        if tip.object()?.kind != gix_object::Kind::Commit {
+            out.insert(tip.detach());
+            return Ok(out);
+        }
+        for info in tip.ancestors().all()? {
+            out.insert(info?.id);
+        }
+        Ok(out)
+    }
 }
 
 /// Generate a message typical for git commit logs based on the given `operation`, commit `message` and `num_parents` of the commit.
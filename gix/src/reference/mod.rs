@@ -11,7 +11,7 @@ pub mod iter;
 pub mod remote;
 
 mod errors;
-pub use errors::{edit, find, head_commit, head_id, head_tree_id, peel};
+pub use errors::{edit, expire_log, find, head_commit, head_id, head_tree_id, peel, rename};
 
 use crate::ext::ObjectIdExt;
 
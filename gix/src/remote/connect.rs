@@ -34,6 +34,9 @@ mod error {
             source: Box<gix_discover::is_git::Error>,
             url: gix_url::Url,
         },
+        #[cfg(feature = "blocking-network-client")]
+        #[error("Could not open bundle file to use it as remote")]
+        Bundle(#[from] gix_bundle::transport::Error),
     }
 
     impl gix_protocol::transport::IsSpuriousError for Error {
@@ -65,6 +68,8 @@ impl<'repo> Remote<'repo> {
             transport_options: None,
             transport,
             trace,
+            #[cfg(feature = "blocking-network-client")]
+            bundle: None,
         }
     }
 
@@ -76,7 +81,11 @@ impl<'repo> Remote<'repo> {
     /// The transport used for connection can be configured via `transport_mut().configure()` assuming the actually
     /// used transport is well known. If that's not the case, the transport can be created by hand and passed to
     /// [to_connection_with_transport()][Self::to_connection_with_transport()].
-    #[cfg(any(feature = "blocking-network-client", feature = "async-network-client-async-std"))]
+    #[cfg(any(
+        feature = "blocking-network-client",
+        feature = "async-network-client-async-std",
+        feature = "async-http-transport-reqwest"
+    ))]
     #[gix_protocol::maybe_async::maybe_async]
     pub async fn connect(
         &self,
@@ -84,6 +93,14 @@ impl<'repo> Remote<'repo> {
     ) -> Result<Connection<'_, 'repo, Box<dyn Transport + Send>>, Error> {
         let (url, version) = self.sanitized_url_and_version(direction)?;
         #[cfg(feature = "blocking-network-client")]
+        if let Some(bundle_path) = bundle_path(&url) {
+            let transport = gix_bundle::transport::connect(bundle_path, self.repo.config.trace_packet())?;
+            let header = transport.bundle().header.clone();
+            let mut connection = self.to_connection_with_transport(Box::new(transport) as Box<dyn Transport + Send>);
+            connection.bundle = Some(header);
+            return Ok(connection);
+        }
+        #[cfg(feature = "blocking-network-client")]
         let scheme_is_ssh = url.scheme == gix_url::Scheme::Ssh;
         let transport = gix_protocol::transport::connect(
             url,
@@ -109,6 +126,10 @@ impl<'repo> Remote<'repo> {
         direction: crate::remote::Direction,
     ) -> Result<(gix_url::Url, gix_protocol::transport::Protocol), Error> {
         fn sanitize(mut url: gix_url::Url) -> Result<gix_url::Url, Error> {
+            #[cfg(feature = "blocking-network-client")]
+            if bundle_path(&url).is_some() {
+                return Ok(url);
+            }
             if url.scheme == gix_url::Scheme::File {
                 let mut dir = gix_path::to_native_path_on_windows(Cow::Borrowed(url.path.as_ref()));
                 let kind = gix_discover::is_git(dir.as_ref())
@@ -150,3 +171,13 @@ impl<'repo> Remote<'repo> {
         Ok((sanitize(url)?, version))
     }
 }
+
+/// Return the path to the bundle file `url` is pointing to, if it is a local file that is a bundle.
+#[cfg(feature = "blocking-network-client")]
+fn bundle_path(url: &gix_url::Url) -> Option<std::path::PathBuf> {
+    if url.scheme != gix_url::Scheme::File {
+        return None;
+    }
+    let path = gix_path::from_bstr(url.path.as_ref()).into_owned();
+    (path.is_file() && gix_bundle::file::is_bundle(&path)).then_some(path)
+}
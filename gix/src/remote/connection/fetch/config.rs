@@ -1,6 +1,10 @@
 use super::Error;
 use crate::{
-    config::{cache::util::ApplyLeniency, tree::Pack},
+    bstr::ByteSlice,
+    config::{
+        cache::util::ApplyLeniency,
+        tree::{Fetch, Pack},
+    },
     Repository,
 };
 
@@ -24,3 +28,20 @@ pub fn pack_index_version(repo: &Repository) -> Result<gix_pack::index::Version,
         .with_leniency(repo.options.lenient_config)?
         .unwrap_or(gix_pack::index::Version::V2))
 }
+
+/// Return the lower-cased protocols that are allowed for downloading packs advertised via `packfile-uris`, as configured in
+/// `fetch.uriProtocols`. If empty, `packfile-uris` must not be used.
+pub fn packfile_uri_protocols(repo: &Repository) -> Vec<String> {
+    repo.config
+        .resolved
+        .string_filter(Fetch::URI_PROTOCOLS, &mut repo.filter_config_section())
+        .map(|protocols| {
+            protocols
+                .to_str_lossy()
+                .split(',')
+                .map(|protocol| protocol.trim().to_ascii_lowercase())
+                .filter(|protocol| !protocol.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
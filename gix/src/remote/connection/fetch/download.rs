@@ -0,0 +1,163 @@
+use std::io::BufRead;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    Repository,
+};
+
+/// The error returned when downloading packs or bundles advertised by a remote, via `packfile-uris` or `bundle-uri`.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Url(#[from] gix_url::parse::Error),
+    #[error("The scheme of {uri:?} isn't supported for downloads, or support for it wasn't compiled in")]
+    UnsupportedScheme { uri: BString },
+    #[error("Could not open {uri:?} for reading")]
+    Io { uri: BString, source: std::io::Error },
+    #[cfg(any(
+        feature = "blocking-http-transport-curl",
+        feature = "blocking-http-transport-reqwest"
+    ))]
+    #[error(transparent)]
+    TransportOptions(#[from] crate::config::transport::Error),
+    #[cfg(any(
+        feature = "blocking-http-transport-curl",
+        feature = "blocking-http-transport-reqwest"
+    ))]
+    #[error("Could not download {uri:?}")]
+    Http {
+        uri: BString,
+        source: gix_protocol::transport::client::http::Error,
+    },
+}
+
+/// Open `uri` for reading, which may be a `file://` URL or a path, or an `http://` or `https://` URL if
+/// support for HTTP was compiled in. The latter will use the `http` configuration of `repo` that applies to `uri`.
+#[cfg_attr(
+    not(any(
+        feature = "blocking-http-transport-curl",
+        feature = "blocking-http-transport-reqwest"
+    )),
+    allow(unused_variables)
+)]
+pub(crate) fn open(repo: &Repository, uri: &BStr) -> Result<Box<dyn BufRead>, Error> {
+    let url = gix_url::parse(uri)?;
+    match url.scheme {
+        gix_url::Scheme::File => {
+            let path = gix_path::from_bstr(url.path.as_bstr());
+            let file = std::fs::File::open(&*path).map_err(|source| Error::Io {
+                uri: uri.to_owned(),
+                source,
+            })?;
+            Ok(Box::new(std::io::BufReader::new(file)))
+        }
+        #[cfg(any(
+            feature = "blocking-http-transport-curl",
+            feature = "blocking-http-transport-reqwest"
+        ))]
+        gix_url::Scheme::Http | gix_url::Scheme::Https => {
+            use gix_protocol::transport::client::http::{GetResponse, Http, Impl};
+            let mut http = Impl::default();
+            if let Some(options) = repo.transport_options(uri, None)? {
+                http.configure(options.as_ref()).map_err(|source| Error::Http {
+                    uri: uri.to_owned(),
+                    source: gix_protocol::transport::client::http::Error::InitHttpClient { source },
+                })?;
+            }
+            let url = uri.to_str_lossy();
+            let GetResponse { mut headers, body } =
+                http.get(&url, &url, None::<&str>).map_err(|source| Error::Http {
+                    uri: uri.to_owned(),
+                    source,
+                })?;
+            std::io::copy(&mut headers, &mut std::io::sink()).map_err(|source| Error::Io {
+                uri: uri.to_owned(),
+                source,
+            })?;
+            Ok(Box::new(HttpBody { _http: http, body }))
+        }
+        _ => Err(Error::UnsupportedScheme { uri: uri.to_owned() }),
+    }
+}
+
+/// Turn `uri` into an absolute URI if it is relative, assuming it is relative to `base`, the URI of the bundle list it was found in.
+#[cfg_attr(
+    not(any(
+        feature = "async-network-client-async-std",
+        feature = "async-http-transport-reqwest",
+        feature = "blocking-network-client"
+    )),
+    allow(dead_code)
+)]
+pub(crate) fn resolve_relative(base: &BStr, uri: &BStr) -> BString {
+    if uri.contains_str("://") || uri.starts_with(b"/") {
+        return uri.to_owned();
+    }
+    let mut out: BString = match base.rfind_byte(b'/') {
+        Some(pos) => base[..=pos].into(),
+        None => BString::default(),
+    };
+    out.push_str(uri);
+    out
+}
+
+/// Keep the client alive while its response body is read.
+#[cfg(any(
+    feature = "blocking-http-transport-curl",
+    feature = "blocking-http-transport-reqwest"
+))]
+struct HttpBody {
+    _http: gix_protocol::transport::client::http::Impl,
+    body: <gix_protocol::transport::client::http::Impl as gix_protocol::transport::client::http::Http>::ResponseBody,
+}
+
+#[cfg(any(
+    feature = "blocking-http-transport-curl",
+    feature = "blocking-http-transport-reqwest"
+))]
+impl std::io::Read for HttpBody {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        std::io::Read::read(&mut self.body, buf)
+    }
+}
+
+#[cfg(any(
+    feature = "blocking-http-transport-curl",
+    feature = "blocking-http-transport-reqwest"
+))]
+impl BufRead for HttpBody {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.body.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.body.consume(amt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve_relative;
+
+    #[test]
+    fn relative_uris_are_resolved_against_the_list_location() {
+        for (base, uri, expected) in [
+            (
+                "https://example.com/bundles/list",
+                "one.bundle",
+                "https://example.com/bundles/one.bundle",
+            ),
+            (
+                "https://example.com/list",
+                "https://cdn.example.com/one.bundle",
+                "https://cdn.example.com/one.bundle",
+            ),
+            ("/srv/bundles/list", "one.bundle", "/srv/bundles/one.bundle"),
+            ("/srv/bundles/list", "/elsewhere/one.bundle", "/elsewhere/one.bundle"),
+            ("list", "one.bundle", "one.bundle"),
+        ] {
+            assert_eq!(resolve_relative(base.into(), uri.into()), expected);
+        }
+    }
+}
@@ -21,6 +21,9 @@ pub enum Error {
     Client(#[from] gix_protocol::transport::client::Error),
     #[error(transparent)]
     WritePack(#[from] gix_pack::bundle::write::Error),
+    #[cfg(feature = "blocking-network-client")]
+    #[error("The pack of the bundle can't be used as not all of its prerequisites are present")]
+    BundlePrerequisites(#[from] gix_bundle::verify::Error),
     #[error(transparent)]
     UpdateRefs(#[from] super::refs::update::Error),
     #[error("Failed to remove .keep file at \"{}\"", path.display())]
@@ -47,6 +50,18 @@ pub enum Error {
     NegotiationAlgorithmConfig(#[from] config::key::GenericErrorWithValue),
     #[error("Failed to read remaining bytes in stream")]
     ReadRemainingBytes(#[source] std::io::Error),
+    #[error(
+        "The server sent the packfile URI {uri:?} even though its protocol wasn't allowed by `fetch.uriProtocols`"
+    )]
+    PackfileUriProtocol { uri: crate::bstr::BString },
+    #[error(transparent)]
+    Download(#[from] super::download::Error),
+    #[error("The pack downloaded from {uri:?} has checksum {actual}, but the server advertised {expected}")]
+    PackfileUriChecksumMismatch {
+        uri: crate::bstr::BString,
+        expected: gix_hash::ObjectId,
+        actual: gix_hash::ObjectId,
+    },
     #[error("None of the refspec(s) {} matched any of the {num_remote_refs} refs on the remote", refspecs.iter().map(|r| r.to_ref().instruction().to_bstring().to_string()).collect::<Vec<_>>().join(", "))]
     NoMapping {
         refspecs: Vec<gix_refspec::RefSpec>,
@@ -74,6 +74,11 @@ pub struct Outcome {
     pub ref_map: RefMap,
     /// The status of the operation to indicate what happened.
     pub status: Status,
+    /// Information collected while writing each pack that the server asked us to download via `packfile-uris`, in addition to
+    /// the pack it sent directly.
+    ///
+    /// This is only non-empty if `fetch.uriProtocols` is configured and the server supports `packfile-uris`.
+    pub packfile_uris: Vec<gix_pack::bundle::write::Outcome>,
 }
 
 /// Additional types related to the outcome of a fetch operation.
@@ -207,9 +212,41 @@ where
     pub fn ref_map(&self) -> &RefMap {
         &self.ref_map
     }
+
+    /// Ask the server for its bundle list with the `bundle-uri` command, returning all of its `(key, value)` pairs,
+    /// or `None` if the server doesn't support it.
+    #[gix_protocol::maybe_async::maybe_async]
+    #[cfg_attr(
+        not(any(
+            feature = "async-network-client-async-std",
+            feature = "async-http-transport-reqwest",
+            feature = "blocking-network-client"
+        )),
+        allow(dead_code)
+    )]
+    pub(crate) async fn bundle_uri(
+        &mut self,
+    ) -> Result<Option<Vec<(BString, BString)>>, gix_protocol::bundle_uri::Error> {
+        let handshake = &self.ref_map.handshake;
+        if handshake.server_protocol_version != gix_protocol::transport::Protocol::V2
+            || !handshake
+                .capabilities
+                .contains(gix_protocol::Command::BundleUri.as_str())
+        {
+            return Ok(None);
+        }
+        let con = self.con.as_mut().expect("not yet consumed by receive()");
+        gix_protocol::bundle_uri(&mut con.transport, &handshake.capabilities, con.trace)
+            .await
+            .map(Some)
+    }
 }
 
-mod config;
+pub(crate) mod config;
+///
+#[allow(clippy::empty_docs)]
+pub mod download;
+mod packfile_uris;
 mod receive_pack;
 ///
 #[path = "update_refs/mod.rs"]
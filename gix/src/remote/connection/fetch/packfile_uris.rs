@@ -0,0 +1,61 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_protocol::fetch::response::PackfileUri;
+
+use super::{download, Error};
+use crate::{bstr::ByteSlice, Repository};
+
+/// Download each pack in `uris`, write it into the object database of `repo` and verify its checksum against the one
+/// advertised by the server. Only URIs whose protocol is contained in `allowed_protocols` are downloaded, others cause an error
+/// as the server shouldn't have sent them.
+pub(crate) fn write_all(
+    repo: &Repository,
+    uris: &[PackfileUri],
+    allowed_protocols: &[String],
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+    options: &gix_pack::bundle::write::Options,
+) -> Result<Vec<gix_pack::bundle::write::Outcome>, Error> {
+    let mut out = Vec::with_capacity(uris.len());
+    for (idx, PackfileUri { hash, uri }) in uris.iter().enumerate() {
+        let protocol = uri.find(b"://").map(|pos| uri[..pos].to_ascii_lowercase());
+        if !protocol.map_or(false, |protocol| {
+            allowed_protocols.iter().any(|allowed| allowed.as_bytes() == protocol)
+        }) {
+            return Err(Error::PackfileUriProtocol { uri: uri.clone() });
+        }
+
+        progress.step();
+        progress.set_name(format!("download pack {}/{}", idx + 1, uris.len()));
+        let mut pack = download::open(repo, uri.as_bstr())?;
+        let outcome = gix_pack::Bundle::write_to_directory(
+            &mut pack,
+            Some(&repo.objects.store_ref().path().join("pack")),
+            progress,
+            should_interrupt,
+            Some(Box::new({
+                let repo = repo.clone();
+                repo.objects
+            })),
+            options.clone(),
+        )?;
+        if outcome.index.data_hash != *hash {
+            // Only remove what we created, an existing pack with the same name is unrelated to this failure.
+            if outcome.keep_path.is_some() {
+                for path in [&outcome.index_path, &outcome.data_path, &outcome.keep_path]
+                    .into_iter()
+                    .flatten()
+                {
+                    std::fs::remove_file(path).ok();
+                }
+            }
+            return Err(Error::PackfileUriChecksumMismatch {
+                uri: uri.clone(),
+                expected: *hash,
+                actual: outcome.index.data_hash,
+            });
+        }
+        out.push(outcome);
+    }
+    Ok(out)
+}
@@ -5,7 +5,8 @@ use std::{
 
 use gix_odb::store::RefreshMode;
 use gix_protocol::{
-    fetch::Arguments,
+    fetch::{response::WantedRef, Arguments},
+    handshake,
     transport::{client::Transport, packetline::read::ProgressAction},
 };
 
@@ -16,7 +17,7 @@ use crate::{
     },
     remote,
     remote::{
-        connection::fetch::config,
+        connection::fetch::{config, packfile_uris},
         fetch,
         fetch::{
             negotiate, negotiate::Algorithm, outcome, refs, Error, Outcome, Prepare, ProgressId, RefLogMessage,
@@ -53,6 +54,16 @@ where
     /// A known application for this behaviour is in `remote-helper` implementations which should send this path via `lock <path>` to stdout
     /// to inform git about the file that it will remove once it updated the refs accordingly.
     ///
+    /// ### `wanted-refs`
+    ///
+    /// If the server supports `ref-in-want`, refs are requested by name and the server reports the object ids it computed the pack for
+    /// in its `wanted-refs` section. These ids take precedence over the ones seen when obtaining the ref-map as the remote may have changed
+    /// in the mean time, and they are visible in the `remote` of each mapping in [`Outcome::ref_map`].
+    ///
+    /// ### Bundles
+    ///
+    /// If the remote is a bundle file, all of its prerequisites must be present in the object database, or nothing is received at all.
+    ///
     /// ### Deviation
     ///
     /// When **updating refs**, the `git-fetch` docs state that the following:
@@ -71,6 +82,9 @@ where
     /// ### Configuration
     ///
     /// - `gitoxide.userAgent` is read to obtain the application user agent for git servers and for HTTP servers as well.
+    /// - `fetch.uriProtocols` is a comma-separated list of protocols like `https` which, if set, makes us ask the server for
+    ///    `packfile-uris`. Packs offered this way are downloaded and verified after the pack sent by the server was received
+    ///    and the interaction with the server ended, and their outcome is available in [`Outcome::packfile_uris`].
     ///
     #[gix_protocol::maybe_async::maybe_async]
     pub async fn receive<P>(self, mut progress: P, should_interrupt: &AtomicBool) -> Result<Outcome, Error>
@@ -100,11 +114,16 @@ where
             });
         }
 
+        let repo = con.remote.repo;
+        #[cfg(feature = "blocking-network-client")]
+        if let Some(bundle) = &con.bundle {
+            bundle.verify_prerequisites(&repo.objects)?;
+        }
+
         let handshake = &self.ref_map.handshake;
         let protocol_version = handshake.server_protocol_version;
 
         let fetch = gix_protocol::Command::Fetch;
-        let repo = con.remote.repo;
         let fetch_features = {
             let mut f = fetch.default_features(protocol_version, &handshake.capabilities);
             f.push(repo.config.user_agent_tuple());
@@ -125,6 +144,10 @@ where
             }
             arguments.use_include_tag();
         }
+        let packfile_uri_protocols = config::packfile_uri_protocols(repo);
+        if !packfile_uri_protocols.is_empty() && arguments.can_use_packfile_uris() {
+            arguments.use_packfile_uris(packfile_uri_protocols.iter().map(String::as_str));
+        }
         let (shallow_commits, mut shallow_lock) = add_shallow_args(&mut arguments, &self.shallow, repo)?;
 
         if self.ref_map.object_hash != repo.object_hash() {
@@ -165,12 +188,12 @@ where
             negotiate::make_refmapping_ignore_predicate(con.remote.fetch_tags, &self.ref_map),
         )?;
         let mut previous_response = None::<gix_protocol::fetch::Response>;
-        let (mut write_pack_bundle, negotiate) = match &action {
+        let (mut write_pack_bundle, mut packfile_uri_bundles, negotiate) = match &action {
             negotiate::Action::NoChange | negotiate::Action::SkipToRefUpdate => {
                 gix_protocol::indicate_end_of_interaction(&mut con.transport, con.trace)
                     .await
                     .ok();
-                (None, None)
+                (None, Vec::new(), None)
             }
             negotiate::Action::MustNegotiate {
                 remote_ref_target_known,
@@ -253,6 +276,7 @@ where
                 drop(negotiate_span);
 
                 let previous_response = previous_response.expect("knowledge of a pack means a response was received");
+                update_mappings_from_wanted_refs(&mut self.ref_map.mappings, previous_response.wanted_refs());
                 if !previous_response.shallow_updates().is_empty() && shallow_lock.is_none() {
                     let reject_shallow_remote = repo
                         .config
@@ -288,7 +312,7 @@ where
                             let repo = repo.clone();
                             repo.objects
                         })),
-                        options,
+                        options.clone(),
                     )?;
                     // Assure the final flush packet is consumed.
                     #[cfg(feature = "async-network-client")]
@@ -319,12 +343,35 @@ where
                         .ok();
                 }
 
+                // Like `git`, download packs only after the pack sent by the server was read, to not keep the server waiting.
+                let packfile_uri_bundles = if matches!(self.dry_run, fetch::DryRun::No) {
+                    packfile_uris::write_all(
+                        repo,
+                        previous_response.packfile_uris(),
+                        &packfile_uri_protocols,
+                        progress,
+                        should_interrupt,
+                        &options,
+                    )?
+                } else {
+                    Vec::new()
+                };
+
                 if let Some(shallow_lock) = shallow_lock {
                     if !previous_response.shallow_updates().is_empty() {
-                        crate::shallow::write(shallow_lock, shallow_commits, previous_response.shallow_updates())?;
+                        crate::shallow::write(
+                            shallow_lock,
+                            shallow_commits,
+                            previous_response.shallow_updates(),
+                            &repo.objects,
+                        )?;
                     }
                 }
-                (write_pack_bundle, Some(outcome::Negotiate { graph, rounds }))
+                (
+                    write_pack_bundle,
+                    packfile_uri_bundles,
+                    Some(outcome::Negotiate { graph, rounds }),
+                )
             }
         };
 
@@ -341,7 +388,7 @@ where
             self.write_packed_refs,
         )?;
 
-        if let Some(bundle) = write_pack_bundle.as_mut() {
+        for bundle in write_pack_bundle.iter_mut().chain(packfile_uri_bundles.iter_mut()) {
             if !update_refs.edits.is_empty() || bundle.index.num_objects == 0 {
                 if let Some(path) = bundle.keep_path.take() {
                     std::fs::remove_file(&path).map_err(|err| Error::RemovePackKeepFile { path, source: err })?;
@@ -363,6 +410,7 @@ where
                     update_refs,
                 },
             },
+            packfile_uris: packfile_uri_bundles,
         };
         Ok(out)
     }
@@ -373,6 +421,41 @@ fn acquire_shallow_lock(repo: &Repository) -> Result<gix_lock::File, Error> {
         .map_err(Into::into)
 }
 
+/// Set the remote side of all `mappings` that were requested by name to the object ids the server reported in its `wanted-refs`
+/// section, as these are the ones the pack was computed for.
+fn update_mappings_from_wanted_refs(mappings: &mut [fetch::Mapping], wanted_refs: &[WantedRef]) {
+    for WantedRef { id, path } in wanted_refs {
+        for mapping in mappings
+            .iter_mut()
+            .filter(|mapping| mapping.remote.as_name() == Some(path.as_ref()))
+        {
+            let fetch::Source::Ref(remote_ref) = &mut mapping.remote else {
+                continue;
+            };
+            match remote_ref {
+                handshake::Ref::Direct { object, .. } => *object = *id,
+                handshake::Ref::Peeled { full_ref_name, tag, .. } => {
+                    if tag != id {
+                        // We don't know what the new tag peels to, which is why it's just a ref pointing to an object now.
+                        let full_ref_name = std::mem::take(full_ref_name);
+                        *remote_ref = handshake::Ref::Direct {
+                            full_ref_name,
+                            object: *id,
+                        };
+                    }
+                }
+                handshake::Ref::Symbolic { tag, object, .. } => {
+                    if tag.unwrap_or(*object) != *id {
+                        *tag = None;
+                        *object = *id;
+                    }
+                }
+                handshake::Ref::Unborn { .. } => {}
+            }
+        }
+    }
+}
+
 fn add_shallow_args(
     args: &mut Arguments,
     shallow: &Shallow,
@@ -18,6 +18,9 @@ pub struct Connection<'a, 'repo, T> {
     pub(crate) transport_options: Option<Box<dyn std::any::Any>>,
     pub(crate) transport: T,
     pub(crate) trace: bool,
+    /// The header of the bundle file we are connected to, if the remote is a bundle.
+    #[cfg(feature = "blocking-network-client")]
+    pub(crate) bundle: Option<gix_bundle::Header>,
 }
 
 mod access;
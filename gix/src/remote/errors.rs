@@ -25,6 +25,22 @@ pub mod find {
         },
         #[error(transparent)]
         Init(#[from] remote::init::Error),
+        #[error("Could not read the remote definition at {path:?}")]
+        ReadFile {
+            path: std::path::PathBuf,
+            source: std::io::Error,
+        },
+        #[error("The url in the remote definition at {path:?} was invalid")]
+        FileUrl {
+            path: std::path::PathBuf,
+            source: gix_url::parse::Error,
+        },
+        #[error("The ref-spec {spec:?} in the remote definition at {path:?} was invalid")]
+        FileRefSpec {
+            path: std::path::PathBuf,
+            spec: BString,
+            source: gix_refspec::parse::Error,
+        },
     }
 
     ///
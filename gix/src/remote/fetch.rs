@@ -12,9 +12,15 @@ pub mod negotiate {
     };
 }
 
+#[cfg(any(
+    feature = "async-network-client-async-std",
+    feature = "async-http-transport-reqwest",
+    feature = "blocking-network-client"
+))]
+pub(crate) use super::connection::fetch::config::{index_threads, pack_index_version};
 #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
 pub use super::connection::fetch::{
-    outcome, prepare, refs, Error, Outcome, Prepare, ProgressId, RefLogMessage, Status,
+    download, outcome, prepare, refs, Error, Outcome, Prepare, ProgressId, RefLogMessage, Status,
 };
 
 /// If `Yes`, don't really make changes but do as much as possible to get an idea of what would be done.
@@ -0,0 +1,38 @@
+use crate::bstr::BString;
+
+/// The error returned by [`Repository::fetch_remote_group()`](crate::Repository::fetch_remote_group()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Neither a remote group nor a remote named {name:?} is configured")]
+    NotFound { name: BString },
+    #[error(transparent)]
+    Parallel(#[from] crate::config::unsigned_integer::Error),
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod fetch {
+    /// The error returned when fetching a single remote of a group.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindRemote(#[from] crate::remote::find::existing::Error),
+        #[error(transparent)]
+        Connect(#[from] crate::remote::connect::Error),
+        #[error(transparent)]
+        PrepareFetch(#[from] crate::remote::fetch::prepare::Error),
+        #[error(transparent)]
+        Fetch(#[from] crate::remote::fetch::Error),
+    }
+}
+
+/// The outcome of fetching a single remote of a group with [`Repository::fetch_remote_group()`](crate::Repository::fetch_remote_group()).
+#[derive(Debug)]
+pub struct Outcome {
+    /// The name of the remote that was fetched.
+    pub remote_name: BString,
+    /// The result of the fetch operation, which fails independently of the fetches of other remotes in the group.
+    pub result: Result<crate::remote::fetch::Outcome, fetch::Error>,
+}
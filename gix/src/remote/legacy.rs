@@ -0,0 +1,64 @@
+//! Support for remotes defined in files below `$GIT_DIR/remotes/` and `$GIT_DIR/branches/`, which predate the configuration
+//! of remotes in `git-config` files.
+use crate::bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// The values of a remote as defined in one of the legacy files.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Definition {
+    pub url: Option<BString>,
+    pub fetch_specs: Vec<BString>,
+    pub push_specs: Vec<BString>,
+}
+
+/// Parse the `content` of a `$GIT_DIR/remotes/<name>` file, which has lines like `URL: <url>`, `Pull: <refspec>` and `Push: <refspec>`.
+///
+/// Like git, only the first url is used and unknown lines are ignored.
+pub(crate) fn from_remotes_file(content: &[u8]) -> Definition {
+    let mut out = Definition::default();
+    for line in content.lines() {
+        let line = line.trim_end();
+        if let Some(url) = line.strip_prefix(b"URL:") {
+            if out.url.is_none() {
+                out.url = Some(url.trim_start().into());
+            }
+        } else if let Some(spec) = line.strip_prefix(b"Pull:") {
+            out.fetch_specs.push(spec.trim_start().into());
+        } else if let Some(spec) = line.strip_prefix(b"Push:") {
+            out.push_specs.push(spec.trim_start().into());
+        }
+    }
+    out
+}
+
+/// Parse the `content` of a `$GIT_DIR/branches/<remote_name>` file, which is a single line like `<url>#<branch>`.
+/// If `#<branch>` is omitted, `default_branch` is used instead.
+///
+/// The branch is fetched into the local branch named after the remote, and `HEAD` is pushed to it.
+/// Return `None` if the file is empty.
+pub(crate) fn from_branches_file(content: &[u8], remote_name: &BStr, default_branch: &BStr) -> Option<Definition> {
+    let line = content.lines().next()?.trim();
+    if line.is_empty() {
+        return None;
+    }
+    let (url, branch) = match line.find_byte(b'#') {
+        Some(pos) => (&line[..pos], line[pos + 1..].as_bstr()),
+        None => (line, default_branch),
+    };
+    let mut fetch_spec = BString::from("refs/heads/");
+    fetch_spec.push_str(branch);
+    fetch_spec.push_str(":refs/heads/");
+    fetch_spec.push_str(remote_name);
+    let mut push_spec = BString::from("HEAD:refs/heads/");
+    push_spec.push_str(branch);
+    Some(Definition {
+        url: Some(url.into()),
+        fetch_specs: vec![fetch_spec],
+        push_specs: vec![push_spec],
+    })
+}
+
+/// Return `true` if `name` can be used to find a legacy file for a remote of the same name, which is the case if it
+/// isn't empty, `.` or `..`, and doesn't contain a `/`, just like git.
+pub(crate) fn is_valid_name(name: &BStr) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(&b'/')
+}
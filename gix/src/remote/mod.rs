@@ -40,6 +40,12 @@ mod build;
 mod errors;
 pub use errors::find;
 
+pub(crate) mod legacy;
+
+///
+#[cfg(feature = "blocking-network-client")]
+pub mod group;
+
 ///
 #[allow(clippy::empty_docs)]
 pub mod init;
@@ -0,0 +1,496 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use gix_diff::{blob::unified_diff::header::Kind, patch};
+use gix_hash::ObjectId;
+use gix_object::tree::{EntryKind, EntryMode};
+
+use crate::{
+    apply,
+    apply::{Error, FileOutcome, Status, Target},
+    bstr::{BStr, BString, ByteSlice},
+    Repository,
+};
+
+/// A version of a file, as it would be stored in the index or in a tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Version {
+    data: Vec<u8>,
+    mode: EntryKind,
+}
+
+/// The result of a three-way merge between the base of a patch with the patch applied, and the current version.
+struct ThreeWay {
+    base: Vec<u8>,
+    theirs: Vec<u8>,
+    merged: patch::merge::Outcome,
+}
+
+/// The versions of a file that couldn't be merged without conflicts.
+struct Conflict {
+    base: Version,
+    ours: Version,
+    theirs: Version,
+}
+
+impl Repository {
+    /// Apply `patch`, which may contain changes to multiple files as produced by `git diff` or `git format-patch`,
+    /// to the target configured in `options`, similar to `git apply`.
+    ///
+    /// The patch is applied entirely or not at all, unless [rejects are allowed](apply::Options::allow_rejects).
+    /// Note that files are read from and written to the worktree as is, without conversions by filters.
+    pub fn apply_patch(&self, patch: &[u8], options: apply::Options) -> Result<apply::Outcome, apply::Error> {
+        let files = patch::parse(patch)?;
+        if files.is_empty() {
+            return Err(Error::EmptyPatch);
+        }
+        let workdir = match options.target {
+            Target::Worktree | Target::IndexAndWorktree => Some(self.work_dir().ok_or(Error::MissingWorktree)?),
+            Target::Index | Target::Tree(_) => None,
+        };
+        let mut index = match options.target {
+            Target::Worktree => None,
+            Target::Index | Target::IndexAndWorktree => Some(if self.index_path().is_file() {
+                self.open_index()?
+            } else {
+                gix_index::File::from_state(gix_index::State::new(self.object_hash()), self.index_path())
+            }),
+            Target::Tree(id) => Some(self.index_from_tree(&id)?),
+        };
+        let protect = self.config.protect_options()?;
+        let reverse = options.hunks.reverse;
+
+        let mut changes = BTreeMap::<BString, Option<Version>>::new();
+        let mut conflicts = BTreeMap::<BString, Conflict>::new();
+        let mut outcomes = Vec::with_capacity(files.len());
+        for file in &files {
+            let (old_path, new_path, new_mode, base_id) = if reverse {
+                (&file.new_path, &file.old_path, file.old_mode, &file.new_id)
+            } else {
+                (&file.old_path, &file.new_path, file.new_mode, &file.old_id)
+            };
+            for path in old_path.iter().chain(new_path.iter()) {
+                validate_path(path.as_ref(), workdir, protect)?;
+            }
+            let read = |path: &BStr| read(self, path, options.target, workdir, index.as_ref(), &changes);
+
+            let current = match old_path {
+                Some(path) => Some(read(path.as_ref())?.ok_or_else(|| Error::MissingFile { path: path.clone() })?),
+                None => None,
+            };
+            if let Some(path) = new_path.as_ref().filter(|path| Some(*path) != old_path.as_ref()) {
+                if read(path.as_ref())?.is_some() {
+                    return Err(Error::AlreadyExists { path: path.clone() });
+                }
+            }
+
+            let mode = new_mode
+                .or(current.as_ref().map(|version| version.mode))
+                .unwrap_or(EntryKind::Blob);
+            let source = current.as_ref().map_or(&[][..], |version| version.data.as_slice());
+            let (data, status) = if let Some(binary) = &file.binary {
+                // Like `git`, verify the preimage if it's fully known as deltas may otherwise produce garbage.
+                if let Some(expected) = base_id.as_ref().and_then(|id| ObjectId::from_hex(id).ok()) {
+                    let actual = gix_object::compute_hash(self.object_hash(), gix_object::Kind::Blob, source);
+                    if actual != expected && !(expected.is_null() && source.is_empty()) {
+                        return Err(Error::BinaryPreimageMismatch {
+                            path: file.path().clone(),
+                            expected,
+                            actual,
+                        });
+                    }
+                }
+                let data = patch::apply::binary(source, binary, reverse).map_err(|err| Error::Binary {
+                    path: file.path().clone(),
+                    source: err,
+                })?;
+                (data, Status::Applied)
+            } else if file.is_binary_without_data {
+                return Err(Error::BinaryWithoutData {
+                    path: file.path().clone(),
+                });
+            } else {
+                let outcome = patch::apply::hunks(source, &file.hunks, options.hunks);
+                let merged = if !outcome.is_complete() && options.three_way {
+                    self.merge_with_base(base_id.as_ref(), source, &file.hunks, options.hunks)?
+                } else {
+                    None
+                };
+                match merged {
+                    _ if outcome.is_complete() => (outcome.data, Status::Applied),
+                    Some(ThreeWay { base, theirs, merged }) => {
+                        if merged.num_conflicts > 0 {
+                            if matches!(options.target, Target::Tree(_)) {
+                                return Err(Error::Conflict {
+                                    path: file.path().clone(),
+                                });
+                            }
+                            if let (Some(path), Some(current)) = (new_path, &current) {
+                                conflicts.insert(
+                                    path.clone(),
+                                    Conflict {
+                                        base: Version { data: base, mode },
+                                        ours: current.clone(),
+                                        theirs: Version { data: theirs, mode },
+                                    },
+                                );
+                            }
+                        }
+                        (
+                            merged.data,
+                            Status::Merged {
+                                num_conflicts: merged.num_conflicts,
+                            },
+                        )
+                    }
+                    None if options.allow_rejects => {
+                        let rejected_hunks = outcome.rejected_hunks();
+                        (outcome.data, Status::PartiallyApplied { rejected_hunks })
+                    }
+                    None => {
+                        return Err(Error::Rejected {
+                            path: file.path().clone(),
+                            hunks: outcome.rejected_hunks(),
+                        })
+                    }
+                }
+            };
+
+            // In reverse, a copy is undone by removing the copy, which is what a rename does as well.
+            let keeps_source = matches!(file.kind, Kind::Copy { .. }) && !reverse;
+            if let Some(path) = old_path.as_ref().filter(|path| Some(*path) != new_path.as_ref()) {
+                if !keeps_source {
+                    changes.insert(path.clone(), None);
+                }
+            }
+            if let Some(path) = new_path {
+                changes.insert(path.clone(), Some(Version { data, mode }));
+            }
+            outcomes.push(FileOutcome {
+                old_path: old_path.clone(),
+                new_path: new_path.clone(),
+                status,
+            });
+        }
+
+        if options.dry_run {
+            return Ok(apply::Outcome {
+                files: outcomes,
+                tree: None,
+            });
+        }
+
+        if let Some(workdir) = workdir {
+            for (path, version) in &changes {
+                let path = workdir.join(gix_path::from_bstr(path.as_bstr()));
+                match version {
+                    None => remove_from_worktree(&path, workdir),
+                    Some(version) => write_to_worktree(&path, version),
+                }
+                .map_err(|err| Error::Io { path, source: err })?;
+            }
+        }
+
+        let mut tree = None;
+        if let Some(index) = index.as_mut() {
+            index.remove_entries(|_, path, _| changes.contains_key(path) || conflicts.contains_key(path));
+            for (path, version) in &changes {
+                let Some(version) = version.as_ref().filter(|_| !conflicts.contains_key(path)) else {
+                    continue;
+                };
+                let id = self.write_blob(&version.data)?.detach();
+                let stat = match workdir {
+                    Some(workdir) => {
+                        let worktree_path = workdir.join(gix_path::from_bstr(path.as_bstr()));
+                        let metadata =
+                            gix_index::fs::Metadata::from_path_no_follow(&worktree_path).map_err(|err| Error::Io {
+                                path: worktree_path,
+                                source: err,
+                            })?;
+                        gix_index::entry::Stat::from_fs(&metadata).map_err(|err| Error::Stat {
+                            path: path.clone(),
+                            source: err,
+                        })?
+                    }
+                    None => Default::default(),
+                };
+                index.dangerously_push_entry(
+                    stat,
+                    id,
+                    gix_index::entry::Flags::empty(),
+                    EntryMode::from(version.mode).into(),
+                    path.as_ref(),
+                );
+            }
+            for (path, conflict) in &conflicts {
+                for (stage, version) in [(1, &conflict.base), (2, &conflict.ours), (3, &conflict.theirs)] {
+                    let id = self.write_blob(&version.data)?.detach();
+                    index.dangerously_push_entry(
+                        Default::default(),
+                        id,
+                        gix_index::entry::Flags::from_bits_retain(stage << 12),
+                        EntryMode::from(version.mode).into(),
+                        path.as_ref(),
+                    );
+                }
+            }
+            index.sort_entries();
+
+            if let Target::Tree(_) = options.target {
+                let entries: Vec<_> = index
+                    .entries()
+                    .iter()
+                    .filter_map(|entry| {
+                        entry
+                            .mode
+                            .to_tree_entry_mode()
+                            .map(|mode| (entry.path(index), mode, entry.id))
+                    })
+                    .collect();
+                tree = Some(self.write_tree_from_entries(&entries)?);
+            } else {
+                index.write(gix_index::write::Options {
+                    extensions: gix_index::write::Extensions::Given {
+                        // The tree-cache would be outdated after our changes.
+                        tree_cache: false,
+                        end_of_index_entry: true,
+                        untracked_cache: false,
+                        fs_monitor: false,
+                    },
+                    skip_hash: false,
+                })?;
+            }
+        }
+
+        Ok(apply::Outcome { files: outcomes, tree })
+    }
+
+    /// Apply `hunks` to the blob with the possibly abbreviated `base_id` and merge the result with `ours` as its
+    /// changed version, returning the base and its patched version along with the merge outcome, or `None` if that's impossible.
+    fn merge_with_base(
+        &self,
+        base_id: Option<&BString>,
+        ours: &[u8],
+        hunks: &[patch::Hunk],
+        options: patch::apply::Options,
+    ) -> Result<Option<ThreeWay>, Error> {
+        let Some(prefix) = base_id
+            .and_then(|id| id.to_str().ok())
+            .and_then(|id| gix_hash::Prefix::from_hex(id).ok())
+        else {
+            return Ok(None);
+        };
+        let Some(Ok(id)) = self.objects.lookup_prefix(prefix, None)? else {
+            return Ok(None);
+        };
+        let base = self.find_object(id)?.detach().data;
+        let theirs = patch::apply::hunks(&base, hunks, options);
+        if !theirs.is_complete() {
+            return Ok(None);
+        }
+        let merged = patch::merge::three_way(
+            &base,
+            ours,
+            &theirs.data,
+            Default::default(),
+            self.config.diff_algorithm()?,
+        );
+        Ok(Some(ThreeWay {
+            base,
+            theirs: theirs.data,
+            merged,
+        }))
+    }
+
+    /// Write a tree from `entries` with paths relative to it, sorted like they are in the index.
+    fn write_tree_from_entries(&self, entries: &[(&BStr, EntryMode, ObjectId)]) -> Result<ObjectId, Error> {
+        let mut tree = gix_object::Tree { entries: Vec::new() };
+        let mut idx = 0;
+        while idx < entries.len() {
+            let (path, mode, id) = entries[idx];
+            match path.find_byte(b'/') {
+                None => {
+                    tree.entries.push(gix_object::tree::Entry {
+                        mode,
+                        filename: path.into(),
+                        oid: id,
+                    });
+                    idx += 1;
+                }
+                Some(pos) => {
+                    let dir = &path[..=pos];
+                    let end = idx
+                        + entries[idx..]
+                            .iter()
+                            .take_while(|(path, _, _)| path.starts_with(dir))
+                            .count();
+                    let children: Vec<_> = entries[idx..end]
+                        .iter()
+                        .map(|(path, mode, id)| (path[pos + 1..].as_bstr(), *mode, *id))
+                        .collect();
+                    tree.entries.push(gix_object::tree::Entry {
+                        mode: EntryKind::Tree.into(),
+                        filename: path[..pos].into(),
+                        oid: self.write_tree_from_entries(&children)?,
+                    });
+                    idx = end;
+                }
+            }
+        }
+        tree.entries.sort();
+        Ok(self.write_object(&tree)?.detach())
+    }
+}
+
+/// Read the current version of the file at `path`, taking previous `changes` into account.
+fn read(
+    repo: &Repository,
+    path: &BStr,
+    target: Target,
+    workdir: Option<&Path>,
+    index: Option<&gix_index::File>,
+    changes: &BTreeMap<BString, Option<Version>>,
+) -> Result<Option<Version>, Error> {
+    if let Some(version) = changes.get(path) {
+        return Ok(version.clone());
+    }
+    let from_index = match index {
+        Some(index) => match index.entry_by_path(path) {
+            Some(entry) => Some(Version {
+                data: repo.find_object(entry.id)?.detach().data,
+                mode: entry
+                    .mode
+                    .to_tree_entry_mode()
+                    .map_or(EntryKind::Blob, |mode| mode.kind()),
+            }),
+            None => None,
+        },
+        None => None,
+    };
+    let from_worktree = match workdir {
+        Some(workdir) => {
+            let path = workdir.join(gix_path::from_bstr(path));
+            read_from_worktree(&path).map_err(|err| Error::Io { path, source: err })?
+        }
+        None => None,
+    };
+    Ok(match target {
+        Target::Worktree => from_worktree,
+        Target::Index | Target::Tree(_) => from_index,
+        Target::IndexAndWorktree => {
+            if from_index != from_worktree {
+                return Err(Error::WorktreeMismatch { path: path.to_owned() });
+            }
+            from_index
+        }
+    })
+}
+
+/// Assure `path` can't be used to write outside of the repository, also through symlinks in the `workdir`.
+fn validate_path(
+    path: &BStr,
+    workdir: Option<&Path>,
+    protect: gix_validate::path::component::Options,
+) -> Result<(), Error> {
+    let invalid = || Error::InvalidPath { path: path.to_owned() };
+    let mut leading_dir = workdir.map(Path::to_owned);
+    let mut components = path.split_str("/").peekable();
+    while let Some(component) = components.next() {
+        if component == b"." || component == b".." {
+            return Err(invalid());
+        }
+        gix_validate::path::component(component.as_bstr(), None, protect).map_err(|_| invalid())?;
+        if let Some(dir) = leading_dir.as_mut().filter(|_| components.peek().is_some()) {
+            dir.push(gix_path::from_bstr(component.as_bstr()));
+            if std::fs::symlink_metadata(&dir).map_or(false, |meta| meta.is_symlink()) {
+                return Err(invalid());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_from_worktree(path: &Path) -> std::io::Result<Option<Version>> {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    Ok(Some(if metadata.is_symlink() {
+        Version {
+            data: gix_path::into_bstr(std::fs::read_link(path)?).into_owned().into(),
+            mode: EntryKind::Link,
+        }
+    } else if metadata.is_dir() {
+        return Ok(None);
+    } else {
+        Version {
+            data: std::fs::read(path)?,
+            mode: if gix_fs::is_executable(&metadata) {
+                EntryKind::BlobExecutable
+            } else {
+                EntryKind::Blob
+            },
+        }
+    }))
+}
+
+fn write_to_worktree(path: &Path, version: &Version) -> std::io::Result<()> {
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) => {
+            if metadata.is_symlink() || version.mode == EntryKind::Link {
+                std::fs::remove_file(path)?;
+            }
+        }
+        Err(_) => {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+    }
+    if version.mode == EntryKind::Link {
+        return gix_fs::symlink::create(&gix_path::from_bstr(version.data.as_bstr()), path);
+    }
+    std::fs::write(path, &version.data)?;
+    set_executable(path, version.mode == EntryKind::BlobExecutable)
+}
+
+/// Remove the file at `path` and all directories that are empty afterwards, up to `workdir`.
+fn remove_from_worktree(path: &Path, workdir: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err),
+        _ => {}
+    }
+    let mut dir: Option<PathBuf> = path.parent().map(ToOwned::to_owned);
+    while let Some(current) = dir.filter(|dir| dir.as_path() != workdir && dir.starts_with(workdir)) {
+        if std::fs::remove_dir(&current).is_err() {
+            break;
+        }
+        dir = current.parent().map(ToOwned::to_owned);
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path, executable: bool) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = std::fs::metadata(path)?.permissions();
+    let mode = permissions.mode();
+    let new_mode = if executable {
+        mode | ((mode & 0o444) >> 2)
+    } else {
+        mode & !0o111
+    };
+    if new_mode != mode {
+        permissions.set_mode(new_mode);
+        std::fs::set_permissions(path, permissions)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path, _executable: bool) -> std::io::Result<()> {
+    Ok(())
+}
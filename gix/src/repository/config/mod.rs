@@ -4,11 +4,55 @@ use crate::{bstr::ByteSlice, config};
 
 /// General Configuration
 impl crate::Repository {
-    /// Return a snapshot of the configuration as seen upon opening the repository.
+    /// Return a snapshot of the configuration as seen upon opening the repository, or when it was last
+    /// [refreshed](Self::refresh_config()).
     pub fn config_snapshot(&self) -> config::Snapshot<'_> {
         config::Snapshot { repo: self }
     }
 
+    /// If [auto-refresh](crate::open::Options::auto_refresh_config()) is enabled, reload all configuration, including includes,
+    /// if any of the files it was assembled from changed, appeared or disappeared on disk, and re-read all cached values from it.
+    /// Return `true` if the configuration was replaced, which is always `false` if auto-refresh is disabled.
+    ///
+    /// Configuration is only reloaded once for all instances of this repository, with each instance adopting the most recent version
+    /// of it when calling this method. This happens automatically when obtaining a thread-local instance from a
+    /// [`ThreadSafeRepository`](crate::ThreadSafeRepository).
+    ///
+    /// Note that in-memory changes made with [`config_snapshot_mut()`](Self::config_snapshot_mut()) are lost if the configuration
+    /// is replaced.
+    pub fn refresh_config(&mut self) -> Result<bool, config::Error> {
+        let (Some(storage), Some(current)) = (self.config.files_storage.clone(), self.config.files.as_ref()) else {
+            return Ok(false);
+        };
+        // Compare with the files as seen by the most recent snapshot, which another instance might have loaded already,
+        // so the configuration is only reloaded if the files changed since then.
+        let modified = config::cache::Files::latest(&storage)
+            .as_deref()
+            .unwrap_or(current)
+            .modification_time();
+        let recent = storage
+            .recent_snapshot(
+                || Some(modified),
+                || {
+                    self.load_config().map(|cache| {
+                        Some(config::cache::Files::new(
+                            cache.resolved,
+                            self.git_dir(),
+                            self.common_dir(),
+                            &self.options.permissions,
+                        ))
+                    })
+                },
+            )?
+            .expect("always present as a modification time is always provided");
+        if gix_features::threading::OwnShared::ptr_eq(&recent, current) {
+            return Ok(false);
+        }
+        self.reread_values_and_clear_caches_replacing_config(recent.config.clone())?;
+        self.config.files = Some(recent);
+        Ok(true)
+    }
+
     /// Return a mutable snapshot of the configuration as seen upon opening the repository, starting a transaction.
     /// When the returned instance is dropped, it is applied in full, even if the reason for the drop is an error.
     ///
@@ -22,6 +66,37 @@ impl crate::Repository {
         }
     }
 
+    /// Lock the configuration file of `source` for modification and load it, for changes to be written back
+    /// with [`FileMut::commit()`](config::FileMut::commit()), which also makes them visible to this instance.
+    ///
+    /// Supported sources are [`Local`](config::Source::Local) and [`Worktree`](config::Source::Worktree) for the files
+    /// in our repository, as well as [`User`](config::Source::User), [`Git`](config::Source::Git) and [`System`](config::Source::System)
+    /// for the files shared with other repositories, at locations that respect the environment like `git` does.
+    /// The file doesn't have to exist yet, and will be created on commit if needed.
+    ///
+    /// Note that the lock is released without changing the file if the returned instance is dropped without committing.
+    pub fn config_file_mut(&mut self, source: config::Source) -> Result<config::FileMut<'_>, config::file_mut::Error> {
+        use config::Source;
+        let path = match source {
+            Source::Local => self.common_dir().join("config"),
+            Source::Worktree => self.git_dir().join("config.worktree"),
+            Source::User | Source::Git | Source::System => source
+                .storage_location(&mut config::Cache::make_source_env(self.options.permissions.env))
+                .ok_or(config::file_mut::Error::StorageLocationUnknown(source))?
+                .into_owned(),
+            Source::GitInstallation | Source::Env | Source::Cli | Source::Api | Source::EnvOverride => {
+                return Err(config::file_mut::Error::UnsupportedSource(source))
+            }
+        };
+        let lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        let file = if path.is_file() {
+            gix_config::File::from_path_no_includes(path, source)?
+        } else {
+            gix_config::File::new(gix_config::file::Metadata::from(source).at(path))
+        };
+        Ok(config::FileMut { repo: self, file, lock })
+    }
+
     /// Return filesystem options as retrieved from the repository configuration.
     ///
     /// Note that these values have not been [probed](gix_fs::Capabilities::probe()).
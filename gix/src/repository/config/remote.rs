@@ -1,4 +1,4 @@
-use crate::bstr::BStr;
+use crate::bstr::{BStr, BString, ByteSlice};
 use std::{borrow::Cow, collections::BTreeSet};
 
 use crate::config::tree::{Remote, Section};
@@ -21,6 +21,22 @@ impl crate::Repository {
             .unwrap_or_default()
     }
 
+    /// Return the names of the remotes in the remote group `name`, as configured by the whitespace-separated values of
+    /// all trusted `remotes.<name>` keys, in order and without duplicates, or `None` if no such group is configured.
+    pub fn remote_group<'a>(&self, name: impl Into<&'a BStr>) -> Option<Vec<BString>> {
+        let values = self.config.resolved.strings_filter(
+            format!("remotes.{}", name.into()).as_str(),
+            &mut self.filter_config_section(),
+        )?;
+        let mut names = Vec::<BString>::new();
+        for remote_name in values.iter().flat_map(|value| value.fields()) {
+            if !names.iter().any(|existing| existing == remote_name) {
+                names.push(remote_name.into());
+            }
+        }
+        Some(names)
+    }
+
     /// Obtain the branch-independent name for a remote for use in the given `direction`, or `None` if it could not be determined.
     ///
     /// For _fetching_, use the only configured remote, or default to `origin` if it exists.
@@ -19,7 +19,8 @@ impl crate::Repository {
     #[cfg_attr(
         not(any(
             feature = "blocking-http-transport-reqwest",
-            feature = "blocking-http-transport-curl"
+            feature = "blocking-http-transport-curl",
+            feature = "async-http-transport-reqwest"
         )),
         allow(unused_variables)
     )]
@@ -29,21 +30,25 @@ impl crate::Repository {
         url: impl Into<&'a BStr>,
         remote_name: Option<&BStr>,
     ) -> Result<Option<Box<dyn Any>>, crate::config::transport::Error> {
-        let url = gix_url::parse(url.into())?;
+        let url = url.into();
+        let url_to_match = gix_config::url_match::Url::from_bytes(url).ok();
+        let url = gix_url::parse(url)?;
         use gix_url::Scheme::*;
 
         match &url.scheme {
             Http | Https => {
                 #[cfg(not(any(
                     feature = "blocking-http-transport-reqwest",
-                    feature = "blocking-http-transport-curl"
+                    feature = "blocking-http-transport-curl",
+                    feature = "async-http-transport-reqwest"
                 )))]
                 {
                     Ok(None)
                 }
                 #[cfg(any(
                     feature = "blocking-http-transport-reqwest",
-                    feature = "blocking-http-transport-curl"
+                    feature = "blocking-http-transport-curl",
+                    feature = "async-http-transport-reqwest"
                 ))]
                 {
                     use std::{
@@ -100,10 +105,49 @@ impl crate::Repository {
                         Ok(value)
                     }
 
+                    /// Obtain the value at `key` from the `http.<url>` section that matches `url` best, or from `http` as fallback.
+                    fn url_matching_string<'c>(
+                        config: &'c gix_config::File<'static>,
+                        key: &str,
+                        url: Option<&gix_config::url_match::Url>,
+                        filter: &mut gix_config::file::MetadataFilter,
+                    ) -> Option<Cow<'c, BStr>> {
+                        match url {
+                            Some(url) => config.url_matching_string_filter(key, url, filter),
+                            None => config.string_filter(key, filter),
+                        }
+                    }
+
+                    fn url_matching_boolean(
+                        config: &gix_config::File<'static>,
+                        key: &str,
+                        url: Option<&gix_config::url_match::Url>,
+                        filter: &mut gix_config::file::MetadataFilter,
+                    ) -> Option<Result<bool, gix_config::value::Error>> {
+                        match url {
+                            Some(url) => config.url_matching_boolean_filter(key, url, filter),
+                            None => config.boolean_filter(key, filter),
+                        }
+                    }
+
+                    fn url_matching_integer(
+                        config: &gix_config::File<'static>,
+                        key: &str,
+                        url: Option<&gix_config::url_match::Url>,
+                        filter: &mut gix_config::file::MetadataFilter,
+                    ) -> Option<Result<i64, gix_config::value::Error>> {
+                        let value = url_matching_string(config, key, url, filter)?;
+                        Some(gix_config::Integer::try_from(value.as_ref()).and_then(|int| {
+                            int.to_decimal()
+                                .ok_or_else(|| gix_config::value::Error::new("Integer overflow", value.into_owned()))
+                        }))
+                    }
+
                     fn ssl_version(
                         config: &gix_config::File<'static>,
                         key_str: &'static str,
                         key: &'static config::tree::http::SslVersion,
+                        url: Option<&gix_config::url_match::Url>,
                         mut filter: fn(&gix_config::file::Metadata) -> bool,
                         lenient: bool,
                     ) -> Result<Option<SslVersion>, config::transport::Error> {
@@ -112,8 +156,12 @@ impl crate::Repository {
                             key.logical_name(),
                             "BUG: hardcoded and generated key names must match"
                         );
-                        config
-                            .string_filter(key_str, &mut filter)
+                        let value = if key_str.starts_with("http.") {
+                            url_matching_string(config, key_str, url, &mut filter)
+                        } else {
+                            config.string_filter(key_str, &mut filter)
+                        };
+                        value
                             .filter(|v| !v.is_empty())
                             .map(|v| {
                                 key.try_into_ssl_version(v)
@@ -145,18 +193,21 @@ impl crate::Repository {
                     let config = &self.config.resolved;
                     let mut trusted_only = self.filter_config_section();
                     let lenient = self.config.lenient_config;
+                    let url_to_match = url_to_match.as_ref();
                     opts.extra_headers = {
                         let key = "http.extraHeader";
                         debug_assert_eq!(key, &config::tree::Http::EXTRA_HEADER.logical_name());
-                        config
-                            .strings_filter(key, &mut trusted_only)
-                            .map(|values| config::tree::Http::EXTRA_HEADER.try_into_extra_header(values))
-                            .transpose()
-                            .map_err(|err| config::transport::Error::IllformedUtf8 {
-                                source: err,
-                                key: Cow::Borrowed(key.into()),
-                            })?
-                            .unwrap_or_default()
+                        match url_to_match {
+                            Some(url) => config.url_matching_strings_filter(key, url, &mut trusted_only),
+                            None => config.strings_filter(key, &mut trusted_only),
+                        }
+                        .map(|values| config::tree::Http::EXTRA_HEADER.try_into_extra_header(values))
+                        .transpose()
+                        .map_err(|err| config::transport::Error::IllformedUtf8 {
+                            source: err,
+                            key: Cow::Borrowed(key.into()),
+                        })?
+                        .unwrap_or_default()
                     };
 
                     opts.follow_redirects = {
@@ -164,10 +215,9 @@ impl crate::Repository {
 
                         config::tree::Http::FOLLOW_REDIRECTS
                             .try_into_follow_redirects(
-                                config.string_filter(key, &mut trusted_only).unwrap_or_default(),
+                                url_matching_string(config, key, url_to_match, &mut trusted_only).unwrap_or_default(),
                                 || {
-                                    config
-                                        .boolean_filter(key, &mut trusted_only)
+                                    url_matching_boolean(config, key, url_to_match, &mut trusted_only)
                                         .transpose()
                                         .with_leniency(lenient)
                                 },
@@ -175,20 +225,20 @@ impl crate::Repository {
                             .map_err(config::transport::http::Error::InvalidFollowRedirects)?
                     };
 
-                    opts.low_speed_time_seconds = config
-                        .integer_filter("http.lowSpeedTime", &mut trusted_only)
-                        .map(|value| config::tree::Http::LOW_SPEED_TIME.try_into_u64(value))
-                        .transpose()
-                        .with_leniency(lenient)
-                        .map_err(config::transport::http::Error::from)?
-                        .unwrap_or_default();
-                    opts.low_speed_limit_bytes_per_second = config
-                        .integer_filter("http.lowSpeedLimit", &mut trusted_only)
-                        .map(|value| config::tree::Http::LOW_SPEED_LIMIT.try_into_u32(value))
-                        .transpose()
-                        .with_leniency(lenient)
-                        .map_err(config::transport::http::Error::from)?
-                        .unwrap_or_default();
+                    opts.low_speed_time_seconds =
+                        url_matching_integer(config, "http.lowSpeedTime", url_to_match, &mut trusted_only)
+                            .map(|value| config::tree::Http::LOW_SPEED_TIME.try_into_u64(value))
+                            .transpose()
+                            .with_leniency(lenient)
+                            .map_err(config::transport::http::Error::from)?
+                            .unwrap_or_default();
+                    opts.low_speed_limit_bytes_per_second =
+                        url_matching_integer(config, "http.lowSpeedLimit", url_to_match, &mut trusted_only)
+                            .map(|value| config::tree::Http::LOW_SPEED_LIMIT.try_into_u32(value))
+                            .transpose()
+                            .with_leniency(lenient)
+                            .map_err(config::transport::http::Error::from)?
+                            .unwrap_or_default();
                     opts.proxy = proxy(
                         remote_name
                             .and_then(|name| {
@@ -202,8 +252,7 @@ impl crate::Repository {
                             .or_else(|| {
                                 let key = "http.proxy";
                                 debug_assert_eq!(key, config::tree::Http::PROXY.logical_name());
-                                let http_proxy = config
-                                    .string_filter(key, &mut trusted_only)
+                                let http_proxy = url_matching_string(config, key, url_to_match, &mut trusted_only)
                                     .map(|v| (v, cow_bstr(key), &config::tree::Http::PROXY))
                                     .or_else(|| {
                                         let key = "gitoxide.http.proxy";
@@ -266,7 +315,7 @@ impl crate::Repository {
                                     .or_else(|| {
                                         let key = "http.proxyAuthMethod";
                                         debug_assert_eq!(key, config::tree::Http::PROXY_AUTH_METHOD.logical_name());
-                                        config.string_filter(key, &mut trusted_only).map(|v| {
+                                        url_matching_string(config, key, url_to_match, &mut trusted_only).map(|v| {
                                             (v, Cow::Borrowed(key.into()), &config::tree::Http::PROXY_AUTH_METHOD)
                                         })
                                     })
@@ -304,8 +353,7 @@ impl crate::Repository {
                     };
                     {
                         let key = "http.userAgent";
-                        opts.user_agent = config
-                            .string_filter(key, &mut trusted_only)
+                        opts.user_agent = url_matching_string(config, key, url_to_match, &mut trusted_only)
                             .and_then(|v| {
                                 try_cow_to_string(
                                     v,
@@ -321,8 +369,7 @@ impl crate::Repository {
 
                     {
                         let key = "http.version";
-                        opts.http_version = config
-                            .string_filter(key, &mut trusted_only)
+                        opts.http_version = url_matching_string(config, key, url_to_match, &mut trusted_only)
                             .map(|v| {
                                 config::tree::Http::VERSION
                                     .try_into_http_version(v)
@@ -340,8 +387,7 @@ impl crate::Repository {
 
                     let may_use_cainfo = {
                         let key = "http.schannelUseSSLCAInfo";
-                        config
-                            .boolean_filter(key, &mut trusted_only)
+                        url_matching_boolean(config, key, url_to_match, &mut trusted_only)
                             .map(|value| config::tree::Http::SCHANNEL_USE_SSL_CA_INFO.enrich_error(value))
                             .transpose()
                             .with_leniency(lenient)
@@ -352,8 +398,8 @@ impl crate::Repository {
                     if may_use_cainfo {
                         let key = "http.sslCAInfo";
                         debug_assert_eq!(key, config::tree::Http::SSL_CA_INFO.logical_name());
-                        opts.ssl_ca_info = config
-                            .path_filter(key, &mut trusted_only)
+                        opts.ssl_ca_info = url_matching_string(config, key, url_to_match, &mut trusted_only)
+                            .map(gix_config::Path::from)
                             .map(|p| {
                                 use crate::config::cache::interpolate_context;
                                 p.interpolate(interpolate_context(
@@ -372,6 +418,7 @@ impl crate::Repository {
                             config,
                             "http.sslVersion",
                             &config::tree::Http::SSL_VERSION,
+                            url_to_match,
                             trusted_only,
                             lenient,
                         )?
@@ -380,6 +427,7 @@ impl crate::Repository {
                             config,
                             "gitoxide.http.sslVersionMin",
                             &gitoxide::Http::SSL_VERSION_MIN,
+                            url_to_match,
                             trusted_only,
                             lenient,
                         )
@@ -388,6 +436,7 @@ impl crate::Repository {
                                 config,
                                 "gitoxide.http.sslVersionMax",
                                 &gitoxide::Http::SSL_VERSION_MAX,
+                                url_to_match,
                                 trusted_only,
                                 lenient,
                             )
@@ -417,8 +466,7 @@ impl crate::Repository {
                             opts.ssl_verify = false;
                         } else {
                             let key = "http.sslVerify";
-                            opts.ssl_verify = config
-                                .boolean_filter(key, &mut trusted_only)
+                            opts.ssl_verify = url_matching_boolean(config, key, url_to_match, &mut trusted_only)
                                 .map(|value| config::tree::Http::SSL_VERIFY.enrich_error(value))
                                 .transpose()
                                 .with_leniency(lenient)
@@ -430,8 +478,7 @@ impl crate::Repository {
                     #[cfg(feature = "blocking-http-transport-curl")]
                     {
                         let key = "http.schannelCheckRevoke";
-                        let schannel_check_revoke = config
-                            .boolean_filter(key, &mut trusted_only)
+                        let schannel_check_revoke = url_matching_boolean(config, key, url_to_match, &mut trusted_only)
                             .map(|value| config::tree::Http::SCHANNEL_CHECK_REVOKE.enrich_error(value))
                             .transpose()
                             .with_leniency(lenient)
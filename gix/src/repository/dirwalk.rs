@@ -19,6 +19,10 @@ impl Repository {
     /// The `index` is used to determine if entries are tracked, and for excludes and attributes
     /// lookup. Note that items will only count as tracked if they have the [`gix_index::entry::Flags::UPTODATE`]
     /// flag set.
+    /// The untracked cache of `index` is used if it was created for this worktree, or if `core.untrackedCache` is `true`,
+    /// but as `index` can't be changed, the updated cache is discarded.
+    /// If it's used and `core.fsmonitor` is configured, the filesystem monitor is queried for changes since the
+    /// token stored in the `index` to avoid checking directories it didn't report as changed.
     ///
     /// See [`gix_dir::walk::delegate::Collect`] for a delegate that collects all seen entries.
     pub fn dirwalk(
@@ -47,7 +51,19 @@ impl Repository {
         let git_dir_realpath =
             crate::path::realpath_opts(self.git_dir(), self.current_dir(), crate::path::realpath::MAX_SYMLINKS)?;
         let fs_caps = self.filesystem_options()?;
-        let accelerate_lookup = fs_caps.ignore_case.then(|| index.prepare_icase_backing());
+        let accelerate_lookup = fs_caps
+            .ignore_case
+            .then(|| index.prepare_icase_backing_with_thread_limit(Some(options.threads.unwrap_or(1))));
+        let mut untracked_cache = self.untracked_cache_for_dirwalk(index)?;
+        // The changes reported by a filesystem monitor are only used along with the untracked cache.
+        #[cfg(feature = "status")]
+        let fs_monitor_changed_paths = match untracked_cache {
+            Some(_) => crate::status::index_worktree::query_fs_monitor_hook(self, index)?
+                .and_then(|outcome| outcome.changes.paths().map(<[BString]>::to_vec)),
+            None => None,
+        };
+        #[cfg(not(feature = "status"))]
+        let fs_monitor_changed_paths: Option<Vec<BString>> = None;
         let (outcome, traversal_root) = gix_dir::walk(
             workdir,
             gix_dir::walk::Context {
@@ -70,6 +86,8 @@ impl Repository {
                 excludes: Some(&mut excludes.inner),
                 objects: &self.objects,
                 explicit_traversal_root: (!options.empty_patterns_match_prefix).then_some(workdir),
+                fs_monitor_changed_paths: fs_monitor_changed_paths.as_deref(),
+                untracked_cache: untracked_cache.as_mut().map(dirwalk::untracked_cache::State::for_walk),
             },
             options.into(),
             delegate,
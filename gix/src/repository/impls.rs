@@ -36,7 +36,7 @@ impl PartialEq<crate::Repository> for crate::Repository {
 
 impl From<&crate::ThreadSafeRepository> for crate::Repository {
     fn from(repo: &crate::ThreadSafeRepository) -> Self {
-        crate::Repository::from_refs_and_objects(
+        with_refreshed_config(crate::Repository::from_refs_and_objects(
             repo.refs.clone(),
             repo.objects.to_handle().into(),
             repo.work_tree.clone(),
@@ -48,13 +48,13 @@ impl From<&crate::ThreadSafeRepository> for crate::Repository {
             repo.shallow_commits.clone(),
             #[cfg(feature = "attributes")]
             repo.modules.clone(),
-        )
+        ))
     }
 }
 
 impl From<crate::ThreadSafeRepository> for crate::Repository {
     fn from(repo: crate::ThreadSafeRepository) -> Self {
-        crate::Repository::from_refs_and_objects(
+        with_refreshed_config(crate::Repository::from_refs_and_objects(
             repo.refs,
             repo.objects.to_handle().into(),
             repo.work_tree,
@@ -66,7 +66,7 @@ impl From<crate::ThreadSafeRepository> for crate::Repository {
             repo.shallow_commits,
             #[cfg(feature = "attributes")]
             repo.modules.clone(),
-        )
+        ))
     }
 }
 
@@ -87,3 +87,11 @@ impl From<crate::Repository> for crate::ThreadSafeRepository {
         }
     }
 }
+
+fn with_refreshed_config(mut repo: crate::Repository) -> crate::Repository {
+    #[allow(unused_variables)]
+    if let Err(err) = repo.refresh_config() {
+        gix_trace::warn!("Configuration could not be refreshed and remains unchanged: {err}");
+    }
+    repo
+}
@@ -0,0 +1,691 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::SystemTime,
+};
+
+use gix_features::{
+    parallel::InOrderIter,
+    progress::{Count, Progress},
+};
+use gix_hash::ObjectId;
+use gix_pack::data::output;
+use gix_ref::{
+    file::transaction::PackedRefs,
+    transaction::{Change, LogChange, PreviousValue, RefEdit},
+};
+
+use crate::{
+    config::{cache::util::ApplyLeniency, tree::Gc},
+    maintenance,
+    maintenance::{is_needed, Error, Outcome, Repack},
+    reference::edit,
+    Repository,
+};
+
+impl Repository {
+    /// Return the options for [`gc()`](Self::gc()) and [`gc_auto()`](Self::gc_auto()) as configured by `gc.auto`,
+    /// `gc.autoPackLimit` and `gc.pruneExpire`.
+    pub fn maintenance_options(&self) -> Result<maintenance::Options, maintenance::options::Error> {
+        let config = &self.config.resolved;
+        let lenient = self.config.lenient_config;
+        let mut opts = maintenance::Options::default();
+        if let Some(limit) = config
+            .integer(Gc::AUTO)
+            .map(|value| Gc::AUTO.try_into_usize(value))
+            .transpose()
+            .with_leniency(lenient)?
+        {
+            opts.auto.loose_objects = (limit != 0).then_some(limit);
+        }
+        if let Some(limit) = config
+            .integer(Gc::AUTO_PACK_LIMIT)
+            .map(|value| Gc::AUTO_PACK_LIMIT.try_into_usize(value))
+            .transpose()
+            .with_leniency(lenient)?
+        {
+            opts.auto.packs = (limit != 0).then_some(limit);
+        }
+        if opts.auto.loose_objects.is_none() {
+            // Like git, `gc.auto = 0` disables all heuristics.
+            opts.auto.packs = None;
+        }
+
+        let now = SystemTime::now();
+        opts.prune_expire = match config
+            .string(Gc::PRUNE_EXPIRE)
+            .map(|value| Gc::PRUNE_EXPIRE.try_into_expiry(value, now))
+            .transpose()
+            .with_leniency(lenient)?
+        {
+            Some(expiry) => expiry,
+            None => Some(seconds_since_epoch(now) - 14 * 24 * 60 * 60),
+        };
+        Ok(opts)
+    }
+
+    /// Return `true` if the amount of loose objects or packs exceeds the thresholds in `auto`, which is when
+    /// [`gc_auto()`](Self::gc_auto()) would run.
+    ///
+    /// Like `git`, the amount of loose objects is estimated by looking at only one of the 256 loose object directories.
+    /// Packs with a `.keep` file are not counted.
+    pub fn gc_is_needed(&self, auto: maintenance::Auto) -> Result<bool, is_needed::Error> {
+        let objects_dir = self.objects.store_ref().path();
+        if let Some(limit) = auto.loose_objects {
+            let loose_file_name_len = self.object_hash().len_in_hex() - 2;
+            let num_loose = match std::fs::read_dir(objects_dir.join("17")) {
+                Ok(entries) => entries
+                    .filter_map(Result::ok)
+                    .filter(|entry| entry.file_name().len() == loose_file_name_len)
+                    .count(),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => 0,
+                Err(err) => return Err(err.into()),
+            };
+            if num_loose > (limit + 255) / 256 {
+                return Ok(true);
+            }
+        }
+        if let Some(limit) = auto.packs {
+            let num_packs = packs_in(&objects_dir.join("pack"))?
+                .into_iter()
+                .filter(|pack| !pack.keep)
+                .count();
+            if num_packs > limit {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Like [`gc()`](Self::gc()), but only run if [`gc_is_needed()`](Self::gc_is_needed()) returns `true`
+    /// for the thresholds in `options`, similar to `git gc --auto`. Return `None` if nothing was done.
+    #[allow(clippy::result_large_err)]
+    pub fn gc_auto(
+        &self,
+        options: maintenance::Options,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Option<Outcome>, Error> {
+        if !self.gc_is_needed(options.auto)? {
+            return Ok(None);
+        }
+        self.gc(options, progress, should_interrupt).map(Some)
+    }
+
+    /// Make this repository more efficient by packing references and objects as configured in `options`, and by removing
+    /// unreachable loose objects that are older than the configured expiry, similar to `git gc`.
+    ///
+    /// Objects are reachable if they are reachable from any reference, the reference logs, the `HEAD` and index of all
+    /// worktrees. Objects in the object databases of alternates are considered as well, and will be copied into the
+    /// written pack if they are reachable.
+    ///
+    /// Only one maintenance operation can run at a time as protected by the `gc.lock` file in the common git directory.
+    /// `progress` is used to report the progress of each step, and `should_interrupt` is checked to stop the operation early,
+    /// which is safe to do as all changes are only made once the new packs are in place.
+    #[allow(clippy::result_large_err)]
+    pub fn gc(
+        &self,
+        options: maintenance::Options,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::gc");
+        let _lock = gix_lock::Marker::acquire_to_hold_resource(
+            self.common_dir().join("gc"),
+            gix_lock::acquire::Fail::Immediately,
+            None,
+        )?;
+        let mut out = Outcome::default();
+
+        if options.expire_reflogs {
+            for reference in self.references()?.all()? {
+                let reference = reference.map_err(Error::NextReference)?;
+                out.num_expired_reflog_entries += reference.expire_log()?.num_removed;
+            }
+            for repo in self.worktree_repos()? {
+                if let Some(head) = repo.try_find_reference("HEAD")? {
+                    out.num_expired_reflog_entries += head.expire_log()?.num_removed;
+                }
+            }
+        }
+        if options.pack_refs {
+            out.num_packed_refs = self.pack_refs()?;
+        }
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+
+        let mut objects = self.objects.clone().into_arc()?;
+        objects.prevent_pack_unload();
+        objects.ignore_replacements = true;
+        let reachable_counts = self.reachable_objects(&objects, progress, should_interrupt)?;
+        let reachable: gix_hashtable::HashSet = reachable_counts.iter().map(|count| count.id).collect();
+
+        let pack_dir = self.objects.store_ref().path().join("pack");
+        let loose = gix_odb::loose::Store::at(self.objects.store_ref().path(), self.object_hash());
+        let loose_objects = loose.iter().filter_map(Result::ok).collect::<Vec<_>>();
+        let is_expired = |mtime: SystemTime| {
+            options
+                .prune_expire
+                .map_or(false, |expire| seconds_since_epoch(mtime) < expire)
+        };
+        let object_hash = self.object_hash();
+        let packs = packs_in(&pack_dir)?;
+
+        let mut recent_unreachable = Vec::new();
+        if options.prune_expire.is_some() {
+            for id in &loose_objects {
+                let mtime = std::fs::metadata(loose.object_path(id))?.modified()?;
+                if !reachable.contains(id) && !is_expired(mtime) {
+                    recent_unreachable.push(*id);
+                }
+            }
+            for pack in packs.iter().filter(|pack| !is_expired(pack.mtime)) {
+                recent_unreachable.extend(
+                    pack.open_index(object_hash)?
+                        .iter()
+                        .map(|entry| entry.oid)
+                        .filter(|id| !reachable.contains(id)),
+                );
+            }
+        }
+        let recent =
+            self.objects_reachable_from_recent(&objects, recent_unreachable, &reachable, progress, should_interrupt)?;
+        let is_prunable = |id: &gix_hash::oid, mtime: SystemTime| {
+            !reachable.contains(id) && !recent.contains(id) && is_expired(mtime)
+        };
+
+        let (counts, packs_to_remove) = match options.repack {
+            Repack::None => (Vec::new(), Vec::new()),
+            Repack::All => {
+                // Like `git repack -A`, unreachable objects that can't be pruned yet are kept, here by putting them into the new pack.
+                let mut kept_objects = gix_hashtable::HashSet::default();
+                let mut unreachable_packed_objects = gix_hashtable::HashSet::default();
+                for pack in &packs {
+                    let index = pack.open_index(object_hash)?;
+                    for entry in index.iter() {
+                        if pack.keep {
+                            kept_objects.insert(entry.oid);
+                        } else if !reachable.contains(&entry.oid) && !is_prunable(&entry.oid, pack.mtime) {
+                            unreachable_packed_objects.insert(entry.oid);
+                        }
+                    }
+                }
+                let mut counts = reachable_counts;
+                counts.retain(|count| !kept_objects.contains(&count.id));
+                counts.extend(count_objects(
+                    &objects,
+                    unreachable_packed_objects
+                        .into_iter()
+                        .filter(|id| !kept_objects.contains(id))
+                        .collect(),
+                    progress,
+                    should_interrupt,
+                )?);
+                (counts, packs.into_iter().filter(|pack| !pack.keep).collect())
+            }
+            Repack::Geometric { factor } => {
+                let mut ids = loose_objects
+                    .iter()
+                    .filter(|id| {
+                        std::fs::metadata(loose.object_path(id))
+                            .and_then(|meta| meta.modified())
+                            .map_or(true, |mtime| !is_prunable(id, mtime))
+                    })
+                    .copied()
+                    .collect::<Vec<_>>();
+                let mut sized_packs = Vec::new();
+                for pack in packs.into_iter().filter(|pack| !pack.keep) {
+                    let num_objects = pack.open_index(object_hash)?.num_objects();
+                    sized_packs.push((num_objects as usize, pack));
+                }
+                let rollup = geometric_rollup(&mut sized_packs, ids.len(), factor as usize);
+                let packs_to_remove = if rollup == 1 && ids.is_empty() {
+                    Vec::new()
+                } else {
+                    sized_packs.truncate(rollup);
+                    sized_packs.into_iter().map(|(_, pack)| pack).collect()
+                };
+                for pack in &packs_to_remove {
+                    ids.extend(pack.open_index(object_hash)?.iter().map(|entry| entry.oid));
+                }
+                (
+                    count_objects(&objects, ids, progress, should_interrupt)?,
+                    packs_to_remove,
+                )
+            }
+        };
+
+        let mut packed_objects = gix_hashtable::HashSet::default();
+        if !counts.is_empty() {
+            out.num_packed_objects = counts.len();
+            packed_objects.extend(counts.iter().map(|count| count.id));
+            let index_path = write_pack(
+                objects,
+                counts,
+                &pack_dir,
+                options.thread_limit,
+                object_hash,
+                progress,
+                should_interrupt,
+            )?;
+            for pack in packs_to_remove {
+                if pack.index_path == index_path {
+                    continue;
+                }
+                for extension in ["pack", "rev", "bitmap", "idx"] {
+                    let path = pack.index_path.with_extension(extension);
+                    match std::fs::remove_file(&path) {
+                        Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+                        _ => {}
+                    }
+                }
+                out.removed_packs.push(pack.index_path);
+            }
+            out.written_pack = Some(index_path);
+        }
+
+        for id in loose_objects {
+            let path = loose.object_path(&id);
+            let is_packed = packed_objects.contains(&id);
+            if !is_packed && !is_prunable(&id, std::fs::metadata(&path)?.modified()?) {
+                continue;
+            }
+            std::fs::remove_file(&path)?;
+            if let Some(fan_out_dir) = path.parent() {
+                // Remove the directory if it's empty, and fail silently if it isn't.
+                std::fs::remove_dir(fan_out_dir).ok();
+            }
+            if is_packed {
+                out.num_removed_loose_objects += 1;
+            } else {
+                out.num_pruned_objects += 1;
+            }
+        }
+
+        let multi_index_path = pack_dir.join("multi-pack-index");
+        if options.write_multi_pack_index || multi_index_path.is_file() {
+            out.multi_pack_index =
+                write_multi_pack_index(&pack_dir, &multi_index_path, object_hash, progress, should_interrupt)?;
+        }
+        Ok(out)
+    }
+
+    /// Move all loose references that aren't symbolic into the `packed-refs` file and return how many were moved.
+    #[allow(clippy::result_large_err)]
+    fn pack_refs(&self) -> Result<usize, Error> {
+        let edits = self
+            .refs
+            .loose_iter()?
+            .filter_map(Result::ok)
+            .filter(|r| {
+                r.kind() == gix_ref::Kind::Peeled
+                    && r.name
+                        .as_ref()
+                        .category()
+                        .map_or(false, |category| !category.is_worktree_private())
+            })
+            .map(|r| RefEdit {
+                change: Change::Update {
+                    log: LogChange::default(),
+                    expected: PreviousValue::MustExistAndMatch(r.target.clone()),
+                    new: r.target,
+                },
+                name: r.name,
+                deref: false,
+            })
+            .collect::<Vec<_>>();
+        if edits.is_empty() {
+            return Ok(0);
+        }
+        let (file_lock_fail, packed_refs_lock_fail) = self.config.lock_timeout().map_err(edit::Error::from)?;
+        let committer = self.committer().transpose().map_err(edit::Error::from)?;
+        let edits = self
+            .refs
+            .transaction()
+            .packed_refs(PackedRefs::DeletionsAndNonSymbolicUpdatesRemoveLooseSourceReference(
+                Box::new(self.objects.clone()),
+            ))
+            .prepare(edits, file_lock_fail, packed_refs_lock_fail)
+            .map_err(edit::Error::from)?
+            .commit(committer)
+            .map_err(edit::Error::from)?;
+        Ok(edits.len())
+    }
+
+    /// Return this repository and the repositories of all other worktrees, including the main worktree.
+    #[allow(clippy::result_large_err)]
+    fn worktree_repos(&self) -> Result<Vec<Repository>, crate::open::Error> {
+        let mut out = vec![self.clone()];
+        if self.git_dir() != self.common_dir() {
+            out.push(self.main_repo()?);
+        }
+        for worktree in self.worktrees()? {
+            if worktree.git_dir() != self.git_dir() {
+                out.push(worktree.into_repo_with_possibly_inaccessible_worktree()?);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Count all objects that are reachable from references, reference logs and the `HEAD` and index of all worktrees.
+    #[allow(clippy::result_large_err)]
+    fn reachable_objects(
+        &self,
+        objects: &gix_odb::HandleArc,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Vec<output::Count>, Error> {
+        let mut tips = Vec::new();
+        let mut direct_tips = Vec::new();
+        let mut index_ids = Vec::new();
+        for reference in self.references()?.all()? {
+            let reference = reference.map_err(Error::NextReference)?;
+            push_logged_ids(&reference, &mut tips)?;
+            if let Some(id) = reference.target().try_id() {
+                direct_tips.push(id.to_owned());
+            }
+        }
+        for repo in self.worktree_repos()? {
+            if let Some(head) = repo.try_find_reference("HEAD")? {
+                push_logged_ids(&head, &mut tips)?;
+                if let Some(id) = head.target().try_id() {
+                    direct_tips.push(id.to_owned());
+                }
+            }
+            if let Some(index) = repo.try_index()? {
+                // Index entries are roots by themselves, and blobs may be missing, for instance in partial clones.
+                index_ids.extend(
+                    index
+                        .entries()
+                        .iter()
+                        .filter(|entry| {
+                            entry.mode != gix_index::entry::Mode::COMMIT
+                                && !entry.flags.contains(gix_index::entry::Flags::INTENT_TO_ADD)
+                                && repo.has_object(entry.id)
+                        })
+                        .map(|entry| entry.id),
+                );
+            }
+        }
+        tips.extend(direct_tips);
+
+        let mut commits = Vec::new();
+        for tip in &tips {
+            let object = self.find_object(*tip)?.peel_tags_to_end()?;
+            if object.kind == gix_object::Kind::Commit {
+                commits.push(object.id);
+            }
+        }
+        {
+            let mut progress = progress.add_child("traversing".into());
+            progress.init(None, gix_features::progress::count("commits"));
+            for info in crate::revision::walk::Platform::new(commits, self).all()? {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                tips.push(info?.id);
+                progress.inc();
+            }
+        }
+        tips.extend(index_ids);
+
+        let mut progress = progress.add_child("counting".into());
+        progress.init(None, gix_features::progress::count("objects"));
+        let (counts, _) = output::count::objects_unthreaded(
+            objects,
+            &mut tips.into_iter().map(Ok),
+            &progress,
+            should_interrupt,
+            output::count::objects::ObjectExpansion::TreeContents,
+        )?;
+        Ok(counts)
+    }
+}
+
+impl Repository {
+    /// Return all objects in `recent`, which are unreachable but too recent to be pruned, along with all objects reachable
+    /// from them that aren't in `reachable`, so none of them is pruned, similar to how `git` retains recent objects.
+    #[allow(clippy::result_large_err)]
+    fn objects_reachable_from_recent(
+        &self,
+        objects: &gix_odb::HandleArc,
+        mut recent: Vec<ObjectId>,
+        reachable: &gix_hashtable::HashSet,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<gix_hashtable::HashSet, Error> {
+        if recent.is_empty() {
+            return Ok(Default::default());
+        }
+        let mut commits = Vec::new();
+        for id in &recent {
+            if self.find_header(*id)?.kind() == gix_object::Kind::Commit {
+                commits.push(*id);
+            }
+        }
+        for info in crate::revision::walk::Platform::new(commits, self).selected(|id| !reachable.contains(id))? {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            recent.push(info?.id);
+        }
+
+        let mut progress = progress.add_child("counting recent".into());
+        progress.init(None, gix_features::progress::count("objects"));
+        let (counts, _) = output::count::objects_unthreaded(
+            objects,
+            &mut recent.into_iter().map(Ok),
+            &progress,
+            should_interrupt,
+            output::count::objects::ObjectExpansion::TreeContents,
+        )?;
+        Ok(counts
+            .into_iter()
+            .map(|count| count.id)
+            .filter(|id| !reachable.contains(id))
+            .collect())
+    }
+}
+
+/// Push all objects that are mentioned in the log of `reference` to `out`, if they still exist.
+#[allow(clippy::result_large_err)]
+fn push_logged_ids(reference: &crate::Reference<'_>, out: &mut Vec<ObjectId>) -> Result<(), Error> {
+    let mut log = reference.log_iter();
+    let Some(lines) = log.all().map_err(|source| Error::ReadReflog {
+        name: reference.name().to_owned(),
+        source,
+    })?
+    else {
+        return Ok(());
+    };
+    for line in lines {
+        let line = line.map_err(|source| Error::DecodeReflog {
+            name: reference.name().to_owned(),
+            source,
+        })?;
+        for id in [line.previous_oid(), line.new_oid()] {
+            // Logs may refer to objects that were pruned already.
+            if !id.is_null() && reference.repo.has_object(id) {
+                out.push(id);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Count the objects with `ids` as they are, without expanding them.
+fn count_objects(
+    objects: &gix_odb::HandleArc,
+    ids: Vec<ObjectId>,
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<Vec<output::Count>, Error> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut progress = progress.add_child("counting".into());
+    progress.init(Some(ids.len()), gix_features::progress::count("objects"));
+    let (counts, _) = output::count::objects_unthreaded(
+        objects,
+        &mut ids.into_iter().map(Ok),
+        &progress,
+        should_interrupt,
+        output::count::objects::ObjectExpansion::AsIs,
+    )?;
+    Ok(counts)
+}
+
+/// Write a pack with all objects in `counts` into `pack_dir`, index it, and return the path to the index.
+fn write_pack(
+    objects: gix_odb::HandleArc,
+    counts: Vec<output::Count>,
+    pack_dir: &Path,
+    thread_limit: Option<usize>,
+    object_hash: gix_hash::Kind,
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<PathBuf, Error> {
+    let mut pack = gix_tempfile::new(
+        pack_dir,
+        gix_tempfile::ContainingDirectory::Exists,
+        gix_tempfile::AutoRemove::Tempfile,
+    )?;
+    let num_objects = counts.len();
+    let entries = InOrderIter::from(output::entry::iter_from_counts(
+        counts,
+        objects,
+        Box::new(progress.add_child("creating entries".into())),
+        output::entry::iter_from_counts::Options {
+            thread_limit,
+            mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+            allow_thin_pack: false,
+            ..Default::default()
+        },
+    ));
+    let mut write = output::bytes::FromEntriesIter::new(
+        entries,
+        &mut pack,
+        num_objects as u32,
+        gix_pack::data::Version::V2,
+        object_hash,
+    );
+    for res in write.by_ref() {
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+        res?;
+    }
+    drop(write);
+
+    let pack_data = pack.with_mut(|tempfile| tempfile.reopen())??;
+    let outcome = gix_pack::Bundle::write_to_directory(
+        &mut std::io::BufReader::new(pack_data),
+        Some(pack_dir),
+        progress,
+        should_interrupt,
+        None::<gix_object::find::Never>,
+        gix_pack::bundle::write::Options {
+            thread_limit,
+            object_hash,
+            ..Default::default()
+        },
+    )?;
+    if let Some(keep_path) = outcome.keep_path {
+        std::fs::remove_file(keep_path)?;
+    }
+    Ok(outcome.index_path.expect("directory was set"))
+}
+
+/// Write a multi-pack-index to `path` for all packs in `pack_dir`, or remove it if there are no packs.
+fn write_multi_pack_index(
+    pack_dir: &Path,
+    path: &Path,
+    object_hash: gix_hash::Kind,
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<Option<PathBuf>, Error> {
+    let index_paths = packs_in(pack_dir)?
+        .into_iter()
+        .map(|pack| pack.index_path)
+        .collect::<Vec<_>>();
+    if index_paths.is_empty() {
+        if path.is_file() {
+            std::fs::remove_file(path)?;
+        }
+        return Ok(None);
+    }
+    let mut lock = gix_lock::File::acquire_to_update_resource(path, gix_lock::acquire::Fail::Immediately, None)?;
+    gix_pack::multi_index::File::write_from_index_paths(
+        index_paths,
+        &mut lock,
+        progress,
+        should_interrupt,
+        gix_pack::multi_index::write::Options { object_hash },
+    )?;
+    lock.commit().map_err(|err| err.error)?;
+    Ok(Some(path.to_owned()))
+}
+
+/// Sort `packs` by their amount of objects and return how many of the smallest packs should be merged along with
+/// `num_loose` objects so that all remaining packs form a geometric progression with `factor`.
+fn geometric_rollup(packs: &mut [(usize, Pack)], num_loose: usize, factor: usize) -> usize {
+    packs.sort_by_key(|(num_objects, _)| *num_objects);
+    let factor = factor.max(2);
+    let mut split = packs
+        .windows(2)
+        .rposition(|pair| pair[1].0 < factor * pair[0].0)
+        .map_or(0, |pos| pos + 1);
+    let mut rollup_objects = num_loose + packs[..split].iter().map(|(num_objects, _)| num_objects).sum::<usize>();
+    while split < packs.len() && packs[split].0 < factor * rollup_objects {
+        rollup_objects += packs[split].0;
+        split += 1;
+    }
+    split
+}
+
+struct Pack {
+    index_path: PathBuf,
+    mtime: SystemTime,
+    keep: bool,
+}
+
+impl Pack {
+    fn open_index(&self, object_hash: gix_hash::Kind) -> Result<gix_pack::index::File, Error> {
+        gix_pack::index::File::at(&self.index_path, object_hash).map_err(|source| Error::OpenPackIndex {
+            path: self.index_path.clone(),
+            source,
+        })
+    }
+}
+
+/// Return all packs in `pack_dir` that have an index.
+fn packs_in(pack_dir: &Path) -> std::io::Result<Vec<Pack>> {
+    let entries = match std::fs::read_dir(pack_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut out = Vec::new();
+    for entry in entries {
+        let index_path = entry?.path();
+        if index_path.extension().map_or(true, |ext| ext != "idx") {
+            continue;
+        }
+        let Ok(mtime) = std::fs::metadata(index_path.with_extension("pack")).and_then(|meta| meta.modified()) else {
+            continue;
+        };
+        out.push(Pack {
+            keep: index_path.with_extension("keep").is_file(),
+            mtime,
+            index_path,
+        });
+    }
+    out.sort_by(|a, b| a.index_path.cmp(&b.index_path));
+    Ok(out)
+}
+
+fn seconds_since_epoch(time: SystemTime) -> gix_date::SecondsSinceUnixEpoch {
+    time.duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() as gix_date::SecondsSinceUnixEpoch)
+}
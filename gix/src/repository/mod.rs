@@ -36,6 +36,8 @@ impl crate::Repository {
     }
 }
 
+#[cfg(feature = "apply")]
+mod apply;
 #[cfg(any(feature = "attributes", feature = "excludes"))]
 pub mod attributes;
 mod cache;
@@ -62,17 +64,23 @@ mod kind;
 mod location;
 #[cfg(feature = "mailmap")]
 mod mailmap;
+#[cfg(feature = "maintenance")]
+mod maintenance;
 mod object;
 #[cfg(feature = "attributes")]
 mod pathspec;
 mod reference;
 mod remote;
+#[cfg(feature = "worktree-mutation")]
+mod reset;
 #[cfg(feature = "revision")]
 mod revision;
 mod shallow;
 mod state;
 #[cfg(feature = "attributes")]
 mod submodule;
+#[cfg(feature = "worktree-mutation")]
+mod switch;
 mod thread_safe;
 mod worktree;
 
@@ -94,6 +102,45 @@ pub mod index_from_tree {
     }
 }
 
+///
+#[allow(clippy::empty_docs)]
+#[cfg(feature = "revision")]
+pub mod merge_base {
+    /// The error returned by [Repository::merge_base()](crate::Repository::merge_base()) and its siblings.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Compute(#[from] gix_revision::merge_base::Error),
+        #[error("No merge base was found between {first} and {second}")]
+        NotFound {
+            first: gix_hash::ObjectId,
+            second: gix_hash::ObjectId,
+        },
+    }
+}
+
+///
+#[allow(clippy::empty_docs)]
+#[cfg(feature = "revision")]
+pub mod merge_base_fork_point {
+    /// The error returned by [Repository::merge_base_fork_point()](crate::Repository::merge_base_fork_point()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        PeelReference(#[from] crate::reference::peel::Error),
+        #[error("Could not read the reference log of the upstream reference")]
+        ReadReflog(#[from] std::io::Error),
+        #[error(transparent)]
+        DecodeReflog(#[from] gix_ref::file::log::iter::decode::Error),
+        #[error(transparent)]
+        Compute(#[from] gix_revision::merge_base::Error),
+    }
+}
+
 ///
 #[allow(clippy::empty_docs)]
 pub mod branch_remote_ref_name {
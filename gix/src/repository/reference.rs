@@ -1,11 +1,17 @@
+use std::borrow::Cow;
+
 use gix_hash::ObjectId;
 use gix_macros::momo;
 use gix_ref::{
-    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
-    FullName, PartialNameRef, Target,
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog, RenameMode},
+    Category, FullName, FullNameRef, PartialNameRef, Target,
 };
 
-use crate::{bstr::BString, ext::ReferenceExt, reference, Reference};
+use crate::{
+    bstr::{BStr, BString},
+    ext::ReferenceExt,
+    reference, Reference,
+};
 
 /// Obtain and alter references comfortably
 impl crate::Repository {
@@ -153,6 +159,93 @@ impl crate::Repository {
             .map_err(Into::into)
     }
 
+    /// Rename the reference `source` to `destination`, or copy it if `mode` is [`Copy`][RenameMode::Copy],
+    /// along with its reflog which receives a new entry with `reflog_message`, and return the reference at its new name.
+    ///
+    /// `destination` must not exist yet. When moving, `HEAD` will follow if it pointed to `source`.
+    /// If `source` is a local branch, its `branch.<name>` configuration sections in the repository-local configuration
+    /// file are renamed or copied as well, both on disk and in memory.
+    pub fn rename_reference(
+        &mut self,
+        source: &FullNameRef,
+        destination: FullName,
+        mode: RenameMode,
+        reflog_message: impl Into<BString>,
+    ) -> Result<Reference<'_>, reference::rename::Error> {
+        let (file_lock_fail, packed_refs_lock_fail) =
+            self.config.lock_timeout().map_err(reference::edit::Error::from)?;
+        let committer = self.committer().transpose().map_err(reference::edit::Error::from)?;
+        self.refs
+            .transaction()
+            .prepare_rename(
+                source,
+                destination.clone(),
+                mode,
+                reflog_message.into(),
+                file_lock_fail,
+                packed_refs_lock_fail,
+            )
+            .map_err(reference::edit::Error::from)?
+            .commit(committer)
+            .map_err(reference::edit::Error::from)?;
+
+        if let (Some((Category::LocalBranch, source)), Some((Category::LocalBranch, destination))) = (
+            source.category_and_short_name(),
+            destination.as_ref().category_and_short_name(),
+        ) {
+            self.rename_branch_config(source, destination, mode)?;
+        }
+        Ok(self.find_reference(destination.as_ref())?)
+    }
+
+    fn rename_branch_config(
+        &mut self,
+        source: &BStr,
+        destination: &BStr,
+        mode: RenameMode,
+    ) -> Result<(), reference::rename::Error> {
+        let is_source_branch =
+            |section: &&gix_config::file::Section<'_>| section.header().subsection_name() == Some(source);
+        let has_branch_config = self
+            .config
+            .resolved
+            .sections_by_name_and_filter("branch", &mut |meta: &gix_config::file::Metadata| {
+                meta.source == gix_config::Source::Local
+            })
+            .map_or(false, |mut sections| sections.any(|section| is_source_branch(&section)));
+        if !has_branch_config {
+            return Ok(());
+        }
+
+        // Edit the local configuration file by itself, without includes, so nothing but the branch sections change.
+        let mut config = self.config_file_mut(gix_config::Source::Local)?;
+        let sections: Vec<_> = config
+            .sections_by_name("branch")
+            .into_iter()
+            .flatten()
+            .filter(is_source_branch)
+            .cloned()
+            .collect();
+        for section in sections {
+            if mode == RenameMode::Move {
+                config.remove_section_by_id(section.id());
+            }
+            let mut new_section = config.push_section(
+                gix_config::file::Section::new(
+                    "branch",
+                    Some(Cow::Owned(destination.to_owned())),
+                    section.meta().clone(),
+                )
+                .expect("valid branch names are valid subsection names"),
+            );
+            for (name, value) in section.body().clone() {
+                new_section.push(name, Some(value.as_ref()));
+            }
+        }
+        config.commit()?;
+        Ok(())
+    }
+
     /// Return the repository head, an abstraction to help dealing with the `HEAD` reference.
     ///
     /// The `HEAD` reference can be in various states, for more information, the documentation of [`Head`][crate::Head].
@@ -1,5 +1,12 @@
 #![allow(clippy::result_large_err)]
-use crate::{bstr::BStr, config, remote, remote::find, Remote};
+use std::borrow::Cow;
+
+use crate::{
+    bstr::{BStr, BString},
+    config, remote,
+    remote::find,
+    Remote,
+};
 
 impl crate::Repository {
     /// Create a new remote available at the given `url`.
@@ -60,6 +67,9 @@ impl crate::Repository {
     /// as negations/excludes are applied after includes.
     ///
     /// We will only include information if we deem it [trustworthy][crate::open::Options::filter_config_section()].
+    ///
+    /// Like git, if no remote named `name_or_url` is configured, it's read from `$GIT_DIR/remotes/<name>` or `$GIT_DIR/branches/<name>`
+    /// if the repository is trusted.
     pub fn try_find_remote<'a>(&self, name_or_url: impl Into<&'a BStr>) -> Option<Result<Remote<'_>, find::Error>> {
         self.try_find_remote_inner(name_or_url.into(), true)
     }
@@ -93,6 +103,85 @@ impl crate::Repository {
         })
     }
 
+    /// Fetch all remotes of the remote group `name` as configured with `remotes.<name>`, or the remote `name` itself if there is
+    /// no such group, similar to `git fetch <group>`. Each remote is fetched according to its configuration, with a child of
+    /// `progress` for each of them, and `should_interrupt` is checked to stop all fetches.
+    ///
+    /// Up to `fetch.parallel` remotes are fetched at the same time, one at a time by default, or as many as there are logical cores
+    /// if it is `0`. Note that without the `parallel` feature of `gix-features` all remotes are fetched one after another,
+    /// whatever `fetch.parallel` is set to.
+    ///
+    /// This method is only available with the `blocking-network-client` feature as it fetches from multiple threads.
+    ///
+    /// Return the outcome of each fetch in the order the remotes are listed in the group, as a failure to fetch one remote doesn't
+    /// affect the others.
+    #[cfg(feature = "blocking-network-client")]
+    pub fn fetch_remote_group<'a>(
+        &self,
+        name: impl Into<&'a BStr>,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+    ) -> Result<Vec<remote::group::Outcome>, remote::group::Error> {
+        use crate::config::cache::util::ApplyLeniency;
+
+        let name = name.into();
+        let remote_names = match self.remote_group(name).filter(|names| !names.is_empty()) {
+            Some(names) => names,
+            None if self.try_find_remote(name).is_some() => vec![name.to_owned()],
+            None => return Err(remote::group::Error::NotFound { name: name.to_owned() }),
+        };
+        let parallel = self
+            .config
+            .resolved
+            .integer_filter(config::tree::Fetch::PARALLEL, &mut self.filter_config_section())
+            .map(|value| config::tree::Fetch::PARALLEL.try_into_usize(value))
+            .transpose()
+            .with_leniency(self.config.lenient_config)?
+            .unwrap_or(1);
+        let num_threads = gix_features::parallel::num_threads(Some(parallel))
+            .min(remote_names.len())
+            .max(1);
+
+        let work = std::sync::Mutex::new(
+            remote_names
+                .into_iter()
+                .enumerate()
+                .map(|(idx, remote_name)| {
+                    let progress = progress.add_child(remote_name.to_string());
+                    (idx, remote_name, progress)
+                })
+                .collect::<std::collections::VecDeque<_>>(),
+        );
+        let fetch_all = |repo: &crate::Repository| {
+            let mut out = Vec::new();
+            loop {
+                let next = work.lock().expect("no panic while holding the lock").pop_front();
+                let Some((idx, remote_name, mut progress)) = next else {
+                    break;
+                };
+                let result = fetch_remote(repo, remote_name.as_ref(), &mut progress, should_interrupt);
+                out.push((idx, remote::group::Outcome { remote_name, result }));
+            }
+            out
+        };
+        let mut outcomes = if num_threads == 1 {
+            fetch_all(self)
+        } else {
+            let repo = self.clone().into_sync();
+            std::thread::scope(|scope| {
+                let threads: Vec<_> = (0..num_threads)
+                    .map(|_| scope.spawn(|| fetch_all(&repo.to_thread_local())))
+                    .collect();
+                threads
+                    .into_iter()
+                    .flat_map(|thread| thread.join().expect("no panic in fetch threads"))
+                    .collect::<Vec<_>>()
+            })
+        };
+        outcomes.sort_by_key(|(idx, _)| *idx);
+        Ok(outcomes.into_iter().map(|(_, outcome)| outcome).collect())
+    }
+
     /// Similar to [`try_find_remote()`][Self::try_find_remote()], but removes a failure mode if rewritten URLs turn out to be invalid
     /// as it skips rewriting them.
     /// Use this in conjunction with [`Remote::rewrite_urls()`] to non-destructively apply the rules and keep the failed urls unchanged.
@@ -183,9 +272,29 @@ impl crate::Repository {
             None => Default::default(),
         };
 
+        let fetch_specs = match fetch_specs.transpose() {
+            Ok(v) => v,
+            Err(err) => return Some(Err(err)),
+        };
+        let push_specs = match push_specs.transpose() {
+            Ok(v) => v,
+            Err(err) => return Some(Err(err)),
+        };
+
         match (url, fetch_specs, push_url, push_specs) {
-            (None, None, None, None) => None,
-            (None, _, None, _) => Some(Err(find::Error::UrlMissing)),
+            (None, fetch_specs, None, push_specs) => {
+                let has_specs = fetch_specs.is_some() || push_specs.is_some();
+                match self.try_find_remote_in_legacy_files(
+                    name_or_url,
+                    fetch_specs.unwrap_or_default(),
+                    push_specs.unwrap_or_default(),
+                    fetch_tags,
+                    rewrite_urls,
+                ) {
+                    None if has_specs => Some(Err(find::Error::UrlMissing)),
+                    remote => remote,
+                }
+            }
             (url, fetch_specs, push_url, push_specs) => {
                 let url = match url {
                     Some(Ok(v)) => Some(v),
@@ -197,24 +306,13 @@ impl crate::Repository {
                     Some(Err(err)) => return Some(Err(err)),
                     None => None,
                 };
-                let fetch_specs = match fetch_specs {
-                    Some(Ok(v)) => v,
-                    Some(Err(err)) => return Some(Err(err)),
-                    None => Vec::new(),
-                };
-                let push_specs = match push_specs {
-                    Some(Ok(v)) => v,
-                    Some(Err(err)) => return Some(Err(err)),
-                    None => Vec::new(),
-                };
-
                 Some(
                     Remote::from_preparsed_config(
                         Some(name_or_url.to_owned()),
                         url,
                         push_url,
-                        fetch_specs,
-                        push_specs,
+                        fetch_specs.unwrap_or_default(),
+                        push_specs.unwrap_or_default(),
                         rewrite_urls,
                         fetch_tags,
                         self,
@@ -224,4 +322,104 @@ impl crate::Repository {
             }
         }
     }
+
+    /// Read the remote `name` from `$GIT_DIR/remotes/<name>`, or from `$GIT_DIR/branches/<name>` if the former doesn't exist
+    /// or has no url, just like git does for remotes that aren't configured with a url.
+    /// The refspecs of the file are added to the configured `fetch_specs` and `push_specs`.
+    fn try_find_remote_in_legacy_files(
+        &self,
+        name: &BStr,
+        mut fetch_specs: Vec<gix_refspec::RefSpec>,
+        mut push_specs: Vec<gix_refspec::RefSpec>,
+        fetch_tags: remote::fetch::Tags,
+        rewrite_urls: bool,
+    ) -> Option<Result<Remote<'_>, find::Error>> {
+        if self.git_dir_trust() != gix_sec::Trust::Full || !remote::legacy::is_valid_name(name) {
+            return None;
+        }
+        let file_name = gix_path::try_from_bstr(name).ok()?;
+        for dir in ["remotes", "branches"] {
+            let path = self.git_dir().join(dir).join(&*file_name);
+            let content = match std::fs::read(&path) {
+                Ok(content) => content,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(err) => return Some(Err(find::Error::ReadFile { path, source: err })),
+            };
+            let definition = if dir == "remotes" {
+                remote::legacy::from_remotes_file(&content)
+            } else {
+                let default_branch = self
+                    .config
+                    .resolved
+                    .string(config::tree::Init::DEFAULT_BRANCH)
+                    .unwrap_or_else(|| Cow::Borrowed(crate::init::DEFAULT_BRANCH_NAME.into()));
+                match remote::legacy::from_branches_file(&content, name, default_branch.as_ref()) {
+                    Some(definition) => definition,
+                    None => continue,
+                }
+            };
+            let Some(url) = definition.url else {
+                continue;
+            };
+            let url = match gix_url::parse(url.as_ref()) {
+                Ok(url) => url,
+                Err(err) => return Some(Err(find::Error::FileUrl { path, source: err })),
+            };
+            let parse_specs = |specs: Vec<BString>, op: gix_refspec::parse::Operation| {
+                specs
+                    .into_iter()
+                    .map(
+                        |spec| match gix_refspec::parse(spec.as_ref(), op).map(|spec| spec.to_owned()) {
+                            Ok(spec) => Ok(spec),
+                            Err(err) => Err(find::Error::FileRefSpec {
+                                path: path.clone(),
+                                spec,
+                                source: err,
+                            }),
+                        },
+                    )
+                    .collect::<Result<Vec<_>, _>>()
+            };
+            match parse_specs(definition.fetch_specs, gix_refspec::parse::Operation::Fetch) {
+                Ok(specs) => fetch_specs.extend(specs),
+                Err(err) => return Some(Err(err)),
+            }
+            match parse_specs(definition.push_specs, gix_refspec::parse::Operation::Push) {
+                Ok(specs) => push_specs.extend(specs),
+                Err(err) => return Some(Err(err)),
+            }
+            for specs in [&mut fetch_specs, &mut push_specs] {
+                specs.sort();
+                specs.dedup();
+            }
+            return Some(
+                Remote::from_preparsed_config(
+                    Some(name.to_owned()),
+                    Some(url),
+                    None,
+                    fetch_specs,
+                    push_specs,
+                    rewrite_urls,
+                    fetch_tags,
+                    self,
+                )
+                .map_err(Into::into),
+            );
+        }
+        None
+    }
+}
+
+#[cfg(feature = "blocking-network-client")]
+fn fetch_remote(
+    repo: &crate::Repository,
+    name: &BStr,
+    progress: &mut dyn crate::DynNestedProgress,
+    should_interrupt: &std::sync::atomic::AtomicBool,
+) -> Result<remote::fetch::Outcome, remote::group::fetch::Error> {
+    Ok(repo
+        .find_remote(name)?
+        .connect(remote::Direction::Fetch)?
+        .prepare_fetch(&mut *progress, Default::default())?
+        .receive_inner(progress, should_interrupt)?)
 }
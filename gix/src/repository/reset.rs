@@ -0,0 +1,404 @@
+use std::{collections::BTreeSet, path::Path, sync::atomic::AtomicBool};
+
+use gix_hash::ObjectId;
+use gix_index::entry::{Flags, Stage, Stat};
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    Target,
+};
+
+use super::switch::{mode_matches, remove_from_worktree, write_updated_index, Worktree};
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    reset,
+    reset::{Error, Mode, ProgressId},
+    Repository,
+};
+
+/// How a file in the worktree relates to its index entry.
+enum Status {
+    Missing,
+    Unchanged,
+    Modified,
+}
+
+impl Repository {
+    /// Reset `HEAD`, or the branch it points to, to `commit` and update the index and the worktree according to `mode`,
+    /// similar to `git reset <mode> <commit>`.
+    /// The previous commit of `HEAD` is stored in `ORIG_HEAD`, and the reflog receives a `reset: moving to <commit>` entry.
+    ///
+    /// Nothing is changed if the [mode](Mode) refuses to overwrite local changes.
+    /// Use `progress` to learn about the files being written, and `should_interrupt` to stop the operation early.
+    pub fn reset<P>(
+        &self,
+        commit: impl Into<ObjectId>,
+        mode: Mode,
+        mut progress: P,
+        should_interrupt: &AtomicBool,
+    ) -> Result<reset::Outcome, Error>
+    where
+        P: gix_features::progress::NestedProgress,
+        P::SubProgress: gix_features::progress::NestedProgress + 'static,
+    {
+        self.reset_inner(commit.into(), mode, &mut progress, should_interrupt)
+    }
+
+    fn reset_inner(
+        &self,
+        commit: ObjectId,
+        mode: Mode,
+        progress: &mut dyn gix_features::progress::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<reset::Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::reset()", ?mode);
+        let commit = self.find_object(commit)?.peel_to_kind(gix_object::Kind::Commit)?;
+        let (commit, tree) = (commit.id, commit.into_commit().tree_id()?.detach());
+        let previous = self.head()?.try_peel_to_id_in_place()?.map(crate::Id::detach);
+
+        let progress_ids = [ProgressId::CheckoutFiles.into(), ProgressId::BytesWritten.into()];
+        let (checkout, files_removed) = match mode {
+            Mode::Soft => {
+                let index = self.open_index_or_empty()?;
+                let unmerged = unmerged_paths(&index);
+                if !unmerged.is_empty() {
+                    return Err(Error::UnmergedIndex { paths: unmerged });
+                }
+                (None, 0)
+            }
+            Mode::Mixed | Mode::Hard | Mode::Merge => {
+                let workdir = self.work_dir().ok_or(Error::MissingWorktree)?;
+                let (lock, current) = self.lock_and_open_index::<Error>()?;
+                let (checkout, files_removed) = self.reset_index_and_worktree(
+                    workdir,
+                    tree,
+                    mode,
+                    lock,
+                    current,
+                    progress_ids,
+                    progress,
+                    should_interrupt,
+                )?;
+                (checkout, files_removed)
+            }
+            Mode::Keep => {
+                let workdir = self.work_dir().ok_or(Error::MissingWorktree)?;
+                let old_tree = previous.map(|id| self.tree_of_commit(id)).transpose()?;
+                let label = commit.to_hex().to_string();
+                let update = self.two_way_update(
+                    workdir,
+                    old_tree,
+                    tree,
+                    label.as_str().into(),
+                    progress_ids,
+                    progress,
+                    should_interrupt,
+                    false,
+                    false,
+                )?;
+                // Like `git`, reset the index to the commit afterwards, so only changes in the worktree are kept.
+                self.reset_index_and_worktree(
+                    workdir,
+                    tree,
+                    Mode::Mixed,
+                    update.lock,
+                    update.index,
+                    progress_ids,
+                    progress,
+                    should_interrupt,
+                )?;
+                (Some(update.checkout), update.files_removed)
+            }
+        };
+
+        self.update_head_after_reset(commit, previous)?;
+        Ok(reset::Outcome {
+            commit,
+            previous,
+            checkout,
+            files_removed,
+        })
+    }
+
+    /// Reset all index entries matching `patterns` to their version in the tree of `tree_ish`, or remove them if they don't exist there,
+    /// similar to `git reset <tree-ish> -- <pathspec>`. `HEAD` and the worktree are left untouched.
+    ///
+    /// Return the paths of all index entries that were changed, and note that entries that don't change retain their stat information.
+    pub fn reset_paths(
+        &self,
+        tree_ish: impl Into<ObjectId>,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+    ) -> Result<Vec<BString>, reset::paths::Error> {
+        let tree = self
+            .find_object(tree_ish.into())?
+            .peel_to_kind(gix_object::Kind::Tree)?
+            .id;
+        let target = self.index_from_tree(&tree)?.into_parts().0;
+        let (lock, mut index) = self.lock_and_open_index::<reset::paths::Error>()?;
+        let mut pathspec = self.pathspec(
+            false,
+            patterns,
+            true,
+            &index,
+            gix_worktree::stack::state::attributes::Source::IdMapping,
+        )?;
+
+        let mut changed = BTreeSet::<BString>::new();
+        for entry in index.entries() {
+            let path = entry.path(&index);
+            if !pathspec.is_included(path, Some(entry.mode.is_submodule())) {
+                continue;
+            }
+            let is_unchanged = entry.stage() == Stage::Unconflicted
+                && target
+                    .entry_by_path(path)
+                    .map_or(false, |new| new.id == entry.id && new.mode == entry.mode);
+            if !is_unchanged {
+                changed.insert(path.to_owned());
+            }
+        }
+        for entry in target.entries() {
+            let path = entry.path(&target);
+            if !index.entry_by_path(path).map_or(false, |current| {
+                current.stage() == Stage::Unconflicted && current.id == entry.id && current.mode == entry.mode
+            }) && pathspec.is_included(path, Some(entry.mode.is_submodule()))
+            {
+                changed.insert(path.to_owned());
+            }
+        }
+
+        index.remove_entries(|_, path, _| changed.contains(path));
+        for entry in target.entries() {
+            let path = entry.path(&target);
+            if changed.contains(path) {
+                index.dangerously_push_entry(Default::default(), entry.id, Flags::empty(), entry.mode, path);
+            }
+        }
+        index.sort_entries();
+        write_updated_index(&index, lock)?;
+        Ok(changed.into_iter().collect())
+    }
+
+    /// Replace the `current` index with the entries of `tree` and update the worktree at `workdir` if `mode` is [hard](Mode::Hard)
+    /// or [merge](Mode::Merge), and write the index through its `lock`.
+    /// Return the outcome of the checkout, if there was one, along with the amount of removed files.
+    #[allow(clippy::too_many_arguments)]
+    fn reset_index_and_worktree(
+        &self,
+        workdir: &Path,
+        tree: ObjectId,
+        mode: Mode,
+        lock: gix_lock::File,
+        current: gix_index::File,
+        progress_ids: [gix_features::progress::Id; 2],
+        progress: &mut dyn gix_features::progress::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(Option<gix_worktree_state::checkout::Outcome>, usize), Error> {
+        let mut index = gix_index::File::from_state(self.index_from_tree(&tree)?.into_parts().0, self.index_path());
+        let update_worktree = mode != Mode::Mixed;
+
+        let unmerged = unmerged_paths(&current);
+        let is_unmerged = |path: &BStr| unmerged.binary_search_by(|p| p.as_bstr().cmp(path)).is_ok();
+        let (mut pipeline, _) = self.filter_pipeline(None)?;
+        let stat_options = self.stat_options()?;
+        let executable_bit = self.config.fs_capabilities()?.executable_bit;
+        let mut status = |entry: &gix_index::Entry, path: &BStr| {
+            self.worktree_status(
+                workdir,
+                entry,
+                path,
+                &current,
+                &mut pipeline,
+                stat_options,
+                executable_bit,
+            )
+        };
+
+        let (mut local_changes, mut untracked, mut to_remove) = (Vec::new(), Vec::new(), Vec::new());
+        for entry in current.entries() {
+            let path = entry.path(&current);
+            if !update_worktree || index.entry_by_path(path).is_some() || to_remove.last() == Some(&path) {
+                continue;
+            }
+            if mode == Mode::Merge
+                && entry.stage() == Stage::Unconflicted
+                && matches!(status(entry, path)?, Status::Modified)
+            {
+                local_changes.push(path.to_owned());
+            } else if !entry.mode.is_submodule() {
+                to_remove.push(path);
+            }
+        }
+
+        let mut num_updates = 0;
+        let (entries, backing) = index.entries_mut_and_pathbacking();
+        for entry in entries {
+            let path = entry.path_in(backing);
+            let current_entry = if is_unmerged(path) {
+                None
+            } else {
+                current.entry_by_path(path)
+            };
+            let needs_update = match current_entry {
+                Some(current_entry) if current_entry.id == entry.id && current_entry.mode == entry.mode => {
+                    entry.stat = current_entry.stat;
+                    mode == Mode::Hard && !matches!(status(current_entry, path)?, Status::Unchanged)
+                }
+                Some(current_entry) => {
+                    if mode == Mode::Merge && matches!(status(current_entry, path)?, Status::Modified) {
+                        local_changes.push(path.to_owned());
+                    }
+                    true
+                }
+                None => {
+                    if mode == Mode::Merge && !is_unmerged(path) && !matches!(status(&*entry, path)?, Status::Missing) {
+                        untracked.push(path.to_owned());
+                    }
+                    true
+                }
+            };
+            if update_worktree && needs_update {
+                entry.flags.insert(Flags::UPDATE);
+                num_updates += 1;
+            }
+        }
+        if !local_changes.is_empty() {
+            return Err(Error::LocalChanges { paths: local_changes });
+        }
+        if !untracked.is_empty() {
+            return Err(Error::UntrackedFiles { paths: untracked });
+        }
+
+        let mut files_removed = 0;
+        for path in to_remove {
+            let path = workdir.join(gix_path::from_bstr(path));
+            if remove_from_worktree(&path, workdir).map_err(|err| crate::switch::Error::Io { path, source: err })? {
+                files_removed += 1;
+            }
+        }
+        let checkout = if update_worktree {
+            Some(self.checkout_flagged(
+                &mut index,
+                workdir,
+                num_updates,
+                progress_ids,
+                progress,
+                should_interrupt,
+            )?)
+        } else {
+            None
+        };
+        write_updated_index(&index, lock)?;
+        Ok((checkout, files_removed))
+    }
+
+    /// Determine how the file at `path` in `workdir` relates to `entry` of `index`, using stat information if possible.
+    #[allow(clippy::too_many_arguments)]
+    fn worktree_status(
+        &self,
+        workdir: &Path,
+        entry: &gix_index::Entry,
+        path: &BStr,
+        index: &gix_index::State,
+        pipeline: &mut crate::filter::Pipeline<'_>,
+        stat_options: gix_index::entry::stat::Options,
+        executable_bit: bool,
+    ) -> Result<Status, crate::switch::Error> {
+        if entry.mode.is_submodule() {
+            return Ok(Status::Unchanged);
+        }
+        if let Ok(metadata) = gix_index::fs::Metadata::from_path_no_follow(&workdir.join(gix_path::from_bstr(path))) {
+            let kind = if metadata.is_symlink() {
+                gix_object::tree::EntryKind::Link
+            } else if metadata.is_executable() {
+                gix_object::tree::EntryKind::BlobExecutable
+            } else {
+                gix_object::tree::EntryKind::Blob
+            };
+            if !metadata.is_dir()
+                && mode_matches(entry.mode, kind, executable_bit)
+                && Stat::from_fs(&metadata).map_or(false, |stat| {
+                    entry.stat.matches(&stat, stat_options) && !entry.stat.is_racy(index.timestamp(), stat_options)
+                })
+            {
+                return Ok(Status::Unchanged);
+            }
+        }
+        Ok(match self.read_worktree(workdir, path, pipeline, index)? {
+            Worktree::Missing => Status::Missing,
+            Worktree::Directory => Status::Modified,
+            Worktree::File { data, kind } => {
+                if mode_matches(entry.mode, kind, executable_bit)
+                    && gix_object::compute_hash(self.object_hash(), gix_object::Kind::Blob, &data) == entry.id
+                {
+                    Status::Unchanged
+                } else {
+                    Status::Modified
+                }
+            }
+        })
+    }
+
+    fn tree_of_commit(&self, id: ObjectId) -> Result<ObjectId, Error> {
+        Ok(self
+            .find_object(id)?
+            .peel_to_kind(gix_object::Kind::Commit)?
+            .into_commit()
+            .tree_id()?
+            .detach())
+    }
+
+    /// Let `HEAD`, or the branch it points to, point to `commit`, and store `previous` in `ORIG_HEAD`.
+    fn update_head_after_reset(&self, commit: ObjectId, previous: Option<ObjectId>) -> Result<(), Error> {
+        let log = LogChange {
+            mode: RefLog::AndReference,
+            force_create_reflog: false,
+            message: format!("reset: moving to {commit}").into(),
+        };
+        let mut edits = vec![RefEdit {
+            change: Change::Update {
+                log: log.clone(),
+                expected: PreviousValue::Any,
+                new: Target::Peeled(commit),
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: true,
+        }];
+        let orig_head: gix_ref::FullName = "ORIG_HEAD".try_into().expect("valid");
+        match previous {
+            Some(previous) => edits.push(RefEdit {
+                change: Change::Update {
+                    log,
+                    expected: PreviousValue::Any,
+                    new: Target::Peeled(previous),
+                },
+                name: orig_head,
+                deref: false,
+            }),
+            None => {
+                if self.try_find_reference(orig_head.as_ref())?.is_some() {
+                    edits.push(RefEdit {
+                        change: Change::Delete {
+                            expected: PreviousValue::Any,
+                            log: RefLog::AndReference,
+                        },
+                        name: orig_head,
+                        deref: false,
+                    });
+                }
+            }
+        }
+        self.edit_references(edits)?;
+        Ok(())
+    }
+}
+
+fn unmerged_paths(index: &gix_index::State) -> Vec<BString> {
+    let mut paths: Vec<BString> = index
+        .entries()
+        .iter()
+        .filter(|entry| entry.stage() != Stage::Unconflicted)
+        .map(|entry| entry.path(index).to_owned())
+        .collect();
+    paths.dedup();
+    paths
+}
@@ -1,6 +1,12 @@
+use gix_hash::ObjectId;
 use gix_macros::momo;
 
-use crate::{bstr::BStr, revision, Id};
+use crate::{
+    bstr::BStr,
+    ext::ObjectIdExt,
+    repository::{merge_base, merge_base_fork_point},
+    revision, Id,
+};
 
 /// Methods for resolving revisions by spec or working with the commit graph.
 impl crate::Repository {
@@ -45,4 +51,126 @@ impl crate::Repository {
     ) -> revision::walk::Platform<'_> {
         revision::walk::Platform::new(tips, self)
     }
+
+    /// Create a platform to traverse the history of the file at `location` starting at the commit `tip`, following it
+    /// through renames and copies similar to `git log --follow -- <location>`.
+    ///
+    /// `location` is a slash-separated path relative to the root of the repository.
+    #[cfg(feature = "blob-diff")]
+    pub fn file_history(
+        &self,
+        tip: impl Into<gix_hash::ObjectId>,
+        location: impl Into<crate::bstr::BString>,
+    ) -> revision::history::Platform<'_> {
+        revision::history::Platform {
+            repo: self,
+            tip: tip.into(),
+            location: location.into(),
+            rewrites: None,
+            use_commit_graph: None,
+        }
+    }
+
+    /// Return the best merge-base between the `one` and `two` commits, or an error if there is none, similar to `git merge-base`.
+    ///
+    /// Use [`merge_bases_many()`](Self::merge_bases_many()) to obtain all merge-bases if there are more than one,
+    /// which can happen with criss-cross merges.
+    pub fn merge_base(&self, one: impl Into<ObjectId>, two: impl Into<ObjectId>) -> Result<Id<'_>, merge_base::Error> {
+        let (first, second) = (one.into(), two.into());
+        let bases = self.merge_bases_many(first, &[second])?;
+        bases
+            .into_iter()
+            .next()
+            .ok_or(merge_base::Error::NotFound { first, second })
+    }
+
+    /// Return all merge-bases between `one` and a hypothetical merge of all `others`, sorted from best to worst,
+    /// similar to `git merge-base --all <one> <others>…`.
+    ///
+    /// The returned list is empty if there is no merge-base.
+    pub fn merge_bases_many(
+        &self,
+        one: impl Into<ObjectId>,
+        others: &[ObjectId],
+    ) -> Result<Vec<Id<'_>>, merge_base::Error> {
+        let mut graph = self.revision_graph();
+        let bases = gix_revision::merge_base(one.into(), others, &mut graph)?;
+        Ok(self.attach_ids(bases.unwrap_or_default()))
+    }
+
+    /// Return the merge-bases of all `commits`, i.e. the best commits that are reachable from all of them, similar to
+    /// `git merge-base --octopus --all`.
+    ///
+    /// The returned list is empty if there is no merge-base.
+    pub fn merge_bases_octopus(
+        &self,
+        commits: impl IntoIterator<Item = impl Into<ObjectId>>,
+    ) -> Result<Vec<Id<'_>>, merge_base::Error> {
+        let commits: Vec<_> = commits.into_iter().map(Into::into).collect();
+        let mut graph = self.revision_graph();
+        let bases = gix_revision::merge_base::octopus(&commits, &mut graph)?;
+        Ok(self.attach_ids(bases.unwrap_or_default()))
+    }
+
+    /// Return only those `commits` that can't be reached from any of the other `commits`, in order and without duplicates,
+    /// similar to `git merge-base --independent`.
+    pub fn independent_commits(
+        &self,
+        commits: impl IntoIterator<Item = impl Into<ObjectId>>,
+    ) -> Result<Vec<Id<'_>>, merge_base::Error> {
+        let commits: Vec<_> = commits.into_iter().map(Into::into).collect();
+        let mut graph = self.revision_graph();
+        Ok(self.attach_ids(gix_revision::merge_base::independent(&commits, &mut graph)?))
+    }
+
+    /// Return `true` if the `ancestor` commit is reachable from the `descendant` commit or if both are the same,
+    /// similar to `git merge-base --is-ancestor`.
+    ///
+    /// This is useful to check if the `descendant` can be fast-forwarded to from `ancestor`.
+    pub fn is_ancestor(
+        &self,
+        ancestor: impl Into<ObjectId>,
+        descendant: impl Into<ObjectId>,
+    ) -> Result<bool, merge_base::Error> {
+        let mut graph = self.revision_graph();
+        Ok(gix_revision::merge_base::is_ancestor(
+            ancestor.into(),
+            descendant.into(),
+            &mut graph,
+        )?)
+    }
+
+    /// Return the point at which `commit` forked from the `upstream` reference, taking into consideration all previous values of
+    /// `upstream` as stored in its reference log, similar to `git merge-base --fork-point <upstream> <commit>`.
+    ///
+    /// This finds the fork-point even if `upstream` was rewritten since, and returns `None` if no such point could be determined.
+    pub fn merge_base_fork_point<'a, Name, E>(
+        &self,
+        upstream: Name,
+        commit: impl Into<ObjectId>,
+    ) -> Result<Option<Id<'_>>, merge_base_fork_point::Error>
+    where
+        Name: TryInto<&'a gix_ref::PartialNameRef, Error = E>,
+        gix_ref::file::find::Error: From<E>,
+    {
+        let mut upstream = self.find_reference(upstream)?;
+        let mut tips = Vec::new();
+        if let Some(log) = upstream.log_iter().all()? {
+            for line in log {
+                tips.push(line?.new_oid());
+            }
+        }
+        let tip = upstream.peel_to_id_in_place()?.detach();
+        if !tips.contains(&tip) {
+            tips.push(tip);
+        }
+        tips.retain(|id| !id.is_null());
+
+        let mut graph = self.revision_graph();
+        Ok(gix_revision::merge_base::fork_point(commit.into(), &tips, &mut graph)?.map(|id| id.attach(self)))
+    }
+
+    fn attach_ids(&self, ids: Vec<ObjectId>) -> Vec<Id<'_>> {
+        ids.into_iter().map(|id| id.attach(self)).collect()
+    }
 }
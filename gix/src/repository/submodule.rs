@@ -30,7 +30,8 @@ impl Repository {
     /// has become stale as the underlying file on disk has changed. The snapshot based on the file on disk is shared across all
     /// clones of this repository.
     ///
-    /// If a file on disk isn't present, we will try to load it from the index, and finally from the current tree.
+    /// If a file on disk isn't present, we will try to load it from the index, and finally from the current tree,
+    /// unless `HEAD` is unborn.
     /// In the latter two cases, the result will not be cached in this repository instance as we can't detect freshness anymore,
     /// so time this method is called a new [modules file](submodule::ModulesSnapshot) will be created.
     ///
@@ -53,6 +54,13 @@ impl Repository {
                         .map(|entry| entry.id)
                 }) {
                     Some(id) => id,
+                    None if self
+                        .head()
+                        .map_err(crate::reference::head_commit::Error::from)?
+                        .is_unborn() =>
+                    {
+                        return Ok(None)
+                    }
                     None => match self
                         .head_commit()?
                         .tree()?
@@ -0,0 +1,632 @@
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use gix_diff::patch;
+use gix_filter::{
+    driver::apply::{Delay, MaybeDelayed},
+    pipeline::convert::{ToGitOutcome, ToWorktreeOutcome},
+};
+use gix_hash::ObjectId;
+use gix_index::entry::{Flags, Mode};
+use gix_object::tree::EntryKind;
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    Target,
+};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    switch,
+    switch::{Error, MergedFile, ProgressId},
+    Progress, Repository,
+};
+
+/// An entry as it is stored in a tree or in the index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Version {
+    pub(super) id: ObjectId,
+    pub(super) mode: Mode,
+}
+
+impl From<&gix_index::Entry> for Version {
+    fn from(entry: &gix_index::Entry) -> Self {
+        Version {
+            id: entry.id,
+            mode: entry.mode,
+        }
+    }
+}
+
+/// What's currently in the worktree at a path.
+pub(super) enum Worktree {
+    Missing,
+    Directory,
+    File { data: Vec<u8>, kind: EntryKind },
+}
+
+/// The result of [`Repository::two_way_update()`].
+pub(super) struct TwoWayOutcome {
+    pub(super) checkout: gix_worktree_state::checkout::Outcome,
+    pub(super) files_removed: usize,
+    pub(super) merged: Vec<MergedFile>,
+    /// The updated index, which still has to be written with [`write_updated_index()`].
+    pub(super) index: gix_index::File,
+    /// The lock of the index, held since it was read.
+    pub(super) lock: gix_lock::File,
+}
+
+/// What to do with a path that changed between the current and the target tree.
+enum Action {
+    /// Replace the index entry with its version in the target, or remove it, and update the worktree accordingly.
+    Take,
+    /// Use the version in the target for the index, and write `data` as result of merging local changes into the worktree.
+    Merge { data: Vec<u8>, num_conflicts: usize },
+}
+
+impl Repository {
+    /// Switch to `target` by updating the index and the worktree from the tree of `HEAD` to the tree of the target,
+    /// and let `HEAD` point to it afterwards, similar to `git switch` or `git checkout <branch>`.
+    /// The reflog of `HEAD` receives a `checkout: moving from <old> to <new>` entry.
+    ///
+    /// Only files that differ between both trees are written to the worktree using the filter pipeline, or removed from it.
+    /// Local changes to all other files are kept, but local changes to files that would be changed cause an error unless
+    /// they are [discarded](switch::Options::force) or [merged](switch::Options::merge).
+    ///
+    /// The index is locked while the worktree is updated, and all checks happen before anything is written, so these
+    /// errors leave the repository untouched. Errors while writing files may leave the worktree partially updated
+    /// though, in which case the index and `HEAD` remain unchanged.
+    ///
+    /// Use `progress` to learn about the files being written, and `should_interrupt` to stop the operation early.
+    pub fn switch<P>(
+        &self,
+        target: switch::Target,
+        mut progress: P,
+        should_interrupt: &AtomicBool,
+        options: switch::Options,
+    ) -> Result<switch::Outcome, Error>
+    where
+        P: gix_features::progress::NestedProgress,
+        P::SubProgress: gix_features::progress::NestedProgress + 'static,
+    {
+        self.switch_inner(target, &mut progress, should_interrupt, options)
+    }
+
+    fn switch_inner(
+        &self,
+        target: switch::Target,
+        progress: &mut dyn gix_features::progress::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: switch::Options,
+    ) -> Result<switch::Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::switch()");
+        let workdir = self.work_dir().ok_or(Error::MissingWorktree)?;
+
+        let (commit, target_name) = match &target {
+            switch::Target::Branch(name) => (
+                self.find_reference(name.as_ref())?.peel_to_id_in_place()?.detach(),
+                name.shorten().to_owned(),
+            ),
+            switch::Target::Detached(id) => (*id, id.to_hex().to_string().into()),
+        };
+        let commit = self.find_object(commit)?.peel_to_kind(gix_object::Kind::Commit)?;
+        let (commit, new_tree) = (commit.id, commit.into_commit().tree_id()?.detach());
+        if let Some(name) = &options.new_branch {
+            if self.try_find_reference(name.as_ref())?.is_some() {
+                return Err(Error::BranchExists { name: name.clone() });
+            }
+        }
+
+        let mut head = self.head()?;
+        let previous_name: BString = match head.referent_name() {
+            Some(name) => name.shorten().to_owned(),
+            None => head.id().map(|id| id.to_hex().to_string().into()).unwrap_or_default(),
+        };
+        let old_tree = match head.try_peel_to_id_in_place()? {
+            Some(id) => Some(
+                id.object()?
+                    .peel_to_kind(gix_object::Kind::Commit)?
+                    .into_commit()
+                    .tree_id()?
+                    .detach(),
+            ),
+            None => None,
+        };
+
+        let update = self.two_way_update(
+            workdir,
+            old_tree,
+            new_tree,
+            target_name.as_ref(),
+            [ProgressId::CheckoutFiles.into(), ProgressId::BytesWritten.into()],
+            progress,
+            should_interrupt,
+            options.force,
+            options.merge,
+        )?;
+        write_updated_index(&update.index, update.lock)?;
+        self.update_head_after_switch(
+            &target,
+            commit,
+            previous_name.as_ref(),
+            target_name.as_ref(),
+            options.new_branch,
+        )?;
+        Ok(switch::Outcome {
+            commit,
+            checkout: update.checkout,
+            files_removed: update.files_removed,
+            merged: update.merged,
+        })
+    }
+
+    /// Perform a two-way merge from `old_tree` to `new_tree` into the index and the worktree at `workdir`.
+    /// `label` is used for conflict markers of merged files.
+    ///
+    /// Only paths that differ between both trees are changed, and local changes to these cause an error
+    /// unless `force` or `merge` is set. `progress_ids` are used for the amount of files and bytes written, in that order.
+    /// The index remains locked and is returned for the caller to write it.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn two_way_update(
+        &self,
+        workdir: &Path,
+        old_tree: Option<ObjectId>,
+        new_tree: ObjectId,
+        label: &BStr,
+        progress_ids: [gix_features::progress::Id; 2],
+        progress: &mut dyn gix_features::progress::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        force: bool,
+        merge: bool,
+    ) -> Result<TwoWayOutcome, Error> {
+        let old = match old_tree {
+            Some(tree) => self.index_from_tree(&tree)?.into_parts().0,
+            None => gix_index::State::new(self.object_hash()),
+        };
+        let new = self.index_from_tree(&new_tree)?.into_parts().0;
+        let (lock, mut index) = self.lock_and_open_index::<Error>()?;
+
+        let mut unmerged: Vec<BString> = index
+            .entries()
+            .iter()
+            .filter(|entry| entry.stage() != gix_index::entry::Stage::Unconflicted)
+            .map(|entry| entry.path(&index).to_owned())
+            .collect();
+        unmerged.dedup();
+        if !unmerged.is_empty() && !force {
+            return Err(Error::UnmergedIndex { paths: unmerged });
+        }
+
+        // All paths that differ between both trees, along with their versions in the old and the new tree.
+        let mut changes = BTreeMap::<BString, (Option<Version>, Option<Version>)>::new();
+        for entry in old.entries() {
+            changes.insert(entry.path(&old).to_owned(), (Some(entry.into()), None));
+        }
+        for entry in new.entries() {
+            changes.entry(entry.path(&new).to_owned()).or_default().1 = Some(entry.into());
+        }
+        changes.retain(|_, (old, new)| old != new);
+        for path in &unmerged {
+            changes.entry(path.clone()).or_insert_with(|| {
+                let version = |state: &gix_index::State| state.entry_by_path(path.as_ref()).map(Version::from);
+                (version(&old), version(&new))
+            });
+        }
+
+        let (mut pipeline, _) = self.filter_pipeline(None)?;
+        let executable_bit = self.config.fs_capabilities()?.executable_bit;
+        let mut actions = BTreeMap::<BString, Action>::new();
+        let (mut local_changes, mut untracked) = (Vec::new(), Vec::new());
+        for (path, (old, new)) in &changes {
+            let path = path.as_bstr();
+            let is_unmerged = unmerged.binary_search_by(|p| p.as_bstr().cmp(path)).is_ok();
+            let current = if is_unmerged {
+                None
+            } else {
+                index.entry_by_path(path).map(Version::from)
+            };
+            if !is_unmerged && current == *new {
+                continue;
+            }
+            if is_unmerged || force {
+                actions.insert(path.to_owned(), Action::Take);
+                continue;
+            }
+
+            let worktree = self.read_worktree(workdir, path, &mut pipeline, &index)?;
+            let is_modified = match (current, &worktree) {
+                (_, Worktree::Missing) => false,
+                (current, Worktree::Directory) => current.map_or(true, |current| current.mode != Mode::COMMIT),
+                (Some(current), Worktree::File { data, kind }) => {
+                    !mode_matches(current.mode, *kind, executable_bit)
+                        || gix_object::compute_hash(self.object_hash(), gix_object::Kind::Blob, data) != current.id
+                }
+                (None, Worktree::File { data, kind }) => !new.map_or(false, |new| {
+                    mode_matches(new.mode, *kind, executable_bit)
+                        && gix_object::compute_hash(self.object_hash(), gix_object::Kind::Blob, data) == new.id
+                }),
+            };
+            if current == *old && !is_modified {
+                actions.insert(path.to_owned(), Action::Take);
+                continue;
+            }
+
+            match (old, current, new, &worktree) {
+                (Some(old), Some(current), Some(new), worktree)
+                    if merge && [old.mode, current.mode, new.mode].iter().all(|mode| is_file(*mode)) =>
+                {
+                    let ours = match worktree {
+                        Worktree::File { data, kind } if *kind != EntryKind::Link => data.clone(),
+                        Worktree::Missing => self.find_object(current.id)?.detach().data,
+                        Worktree::File { .. } | Worktree::Directory => {
+                            local_changes.push(path.to_owned());
+                            continue;
+                        }
+                    };
+                    let base = self.find_object(old.id)?.detach().data;
+                    let theirs = self.find_object(new.id)?.detach().data;
+                    let merged = patch::merge::three_way(
+                        &base,
+                        &ours,
+                        &theirs,
+                        patch::merge::Labels {
+                            ours: "local",
+                            theirs: label.to_str_lossy().as_ref(),
+                        },
+                        self.config.diff_algorithm()?,
+                    );
+                    actions.insert(
+                        path.to_owned(),
+                        Action::Merge {
+                            data: merged.data,
+                            num_conflicts: merged.num_conflicts,
+                        },
+                    );
+                }
+                (None, None, _, _) => untracked.push(path.to_owned()),
+                _ => local_changes.push(path.to_owned()),
+            }
+        }
+        if !local_changes.is_empty() {
+            return Err(Error::LocalChanges { paths: local_changes });
+        }
+        if !untracked.is_empty() {
+            return Err(Error::UntrackedFiles { paths: untracked });
+        }
+
+        let mut files_removed = 0;
+        for (path, action) in &actions {
+            let (old, new) = changes[path];
+            if new.is_some() || !matches!(action, Action::Take) {
+                continue;
+            }
+            if old.map_or(false, |old| old.mode == Mode::COMMIT) {
+                continue;
+            }
+            let path = workdir.join(gix_path::from_bstr(path.as_bstr()));
+            if remove_from_worktree(&path, workdir).map_err(|err| Error::Io { path, source: err })? {
+                files_removed += 1;
+            }
+        }
+
+        index.remove_entries(|_, path, _| actions.contains_key(path));
+        let mut num_updates = 0;
+        for (path, action) in &actions {
+            let Some(new) = changes[path].1 else {
+                continue;
+            };
+            let flags = match action {
+                Action::Take => {
+                    num_updates += 1;
+                    Flags::UPDATE
+                }
+                Action::Merge { .. } => Flags::empty(),
+            };
+            index.dangerously_push_entry(Default::default(), new.id, flags, new.mode, path.as_ref());
+        }
+        index.sort_entries();
+
+        let checkout = self.checkout_flagged(
+            &mut index,
+            workdir,
+            num_updates,
+            progress_ids,
+            progress,
+            should_interrupt,
+        )?;
+
+        let mut merged = Vec::new();
+        for (path, action) in &actions {
+            let Action::Merge { data, num_conflicts } = action else {
+                continue;
+            };
+            let worktree_path = workdir.join(gix_path::from_bstr(path.as_bstr()));
+            let executable = changes[path].1.map_or(false, |new| new.mode == Mode::FILE_EXECUTABLE);
+            write_to_worktree(&worktree_path, data, path.as_ref(), executable, &mut pipeline)?;
+            merged.push(MergedFile {
+                path: path.clone(),
+                num_conflicts: *num_conflicts,
+            });
+        }
+
+        Ok(TwoWayOutcome {
+            checkout,
+            files_removed,
+            merged,
+            index,
+            lock,
+        })
+    }
+
+    /// Lock the index so nobody else can change it until the returned lock is committed with [`write_updated_index()`]
+    /// or dropped, and read it afterwards, or create an empty one in memory if it doesn't exist yet.
+    pub(super) fn lock_and_open_index<E>(&self) -> Result<(gix_lock::File, gix_index::File), E>
+    where
+        E: From<gix_index::file::write::Error> + From<crate::worktree::open_index::Error>,
+    {
+        let lock =
+            gix_lock::File::acquire_to_update_resource(self.index_path(), gix_lock::acquire::Fail::Immediately, None)
+                .map_err(gix_index::file::write::Error::from)?;
+        Ok((lock, self.open_index_or_empty()?))
+    }
+
+    /// Open the index, or create an empty one in memory if it doesn't exist yet.
+    pub(super) fn open_index_or_empty(&self) -> Result<gix_index::File, crate::worktree::open_index::Error> {
+        Ok(if self.index_path().is_file() {
+            self.open_index()?
+        } else {
+            gix_index::File::from_state(gix_index::State::new(self.object_hash()), self.index_path())
+        })
+    }
+
+    /// Check out all entries in `index` that are flagged for update into `workdir`, while overwriting what's there.
+    /// `progress_ids` are used for the amount of files and bytes written, in that order.
+    pub(super) fn checkout_flagged(
+        &self,
+        index: &mut gix_index::File,
+        workdir: &Path,
+        num_updates: usize,
+        progress_ids: [gix_features::progress::Id; 2],
+        progress: &mut dyn gix_features::progress::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<gix_worktree_state::checkout::Outcome, Error> {
+        let mut opts = self
+            .config
+            .checkout_options(self, gix_worktree::stack::state::attributes::Source::IdMapping)?;
+        opts.only_entries_flagged_for_update = true;
+        opts.overwrite_existing = true;
+
+        let mut files = progress.add_child_with_id("checkout".to_string(), progress_ids[0]);
+        let mut bytes = progress.add_child_with_id("writing".to_string(), progress_ids[1]);
+        files.init(Some(num_updates), crate::progress::count("files"));
+        bytes.init(None, crate::progress::bytes());
+
+        let start = std::time::Instant::now();
+        let outcome = gix_worktree_state::checkout(
+            index,
+            workdir,
+            self.objects.clone().into_arc().map_err(Error::OpenArcOdb)?,
+            &files,
+            &bytes,
+            should_interrupt,
+            opts,
+        )?;
+        files.show_throughput(start);
+        bytes.show_throughput(start);
+        Ok(outcome)
+    }
+
+    /// Read what's in the worktree at `rela_path`, converted to what it would be in `git` with `pipeline`.
+    pub(super) fn read_worktree(
+        &self,
+        workdir: &Path,
+        rela_path: &BStr,
+        pipeline: &mut crate::filter::Pipeline<'_>,
+        index: &gix_index::State,
+    ) -> Result<Worktree, Error> {
+        let path = workdir.join(gix_path::from_bstr(rela_path));
+        let io_err = |source| Error::Io {
+            path: path.clone(),
+            source,
+        };
+        let metadata = match std::fs::symlink_metadata(&path) {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Worktree::Missing),
+            Err(err) => return Err(io_err(err)),
+        };
+        Ok(if metadata.is_dir() {
+            Worktree::Directory
+        } else if metadata.is_symlink() {
+            Worktree::File {
+                data: gix_path::into_bstr(std::fs::read_link(&path).map_err(io_err)?)
+                    .into_owned()
+                    .into(),
+                kind: EntryKind::Link,
+            }
+        } else {
+            let file = std::fs::File::open(&path).map_err(io_err)?;
+            let mut data = Vec::new();
+            match pipeline.convert_to_git(file, gix_path::from_bstr(rela_path).as_ref(), index)? {
+                ToGitOutcome::Unchanged(mut file) => file.read_to_end(&mut data).map(|_| ()),
+                ToGitOutcome::Process(mut stream) => stream.read_to_end(&mut data).map(|_| ()),
+                ToGitOutcome::Buffer(buf) => {
+                    data.extend_from_slice(buf);
+                    Ok(())
+                }
+            }
+            .map_err(io_err)?;
+            Worktree::File {
+                data,
+                kind: if gix_fs::is_executable(&metadata) {
+                    EntryKind::BlobExecutable
+                } else {
+                    EntryKind::Blob
+                },
+            }
+        })
+    }
+
+    /// Create the new branch if requested, and let `HEAD` point to `commit` or the branch.
+    fn update_head_after_switch(
+        &self,
+        target: &switch::Target,
+        commit: ObjectId,
+        previous_name: &BStr,
+        target_name: &BStr,
+        new_branch: Option<gix_ref::FullName>,
+    ) -> Result<(), Error> {
+        let branch = match new_branch {
+            Some(name) => {
+                self.reference(
+                    name.clone(),
+                    commit,
+                    PreviousValue::MustNotExist,
+                    format!("branch: Created from {target_name}"),
+                )?;
+                Some(name)
+            }
+            None => match target {
+                switch::Target::Branch(name) => Some(name.clone()),
+                switch::Target::Detached(_) => None,
+            },
+        };
+        let message = format!(
+            "checkout: moving from {previous_name} to {}",
+            branch.as_ref().map_or(target_name, |name| name.shorten())
+        );
+        let head_edit = |new: Target| RefEdit {
+            change: Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: message.clone().into(),
+                },
+                expected: PreviousValue::Any,
+                new,
+            },
+            name: "HEAD".try_into().expect("valid"),
+            deref: false,
+        };
+        match branch {
+            Some(branch) => {
+                // Symbolic refs don't receive reflog entries on change, so the move is logged by detaching `HEAD` first,
+                // which also records the commit it pointed to previously.
+                self.edit_reference(head_edit(Target::Peeled(commit)))?;
+                self.edit_reference(head_edit(Target::Symbolic(branch)))?;
+            }
+            None => {
+                self.edit_reference(head_edit(Target::Peeled(commit)))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write `index` into `lock` obtained by [`Repository::lock_and_open_index()`] and commit it.
+pub(super) fn write_updated_index(
+    index: &gix_index::File,
+    lock: gix_lock::File,
+) -> Result<(), gix_index::file::write::Error> {
+    let mut out = std::io::BufWriter::with_capacity(64 * 1024, lock);
+    index.write_to(
+        &mut out,
+        gix_index::write::Options {
+            extensions: gix_index::write::Extensions::Given {
+                // The tree-cache would be outdated after our changes.
+                tree_cache: false,
+                end_of_index_entry: true,
+                untracked_cache: false,
+                fs_monitor: false,
+            },
+            skip_hash: false,
+        },
+    )?;
+    out.into_inner()
+        .map_err(std::io::IntoInnerError::into_error)?
+        .commit()?;
+    Ok(())
+}
+
+fn is_file(mode: Mode) -> bool {
+    mode == Mode::FILE || mode == Mode::FILE_EXECUTABLE
+}
+
+/// Return `true` if `mode` of an index entry is compatible with `kind` of a file in the worktree.
+pub(super) fn mode_matches(mode: Mode, kind: EntryKind, executable_bit: bool) -> bool {
+    match kind {
+        EntryKind::Link => mode == Mode::SYMLINK,
+        EntryKind::BlobExecutable if executable_bit => mode == Mode::FILE_EXECUTABLE,
+        EntryKind::Blob if executable_bit => mode == Mode::FILE,
+        _ => is_file(mode),
+    }
+}
+
+/// Remove the file at `path` and all directories that are empty afterwards, up to `workdir`, and return `true`
+/// if there was a file to remove.
+pub(super) fn remove_from_worktree(path: &Path, workdir: &Path) -> std::io::Result<bool> {
+    let existed = match std::fs::remove_file(path) {
+        Ok(()) => true,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => false,
+        Err(err) => return Err(err),
+    };
+    let mut dir: Option<PathBuf> = path.parent().map(ToOwned::to_owned);
+    while let Some(current) = dir.filter(|dir| dir.as_path() != workdir && dir.starts_with(workdir)) {
+        if std::fs::remove_dir(&current).is_err() {
+            break;
+        }
+        dir = current.parent().map(ToOwned::to_owned);
+    }
+    Ok(existed)
+}
+
+/// Write `data` in its `git` representation to `path` after converting it with `pipeline`.
+fn write_to_worktree(
+    path: &Path,
+    data: &[u8],
+    rela_path: &BStr,
+    executable: bool,
+    pipeline: &mut crate::filter::Pipeline<'_>,
+) -> Result<(), Error> {
+    let io_err = |source| Error::Io {
+        path: path.to_owned(),
+        source,
+    };
+    if std::fs::symlink_metadata(path).map_or(false, |meta| meta.is_symlink()) {
+        std::fs::remove_file(path).map_err(io_err)?;
+    }
+    let mut file = std::fs::File::create(path).map_err(io_err)?;
+    match pipeline.convert_to_worktree(data, rela_path, Delay::Forbid)? {
+        ToWorktreeOutcome::Unchanged(buf) | ToWorktreeOutcome::Buffer(buf) => file.write_all(buf),
+        ToWorktreeOutcome::Process(MaybeDelayed::Immediate(mut stream)) => {
+            std::io::copy(&mut stream, &mut file).map(|_| ())
+        }
+        ToWorktreeOutcome::Process(MaybeDelayed::Delayed(_)) => unreachable!("we prohibit this"),
+    }
+    .map_err(io_err)?;
+    set_executable(path, executable).map_err(io_err)
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path, executable: bool) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = std::fs::metadata(path)?.permissions();
+    let mode = permissions.mode();
+    let new_mode = if executable {
+        mode | ((mode & 0o444) >> 2)
+    } else {
+        mode & !0o111
+    };
+    if new_mode != mode {
+        permissions.set_mode(new_mode);
+        std::fs::set_permissions(path, permissions)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path, _executable: bool) -> std::io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,146 @@
+//! Reset `HEAD`, the index and possibly the worktree to a commit, similar to `git reset`.
+//!
+//! This is what [`Repository::reset()`](crate::Repository::reset()) does for all [modes](Mode) of `git reset`,
+//! while [`Repository::reset_paths()`](crate::Repository::reset_paths()) resets only selected index entries
+//! like `git reset <tree-ish> -- <pathspec>`.
+use gix_hash::ObjectId;
+
+use crate::{bstr::BString, switch};
+
+/// How to reset the index and the worktree, with each mode corresponding to a flag of `git reset`.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// Only move `HEAD`, but leave the index and the worktree untouched, like `--soft`.
+    Soft,
+    /// Move `HEAD` and reset the index to the tree of the commit, but leave the worktree untouched, like `--mixed`.
+    ///
+    /// Index entries that don't change retain their stat information.
+    #[default]
+    Mixed,
+    /// Move `HEAD` and reset the index and the worktree to the tree of the commit, discarding all local changes to
+    /// tracked files, like `--hard`.
+    ///
+    /// Untracked files that are in the way are overwritten, but all other untracked files are kept.
+    Hard,
+    /// Like [`Hard`](Mode::Hard), but keep local changes which aren't staged, like `--merge`.
+    ///
+    /// The operation is aborted if a file that differs between the index and the commit has local changes,
+    /// or if untracked files are in the way. Unresolved conflicts in the index are discarded.
+    Merge,
+    /// Move `HEAD` and update the worktree with the files that changed between `HEAD` and the commit,
+    /// while keeping all other local changes in the worktree, like `--keep`.
+    ///
+    /// The operation is aborted if any file that changed between `HEAD` and the commit has local changes.
+    /// Otherwise the index is reset to the tree of the commit like with [`Mixed`](Mode::Mixed), which unstages all
+    /// other staged changes while keeping them in the worktree.
+    Keep,
+}
+
+/// The outcome of [`Repository::reset()`](crate::Repository::reset()).
+#[derive(Debug)]
+pub struct Outcome {
+    /// The id of the commit that `HEAD` points to now.
+    pub commit: ObjectId,
+    /// The id of the commit that `HEAD` pointed to before, which is now stored in `ORIG_HEAD`, or `None` if `HEAD` was unborn.
+    pub previous: Option<ObjectId>,
+    /// Information about the files written to the worktree, if the [mode](Mode) changes the worktree.
+    pub checkout: Option<gix_worktree_state::checkout::Outcome>,
+    /// The amount of files that were removed from the worktree as they don't exist in the commit.
+    pub files_removed: usize,
+}
+
+/// The progress ids used in [`Repository::reset()`](crate::Repository::reset()).
+///
+/// Use this information to selectively extract the progress of interest in case the parent application has custom visualization.
+#[derive(Debug, Copy, Clone)]
+pub enum ProgressId {
+    /// The amount of files checked out thus far.
+    CheckoutFiles,
+    /// The amount of bytes written in total, the aggregate of the size of the content of all files thus far.
+    BytesWritten,
+}
+
+impl From<ProgressId> for gix_features::progress::Id {
+    fn from(v: ProgressId) -> Self {
+        match v {
+            ProgressId::CheckoutFiles => *b"RSCF",
+            ProgressId::BytesWritten => *b"RSCB",
+        }
+    }
+}
+
+/// The error returned by [`Repository::reset()`](crate::Repository::reset()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A worktree is required to reset the index or the worktree")]
+    MissingWorktree,
+    #[error("The index has unresolved conflicts at {paths:?}")]
+    UnmergedIndex { paths: Vec<BString> },
+    #[error("Local changes to {paths:?} would be overwritten")]
+    LocalChanges { paths: Vec<BString> },
+    #[error("Untracked files at {paths:?} would be overwritten")]
+    UntrackedFiles { paths: Vec<BString> },
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    FindReferenceOptional(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    PeelHead(#[from] crate::head::peel::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error(transparent)]
+    StatOptions(#[from] crate::config::stat_options::Error),
+    #[error(transparent)]
+    BooleanConfig(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error("Could not update the worktree")]
+    UpdateWorktree(#[source] switch::Error),
+}
+
+impl From<switch::Error> for Error {
+    fn from(err: switch::Error) -> Self {
+        match err {
+            switch::Error::MissingWorktree => Error::MissingWorktree,
+            switch::Error::UnmergedIndex { paths } => Error::UnmergedIndex { paths },
+            switch::Error::LocalChanges { paths } => Error::LocalChanges { paths },
+            switch::Error::UntrackedFiles { paths } => Error::UntrackedFiles { paths },
+            err => Error::UpdateWorktree(err),
+        }
+    }
+}
+
+///
+#[allow(clippy::empty_docs)]
+pub mod paths {
+    /// The error returned by [`Repository::reset_paths()`](crate::Repository::reset_paths()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        PeelToTree(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+        #[error(transparent)]
+        Pathspec(#[from] crate::pathspec::init::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::HashSet;
+
+use gix_hash::ObjectId;
+use gix_object::FindExt;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    diff::Rewrites,
+    object::tree::diff::{change::Event, Action},
+    Repository,
+};
+
+/// The error returned by [`Platform::all()`] and the [`Iter`] it creates.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    RewritesConfig(#[from] crate::diff::new_rewrites::Error),
+    #[error(transparent)]
+    FindCommit(#[from] gix_object::find::existing_object::Error),
+    #[error(transparent)]
+    FindTree(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    TreeConversion(#[from] crate::object::try_into::Error),
+    #[error("Could not diff trees to find the source of a rename")]
+    TreeDiff(#[from] crate::object::tree::diff::for_each::Error),
+}
+
+/// The way the tracked path changed in a commit, compared to its parent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// The path was added without a known source, so history stops here.
+    Addition,
+    /// The path was deleted.
+    Deletion,
+    /// The path was modified, either in content or mode or both.
+    ///
+    /// For merges, this means the path differs from all of its parents.
+    Modification,
+    /// The path was renamed or copied from `source_location`, possibly with modifications, and history continues
+    /// with `source_location` in the parent.
+    Rewrite {
+        /// The location of the path in the parent commit.
+        source_location: BString,
+        /// If `true`, the path was copied, otherwise it was renamed.
+        copy: bool,
+    },
+}
+
+/// A commit that changed the tracked path, as returned by [`Iter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item {
+    /// The id of the commit that changed the path.
+    pub id: ObjectId,
+    /// The time at which the commit was committed, in seconds since the unix epoch.
+    pub commit_time: gix_date::SecondsSinceUnixEpoch,
+    /// The location of the tracked path in this commit.
+    pub location: BString,
+    /// How the path changed compared to the parent.
+    pub change: Change,
+}
+
+/// A platform to configure the traversal of the history of a single path,
+/// returned by [`Repository::file_history()`].
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo Repository,
+    pub(crate) tip: ObjectId,
+    pub(crate) location: BString,
+    pub(crate) rewrites: Option<Option<Rewrites>>,
+    pub(crate) use_commit_graph: Option<bool>,
+}
+
+/// Create-time builder methods
+impl<'repo> Platform<'repo> {
+    /// Provide `None` to disable following renames and copies, or `Some(<configuration>)` to control how they are detected.
+    ///
+    /// By default, the git configuration determines rewrite tracking, with git defaults being used if nothing is configured,
+    /// which follows renames with 50% similarity, but not copies.
+    pub fn track_rewrites(mut self, rewrites: Option<Rewrites>) -> Self {
+        self.rewrites = Some(rewrites);
+        self
+    }
+
+    /// Allow using the commit-graph and its changed-path Bloom filters, if present, if `toggle` is `true`, or disallow
+    /// it with `false`. Set it to `None` to leave control over this to the configuration of `core.commitGraph` (the default).
+    pub fn use_commit_graph(mut self, toggle: impl Into<Option<bool>>) -> Self {
+        self.use_commit_graph = toggle.into();
+        self
+    }
+}
+
+/// Produce the iterator
+impl<'repo> Platform<'repo> {
+    /// Return an iterator over all commits that changed the tracked path, from newest to oldest, following the path
+    /// through renames and copies similar to `git log --follow -- <path>`.
+    ///
+    /// Like `git`, merges that didn't change the path compared to one of their parents are simplified by following only
+    /// that parent.
+    pub fn all(self) -> Result<Iter<'repo>, Error> {
+        let Platform {
+            repo,
+            tip,
+            location,
+            rewrites,
+            use_commit_graph,
+        } = self;
+        let rewrites = match rewrites {
+            Some(rewrites) => rewrites,
+            None => repo.config.diff_renames()?.unwrap_or_default().into(),
+        };
+        let commit_graph = use_commit_graph
+            .map_or_else(|| repo.config.may_use_commit_graph(), Ok)?
+            .then(|| repo.commit_graph().ok())
+            .flatten();
+        let mut iter = Iter {
+            repo,
+            rewrites,
+            commit_graph,
+            bloom_key: None,
+            queue: gix_revwalk::PriorityQueue::new(),
+            seen: Default::default(),
+            buf: Vec::new(),
+        };
+        iter.enqueue(tip, location)?;
+        Ok(iter)
+    }
+}
+
+/// An iterator over the commits that changed a path, created by [`Platform::all()`].
+pub struct Iter<'repo> {
+    repo: &'repo Repository,
+    rewrites: Option<Rewrites>,
+    commit_graph: Option<gix_commitgraph::Graph>,
+    /// The key of the currently tracked path, kept to avoid recomputing it for each Bloom filter.
+    bloom_key: Option<(BString, gix_commitgraph::bloom::Key)>,
+    queue: gix_revwalk::PriorityQueue<gix_date::SecondsSinceUnixEpoch, Queued>,
+    seen: HashSet<ObjectId>,
+    buf: Vec<u8>,
+}
+
+struct Queued {
+    id: ObjectId,
+    location: BString,
+    commit: Commit,
+}
+
+struct Commit {
+    time: gix_date::SecondsSinceUnixEpoch,
+    tree: ObjectId,
+    parents: Vec<ObjectId>,
+}
+
+impl<'repo> Iterator for Iter<'repo> {
+    type Item = Result<Item, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(queued) = self.queue.pop_value() {
+            match self.process(queued) {
+                Ok(Some(item)) => return Some(Ok(item)),
+                Ok(None) => continue,
+                Err(err) => {
+                    self.queue.clear();
+                    return Some(Err(err));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<'repo> Iter<'repo> {
+    /// Determine how the tracked path changed in `queued` compared to its parents, and queue the parents that
+    /// carry its history.
+    fn process(&mut self, queued: Queued) -> Result<Option<Item>, Error> {
+        let Queued { id, location, commit } = queued;
+        let entry = self.entry_at(commit.tree, location.as_ref())?;
+        let item = |location: BString, change| Item {
+            id,
+            commit_time: commit.time,
+            location,
+            change,
+        };
+        if commit.parents.is_empty() {
+            return Ok(entry.map(|_| item(location, Change::Addition)));
+        }
+
+        let mut parents = Vec::with_capacity(commit.parents.len());
+        for (idx, parent_id) in commit.parents.iter().enumerate() {
+            let parent = self.lookup_commit(parent_id)?;
+            if idx == 0 && !self.may_have_changed_in_first_parent(&id, location.as_ref()) {
+                self.insert(*parent_id, location, parent);
+                return Ok(None);
+            }
+            let parent_entry = self.entry_at(parent.tree, location.as_ref())?;
+            if parent_entry == entry {
+                self.insert(*parent_id, location, parent);
+                return Ok(None);
+            }
+            parents.push((*parent_id, parent, parent_entry.is_some()));
+        }
+
+        if entry.is_some() && parents.len() == 1 && !parents[0].2 {
+            let (parent_id, parent, _) = parents.pop().expect("one parent");
+            return Ok(Some(
+                match self.rewrite_source(parent.tree, commit.tree, location.as_ref())? {
+                    Some((source_location, copy)) => {
+                        self.insert(parent_id, source_location.clone(), parent);
+                        item(location, Change::Rewrite { source_location, copy })
+                    }
+                    None => item(location, Change::Addition),
+                },
+            ));
+        }
+
+        let change = match (&entry, parents.iter().any(|(_, _, has_entry)| *has_entry)) {
+            (None, _) => Change::Deletion,
+            (Some(_), true) => Change::Modification,
+            (Some(_), false) => Change::Addition,
+        };
+        for (parent_id, parent, has_entry) in parents {
+            if has_entry {
+                self.insert(parent_id, location.clone(), parent);
+            }
+        }
+        Ok(Some(item(location, change)))
+    }
+
+    fn enqueue(&mut self, id: ObjectId, location: BString) -> Result<(), Error> {
+        if !self.seen.contains(&id) {
+            let commit = self.lookup_commit(&id)?;
+            self.insert(id, location, commit);
+        }
+        Ok(())
+    }
+
+    fn insert(&mut self, id: ObjectId, location: BString, commit: Commit) {
+        if self.seen.insert(id) {
+            self.queue.insert(commit.time, Queued { id, location, commit });
+        }
+    }
+
+    fn lookup_commit(&mut self, id: &gix_hash::oid) -> Result<Commit, Error> {
+        if let Some(graph) = self.commit_graph.as_ref() {
+            if let Some(commit) = graph.commit_by_id(id) {
+                let parents: Result<Vec<_>, _> = commit
+                    .iter_parents()
+                    .map(|pos| pos.map(|pos| graph.id_at(pos).to_owned()))
+                    .collect();
+                if let Ok(parents) = parents {
+                    return Ok(Commit {
+                        time: commit.committer_timestamp() as gix_date::SecondsSinceUnixEpoch,
+                        tree: commit.root_tree_id().to_owned(),
+                        parents,
+                    });
+                }
+            }
+        }
+        let commit = self.repo.objects.find_commit(id, &mut self.buf)?;
+        Ok(Commit {
+            time: commit.committer.time.seconds,
+            tree: commit.tree(),
+            parents: commit.parents().collect(),
+        })
+    }
+
+    /// Return `false` if the changed-path Bloom filter of the commit with `id` says that `location` didn't change
+    /// compared to its first parent.
+    fn may_have_changed_in_first_parent(&mut self, id: &gix_hash::oid, location: &BStr) -> bool {
+        let Some(filter) = self
+            .commit_graph
+            .as_ref()
+            .and_then(|graph| graph.commit_by_id(id))
+            .and_then(|commit| commit.bloom_filter())
+        else {
+            return true;
+        };
+        let settings = filter.settings();
+        match &self.bloom_key {
+            Some((key_location, key)) if key_location == location && key.settings() == settings => filter.contains(key),
+            _ => {
+                let key = gix_commitgraph::bloom::Key::new(location, settings);
+                let may_have_changed = filter.contains(&key);
+                self.bloom_key = Some((location.to_owned(), key));
+                may_have_changed
+            }
+        }
+    }
+
+    fn entry_at(
+        &mut self,
+        tree: ObjectId,
+        location: &BStr,
+    ) -> Result<Option<(gix_object::tree::EntryMode, ObjectId)>, Error> {
+        let tree = self.repo.find_object(tree)?.try_into_tree()?;
+        Ok(tree
+            .lookup_entry(location.split_str("/"), &mut self.buf)?
+            .map(|entry| (entry.mode(), entry.object_id())))
+    }
+
+    /// Find the location `location` in `new_tree` was renamed or copied from in `old_tree`, if rewrites are tracked.
+    fn rewrite_source(
+        &self,
+        old_tree: ObjectId,
+        new_tree: ObjectId,
+        location: &BStr,
+    ) -> Result<Option<(BString, bool)>, Error> {
+        let Some(rewrites) = self.rewrites else {
+            return Ok(None);
+        };
+        let old_tree = self.repo.find_object(old_tree)?.try_into_tree()?;
+        let new_tree = self.repo.find_object(new_tree)?.try_into_tree()?;
+        let mut source = None;
+        old_tree
+            .changes()?
+            .track_path()
+            .track_rewrites(Some(rewrites))
+            .for_each_to_obtain_tree(&new_tree, |change| {
+                if let Event::Rewrite {
+                    source_location, copy, ..
+                } = change.event
+                {
+                    if change.location == location {
+                        source = Some((source_location.to_owned(), copy));
+                    }
+                }
+                Ok::<_, std::convert::Infallible>(Action::Continue)
+            })?;
+        Ok(source)
+    }
+}
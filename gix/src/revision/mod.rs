@@ -15,6 +15,11 @@ pub use walk::iter::Walk;
 #[allow(clippy::empty_docs)]
 pub mod spec;
 
+///
+#[cfg(all(feature = "revision", feature = "blob-diff"))]
+#[allow(clippy::empty_docs)]
+pub mod history;
+
 /// The specification of a revision as parsed from a revision specification like `HEAD@{1}` or `v1.2.3...main`.
 /// It's typically created by [`repo.rev_parse()`][crate::Repository::rev_parse()].
 ///
@@ -97,4 +97,15 @@ impl<'repo> Spec<'repo> {
             | gix_revision::Spec::IncludeOnlyParents { .. } => None,
         }
     }
+
+    /// Return all merge-bases of a symmetric difference like `a...b`, sorted from best to worst, which are to be excluded
+    /// to obtain the commits reachable from either side but not from both. Return `None` if this isn't a symmetric difference.
+    ///
+    /// The returned list is empty if there is no merge-base.
+    pub fn merge_bases(&self) -> Option<Result<Vec<Id<'repo>>, crate::repository::merge_base::Error>> {
+        match self.inner {
+            gix_revision::Spec::Merge { theirs, ours } => Some(self.repo.merge_bases_many(theirs, &[ours])),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,143 @@
+use gix_date::SecondsSinceUnixEpoch;
+use gix_hash::oid;
+use gix_object::FindExt;
+
+use crate::bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// Filters to apply to each commit that is about to be returned, similar to the commit limiting options of `git rev-list`.
+#[derive(Default, Clone)]
+pub(crate) struct Filter {
+    pub since: Option<SecondsSinceUnixEpoch>,
+    pub until: Option<SecondsSinceUnixEpoch>,
+    pub authors: Vec<BString>,
+    pub committers: Vec<BString>,
+    pub messages: Vec<BString>,
+    pub min_parents: Option<usize>,
+    pub max_parents: Option<usize>,
+}
+
+impl Filter {
+    /// Turn this instance into a matcher, or return `None` if there is nothing to filter.
+    pub fn into_matcher(self) -> Result<Option<Matcher>, super::Error> {
+        let Filter {
+            since,
+            until,
+            authors,
+            committers,
+            messages,
+            min_parents,
+            max_parents,
+        } = self;
+        if since.is_none()
+            && until.is_none()
+            && authors.is_empty()
+            && committers.is_empty()
+            && messages.is_empty()
+            && min_parents.is_none()
+            && max_parents.is_none()
+        {
+            return Ok(None);
+        }
+        let compile = |patterns: Vec<BString>| -> Result<Vec<Pattern>, super::Error> {
+            patterns.iter().map(|pattern| Pattern::new(pattern.as_ref())).collect()
+        };
+        Ok(Some(Matcher {
+            since,
+            until,
+            authors: compile(authors)?,
+            committers: compile(committers)?,
+            messages: compile(messages)?,
+            min_parents,
+            max_parents,
+            buf: Vec::new(),
+            ident: BString::default(),
+        }))
+    }
+}
+
+/// The compiled version of a [`Filter`].
+pub(crate) struct Matcher {
+    since: Option<SecondsSinceUnixEpoch>,
+    until: Option<SecondsSinceUnixEpoch>,
+    authors: Vec<Pattern>,
+    committers: Vec<Pattern>,
+    messages: Vec<Pattern>,
+    min_parents: Option<usize>,
+    max_parents: Option<usize>,
+    buf: Vec<u8>,
+    ident: BString,
+}
+
+impl Matcher {
+    /// Return `true` if the commit with `id` passes all filters, looking it up in `objects`.
+    ///
+    /// Patterns of the same kind match if any of them matches, whereas all kinds of filters must match.
+    pub fn matches(
+        &mut self,
+        id: &oid,
+        objects: &impl gix_object::Find,
+    ) -> Result<bool, gix_traverse::commit::simple::Error> {
+        objects.find_commit_iter(id, &mut self.buf)?;
+        let commit = gix_object::CommitRef::from_bytes(&self.buf)?;
+
+        let time = commit.committer.time.seconds;
+        if self.since.map_or(false, |since| time < since) || self.until.map_or(false, |until| time > until) {
+            return Ok(false);
+        }
+        let num_parents = commit.parents.len();
+        if self.min_parents.map_or(false, |min| num_parents < min)
+            || self.max_parents.map_or(false, |max| num_parents > max)
+        {
+            return Ok(false);
+        }
+        Ok(matches_ident(&self.authors, commit.author, &mut self.ident)
+            && matches_ident(&self.committers, commit.committer, &mut self.ident)
+            && (self.messages.is_empty() || self.messages.iter().any(|p| p.is_match(commit.message))))
+    }
+}
+
+/// Match `patterns` against the `Name <email>` portion of `signature`, just like `git` does.
+fn matches_ident(patterns: &[Pattern], signature: gix_actor::SignatureRef<'_>, buf: &mut BString) -> bool {
+    if patterns.is_empty() {
+        return true;
+    }
+    buf.clear();
+    buf.push_str(signature.name);
+    buf.push_str(" <");
+    buf.push_str(signature.email);
+    buf.push_byte(b'>');
+    patterns.iter().any(|p| p.is_match(buf.as_ref()))
+}
+
+/// A pattern to match commit headers or messages, which is a regular expression if the `revparse-regex` feature is enabled,
+/// or a sub-string otherwise.
+struct Pattern {
+    #[cfg(feature = "revparse-regex")]
+    regex: regex::bytes::Regex,
+    #[cfg(not(feature = "revparse-regex"))]
+    text: BString,
+}
+
+impl Pattern {
+    fn new(pattern: &BStr) -> Result<Self, super::Error> {
+        Ok(Pattern {
+            #[cfg(feature = "revparse-regex")]
+            regex: regex::bytes::RegexBuilder::new(pattern.to_str_lossy().as_ref())
+                .multi_line(true)
+                .build()?,
+            #[cfg(not(feature = "revparse-regex"))]
+            text: pattern.into(),
+        })
+    }
+
+    fn is_match(&self, haystack: &BStr) -> bool {
+        #[cfg(feature = "revparse-regex")]
+        {
+            self.regex.is_match(haystack)
+        }
+        #[cfg(not(feature = "revparse-regex"))]
+        {
+            haystack.contains_str(&self.text)
+        }
+    }
+}
@@ -0,0 +1,314 @@
+//! Limit the commits of a traversal with algorithms that need to see all of them before any can be returned.
+use gix_hash::ObjectId;
+use gix_hashtable::HashMap;
+use gix_traverse::commit::Info;
+
+/// Keep only the commits that are descendants of any of `bottoms`, similar to `git rev-list --ancestry-path`.
+pub(crate) fn ancestry_path(infos: Vec<Info>, bottoms: &[ObjectId]) -> Vec<Info> {
+    let index = index_by_id(&infos);
+    let mut is_descendant = vec![false; infos.len()];
+    for idx in parents_first(&infos, &index) {
+        is_descendant[idx] = infos[idx]
+            .parent_ids
+            .iter()
+            .any(|parent| bottoms.contains(parent) || index.get(parent).map_or(false, |pidx| is_descendant[*pidx]));
+    }
+    retain(infos, is_descendant)
+}
+
+fn index_by_id(infos: &[Info]) -> HashMap<ObjectId, usize> {
+    infos.iter().enumerate().map(|(idx, info)| (info.id, idx)).collect()
+}
+
+fn retain(infos: Vec<Info>, keep: Vec<bool>) -> Vec<Info> {
+    infos
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(info, keep)| keep.then_some(info))
+        .collect()
+}
+
+/// Return the indices into `infos` so that each commit comes after all of its parents.
+fn parents_first(infos: &[Info], index: &HashMap<ObjectId, usize>) -> Vec<usize> {
+    let mut order = Vec::with_capacity(infos.len());
+    let mut visited = vec![false; infos.len()];
+    let mut stack = Vec::new();
+    for start in 0..infos.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        stack.push((start, 0));
+        while let Some((idx, next_parent)) = stack.pop() {
+            match infos[idx].parent_ids.get(next_parent) {
+                Some(parent) => {
+                    stack.push((idx, next_parent + 1));
+                    if let Some(&pidx) = index.get(parent) {
+                        if !visited[pidx] {
+                            visited[pidx] = true;
+                            stack.push((pidx, 0));
+                        }
+                    }
+                }
+                None => order.push(idx),
+            }
+        }
+    }
+    order
+}
+
+#[cfg(feature = "attributes")]
+pub(crate) use paths::simplify_by_paths;
+
+#[cfg(feature = "attributes")]
+mod paths {
+    use std::collections::VecDeque;
+
+    use gix_diff::tree::{visit, Visit};
+    use gix_hash::ObjectId;
+    use gix_hashtable::HashMap;
+    use gix_object::FindExt;
+    use gix_traverse::commit::Info;
+
+    use super::{index_by_id, parents_first, retain};
+    use crate::{
+        bstr::{BStr, BString, ByteSlice, ByteVec},
+        revision::walk::{Error, Simplification},
+    };
+
+    /// Keep only the commits that change paths matching `pathspec`, while following the history from `tips` as
+    /// determined by `mode`, similar to `git rev-list <paths>`.
+    pub(crate) fn simplify_by_paths(
+        infos: Vec<Info>,
+        tips: &[ObjectId],
+        pathspec: crate::Pathspec<'_>,
+        mode: Simplification,
+        repo: &crate::Repository,
+    ) -> Result<Vec<Info>, Error> {
+        let index = index_by_id(&infos);
+        let mut tree_same = TreeSame {
+            repo,
+            pathspec,
+            trees: Default::default(),
+            cache: Default::default(),
+            state: Default::default(),
+            buf: Vec::new(),
+            lhs_buf: Vec::new(),
+            rhs_buf: Vec::new(),
+        };
+        let mut keep = vec![false; infos.len()];
+        match mode {
+            Simplification::Simplify => {
+                let mut reached = vec![false; infos.len()];
+                let mut queue = VecDeque::new();
+                for tip in tips {
+                    if let Some(&idx) = index.get(tip) {
+                        if !std::mem::replace(&mut reached[idx], true) {
+                            queue.push_back(idx);
+                        }
+                    }
+                }
+                while let Some(idx) = queue.pop_front() {
+                    let info = &infos[idx];
+                    let mut same_parent = None;
+                    for parent in &info.parent_ids {
+                        if tree_same.is(info.id, Some(*parent))? {
+                            same_parent = Some(*parent);
+                            break;
+                        }
+                    }
+                    let parents_to_follow = match same_parent {
+                        Some(parent) => vec![parent],
+                        None => {
+                            keep[idx] = !info.parent_ids.is_empty() || !tree_same.is(info.id, None)?;
+                            info.parent_ids.to_vec()
+                        }
+                    };
+                    for parent in parents_to_follow {
+                        if let Some(&pidx) = index.get(&parent) {
+                            if !std::mem::replace(&mut reached[pidx], true) {
+                                queue.push_back(pidx);
+                            }
+                        }
+                    }
+                }
+            }
+            Simplification::FullHistory => {
+                for (info, keep) in infos.iter().zip(keep.iter_mut()) {
+                    *keep = tree_same.is_relevant(info)?;
+                }
+            }
+            Simplification::SimplifyMerges => {
+                // The commit each commit was simplified to, or `None` if it vanished entirely.
+                let mut simplified: Vec<Option<ObjectId>> = vec![None; infos.len()];
+                for idx in parents_first(&infos, &index) {
+                    let info = &infos[idx];
+                    let simplified_parents = || {
+                        info.parent_ids
+                            .iter()
+                            .filter_map(|parent| index.get(parent).and_then(|pidx| simplified[*pidx]))
+                    };
+                    if !tree_same.is_relevant(info)? {
+                        simplified[idx] = simplified_parents().next();
+                        continue;
+                    }
+                    let mut parents = Vec::<ObjectId>::new();
+                    for parent in simplified_parents() {
+                        if !parents.contains(&parent) {
+                            parents.push(parent);
+                        }
+                    }
+                    let parents: Vec<_> = parents
+                        .iter()
+                        .filter(|parent| {
+                            !parents
+                                .iter()
+                                .any(|other| other != *parent && is_ancestor(**parent, *other, &infos, &index))
+                        })
+                        .copied()
+                        .collect();
+                    match parents.as_slice() {
+                        [parent] if tree_same.is(info.id, Some(*parent))? => simplified[idx] = Some(*parent),
+                        _ => {
+                            keep[idx] = true;
+                            simplified[idx] = Some(info.id);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(retain(infos, keep))
+    }
+
+    /// Return `true` if `ancestor` can be reached from `descendant` using only the commits in `infos`.
+    fn is_ancestor(ancestor: ObjectId, descendant: ObjectId, infos: &[Info], index: &HashMap<ObjectId, usize>) -> bool {
+        let mut seen = gix_hashtable::HashSet::default();
+        let mut queue = vec![descendant];
+        while let Some(id) = queue.pop() {
+            if id == ancestor {
+                return true;
+            }
+            if let Some(&idx) = index.get(&id) {
+                queue.extend(infos[idx].parent_ids.iter().filter(|parent| seen.insert(**parent)));
+            }
+        }
+        false
+    }
+
+    /// A utility to determine if commits have the same tree as their parents, considering only paths that match a pathspec.
+    struct TreeSame<'a, 'repo> {
+        repo: &'a crate::Repository,
+        pathspec: crate::Pathspec<'repo>,
+        trees: HashMap<ObjectId, ObjectId>,
+        cache: std::collections::HashMap<(ObjectId, Option<ObjectId>), bool>,
+        state: gix_diff::tree::State,
+        buf: Vec<u8>,
+        lhs_buf: Vec<u8>,
+        rhs_buf: Vec<u8>,
+    }
+
+    impl TreeSame<'_, '_> {
+        /// Return `true` if the commit changes paths compared to its parents, or introduces paths if it's a root commit.
+        ///
+        /// Merges are relevant if they are different from at least one of their parents.
+        fn is_relevant(&mut self, info: &Info) -> Result<bool, Error> {
+            if info.parent_ids.is_empty() {
+                return Ok(!self.is(info.id, None)?);
+            }
+            for parent in &info.parent_ids {
+                if !self.is(info.id, Some(*parent))? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+
+        /// Return `true` if `commit` and `parent`, or the empty tree if `None`, don't differ in paths matching the pathspec.
+        fn is(&mut self, commit: ObjectId, parent: Option<ObjectId>) -> Result<bool, Error> {
+            if let Some(is_same) = self.cache.get(&(commit, parent)) {
+                return Ok(*is_same);
+            }
+            let tree = self.tree_of(commit)?;
+            let parent_tree = parent.map(|parent| self.tree_of(parent)).transpose()?;
+            let is_same = parent_tree == Some(tree) || {
+                let objects = &self.repo.objects;
+                let lhs = parent_tree
+                    .map(|tree| objects.find_tree_iter(&tree, &mut self.lhs_buf))
+                    .transpose()?;
+                let rhs = objects.find_tree_iter(&tree, &mut self.rhs_buf)?;
+                let mut delegate = MatchingChange {
+                    pathspec: &mut self.pathspec,
+                    path: Default::default(),
+                    path_deque: Default::default(),
+                };
+                match gix_diff::tree::Changes::from(lhs).needed_to_obtain(rhs, &mut self.state, objects, &mut delegate)
+                {
+                    Ok(()) => true,
+                    Err(gix_diff::tree::changes::Error::Cancelled) => false,
+                    Err(err) => return Err(err.into()),
+                }
+            };
+            self.cache.insert((commit, parent), is_same);
+            Ok(is_same)
+        }
+
+        fn tree_of(&mut self, commit: ObjectId) -> Result<ObjectId, Error> {
+            if let Some(tree) = self.trees.get(&commit) {
+                return Ok(*tree);
+            }
+            let tree = self.repo.objects.find_commit_iter(&commit, &mut self.buf)?.tree_id()?;
+            self.trees.insert(commit, tree);
+            Ok(tree)
+        }
+    }
+
+    /// A delegate to stop the diff at the first change to a path matching the pathspec.
+    struct MatchingChange<'a, 'repo> {
+        pathspec: &'a mut crate::Pathspec<'repo>,
+        path: BString,
+        path_deque: VecDeque<BString>,
+    }
+
+    impl MatchingChange<'_, '_> {
+        fn push_element(&mut self, name: &BStr) {
+            if !self.path.is_empty() {
+                self.path.push(b'/');
+            }
+            self.path.push_str(name);
+        }
+    }
+
+    impl Visit for MatchingChange<'_, '_> {
+        fn pop_front_tracked_path_and_set_current(&mut self) {
+            self.path = self.path_deque.pop_front().expect("every parent is set only once");
+        }
+
+        fn push_back_tracked_path_component(&mut self, component: &BStr) {
+            self.push_element(component);
+            self.path_deque.push_back(self.path.clone());
+        }
+
+        fn push_path_component(&mut self, component: &BStr) {
+            self.push_element(component);
+        }
+
+        fn pop_path_component(&mut self) {
+            match self.path.rfind_byte(b'/') {
+                Some(pos) => self.path.resize(pos, 0),
+                None => self.path.clear(),
+            }
+        }
+
+        fn visit(&mut self, change: visit::Change) -> visit::Action {
+            // Trees are entered, and we are only interested in the changes to their entries.
+            if change.entry_mode().is_tree() {
+                return visit::Action::Continue;
+            }
+            if self.pathspec.is_included(self.path.as_bstr(), Some(false)) {
+                visit::Action::Cancel
+            } else {
+                visit::Action::Continue
+            }
+        }
+    }
+}
@@ -17,17 +17,16 @@ pub mod write {
         /// Write the previously obtained (possibly non-existing) `shallow_commits` to the shallow `file`
         /// after applying all `updates`.
         ///
+        /// Previously obtained `shallow_commits` that don't exist in `objects` anymore are pruned, as git would do.
         /// If this leaves the list of shallow commits empty, the file is removed.
-        ///
-        /// ### Deviation
-        ///
-        /// Git also prunes the set of shallow commits while writing, we don't until we support some sort of pruning.
         pub fn write(
             mut file: gix_lock::File,
             shallow_commits: Option<Commits>,
             updates: &[ShallowUpdate],
+            objects: &impl gix_object::Exists,
         ) -> Result<(), Error> {
             let mut shallow_commits = shallow_commits.map(|sc| (**sc).to_owned()).unwrap_or_default();
+            shallow_commits.retain(|id| objects.exists(id));
             for update in updates {
                 match update {
                     ShallowUpdate::Shallow(id) => {
@@ -23,10 +23,12 @@ pub enum Error {
     StatOptions(#[from] config::stat_options::Error),
     #[error(transparent)]
     ResourceCache(#[from] crate::diff::resource_cache::Error),
+    #[error(transparent)]
+    UntrackedCache(#[from] crate::dirwalk::untracked_cache::Error),
 }
 
 /// Options for use with [Repository::index_worktree_status()].
-#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct Options {
     /// The way all output should be sorted.
     ///
@@ -55,6 +57,54 @@ pub struct Options {
     /// Otherwise, usually use as many threads as there are logical cores.
     /// A value of 0 is interpreted as no-limit
     pub thread_limit: Option<usize>,
+    /// If not `None`, the sorted repository-relative paths that a filesystem monitor reported as changed since the token
+    /// stored in the index, which allows the directory walk to trust the untracked cache for all directories that
+    /// aren't affected by these changes.
+    ///
+    /// It's set automatically by [`Platform::into_index_worktree_iter()`](crate::status::Platform::into_index_worktree_iter())
+    /// if `core.fsmonitor` is configured.
+    pub fs_monitor_changed_paths: Option<Vec<BString>>,
+}
+
+/// Query the filesystem monitor hook configured with `core.fsmonitor`, if any, for the changes since the token stored in `index`.
+///
+/// If the hook can't be queried, it's ignored just as if it wasn't configured.
+pub(crate) fn query_fs_monitor_hook(
+    repo: &Repository,
+    index: &gix_index::State,
+) -> Result<Option<gix_status::fs_monitor::Outcome>, config::key::GenericErrorWithValue> {
+    use crate::config::cache::util::ApplyLeniencyDefault;
+    use crate::config::tree::Core;
+    let Some(program) = repo
+        .config
+        .resolved
+        .string(Core::FS_MONITOR)
+        .and_then(|value| Core::FS_MONITOR.hook_program(value))
+    else {
+        return Ok(None);
+    };
+    let Some(workdir) = repo.work_dir() else {
+        return Ok(None);
+    };
+    let version = repo
+        .config
+        .resolved
+        .string(Core::FS_MONITOR_HOOK_VERSION)
+        .map(|value| Core::FS_MONITOR_HOOK_VERSION.try_into_hook_version(value))
+        .transpose()
+        .with_lenient_default(repo.config.lenient_config)?;
+    let hook = gix_status::fs_monitor::Hook {
+        program: program.into_owned(),
+        version,
+        context: repo.command_context().ok(),
+    };
+    match hook.query(workdir, index.fs_monitor().map(gix_index::extension::FsMonitor::token)) {
+        Ok(outcome) => Ok(Some(outcome)),
+        Err(_err) => {
+            gix_trace::warn!("Ignoring filesystem monitor as it couldn't be queried: {_err}");
+            Ok(None)
+        }
+    }
 }
 
 impl Repository {
@@ -78,6 +128,10 @@ impl Repository {
     /// * `options`
     ///     - Additional configuration for all parts of the operation.
     ///
+    /// The untracked cache of `index` is used if it was created for this worktree, or if `core.untrackedCache` is `true`,
+    /// but as `index` can't be changed, the updated cache is discarded.
+    /// Use the [`status`](Repository::status()) iterator to be able to write it back.
+    ///
     /// ### Note
     ///
     /// This is a lower-level method, prefer the [`status`](Repository::status()) method for greater ease of use.
@@ -97,6 +151,44 @@ impl Repository {
         should_interrupt: &AtomicBool,
         options: Options,
     ) -> Result<gix_status::index_as_worktree_with_renames::Outcome, Error>
+    where
+        T: Send + Clone,
+        U: Send + Clone,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let mut untracked_cache = self.untracked_cache_for_dirwalk(index)?;
+        self.index_worktree_status_with_untracked_cache(
+            index,
+            patterns,
+            delegate,
+            compare,
+            submodule,
+            progress,
+            should_interrupt,
+            options,
+            untracked_cache.as_mut(),
+        )
+    }
+
+    /// Like [`index_worktree_status()`](Self::index_worktree_status()), but use `untracked_cache` for the directory walk
+    /// and update it.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn index_worktree_status_with_untracked_cache<'index, T, U, E>(
+        &self,
+        index: &'index gix_index::State,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+        delegate: &mut impl gix_status::index_as_worktree_with_renames::VisitEntry<
+            'index,
+            ContentChange = T,
+            SubmoduleStatus = U,
+        >,
+        compare: impl CompareBlobs<Output = T> + Send + Clone,
+        submodule: impl SubmoduleStatus<Output = U, Error = E> + Send + Clone,
+        progress: &mut dyn gix_features::progress::Progress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+        untracked_cache: Option<&mut crate::dirwalk::untracked_cache::State>,
+    ) -> Result<gix_status::index_as_worktree_with_renames::Outcome, Error>
     where
         T: Send + Clone,
         U: Send + Clone,
@@ -124,7 +216,9 @@ impl Repository {
         let cwd = self.current_dir();
         let git_dir_realpath = crate::path::realpath_opts(self.git_dir(), cwd, crate::path::realpath::MAX_SYMLINKS)?;
         let fs_caps = self.filesystem_options()?;
-        let accelerate_lookup = fs_caps.ignore_case.then(|| index.prepare_icase_backing());
+        let accelerate_lookup = fs_caps
+            .ignore_case
+            .then(|| index.prepare_icase_backing_with_thread_limit(options.thread_limit));
         let resource_cache = crate::diff::resource_cache(
             self,
             gix_diff::blob::pipeline::Mode::ToGit,
@@ -151,6 +245,8 @@ impl Repository {
                     git_dir_realpath: git_dir_realpath.as_path(),
                     current_dir: cwd,
                     ignore_case_index_lookup: accelerate_lookup.as_ref(),
+                    fs_monitor_changed_paths: options.fs_monitor_changed_paths.as_deref(),
+                    untracked_cache: untracked_cache.map(crate::dirwalk::untracked_cache::State::for_walk),
                 },
             },
             gix_status::index_as_worktree_with_renames::Options {
@@ -303,6 +399,8 @@ pub struct Iter {
     out: Option<iter::Outcome>,
     /// The set of `(entry_index, change)` we extracted in order to potentially write back the index with the changes applied.
     changes: Vec<(usize, iter::ApplyChange)>,
+    /// The indices of all entries that were reported as changed, which are not known to be unchanged by the filesystem monitor.
+    changed_entries: Vec<usize>,
 }
 
 ///
@@ -330,6 +428,9 @@ pub mod iter {
         pub index: crate::worktree::IndexPersistedOrInMemory,
         skip_hash: bool,
         changes: Option<Vec<(usize, iter::ApplyChange)>>,
+        changed_entries: Vec<usize>,
+        fs_monitor_token: Option<gix_status::fs_monitor::Token>,
+        untracked_cache: Option<gix_index::extension::UntrackedCache>,
     }
 
     impl Outcome {
@@ -337,20 +438,66 @@ pub mod iter {
         ///
         /// If they are not written back, subsequent `status` operations will take longer to complete, whereas the
         /// additional work can be prevented by writing the changes back to the index.
+        ///
+        /// This is always the case if a filesystem monitor was queried, as its new token should be stored in the index,
+        /// and if the untracked cache was updated.
         pub fn has_changes(&self) -> bool {
-            self.changes.as_ref().map_or(false, |changes| !changes.is_empty())
+            self.fs_monitor_token.is_some()
+                || self.untracked_cache_changed()
+                || self.changes.as_ref().map_or(false, |changes| !changes.is_empty())
+        }
+
+        fn untracked_cache_changed(&self) -> bool {
+            self.untracked_cache
+                .as_ref()
+                .map_or(false, |cache| Some(cache) != self.index.untracked())
         }
 
         /// Write the changes if there are any back to the index file.
         /// This can only be done once as the changes are consumed in the process, if there were any.
+        ///
+        /// If a filesystem monitor was queried, its token is written as well, along with the information about which entries
+        /// are known to be unchanged. The same is true for the untracked cache, if it was updated.
         pub fn write_changes(&mut self) -> Option<Result<(), gix_index::file::write::Error>> {
             let _span = gix_features::trace::coarse!("gix::status::index_worktree::iter::Outcome::write_changes()");
-            let changes = self.changes.take()?;
+            let fs_monitor_token = self.fs_monitor_token.take();
+            let untracked_cache = self
+                .untracked_cache_changed()
+                .then(|| self.untracked_cache.take())
+                .flatten();
+            let changes = match self.changes.take() {
+                Some(changes) => changes,
+                None if fs_monitor_token.is_some() || untracked_cache.is_some() => Vec::new(),
+                None => return None,
+            };
             let mut index = match &self.index {
                 IndexPersistedOrInMemory::Persisted(persisted) => (***persisted).clone(),
                 IndexPersistedOrInMemory::InMemory(index) => index.clone(),
             };
 
+            if let Some(token) = fs_monitor_token {
+                let modifications = &self.index_worktree.tracked_file_modification;
+                let all_entries_checked = modifications.entries_processed == modifications.entries_to_process
+                    && modifications.entries_skipped_by_common_prefix == 0
+                    && modifications.entries_skipped_by_pathspec == 0;
+                let entries = index.entries_mut();
+                if all_entries_checked {
+                    for entry in entries.iter_mut() {
+                        entry.flags.insert(gix_index::entry::Flags::FSMONITOR_VALID);
+                    }
+                }
+                for entry_index in self.changed_entries.drain(..) {
+                    entries[entry_index]
+                        .flags
+                        .remove(gix_index::entry::Flags::FSMONITOR_VALID);
+                }
+                let ext = gix_status::fs_monitor::to_extension(&index, token);
+                index.set_fs_monitor(Some(ext));
+            }
+            if let Some(untracked_cache) = untracked_cache {
+                index.set_untracked(Some(untracked_cache));
+            }
+
             let entries = index.entries_mut();
             for (entry_index, change) in changes {
                 let entry = &mut entries[entry_index];
@@ -609,6 +756,10 @@ pub mod iter {
         ConfigSkipHash(#[from] crate::config::boolean::Error),
         #[error(transparent)]
         PrepareSubmodules(#[from] crate::submodule::modules::Error),
+        #[error(transparent)]
+        FsMonitorHookVersion(#[from] crate::config::key::GenericErrorWithValue),
+        #[error(transparent)]
+        UntrackedCache(#[from] crate::dirwalk::untracked_cache::Error),
     }
 
     /// Lifecycle
@@ -629,6 +780,15 @@ pub mod iter {
                 None => IndexPersistedOrInMemory::Persisted(self.repo.index_or_empty()?),
                 Some(index) => index,
             };
+            let (index, fs_monitor) = query_fs_monitor(self.repo, index)?;
+            let mut options = self.index_worktree_options;
+            let fs_monitor_token = fs_monitor.map(|outcome| {
+                if options.fs_monitor_changed_paths.is_none() {
+                    options.fs_monitor_changed_paths = outcome.changes.paths().map(<[BString]>::to_vec);
+                }
+                outcome.token
+            });
+            let mut untracked_cache = self.repo.untracked_cache_for_dirwalk(&index)?;
 
             let skip_hash = self
                 .repo
@@ -650,12 +810,11 @@ pub mod iter {
                     .name("gix::status::index_worktree::iter::producer".into())
                     .spawn({
                         let repo = self.repo.clone().into_sync();
-                        let options = self.index_worktree_options;
                         let should_interrupt = should_interrupt.clone();
                         let mut progress = self.progress;
                         move || -> Result<_, crate::status::index_worktree::Error> {
                             let repo = repo.to_thread_local();
-                            let out = repo.index_worktree_status(
+                            let out = repo.index_worktree_status_with_untracked_cache(
                                 &index,
                                 patterns,
                                 &mut collect,
@@ -664,11 +823,15 @@ pub mod iter {
                                 &mut progress,
                                 &should_interrupt,
                                 options,
+                                untracked_cache.as_mut(),
                             )?;
                             Ok(Outcome {
                                 index_worktree: out,
                                 index,
                                 changes: None,
+                                changed_entries: Vec::new(),
+                                fs_monitor_token,
+                                untracked_cache: untracked_cache.map(|state| state.cache),
                                 skip_hash,
                             })
                         }
@@ -679,6 +842,7 @@ pub mod iter {
                     rx_and_join: Some((rx, join)),
                     should_interrupt,
                     changes: Vec::new(),
+                    changed_entries: Vec::new(),
                     out: None,
                 })
             }
@@ -687,10 +851,9 @@ pub mod iter {
                 let mut collect = Collect { items: Vec::new() };
 
                 let repo = self.repo.clone().into_sync();
-                let options = self.index_worktree_options;
                 let mut progress = self.progress;
                 let repo = repo.to_thread_local();
-                let out = repo.index_worktree_status(
+                let out = repo.index_worktree_status_with_untracked_cache(
                     &index,
                     patterns,
                     &mut collect,
@@ -699,16 +862,21 @@ pub mod iter {
                     &mut progress,
                     &should_interrupt,
                     options,
+                    untracked_cache.as_mut(),
                 )?;
                 let mut out = Outcome {
                     index_worktree: out,
                     index,
                     changes: None,
+                    changed_entries: Vec::new(),
+                    fs_monitor_token,
+                    untracked_cache: untracked_cache.map(|state| state.cache),
                     skip_hash,
                 };
                 let mut iter = super::Iter {
                     items: Vec::new().into_iter(),
                     changes: Vec::new(),
+                    changed_entries: Vec::new(),
                     out: None,
                 };
                 let items = collect
@@ -717,6 +885,7 @@ pub mod iter {
                     .filter_map(|item| iter.maybe_keep_index_change(item))
                     .collect::<Vec<_>>();
                 out.changes = (!iter.changes.is_empty()).then(|| std::mem::take(&mut iter.changes));
+                out.changed_entries = std::mem::take(&mut iter.changed_entries);
                 iter.items = items.into_iter();
                 iter.out = Some(out);
                 Ok(iter)
@@ -743,6 +912,7 @@ pub mod iter {
                         break match handle.join().expect("no panic") {
                             Ok(mut out) => {
                                 out.changes = Some(std::mem::take(&mut self.changes));
+                                out.changed_entries = std::mem::take(&mut self.changed_entries);
                                 self.out = Some(out);
                                 None
                             }
@@ -770,8 +940,39 @@ pub mod iter {
         }
     }
 
+    /// Query the filesystem monitor hook configured with `core.fsmonitor`, if any, and return a copy of `index` with all entries
+    /// marked that are known to be unchanged, along with the new token to write back to the index.
+    ///
+    /// If the hook can't be queried, it's ignored just as if it wasn't configured.
+    fn query_fs_monitor(
+        repo: &crate::Repository,
+        index: IndexPersistedOrInMemory,
+    ) -> Result<(IndexPersistedOrInMemory, Option<gix_status::fs_monitor::Outcome>), Error> {
+        let Some(outcome) = index_worktree::query_fs_monitor_hook(repo, &index)? else {
+            return Ok((index, None));
+        };
+        let mut index = match index {
+            IndexPersistedOrInMemory::Persisted(persisted) => (**persisted).clone(),
+            IndexPersistedOrInMemory::InMemory(index) => index,
+        };
+        gix_status::fs_monitor::mark_valid_entries(&mut index, &outcome.changes);
+        Ok((IndexPersistedOrInMemory::InMemory(index), Some(outcome)))
+    }
+
     impl super::Iter {
         fn maybe_keep_index_change(&mut self, item: Item) -> Option<Item> {
+            match &item {
+                Item::Modification {
+                    status: gix_status::index_as_worktree::EntryStatus::NeedsUpdate(_),
+                    ..
+                } => {}
+                Item::Modification { entry_index, .. } => self.changed_entries.push(*entry_index),
+                Item::Rewrite {
+                    source: RewriteSource::RewriteFromIndex { source_entry_index, .. },
+                    ..
+                } => self.changed_entries.push(*source_entry_index),
+                Item::DirectoryContents { .. } | Item::Rewrite { .. } => {}
+            }
             let change = match item {
                 Item::Modification {
                     status: gix_status::index_as_worktree::EntryStatus::NeedsUpdate(stat),
@@ -0,0 +1,104 @@
+use crate::bstr::{BStr, BString};
+use crate::status::{index_worktree, tree_index, Platform};
+
+/// An item of the [status iterator](crate::status::Iter), which is either a change between the `HEAD` tree and the index,
+/// or a change between the index and the worktree.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Item {
+    /// A change that was staged, as the index differs from the tree of `HEAD`.
+    TreeIndex(tree_index::Item),
+    /// A change that wasn't staged, as the worktree differs from the index.
+    IndexWorktree(index_worktree::iter::Item),
+}
+
+impl Item {
+    /// The repository-relative path of the entry contained in this item.
+    pub fn location(&self) -> &BStr {
+        match self {
+            Item::TreeIndex(item) => item.rela_path(),
+            Item::IndexWorktree(item) => item.rela_path(),
+        }
+    }
+}
+
+/// The error returned by [Platform::into_iter()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Index(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    HeadTreeId(#[from] crate::reference::head_tree_id::Error),
+    #[error(transparent)]
+    TreeIndex(#[from] tree_index::Error),
+    #[error(transparent)]
+    IndexWorktree(#[from] index_worktree::iter::Error),
+}
+
+/// Lifecycle
+impl<'repo, Progress> Platform<'repo, Progress>
+where
+    Progress: gix_features::progress::Progress,
+{
+    /// Turn the platform into an iterator for changes between the tree of `HEAD` and the index, followed by changes
+    /// between the index and the worktree, similar to what `git status --porcelain=v2` would show.
+    ///
+    /// * `patterns`
+    ///     - Optional patterns to use to limit the paths to look at. If empty, all paths are considered.
+    ///
+    /// Note that the changes between the `HEAD` tree and the index are computed right away, whereas the changes between
+    /// the index and the worktree are [computed as configured](Self::into_index_worktree_iter()).
+    /// If `HEAD` is unborn, all entries of the index are considered added.
+    pub fn into_iter(mut self, patterns: impl IntoIterator<Item = BString>) -> Result<crate::status::Iter, Error> {
+        let index = match self.index.take() {
+            None => crate::worktree::IndexPersistedOrInMemory::Persisted(self.repo.index_or_empty()?),
+            Some(index) => index,
+        };
+        let head_tree = match self.head_tree {
+            Some(id) => id,
+            None if self.repo.head()?.is_unborn() => gix_hash::ObjectId::empty_tree(self.repo.object_hash()),
+            None => self.repo.head_tree_id()?.detach(),
+        };
+        let patterns: Vec<_> = patterns.into_iter().collect();
+        let mut tree_index = Vec::new();
+        let tree_index_outcome =
+            self.repo
+                .tree_index_status(&head_tree, &index, patterns.iter(), self.tree_index_rewrites, |entry| {
+                    tree_index.push(entry.into());
+                    gix_diff::tree::visit::Action::Continue
+                })?;
+        self.index = Some(index);
+        Ok(crate::status::Iter {
+            tree_index: tree_index.into_iter(),
+            tree_index_outcome,
+            index_worktree: self.into_index_worktree_iter(patterns)?,
+        })
+    }
+}
+
+impl Iterator for crate::status::Iter {
+    type Item = Result<Item, index_worktree::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.tree_index.next() {
+            Some(item) => Some(Ok(Item::TreeIndex(item))),
+            None => self.index_worktree.next().map(|res| res.map(Item::IndexWorktree)),
+        }
+    }
+}
+
+/// Access
+impl crate::status::Iter {
+    /// Return the outcome of the comparison between the `HEAD` tree and the index.
+    pub fn tree_index_outcome(&self) -> &gix_status::tree_index::Outcome {
+        &self.tree_index_outcome
+    }
+
+    /// Return the outcome of the comparison between the index and the worktree, or `None` if the iterator
+    /// isn't fully consumed.
+    pub fn index_worktree_outcome_mut(&mut self) -> Option<&mut index_worktree::iter::Outcome> {
+        self.index_worktree.outcome_mut()
+    }
+}
@@ -13,9 +13,19 @@ where
     index: Option<crate::worktree::IndexPersistedOrInMemory>,
     submodules: Submodule,
     index_worktree_options: index_worktree::Options,
+    head_tree: Option<gix_hash::ObjectId>,
+    tree_index_rewrites: Option<gix_diff::Rewrites>,
     should_interrupt: Option<OwnedOrStaticAtomicBool>,
 }
 
+/// An iterator for changes between the tree of `HEAD` and the index, followed by changes between the index and
+/// the worktree, created with [`Platform::into_iter()`].
+pub struct Iter {
+    tree_index: std::vec::IntoIter<tree_index::Item>,
+    tree_index_outcome: gix_status::tree_index::Outcome,
+    index_worktree: index_worktree::Iter,
+}
+
 /// How to obtain a submodule's status.
 #[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum Submodule {
@@ -69,6 +79,8 @@ pub enum Error {
     DirwalkOptions(#[from] config::boolean::Error),
     #[error(transparent)]
     ConfigureUntrackedFiles(#[from] config::key::GenericErrorWithValue),
+    #[error(transparent)]
+    ConfigureRenames(#[from] crate::diff::new_rewrites::Error),
 }
 
 /// Status
@@ -83,7 +95,9 @@ impl Repository {
     /// which implies that submodule information is provided by default.
     ///
     /// Note that `status.showUntrackedFiles` is respected, which leads to untracked files being
-    /// collapsed by default. If that needs to be controlled,
+    /// collapsed by default. Renames between the `HEAD` tree and the index are tracked according to
+    /// `status.renames` and `status.renameLimit`, which fall back to `diff.renames` and `diff.renameLimit`,
+    /// and are enabled if none of them is set. If that needs to be controlled,
     /// [configure the directory walk explicitly](Platform::dirwalk_options) or more [implicitly](Platform::untracked_files).
     ///
     /// Pass `progress` to receive progress information on file modifications on this repository.
@@ -103,12 +117,15 @@ impl Repository {
             progress,
             index: None,
             submodules: Submodule::default(),
+            head_tree: None,
+            tree_index_rewrites: self.config.status_renames()?,
             should_interrupt: None,
             index_worktree_options: index_worktree::Options {
                 sorting: None,
                 dirwalk_options: Some(self.dirwalk_options()?),
                 rewrites: None,
                 thread_limit: None,
+                fs_monitor_changed_paths: None,
             },
         };
 
@@ -178,3 +195,11 @@ mod platform;
 ///
 #[allow(clippy::empty_docs)]
 pub mod index_worktree;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod tree_index;
+
+///
+#[allow(clippy::empty_docs)]
+pub mod iter;
@@ -82,6 +82,20 @@ where
         self
     }
 
+    /// Set the tree to compare the index to, instead of the tree of `HEAD` which is the default.
+    pub fn head_tree(mut self, tree_id: impl Into<gix_hash::ObjectId>) -> Self {
+        self.head_tree = Some(tree_id.into());
+        self
+    }
+
+    /// Configure the tree-to-index rename tracking with `rewrites`, or disable it with `None`.
+    ///
+    /// By default, this is configured by `status.renames` or `diff.renames`, and enabled if neither is set, just like `git status` does.
+    pub fn tree_index_track_renames(mut self, rewrites: impl Into<Option<gix_diff::Rewrites>>) -> Self {
+        self.tree_index_rewrites = rewrites.into();
+        self
+    }
+
     /// Configure the index-to-worktree rename tracking with `rewrites`, which is `None` by default.
     ///
     /// Note that Git does not have configuration related to rename tracking of changes between the index
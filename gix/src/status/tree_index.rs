@@ -0,0 +1,280 @@
+use crate::bstr::{BStr, BString};
+use crate::Repository;
+use gix_status::index_as_worktree::Conflict;
+use gix_status::tree_index::{Entry, Summary};
+
+/// The error returned by [Repository::tree_index_status()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    AttributesStack(#[from] crate::config::attribute_stack::Error),
+    #[error(transparent)]
+    Pathspec(#[from] crate::pathspec::init::Error),
+    #[error(transparent)]
+    ResourceCache(#[from] crate::diff::resource_cache::Error),
+    #[error(transparent)]
+    TreeIndex(#[from] gix_status::tree_index::Error),
+}
+
+impl Repository {
+    /// Obtain the changes between the tree with `tree_id`, typically the one of `HEAD`, and `worktree_index`,
+    /// which is what would be committed next, similar to `git diff --cached`.
+    ///
+    /// * `tree_id`
+    ///     - The tree to compare the index to, which is turned into an index in memory for comparison.
+    /// * `worktree_index`
+    ///     - The index to compare the tree to. Conflicting entries are reported as such, and entries added with
+    ///       `git add --intent-to-add` are ignored.
+    /// * `patterns`
+    ///     - Optional patterns to use to limit the paths to look at. If empty, all paths are considered.
+    /// * `rewrites`
+    ///     - If `Some(_)`, renames and copies will be tracked, which delays the output of all entries that participate in the
+    ///       rewrite tracking until all changes are known.
+    /// * `cb`
+    ///     - Called for each change, and may return [`Action::Cancel`](gix_diff::tree::visit::Action::Cancel) to stop the operation.
+    ///
+    /// ### Note
+    ///
+    /// This is a lower-level method, prefer the [`status`](Repository::status()) method for greater ease of use.
+    pub fn tree_index_status(
+        &self,
+        tree_id: &gix_hash::oid,
+        worktree_index: &gix_index::State,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+        rewrites: Option<gix_diff::Rewrites>,
+        cb: impl FnMut(Entry<'_, '_>) -> gix_diff::tree::visit::Action,
+    ) -> Result<gix_status::tree_index::Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::tree_index_status");
+        let tree_index = if tree_id == gix_hash::ObjectId::empty_tree(self.object_hash()) {
+            gix_index::File::from_state(gix_index::State::new(self.object_hash()), self.index_path())
+        } else {
+            self.index_from_tree(tree_id)?
+        };
+        let attributes = self
+            .attributes_only(
+                worktree_index,
+                gix_worktree::stack::state::attributes::Source::IdMapping,
+            )?
+            .inner;
+        let pathspec = crate::Pathspec::new(self, false, patterns, true, || Ok(attributes.clone()))?;
+        let mut pathspec_attributes = pathspec.stack;
+        let mut resource_cache = crate::diff::resource_cache(
+            self,
+            gix_diff::blob::pipeline::Mode::ToGit,
+            attributes,
+            Default::default(),
+        )?;
+
+        struct Delegate<F>(F);
+        impl<'lhs, 'rhs, F> gix_status::tree_index::VisitEntry<'lhs, 'rhs> for Delegate<F>
+        where
+            F: FnMut(Entry<'_, '_>) -> gix_diff::tree::visit::Action,
+        {
+            fn visit_entry(&mut self, entry: Entry<'lhs, 'rhs>) -> gix_diff::tree::visit::Action {
+                (self.0)(entry)
+            }
+        }
+
+        Ok(gix_status::tree_index(
+            &tree_index,
+            worktree_index,
+            &mut Delegate(cb),
+            &self.objects,
+            gix_status::tree_index::Context {
+                pathspec: pathspec.search,
+                pathspec_attributes: &mut |relative_path, case, is_dir, out| {
+                    let stack = pathspec_attributes
+                        .as_mut()
+                        .expect("can only be called if attributes are used in patterns");
+                    let mode = if is_dir {
+                        gix_index::entry::Mode::DIR
+                    } else {
+                        gix_index::entry::Mode::FILE
+                    };
+                    stack
+                        .set_case(case)
+                        .at_entry(relative_path, Some(mode), &self.objects)
+                        .map_or(false, |platform| platform.matching_attributes(out))
+                },
+                resource_cache: &mut resource_cache,
+            },
+            gix_status::tree_index::Options { rewrites },
+        )?)
+    }
+}
+
+/// A change between a tree and the index, as produced by [`status::Iter`](crate::status::Iter).
+///
+/// Entries of the tree are referred to as `previous_*` or `source_*`.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Item {
+    /// An entry was added to the index, and isn't present in the tree.
+    Addition {
+        /// The added index entry.
+        entry: gix_index::Entry,
+        /// The position of `entry` in [`gix_index::State::entries()`].
+        entry_index: usize,
+        /// The repository-relative path of the entry.
+        rela_path: BString,
+    },
+    /// An entry of the tree is missing in the index.
+    Deletion {
+        /// The entry of the tree that was deleted, as if it was an index entry.
+        entry: gix_index::Entry,
+        /// The repository-relative path of the entry.
+        rela_path: BString,
+    },
+    /// An entry is present in both, but changed its content or its mode or both.
+    ///
+    /// If both entries are submodules, the commit that is checked out in the submodule changed.
+    Modification {
+        /// The entry in the tree, as if it was an index entry.
+        previous_entry: gix_index::Entry,
+        /// The entry in the index.
+        entry: gix_index::Entry,
+        /// The position of `entry` in [`gix_index::State::entries()`].
+        entry_index: usize,
+        /// The repository-relative path of the entry, which is the same in the tree and in the index.
+        rela_path: BString,
+    },
+    /// The rewrite tracking matched an entry of the tree to an index entry at a different location.
+    Rewrite {
+        /// The entry of the tree that is the source of the rewrite, as if it was an index entry.
+        source_entry: gix_index::Entry,
+        /// The repository-relative path of `source_entry`.
+        source_rela_path: BString,
+        /// The index entry that is the destination of the rewrite.
+        entry: gix_index::Entry,
+        /// The position of `entry` in [`gix_index::State::entries()`].
+        entry_index: usize,
+        /// The repository-relative path of `entry`.
+        rela_path: BString,
+        /// It's `None` if `source_entry` and `entry` have the same id, as identity made an actual diff computation unnecessary.
+        /// Otherwise, and if enabled, it's `Some(stats)` to indicate how similar both entries were.
+        diff: Option<gix_diff::blob::DiffLineStats>,
+        /// If true, this rewrite is created by copy, and `source_entry` still exists in the index.
+        /// Otherwise, it's a rename, and `source_entry` was deleted.
+        copy: bool,
+    },
+    /// The index has unresolved conflicts at a path.
+    Conflict {
+        /// The first of the index entries that make up the conflict.
+        entry: gix_index::Entry,
+        /// The position of `entry` in [`gix_index::State::entries()`], with all other stages following it.
+        entry_index: usize,
+        /// The repository-relative path of the conflicting entries.
+        rela_path: BString,
+        /// The kind of conflict.
+        conflict: Conflict,
+    },
+}
+
+impl Item {
+    /// Return a simplified summary of the item as digest of its status.
+    pub fn summary(&self) -> Summary {
+        match self {
+            Item::Addition { .. } => Summary::Added,
+            Item::Deletion { .. } => Summary::Removed,
+            Item::Modification {
+                previous_entry, entry, ..
+            } => Summary::from_modification(previous_entry.mode, entry.mode),
+            Item::Rewrite { copy, .. } => {
+                if *copy {
+                    Summary::Copied
+                } else {
+                    Summary::Renamed
+                }
+            }
+            Item::Conflict { .. } => Summary::Conflict,
+        }
+    }
+
+    /// The repository-relative path of the entry in the index, or of the deleted entry in the tree.
+    pub fn rela_path(&self) -> &BStr {
+        match self {
+            Item::Addition { rela_path, .. }
+            | Item::Deletion { rela_path, .. }
+            | Item::Modification { rela_path, .. }
+            | Item::Rewrite { rela_path, .. }
+            | Item::Conflict { rela_path, .. } => rela_path.as_ref(),
+        }
+    }
+
+    /// The repository-relative path at which the source of a rewrite is located.
+    ///
+    /// If this isn't a rewrite, the path is the location of the entry itself.
+    pub fn source_rela_path(&self) -> &BStr {
+        match self {
+            Item::Rewrite { source_rela_path, .. } => source_rela_path.as_ref(),
+            _ => self.rela_path(),
+        }
+    }
+}
+
+impl From<Entry<'_, '_>> for Item {
+    fn from(value: Entry<'_, '_>) -> Self {
+        match value {
+            Entry::Addition {
+                entry,
+                entry_index,
+                rela_path,
+            } => Item::Addition {
+                entry: entry.clone(),
+                entry_index,
+                rela_path: rela_path.to_owned(),
+            },
+            Entry::Deletion {
+                entry,
+                entry_index: _,
+                rela_path,
+            } => Item::Deletion {
+                entry: entry.clone(),
+                rela_path: rela_path.to_owned(),
+            },
+            Entry::Modification {
+                previous_entry,
+                previous_entry_index: _,
+                entry,
+                entry_index,
+                rela_path,
+            } => Item::Modification {
+                previous_entry: previous_entry.clone(),
+                entry: entry.clone(),
+                entry_index,
+                rela_path: rela_path.to_owned(),
+            },
+            Entry::Rewrite {
+                source_entry,
+                source_entry_index: _,
+                source_rela_path,
+                entry,
+                entry_index,
+                rela_path,
+                diff,
+                copy,
+            } => Item::Rewrite {
+                source_entry: source_entry.clone(),
+                source_rela_path: source_rela_path.to_owned(),
+                entry: entry.clone(),
+                entry_index,
+                rela_path: rela_path.to_owned(),
+                diff,
+                copy,
+            },
+            Entry::Conflict {
+                entry,
+                entry_index,
+                rela_path,
+                conflict,
+            } => Item::Conflict {
+                entry: entry.clone(),
+                entry_index,
+                rela_path: rela_path.to_owned(),
+                conflict,
+            },
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! Switch the worktree, the index and `HEAD` from one commit to another, similar to `git switch` and `git checkout <branch>`.
+//!
+//! This is what [`Repository::switch()`](crate::Repository::switch()) does, which performs a two-way merge from the tree
+//! of `HEAD` to the tree of the target into the index and only writes the files that changed between both trees.
+use gix_hash::ObjectId;
+use gix_ref::FullName;
+
+use crate::bstr::BString;
+
+/// What to switch to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// Check out the branch with the given full name, like `refs/heads/main`, and make `HEAD` point to it.
+    Branch(FullName),
+    /// Check out the commit with the given id and detach `HEAD`, like `git switch --detach <commit>`.
+    Detached(ObjectId),
+}
+
+/// Options for use in [`Repository::switch()`](crate::Repository::switch()).
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// If set, create a new branch with this full name, like `refs/heads/feature`, at the commit of the target and make `HEAD`
+    /// point to it, like `git checkout -b <name>`. The branch must not exist yet.
+    pub new_branch: Option<FullName>,
+    /// If `true`, local changes to files that differ between both trees are discarded, and untracked files in the way
+    /// are overwritten, like `git checkout --force`.
+    ///
+    /// This also resolves conflicts in the index by replacing them with their version in the target.
+    pub force: bool,
+    /// If `true`, files with local changes that differ between both trees are merged with their version in the target
+    /// instead of causing an error, like `git checkout --merge`.
+    ///
+    /// Conflicts are marked with conflict markers in the worktree, with the index receiving the version of the target.
+    /// Only files whose local, current and target versions are all regular files can be merged.
+    pub merge: bool,
+}
+
+/// Information about a file whose local changes were merged into its new version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergedFile {
+    /// The path of the file relative to the root of the worktree.
+    pub path: BString,
+    /// The amount of conflicts that are marked in the file.
+    pub num_conflicts: usize,
+}
+
+/// The outcome of [`Repository::switch()`](crate::Repository::switch()).
+#[derive(Debug)]
+pub struct Outcome {
+    /// The id of the commit that `HEAD` points to now.
+    pub commit: ObjectId,
+    /// Information about the files that were written to the worktree as they changed between both trees.
+    pub checkout: gix_worktree_state::checkout::Outcome,
+    /// The amount of files that were removed from the worktree as they don't exist in the target.
+    pub files_removed: usize,
+    /// The files whose local changes were merged, if [merging](Options::merge) was enabled.
+    pub merged: Vec<MergedFile>,
+}
+
+impl Outcome {
+    /// Return `true` if there are no conflicts in merged files.
+    pub fn is_clean(&self) -> bool {
+        self.merged.iter().all(|file| file.num_conflicts == 0)
+    }
+}
+
+/// The progress ids used in [`Repository::switch()`](crate::Repository::switch()).
+///
+/// Use this information to selectively extract the progress of interest in case the parent application has custom visualization.
+#[derive(Debug, Copy, Clone)]
+pub enum ProgressId {
+    /// The amount of files checked out thus far.
+    CheckoutFiles,
+    /// The amount of bytes written in total, the aggregate of the size of the content of all files thus far.
+    BytesWritten,
+}
+
+impl From<ProgressId> for gix_features::progress::Id {
+    fn from(v: ProgressId) -> Self {
+        match v {
+            ProgressId::CheckoutFiles => *b"SWCF",
+            ProgressId::BytesWritten => *b"SWCB",
+        }
+    }
+}
+
+/// The error returned by [`Repository::switch()`](crate::Repository::switch()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A worktree is required to switch to another commit")]
+    MissingWorktree,
+    #[error("The index has unresolved conflicts at {paths:?}")]
+    UnmergedIndex { paths: Vec<BString> },
+    #[error("Local changes to {paths:?} would be overwritten")]
+    LocalChanges { paths: Vec<BString> },
+    #[error("Untracked files at {paths:?} would be overwritten")]
+    UntrackedFiles { paths: Vec<BString> },
+    #[error("The branch {name:?} to create already exists")]
+    BranchExists { name: FullName },
+    #[error("Could not access {path:?} in the worktree")]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
+    OpenArcOdb(#[source] std::io::Error),
+    #[error(transparent)]
+    FindReference(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    FindReferenceOptional(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    PeelReference(#[from] crate::reference::peel::Error),
+    #[error(transparent)]
+    PeelHead(#[from] crate::head::peel::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+    #[error(transparent)]
+    BooleanConfig(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    DiffAlgorithm(#[from] crate::config::diff::algorithm::Error),
+    #[error(transparent)]
+    CheckoutOptions(#[from] crate::config::checkout_options::Error),
+    #[error(transparent)]
+    FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+    #[error(transparent)]
+    ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+    #[error(transparent)]
+    ConvertToWorktree(#[from] crate::filter::pipeline::convert_to_worktree::Error),
+    #[error(transparent)]
+    Checkout(#[from] gix_worktree_state::checkout::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+}
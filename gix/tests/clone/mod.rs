@@ -198,6 +198,65 @@ mod blocking_io {
         Ok(())
     }
 
+    #[test]
+    fn shallow_follows_tags_into_fetched_history() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, _out) = gix::prepare_clone_bare(remote::repo("base").path(), tmp.path())?
+            .with_shallow(Shallow::DepthAtRemote(1.try_into()?))
+            .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        assert!(repo.is_shallow());
+        let tags = repo
+            .references()?
+            .tags()?
+            .map(|r| r.map(|r| r.name().shorten().to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            tags,
+            ["b-tag", "i-tag"],
+            "like git, only tags pointing into the fetched history are obtained instead of all tags with their history"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn shallow_commits_that_do_not_exist_are_pruned() -> crate::Result {
+        use std::io::Write;
+
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, _change) = gix::prepare_clone_bare(remote::repo("base").path(), tmp.path())?
+            .with_shallow(Shallow::DepthAtRemote(2.try_into()?))
+            .configure_remote(|mut r| {
+                r.replace_refspecs(Some("refs/heads/main:refs/remotes/origin/main"), Direction::Fetch)?;
+                Ok(r)
+            })
+            .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(repo.shallow_file())?
+            .write_all(b"ffffffffffffffffffffffffffffffffffffffff\n")?;
+        let repo = gix::open_opts(repo.path(), restricted())?;
+        assert_eq!(repo.shallow_commits()?.expect("present").len(), 3);
+
+        let remote = repo.head()?.into_remote(Direction::Fetch).expect("present")?;
+        remote
+            .connect(Direction::Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .with_shallow(Shallow::Deepen(1))
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+
+        assert_eq!(
+            repo.shallow_commits()?.expect("present").as_slice(),
+            vec![
+                hex_to_id("27e71576a6335294aa6073ab767f8b36bdba81d0"),
+                hex_to_id("82024b2ef7858273337471cbd1ca1cedbdfd5616"),
+                hex_to_id("b5152869aedeb21e55696bb81de71ea1bb880c85"),
+            ],
+            "the shallow commit that doesn't exist is removed while writing the updated boundary"
+        );
+        Ok(())
+    }
+
     #[test]
     fn from_non_shallow_by_deepen_exclude_then_deepen_to_unshallow() -> crate::Result {
         let tmp = gix_testtools::tempfile::TempDir::new()?;
@@ -717,6 +776,236 @@ mod blocking_io {
         }
         Ok(())
     }
+
+    #[test]
+    fn fetch_and_checkout_from_bundle() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let bundle = remote::repo_path("base.bundle");
+        let (mut checkout, out) = gix::clone::PrepareFetch::new(
+            bundle.as_path(),
+            tmp.path(),
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            restricted(),
+        )?
+        .fetch_then_checkout(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+        assert!(
+            matches!(out.status, gix::remote::fetch::Status::Change { .. }),
+            "the pack of the bundle was received"
+        );
+        let (repo, _) = checkout.main_worktree(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        let remote_repo = remote::repo("base");
+        assert_eq!(
+            repo.head_id()?,
+            remote_repo.head_id()?,
+            "HEAD is provided by the bundle and checked out"
+        );
+        assert_eq!(
+            repo.references()?.remote_branches()?.count(),
+            remote_repo.references()?.local_branches()?.count() + 1,
+            "all branches are cloned, along with the remote HEAD"
+        );
+        assure_index_entries_on_disk(&repo.index()?, repo.work_dir().expect("non-bare"));
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_from_bundle_with_missing_prerequisites_fails() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let bundle = remote::repo_path("bundle-uri/main.bundle");
+        let err = gix::clone::PrepareFetch::new(
+            bundle.as_path(),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            restricted(),
+        )?
+        .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())
+        .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                gix::clone::fetch::Error::Fetch(gix::remote::fetch::Error::BundlePrerequisites(ref err)) if !err.missing.is_empty()
+            ),
+            "the bundle requires the tip of `g` which the new repository doesn't have: {err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_only_bootstrapped_from_bundle_list() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, _out) = gix::clone::PrepareFetch::new(
+            remote::repo("base").path(),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            restricted(),
+        )?
+        .with_bundle_uri(gix::path::into_bstr(remote::repo_path("bundle-uri/list")).into_owned())
+        .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        let remote_repo = remote::repo("base");
+        for branch in ["main", "g"] {
+            assert_eq!(
+                repo.find_reference(format!("refs/bundles/{branch}").as_str())?.id(),
+                remote_repo
+                    .find_reference(format!("refs/heads/{branch}").as_str())?
+                    .id(),
+                "the list applies the bundle for 'g' first as it provides the prerequisites for 'main'"
+            );
+        }
+        assert_eq!(
+            repo.find_reference("refs/remotes/origin/main")?.id(),
+            remote_repo.find_reference("refs/heads/main")?.id(),
+            "the clone completes as usual"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_only_bootstrapped_from_configured_bundle() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let bundle = remote::repo_path("bundle-uri/g.bundle");
+        let (repo, _out) = gix::clone::PrepareFetch::new(
+            remote::repo("base").path(),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            restricted(),
+        )?
+        .with_in_memory_config_overrides(Some(format!(
+            "{}={}",
+            Clone::BUNDLE_URI.logical_name(),
+            bundle.display()
+        )))
+        .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        let remote_repo = remote::repo("base");
+        assert_eq!(
+            repo.find_reference("refs/bundles/g")?.id(),
+            remote_repo.find_reference("refs/heads/g")?.id()
+        );
+        assert!(
+            repo.try_find_reference("refs/bundles/main")?.is_none(),
+            "only the configured bundle is applied"
+        );
+        assert_eq!(
+            repo.find_reference("refs/remotes/origin/main")?.id(),
+            remote_repo.find_reference("refs/heads/main")?.id(),
+            "the remainder is fetched from the remote"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_only_bootstrapped_from_bundle_receives_a_smaller_pack() -> crate::Result {
+        let clone = |bundle_uri: Option<&Path>| -> crate::Result<u32> {
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let mut prepare = gix::clone::PrepareFetch::new(
+                remote::repo("base").path(),
+                tmp.path(),
+                gix::create::Kind::Bare,
+                Default::default(),
+                restricted(),
+            )?;
+            if let Some(uri) = bundle_uri {
+                prepare = prepare.with_bundle_uri(gix::path::into_bstr(uri).into_owned());
+            }
+            let (_repo, out) = prepare.fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+            match out.status {
+                gix::remote::fetch::Status::Change { write_pack_bundle, .. } => Ok(write_pack_bundle.index.num_objects),
+                _ => unreachable!("the clone always receives a pack"),
+            }
+        };
+        let without_bundle = clone(None)?;
+        let with_bundle = clone(Some(&remote::repo_path("bundle-uri/g.bundle")))?;
+        assert!(
+            with_bundle < without_bundle,
+            "objects of the bundle are known to the server as they are advertised as 'have': {with_bundle} < {without_bundle}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_only_downloads_packs_advertised_via_packfile_uris() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, out) = gix::clone::PrepareFetch::new(
+            remote::repo("packfile-uris").path(),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            restricted(),
+        )?
+        .with_in_memory_config_overrides(Some("fetch.uriProtocols=file"))
+        .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        assert_eq!(out.packfile_uris.len(), 1, "the large blob is downloaded separately");
+        assert_eq!(out.packfile_uris[0].index.num_objects, 1);
+        let gix::remote::fetch::Status::Change { write_pack_bundle, .. } = out.status else {
+            unreachable!("the remainder is sent by the server")
+        };
+        assert_eq!(
+            write_pack_bundle.index.num_objects, 3,
+            "the commit, its tree and the small blob"
+        );
+
+        let remote_repo = remote::repo("packfile-uris");
+        let tree = remote_repo.head_commit()?.tree()?;
+        for name in ["large", "small"] {
+            let id = tree.find_entry(name).expect("present").object_id();
+            assert!(repo.has_object(id), "{name} is available after the clone");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_only_receives_everything_if_no_protocol_of_packfile_uris_is_allowed() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (_repo, out) = gix::clone::PrepareFetch::new(
+            remote::repo("packfile-uris").path(),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            restricted(),
+        )?
+        .with_in_memory_config_overrides(Some("fetch.uriProtocols=https"))
+        .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        assert!(
+            out.packfile_uris.is_empty(),
+            "the server only sends URIs with allowed protocols"
+        );
+        let gix::remote::fetch::Status::Change { write_pack_bundle, .. } = out.status else {
+            unreachable!("everything is sent by the server")
+        };
+        assert_eq!(write_pack_bundle.index.num_objects, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_only_fails_if_the_checksum_of_a_downloaded_pack_does_not_match() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let err = gix::clone::PrepareFetch::new(
+            remote::repo("packfile-uris-bad-checksum").path(),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            restricted(),
+        )?
+        .with_in_memory_config_overrides(Some("fetch.uriProtocols=file"))
+        .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())
+        .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                gix::clone::fetch::Error::Fetch(gix::remote::fetch::Error::PackfileUriChecksumMismatch { .. })
+            ),
+            "{err:?}"
+        );
+        Ok(())
+    }
 }
 
 #[test]
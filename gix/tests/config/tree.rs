@@ -433,6 +433,64 @@ mod core {
         Ok(())
     }
 
+    #[test]
+    fn fs_monitor() {
+        for value in ["true", "false", "yes", "off", ""] {
+            assert_eq!(
+                Core::FS_MONITOR.hook_program(bcow(value)),
+                None,
+                "booleans don't configure a hook"
+            );
+            assert!(Core::FS_MONITOR.validate(value.into()).is_ok());
+        }
+        assert_eq!(
+            Core::FS_MONITOR.hook_program(bcow(".git/hooks/query-watchman")),
+            Some(bcow(".git/hooks/query-watchman"))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "status")]
+    fn fs_monitor_hook_version() -> crate::Result {
+        use gix::status::plumbing::fs_monitor::Version;
+        for (value, expected) in [("1", Version::V1), ("2", Version::V2)] {
+            assert_eq!(
+                Core::FS_MONITOR_HOOK_VERSION.try_into_hook_version(bcow(value))?,
+                expected
+            );
+            assert!(Core::FS_MONITOR_HOOK_VERSION.validate(value.into()).is_ok());
+        }
+        for value in ["0", "3", "v2"] {
+            assert_eq!(
+                Core::FS_MONITOR_HOOK_VERSION
+                    .try_into_hook_version(bcow(value))
+                    .unwrap_err()
+                    .to_string(),
+                format!("The key \"core.fsmonitorHookVersion={value}\" was invalid")
+            );
+            assert!(Core::FS_MONITOR_HOOK_VERSION.validate(value.into()).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn untracked_cache() -> crate::Result {
+        for (value, expected) in [("true", Some(true)), ("false", Some(false)), ("keep", None)] {
+            let boolean = gix_config::Boolean::try_from(bcow(value)).map(|b| b.0);
+            assert_eq!(Core::UNTRACKED_CACHE.try_into_untracked_cache(boolean)?, expected);
+            assert!(Core::UNTRACKED_CACHE.validate(value.into()).is_ok());
+        }
+        assert_eq!(
+            Core::UNTRACKED_CACHE
+                .try_into_untracked_cache(gix_config::Boolean::try_from(bcow("sometimes")).map(|b| b.0))
+                .unwrap_err()
+                .to_string(),
+            "The key \"core.untrackedCache=sometimes\" was invalid"
+        );
+        assert!(Core::UNTRACKED_CACHE.validate("sometimes".into()).is_err());
+        Ok(())
+    }
+
     #[test]
     fn log_all_ref_updates() -> crate::Result {
         assert_eq!(
@@ -651,6 +709,56 @@ mod index {
     }
 }
 
+mod gc {
+    use std::time::{Duration, SystemTime};
+
+    use gix::config::tree::{Gc, Key};
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn reflog_expire() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100 * 24 * 60 * 60);
+        let ten_days_ago = 90 * 24 * 60 * 60;
+        for value in ["10.days.ago", "10 days ago", "10.days", "10 days"] {
+            assert_eq!(
+                Gc::REFLOG_EXPIRE.try_into_expiry(bcow(value), now).unwrap(),
+                Some(ten_days_ago),
+                "{value}"
+            );
+            assert!(Gc::REFLOG_EXPIRE.validate(value.into()).is_ok());
+        }
+        for value in ["never", "false"] {
+            assert_eq!(
+                Gc::REFLOG_EXPIRE_UNREACHABLE.try_into_expiry(bcow(value), now).unwrap(),
+                None,
+                "entries never expire"
+            );
+        }
+        for value in ["now", "all"] {
+            assert_eq!(
+                Gc::REFLOG_EXPIRE_UNREACHABLE.try_into_expiry(bcow(value), now).unwrap(),
+                Some(i64::MAX),
+                "all entries expire"
+            );
+        }
+        assert_eq!(
+            Gc::REFLOG_EXPIRE_UNREACHABLE
+                .try_into_expiry(bcow("1234"), now)
+                .unwrap(),
+            Some(1234),
+            "absolute dates are supported as well"
+        );
+        assert_eq!(
+            Gc::REFLOG_EXPIRE
+                .try_into_expiry(bcow("sometime"), now)
+                .unwrap_err()
+                .to_string(),
+            "The key \"gc.reflogExpire=sometime\" was invalid"
+        );
+    }
+}
+
 mod extensions {
     use gix::config::tree::{Extensions, Key};
 
@@ -0,0 +1,28 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+seq 1 20 >numbers
+echo hello >greeting
+echo gone >deleted
+seq 1 20 >to-rename
+printf 'binary\0data\n' >binary
+git add . && git commit -q -m base
+git tag base
+
+git checkout -q -b patched
+sed -i.bak -e 's/^2$/two/' -e 's/^15$/fifteen/' numbers && rm numbers.bak
+chmod +x greeting
+git rm -q deleted
+mkdir dir && echo new >dir/added
+git mv to-rename renamed && echo 21 >>renamed
+printf 'binary\0other data\n' >binary
+git add . && git commit -q -m patched
+git diff --binary --full-index base patched >.git/changes.patch
+
+git checkout -q main
+sed -i.bak 's/^2$/deux/' numbers && rm numbers.bak
+git commit -q -am "conflicting change"
+git format-patch -q -1 --stdout >.git/conflicting.patch
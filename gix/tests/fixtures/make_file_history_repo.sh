@@ -0,0 +1,40 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+git config merge.ff false
+
+time=1000000000
+function commit() {
+  local message=${1:?first argument is the commit message}
+  time=$((time + 3600))
+  GIT_AUTHOR_DATE="$time +0000" GIT_COMMITTER_DATE="$time +0000" git commit -q --allow-empty -m "$message"
+}
+function merge() {
+  local branch=${1:?first argument is the branch to merge}
+  local message=${2:?second argument is the commit message}
+  time=$((time + 3600))
+  GIT_AUTHOR_DATE="$time +0000" GIT_COMMITTER_DATE="$time +0000" git merge -q --no-edit "$branch" -m "$message" >/dev/null
+}
+
+seq 1 20 >a && echo x >other && git add . && commit "add a"
+seq 1 21 >a && git add a && commit "modify a"
+echo y >other && git add other && commit "modify other"
+git mv a b && commit "rename a to b"
+seq 0 21 >b && git add b && commit "modify b"
+git checkout -q -b side
+seq 0 22 >b && git add b && commit "modify b on the side"
+git checkout -q main
+echo z >other && git add other && commit "modify other on main"
+merge side "merge side"
+mkdir dir && git mv b dir/c && seq 0 23 >dir/c && git add dir/c && commit "rename b to dir/c with changes"
+seq 0 24 >dir/c && git add dir/c && commit "modify dir/c"
+git rm -q other && commit "delete other"
+git commit-graph write --no-progress --reachable --changed-paths
+
+for path in dir/c other; do
+  echo "$path"
+  git log --follow --format="commit %H" --name-status -- "$path" | grep -v '^$'
+  echo
+done > baseline.git
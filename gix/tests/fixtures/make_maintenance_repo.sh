@@ -0,0 +1,20 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+git commit -q --allow-empty -m c1
+echo a >a && git add a && git commit -q -m c2
+git tag -a -m "annotated" v1
+git repack -q -d
+
+echo b >b && git add b && git commit -q -m c3
+git repack -q -d
+
+echo c >c && git add c && git commit -q -m c4
+echo amended >c && git add c && git commit -q --amend -m c4-amended
+echo staged >staged && git add staged
+
+echo "unreachable" | git hash-object -w --stdin >/dev/null
+echo "also unreachable" | git hash-object -w --stdin >/dev/null
@@ -0,0 +1,30 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+git commit -q --allow-empty -m A
+git commit -q --allow-empty -m B
+git tag base
+git commit -q --allow-empty -m C
+
+git checkout -q -b topic base
+git commit -q --allow-empty -m D
+git commit -q --allow-empty -m E
+
+git checkout -q -b left main
+git merge -q --no-edit --no-ff topic -m M1
+git checkout -q -b right topic
+git merge -q --no-edit --no-ff main -m M2
+
+git checkout -q -b upstream main
+git commit -q --allow-empty -m U1
+git tag U1
+git checkout -q -b feature
+git commit -q --allow-empty -m F1
+git checkout -q upstream
+git reset -q --hard main
+git commit -q --allow-empty -m U1-rewritten
+
+git checkout -q main
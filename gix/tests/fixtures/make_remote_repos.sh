@@ -381,3 +381,66 @@ git init empty-core-askpass
 (cd empty-core-askpass
   echo "    askpass =" >> .git/config
 )
+
+git -C base bundle create ../base.bundle --all
+
+mkdir bundle-uri
+git -C base bundle create ../bundle-uri/g.bundle refs/heads/g
+git -C base bundle create ../bundle-uri/main.bundle g..main
+cat <<EOF >bundle-uri/list
+[bundle]
+	version = 1
+	mode = all
+[bundle "main"]
+	uri = main.bundle
+[bundle "g"]
+	uri = g.bundle
+EOF
+
+git clone --shared base legacy-remote-files
+(cd legacy-remote-files
+  base_dir="$(cd ../base && pwd -P)"
+  git remote add myself "$(pwd -P)"
+  git config remotes.mirrors "origin myself"
+  git config --add remotes.mirrors "from-remotes-file"
+  git config remotes.empty ""
+
+  mkdir -p .git/remotes .git/branches
+  {
+    echo "URL: $base_dir"
+    echo "Pull: refs/heads/main:refs/remotes/from-remotes-file/main"
+    echo "Pull: +refs/heads/g:refs/remotes/from-remotes-file/g"
+    echo "Push: refs/heads/main:refs/heads/legacy"
+  } > .git/remotes/from-remotes-file
+  echo "$base_dir#g" > .git/branches/from-branches-file
+  echo "$base_dir" > .git/branches/from-branches-file-default
+
+  git config remote.with-configured-fetchspec.fetch "+refs/heads/main:refs/remotes/with-configured-fetchspec/main"
+  echo "$base_dir#g" > .git/branches/with-configured-fetchspec
+  git config remote.without-url.fetch "+refs/heads/*:refs/remotes/without-url/*"
+)
+
+mkdir packfile-uris-cdn
+git init packfile-uris
+(cd packfile-uris
+  git config uploadpack.allowSidebandAll true
+  echo "content that is served from elsewhere" > large
+  echo "content that is sent along" > small
+  git add . && git commit -q -m "one large, one small"
+  blob=$(git rev-parse HEAD:large)
+  pack=$(echo "$blob" | git pack-objects -q ../packfile-uris-cdn/pack)
+  git config uploadpack.blobPackfileUri "$blob $pack file://$(cd ../packfile-uris-cdn && pwd -P)/pack-$pack.pack"
+)
+
+# The objects have to be loose as `git` only sends the URIs of blobs it doesn't find in a pack.
+git init packfile-uris-bad-checksum
+(cd packfile-uris-bad-checksum
+  git config uploadpack.allowSidebandAll true
+  echo "content that is served from elsewhere" > large
+  echo "content that is sent along" > small
+  git add . && git commit -q -m "one large, one small"
+  blob=$(git rev-parse HEAD:large)
+  pack=$(cd ../packfile-uris-cdn && ls pack-*.pack | sed -e 's/^pack-//' -e 's/\.pack$//')
+  wrong_pack=$(echo "$pack" | tr 0-9a-f 1-9a-f0)
+  git config uploadpack.blobPackfileUri "$blob $wrong_pack file://$(cd ../packfile-uris-cdn && pwd -P)/pack-$pack.pack"
+)
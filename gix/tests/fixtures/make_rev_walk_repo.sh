@@ -0,0 +1,66 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+git config merge.ff false
+
+time=1000000000
+function commit() {
+  local message=${1:?first argument is the commit message}
+  time=$((time + 3600))
+  GIT_AUTHOR_DATE="$time +0000" GIT_COMMITTER_DATE="$time +0000" git commit -q --allow-empty -m "$message"
+}
+function merge() {
+  local branch=${1:?first argument is the branch to merge}
+  local message=${2:?second argument is the commit message}
+  shift 2
+  time=$((time + 3600))
+  GIT_AUTHOR_DATE="$time +0000" GIT_COMMITTER_DATE="$time +0000" git merge -q --no-edit "$branch" -m "$message" "$@" >/dev/null
+}
+
+echo asdf >foo && echo x >other && git add . && commit I
+git tag I
+echo quux >foo && git add foo && commit "A: change foo"
+git checkout -q -b side I
+echo y >other && git add other && GIT_AUTHOR_NAME="Side Author" GIT_AUTHOR_EMAIL="side@example.com" commit "B: change other"
+git checkout -q main
+merge side "M: merge side"
+git checkout -q -b side2 main
+echo z >other && git add other && commit "C: change other again"
+echo side >foo && git add foo && commit "D: change foo on the side"
+git checkout -q main
+echo final >foo && git add foo && commit "E: change foo"
+mkdir dir && echo new >dir/file && git add dir && commit "F: add dir/file"
+git checkout -q side2
+merge main "N: merge main into side2, keeping our foo" -X ours
+git checkout -q main
+merge side2 "O: merge side2"
+git commit-graph write --no-progress --reachable
+
+function baseline() {
+  local name=${1:?first argument is the name of the baseline}
+  shift
+  {
+    echo "$name"
+    git rev-list "$@"
+    echo
+  } >>baseline.git
+}
+
+after_a=$((1000000000 + 2 * 3600))
+before_f=$((time - 2 * 3600))
+baseline hidden main ^side
+baseline hidden-merged main ^side2~1 ^side
+baseline since-until main --since=$after_a --until=$before_f
+baseline author main --author="^Side Author"
+baseline committer main --committer=nobody
+baseline grep main --grep="^[AD]: change foo"
+baseline no-merges main --no-merges
+baseline merges main --merges
+baseline skip-and-max-count main --skip=2 --max-count=3
+baseline ancestry-path main --ancestry-path ^side
+baseline paths main -- foo
+baseline paths-dir main -- dir
+baseline full-history main --full-history -- foo
+baseline simplify-merges main --simplify-merges -- foo
@@ -13,3 +13,40 @@ git init -q untracked-only
   mkdir new
   touch new/untracked subdir/untracked
 )
+
+git init -q staged-and-unstaged
+(cd staged-and-unstaged
+  printf '1\n2\n3\n4\n5\n6\n7\n8\n' >to-rename
+  echo content >modified
+  echo content >unstaged
+  git add .
+  git commit -q -m init
+
+  git mv to-rename renamed
+  echo changed >modified
+  echo added >added
+  git add modified added
+  echo "changed with a different size" >unstaged
+)
+
+git init -q unborn-with-index
+(cd unborn-with-index
+  echo content >staged
+  git add staged
+)
+
+git init -q fs-monitor
+(cd fs-monitor
+  echo content >modified
+  echo content >unreported
+  git add .
+  git commit -q -m init
+
+  cat <<'HOOK' >.git/hooks/query-fsmonitor
+#!/bin/sh
+printf 'token-2\0modified\0'
+HOOK
+  chmod +x .git/hooks/query-fsmonitor
+  git config core.fsmonitor .git/hooks/query-fsmonitor
+  git config core.fsmonitorHookVersion 2
+)
@@ -0,0 +1,20 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo same >unchanged
+echo 1 >changed
+echo gone >removed
+seq 1 10 >numbers
+git add . && git commit -q -m base
+
+git checkout -q -b other
+echo 2 >changed
+git rm -q removed
+mkdir dir && echo new >dir/added
+sed -i.bak 's/^2$/two/' numbers && rm numbers.bak
+git add . && git commit -q -m other
+
+git checkout -q main
@@ -101,4 +101,128 @@ fn set_target_id() {
         .starts_with("Reference \"refs/heads/main\" was supposed to exist"));
 }
 
+#[test]
+fn rename_and_copy_with_branch_configuration() -> crate::Result {
+    use gix::refs::transaction::RenameMode;
+
+    use crate::repo_rw;
+    let (repo, tmp) = repo_rw("make_basic_repo.sh")?;
+    let config_path = repo.git_dir().join("config");
+    let mut config = std::fs::read_to_string(&config_path)?;
+    config.push_str(
+        "[branch \"main\"]\n\tremote = origin\n\tmerge = refs/heads/main\n[include]\n\tpath = included.config\n",
+    );
+    std::fs::write(&config_path, config)?;
+    std::fs::write(repo.git_dir().join("included.config"), "[user]\n\tname = included\n")?;
+    let mut repo = gix::open_opts(repo.git_dir(), crate::restricted())?;
+    let head_id = repo.head_id()?.detach();
+    let num_log_entries = repo.find_reference("main")?.log_iter().all()?.expect("present").count();
+
+    let renamed = repo.rename_reference(
+        "refs/heads/main".try_into()?,
+        "refs/heads/renamed".try_into()?,
+        RenameMode::Move,
+        "Branch: renamed refs/heads/main to refs/heads/renamed",
+    )?;
+    assert_eq!(renamed.id(), head_id);
+    assert_eq!(
+        renamed.log_iter().all()?.expect("present").count(),
+        num_log_entries + 1,
+        "the reflog moves with the reference"
+    );
+    assert!(repo.try_find_reference("main")?.is_none());
+    assert_eq!(
+        repo.head_name()?.expect("not detached").as_bstr(),
+        "refs/heads/renamed",
+        "HEAD follows"
+    );
+
+    repo.rename_reference(
+        "refs/heads/renamed".try_into()?,
+        "refs/heads/copied".try_into()?,
+        RenameMode::Copy,
+        "Branch: copied refs/heads/renamed to refs/heads/copied",
+    )?;
+
+    for repo in [repo.clone(), gix::open_opts(repo.git_dir(), crate::restricted())?] {
+        let config = repo.config_snapshot();
+        assert_eq!(config.string("branch.main.remote"), None, "the old section is gone");
+        for name in ["renamed", "copied"] {
+            assert_eq!(
+                config
+                    .string(format!("branch.{name}.remote").as_str())
+                    .expect("present")
+                    .as_ref(),
+                "origin",
+                "configuration moved and was copied, in memory and on disk"
+            );
+            assert_eq!(
+                config
+                    .string(format!("branch.{name}.merge").as_str())
+                    .expect("present")
+                    .as_ref(),
+                "refs/heads/main",
+                "values are carried over unchanged"
+            );
+        }
+        assert_eq!(repo.find_reference("copied")?.id(), head_id);
+    }
+    let config = std::fs::read_to_string(&config_path)?;
+    assert!(
+        config.contains("path = included.config") && !config.contains("name = included"),
+        "only branch sections are changed and included values aren't inlined"
+    );
+    assert!(
+        !repo.git_dir().join("config.lock").exists(),
+        "the configuration is written through a lock file which is committed"
+    );
+    drop(tmp);
+    Ok(())
+}
+
+#[test]
+fn expire_log() -> crate::Result {
+    use gix::config::tree::Gc;
+
+    use crate::repo_rw;
+    let (mut repo, _tmp) = repo_rw("make_basic_repo.sh")?;
+    let mut config = repo.config_snapshot_mut();
+    config.set_raw_value(&Gc::REFLOG_EXPIRE, "never")?;
+    config.set_raw_value(&Gc::REFLOG_EXPIRE_UNREACHABLE, "never")?;
+    let repo = config.commit()?;
+
+    let mut main = repo.find_reference("main")?;
+    let out = main.expire_log()?;
+    assert_eq!(out.num_removed, 0, "nothing expires");
+    assert_eq!(out.num_kept, 2, "two commits");
+
+    let first_commit = repo
+        .head_commit()?
+        .parent_ids()
+        .next()
+        .expect("c2 has c1 as parent")
+        .detach();
+    main.set_target_id(first_commit, "reset: moving to c1")?;
+    let mut config = repo.config_snapshot_mut();
+    config.set_raw_value(&Gc::REFLOG_EXPIRE_UNREACHABLE, "1.day.ago")?;
+    let repo = config.commit()?;
+    let out = repo.find_reference("main")?.expire_log()?;
+    assert_eq!(
+        out.num_removed, 1,
+        "the old entry pointing to the second commit is now unreachable"
+    );
+    assert_eq!(
+        out.num_kept, 2,
+        "the new entry is kept despite pointing to the now unreachable commit"
+    );
+
+    let mut config = repo.config_snapshot_mut();
+    config.set_raw_value(&Gc::REFLOG_EXPIRE, "now")?;
+    let repo = config.commit()?;
+    let out = repo.find_reference("main")?.expire_log()?;
+    assert_eq!(out.num_removed, 2, "everything expires");
+    assert_eq!(out.num_kept, 0);
+    Ok(())
+}
+
 mod remote;
@@ -160,6 +160,59 @@ mod blocking_and_async_io {
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_with_ref_in_want_uses_ids_of_wanted_refs() -> crate::Result {
+        use std::io::Write;
+
+        let (repo, tmp) = repo_rw("two-origins");
+        let remote_dir = tmp.path().join("clone-as-base-with-changes");
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(remote_dir.join(".git").join("config"))?
+            .write_all(b"[uploadpack]\n\tallowRefInWant = true\n")?;
+
+        let mut remote = repo
+            .find_remote("changes-on-top-of-origin")?
+            .with_fetch_tags(fetch::Tags::None);
+        remote.replace_refspecs(
+            Some("refs/heads/main:refs/remotes/changes-on-top-of-origin/main"),
+            Fetch,
+        )?;
+        let prepare = remote
+            .connect(Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?;
+        let advertised_id = prepare.ref_map().mappings[0].remote.as_id().expect("born").to_owned();
+
+        let remote_repo = gix::open_opts(&remote_dir, crate::restricted())?;
+        let parent_id = remote_repo
+            .find_object(advertised_id)?
+            .into_commit()
+            .parent_ids()
+            .next()
+            .expect("has parent")
+            .detach();
+        remote_repo.reference(
+            "refs/heads/main",
+            parent_id,
+            gix::refs::transaction::PreviousValue::Any,
+            "move main back after the ref-map was obtained",
+        )?;
+
+        let out = prepare.receive(gix::progress::Discard, &AtomicBool::default())?;
+        assert_eq!(
+            out.ref_map.mappings[0].remote.as_id(),
+            Some(parent_id.as_ref()),
+            "the id reported in the 'wanted-refs' section overrides the one that was advertised"
+        );
+        assert_eq!(
+            repo.find_reference("refs/remotes/changes-on-top-of-origin/main")?.id(),
+            parent_id,
+            "the tracking branch is set to what the pack was computed for, even though the advertised commit was never received"
+        );
+        Ok(())
+    }
+
     #[maybe_async::test(
         feature = "blocking-network-client",
         async(feature = "async-network-client-async-std", async_std::test)
@@ -0,0 +1,161 @@
+//! Fetch with the async `reqwest` transport from `git http-backend`, which is served by a minimal HTTP server.
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::{atomic::AtomicBool, Arc, Mutex},
+};
+
+use gix::remote::{fetch, Direction::Fetch};
+
+/// A server which passes each request to `git http-backend`, and records the headers of all requests it received.
+struct HttpBackend {
+    url: String,
+    requests: Arc<Mutex<Vec<Vec<(String, String)>>>>,
+}
+
+impl HttpBackend {
+    fn spawn(project_root: PathBuf) -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        std::thread::spawn({
+            let requests = requests.clone();
+            move || {
+                for stream in listener.incoming() {
+                    let Ok(stream) = stream else { break };
+                    if let Err(err) = serve(stream, &project_root, &requests) {
+                        eprintln!("failed to serve request: {err}");
+                    }
+                }
+            }
+        });
+        Ok(HttpBackend { url, requests })
+    }
+}
+
+/// Serve a single request on `stream` and close the connection afterwards.
+fn serve(
+    mut stream: TcpStream,
+    project_root: &Path,
+    requests: &Mutex<Vec<Vec<(String, String)>>>,
+) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let target = parts.next().unwrap_or_default();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_owned()));
+        }
+    }
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find_map(|(n, v)| (n == name).then(|| v.clone()))
+            .unwrap_or_default()
+    };
+    let mut body = vec![0; header("content-length").parse().unwrap_or(0)];
+    reader.read_exact(&mut body)?;
+
+    let mut backend = Command::new("git")
+        .arg("http-backend")
+        .env("GIT_PROJECT_ROOT", project_root)
+        .env("GIT_HTTP_EXPORT_ALL", "1")
+        .env("REQUEST_METHOD", method)
+        .env("PATH_INFO", path)
+        .env("QUERY_STRING", query)
+        .env("CONTENT_TYPE", header("content-type"))
+        .env("CONTENT_LENGTH", body.len().to_string())
+        .env("GIT_PROTOCOL", header("git-protocol"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    backend.stdin.take().expect("configured").write_all(&body)?;
+    let output = backend.wait_with_output()?;
+    requests.lock().expect("no panic").push(headers);
+
+    let headers_end = output
+        .stdout
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .expect("CGI output has headers");
+    let (cgi_headers, body) = (&output.stdout[..headers_end], &output.stdout[headers_end + 4..]);
+    let cgi_headers = String::from_utf8_lossy(cgi_headers);
+    let status = cgi_headers
+        .lines()
+        .find_map(|line| line.strip_prefix("Status: "))
+        .unwrap_or("200 OK");
+    write!(stream, "HTTP/1.1 {status}\r\n")?;
+    for line in cgi_headers.lines().filter(|line| !line.starts_with("Status: ")) {
+        write!(stream, "{line}\r\n")?;
+    }
+    write!(stream, "Content-Length: {}\r\nConnection: close\r\n\r\n", body.len())?;
+    stream.write_all(body)?;
+    stream.flush()
+}
+
+#[test]
+fn fetch_from_http_backend_applies_http_options() -> crate::Result {
+    let base = gix_testtools::scripted_fixture_read_only("make_remote_repos.sh")?;
+    let server = HttpBackend::spawn(base.clone())?;
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    gix::init_bare(tmp.path())?;
+    let repo = gix::open_opts(
+        tmp.path(),
+        crate::restricted().config_overrides([
+            "user.name=gitoxide",
+            "user.email=gitoxide@localhost",
+            "http.userAgent=gix-async-test",
+            "http.extraHeader=X-Gix-Test: extra",
+        ]),
+    )?;
+
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    let outcome = runtime.block_on(async {
+        let outcome = repo
+            .remote_at(format!("{}/base", server.url))?
+            .with_refspecs(Some("+refs/heads/*:refs/remotes/origin/*"), Fetch)?
+            .connect(Fetch)
+            .await?
+            .prepare_fetch(gix::progress::Discard, Default::default())
+            .await?
+            .receive(gix::progress::Discard, &AtomicBool::default())
+            .await?;
+        crate::Result::<_>::Ok(outcome)
+    })?;
+
+    let fetch::Status::Change { write_pack_bundle, .. } = outcome.status else {
+        panic!("a pack is received into the empty repository")
+    };
+    assert!(write_pack_bundle.index.num_objects > 0);
+    let source = gix::open_opts(base.join("base"), crate::restricted())?;
+    let mut num_refs = 0;
+    for reference in source.references()?.local_branches()? {
+        let reference = reference?;
+        let tracking_name = format!("refs/remotes/origin/{}", reference.name().shorten());
+        assert_eq!(repo.find_reference(tracking_name.as_str())?.id(), reference.id());
+        num_refs += 1;
+    }
+    assert!(num_refs > 1, "all branches were fetched");
+
+    let requests = server.requests.lock().expect("no panic");
+    assert!(requests.len() >= 2, "at least the handshake and the fetch were sent");
+    for headers in requests.iter() {
+        assert!(headers.contains(&("user-agent".into(), "gix-async-test".into())));
+        assert!(headers.contains(&("x-gix-test".into(), "extra".into())));
+    }
+    Ok(())
+}
@@ -0,0 +1,232 @@
+use gix::{
+    apply::{Error, Options, Status, Target},
+    bstr::ByteSlice,
+};
+use gix_testtools::tempfile;
+
+fn repo_rw() -> crate::Result<(gix::Repository, tempfile::TempDir)> {
+    let dir = gix_testtools::scripted_fixture_writable_with_args(
+        "make_apply_repo.sh",
+        None::<String>,
+        gix_testtools::Creation::ExecuteScript,
+    )?;
+    let repo = gix::open_opts(dir.path(), crate::restricted())?;
+    Ok((repo, dir))
+}
+
+fn patch(repo: &gix::Repository, name: &str) -> std::io::Result<Vec<u8>> {
+    std::fs::read(repo.git_dir().join(name))
+}
+
+fn tree_of(repo: &gix::Repository, rev: &str) -> crate::Result<gix::ObjectId> {
+    Ok(repo.rev_parse_single(format!("{rev}^{{tree}}").as_str())?.detach())
+}
+
+fn worktree_file(repo: &gix::Repository, path: &str) -> std::io::Result<String> {
+    std::fs::read_to_string(repo.work_dir().expect("non-bare").join(path))
+}
+
+#[test]
+fn to_tree_and_in_reverse() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let patch = patch(&repo, "changes.patch")?;
+    let outcome = repo.apply_patch(
+        &patch,
+        Options {
+            target: Target::Tree(tree_of(&repo, "base")?),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        outcome.tree,
+        Some(tree_of(&repo, "patched")?),
+        "additions, deletions, renames, mode changes and binary changes are all applied"
+    );
+    assert_eq!(outcome.files.len(), 6);
+    assert!(outcome.is_clean());
+
+    let outcome = repo.apply_patch(
+        &patch,
+        Options {
+            target: Target::Tree(tree_of(&repo, "patched")?),
+            hunks: gix::diff::patch::apply::Options {
+                reverse: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.tree, Some(tree_of(&repo, "base")?), "patches can be undone");
+
+    let err = repo
+        .apply_patch(
+            &patch,
+            Options {
+                target: Target::Tree(tree_of(&repo, "patched")?),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::BinaryPreimageMismatch { path, .. } if path == "binary"),
+        "the binary delta is verified against its preimage: {err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn to_worktree_is_all_or_nothing_unless_rejects_are_allowed() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let patch = patch(&repo, "changes.patch")?;
+    let err = repo.apply_patch(&patch, Options::default()).unwrap_err();
+    assert!(
+        matches!(&err, Error::Rejected { path, hunks } if path == "numbers" && hunks == &[0]),
+        "{err:?}"
+    );
+    assert!(
+        worktree_file(&repo, "deleted").is_ok(),
+        "nothing was written as the patch didn't apply"
+    );
+
+    let outcome = repo.apply_patch(
+        &patch,
+        Options {
+            dry_run: true,
+            allow_rejects: true,
+            ..Default::default()
+        },
+    )?;
+    assert!(!outcome.is_clean());
+    assert!(worktree_file(&repo, "deleted").is_ok(), "dry-runs don't write");
+
+    let outcome = repo.apply_patch(
+        &patch,
+        Options {
+            allow_rejects: true,
+            ..Default::default()
+        },
+    )?;
+    let numbers = outcome
+        .files
+        .iter()
+        .find(|file| file.new_path.as_ref().map_or(false, |path| path == "numbers"))
+        .expect("present");
+    assert_eq!(
+        numbers.status,
+        Status::PartiallyApplied {
+            rejected_hunks: vec![0]
+        }
+    );
+    assert_eq!(
+        worktree_file(&repo, "numbers")?,
+        "1\ndeux\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n14\nfifteen\n16\n17\n18\n19\n20\n"
+    );
+    assert_eq!(worktree_file(&repo, "dir/added")?, "new\n");
+    assert!(worktree_file(&repo, "deleted").is_err());
+    assert!(worktree_file(&repo, "to-rename").is_err());
+    assert!(worktree_file(&repo, "renamed")?.ends_with("20\n21\n"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(repo.work_dir().expect("non-bare").join("greeting"))?
+            .permissions()
+            .mode();
+        assert_ne!(mode & 0o100, 0, "the executable bit is set");
+    }
+
+    let index = repo.open_index()?;
+    assert!(
+        index.entry_by_path("deleted".into()).is_some(),
+        "the index isn't touched by default"
+    );
+    Ok(())
+}
+
+#[test]
+fn three_way_merge_records_conflicts_in_index() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let outcome = repo.apply_patch(
+        &patch(&repo, "changes.patch")?,
+        Options {
+            target: Target::IndexAndWorktree,
+            three_way: true,
+            ..Default::default()
+        },
+    )?;
+    let numbers = &outcome.files[4];
+    assert_eq!(
+        numbers.new_path.as_ref().map(ToString::to_string),
+        Some("numbers".into())
+    );
+    assert_eq!(numbers.status, Status::Merged { num_conflicts: 1 });
+    assert_eq!(
+        worktree_file(&repo, "numbers")?,
+        "1\n<<<<<<< ours\ndeux\n=======\ntwo\n>>>>>>> theirs\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n14\nfifteen\n16\n17\n18\n19\n20\n"
+    );
+
+    let index = repo.open_index()?;
+    let stages: Vec<_> = index
+        .entries()
+        .iter()
+        .filter(|entry| entry.path(&index) == "numbers")
+        .map(gix::index::Entry::stage_raw)
+        .collect();
+    assert_eq!(stages, [1, 2, 3], "the conflict is recorded in the index");
+    assert!(index.entry_by_path("deleted".into()).is_none());
+    let added = index.entry_by_path("dir/added".into()).expect("added");
+    assert_eq!(repo.find_object(added.id)?.data.as_bstr(), "new\n");
+    assert_ne!(added.stat, Default::default(), "stats are taken from the worktree");
+    Ok(())
+}
+
+#[test]
+fn to_index_only() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let outcome = repo.apply_patch(
+        &patch(&repo, "conflicting.patch")?,
+        Options {
+            target: Target::Index,
+            hunks: gix::diff::patch::apply::Options {
+                reverse: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )?;
+    assert!(outcome.is_clean());
+    assert!(outcome.tree.is_none());
+
+    let index = repo.open_index()?;
+    let base = repo.rev_parse_single("base:numbers")?.detach();
+    assert_eq!(index.entry_by_path("numbers".into()).expect("present").id, base);
+    assert!(
+        worktree_file(&repo, "numbers")?.contains("deux"),
+        "the worktree is left untouched"
+    );
+
+    std::fs::write(repo.work_dir().expect("non-bare").join("numbers"), "changed\n")?;
+    let err = repo
+        .apply_patch(
+            &patch(&repo, "conflicting.patch")?,
+            Options {
+                target: Target::IndexAndWorktree,
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, Error::WorktreeMismatch { .. }));
+    Ok(())
+}
+
+#[test]
+fn paths_may_not_leave_the_worktree() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    for path in ["../outside", ".git/config", "a/../../outside"] {
+        let patch = format!(
+            "diff --git a/{path} b/{path}\nnew file mode 100644\n--- /dev/null\n+++ b/{path}\n@@ -0,0 +1 @@\n+evil\n"
+        );
+        let err = repo.apply_patch(patch.as_bytes(), Options::default()).unwrap_err();
+        assert!(matches!(err, Error::InvalidPath { .. }), "{path}: {err:?}");
+    }
+    Ok(())
+}
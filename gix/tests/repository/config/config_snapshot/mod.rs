@@ -104,6 +104,44 @@ fn set_value_in_subsection() {
     }
 }
 
+#[test]
+fn url_matching_string() -> crate::Result {
+    let mut repo = named_repo("make_config_repo.sh").unwrap();
+    repo.config_snapshot_mut().append_config(
+        [
+            "http.proxy=generic",
+            "http.https://example.com.proxy=host",
+            "http.https://user@example.com.proxy=user",
+        ],
+        gix_config::Source::Cli,
+    )?;
+
+    let config = repo.config_snapshot();
+    assert_eq!(
+        config
+            .url_matching_string("http.proxy", "https://EXAMPLE.com:443/repo")
+            .expect("present")
+            .as_ref(),
+        "host"
+    );
+    assert_eq!(
+        config
+            .url_matching_string("http.proxy", "https://user@example.com/repo")
+            .expect("present")
+            .as_ref(),
+        "user"
+    );
+    assert_eq!(
+        config
+            .url_matching_string("http.proxy", "https://other.com")
+            .expect("present")
+            .as_ref(),
+        "generic"
+    );
+    assert_eq!(config.url_matching_string("http.proxy", "not a url"), None);
+    Ok(())
+}
+
 #[test]
 fn apply_cli_overrides() -> crate::Result {
     let mut repo = named_repo("make_config_repo.sh").unwrap();
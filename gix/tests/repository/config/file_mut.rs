@@ -0,0 +1,158 @@
+use gix::config::tree::Core;
+
+use crate::util::repo_rw;
+
+#[test]
+fn set_value_is_written_to_disk_and_visible_to_the_repository() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw("make_basic_repo.sh")?;
+    assert_eq!(repo.head_id()?.shorten()?.to_string(), "3189cd3");
+
+    let config_path = repo.common_dir().join("config");
+    let mut file = repo.config_file_mut(gix::config::Source::Local)?;
+    assert_eq!(file.path(), config_path);
+    assert_eq!(file.set_value(&Core::ABBREV, "4")?, None, "there was no previous value");
+    assert_eq!(file.set_value("core.abbrev", "5")?.expect("previous"), "4");
+    let repo = file.commit()?;
+
+    assert_eq!(
+        repo.head_id()?.shorten()?.to_string(),
+        "3189c",
+        "cached values were re-read"
+    );
+    assert_eq!(repo.config_snapshot().integer("core.abbrev"), Some(5));
+
+    let reopened = gix::open_opts(repo.git_dir(), repo.open_options().clone())?;
+    assert_eq!(
+        reopened.config_snapshot().integer("core.abbrev"),
+        Some(5),
+        "other instances see the change as well"
+    );
+    Ok(())
+}
+
+#[test]
+fn dropping_without_commit_leaves_the_file_unchanged_and_releases_the_lock() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw("make_basic_repo.sh")?;
+    let config_path = repo.common_dir().join("config");
+    let before = std::fs::read(&config_path)?;
+    {
+        let mut file = repo.config_file_mut(gix::config::Source::Local)?;
+        file.set_value("core.abbrev", "4")?;
+        assert!(
+            matches!(
+                gix::open(file.path().parent().expect("git dir"))?.config_file_mut(gix::config::Source::Local),
+                Err(gix::config::file_mut::Error::Lock(_))
+            ),
+            "only one writer at a time"
+        );
+    }
+    assert_eq!(std::fs::read(&config_path)?, before);
+    assert_eq!(repo.config_snapshot().integer("core.abbrev"), None);
+    repo.config_file_mut(gix::config::Source::Local)?;
+    Ok(())
+}
+
+#[test]
+fn multi_values_can_be_added_replaced_and_removed() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw("make_basic_repo.sh")?;
+    let key = "remote.origin.fetch";
+
+    let mut file = repo.config_file_mut(gix::config::Source::Local)?;
+    for value in ["+refs/heads/*:refs/remotes/origin/*", "refs/tags/a", "refs/tags/b"] {
+        file.add_value(key, value)?;
+    }
+    assert!(
+        matches!(
+            file.set_value(key, "other"),
+            Err(gix::config::file_mut::Error::MultipleValues { count: 3, .. })
+        ),
+        "like git, single values can't be set if there are multiple"
+    );
+    assert!(matches!(
+        file.remove_value(key),
+        Err(gix::config::file_mut::Error::MultipleValues { count: 3, .. })
+    ));
+
+    let replaced = file.replace_values(key, "refs/tags/*", |value| value.starts_with(b"refs/tags/"))?;
+    assert_eq!(replaced, 2);
+    assert_eq!(
+        file.raw_values(key)?,
+        [cow("+refs/heads/*:refs/remotes/origin/*"), cow("refs/tags/*")]
+    );
+
+    assert_eq!(
+        file.replace_values(key, "refs/notes/*", |_| false)?,
+        0,
+        "non-matching replacements add the value"
+    );
+    assert_eq!(file.raw_values(key)?.len(), 3);
+
+    assert_eq!(file.remove_values(key, |value| value.starts_with(b"refs/"))?, 2);
+    let repo = file.commit()?;
+    assert_eq!(
+        repo.config_snapshot().strings(key).expect("present"),
+        [cow("+refs/heads/*:refs/remotes/origin/*")]
+    );
+
+    let mut file = repo.config_file_mut(gix::config::Source::Local)?;
+    assert_eq!(
+        file.remove_value(key)?.expect("present"),
+        "+refs/heads/*:refs/remotes/origin/*"
+    );
+    assert_eq!(file.remove_value(key)?, None, "nothing left to remove");
+    let repo = file.commit()?;
+    assert_eq!(repo.config_snapshot().strings(key), None);
+    Ok(())
+}
+
+#[test]
+fn sections_can_be_renamed_and_removed() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw("make_basic_repo.sh")?;
+    let mut file = repo.config_file_mut(gix::config::Source::Local)?;
+    file.set_value("remote.origin.url", "https://example.com/repo")?;
+    file.set_value("a.b", "c")?;
+    file.rename_section(
+        "remote",
+        Some("origin".into()),
+        "remote",
+        Some(std::borrow::Cow::Borrowed("upstream".into())),
+    )?;
+    file.remove_section("a", None).expect("present");
+    let repo = file.commit()?;
+
+    let config = repo.config_snapshot();
+    assert_eq!(
+        config.string("remote.upstream.url").expect("present").as_ref(),
+        "https://example.com/repo"
+    );
+    assert_eq!(config.string("remote.origin.url"), None);
+    assert_eq!(config.string("a.b"), None);
+    assert_eq!(
+        repo.remote_names()
+            .into_iter()
+            .map(std::borrow::Cow::into_owned)
+            .collect::<Vec<_>>(),
+        ["upstream"]
+    );
+    Ok(())
+}
+
+#[test]
+fn sources_without_file_are_rejected() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw("make_basic_repo.sh")?;
+    for source in [
+        gix::config::Source::Api,
+        gix::config::Source::Cli,
+        gix::config::Source::Env,
+    ] {
+        assert!(matches!(
+            repo.config_file_mut(source),
+            Err(gix::config::file_mut::Error::UnsupportedSource(actual)) if actual == source
+        ));
+    }
+    Ok(())
+}
+
+fn cow(value: &str) -> std::borrow::Cow<'_, gix::bstr::BStr> {
+    std::borrow::Cow::Borrowed(value.into())
+}
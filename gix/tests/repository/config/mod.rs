@@ -1,5 +1,7 @@
 mod config_snapshot;
+mod file_mut;
 mod identity;
+mod refresh;
 mod remote;
 
 #[cfg(feature = "blocking-network-client")]
@@ -0,0 +1,63 @@
+use crate::util::{repo_rw, repo_rw_opts, restricted};
+
+#[test]
+fn changed_files_are_reloaded_including_includes() -> crate::Result {
+    let mut opts = restricted().auto_refresh_config(true);
+    opts.permissions.config.includes = true;
+    let (mut repo, _tmp) = repo_rw_opts("make_basic_repo.sh", opts)?;
+    assert!(!repo.refresh_config()?, "nothing changed yet");
+    let sync_repo = repo.clone().into_sync();
+    let mut other = repo.clone();
+
+    let include_path = repo.common_dir().join("included");
+    std::fs::write(&include_path, "[core]\n\tabbrev = 5\n")?;
+    let config_path = repo.common_dir().join("config");
+    let mut config = std::fs::read(&config_path)?;
+    config.extend_from_slice(b"[include]\n\tpath = included\n");
+    std::fs::write(&config_path, config)?;
+
+    assert!(repo.refresh_config()?);
+    assert_eq!(repo.config_snapshot().integer("core.abbrev"), Some(5));
+    assert_eq!(
+        repo.head_id()?.shorten()?.to_string(),
+        "3189c",
+        "cached values were re-read"
+    );
+    assert!(!repo.refresh_config()?, "nothing changed since the last refresh");
+    assert!(
+        other.refresh_config()?,
+        "other instances adopt the configuration that was already reloaded"
+    );
+    assert_eq!(other.config_snapshot().integer("core.abbrev"), Some(5));
+    assert!(!other.refresh_config()?);
+    assert_eq!(
+        sync_repo.to_thread_local().config_snapshot().integer("core.abbrev"),
+        Some(5),
+        "thread-local instances are refreshed automatically"
+    );
+
+    std::fs::write(&include_path, "[core]\n\tabbrev = 6\n")?;
+    assert!(
+        repo.refresh_config()?,
+        "changes of the same size are detected without waiting for the modification time to change"
+    );
+    assert_eq!(repo.config_snapshot().integer("core.abbrev"), Some(6));
+
+    std::fs::remove_file(&include_path)?;
+    assert!(repo.refresh_config()?, "removed files are detected");
+    assert_eq!(repo.config_snapshot().integer("core.abbrev"), None);
+    Ok(())
+}
+
+#[test]
+fn nothing_is_reloaded_by_default() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw("make_basic_repo.sh")?;
+    let config_path = repo.common_dir().join("config");
+    let mut config = std::fs::read(&config_path)?;
+    config.extend_from_slice(b"[core]\n\tabbrev = 5\n");
+    std::fs::write(&config_path, config)?;
+
+    assert!(!repo.refresh_config()?);
+    assert_eq!(repo.config_snapshot().integer("core.abbrev"), None);
+    Ok(())
+}
@@ -203,6 +203,42 @@ mod http {
         assert_eq!(opts.follow_redirects, FollowRedirects::None);
     }
 
+    #[test]
+    fn url_specific_configuration_is_used_for_matching_urls() -> crate::Result {
+        let mut repo = repo("http-config");
+        repo.config_snapshot_mut().append_config(
+            [
+                "http.https://*.example.com.proxy=http://wildcard",
+                "http.https://internal.example.com/org.proxy=http://org",
+                "http.https://internal.example.com/org.sslVerify=false",
+                "http.https://internal.example.com.lowSpeedLimit=42",
+            ],
+            gix::config::Source::Api,
+        )?;
+
+        let opts = http_options(&repo, None, "https://internal.example.com/org/repo");
+        assert_eq!(opts.proxy.as_deref(), Some("http://org"), "the longest path wins");
+        assert!(!opts.ssl_verify);
+        assert_eq!(opts.low_speed_limit_bytes_per_second, 42);
+
+        let opts = http_options(&repo, None, "https://INTERNAL.example.com:443/other/../organization");
+        assert_eq!(
+            opts.proxy.as_deref(),
+            Some("http://wildcard"),
+            "urls are normalized, and paths only match on component boundaries"
+        );
+        assert!(opts.ssl_verify);
+        assert_eq!(opts.low_speed_limit_bytes_per_second, 42);
+
+        let opts = http_options(&repo, None, "https://example.com/org");
+        assert_eq!(
+            opts.proxy.as_deref(),
+            Some("http://localhost:9090"),
+            "the value without url is used if nothing else matches"
+        );
+        Ok(())
+    }
+
     #[test]
     fn https_specific_proxy_only() {
         let repo = repo("https-proxy-only");
@@ -0,0 +1,269 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::{
+    config::tree::Gc,
+    maintenance::{Auto, Repack},
+};
+use gix_testtools::tempfile;
+
+fn repo_rw() -> crate::Result<(gix::Repository, tempfile::TempDir)> {
+    let dir = gix_testtools::scripted_fixture_writable_with_args(
+        "make_maintenance_repo.sh",
+        None::<String>,
+        gix_testtools::Creation::ExecuteScript,
+    )?;
+    let repo = gix::open_opts(dir.path(), crate::restricted())?;
+    Ok((repo, dir))
+}
+
+fn num_packs(repo: &gix::Repository) -> std::io::Result<usize> {
+    Ok(std::fs::read_dir(repo.objects.store_ref().path().join("pack"))?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().map_or(false, |ext| ext == "idx"))
+        .count())
+}
+
+fn loose_objects(repo: &gix::Repository) -> Vec<gix::ObjectId> {
+    gix::odb::loose::Store::at(repo.objects.store_ref().path(), repo.object_hash())
+        .iter()
+        .map(Result::unwrap)
+        .collect()
+}
+
+fn loose_mtime(repo: &gix::Repository, id: gix::ObjectId) -> std::io::Result<gix::date::SecondsSinceUnixEpoch> {
+    let path = gix::odb::loose::Store::at(repo.objects.store_ref().path(), repo.object_hash()).object_path(&id);
+    Ok(std::fs::metadata(path)?
+        .modified()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("after epoch")
+        .as_secs() as gix::date::SecondsSinceUnixEpoch)
+}
+
+/// Assert that everything reachable from `HEAD`, its log and the index is still present.
+fn assert_reachable_objects_exist(repo: &gix::Repository) -> crate::Result {
+    let repo = gix::open_opts(repo.git_dir(), crate::restricted())?;
+    let mut tips = vec![repo.head_id()?.detach()];
+    for line in repo.head()?.log_iter().all()?.expect("log present") {
+        tips.push(line?.new_oid());
+    }
+    for info in repo.rev_walk(tips).all()? {
+        let tree = info?.object()?.tree()?;
+        for entry in tree.traverse().breadthfirst.files()? {
+            assert!(repo.has_object(entry.oid), "{} must be present", entry.filepath);
+        }
+    }
+    for entry in repo.index()?.entries() {
+        assert!(repo.has_object(entry.id));
+    }
+    repo.find_reference("v1")?
+        .peel_to_id_in_place()
+        .expect("the annotated tag can still be peeled");
+    Ok(())
+}
+
+#[test]
+fn options_from_configuration() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw()?;
+    let opts = repo.maintenance_options()?;
+    assert_eq!(opts.auto, Auto::default());
+    assert_eq!(opts.repack, Repack::All);
+    assert!(
+        opts.prune_expire.is_some(),
+        "unreachable objects expire after two weeks by default"
+    );
+
+    let mut config = repo.config_snapshot_mut();
+    config.set_raw_value(&Gc::AUTO, "0")?;
+    config.set_raw_value(&Gc::AUTO_PACK_LIMIT, "10")?;
+    config.set_raw_value(&Gc::PRUNE_EXPIRE, "never")?;
+    drop(config);
+    let opts = repo.maintenance_options()?;
+    assert_eq!(
+        opts.auto,
+        Auto {
+            loose_objects: None,
+            packs: None
+        },
+        "disabling `gc.auto` disables all heuristics"
+    );
+    assert_eq!(opts.prune_expire, None);
+    Ok(())
+}
+
+#[test]
+fn gc_is_needed() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    assert!(!repo.gc_is_needed(Auto::default())?);
+    assert!(repo.gc_is_needed(Auto {
+        loose_objects: None,
+        packs: Some(1),
+    })?);
+    assert!(!repo.gc_is_needed(Auto {
+        loose_objects: None,
+        packs: Some(2),
+    })?);
+
+    let outcome = repo.gc_auto(
+        gix::maintenance::Options {
+            auto: Auto {
+                loose_objects: None,
+                packs: Some(2),
+            },
+            ..Default::default()
+        },
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+    )?;
+    assert!(outcome.is_none(), "nothing is done if no threshold is exceeded");
+    Ok(())
+}
+
+#[test]
+fn gc_repacks_everything_and_prunes_old_unreachable_objects() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    assert_eq!(num_packs(&repo)?, 2);
+    let loose_before = loose_objects(&repo);
+    let mtime = loose_mtime(&repo, loose_before[0])?;
+
+    let opts = gix::maintenance::Options {
+        expire_reflogs: false,
+        prune_expire: Some(mtime),
+        ..repo.maintenance_options()?
+    };
+    let out = repo.gc(opts, &mut gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(out.num_expired_reflog_entries, 0);
+    assert_eq!(out.num_packed_refs, 2, "main and v1 were loose");
+    assert!(repo.refs.packed_refs_path().is_file());
+    assert!(repo.refs.try_find_loose("main")?.is_none());
+
+    assert_eq!(out.removed_packs.len(), 2);
+    assert!(out.written_pack.as_ref().expect("written").is_file());
+    assert_eq!(num_packs(&repo)?, 1, "everything is in one pack now");
+    assert_eq!(out.num_pruned_objects, 0, "unreachable objects aren't old enough");
+    assert_eq!(
+        out.num_removed_loose_objects,
+        loose_before.len() - 2,
+        "all loose objects are packed, except for the unreachable ones"
+    );
+    assert_eq!(loose_objects(&repo).len(), 2, "recent unreachable objects remain");
+    assert_eq!(out.multi_pack_index, None, "it's not written by default");
+
+    assert_reachable_objects_exist(&repo)?;
+
+    let out = repo.gc(
+        gix::maintenance::Options {
+            prune_expire: Some(mtime + 1),
+            ..opts
+        },
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+    )?;
+    assert_eq!(out.num_packed_refs, 0);
+    assert_eq!(out.removed_packs.len(), 1, "the previous pack is replaced");
+    assert_eq!(out.num_pruned_objects, 2, "now the unreachable objects are old enough");
+    assert_eq!(loose_objects(&repo).len(), 0);
+    assert_eq!(num_packs(&repo)?, 1);
+
+    assert_reachable_objects_exist(&repo)?;
+    Ok(())
+}
+
+#[test]
+fn gc_keeps_objects_reachable_from_recent_unreachable_objects() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let old_blob = gix::objs::compute_hash(repo.object_hash(), gix::object::Kind::Blob, b"unreachable\n");
+    let old_mtime = loose_mtime(&repo, old_blob)?;
+    let recent_tree = repo
+        .write_object(gix::objs::Tree {
+            entries: vec![gix::objs::tree::Entry {
+                mode: gix::objs::tree::EntryKind::Blob.into(),
+                filename: "unreachable".into(),
+                oid: old_blob,
+            }],
+        })?
+        .detach();
+    let recent_mtime = loose_mtime(&repo, recent_tree)?;
+
+    let out = repo.gc(
+        gix::maintenance::Options {
+            expire_reflogs: false,
+            prune_expire: Some(recent_mtime),
+            ..repo.maintenance_options()?
+        },
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+    )?;
+    assert!(repo.has_object(recent_tree), "the tree isn't old enough to be pruned");
+    assert!(
+        repo.has_object(old_blob),
+        "the blob is kept as the recent tree refers to it, even if it would be old enough to be pruned by itself"
+    );
+    if old_mtime < recent_mtime {
+        assert_eq!(
+            out.num_pruned_objects, 1,
+            "only the other old unreachable blob is pruned"
+        );
+    }
+    assert_reachable_objects_exist(&repo)?;
+    Ok(())
+}
+
+#[test]
+fn gc_ignores_missing_objects_in_the_index() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let staged = repo
+        .index()?
+        .entry_by_path("staged".into())
+        .expect("staged file is present")
+        .id;
+    let loose = gix::odb::loose::Store::at(repo.objects.store_ref().path(), repo.object_hash());
+    std::fs::remove_file(loose.object_path(&staged))?;
+
+    let out = repo.gc(
+        gix::maintenance::Options {
+            prune_expire: None,
+            ..repo.maintenance_options()?
+        },
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+    )?;
+    assert!(
+        out.written_pack.is_some(),
+        "blobs missing from the index, like in partial clones, don't prevent maintenance"
+    );
+    Ok(())
+}
+
+#[test]
+fn gc_geometric_with_multi_pack_index_and_reflog_expiry() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let opts = gix::maintenance::Options {
+        repack: Repack::Geometric { factor: 2 },
+        write_multi_pack_index: true,
+        prune_expire: None,
+        ..repo.maintenance_options()?
+    };
+    let out = repo.gc(opts, &mut gix::progress::Discard, &AtomicBool::default())?;
+    assert!(
+        out.num_expired_reflog_entries > 0,
+        "all reflog entries are from the year 2000 and expire"
+    );
+    assert_eq!(
+        out.removed_packs.len(),
+        2,
+        "both small packs are rolled up along with the loose objects"
+    );
+    assert_eq!(out.num_pruned_objects, 0, "nothing expires");
+    assert_eq!(loose_objects(&repo).len(), 0, "all loose objects were packed");
+    let multi_index_path = out.multi_pack_index.expect("written");
+    let multi_index = gix::odb::pack::multi_index::File::at(&multi_index_path)?;
+    assert_eq!(multi_index.num_indices(), 1);
+    assert_eq!(multi_index.num_objects() as usize, out.num_packed_objects);
+
+    let out = repo.gc(opts, &mut gix::progress::Discard, &AtomicBool::default())?;
+    assert_eq!(out.written_pack, None, "a single pack is already geometric");
+    assert_eq!(out.removed_packs.len(), 0);
+    assert_eq!(num_packs(&repo)?, 1);
+    assert!(out.multi_pack_index.is_some(), "it's always rewritten");
+    Ok(())
+}
@@ -1,20 +1,28 @@
 use gix::Repository;
 
+#[cfg(feature = "apply")]
+mod apply;
 mod config;
 #[cfg(feature = "excludes")]
 mod excludes;
 #[cfg(feature = "attributes")]
 mod filter;
+#[cfg(feature = "maintenance")]
+mod maintenance;
 mod object;
 mod open;
 #[cfg(feature = "attributes")]
 mod pathspec;
 mod reference;
 mod remote;
+#[cfg(feature = "worktree-mutation")]
+mod reset;
 mod shallow;
 mod state;
 #[cfg(feature = "attributes")]
 mod submodule;
+#[cfg(feature = "worktree-mutation")]
+mod switch;
 mod worktree;
 
 #[cfg(feature = "dirwalk")]
@@ -266,6 +266,70 @@ mod find_remote {
         Ok(())
     }
 
+    #[test]
+    fn legacy_remotes_and_branches_files() -> crate::Result {
+        let repo = remote::repo("legacy-remote-files");
+        let base_dir = base_dir(&repo);
+        let remote = repo.find_remote("from-remotes-file")?;
+        assert_eq!(remote.name().expect("set").as_bstr(), "from-remotes-file");
+        assert_eq!(remote.url(Direction::Fetch).unwrap().to_bstring(), base_dir);
+        assert_eq!(
+            remote.refspecs(Direction::Fetch),
+            &[
+                fetchspec("+refs/heads/g:refs/remotes/from-remotes-file/g"),
+                fetchspec("refs/heads/main:refs/remotes/from-remotes-file/main"),
+            ]
+        );
+        assert_eq!(
+            remote.refspecs(Direction::Push),
+            &[pushspec("refs/heads/main:refs/heads/legacy")]
+        );
+
+        for (name, branch) in [("from-branches-file", "g"), ("from-branches-file-default", "main")] {
+            let remote = repo.find_remote(name)?;
+            assert_eq!(remote.url(Direction::Fetch).unwrap().to_bstring(), base_dir);
+            assert_eq!(
+                remote.refspecs(Direction::Fetch),
+                &[fetchspec(&format!("refs/heads/{branch}:refs/heads/{name}"))]
+            );
+            assert_eq!(
+                remote.refspecs(Direction::Push),
+                &[pushspec(&format!("HEAD:refs/heads/{branch}"))],
+                "the default branch is used if no branch is specified"
+            );
+        }
+
+        let remote = repo.find_remote("with-configured-fetchspec")?;
+        assert_eq!(
+            remote.url(Direction::Fetch).unwrap().to_bstring(),
+            base_dir,
+            "a configured remote without url is read from legacy files as well"
+        );
+        assert_eq!(
+            remote.refspecs(Direction::Fetch),
+            &[
+                fetchspec("refs/heads/g:refs/heads/with-configured-fetchspec"),
+                fetchspec("+refs/heads/main:refs/remotes/with-configured-fetchspec/main"),
+            ],
+            "configured refspecs are merged with the ones of the file"
+        );
+        assert!(matches!(
+            repo.find_remote("without-url").unwrap_err(),
+            gix::remote::find::existing::Error::Find(gix::remote::find::Error::UrlMissing)
+        ));
+
+        assert!(
+            repo.try_find_remote("missing").is_none(),
+            "names without legacy file are still unknown"
+        );
+        assert_eq!(
+            repo.remote_names().len(),
+            4,
+            "legacy remotes aren't listed as they aren't configured"
+        );
+        Ok(())
+    }
+
     fn fetchspec(spec: &str) -> gix_refspec::RefSpec {
         gix::refspec::parse(spec.into(), gix::refspec::parse::Operation::Fetch)
             .unwrap()
@@ -350,3 +414,187 @@ mod find_default_remote {
         Ok(())
     }
 }
+
+mod remote_group {
+    use crate::remote;
+
+    #[test]
+    fn names_are_split_and_deduplicated_across_values() {
+        let repo = remote::repo("legacy-remote-files");
+        assert_eq!(
+            repo.remote_group("mirrors").expect("configured"),
+            ["origin", "myself", "from-remotes-file"]
+        );
+        assert_eq!(
+            repo.remote_group("empty").expect("configured"),
+            Vec::<gix::bstr::BString>::new()
+        );
+        assert_eq!(repo.remote_group("unknown"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_all_remotes_of_group_in_parallel() -> crate::Result {
+        use gix::remote::fetch::Status;
+
+        let tmp = gix_testtools::scripted_fixture_writable("make_remote_repos.sh")?;
+        let mut repo = gix::open_opts(tmp.path().join("legacy-remote-files"), crate::restricted())?;
+        repo.config_snapshot_mut()
+            .set_raw_value(&gix::config::tree::Fetch::PARALLEL, "0")?;
+
+        let outcomes = repo.fetch_remote_group(
+            "mirrors",
+            &mut gix::progress::Discard,
+            &std::sync::atomic::AtomicBool::default(),
+        )?;
+        assert_eq!(
+            outcomes.iter().map(|o| o.remote_name.clone()).collect::<Vec<_>>(),
+            ["origin", "myself", "from-remotes-file"],
+            "outcomes are in the order of the group"
+        );
+        for outcome in &outcomes {
+            let outcome = outcome.result.as_ref().expect("each fetch succeeds");
+            assert!(matches!(
+                outcome.status,
+                Status::Change { .. } | Status::NoPackReceived { .. }
+            ));
+        }
+        assert!(repo.find_reference("refs/remotes/from-remotes-file/g").is_ok());
+
+        assert_eq!(
+            repo.fetch_remote_group("myself", &mut gix::progress::Discard, &Default::default())?
+                .len(),
+            1,
+            "a remote without a group is fetched by itself"
+        );
+        assert!(matches!(
+            repo.fetch_remote_group("empty", &mut gix::progress::Discard, &Default::default())
+                .unwrap_err(),
+            gix::remote::group::Error::NotFound { .. }
+        ));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "blocking-network-client", feature = "parallel"))]
+    fn fetches_of_a_group_run_at_the_same_time() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("make_remote_repos.sh")?;
+        let mut repo = gix::open_opts(tmp.path().join("legacy-remote-files"), crate::restricted())?;
+        repo.config_snapshot_mut()
+            .set_raw_value(&gix::config::tree::Fetch::PARALLEL, "3")?;
+
+        let mut progress = meeting::Meeting::new(3);
+        let outcomes = repo.fetch_remote_group("mirrors", &mut progress, &Default::default())?;
+        assert_eq!(outcomes.len(), 3);
+        for outcome in outcomes {
+            outcome.result.expect("each fetch succeeds");
+        }
+        assert!(
+            progress.everyone_met(),
+            "all fetches were in their handshake at the same time"
+        );
+        Ok(())
+    }
+
+    #[cfg(all(feature = "blocking-network-client", feature = "parallel"))]
+    mod meeting {
+        use std::{
+            sync::{
+                atomic::{AtomicUsize, Ordering},
+                Arc, Condvar, Mutex,
+            },
+            time::Duration,
+        };
+
+        use gix::progress::{Count, Id, MessageLevel, NestedProgress, Progress, Step, StepShared, Unit, UNKNOWN};
+
+        /// A progress whose children wait in their handshake until `expected` of them entered it, or until a timeout.
+        #[derive(Clone)]
+        pub struct Meeting {
+            in_handshake: Arc<(Mutex<usize>, Condvar)>,
+            timeouts: Arc<AtomicUsize>,
+            expected: usize,
+            step: StepShared,
+        }
+
+        impl Meeting {
+            pub fn new(expected: usize) -> Self {
+                Meeting {
+                    in_handshake: Default::default(),
+                    timeouts: Default::default(),
+                    expected,
+                    step: Default::default(),
+                }
+            }
+
+            /// Return `true` if `expected` children entered their handshake, and none of them had to stop waiting for the others.
+            pub fn everyone_met(&self) -> bool {
+                *self.in_handshake.0.lock().expect("no panic") >= self.expected
+                    && self.timeouts.load(Ordering::SeqCst) == 0
+            }
+        }
+
+        impl Count for Meeting {
+            fn set(&self, step: Step) {
+                self.step.store(step, Ordering::SeqCst);
+            }
+
+            fn step(&self) -> Step {
+                self.step.load(Ordering::SeqCst)
+            }
+
+            fn inc_by(&self, step: Step) {
+                self.step.fetch_add(step, Ordering::SeqCst);
+            }
+
+            fn counter(&self) -> StepShared {
+                self.step.clone()
+            }
+        }
+
+        impl Progress for Meeting {
+            fn init(&mut self, _max: Option<Step>, _unit: Option<Unit>) {}
+
+            fn set_name(&mut self, name: String) {
+                if name != "handshake" {
+                    return;
+                }
+                let (count, cond) = &*self.in_handshake;
+                let mut count = count.lock().expect("no panic");
+                *count += 1;
+                cond.notify_all();
+                let (_count, res) = cond
+                    .wait_timeout_while(count, Duration::from_secs(30), |count| *count < self.expected)
+                    .expect("no panic");
+                if res.timed_out() {
+                    self.timeouts.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+
+            fn name(&self) -> Option<String> {
+                None
+            }
+
+            fn id(&self) -> Id {
+                UNKNOWN
+            }
+
+            fn message(&self, _level: MessageLevel, _message: String) {}
+        }
+
+        impl NestedProgress for Meeting {
+            type SubProgress = Self;
+
+            fn add_child(&mut self, _name: impl Into<String>) -> Self::SubProgress {
+                Meeting {
+                    step: Default::default(),
+                    ..self.clone()
+                }
+            }
+
+            fn add_child_with_id(&mut self, name: impl Into<String>, _id: Id) -> Self::SubProgress {
+                self.add_child(name)
+            }
+        }
+    }
+}
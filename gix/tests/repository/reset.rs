@@ -0,0 +1,271 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::reset::{Error, Mode};
+use gix_testtools::tempfile;
+
+fn repo_rw() -> crate::Result<(gix::Repository, tempfile::TempDir)> {
+    let dir = gix_testtools::scripted_fixture_writable_with_args(
+        "make_switch_repo.sh",
+        None::<String>,
+        gix_testtools::Creation::ExecuteScript,
+    )?;
+    let repo = gix::open_opts(dir.path(), crate::restricted())?;
+    Ok((repo, dir))
+}
+
+fn reset(repo: &gix::Repository, rev: &str, mode: Mode) -> Result<gix::reset::Outcome, Error> {
+    let id = repo.rev_parse_single(rev).expect("valid rev");
+    repo.reset(id, mode, gix::progress::Discard, &AtomicBool::default())
+}
+
+fn worktree_file(repo: &gix::Repository, path: &str) -> std::io::Result<String> {
+    std::fs::read_to_string(repo.work_dir().expect("non-bare").join(path))
+}
+
+fn write_worktree_file(repo: &gix::Repository, path: &str, content: &str) -> std::io::Result<()> {
+    std::fs::write(repo.work_dir().expect("non-bare").join(path), content)
+}
+
+/// Put `content` into the index at `path` without touching the worktree, like `git add` would.
+fn stage(repo: &gix::Repository, path: &str, content: &str) -> crate::Result {
+    let id = repo.write_blob(content)?.detach();
+    let mut index = repo.open_index()?;
+    index.remove_entries(|_, p, _| p == path);
+    index.dangerously_push_entry(
+        Default::default(),
+        id,
+        gix::index::entry::Flags::empty(),
+        gix::index::entry::Mode::FILE,
+        path.into(),
+    );
+    index.sort_entries();
+    index.write(Default::default())?;
+    Ok(())
+}
+
+fn index_entries(repo: &gix::Repository) -> crate::Result<Vec<(String, gix::ObjectId)>> {
+    let index = repo.open_index()?;
+    Ok(index
+        .entries()
+        .iter()
+        .map(|e| (e.path(&index).to_string(), e.id))
+        .collect())
+}
+
+fn tree_entries(repo: &gix::Repository, rev: &str) -> crate::Result<Vec<(String, gix::ObjectId)>> {
+    let index = repo.index_from_tree(&repo.rev_parse_single(format!("{rev}^{{tree}}").as_str())?)?;
+    Ok(index
+        .entries()
+        .iter()
+        .map(|e| (e.path(&index).to_string(), e.id))
+        .collect())
+}
+
+fn last_head_log_message(repo: &gix::Repository) -> std::io::Result<String> {
+    let log = std::fs::read_to_string(repo.git_dir().join("logs").join("HEAD"))?;
+    let line = log.lines().last().expect("at least one line");
+    Ok(line.split_once('\t').expect("message is present").1.to_owned())
+}
+
+#[test]
+fn soft_only_moves_the_branch_and_writes_orig_head() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let previous = repo.head_id()?.detach();
+    let index_before = index_entries(&repo)?;
+
+    let outcome = reset(&repo, "other", Mode::Soft)?;
+    let other = repo.rev_parse_single("other")?.detach();
+    assert_eq!(outcome.commit, other);
+    assert_eq!(outcome.previous, Some(previous));
+    assert!(outcome.checkout.is_none());
+
+    assert_eq!(repo.head_name()?.expect("still on branch").as_bstr(), "refs/heads/main");
+    assert_eq!(repo.find_reference("refs/heads/main")?.id(), other);
+    assert_eq!(repo.find_reference("ORIG_HEAD")?.id(), previous);
+    assert_eq!(last_head_log_message(&repo)?, format!("reset: moving to {other}"));
+
+    assert_eq!(index_entries(&repo)?, index_before, "the index is untouched");
+    assert_eq!(worktree_file(&repo, "changed")?, "1\n", "the worktree is untouched");
+    Ok(())
+}
+
+#[test]
+fn mixed_resets_the_index_but_not_the_worktree() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let stat_before = repo
+        .open_index()?
+        .entry_by_path("unchanged".into())
+        .expect("present")
+        .stat;
+
+    let outcome = reset(&repo, "other", Mode::Mixed)?;
+    assert!(outcome.checkout.is_none());
+    assert_eq!(outcome.files_removed, 0);
+
+    assert_eq!(index_entries(&repo)?, tree_entries(&repo, "other")?);
+    let index = repo.open_index()?;
+    assert_eq!(
+        index.entry_by_path("unchanged".into()).expect("present").stat,
+        stat_before,
+        "unchanged entries retain their stat information"
+    );
+    assert_eq!(worktree_file(&repo, "changed")?, "1\n");
+    assert_eq!(worktree_file(&repo, "removed")?, "gone\n");
+    assert!(!repo.work_dir().expect("non-bare").join("dir").exists());
+    Ok(())
+}
+
+#[test]
+fn hard_discards_all_local_changes_to_tracked_files() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    write_worktree_file(&repo, "unchanged", "local change\n")?;
+    write_worktree_file(&repo, "untracked", "keep me\n")?;
+
+    let outcome = reset(&repo, "other", Mode::Hard)?;
+    assert_eq!(outcome.files_removed, 1);
+    assert_eq!(
+        outcome.checkout.expect("worktree was changed").files_updated,
+        4,
+        "changed files and the locally modified one are written"
+    );
+
+    assert_eq!(index_entries(&repo)?, tree_entries(&repo, "other")?);
+    assert_eq!(worktree_file(&repo, "unchanged")?, "same\n");
+    assert_eq!(worktree_file(&repo, "changed")?, "2\n");
+    assert_eq!(worktree_file(&repo, "dir/added")?, "new\n");
+    assert!(!repo.work_dir().expect("non-bare").join("removed").exists());
+    assert_eq!(
+        worktree_file(&repo, "untracked")?,
+        "keep me\n",
+        "untracked files are kept"
+    );
+    Ok(())
+}
+
+#[test]
+fn merge_keeps_unstaged_changes_unless_they_would_be_overwritten() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    write_worktree_file(&repo, "unchanged", "local change\n")?;
+
+    reset(&repo, "other", Mode::Merge)?;
+    assert_eq!(index_entries(&repo)?, tree_entries(&repo, "other")?);
+    assert_eq!(worktree_file(&repo, "changed")?, "2\n");
+    assert_eq!(
+        worktree_file(&repo, "unchanged")?,
+        "local change\n",
+        "local changes to files that don't change are kept"
+    );
+
+    write_worktree_file(&repo, "changed", "local\n")?;
+    let err = reset(&repo, "other~1", Mode::Merge).unwrap_err();
+    assert!(
+        matches!(&err, Error::LocalChanges { paths } if paths == &["changed"]),
+        "{err:?}"
+    );
+    assert_eq!(
+        repo.find_reference("refs/heads/main")?.id(),
+        repo.rev_parse_single("other")?,
+        "nothing changed"
+    );
+    Ok(())
+}
+
+#[test]
+fn merge_only_keeps_unstaged_changes_if_the_index_matches_the_commit() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    stage(&repo, "changed", "staged\n")?;
+    write_worktree_file(&repo, "changed", "staged\n")?;
+    reset(&repo, "other", Mode::Merge)?;
+    assert_eq!(
+        worktree_file(&repo, "changed")?,
+        "2\n",
+        "staged changes without unstaged changes are discarded, even if the file changed between HEAD and the commit"
+    );
+
+    stage(&repo, "unchanged", "staged\n")?;
+    write_worktree_file(&repo, "unchanged", "worktree\n")?;
+    let err = reset(&repo, "other", Mode::Merge).unwrap_err();
+    assert!(
+        matches!(&err, Error::LocalChanges { paths } if paths == &["unchanged"]),
+        "{err:?}: unstaged changes on top of staged ones can't be kept, even if the file is the same in HEAD and the commit"
+    );
+
+    stage(&repo, "unchanged", "same\n")?;
+    reset(&repo, "other~1", Mode::Hard)?;
+    std::fs::create_dir_all(repo.work_dir().expect("non-bare").join("dir"))?;
+    write_worktree_file(&repo, "dir/added", "new\n")?;
+    let err = reset(&repo, "other", Mode::Merge).unwrap_err();
+    assert!(
+        matches!(&err, Error::UntrackedFiles { paths } if paths == &["dir/added"]),
+        "{err:?}: untracked files are in the way even if they match the commit"
+    );
+    Ok(())
+}
+
+#[test]
+fn keep_unstages_changes_and_only_keeps_them_in_the_worktree() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    stage(&repo, "unchanged", "staged\n")?;
+    write_worktree_file(&repo, "unchanged", "worktree\n")?;
+    stage(&repo, "added", "new\n")?;
+    write_worktree_file(&repo, "added", "new\n")?;
+
+    reset(&repo, "other", Mode::Keep)?;
+    assert_eq!(
+        index_entries(&repo)?,
+        tree_entries(&repo, "other")?,
+        "the index matches the commit, without staged changes or additions"
+    );
+    assert_eq!(worktree_file(&repo, "unchanged")?, "worktree\n");
+    assert_eq!(worktree_file(&repo, "added")?, "new\n", "added files remain untracked");
+    assert_eq!(worktree_file(&repo, "changed")?, "2\n");
+    Ok(())
+}
+
+#[test]
+fn keep_refuses_to_overwrite_local_changes_to_changed_files() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    write_worktree_file(&repo, "numbers", "local\n")?;
+
+    let err = reset(&repo, "other", Mode::Keep).unwrap_err();
+    assert!(
+        matches!(&err, Error::LocalChanges { paths } if paths == &["numbers"]),
+        "{err:?}"
+    );
+
+    write_worktree_file(&repo, "numbers", "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n")?;
+    write_worktree_file(&repo, "unchanged", "local change\n")?;
+    let outcome = reset(&repo, "other", Mode::Keep)?;
+    assert_eq!(outcome.files_removed, 1);
+    assert_eq!(worktree_file(&repo, "changed")?, "2\n");
+    assert_eq!(worktree_file(&repo, "unchanged")?, "local change\n");
+    assert_eq!(index_entries(&repo)?, tree_entries(&repo, "other")?);
+    Ok(())
+}
+
+#[test]
+fn paths_only_reset_matching_index_entries() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let head = repo.head_id()?.detach();
+
+    let changed = repo.reset_paths(repo.rev_parse_single("other")?, ["changed", "dir", "removed"])?;
+    assert_eq!(changed, ["changed", "dir/added", "removed"]);
+
+    let other = tree_entries(&repo, "other")?;
+    let main = tree_entries(&repo, "main")?;
+    let entry =
+        |entries: &[(String, gix::ObjectId)], path: &str| entries.iter().find(|(p, _)| p == path).map(|(_, id)| *id);
+    let index = index_entries(&repo)?;
+    assert_eq!(entry(&index, "changed"), entry(&other, "changed"));
+    assert_eq!(entry(&index, "dir/added"), entry(&other, "dir/added"));
+    assert_eq!(entry(&index, "removed"), None);
+    assert_eq!(
+        entry(&index, "numbers"),
+        entry(&main, "numbers"),
+        "entries that don't match the pathspec are untouched"
+    );
+
+    assert_eq!(repo.head_id()?, head, "HEAD is untouched");
+    assert_eq!(worktree_file(&repo, "changed")?, "1\n", "the worktree is untouched");
+    Ok(())
+}
@@ -0,0 +1,269 @@
+use std::sync::atomic::AtomicBool;
+
+use gix::switch::{Error, MergedFile, Options, Target};
+use gix_testtools::tempfile;
+
+fn repo_rw() -> crate::Result<(gix::Repository, tempfile::TempDir)> {
+    let dir = gix_testtools::scripted_fixture_writable_with_args(
+        "make_switch_repo.sh",
+        None::<String>,
+        gix_testtools::Creation::ExecuteScript,
+    )?;
+    let repo = gix::open_opts(dir.path(), crate::restricted())?;
+    Ok((repo, dir))
+}
+
+fn branch(name: &str) -> Target {
+    Target::Branch(format!("refs/heads/{name}").try_into().expect("valid"))
+}
+
+fn worktree_file(repo: &gix::Repository, path: &str) -> std::io::Result<String> {
+    std::fs::read_to_string(repo.work_dir().expect("non-bare").join(path))
+}
+
+fn write_worktree_file(repo: &gix::Repository, path: &str, content: &str) -> std::io::Result<()> {
+    std::fs::write(repo.work_dir().expect("non-bare").join(path), content)
+}
+
+fn last_head_log_message(repo: &gix::Repository) -> std::io::Result<String> {
+    let log = std::fs::read_to_string(repo.git_dir().join("logs").join("HEAD"))?;
+    let line = log.lines().last().expect("at least one line");
+    Ok(line.split_once('\t').expect("message is present").1.to_owned())
+}
+
+#[test]
+fn to_branch_updates_changed_files_and_keeps_unrelated_local_changes() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    write_worktree_file(&repo, "unchanged", "local change\n")?;
+    let previous_id = repo.head_id()?.detach();
+
+    let outcome = repo.switch(
+        branch("other"),
+        gix::progress::Discard,
+        &AtomicBool::default(),
+        Default::default(),
+    )?;
+    assert_eq!(outcome.commit, repo.rev_parse_single("other")?.detach());
+    assert_eq!(
+        outcome.checkout.files_updated, 3,
+        "only files that changed between both trees are written"
+    );
+    assert_eq!(outcome.files_removed, 1);
+    assert!(outcome.merged.is_empty());
+
+    assert_eq!(worktree_file(&repo, "changed")?, "2\n");
+    assert_eq!(worktree_file(&repo, "dir/added")?, "new\n");
+    assert!(worktree_file(&repo, "numbers")?.starts_with("1\ntwo\n3\n"));
+    assert!(!repo.work_dir().expect("non-bare").join("removed").exists());
+    assert_eq!(
+        worktree_file(&repo, "unchanged")?,
+        "local change\n",
+        "local changes to files that don't change are kept"
+    );
+
+    let index = repo.open_index()?;
+    let other_tree = repo.index_from_tree(&repo.rev_parse_single("other^{tree}")?)?;
+    assert_eq!(
+        index
+            .entries()
+            .iter()
+            .map(|e| (e.path(&index), e.id))
+            .collect::<Vec<_>>(),
+        other_tree
+            .entries()
+            .iter()
+            .map(|e| (e.path(&other_tree), e.id))
+            .collect::<Vec<_>>(),
+        "the index matches the target tree"
+    );
+
+    assert_eq!(repo.head_name()?.expect("not detached").as_bstr(), "refs/heads/other");
+    assert_eq!(last_head_log_message(&repo)?, "checkout: moving from main to other");
+    let log = std::fs::read_to_string(repo.git_dir().join("logs").join("HEAD"))?;
+    assert!(
+        log.lines()
+            .last()
+            .expect("present")
+            .starts_with(&format!("{previous_id} {}", outcome.commit)),
+        "the move is recorded with the previous and the new commit"
+    );
+    Ok(())
+}
+
+#[test]
+fn local_changes_to_changed_files_prevent_switching_unless_merged_or_forced() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let local = "1\n2\n3\n4\n5\n6\n7\n8\nnine\n10\n";
+    write_worktree_file(&repo, "numbers", local)?;
+
+    let err = repo
+        .switch(
+            branch("other"),
+            gix::progress::Discard,
+            &AtomicBool::default(),
+            Default::default(),
+        )
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::LocalChanges { paths } if paths == &["numbers"]),
+        "{err:?}"
+    );
+    assert_eq!(
+        repo.head_name()?.expect("not detached").as_bstr(),
+        "refs/heads/main",
+        "nothing changed"
+    );
+    assert_eq!(worktree_file(&repo, "changed")?, "1\n");
+    assert_eq!(worktree_file(&repo, "numbers")?, local);
+
+    let outcome = repo.switch(
+        branch("other"),
+        gix::progress::Discard,
+        &AtomicBool::default(),
+        Options {
+            merge: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        outcome.merged,
+        vec![MergedFile {
+            path: "numbers".into(),
+            num_conflicts: 0
+        }]
+    );
+    assert!(outcome.is_clean());
+    assert_eq!(
+        worktree_file(&repo, "numbers")?,
+        "1\ntwo\n3\n4\n5\n6\n7\n8\nnine\n10\n",
+        "local changes are carried over"
+    );
+    assert_eq!(worktree_file(&repo, "changed")?, "2\n");
+
+    write_worktree_file(&repo, "changed", "local\n")?;
+    let outcome = repo.switch(
+        branch("main"),
+        gix::progress::Discard,
+        &AtomicBool::default(),
+        Options {
+            force: true,
+            ..Default::default()
+        },
+    )?;
+    assert!(outcome.merged.is_empty());
+    assert_eq!(worktree_file(&repo, "changed")?, "1\n", "local changes are discarded");
+    assert_eq!(worktree_file(&repo, "numbers")?, "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n");
+    Ok(())
+}
+
+#[test]
+fn untracked_files_in_the_way_prevent_switching_unless_forced() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    std::fs::create_dir(repo.work_dir().expect("non-bare").join("dir"))?;
+    write_worktree_file(&repo, "dir/added", "untracked\n")?;
+
+    let err = repo
+        .switch(
+            branch("other"),
+            gix::progress::Discard,
+            &AtomicBool::default(),
+            Default::default(),
+        )
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::UntrackedFiles { paths } if paths == &["dir/added"]),
+        "{err:?}"
+    );
+
+    repo.switch(
+        branch("other"),
+        gix::progress::Discard,
+        &AtomicBool::default(),
+        Options {
+            force: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(worktree_file(&repo, "dir/added")?, "new\n");
+    Ok(())
+}
+
+#[test]
+fn a_locked_index_prevents_switching_without_changing_anything() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let lock_path = repo.git_dir().join("index.lock");
+    std::fs::write(&lock_path, "held by someone else")?;
+    let previous_id = repo.head_id()?.detach();
+
+    let err = repo
+        .switch(
+            branch("other"),
+            gix::progress::Discard,
+            &AtomicBool::default(),
+            Default::default(),
+        )
+        .unwrap_err();
+    assert!(matches!(err, Error::WriteIndex(_)), "{err:?}");
+    assert_eq!(worktree_file(&repo, "changed")?, "1\n", "the worktree wasn't touched");
+    assert_eq!(repo.head_id()?, previous_id);
+    assert_eq!(
+        std::fs::read_to_string(&lock_path)?,
+        "held by someone else",
+        "the lock of someone else is left alone"
+    );
+    Ok(())
+}
+
+#[test]
+fn detached_and_with_new_branch() -> crate::Result {
+    let (repo, _tmp) = repo_rw()?;
+    let other = repo.rev_parse_single("other")?.detach();
+
+    repo.switch(
+        Target::Detached(other),
+        gix::progress::Discard,
+        &AtomicBool::default(),
+        Default::default(),
+    )?;
+    assert!(repo.head()?.is_detached());
+    assert_eq!(repo.head_id()?, other);
+    assert_eq!(
+        last_head_log_message(&repo)?,
+        format!("checkout: moving from main to {other}")
+    );
+
+    let new_branch: gix::refs::FullName = "refs/heads/feature".try_into()?;
+    repo.switch(
+        branch("main"),
+        gix::progress::Discard,
+        &AtomicBool::default(),
+        Options {
+            new_branch: Some(new_branch.clone()),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(worktree_file(&repo, "changed")?, "1\n");
+    assert_eq!(repo.head_name()?, Some(new_branch.clone()));
+    assert_eq!(
+        repo.find_reference(new_branch.as_ref())?.id(),
+        repo.rev_parse_single("main")?
+    );
+    assert_eq!(
+        last_head_log_message(&repo)?,
+        format!("checkout: moving from {other} to feature")
+    );
+
+    let err = repo
+        .switch(
+            branch("other"),
+            gix::progress::Discard,
+            &AtomicBool::default(),
+            Options {
+                new_branch: Some(new_branch),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, Error::BranchExists { .. }), "{err:?}");
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use gix::revision::history::{Change, Item};
+
+fn repo() -> crate::Result<gix::Repository> {
+    crate::named_repo("make_file_history_repo.sh")
+}
+
+/// Return the `git log --follow --name-status` baseline for `path`, with one line per commit,
+/// and without the similarity score of renames.
+fn baseline(repo: &gix::Repository, path: &str) -> crate::Result<Vec<String>> {
+    let baseline = std::fs::read_to_string(repo.work_dir().expect("non-bare").join("baseline.git"))?;
+    let block = baseline
+        .split("\n\n")
+        .find(|block| block.lines().next() == Some(path))
+        .expect("baseline for path exists");
+    Ok(block
+        .lines()
+        .skip(1)
+        .collect::<Vec<_>>()
+        .chunks(2)
+        .map(|lines| {
+            let id = lines[0].strip_prefix("commit ").expect("commit line");
+            let status = lines[1].trim_start_matches(|c: char| c.is_ascii_digit() || c == 'R');
+            let status = if lines[1].starts_with('R') {
+                format!("R{status}")
+            } else {
+                status.to_owned()
+            };
+            format!("{id} {status}")
+        })
+        .collect())
+}
+
+fn history(
+    repo: &gix::Repository,
+    path: &str,
+    configure: impl FnOnce(gix::revision::history::Platform<'_>) -> gix::revision::history::Platform<'_>,
+) -> crate::Result<Vec<String>> {
+    let tip = repo.rev_parse_single("main")?.detach();
+    Ok(configure(repo.file_history(tip, path))
+        .all()?
+        .map(|item| {
+            item.map(
+                |Item {
+                     id, location, change, ..
+                 }| {
+                    let status = match change {
+                        Change::Addition => format!("A\t{location}"),
+                        Change::Deletion => format!("D\t{location}"),
+                        Change::Modification => format!("M\t{location}"),
+                        Change::Rewrite { source_location, copy } => {
+                            format!("{}\t{source_location}\t{location}", if copy { "C" } else { "R" })
+                        }
+                    };
+                    format!("{id} {status}")
+                },
+            )
+        })
+        .collect::<Result<_, _>>()?)
+}
+
+#[test]
+fn follows_renames_like_git() -> crate::Result {
+    let repo = repo()?;
+    for path in ["dir/c", "other"] {
+        let expected = baseline(&repo, path)?;
+        for use_commit_graph in [false, true] {
+            assert_eq!(
+                history(&repo, path, |platform| platform.use_commit_graph(use_commit_graph))?,
+                expected,
+                "{path}: renames are followed, and merges are simplified, with and without Bloom filters"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn without_rewrite_tracking_history_stops_at_renames() -> crate::Result {
+    let repo = repo()?;
+    let actual = history(&repo, "dir/c", |platform| platform.track_rewrites(None))?;
+    let expected = baseline(&repo, "dir/c")?;
+    assert_eq!(actual[..1], expected[..1]);
+    assert_eq!(actual.len(), 2);
+    assert!(
+        actual[1].ends_with("A\tdir/c"),
+        "the rename is seen as addition: {actual:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn paths_that_never_existed_have_no_history() -> crate::Result {
+    let repo = repo()?;
+    assert_eq!(
+        history(&repo, "does-not-exist", |platform| platform)?,
+        Vec::<String>::new()
+    );
+    Ok(())
+}
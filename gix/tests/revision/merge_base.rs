@@ -0,0 +1,75 @@
+use gix::{prelude::ObjectIdExt, ObjectId};
+
+fn repo() -> crate::Result<gix::Repository> {
+    crate::named_repo("make_merge_base_repo.sh")
+}
+
+fn id(repo: &gix::Repository, spec: &str) -> crate::Result<ObjectId> {
+    Ok(repo.rev_parse_single(spec)?.detach())
+}
+
+#[test]
+fn merge_base_and_all_merge_bases() -> crate::Result {
+    let repo = repo()?;
+    let (main, topic) = (id(&repo, "main")?, id(&repo, "topic")?);
+    assert_eq!(repo.merge_base(main, topic)?, id(&repo, "base")?);
+    assert_eq!(repo.merge_base(main, main)?, main, "a commit is its own merge-base");
+
+    let bases = repo.merge_bases_many(id(&repo, "left")?, &[id(&repo, "right")?])?;
+    let mut bases: Vec<_> = bases.into_iter().map(gix::Id::detach).collect();
+    bases.sort();
+    let mut expected = vec![main, topic];
+    expected.sort();
+    assert_eq!(bases, expected, "criss-cross merges have more than one merge-base");
+    Ok(())
+}
+
+#[test]
+fn octopus_and_independent() -> crate::Result {
+    let repo = repo()?;
+    let (main, topic, upstream) = (id(&repo, "main")?, id(&repo, "topic")?, id(&repo, "upstream")?);
+    assert_eq!(repo.merge_bases_octopus([main, topic, upstream])?, [id(&repo, "base")?]);
+    assert_eq!(
+        repo.independent_commits([upstream, main, topic, upstream])?,
+        [upstream, topic],
+        "main is reachable from upstream, and duplicates are removed"
+    );
+    Ok(())
+}
+
+#[test]
+fn is_ancestor() -> crate::Result {
+    let repo = repo()?;
+    let (b, main) = (id(&repo, "base")?, id(&repo, "main")?);
+    assert!(repo.is_ancestor(b, main)?);
+    assert!(!repo.is_ancestor(main, b)?);
+    assert!(!repo.is_ancestor(main, id(&repo, "topic")?)?);
+    Ok(())
+}
+
+#[test]
+fn fork_point_considers_reflog_of_upstream() -> crate::Result {
+    let repo = repo()?;
+    let feature = id(&repo, "feature")?;
+    assert_eq!(
+        repo.merge_base_fork_point("upstream", feature)?.map(gix::Id::detach),
+        Some(id(&repo, "U1")?),
+        "U1 was rewritten, but is still known to the reflog"
+    );
+    assert_eq!(
+        repo.merge_base(id(&repo, "upstream")?, feature)?,
+        id(&repo, "main")?,
+        "the merge-base doesn't know about U1 anymore"
+    );
+    Ok(())
+}
+
+#[test]
+fn symmetric_difference_spec_provides_merge_bases() -> crate::Result {
+    let repo = repo()?;
+    let spec = repo.rev_parse("main...topic")?;
+    let bases = spec.merge_bases().expect("symmetric difference")?;
+    assert_eq!(bases, [id(&repo, "base")?.attach(&repo)]);
+    assert!(repo.rev_parse("main..topic")?.merge_bases().is_none());
+    Ok(())
+}
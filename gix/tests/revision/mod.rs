@@ -1 +1,4 @@
+mod history;
+mod merge_base;
 mod spec;
+mod walk;
@@ -78,10 +78,49 @@ fn by_index() {
 }
 
 #[test]
-fn by_date_is_planned_until_git_date_crate_is_implements_parsing() {
-    let repo = repo("complex_graph").unwrap();
+fn by_date() {
+    let repo = &repo("complex_graph").unwrap();
+    for (spec, expected) in [
+        ("main@{1112912200 +0000}", "a8fbd8e0c8753ef07ccdbfa93da030d53b68f0bc"),
+        (
+            "main@{2005-04-07 15:15:13 -0700}",
+            "a8fbd8e0c8753ef07ccdbfa93da030d53b68f0bc",
+        ),
+        (
+            "@{2005-04-07 15:15:13 -0700}",
+            "a8fbd8e0c8753ef07ccdbfa93da030d53b68f0bc",
+        ),
+        ("HEAD@{1112912400 +0000}", "a28bcc15042a930ebe97aa310744bba7df1de322"),
+    ] {
+        let spec_parsed = parse_spec_no_baseline(spec, repo).unwrap_or_else(|err| panic!("{spec}: {err}"));
+        assert_eq!(spec_parsed, Spec::from_id(hex_to_id(expected).attach(repo)), "{spec}");
+    }
+
+    for spec in [
+        "main@{1112999999 +0000}",
+        "main@{2024-01-01}",
+        "main@{1.year.ago}",
+        "main@{yesterday}",
+    ] {
+        assert_eq!(
+            parse_spec_no_baseline(spec, repo).unwrap(),
+            Spec::from_id(hex_to_id("55e825ebe8fd2ff78cad3826afb696b96b576a7e").attach(repo)),
+            "{spec}: dates after the newest entry yield the newest entry"
+        );
+    }
+
+    assert_eq!(
+        parse_spec_no_baseline("main@{1112911000 +0000}", repo).unwrap(),
+        Spec::from_id(hex_to_id("9f9eac6bd1cd4b4cc6a494f044b28c985a22972b").attach(repo)),
+        "dates before the oldest entry yield its oldest known value"
+    );
+}
+
+#[test]
+fn by_date_unborn_head() {
+    let repo = &repo("new").unwrap();
     assert!(matches!(
-        parse_spec_no_baseline("main@{1979-02-26 18:30:00}", &repo).unwrap_err(),
-        Error::Planned { .. }
+        parse_spec_no_baseline("@{yesterday}", repo).unwrap_err(),
+        Error::UnbornHeadsHaveNoRefLog
     ));
 }
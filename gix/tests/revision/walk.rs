@@ -0,0 +1,159 @@
+use gix::{revision::walk::Platform, traverse::commit::simple::Sorting, ObjectId};
+
+fn repo() -> crate::Result<gix::Repository> {
+    crate::named_repo("make_rev_walk_repo.sh")
+}
+
+/// Return all baselines produced by `git rev-list`, by name.
+fn baselines(repo: &gix::Repository) -> crate::Result<std::collections::HashMap<String, Vec<ObjectId>>> {
+    let baseline = std::fs::read_to_string(repo.work_dir().expect("non-bare").join("baseline.git"))?;
+    Ok(baseline
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .map(|block| {
+            let mut lines = block.lines();
+            let name = lines.next().expect("name").to_owned();
+            (
+                name,
+                lines
+                    .map(|hex| ObjectId::from_hex(hex.as_bytes()).expect("valid"))
+                    .collect(),
+            )
+        })
+        .collect())
+}
+
+fn id(repo: &gix::Repository, spec: &str) -> crate::Result<ObjectId> {
+    Ok(repo.rev_parse_single(spec)?.detach())
+}
+
+fn main(repo: &gix::Repository) -> crate::Result<Platform<'_>> {
+    Ok(repo
+        .rev_walk([id(repo, "main")?])
+        .sorting(Sorting::ByCommitTimeNewestFirst))
+}
+
+fn ids(walk: Platform<'_>) -> crate::Result<Vec<ObjectId>> {
+    Ok(walk
+        .all()?
+        .map(|info| info.map(|info| info.id))
+        .collect::<Result<_, _>>()?)
+}
+
+#[test]
+fn hidden_tips() -> crate::Result {
+    let repo = repo()?;
+    let expected = baselines(&repo)?;
+    for use_commit_graph in [false, true] {
+        let main = || main(&repo).map(|walk| walk.use_commit_graph(use_commit_graph));
+        assert_eq!(
+            ids(main()?.with_hidden([id(&repo, "side")?]))?,
+            expected["hidden"],
+            "main ^side"
+        );
+        assert_eq!(
+            ids(main()?.with_hidden([id(&repo, "side2~1")?, id(&repo, "side")?]))?,
+            expected["hidden-merged"],
+            "main ^side2~1 ^side"
+        );
+        assert_eq!(
+            ids(main()?.with_hidden([id(&repo, "main")?]))?,
+            Vec::<ObjectId>::new(),
+            "hiding the tip hides everything"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn commit_filters() -> crate::Result {
+    let repo = repo()?;
+    let expected = baselines(&repo)?;
+    let time_of = |spec: &str| -> crate::Result<gix::date::SecondsSinceUnixEpoch> {
+        Ok(repo.rev_parse_single(spec)?.object()?.into_commit().time()?.seconds)
+    };
+    assert_eq!(
+        ids(main(&repo)?.since(time_of(":/^A:")?).until(time_of(":/^F:")?))?,
+        expected["since-until"],
+        "both bounds are inclusive"
+    );
+    assert_eq!(ids(main(&repo)?.author_matches("Side Author"))?, expected["author"]);
+    assert_eq!(ids(main(&repo)?.committer_matches("nobody"))?, expected["committer"]);
+    assert_eq!(
+        ids(main(&repo)?
+            .message_matches("A: change foo")
+            .message_matches("D: change foo"))?,
+        expected["grep"],
+        "multiple patterns match if any of them matches"
+    );
+    assert_eq!(ids(main(&repo)?.no_merges())?, expected["no-merges"]);
+    assert_eq!(ids(main(&repo)?.merges_only())?, expected["merges"]);
+    assert_eq!(ids(main(&repo)?.skip(2).max_count(3))?, expected["skip-and-max-count"]);
+    assert_eq!(
+        ids(main(&repo)?.no_merges().max_count(1))?,
+        expected["no-merges"][..1],
+        "limits apply after filtering"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "revparse-regex")]
+fn commit_filters_with_regex() -> crate::Result {
+    let repo = repo()?;
+    let expected = baselines(&repo)?;
+    assert_eq!(
+        ids(main(&repo)?.message_matches("^[AD]: change foo"))?,
+        expected["grep"]
+    );
+    assert_eq!(ids(main(&repo)?.author_matches("^Side Author"))?, expected["author"]);
+    assert!(matches!(
+        main(&repo)?.author_matches("(").all(),
+        Err(gix::revision::walk::Error::InvalidRegex(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn ancestry_path() -> crate::Result {
+    let repo = repo()?;
+    let expected = baselines(&repo)?;
+    assert_eq!(
+        ids(main(&repo)?.with_hidden([id(&repo, "side")?]).ancestry_path())?,
+        expected["ancestry-path"],
+        "A isn't a descendant of side"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "attributes")]
+fn paths_with_history_simplification() -> crate::Result {
+    use gix::revision::walk::Simplification;
+
+    let repo = repo()?;
+    let expected = baselines(&repo)?;
+    let index = repo.index_or_empty()?;
+    let pathspec = |pattern: &str| {
+        repo.pathspec(
+            true,
+            Some(pattern),
+            false,
+            &index,
+            gix::worktree::stack::state::attributes::Source::IdMapping,
+        )
+    };
+    for (pattern, simplification, name) in [
+        ("foo", Simplification::Simplify, "paths"),
+        ("dir", Simplification::Simplify, "paths-dir"),
+        ("foo", Simplification::FullHistory, "full-history"),
+        ("foo", Simplification::SimplifyMerges, "simplify-merges"),
+    ] {
+        assert_eq!(
+            ids(main(&repo)?.with_pathspec(pathspec(pattern)?, simplification))?,
+            expected[name],
+            "{name}"
+        );
+    }
+    Ok(())
+}
@@ -112,6 +112,133 @@ mod index_worktree {
             Ok(())
         }
 
+        #[test]
+        fn fs_monitor_hook_limits_modification_checks_and_its_token_is_written_back() -> crate::Result {
+            let tmp = gix_testtools::scripted_fixture_writable("make_status_repos.sh")?;
+            let worktree = tmp.path().join("fs-monitor");
+            let open = || gix::open_opts(&worktree, gix::open::Options::isolated());
+            let modified_paths = |repo: &gix::Repository| -> crate::Result<_> {
+                let mut status = repo
+                    .status(gix::progress::Discard)?
+                    .into_index_worktree_iter(Vec::new())?;
+                let paths: Vec<gix::bstr::BString> = status
+                    .by_ref()
+                    .filter_map(Result::ok)
+                    .filter(|item| item.summary().is_some())
+                    .map(|item| item.rela_path().to_owned())
+                    .collect();
+                let mut out = status.into_outcome().expect("iteration done");
+                assert!(
+                    out.has_changes(),
+                    "the token of the filesystem monitor is always written"
+                );
+                out.write_changes().expect("changes present")?;
+                Ok(paths)
+            };
+
+            let repo = open()?;
+            assert!(repo.index()?.fs_monitor().is_none());
+            assert_eq!(
+                modified_paths(&repo)?,
+                Vec::<gix::bstr::BString>::new(),
+                "without token, all entries are checked, and nothing changed"
+            );
+            let index = open()?.open_index()?;
+            let ext = index.fs_monitor().expect("extension was written");
+            assert_eq!(ext.entry_dirty().num_bits(), 0, "all entries are known to be unchanged");
+
+            for path in ["modified", "unreported"] {
+                std::fs::write(worktree.join(path), "changed with a different size\n")?;
+            }
+            std::fs::write(worktree.join("untracked-unreported"), "new\n")?;
+            let mut paths = modified_paths(&open()?)?;
+            paths.sort();
+            assert_eq!(
+                paths,
+                ["modified", "untracked-unreported"],
+                "only the entries reported by the hook are checked, the other modification goes unnoticed, \
+                 but untracked files are still found as there is no untracked cache"
+            );
+            let index = open()?.open_index()?;
+            let ext = index.fs_monitor().expect("extension was written");
+            assert_eq!(
+                ext.token(),
+                &gix::status::plumbing::fs_monitor::Token::V2 {
+                    token: "token-2".into()
+                }
+            );
+            let mut dirty = Vec::new();
+            ext.entry_dirty().for_each_set_bit(|idx| {
+                dirty.push(idx);
+                Some(())
+            });
+            assert_eq!(dirty, [0], "the modified entry is still dirty");
+            Ok(())
+        }
+
+        #[test]
+        fn untracked_cache_is_created_used_and_written_back() -> crate::Result {
+            let tmp = gix_testtools::scripted_fixture_writable("make_status_repos.sh")?;
+            let worktree = tmp.path().join("untracked-only");
+            let mut repo = gix::open_opts(&worktree, gix::open::Options::isolated())?;
+            {
+                let mut config = repo.config_snapshot_mut();
+                config.set_value(&gix::config::tree::Core::UNTRACKED_CACHE, "true")?;
+                // Directories changed in the same second as the index was written are racy and always read again.
+                config.set_value(&gix::config::tree::gitoxide::Core::USE_NSEC, "true")?;
+            }
+            let status = |repo: &gix::Repository| -> crate::Result<_> {
+                let mut status = repo
+                    .status(gix::progress::Discard)?
+                    .into_index_worktree_iter(Vec::new())?;
+                let mut paths: Vec<gix::bstr::BString> = status
+                    .by_ref()
+                    .filter_map(Result::ok)
+                    .map(|item| item.rela_path().to_owned())
+                    .collect();
+                paths.sort();
+                let mut out = status.into_outcome().expect("iteration done");
+                let read_dir_calls = out.index_worktree.dirwalk.as_ref().expect("dirwalk ran").read_dir_calls;
+                if out.has_changes() {
+                    out.write_changes().expect("changes present")?;
+                }
+                Ok((paths, read_dir_calls))
+            };
+
+            assert!(repo.index()?.untracked().is_none());
+            let (paths, read_dir_calls) = status(&repo)?;
+            assert_eq!(paths, ["new", "subdir/untracked"]);
+            assert_eq!(read_dir_calls, 3, "all directories are read to fill the new cache");
+            let index = repo.open_index()?;
+            let cache = index.untracked().expect("the cache was written to the index");
+            assert!(cache.identifier().starts_with(b"Location "));
+            assert_eq!(
+                cache.directories().len(),
+                2,
+                "only directories with tracked files are cached"
+            );
+
+            assert_eq!(
+                status(&repo)?,
+                (paths, 1),
+                "now only the untracked directory has to be read"
+            );
+
+            std::fs::write(worktree.join("subdir/new-file"), b"")?;
+            let (paths, read_dir_calls) = status(&repo)?;
+            assert_eq!(paths, ["new", "subdir/new-file", "subdir/untracked"]);
+            assert_eq!(read_dir_calls, 2, "the changed directory is read again");
+
+            repo.config_snapshot_mut()
+                .set_value(&gix::config::tree::Core::UNTRACKED_CACHE, "false")?;
+            assert_eq!(
+                status(&repo)?.1,
+                3,
+                "the cache isn't used if it's disabled by configuration"
+            );
+            Ok(())
+        }
+
         #[test]
         fn early_drop_for_is_dirty_emulation() -> crate::Result {
             let repo = submodule_repo("modified-untracked-and-submodule-head-changed-and-modified")?;
@@ -131,6 +258,110 @@ mod index_worktree {
     }
 }
 
+mod iter {
+    use crate::status::repo;
+    use gix::status::iter::Item;
+    use gix::status::plumbing::tree_index::Summary;
+    use gix::status::tree_index::Item as TreeIndexItem;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn staged_changes_come_first_and_renames_are_tracked_by_default() -> crate::Result {
+        let repo = repo("staged-and-unstaged")?;
+        let mut status = repo.status(gix::progress::Discard)?.into_iter(None)?;
+        let items: Vec<_> = status.by_ref().filter_map(Result::ok).collect();
+        let staged: Vec<_> = items
+            .iter()
+            .filter_map(|item| match item {
+                Item::TreeIndex(item) => Some((item.source_rela_path(), item.rela_path(), item.summary())),
+                Item::IndexWorktree(_) => None,
+            })
+            .collect();
+        assert_eq!(
+            staged,
+            [
+                ("modified".into(), "modified".into(), Summary::Modified),
+                ("to-rename".into(), "renamed".into(), Summary::Renamed),
+                ("added".into(), "added".into(), Summary::Added),
+            ]
+        );
+        assert_eq!(items.len(), 4, "the unstaged modification follows the staged changes");
+        assert_eq!(items[3].location(), "unstaged");
+        assert!(matches!(items[3], Item::IndexWorktree(_)));
+        assert_eq!(status.tree_index_outcome().entries_skipped_intent_to_add, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn rename_tracking_can_be_disabled() -> crate::Result {
+        let repo = repo("staged-and-unstaged")?;
+        let items: Vec<_> = repo
+            .status(gix::progress::Discard)?
+            .tree_index_track_renames(None)
+            .index_worktree_rewrites(None)
+            .into_iter(Some("*rename*".into()))?
+            .filter_map(Result::ok)
+            .map(|item| match item {
+                Item::TreeIndex(item) => (item.rela_path().to_owned(), item.summary()),
+                Item::IndexWorktree(item) => unreachable!("no unstaged changes match the pathspec: {item:?}"),
+            })
+            .collect();
+        assert_eq!(
+            items,
+            [
+                ("renamed".into(), Summary::Added),
+                ("to-rename".into(), Summary::Removed),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn status_renames_overrides_diff_renames() -> crate::Result {
+        let mut repo = repo("staged-and-unstaged")?;
+        {
+            let mut config = repo.config_snapshot_mut();
+            config.set_value(&gix::config::tree::Diff::RENAMES, "true")?;
+            config.set_value(&gix::config::tree::Status::RENAMES, "false")?;
+        }
+        let items: Vec<_> = repo
+            .status(gix::progress::Discard)?
+            .index_worktree_rewrites(None)
+            .into_iter(Some("*rename*".into()))?
+            .filter_map(Result::ok)
+            .map(|item| match item {
+                Item::TreeIndex(item) => (item.rela_path().to_owned(), item.summary()),
+                Item::IndexWorktree(item) => unreachable!("no unstaged changes match the pathspec: {item:?}"),
+            })
+            .collect();
+        assert_eq!(
+            items,
+            [
+                ("renamed".into(), Summary::Added),
+                ("to-rename".into(), Summary::Removed),
+            ],
+            "`status.renames` takes precedence"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unborn_head_shows_all_entries_as_added() -> crate::Result {
+        let repo = repo("unborn-with-index")?;
+        let items: Vec<_> = repo
+            .status(gix::progress::Discard)?
+            .into_iter(None)?
+            .filter_map(Result::ok)
+            .collect();
+        assert_eq!(items.len(), 1);
+        let Item::TreeIndex(TreeIndexItem::Addition { rela_path, .. }) = &items[0] else {
+            panic!("expected staged addition, got {:?}", items[0]);
+        };
+        assert_eq!(rela_path, "staged");
+        Ok(())
+    }
+}
+
 mod is_dirty {
     use crate::status::submodule_repo;
 
@@ -533,7 +533,68 @@ pub fn main() -> Result<()> {
                 }
             }
         }
-        Subcommands::Config(config::Platform { filter }) => prepare_and_run(
+        Subcommands::Config(config::Platform {
+            filter,
+            scope,
+            cmd: Some(cmd),
+        }) => prepare_and_run(
+            "config-edit",
+            trace,
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, _out, _err| {
+                use core::repository::config::{Edit, ValuePattern};
+                if !filter.is_empty() {
+                    anyhow::bail!("Filters can't be used when editing configuration");
+                }
+                let value_pattern = |pattern: config::ValuePattern| {
+                    pattern.value_pattern.map(|value_pattern| ValuePattern {
+                        pattern: value_pattern,
+                        fixed_value: pattern.fixed_value,
+                    })
+                };
+                let edit = match cmd {
+                    config::Subcommands::Set { key, value } => Edit::Set { key, value },
+                    config::Subcommands::Add { key, value } => Edit::Add { key, value },
+                    config::Subcommands::ReplaceAll {
+                        key,
+                        value,
+                        value_pattern: pattern,
+                    } => Edit::ReplaceAll {
+                        key,
+                        value,
+                        value_pattern: value_pattern(pattern),
+                    },
+                    config::Subcommands::Unset {
+                        key,
+                        value_pattern: pattern,
+                    } => Edit::Unset {
+                        key,
+                        value_pattern: value_pattern(pattern),
+                    },
+                    config::Subcommands::UnsetAll {
+                        key,
+                        value_pattern: pattern,
+                    } => Edit::UnsetAll {
+                        key,
+                        value_pattern: value_pattern(pattern),
+                    },
+                    config::Subcommands::RenameSection { old_name, new_name } => {
+                        Edit::RenameSection { old_name, new_name }
+                    }
+                    config::Subcommands::RemoveSection { name } => Edit::RemoveSection { name },
+                };
+                core::repository::config::edit(repository(Mode::Lenient)?, scope.into(), edit)
+            },
+        )
+        .map(|_| ()),
+        Subcommands::Config(config::Platform {
+            filter,
+            scope: _,
+            cmd: None,
+        }) => prepare_and_run(
             "config-list",
             trace,
             verbose,
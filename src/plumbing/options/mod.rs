@@ -322,6 +322,102 @@ pub mod config {
         /// and comparisons are case-insensitive.
         #[clap(value_parser = crate::shared::AsBString)]
         pub filter: Vec<BString>,
+
+        /// The configuration file to change when using a sub-command.
+        #[clap(long, short = 's', value_enum, default_value = "local", global = true)]
+        pub scope: Scope,
+
+        /// Subcommands
+        #[clap(subcommand)]
+        pub cmd: Option<Subcommands>,
+    }
+
+    #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+    pub enum Scope {
+        /// The `config` file of the repository.
+        #[default]
+        Local,
+        /// The `config.worktree` file of the current worktree.
+        Worktree,
+        /// The configuration file of the current user, typically `~/.gitconfig`.
+        Global,
+        /// The configuration file shared by all users of the system.
+        System,
+    }
+
+    impl From<Scope> for gix::config::Source {
+        fn from(value: Scope) -> Self {
+            match value {
+                Scope::Local => gix::config::Source::Local,
+                Scope::Worktree => gix::config::Source::Worktree,
+                Scope::Global => gix::config::Source::User,
+                Scope::System => gix::config::Source::System,
+            }
+        }
+    }
+
+    /// A pattern to select values of multi-valued keys.
+    #[derive(Debug, clap::Parser)]
+    pub struct ValuePattern {
+        /// A regular expression to match values with, matching only values that don't match if prefixed with `!`.
+        #[clap(value_parser = crate::shared::AsBString)]
+        pub value_pattern: Option<BString>,
+        /// Compare the value pattern literally instead of interpreting it as regular expression.
+        #[clap(long, requires = "value_pattern")]
+        pub fixed_value: bool,
+    }
+
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// Set the single value of a key, like `git config <key> <value>`.
+        Set {
+            #[clap(value_parser = crate::shared::AsBString)]
+            key: BString,
+            #[clap(value_parser = crate::shared::AsBString)]
+            value: BString,
+        },
+        /// Add another value to a key, like `git config --add`.
+        Add {
+            #[clap(value_parser = crate::shared::AsBString)]
+            key: BString,
+            #[clap(value_parser = crate::shared::AsBString)]
+            value: BString,
+        },
+        /// Replace all matching values of a key with a single value, like `git config --replace-all`.
+        ReplaceAll {
+            #[clap(value_parser = crate::shared::AsBString)]
+            key: BString,
+            #[clap(value_parser = crate::shared::AsBString)]
+            value: BString,
+            #[clap(flatten)]
+            value_pattern: ValuePattern,
+        },
+        /// Remove the single matching value of a key, like `git config --unset`.
+        Unset {
+            #[clap(value_parser = crate::shared::AsBString)]
+            key: BString,
+            #[clap(flatten)]
+            value_pattern: ValuePattern,
+        },
+        /// Remove all matching values of a key, like `git config --unset-all`.
+        UnsetAll {
+            #[clap(value_parser = crate::shared::AsBString)]
+            key: BString,
+            #[clap(flatten)]
+            value_pattern: ValuePattern,
+        },
+        /// Rename a section like `remote.origin`, like `git config --rename-section`.
+        RenameSection {
+            #[clap(value_parser = crate::shared::AsBString)]
+            old_name: BString,
+            #[clap(value_parser = crate::shared::AsBString)]
+            new_name: BString,
+        },
+        /// Remove a section like `remote.origin` with all of its values, like `git config --remove-section`.
+        RemoveSection {
+            #[clap(value_parser = crate::shared::AsBString)]
+            name: BString,
+        },
     }
 }
 